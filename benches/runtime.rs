@@ -0,0 +1,152 @@
+//! Throughput of the mutation engine on the reference modules of the `testdata`
+//! submodule: discovery rate, compile time of meta-mutants, and executed mutants
+//! per second in one-by-one and meta-mutant mode.
+//!
+//! To guard against regressions, save a baseline before a change
+//! (`cargo bench -- --save-baseline before`) and compare against it
+//! afterwards (`cargo bench -- --baseline before`).
+
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use wasmut::{
+    runtime::RuntimeFactory, Config, Executor, MutationEngine, MutationLocation, WasmModule,
+};
+
+/// Reference modules, skipped if the testdata submodule is not checked out
+const MODULES: &[&str] = &[
+    "testdata/simple_add/test.wasm",
+    "testdata/factorial/test.wasm",
+    "testdata/count_words/test.wasm",
+    "testdata/i64_param/test.wasm",
+];
+
+/// A reference module, its configuration and all of its mutants
+struct Reference {
+    name: &'static str,
+    module: WasmModule<'static>,
+    config: Config,
+    locations: Vec<MutationLocation>,
+}
+
+impl Reference {
+    fn load(path: &'static str) -> Option<Self> {
+        if !Path::new(path).exists() {
+            eprintln!("Skipping {path}, is the testdata submodule checked out?");
+            return None;
+        }
+
+        let module = WasmModule::from_file(path).expect("Could not load module");
+        let config_path = Path::new(path).with_file_name("wasmut.toml");
+        let config = if config_path.exists() {
+            Config::parse_file(config_path).expect("Could not parse configuration")
+        } else {
+            Config::default()
+        };
+
+        let locations = MutationEngine::new(&config, 100)
+            .and_then(|engine| engine.discover_mutation_positions(&module))
+            .expect("Could not discover mutants")
+            .locations;
+
+        let name = Path::new(path)
+            .parent()
+            .and_then(|directory| directory.file_name())
+            .and_then(|name| name.to_str())
+            .unwrap_or(path);
+
+        Some(Self {
+            name,
+            module,
+            config,
+            locations,
+        })
+    }
+
+    fn mutants(&self) -> u64 {
+        self.locations
+            .iter()
+            .map(|location| location.mutations.len() as u64)
+            .sum()
+    }
+
+    fn instructions(&self) -> u64 {
+        self.module.function_lengths().iter().sum()
+    }
+}
+
+fn references() -> Vec<Reference> {
+    MODULES
+        .iter()
+        .filter_map(|path| Reference::load(path))
+        .collect()
+}
+
+/// Candidates discovered per second, measured in instructions of the module
+fn discovery(c: &mut Criterion) {
+    let mut group = c.benchmark_group("discovery");
+    for reference in references() {
+        let engine = MutationEngine::new(&reference.config, 100).unwrap();
+
+        group.throughput(Throughput::Elements(reference.instructions()));
+        group.bench_function(reference.name, |b| {
+            b.iter(|| {
+                engine
+                    .discover_mutation_positions(&reference.module)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Generating and compiling a meta-mutant containing all mutants of a module
+fn meta_mutant_compilation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("meta_mutant_compilation");
+    group.sample_size(10);
+    for reference in references() {
+        let engine = reference.config.engine();
+
+        group.throughput(Throughput::Elements(reference.mutants()));
+        group.bench_function(reference.name, |b| {
+            b.iter(|| {
+                let meta_mutant = reference
+                    .module
+                    .clone_and_mutate_all(&reference.locations, engine.meta_mutant_activation())
+                    .unwrap();
+                RuntimeFactory::new(engine.runtime(), &meta_mutant, true, &[]).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Executed mutants per second, in one-by-one and meta-mutant mode
+fn execution(c: &mut Criterion) {
+    let mut group = c.benchmark_group("execution");
+    group.sample_size(10);
+    for mut reference in references() {
+        group.throughput(Throughput::Elements(reference.mutants()));
+
+        for meta_mutant in [false, true] {
+            reference
+                .config
+                .engine_mut()
+                .override_meta_mutant(meta_mutant);
+            let config = &reference.config;
+            let id = BenchmarkId::new(wasmut::executor::engine_mode(meta_mutant), reference.name);
+
+            group.bench_with_input(id, &reference, |b, reference| {
+                b.iter(|| {
+                    Executor::new(config)
+                        .execute_mutants(&reference.module, &reference.locations)
+                        .unwrap()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, discovery, meta_mutant_compilation, execution);
+criterion_main!(benches);
@@ -1,19 +1,88 @@
 use addr2line::Context;
+use anyhow::{Context as _, Result};
 use gimli::{EndianRcSlice, SectionId};
 use object::{Object, ObjectSection, SymbolMap, SymbolMapName};
-use serde::Serialize;
-use std::{borrow::Cow, rc::Rc};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    path::Path,
+    rc::Rc,
+};
 
 // Partly based on https://github.com/gimli-rs/addr2line/blob/master/examples/addr2line.rs
 // Licensed under the MIT license, retrived on 2021-12-23
 // Copyright (c) 2016-2018 The gimli Developers
 
-#[derive(Debug, Default, PartialEq, Clone, Serialize)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct CodeLocation {
+    /// Source file, normalized with `normalize_path`
     pub file: Option<String>,
     pub function: Option<String>,
     pub line: Option<u64>,
     pub column: Option<u64>,
+    /// Source file as recorded in the debug information,
+    /// if it differs from the normalized `file`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_file: Option<String>,
+}
+
+impl CodeLocation {
+    /// Source file as recorded in the debug information
+    pub fn raw_file(&self) -> Option<&str> {
+        self.raw_file.as_deref().or(self.file.as_deref())
+    }
+}
+
+/// Normalize a source path lexically: remove `.` components and
+/// resolve `..` components, without accessing the file system.
+///
+/// addr2line joins relative paths with the compilation directory of the unit
+/// (`DW_AT_comp_dir`), which leaves `..` components in paths like
+/// `/build/out/../src/add.c`. If the compilation directory is relative itself,
+/// e.g. `.` for reproducible builds, the path stays relative.
+pub fn normalize_path(path: &str) -> String {
+    let absolute = path.starts_with('/');
+    let mut components: Vec<&str> = Vec::new();
+
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => match components.last() {
+                Some(&last) if last != ".." => {
+                    components.pop();
+                }
+                // `..` at the root refers to the root itself
+                _ if absolute => {}
+                _ => components.push(component),
+            },
+            component => components.push(component),
+        }
+    }
+
+    let normalized = components.join("/");
+    match (absolute, normalized.is_empty()) {
+        (true, _) => format!("/{normalized}"),
+        (false, true) => ".".into(),
+        (false, false) => normalized,
+    }
+}
+
+impl Display for CodeLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.function.as_deref().unwrap_or("<unknown>"))?;
+
+        if let Some(file) = &self.file {
+            write!(f, " at {file}")?;
+
+            if let Some(line) = self.line {
+                write!(f, ":{line}")?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 pub struct AddressResolver<'data> {
@@ -64,11 +133,14 @@ impl<'data> AddressResolver<'data> {
                     .map(|name| function_name(name, None))
             };
 
+            let raw_file = frame.location.as_ref().and_then(|l| l.file);
+            let file = raw_file.map(normalize_path);
+
             Some(CodeLocation {
-                file: frame
-                    .location
-                    .as_ref()
-                    .and_then(|l| l.file.map(String::from)),
+                raw_file: raw_file
+                    .filter(|raw_file| file.as_deref() != Some(*raw_file))
+                    .map(String::from),
+                file,
                 function: function_name,
                 line: frame.location.as_ref().and_then(|l| l.line.map(u64::from)),
                 column: frame
@@ -83,12 +155,239 @@ impl<'data> AddressResolver<'data> {
                 .map(|x| x.name())
                 .map(|name| function_name(name, None));
             Some(CodeLocation {
-                file: None,
                 function: func,
-                line: None,
-                column: None,
+                ..Default::default()
+            })
+        }
+    }
+}
+
+/// Version of the on-disk format of `AddressIndex`,
+/// part of the file name so that old indices are not reused
+const ADDRESS_INDEX_VERSION: u32 = 2;
+
+/// Precomputed source locations of all instructions of a module.
+///
+/// Resolving locations using DWARF is slow for large modules, so the index can be
+/// stored on disk and reused by subsequent invocations.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AddressIndex {
+    /// Instruction offsets and their locations, sorted by offset
+    entries: Vec<(u64, Option<CodeLocation>)>,
+}
+
+impl AddressIndex {
+    /// Construct an index from already resolved locations
+    pub fn from_entries(mut entries: Vec<(u64, Option<CodeLocation>)>) -> Self {
+        entries.sort_by_key(|(offset, _)| *offset);
+        entries.dedup_by_key(|(offset, _)| *offset);
+
+        Self { entries }
+    }
+
+    /// Look up the location of the instruction containing `addr`
+    pub fn lookup_address(&self, addr: u64) -> Option<CodeLocation> {
+        let index = match self
+            .entries
+            .binary_search_by_key(&addr, |(offset, _)| *offset)
+        {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+
+        self.entries[index].1.clone()
+    }
+
+    /// Reverse mapping from source lines to the instructions of this index
+    pub fn line_index(&self) -> LineIndex {
+        let mut lines: BTreeMap<String, BTreeMap<u64, Vec<u64>>> = BTreeMap::new();
+
+        for (offset, location) in &self.entries {
+            if let Some(CodeLocation {
+                file: Some(file),
+                line: Some(line),
+                ..
+            }) = location
+            {
+                lines
+                    .entry(file.clone())
+                    .or_default()
+                    .entry(*line)
+                    .or_default()
+                    .push(*offset);
+            }
+        }
+
+        LineIndex { lines }
+    }
+
+    /// Path of the cached index for a module with the given bytecode
+    pub fn cache_path(cache_directory: &Path, bytes: &[u8]) -> std::path::PathBuf {
+        let hash = md5::compute(bytes);
+        cache_directory
+            .join("address-index")
+            .join(format!("{hash:?}-v{ADDRESS_INDEX_VERSION}.json"))
+    }
+
+    /// Load an index from a file
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let stored: StoredAddressIndex = serde_json::from_reader(file)?;
+        stored.into_index()
+    }
+
+    /// Store the index in a file, creating parent directories if necessary
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // Write to a temporary file first, so that concurrent
+        // invocations never read a partially written index
+        let temporary = path.with_extension(format!("tmp{}", std::process::id()));
+        let file = std::io::BufWriter::new(std::fs::File::create(&temporary)?);
+        serde_json::to_writer(file, &StoredAddressIndex::new(self))?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
+
+/// Instruction offsets of the source lines of a module, the reverse of `AddressIndex`
+#[derive(Debug, Default, PartialEq)]
+pub struct LineIndex {
+    /// Offsets of the instructions of every line, sorted, per file
+    lines: BTreeMap<String, BTreeMap<u64, Vec<u64>>>,
+}
+
+impl LineIndex {
+    /// Source files whose path is `file` or ends with `file`, e.g. `src/add.c` for `add.c`
+    pub fn matching_files(&self, file: &str) -> Vec<&str> {
+        if let Some((path, _)) = self.lines.get_key_value(file) {
+            return vec![path.as_str()];
+        }
+
+        let suffix = format!("/{}", file.trim_start_matches("./"));
+        self.lines
+            .keys()
+            .filter(|path| path.ends_with(&suffix))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Offsets of the instructions mapped to `line` of `file`
+    pub fn offsets(&self, file: &str, line: u64) -> &[u64] {
+        self.lines
+            .get(file)
+            .and_then(|lines| lines.get(&line))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Location with file and function names replaced by indices into a string table
+type StoredLocation = (
+    Option<u32>,
+    Option<u32>,
+    Option<u64>,
+    Option<u64>,
+    Option<u32>,
+);
+
+/// On-disk representation of `AddressIndex`.
+/// File and function names are stored only once, which keeps the index small.
+#[derive(Serialize, Deserialize)]
+struct StoredAddressIndex {
+    strings: Vec<String>,
+    entries: Vec<(u64, Option<StoredLocation>)>,
+}
+
+impl StoredAddressIndex {
+    fn new(index: &AddressIndex) -> Self {
+        fn intern<'a>(
+            s: &'a Option<String>,
+            strings: &mut Vec<String>,
+            string_indices: &mut HashMap<&'a str, u32>,
+        ) -> Option<u32> {
+            s.as_deref().map(|s| {
+                *string_indices.entry(s).or_insert_with(|| {
+                    strings.push(s.to_string());
+                    (strings.len() - 1) as u32
+                })
             })
         }
+
+        let mut strings = Vec::new();
+        let mut string_indices = HashMap::new();
+
+        let entries = index
+            .entries
+            .iter()
+            .map(|(offset, location)| {
+                let location = location.as_ref().map(|l| {
+                    (
+                        intern(&l.file, &mut strings, &mut string_indices),
+                        intern(&l.function, &mut strings, &mut string_indices),
+                        l.line,
+                        l.column,
+                        intern(&l.raw_file, &mut strings, &mut string_indices),
+                    )
+                });
+                (*offset, location)
+            })
+            .collect();
+
+        Self { strings, entries }
+    }
+
+    fn into_index(self) -> Result<AddressIndex> {
+        let string = |index: Option<u32>| -> Result<Option<String>> {
+            index
+                .map(|i| {
+                    self.strings
+                        .get(i as usize)
+                        .cloned()
+                        .context("Invalid string index in address index")
+                })
+                .transpose()
+        };
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|(offset, location)| {
+                let location = location
+                    .map(|(file, function, line, column, raw_file)| -> Result<_> {
+                        Ok(CodeLocation {
+                            file: string(file)?,
+                            function: string(function)?,
+                            line,
+                            column,
+                            raw_file: string(raw_file)?,
+                        })
+                    })
+                    .transpose()?;
+                Ok((*offset, location))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(AddressIndex::from_entries(entries))
+    }
+}
+
+/// Resolves code section offsets to source locations, either
+/// using DWARF debug information or a precomputed index
+pub enum Locator<'a> {
+    Dwarf(AddressResolver<'a>),
+    Index(&'a AddressIndex),
+}
+
+impl<'a> Locator<'a> {
+    pub fn lookup_address(&self, addr: u64) -> Option<CodeLocation> {
+        match self {
+            Locator::Dwarf(resolver) => resolver.lookup_address(addr),
+            Locator::Index(index) => index.lookup_address(addr),
+        }
     }
 }
 
@@ -120,6 +419,137 @@ mod tests {
         Ok(())
     }
 
+    fn location(function: &str) -> Option<CodeLocation> {
+        Some(CodeLocation {
+            function: Some(function.into()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn address_index_lookup() {
+        let index = AddressIndex::from_entries(vec![
+            (20, location("b")),
+            (10, location("a")),
+            (30, None),
+            (20, location("c")),
+        ]);
+
+        assert_eq!(index.lookup_address(5), None);
+        assert_eq!(index.lookup_address(10), location("a"));
+        assert_eq!(index.lookup_address(15), location("a"));
+        assert_eq!(index.lookup_address(20), location("b"));
+        assert_eq!(index.lookup_address(30), None);
+        assert_eq!(index.lookup_address(100), None);
+    }
+
+    #[test]
+    fn address_index_save_load() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let index = AddressIndex::from_entries(vec![
+            (10, location("a")),
+            (20, None),
+            (
+                30,
+                Some(CodeLocation {
+                    file: Some("a.c".into()),
+                    function: Some("a".into()),
+                    line: Some(3),
+                    column: None,
+                    raw_file: Some("build/../a.c".into()),
+                }),
+            ),
+        ]);
+
+        let path = AddressIndex::cache_path(dir.path(), b"module");
+        assert_ne!(path, AddressIndex::cache_path(dir.path(), b"other module"));
+
+        index.save(&path)?;
+        assert_eq!(AddressIndex::load(&path)?, index);
+        assert!(AddressIndex::load(&dir.path().join("missing.json")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn line_index() {
+        let at = |file: &str, line: u64| {
+            Some(CodeLocation {
+                file: Some(file.into()),
+                line: Some(line),
+                ..Default::default()
+            })
+        };
+        let index = AddressIndex::from_entries(vec![
+            (10, at("/src/lib/add.c", 3)),
+            (12, at("/src/lib/add.c", 4)),
+            (14, at("/src/lib/add.c", 3)),
+            (16, location("add")),
+            (20, at("/src/app/add.c", 3)),
+            (22, at("/src/app/main.c", 7)),
+        ])
+        .line_index();
+
+        assert_eq!(index.matching_files("lib/add.c"), vec!["/src/lib/add.c"]);
+        assert_eq!(index.matching_files("./main.c"), vec!["/src/app/main.c"]);
+        assert_eq!(
+            index.matching_files("add.c"),
+            vec!["/src/app/add.c", "/src/lib/add.c"]
+        );
+        assert!(index.matching_files("d.c").is_empty());
+
+        assert_eq!(index.offsets("/src/lib/add.c", 3), [10, 14]);
+        assert_eq!(index.offsets("/src/lib/add.c", 4), [12]);
+        assert!(index.offsets("/src/lib/add.c", 5).is_empty());
+        assert!(index.offsets("add.c", 3).is_empty());
+    }
+
+    #[test]
+    fn normalize_paths() {
+        assert_eq!(normalize_path("/src/add.c"), "/src/add.c");
+        assert_eq!(
+            normalize_path("/build/out/../src/./add.c"),
+            "/build/src/add.c"
+        );
+        assert_eq!(normalize_path("/build//src/add.c"), "/build/src/add.c");
+        assert_eq!(normalize_path("/../src/add.c"), "/src/add.c");
+        assert_eq!(normalize_path("./src/add.c"), "src/add.c");
+        assert_eq!(normalize_path("src/../../lib/add.c"), "../lib/add.c");
+        assert_eq!(normalize_path("../../add.c"), "../../add.c");
+        assert_eq!(normalize_path("src/.."), ".");
+
+        let location = CodeLocation {
+            file: Some("src/add.c".into()),
+            raw_file: Some("./src/add.c".into()),
+            ..Default::default()
+        };
+        assert_eq!(location.raw_file(), Some("./src/add.c"));
+
+        let location = CodeLocation {
+            file: Some("src/add.c".into()),
+            ..Default::default()
+        };
+        assert_eq!(location.raw_file(), Some("src/add.c"));
+    }
+
+    #[test]
+    fn display_code_location() {
+        let location = CodeLocation {
+            file: Some("test.c".into()),
+            function: Some("add".into()),
+            line: Some(3),
+            column: Some(14),
+            raw_file: None,
+        };
+        assert_eq!(format!("{location}"), "add at test.c:3");
+
+        let location = CodeLocation {
+            function: Some("_start".into()),
+            ..Default::default()
+        };
+        assert_eq!(format!("{location}"), "_start");
+        assert_eq!(format!("{}", CodeLocation::default()), "<unknown>");
+    }
+
     #[test]
     fn start_function() -> Result<()> {
         let bytes = read("testdata/simple_add/test.wasm")?;
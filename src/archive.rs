@@ -0,0 +1,143 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use zip::{write::FileOptions, ZipWriter};
+
+/// Pack all files in `directory` (recursively) into a zip archive at `archive_path`.
+pub fn archive_directory<P: AsRef<Path>, Q: AsRef<Path>>(
+    directory: P,
+    archive_path: Q,
+) -> Result<()> {
+    let archive_path = archive_path.as_ref();
+    let mut writer = create_writer(archive_path)?;
+
+    // The archive may be created inside `directory`, it must not contain itself
+    let skip = archive_path.canonicalize()?;
+    add_directory(&mut writer, directory.as_ref(), "", &skip)?;
+
+    writer
+        .finish()
+        .with_context(|| format!("Failed to finish archive {archive_path:?}"))?;
+    log::info!("Created report archive {archive_path:?}");
+    Ok(())
+}
+
+/// Create a zip archive at `archive_path`, containing a single file `name`.
+pub fn archive_content<P: AsRef<Path>>(name: &str, content: &[u8], archive_path: P) -> Result<()> {
+    let archive_path = archive_path.as_ref();
+    let mut writer = create_writer(archive_path)?;
+
+    writer.start_file(name, FileOptions::default())?;
+    writer.write_all(content)?;
+
+    writer
+        .finish()
+        .with_context(|| format!("Failed to finish archive {archive_path:?}"))?;
+    log::info!("Created report archive {archive_path:?}");
+    Ok(())
+}
+
+fn create_writer(archive_path: &Path) -> Result<ZipWriter<File>> {
+    let file = File::create(archive_path)
+        .with_context(|| format!("Failed to create archive {archive_path:?}"))?;
+    Ok(ZipWriter::new(file))
+}
+
+/// Add the content of `directory` to the archive, prefixing all paths with `prefix`.
+/// The file at the canonical path `skip` is not added.
+fn add_directory<W: Write + Seek>(
+    writer: &mut ZipWriter<W>,
+    directory: &Path,
+    prefix: &str,
+    skip: &Path,
+) -> Result<()> {
+    let mut entries = std::fs::read_dir(directory)
+        .with_context(|| format!("Failed to read directory {directory:?}"))?
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    // Sort entries, so that archives are reproducible
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let file_name = entry.file_name();
+        let name = format!("{prefix}{}", file_name.to_string_lossy());
+        let path = entry.path();
+
+        if path.canonicalize().ok().as_deref() == Some(skip) {
+            continue;
+        }
+
+        if path.is_dir() {
+            writer.add_directory(&name, FileOptions::default())?;
+            add_directory(writer, &path, &format!("{name}/"), skip)?;
+        } else {
+            let mut content = Vec::new();
+            File::open(&path)?.read_to_end(&mut content)?;
+
+            writer.start_file(&name, FileOptions::default())?;
+            writer.write_all(&content)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use zip::ZipArchive;
+
+    fn read_entry(archive: &Path, name: &str) -> Result<String> {
+        let mut archive = ZipArchive::new(File::open(archive)?)?;
+        let mut content = String::new();
+        archive.by_name(name)?.read_to_string(&mut content)?;
+        Ok(content)
+    }
+
+    #[test]
+    fn archive_directory_contains_all_files() -> Result<()> {
+        let dir = tempdir()?;
+        let report_dir = dir.path().join("report");
+        std::fs::create_dir_all(report_dir.join("sub"))?;
+        std::fs::write(report_dir.join("index.html"), "index")?;
+        std::fs::write(report_dir.join("sub").join("file.html"), "file")?;
+
+        let archive = dir.path().join("report.zip");
+        archive_directory(&report_dir, &archive)?;
+
+        assert_eq!(read_entry(&archive, "index.html")?, "index");
+        assert_eq!(read_entry(&archive, "sub/file.html")?, "file");
+        Ok(())
+    }
+
+    #[test]
+    fn archive_inside_directory_is_skipped() -> Result<()> {
+        let dir = tempdir()?;
+        std::fs::write(dir.path().join("index.html"), "index")?;
+
+        let archive = dir.path().join("report.zip");
+        archive_directory(dir.path(), &archive)?;
+
+        let entries = ZipArchive::new(File::open(&archive)?)?
+            .file_names()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(entries, ["index.html"]);
+        Ok(())
+    }
+
+    #[test]
+    fn archive_single_file() -> Result<()> {
+        let dir = tempdir()?;
+        let archive = dir.path().join("report.zip");
+        archive_content("report.json", b"{}", &archive)?;
+
+        assert_eq!(read_entry(&archive, "report.json")?, "{}");
+        Ok(())
+    }
+}
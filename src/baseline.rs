@@ -0,0 +1,131 @@
+//! Results of the baseline run (`wasmut baseline`), i.e. the execution of
+//! the unmutated module, which can be reused by `wasmut mutate --use-baseline`.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ErrorKind, runtime::TracePoints};
+
+/// Default file name of a baseline file
+pub const BASELINE_FILE_NAME: &str = "wasmut-baseline.json";
+
+/// Result of executing the unmutated module
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Baseline {
+    /// Version of wasmut that recorded the baseline
+    pub version: String,
+
+    /// MD5 hash of the module, used to detect outdated baselines
+    pub module_hash: String,
+
+    /// Exit code of the module
+    pub exit_code: u32,
+
+    /// Number of instructions executed by the uninstrumented module
+    pub execution_cost: u64,
+
+    /// Wall-clock time needed to execute the module, in milliseconds
+    pub duration: u64,
+
+    /// How often every instruction was executed, if the module was instrumented
+    pub coverage: Option<TracePoints>,
+
+    /// Captured stdout of the module
+    pub stdout: String,
+
+    /// Captured stderr of the module
+    pub stderr: String,
+}
+
+impl Baseline {
+    /// Hash identifying the bytecode of a module
+    pub fn hash_module(bytes: &[u8]) -> String {
+        format!("{:?}", md5::compute(bytes))
+    }
+
+    /// Load a baseline file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline file {path:?}"))
+            .context(ErrorKind::Baseline)?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid baseline file {path:?}"))
+            .context(ErrorKind::Baseline)
+    }
+
+    /// Save the baseline to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write baseline file {path:?}"))
+    }
+
+    /// Check that the baseline was recorded successfully for a module with the given bytecode
+    pub fn check(&self, bytes: &[u8]) -> Result<()> {
+        if self.module_hash != Self::hash_module(bytes) {
+            return Err(anyhow!(
+                "Baseline was recorded for a different module, run `wasmut baseline` again"
+            ))
+            .context(ErrorKind::Baseline);
+        }
+
+        if self.exit_code != 0 {
+            return Err(anyhow!(
+                "Module without any mutations returned exit code {}",
+                self.exit_code
+            ))
+            .context(ErrorKind::Baseline);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline(bytes: &[u8], exit_code: u32) -> Baseline {
+        let mut coverage = TracePoints::default();
+        coverage.add_point(42);
+
+        Baseline {
+            version: "0.1.0".into(),
+            module_hash: Baseline::hash_module(bytes),
+            exit_code,
+            execution_cost: 1234,
+            duration: 10,
+            coverage: Some(coverage),
+            stdout: "hello".into(),
+            stderr: String::new(),
+        }
+    }
+
+    #[test]
+    fn save_and_load() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(BASELINE_FILE_NAME);
+
+        baseline(b"module", 0).save(&path)?;
+        let loaded = Baseline::load(&path)?;
+
+        assert_eq!(loaded.execution_cost, 1234);
+        assert_eq!(loaded.stdout, "hello");
+        assert!(loaded.coverage.unwrap().is_covered(42));
+        Ok(())
+    }
+
+    #[test]
+    fn check_baseline() {
+        assert!(baseline(b"module", 0).check(b"module").is_ok());
+
+        let error = baseline(b"module", 0).check(b"other").unwrap_err();
+        assert_eq!(crate::error::exit_code(&error), 3);
+
+        assert!(baseline(b"module", 1).check(b"module").is_err());
+    }
+}
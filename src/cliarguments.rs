@@ -1,14 +1,24 @@
-use std::ffi::OsString;
+use std::{ffi::OsString, time::Duration};
 
 use clap::{Parser, Subcommand, ValueEnum};
 
+use wasmut::{
+    baseline::BASELINE_FILE_NAME, server::DEFAULT_ADDRESS, workspace::WORKSPACE_FILE_NAME,
+};
+
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 pub struct CLIArguments {
     #[clap(subcommand)]
     pub command: CLICommand,
+
+    /// Write command output (e.g. reports) to a file instead of stdout
+    #[clap(long, global = true, value_name = "FILE")]
+    pub output_file: Option<String>,
 }
 
+// Parsed once per invocation, so the size of the `mutate` arguments does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum CLICommand {
     /// List all functions of the binary.
@@ -25,6 +35,15 @@ pub enum CLICommand {
         #[clap(short = 'C', long)]
         config_samedir: bool,
 
+        /// Also show function indices, type signatures, code sizes
+        /// and whether the function is exported
+        #[clap(short, long)]
+        detailed: bool,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t=Format::Text)]
+        format: Format,
+
         /// Path to the wasm module
         wasmfile: String,
     },
@@ -42,6 +61,149 @@ pub enum CLICommand {
         #[clap(short = 'C', long)]
         config_samedir: bool,
 
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t=Format::Text)]
+        format: Format,
+
+        /// Path to the wasm module
+        wasmfile: String,
+    },
+    /// Show per-function coverage and execution cost of the unmutated module.
+    ///
+    /// The module is executed once with tracing enabled. For every function,
+    /// wasmut shows how many of its instructions were covered, how many
+    /// instructions were executed in total and how many edges of its
+    /// conditional branches were taken.
+    Coverage {
+        /// Load wasmut.toml configuration file from the provided path
+        #[clap(short, long)]
+        config: Option<String>,
+
+        /// Attempt to load wasmut.toml from the same directory as the wasm module
+        #[clap(short = 'C', long)]
+        config_samedir: bool,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t=Format::Text)]
+        format: Format,
+
+        /// Path to the wasm module
+        wasmfile: String,
+    },
+    /// Compare the execution of a single mutant with the unmutated module.
+    ///
+    /// Both are executed with tracing of basic blocks enabled. wasmut shows the
+    /// first block at which the executions diverge, and the functions in which
+    /// a different number of blocks was executed, e.g. to explain why a mutant survived.
+    TraceDiff {
+        /// Load wasmut.toml configuration file from the provided path
+        #[clap(short, long)]
+        config: Option<String>,
+
+        /// Attempt to load wasmut.toml from the same directory as the wasm module
+        #[clap(short = 'C', long)]
+        config_samedir: bool,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t=Format::Text)]
+        format: Format,
+
+        /// ID of the mutant, as shown in the reports
+        #[clap(short, long, value_name = "ID")]
+        mutant_id: String,
+
+        /// Path to the wasm module
+        wasmfile: String,
+    },
+    /// Apply every discovered mutation on its own and validate the resulting module.
+    ///
+    /// Reports all operators producing invalid bytecode for the module, e.g. to
+    /// check modules from unusual toolchains before starting a long mutation run.
+    VerifyOperators {
+        /// Load wasmut.toml configuration file from the provided path
+        #[clap(short, long)]
+        config: Option<String>,
+
+        /// Attempt to load wasmut.toml from the same directory as the wasm module
+        #[clap(short = 'C', long)]
+        config_samedir: bool,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t=Format::Text)]
+        format: Format,
+
+        /// Path to the wasm module
+        wasmfile: String,
+    },
+    /// Execute the unmutated module and save the results to a baseline file.
+    ///
+    /// The baseline contains the exit code, execution cost, coverage and output
+    /// of the module. It can be reused by `mutate --use-baseline`.
+    Baseline {
+        /// Load wasmut.toml configuration file from the provided path
+        #[clap(short, long)]
+        config: Option<String>,
+
+        /// Attempt to load wasmut.toml from the same directory as the wasm module
+        #[clap(short = 'C', long)]
+        config_samedir: bool,
+
+        /// Path of the baseline file
+        #[clap(short, long, default_value = BASELINE_FILE_NAME)]
+        output: String,
+
+        /// Do not record coverage
+        #[clap(long)]
+        no_coverage: bool,
+
+        /// Path to the wasm module
+        wasmfile: String,
+    },
+    /// Explain what `mutate` would do, without executing any mutants.
+    ///
+    /// Shows the enabled operators, the number of mutants per file, the execution
+    /// mode, the number of compilations and the timeout budget.
+    Plan {
+        /// Load wasmut.toml configuration file from the provided path
+        #[clap(short, long)]
+        config: Option<String>,
+
+        /// Attempt to load wasmut.toml from the same directory as the wasm module
+        #[clap(short = 'C', long)]
+        config_samedir: bool,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t=Format::Text)]
+        format: Format,
+
+        /// Path to the wasm module
+        wasmfile: String,
+    },
+    /// Show the mutants of a source line, without executing them.
+    ///
+    /// Lists every instruction mapped to the line, the enabled operators
+    /// applicable to it and the instructions it would be replaced with.
+    Preview {
+        /// Load wasmut.toml configuration file from the provided path
+        #[clap(short, long)]
+        config: Option<String>,
+
+        /// Attempt to load wasmut.toml from the same directory as the wasm module
+        #[clap(short = 'C', long)]
+        config_samedir: bool,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t=Format::Text)]
+        format: Format,
+
+        /// Source file, or the end of its path (e.g. `src/add.c` or `add.c`)
+        #[clap(long)]
+        file: String,
+
+        /// Line in the source file
+        #[clap(long)]
+        line: u64,
+
         /// Path to the wasm module
         wasmfile: String,
     },
@@ -66,18 +228,152 @@ pub enum CLICommand {
         #[clap(short, long, value_enum, default_value_t=Output::Console)]
         report: Output,
 
-        /// Output directory for reports
-        #[clap(short, long, default_value = "wasmut-report")]
-        output: String,
+        /// Output directory for reports, may contain {timestamp} and {module}.
+        /// Defaults to `output_pattern` of the configuration or wasmut-report
+        #[clap(short, long)]
+        output: Option<String>,
 
         /// The percentage of all mutants which should be executed
         #[clap(short, long, default_value_t = 100)]
         sample_threshold: i32,
 
+        /// Seed for selecting the executed mutants with --sample-threshold.
+        /// Defaults to a random seed, which is recorded in the run manifest
+        #[clap(long)]
+        seed: Option<u32>,
+
+        /// Do not ask for confirmation before executing mutants
+        #[clap(short, long)]
+        yes: bool,
+
+        /// Only mutate functions matching this regular expression.
+        /// Overrides the filter configuration, can be used multiple times
+        #[clap(long = "function", value_name = "REGEX")]
+        functions: Vec<String>,
+
+        /// Only mutate files matching this regular expression.
+        /// Overrides the filter configuration, can be used multiple times
+        #[clap(long = "file", value_name = "REGEX")]
+        files: Vec<String>,
+
+        /// Only mutate files changed since this git revision (e.g. `origin/main`),
+        /// including uncommitted and untracked files
+        #[clap(long, value_name = "REV")]
+        changed_since: Option<String>,
+
+        /// Package the generated html, json or gitlab report into a zip archive
+        #[clap(long, value_name = "ZIPFILE")]
+        archive: Option<String>,
+
+        /// Replace source paths and function names in html and json reports by
+        /// stable pseudonyms, so that reports can be shared without revealing
+        /// the structure of the code
+        #[clap(long)]
+        redact_paths: bool,
+
+        /// Exit with code 4 if the mutation score is below SCORE (in percent).
+        /// Without a value, the `warning` threshold of the configuration is used.
+        /// Runs without any executed mutant fail as well
+        #[clap(long, value_name = "SCORE")]
+        fail_under: Option<Option<f32>>,
+
+        /// Only execute the mutants with these IDs (comma-separated), as shown
+        /// in the reports. The mutation score only covers the selected mutants
+        #[clap(long, value_name = "IDS", value_delimiter = ',')]
+        mutant_ids: Vec<String>,
+
+        /// Only execute the mutants that survived in the latest run of the module
+        /// stored in the result database (`[database] path`), e.g. to re-check
+        /// triaged survivors after adding tests
+        #[clap(long)]
+        rerun_survivors: bool,
+
+        /// Use the baseline recorded by `wasmut baseline` instead of
+        /// executing the unmutated module again
+        #[clap(long, value_name = "FILE")]
+        use_baseline: Option<String>,
+
+        /// Abort if the module has no DWARF line information,
+        /// instead of reporting mutants by function and code offset
+        #[clap(long)]
+        require_debug_info: bool,
+
+        /// Stop starting new mutants after DURATION (e.g. 90s, 30m, 1h30m).
+        /// Mutants that were not started are reported as not run and are
+        /// excluded from the mutation score
+        #[clap(long, value_name = "DURATION", value_parser = parse_duration)]
+        max_runtime: Option<Duration>,
+
+        /// Ignore locks of other wasmut runs on the output and cache directories
+        #[clap(long)]
+        force: bool,
+
+        /// After a meta-mutant run, execute N randomly picked mutants again
+        /// one by one, and fail if any of their outcomes differs
+        #[clap(long, value_name = "N")]
+        verify_sample: Option<usize>,
+
+        /// Path to the wasm module
+        wasmfile: String,
+    },
+
+    /// Generate inputs that kill surviving mutants (experimental).
+    ///
+    /// After executing all mutants, wasmut perturbs the command line arguments
+    /// and input files configured in the [suggest] section and reruns the surviving
+    /// mutants. Inputs that kill a mutant are reported and saved to the output directory.
+    Suggest {
+        /// Load wasmut.toml configuration file from the provided path
+        #[clap(short, long)]
+        config: Option<String>,
+
+        /// Attempt to load wasmut.toml from the same directory as the wasm module
+        #[clap(short = 'C', long)]
+        config_samedir: bool,
+
+        /// Number of threads to use when executing mutants
+        #[clap(short, long)]
+        threads: Option<usize>,
+
+        /// Output directory for generated inputs
+        #[clap(short, long, default_value = "wasmut-suggestions")]
+        output: String,
+
+        /// Do not ask for confirmation before executing mutants
+        #[clap(short, long)]
+        yes: bool,
+
         /// Path to the wasm module
         wasmfile: String,
     },
 
+    /// Serve an HTTP API for submitting mutation runs and fetching their results.
+    ///
+    /// Runs are submitted with `POST /runs` and executed one after another.
+    /// Their status is available at `GET /runs/<id>`, the JSON report
+    /// of a finished run at `GET /runs/<id>/report`.
+    ServeApi {
+        /// Address to listen on
+        #[clap(short, long, default_value = DEFAULT_ADDRESS)]
+        address: String,
+
+        /// Token that requests have to send as `Authorization: Bearer <TOKEN>`.
+        /// Defaults to the WASMUT_API_TOKEN environment variable, or a random
+        /// token that is logged at startup
+        #[clap(long)]
+        token: Option<String>,
+
+        /// Number of threads to use when executing mutants
+        #[clap(short, long)]
+        threads: Option<usize>,
+    },
+
+    /// Run commands for all modules described in a workspace manifest.
+    Workspace {
+        #[clap(subcommand)]
+        command: WorkspaceCommand,
+    },
+
     /// Create new configuration file.
     NewConfig {
         /// Path to the new configuration file
@@ -112,9 +408,59 @@ pub enum CLICommand {
         #[clap(short = 'C', long)]
         config_samedir: bool,
 
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t=Format::Text)]
+        format: Format,
+
         /// Path to the wasm module
         wasmfile: Option<String>,
     },
+
+    /// Generate a corpus of self-checking test modules.
+    ///
+    /// Every module gets its own directory containing the module and a
+    /// default configuration file. Only available with the `testgen` feature.
+    #[cfg(feature = "testgen")]
+    Testgen {
+        /// Output directory
+        #[clap(short, long, default_value = "testgen")]
+        output: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorkspaceCommand {
+    /// Generate and run mutants for all targets of a workspace.
+    ///
+    /// Every target is mutated with its own configuration. Afterwards, a dashboard
+    /// summarizing all targets is shown. With --report html, the report of every
+    /// target is written to a subdirectory of the output directory, together with
+    /// a combined dashboard (index.html).
+    Mutate {
+        /// Path to the workspace manifest
+        #[clap(short, long, default_value = WORKSPACE_FILE_NAME)]
+        workspace: String,
+
+        /// Number of threads to use when executing mutants
+        #[clap(short, long)]
+        threads: Option<usize>,
+
+        /// Report output format (console or html)
+        #[clap(short, long, value_enum, default_value_t=Output::Console)]
+        report: Output,
+
+        /// Output directory for reports
+        #[clap(short, long, default_value = "wasmut-report")]
+        output: String,
+
+        /// Do not ask for confirmation before executing mutants
+        #[clap(short, long)]
+        yes: bool,
+
+        /// Ignore locks of other wasmut runs on the output and cache directories
+        #[clap(long)]
+        force: bool,
+    },
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -122,6 +468,17 @@ pub enum Output {
     Console,
     Html,
     Json,
+    /// GitHub Actions workflow commands, shown as annotations on pull requests
+    Github,
+    /// GitLab Code Quality report, shown in the merge request widget
+    Gitlab,
+}
+
+/// Output format of informational commands (coverage and list-*)
+#[derive(ValueEnum, Clone, Debug)]
+pub enum Format {
+    Text,
+    Json,
 }
 
 impl CLIArguments {
@@ -139,13 +496,63 @@ impl CLIArguments {
     }
 }
 
+/// Parse a duration like `90s`, `30m` or `1h30m`. Plain numbers are seconds.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    if let Ok(seconds) = s.parse::<u64>() {
+        return Ok(Duration::from_secs(seconds));
+    }
+
+    if s.is_empty() {
+        return Err("empty duration".into());
+    }
+
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let factor = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(format!("invalid unit '{c}', expected h, m or s")),
+        };
+        let value: u64 = number
+            .parse()
+            .map_err(|_| format!("missing number before '{c}'"))?;
+        seconds += value * factor;
+        number.clear();
+    }
+
+    if !number.is_empty() {
+        return Err(format!("missing unit after '{number}'"));
+    }
+
+    Ok(Duration::from_secs(seconds))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::cliarguments::CLIArguments;
+    use super::*;
 
     #[test]
     fn test_cli() {
         use clap::CommandFactory;
         CLIArguments::command().debug_assert()
     }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("5 min").is_err());
+    }
 }
@@ -1,20 +1,70 @@
-use anyhow::{Context, Result};
-use std::path::Path;
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
 
-use crate::templates;
-use serde::Deserialize;
+use crate::{
+    cost::CostUnit,
+    criterion::{Combination, CriterionKind},
+    error::ErrorKind,
+    executor::ExpensiveFunctionAction,
+    operator::OperatorPreset,
+    policy::FilterPreset,
+    reportdir,
+    reporter::ScoreThresholds,
+    runtime::RuntimeKind,
+    templates,
+    wasmmodule::MutantActivation,
+};
+use serde::{Deserialize, Serialize};
 
 /// Default value for the `timeout_multiplier` configuration key
 pub const TIMEOUT_MULTIPLIER: f64 = 2.0;
 
+/// Default value for the `timeout_warning_threshold` configuration key
+pub const TIMEOUT_WARNING_THRESHOLD: f64 = 20.0;
+
+/// Default value for the `meta_mutant_chunk_size` configuration key
+pub const META_MUTANT_CHUNK_SIZE: usize = 10000;
+
+/// Default value for the `hybrid_threshold` configuration key
+pub const HYBRID_THRESHOLD: u64 = 10000;
+
+/// Default value for the `expensive_function_timeout_multiplier` configuration key
+pub const EXPENSIVE_FUNCTION_TIMEOUT_MULTIPLIER: f64 = 1.25;
+
 /// Configuration for mutant filtering.
-#[derive(Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct FilterConfig {
     /// Regex list of all files that should be mutated
     allowed_files: Option<Vec<String>>,
 
     /// Regex list of all functions that should be mutated
     allowed_functions: Option<Vec<String>>,
+
+    /// If true, instructions without source file information are never mutated.
+    /// If false, they can still be allowed by `allowed_functions`.
+    /// Defaults to false
+    require_source_info: Option<bool>,
+
+    /// Functions with fewer instructions than this are not mutated.
+    /// Defaults to 0
+    min_function_instructions: Option<u64>,
+
+    /// If set, only files changed since this git revision are mutated
+    only_changed_files: Option<String>,
+
+    /// If set, only the given number of functions with the highest
+    /// complexity (number of branches) are mutated
+    most_complex_functions: Option<usize>,
+
+    /// If set, at most this number of mutants is generated per function,
+    /// spread evenly over the function
+    max_mutants_per_function: Option<usize>,
+
+    /// Operators and sampling rates for specific files
+    overrides: Option<Vec<FilterOverride>>,
+
+    /// Built-in set of runtime support functions that are never mutated
+    preset: Option<FilterPreset>,
 }
 
 impl FilterConfig {
@@ -27,15 +77,131 @@ impl FilterConfig {
     pub fn allowed_functions(&self) -> Option<&Vec<String>> {
         self.allowed_functions.as_ref()
     }
+
+    /// Only mutate instructions with source file information
+    pub fn require_source_info(&self) -> bool {
+        self.require_source_info.unwrap_or(false)
+    }
+
+    /// Minimum number of instructions a function needs to have to be mutated
+    pub fn min_function_instructions(&self) -> u64 {
+        self.min_function_instructions.unwrap_or(0)
+    }
+
+    /// Git revision, if only files changed since this revision should be mutated
+    pub fn only_changed_files(&self) -> Option<&str> {
+        self.only_changed_files.as_deref()
+    }
+
+    /// Number of most complex functions that are mutated, if set
+    pub fn most_complex_functions(&self) -> Option<usize> {
+        self.most_complex_functions
+    }
+
+    /// Maximum number of mutants per function, if set
+    pub fn max_mutants_per_function(&self) -> Option<usize> {
+        self.max_mutants_per_function
+    }
+
+    /// Overrides for specific files, in the order of the configuration
+    pub fn overrides(&self) -> &[FilterOverride] {
+        self.overrides.as_deref().unwrap_or_default()
+    }
+
+    /// Built-in set of functions excluded from mutation, if any
+    pub fn preset(&self) -> Option<FilterPreset> {
+        self.preset
+    }
+
+    /// Only mutate files changed since `revision`, e.g. as provided on the command line.
+    pub fn override_only_changed_files(&mut self, revision: String) {
+        self.only_changed_files = Some(revision);
+    }
+
+    /// Replace allowed files and functions, e.g. with values
+    /// provided on the command line.
+    pub fn override_allowed(&mut self, files: Vec<String>, functions: Vec<String>) {
+        self.allowed_files = Some(files);
+        self.allowed_functions = Some(functions);
+    }
+
+    /// Copy of the section, with defaults filled in
+    fn effective(&self) -> Self {
+        Self {
+            allowed_files: self.allowed_files.clone(),
+            allowed_functions: self.allowed_functions.clone(),
+            require_source_info: Some(self.require_source_info()),
+            min_function_instructions: Some(self.min_function_instructions()),
+            only_changed_files: self.only_changed_files.clone(),
+            most_complex_functions: self.most_complex_functions,
+            max_mutants_per_function: self.max_mutants_per_function,
+            overrides: Some(self.overrides().to_vec()),
+            preset: self.preset,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.max_mutants_per_function == Some(0) {
+            return Err(anyhow!("max_mutants_per_function must be greater than 0"))
+                .context(ErrorKind::Config);
+        }
+
+        Ok(())
+    }
+}
+
+/// Operators and sampling rate used for the files matched by an override.
+///
+/// If a file is matched by multiple overrides, the first one is used.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct FilterOverride {
+    /// Regex list of files the override applies to
+    files: Vec<String>,
+
+    /// (Regex) list of enabled mutation operators, replacing `operators.enabled_operators`
+    enabled_operators: Option<Vec<String>>,
+
+    /// (Regex) list of disabled mutation operators, replacing `operators.disabled_operators`
+    disabled_operators: Option<Vec<String>>,
+
+    /// Percentage of mutants that are executed, replacing `--sample-threshold`
+    sample_threshold: Option<i32>,
+}
+
+impl FilterOverride {
+    /// Regex list of files the override applies to
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// Enabled operators, if they differ from the `[operators]` section
+    pub fn enabled_operators(&self) -> Option<&[String]> {
+        self.enabled_operators.as_deref()
+    }
+
+    /// Disabled operators, if they differ from the `[operators]` section
+    pub fn disabled_operators(&self) -> Option<&[String]> {
+        self.disabled_operators.as_deref()
+    }
+
+    /// Percentage of mutants that are executed, if it differs from the command line
+    pub fn sample_threshold(&self) -> Option<i32> {
+        self.sample_threshold
+    }
 }
 
 /// Configuration for the execution engine
-#[derive(Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct EngineConfig {
     /// Execution timeout multiplier. timeout will be
     /// set to cycles measured in baseline run multiplied by this factor
     timeout_multiplier: Option<f64>,
 
+    /// If more than this percentage of the executed mutants time out,
+    /// wasmut suggests raising `timeout_multiplier`.
+    /// Defaults to `TIMEOUT_WARNING_THRESHOLD`.
+    timeout_warning_threshold: Option<f64>,
+
     /// A list of all directories that are to be mapped into the runtime
     map_dirs: Option<Vec<(String, String)>>,
 
@@ -48,6 +214,45 @@ pub struct EngineConfig {
     /// During execution, mutations are activated by setting a flag
     /// Defaults to true.
     meta_mutant: Option<bool>,
+
+    /// Maximum number of mutations contained in a single meta-mutant.
+    /// If there are more mutations, multiple meta-mutants are generated.
+    /// Defaults to `META_MUTANT_CHUNK_SIZE`.
+    meta_mutant_chunk_size: Option<usize>,
+
+    /// How the meta-mutant checks whether a mutation is activated.
+    /// Defaults to `MutantActivation::Global`.
+    meta_mutant_activation: Option<MutantActivation>,
+
+    /// If true, surviving mutants are executed a second time, recording
+    /// their output and the sequence of called functions.
+    /// Defaults to false.
+    trace_survivors: Option<bool>,
+
+    /// WebAssembly runtime used to execute the module and its mutants.
+    /// Defaults to `RuntimeKind::Wasmer`.
+    runtime: Option<RuntimeKind>,
+
+    /// With the hybrid runtime, mutants in functions that executed at most
+    /// this many instructions in the baseline run are interpreted.
+    /// Defaults to `HYBRID_THRESHOLD`.
+    hybrid_threshold: Option<u64>,
+
+    /// Number of instructions executed per second, used to convert execution
+    /// costs into approximate time. Defaults to a calibration during the baseline run.
+    instructions_per_second: Option<u64>,
+
+    /// Functions that executed more than this percentage of all instructions
+    /// in the baseline run are reported as expensive. Disabled by default.
+    expensive_function_threshold: Option<f64>,
+
+    /// What happens to mutants of expensive functions.
+    /// Defaults to `ExpensiveFunctionAction::Report`.
+    expensive_function_action: Option<ExpensiveFunctionAction>,
+
+    /// Timeout multiplier for mutants of expensive functions, if their timeout is shortened.
+    /// Defaults to `EXPENSIVE_FUNCTION_TIMEOUT_MULTIPLIER`.
+    expensive_function_timeout_multiplier: Option<f64>,
 }
 
 impl EngineConfig {
@@ -56,6 +261,12 @@ impl EngineConfig {
         self.timeout_multiplier.unwrap_or(TIMEOUT_MULTIPLIER)
     }
 
+    /// Percentage of timed out mutants above which a warning is emitted
+    pub fn timeout_warning_threshold(&self) -> f64 {
+        self.timeout_warning_threshold
+            .unwrap_or(TIMEOUT_WARNING_THRESHOLD)
+    }
+
     /// A list of all directories that are to be mapped into the runtime
     pub fn map_dirs(&self) -> &[(String, String)] {
         if let Some(map_dirs) = self.map_dirs.as_ref() {
@@ -74,13 +285,185 @@ impl EngineConfig {
     pub fn meta_mutant(&self) -> bool {
         self.meta_mutant.unwrap_or(true)
     }
+
+    /// Switch between meta-mutant and one-by-one mode, e.g. to compare both modes
+    pub fn override_meta_mutant(&mut self, meta_mutant: bool) {
+        self.meta_mutant = Some(meta_mutant);
+    }
+
+    /// Maximum number of mutations in a single meta-mutant
+    pub fn meta_mutant_chunk_size(&self) -> usize {
+        self.meta_mutant_chunk_size
+            .unwrap_or(META_MUTANT_CHUNK_SIZE)
+            .max(1)
+    }
+
+    /// How the meta-mutant checks whether a mutation is activated
+    pub fn meta_mutant_activation(&self) -> MutantActivation {
+        self.meta_mutant_activation
+            .unwrap_or(MutantActivation::Global)
+    }
+
+    /// Record execution traces of surviving mutants
+    pub fn trace_survivors(&self) -> bool {
+        self.trace_survivors.unwrap_or(false)
+    }
+
+    /// WebAssembly runtime used for execution
+    pub fn runtime(&self) -> RuntimeKind {
+        self.runtime.unwrap_or_default()
+    }
+
+    /// Maximum number of instructions executed by a function in the baseline
+    /// run, for which the hybrid runtime interprets mutants of the function
+    pub fn hybrid_threshold(&self) -> u64 {
+        self.hybrid_threshold.unwrap_or(HYBRID_THRESHOLD)
+    }
+
+    /// Configured number of instructions executed per second,
+    /// `None` if it is calibrated during the baseline run
+    pub fn instructions_per_second(&self) -> Option<u64> {
+        self.instructions_per_second
+    }
+
+    /// Percentage of the instructions executed in the baseline run above which
+    /// a function is expensive, `None` if expensive functions are not detected
+    pub fn expensive_function_threshold(&self) -> Option<f64> {
+        self.expensive_function_threshold
+    }
+
+    /// What happens to mutants of expensive functions
+    pub fn expensive_function_action(&self) -> ExpensiveFunctionAction {
+        self.expensive_function_action.unwrap_or_default()
+    }
+
+    /// Timeout multiplier for mutants of expensive functions,
+    /// used if their timeout is shortened
+    pub fn expensive_function_timeout_multiplier(&self) -> f64 {
+        self.expensive_function_timeout_multiplier
+            .unwrap_or(EXPENSIVE_FUNCTION_TIMEOUT_MULTIPLIER)
+    }
+
+    /// Copy of the section, with defaults filled in
+    fn effective(&self) -> Self {
+        Self {
+            timeout_multiplier: Some(self.timeout_multiplier()),
+            timeout_warning_threshold: Some(self.timeout_warning_threshold()),
+            map_dirs: Some(self.map_dirs().to_vec()),
+            coverage_based_execution: Some(self.coverage_based_execution()),
+            meta_mutant: Some(self.meta_mutant()),
+            meta_mutant_chunk_size: Some(self.meta_mutant_chunk_size()),
+            meta_mutant_activation: Some(self.meta_mutant_activation()),
+            trace_survivors: Some(self.trace_survivors()),
+            runtime: Some(self.runtime()),
+            hybrid_threshold: Some(self.hybrid_threshold()),
+            instructions_per_second: self.instructions_per_second,
+            expensive_function_threshold: self.expensive_function_threshold,
+            expensive_function_action: Some(self.expensive_function_action()),
+            expensive_function_timeout_multiplier: Some(
+                self.expensive_function_timeout_multiplier(),
+            ),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.runtime() != RuntimeKind::Wasmer && !self.map_dirs().is_empty() {
+            return Err(anyhow!(
+                "engine.map_dirs is not supported by the wasmi runtime, use runtime = \"wasmer\""
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        if self.runtime() == RuntimeKind::Hybrid && !self.meta_mutant() {
+            return Err(anyhow!("runtime = \"hybrid\" requires meta_mutant = true"))
+                .context(ErrorKind::Config);
+        }
+
+        if !(0.0..=100.0).contains(&self.timeout_warning_threshold()) {
+            return Err(anyhow!(
+                "timeout_warning_threshold must be between 0 and 100"
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        if self.instructions_per_second == Some(0) {
+            return Err(anyhow!("instructions_per_second must be greater than 0"))
+                .context(ErrorKind::Config);
+        }
+
+        if let Some(threshold) = self.expensive_function_threshold {
+            if !(threshold > 0.0 && threshold <= 100.0) {
+                return Err(anyhow!(
+                    "expensive_function_threshold must be greater than 0 and at most 100"
+                ))
+                .context(ErrorKind::Config);
+            }
+        }
+
+        if self.expensive_function_action() != ExpensiveFunctionAction::Report
+            && self.expensive_function_threshold.is_none()
+        {
+            return Err(anyhow!(
+                "expensive_function_action requires expensive_function_threshold"
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        let multiplier = self.expensive_function_timeout_multiplier();
+        if self.expensive_function_action() == ExpensiveFunctionAction::ShortenTimeout
+            && !(1.0..=self.timeout_multiplier()).contains(&multiplier)
+        {
+            return Err(anyhow!(
+                "expensive_function_timeout_multiplier must be between 1 and \
+                timeout_multiplier ({}), got {multiplier}",
+                self.timeout_multiplier()
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        Ok(())
+    }
 }
 
 /// Configuration regarding report generation
-#[derive(Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct ReportConfig {
     /// Rewrite paths using Regex::replace
     path_rewrite: Option<(String, String)>,
+
+    /// Write an SVG badge with the mutation score into the report directory
+    badge: Option<bool>,
+
+    /// Mutation score thresholds, used by all reporters
+    thresholds: Option<ScoreThresholds>,
+
+    /// Unit in which execution costs are shown in logs and summaries
+    cost_unit: Option<CostUnit>,
+
+    /// Merge variants of an operator at the same instruction into a single mutant
+    group_variants: Option<bool>,
+
+    /// Output directory of HTML reports, may contain `{timestamp}` and `{module}`
+    output_pattern: Option<String>,
+
+    /// Number of reports created from `output_pattern` that are kept
+    keep_last: Option<usize>,
+
+    /// List the reason and the nearest executed line for mutants skipped because
+    /// of missing coverage
+    explain_skipped: Option<bool>,
+
+    /// Directories with additional syntect syntax definitions (`.sublime-syntax`)
+    syntax_dirs: Option<Vec<String>>,
+
+    /// Number of source lines shown before and after each mutant by the CLI reporter
+    context_lines: Option<usize>,
+
+    /// Log a progress summary every this many minutes during execution
+    summary_interval_minutes: Option<u64>,
+
+    /// Log a progress summary every this many finished mutants during execution
+    summary_interval_mutants: Option<usize>,
 }
 
 impl ReportConfig {
@@ -90,144 +473,811 @@ impl ReportConfig {
             .as_ref()
             .map(|(regex, replacement)| (regex.as_ref(), replacement.as_ref()))
     }
+
+    /// Return whether an SVG badge should be written
+    pub fn badge(&self) -> bool {
+        self.badge.unwrap_or(false)
+    }
+
+    /// Return the mutation score thresholds
+    pub fn thresholds(&self) -> ScoreThresholds {
+        self.thresholds.unwrap_or_default()
+    }
+
+    /// Return the unit in which execution costs are shown
+    pub fn cost_unit(&self) -> CostUnit {
+        self.cost_unit.unwrap_or_default()
+    }
+
+    /// Return whether variants of an operator at the same instruction
+    /// (e.g. signed and unsigned division) are reported as a single mutant
+    pub fn group_variants(&self) -> bool {
+        self.group_variants.unwrap_or(false)
+    }
+
+    /// Return the pattern of the output directory, if configured
+    pub fn output_pattern(&self) -> Option<&str> {
+        self.output_pattern.as_deref()
+    }
+
+    /// Return the number of reports created from `output_pattern` that are kept,
+    /// `None` if old reports are never removed
+    pub fn keep_last(&self) -> Option<usize> {
+        self.keep_last
+    }
+
+    /// List why mutants were skipped because of missing coverage
+    pub fn explain_skipped(&self) -> bool {
+        self.explain_skipped.unwrap_or(false)
+    }
+
+    /// Return the directories with additional syntax definitions for
+    /// highlighting source code in reports
+    pub fn syntax_dirs(&self) -> &[String] {
+        self.syntax_dirs.as_deref().unwrap_or_default()
+    }
+
+    /// Return the number of source lines shown before and after
+    /// each mutant in the console report
+    pub fn context_lines(&self) -> usize {
+        self.context_lines.unwrap_or(0)
+    }
+
+    /// Return the interval in minutes in which a progress summary is logged,
+    /// `None` if summaries are not logged periodically
+    pub fn summary_interval_minutes(&self) -> Option<u64> {
+        self.summary_interval_minutes
+    }
+
+    /// Return the number of mutants after which a progress summary is logged,
+    /// `None` if summaries are not logged after a number of mutants
+    pub fn summary_interval_mutants(&self) -> Option<usize> {
+        self.summary_interval_mutants
+    }
+
+    /// Copy of the section, with defaults filled in
+    fn effective(&self) -> Self {
+        Self {
+            path_rewrite: self.path_rewrite.clone(),
+            badge: Some(self.badge()),
+            thresholds: Some(self.thresholds()),
+            cost_unit: Some(self.cost_unit()),
+            group_variants: Some(self.group_variants()),
+            output_pattern: self.output_pattern.clone(),
+            keep_last: self.keep_last,
+            explain_skipped: Some(self.explain_skipped()),
+            syntax_dirs: Some(self.syntax_dirs().to_vec()),
+            context_lines: Some(self.context_lines()),
+            summary_interval_minutes: self.summary_interval_minutes,
+            summary_interval_mutants: self.summary_interval_mutants,
+        }
+    }
+
+    /// Check that the score thresholds and the output pattern are valid
+    fn validate(&self) -> Result<()> {
+        if let Some(pattern) = self.output_pattern() {
+            let parent = Path::new(pattern).parent().unwrap_or_else(|| Path::new(""));
+            if parent.to_string_lossy().contains('{') {
+                return Err(anyhow!(
+                    "output_pattern: placeholders are only allowed in the last component, \
+                    got {pattern:?}"
+                ))
+                .context(ErrorKind::Config);
+            }
+        }
+
+        if let Some(keep_last) = self.keep_last {
+            let has_timestamp = self
+                .output_pattern()
+                .map(|pattern| pattern.contains(reportdir::TIMESTAMP_PLACEHOLDER))
+                .unwrap_or(false);
+            if keep_last == 0 || !has_timestamp {
+                return Err(anyhow!(
+                    "keep_last must be at least 1 and requires an output_pattern containing {}",
+                    reportdir::TIMESTAMP_PLACEHOLDER
+                ))
+                .context(ErrorKind::Config);
+            }
+        }
+
+        self.thresholds().validate()?;
+
+        if self.summary_interval_minutes == Some(0) || self.summary_interval_mutants == Some(0) {
+            return Err(anyhow!(
+                "summary_interval_minutes and summary_interval_mutants must be at least 1"
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        Ok(())
+    }
 }
 
 /// Configuration for mutation operators
-#[derive(Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct OperatorConfig {
+    /// Curated set of enabled operators, extended by `enabled_operators`
+    preset: Option<OperatorPreset>,
+
     /// (Regex) list of all enabled mutation operators
     enabled_operators: Option<Vec<String>>,
+
+    /// (Regex) list of operators that are disabled, even if enabled
+    disabled_operators: Option<Vec<String>>,
 }
 
 impl OperatorConfig {
-    /// Return a (regex) list of all enabled mutation operators
+    /// Return the operator preset, if one is selected
+    pub fn preset(&self) -> Option<OperatorPreset> {
+        self.preset
+    }
+
+    /// Return a (regex) list of all enabled mutation operators,
+    /// i.e. the operators of the preset and `enabled_operators`
     pub fn enabled_operators(&self) -> Vec<String> {
-        self.enabled_operators
-            .clone()
-            .unwrap_or_else(|| vec![String::new()])
+        match self.preset {
+            Some(preset) => preset
+                .enabled_operators()
+                .iter()
+                .map(|pattern| String::from(*pattern))
+                .chain(self.enabled_operators.iter().flatten().cloned())
+                .collect(),
+            None => self
+                .enabled_operators
+                .clone()
+                .unwrap_or_else(|| vec![String::new()]),
+        }
     }
-}
 
-/// Main toml configuration
-#[derive(Deserialize)]
-pub struct Config {
-    engine: Option<EngineConfig>,
-    filter: Option<FilterConfig>,
-    report: Option<ReportConfig>,
-    operators: Option<OperatorConfig>,
-}
+    /// Return a (regex) list of operators that are disabled,
+    /// even if they are matched by `enabled_operators`
+    pub fn disabled_operators(&self) -> Vec<String> {
+        self.disabled_operators.clone().unwrap_or_default()
+    }
+
+    /// Copy of the section, with defaults filled in
+    fn effective(&self) -> Self {
+        // The patterns of a preset are not repeated in `enabled_operators`
+        let enabled_operators = match self.preset {
+            Some(_) => self.enabled_operators.clone().unwrap_or_default(),
+            None => self.enabled_operators(),
+        };
 
-impl Default for Config {
-    fn default() -> Self {
         Self {
-            engine: Some(Default::default()),
-            filter: Some(Default::default()),
-            report: Some(Default::default()),
-            operators: Some(Default::default()),
+            preset: self.preset,
+            enabled_operators: Some(enabled_operators),
+            disabled_operators: Some(self.disabled_operators()),
         }
     }
 }
 
-impl Config {
-    /// Save default configuration to given path
-    pub fn save_default_config<P: AsRef<Path>>(path: P) -> Result<()> {
-        let p = path.as_ref();
-        std::fs::write(p, templates::DEFAULT_CONFIG)
-            .with_context(|| format!("Failed to write configuration file {p:?}"))?;
-        Ok(())
+/// Configuration for `wasmut suggest`
+#[derive(Serialize, Deserialize, Default)]
+pub struct SuggestConfig {
+    /// Command line arguments passed to the module, which are perturbed
+    args: Option<Vec<String>>,
+
+    /// Input files that are perturbed. Files must be
+    /// located in one of the directories in `map_dirs`.
+    input_files: Option<Vec<String>>,
+
+    /// Number of generated inputs.
+    /// Defaults to 100
+    attempts: Option<usize>,
+
+    /// Maximum size of a perturbed input file in bytes.
+    /// Defaults to 65536
+    max_input_size: Option<usize>,
+
+    /// Seed for the input generator.
+    /// Defaults to 0
+    seed: Option<u64>,
+}
+
+impl SuggestConfig {
+    /// Command line arguments that are perturbed
+    pub fn args(&self) -> &[String] {
+        self.args.as_deref().unwrap_or_default()
     }
 
-    /// Parse configuration at a given path
-    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let p = path.as_ref();
+    /// Input files that are perturbed
+    pub fn input_files(&self) -> &[String] {
+        self.input_files.as_deref().unwrap_or_default()
+    }
 
-        let s = std::fs::read_to_string(p)
-            .with_context(|| format!("Failed to read configuration file {p:?}"))?;
+    /// Number of generated inputs
+    pub fn attempts(&self) -> usize {
+        self.attempts.unwrap_or(100)
+    }
 
-        Self::parse(&s)
+    /// Maximum size of a perturbed input file
+    pub fn max_input_size(&self) -> usize {
+        self.max_input_size.unwrap_or(65536)
     }
 
-    /// Parse configuration from string
-    pub fn parse(s: &str) -> Result<Self> {
-        let mut config: Config = toml::from_str(s)?;
+    /// Seed for the input generator
+    pub fn seed(&self) -> u64 {
+        self.seed.unwrap_or(0)
+    }
 
-        if config.engine.is_none() {
-            config.engine = Some(Default::default());
+    /// Copy of the section, with defaults filled in
+    fn effective(&self) -> Self {
+        Self {
+            args: Some(self.args().to_vec()),
+            input_files: Some(self.input_files().to_vec()),
+            attempts: Some(self.attempts()),
+            max_input_size: Some(self.max_input_size()),
+            seed: Some(self.seed()),
         }
+    }
+}
 
-        if config.filter.is_none() {
-            config.filter = Some(Default::default());
-        }
+/// Configuration of on-disk caches
+#[derive(Serialize, Deserialize, Default)]
+pub struct CacheConfig {
+    /// Directory containing all caches.
+    /// Defaults to `$XDG_CACHE_HOME/wasmut` or `~/.cache/wasmut`
+    directory: Option<String>,
 
-        if config.report.is_none() {
-            config.report = Some(Default::default());
+    /// If true, source locations of all instructions are resolved once
+    /// per module and cached. Defaults to false
+    address_index: Option<bool>,
+
+    /// If true, outcomes of executed mutants are cached and reused
+    /// by later runs with identical mutants. Defaults to false
+    results: Option<bool>,
+}
+
+impl CacheConfig {
+    /// Directory containing all caches
+    pub fn directory(&self) -> PathBuf {
+        if let Some(directory) = &self.directory {
+            return PathBuf::from(directory);
         }
 
-        if config.operators.is_none() {
-            config.operators = Some(Default::default());
+        match (std::env::var_os("XDG_CACHE_HOME"), std::env::var_os("HOME")) {
+            (Some(cache_home), _) if !cache_home.is_empty() => {
+                Path::new(&cache_home).join("wasmut")
+            }
+            (_, Some(home)) => Path::new(&home).join(".cache").join("wasmut"),
+            _ => PathBuf::from(".wasmut-cache"),
         }
-        Ok(config)
     }
 
-    /// Return engine subsection
-    pub fn engine(&self) -> &EngineConfig {
-        self.engine.as_ref().unwrap()
+    /// Cache source locations of all instructions
+    pub fn address_index(&self) -> bool {
+        self.address_index.unwrap_or(false)
     }
 
-    /// Return filter subsection
-    pub fn filter(&self) -> &FilterConfig {
-        self.filter.as_ref().unwrap()
+    /// Cache outcomes of executed mutants
+    pub fn results(&self) -> bool {
+        self.results.unwrap_or(false)
     }
 
-    /// Return report subsection
-    pub fn report(&self) -> &ReportConfig {
-        self.report.as_ref().unwrap()
+    /// Copy of the section, with defaults filled in
+    fn effective(&self) -> Self {
+        Self {
+            directory: Some(self.directory().to_string_lossy().into()),
+            address_index: Some(self.address_index()),
+            results: Some(self.results()),
+        }
     }
 
-    /// Return operators subsection
-    pub fn operators(&self) -> &OperatorConfig {
-        self.operators.as_ref().unwrap()
+    /// Check that wasmut was built with database support, if outcomes are cached
+    fn validate(&self) -> Result<()> {
+        if self.results() && !cfg!(feature = "sqlite") {
+            return Err(anyhow!(
+                "cache.results requires SQLite support, rebuild wasmut with `--features sqlite`"
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use anyhow::Result;
+/// Configuration of the result database
+#[derive(Serialize, Deserialize, Default)]
+pub struct DatabaseConfig {
+    /// SQLite database, in which the results of all runs are stored.
+    /// Requires the `sqlite` feature
+    path: Option<String>,
+}
 
-    #[test]
-    fn filters() -> Result<()> {
-        let config = Config::parse(
-            r#"
-            [filter]
-            allowed_files = ["src/", "test/"]
-            allowed_functions = ["simple_rust", "test"]
-            "#,
-        )?;
+impl DatabaseConfig {
+    /// Path of the result database, if results should be stored
+    #[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
 
-        assert_eq!(
-            config.filter().allowed_files(),
-            Some(&vec![String::from("src/"), String::from("test/")])
-        );
-        assert_eq!(
-            config.filter().allowed_functions(),
-            Some(&vec![String::from("simple_rust"), String::from("test")])
-        );
-        Ok(())
+    /// Copy of the section, with defaults filled in
+    fn effective(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+        }
     }
 
-    #[test]
+    /// Check that wasmut was built with database support, if a database is configured
+    fn validate(&self) -> Result<()> {
+        if self.path.is_some() && !cfg!(feature = "sqlite") {
+            return Err(anyhow!(
+                "database.path requires SQLite support, rebuild wasmut with `--features sqlite`"
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        Ok(())
+    }
+}
+
+/// Configuration of notifications about finished runs
+#[derive(Serialize, Deserialize, Default)]
+pub struct NotifyConfig {
+    /// URL to which a JSON summary of every run is posted.
+    /// Requires the `notify` feature
+    webhook_url: Option<String>,
+
+    /// URL of a Slack incoming webhook, to which a summary message of every
+    /// run is posted. Requires the `notify` feature
+    slack_webhook_url: Option<String>,
+
+    /// Link to the report, included in the notifications
+    report_url: Option<String>,
+}
+
+impl NotifyConfig {
+    /// URL to which a JSON summary is posted
+    pub fn webhook_url(&self) -> Option<&str> {
+        self.webhook_url.as_deref()
+    }
+
+    /// URL of a Slack incoming webhook
+    pub fn slack_webhook_url(&self) -> Option<&str> {
+        self.slack_webhook_url.as_deref()
+    }
+
+    /// Link to the report
+    pub fn report_url(&self) -> Option<&str> {
+        self.report_url.as_deref()
+    }
+
+    /// True if notifications are sent
+    pub fn enabled(&self) -> bool {
+        self.webhook_url.is_some() || self.slack_webhook_url.is_some()
+    }
+
+    /// Copy of the section, with defaults filled in. The webhook URLs usually
+    /// contain credentials, so they are left out of the run manifest.
+    fn effective(&self) -> Self {
+        Self {
+            webhook_url: None,
+            slack_webhook_url: None,
+            report_url: self.report_url.clone(),
+        }
+    }
+
+    /// Check that wasmut was built with notification support, if notifications
+    /// are configured, and that the webhook URLs use HTTP(S)
+    fn validate(&self) -> Result<()> {
+        if self.enabled() && !cfg!(feature = "notify") {
+            return Err(anyhow!(
+                "notifications require HTTP support, rebuild wasmut with `--features notify`"
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        let urls = [
+            ("webhook_url", &self.webhook_url),
+            ("slack_webhook_url", &self.slack_webhook_url),
+        ];
+        for (option, url) in urls {
+            if let Some(url) = url {
+                if !(url.starts_with("http://") || url.starts_with("https://")) {
+                    return Err(anyhow!(
+                        "notify.{option} must be an http:// or https:// URL"
+                    ))
+                    .context(ErrorKind::Config);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Configuration of the criteria deciding whether a mutant was killed
+#[derive(Serialize, Deserialize, Default)]
+pub struct KillCriteriaConfig {
+    /// Criteria deciding whether a mutant was killed.
+    /// Defaults to `exit_code`
+    criteria: Option<Vec<CriterionKind>>,
+
+    /// How multiple criteria are combined. Defaults to `Combination::Any`
+    combine: Option<Combination>,
+
+    /// File containing the expected stdout, used by the `golden_file` criterion
+    golden_file: Option<String>,
+}
+
+impl KillCriteriaConfig {
+    /// Selected kill criteria
+    pub fn criteria(&self) -> Vec<CriterionKind> {
+        match &self.criteria {
+            Some(criteria) if !criteria.is_empty() => criteria.clone(),
+            _ => vec![CriterionKind::ExitCode],
+        }
+    }
+
+    /// How multiple criteria are combined
+    pub fn combine(&self) -> Combination {
+        self.combine.unwrap_or(Combination::Any)
+    }
+
+    /// File containing the expected stdout
+    pub fn golden_file(&self) -> Option<&str> {
+        self.golden_file.as_deref()
+    }
+
+    /// Copy of the section, with defaults filled in
+    fn effective(&self) -> Self {
+        Self {
+            criteria: Some(self.criteria()),
+            combine: Some(self.combine()),
+            golden_file: self.golden_file.clone(),
+        }
+    }
+
+    /// Check that a golden file is given if the `golden_file` criterion is used
+    fn validate(&self) -> Result<()> {
+        if self.criteria().contains(&CriterionKind::GoldenFile) && self.golden_file.is_none() {
+            return Err(anyhow!(
+                "kill criterion golden_file requires kill_criteria.golden_file to be set"
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        Ok(())
+    }
+}
+
+/// Main toml configuration
+#[derive(Serialize, Deserialize)]
+pub struct Config {
+    engine: Option<EngineConfig>,
+    filter: Option<FilterConfig>,
+    report: Option<ReportConfig>,
+    operators: Option<OperatorConfig>,
+    suggest: Option<SuggestConfig>,
+    cache: Option<CacheConfig>,
+    database: Option<DatabaseConfig>,
+    kill_criteria: Option<KillCriteriaConfig>,
+    notify: Option<NotifyConfig>,
+
+    /// Directory relative to which the configuration is interpreted,
+    /// the directory of the configuration file, if there is one
+    #[serde(skip)]
+    directory: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            engine: Some(Default::default()),
+            filter: Some(Default::default()),
+            report: Some(Default::default()),
+            operators: Some(Default::default()),
+            suggest: Some(Default::default()),
+            cache: Some(Default::default()),
+            database: Some(Default::default()),
+            kill_criteria: Some(Default::default()),
+            notify: Some(Default::default()),
+            directory: None,
+        }
+    }
+}
+
+impl Config {
+    /// Save default configuration to given path
+    pub fn save_default_config<P: AsRef<Path>>(path: P) -> Result<()> {
+        let p = path.as_ref();
+        std::fs::write(p, templates::DEFAULT_CONFIG)
+            .with_context(|| format!("Failed to write configuration file {p:?}"))?;
+        Ok(())
+    }
+
+    /// Parse configuration at a given path
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let p = path.as_ref();
+
+        let s = std::fs::read_to_string(p)
+            .with_context(|| format!("Failed to read configuration file {p:?}"))
+            .context(ErrorKind::Config)?;
+
+        let directory = p.parent().unwrap_or_else(|| Path::new("."));
+        Ok(Self::parse(&s)?.with_directory(directory))
+    }
+
+    /// Parse configuration from string
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut config: Config = toml::from_str(s).context(ErrorKind::Config)?;
+
+        if config.engine.is_none() {
+            config.engine = Some(Default::default());
+        }
+
+        if config.filter.is_none() {
+            config.filter = Some(Default::default());
+        }
+
+        if config.report.is_none() {
+            config.report = Some(Default::default());
+        }
+
+        if config.operators.is_none() {
+            config.operators = Some(Default::default());
+        }
+
+        if config.suggest.is_none() {
+            config.suggest = Some(Default::default());
+        }
+
+        if config.cache.is_none() {
+            config.cache = Some(Default::default());
+        }
+
+        if config.database.is_none() {
+            config.database = Some(Default::default());
+        }
+
+        if config.kill_criteria.is_none() {
+            config.kill_criteria = Some(Default::default());
+        }
+
+        if config.notify.is_none() {
+            config.notify = Some(Default::default());
+        }
+
+        config.engine().validate()?;
+        config.filter().validate()?;
+        config.report().validate()?;
+        config.cache().validate()?;
+        config.database().validate()?;
+        config.kill_criteria().validate()?;
+        config.notify().validate()?;
+        Ok(config)
+    }
+
+    /// Return engine subsection
+    pub fn engine(&self) -> &EngineConfig {
+        self.engine.as_ref().unwrap()
+    }
+
+    /// Return mutable engine subsection
+    pub fn engine_mut(&mut self) -> &mut EngineConfig {
+        self.engine.as_mut().unwrap()
+    }
+
+    /// Return filter subsection
+    pub fn filter(&self) -> &FilterConfig {
+        self.filter.as_ref().unwrap()
+    }
+
+    /// Return mutable filter subsection
+    pub fn filter_mut(&mut self) -> &mut FilterConfig {
+        self.filter.as_mut().unwrap()
+    }
+
+    /// Return report subsection
+    pub fn report(&self) -> &ReportConfig {
+        self.report.as_ref().unwrap()
+    }
+
+    /// Return operators subsection
+    pub fn operators(&self) -> &OperatorConfig {
+        self.operators.as_ref().unwrap()
+    }
+
+    /// Return suggest subsection
+    pub fn suggest(&self) -> &SuggestConfig {
+        self.suggest.as_ref().unwrap()
+    }
+
+    /// Return cache subsection
+    pub fn cache(&self) -> &CacheConfig {
+        self.cache.as_ref().unwrap()
+    }
+
+    /// Return database subsection
+    pub fn database(&self) -> &DatabaseConfig {
+        self.database.as_ref().unwrap()
+    }
+
+    /// Return kill_criteria subsection
+    pub fn kill_criteria(&self) -> &KillCriteriaConfig {
+        self.kill_criteria.as_ref().unwrap()
+    }
+
+    /// Return notify subsection
+    pub fn notify(&self) -> &NotifyConfig {
+        self.notify.as_ref().unwrap()
+    }
+
+    /// Configuration as it is used by wasmut, including overrides
+    /// from the command line and with defaults filled in
+    pub fn effective(&self) -> Config {
+        Config {
+            engine: Some(self.engine().effective()),
+            filter: Some(self.filter().effective()),
+            report: Some(self.report().effective()),
+            operators: Some(self.operators().effective()),
+            suggest: Some(self.suggest().effective()),
+            cache: Some(self.cache().effective()),
+            database: Some(self.database().effective()),
+            kill_criteria: Some(self.kill_criteria().effective()),
+            notify: Some(self.notify().effective()),
+            directory: self.directory.clone(),
+        }
+    }
+
+    /// Interpret the configuration relative to `directory`, e.g. the
+    /// directory of the module, if there is no configuration file
+    pub fn with_directory<P: AsRef<Path>>(self, directory: P) -> Self {
+        Self {
+            directory: Some(directory.as_ref().into()),
+            ..self
+        }
+    }
+
+    /// Directory relative to which the configuration is interpreted, e.g. to
+    /// determine changed files for `only_changed_files`. Defaults to the working directory.
+    pub fn directory(&self) -> &Path {
+        match &self.directory {
+            // `parent` of a relative file name is an empty path
+            Some(directory) if !directory.as_os_str().is_empty() => directory,
+            _ => Path::new("."),
+        }
+    }
+
+    /// Serialize the configuration into TOML
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string(self).context("Could not serialize configuration")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    #[test]
+    fn filters() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [filter]
+            allowed_files = ["src/", "test/"]
+            allowed_functions = ["simple_rust", "test"]
+            require_source_info = true
+            min_function_instructions = 10
+            only_changed_files = "origin/main"
+            most_complex_functions = 5
+            max_mutants_per_function = 200
+            preset = "rust"
+            "#,
+        )?;
+
+        assert_eq!(
+            config.filter().allowed_files(),
+            Some(&vec![String::from("src/"), String::from("test/")])
+        );
+        assert_eq!(
+            config.filter().allowed_functions(),
+            Some(&vec![String::from("simple_rust"), String::from("test")])
+        );
+        assert!(config.filter().require_source_info());
+        assert_eq!(config.filter().min_function_instructions(), 10);
+        assert_eq!(config.filter().only_changed_files(), Some("origin/main"));
+        assert_eq!(config.filter().most_complex_functions(), Some(5));
+        assert_eq!(config.filter().max_mutants_per_function(), Some(200));
+        assert_eq!(config.filter().preset(), Some(FilterPreset::Rust));
+
+        assert!(Config::parse("[filter]\nmax_mutants_per_function = 0").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn override_meta_mutant() -> Result<()> {
+        let mut config = Config::parse("[engine]\nmeta_mutant = true")?;
+        config.engine_mut().override_meta_mutant(false);
+        assert!(!config.engine().meta_mutant());
+        Ok(())
+    }
+
+    #[test]
+    fn filter_presets() -> Result<()> {
+        let config = Config::parse("[filter]\npreset = \"c\"")?;
+        assert_eq!(config.filter().preset(), Some(FilterPreset::C));
+
+        assert!(Config::parse("[filter]\npreset = \"go\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn filter_overrides() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [[filter.overrides]]
+            files = ["^src/core/"]
+            enabled_operators = ["binop", "relop"]
+
+            [[filter.overrides]]
+            files = ["generated"]
+            disabled_operators = ["call_remove"]
+            sample_threshold = 10
+            "#,
+        )?;
+
+        let overrides = config.filter().overrides();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].files(), ["^src/core/"]);
+        assert_eq!(
+            overrides[0].enabled_operators(),
+            Some(&[String::from("binop"), String::from("relop")][..])
+        );
+        assert_eq!(overrides[0].disabled_operators(), None);
+        assert_eq!(overrides[0].sample_threshold(), None);
+        assert_eq!(overrides[1].enabled_operators(), None);
+        assert_eq!(overrides[1].sample_threshold(), Some(10));
+
+        assert!(Config::parse("[[filter.overrides]]\nsample_threshold = 10").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn filters_override() -> Result<()> {
+        let mut config = Config::parse(
+            r#"
+            [filter]
+            allowed_files = ["src/", "test/"]
+            "#,
+        )?;
+
+        config
+            .filter_mut()
+            .override_allowed(vec![], vec![String::from("^add$")]);
+
+        assert_eq!(config.filter().allowed_files(), Some(&vec![]));
+        assert_eq!(
+            config.filter().allowed_functions(),
+            Some(&vec![String::from("^add$")])
+        );
+        Ok(())
+    }
+
+    #[test]
     fn engine_config() -> Result<()> {
         let config = Config::parse(
             r#"
             [engine]
             timeout_multiplier = 10
+            timeout_warning_threshold = 50
             map_dirs = [["a/foo", "b/bar"], ["abcd", "abcd"]]
             coverage_based_execution = false
             meta_mutant = false
+            meta_mutant_chunk_size = 500
+            meta_mutant_activation = "host_call"
+            trace_survivors = true
+            runtime = "wasmer"
             "#,
         )?;
+        assert_eq!(
+            config.engine().meta_mutant_activation(),
+            MutantActivation::HostCall
+        );
+        assert_eq!(config.engine().meta_mutant_chunk_size(), 500);
         assert_eq!(config.engine().timeout_multiplier(), 10.0);
+        assert_eq!(config.engine().timeout_warning_threshold(), 50.0);
+        assert!(config.engine().trace_survivors());
         assert!(!config.engine().coverage_based_execution());
         assert!(!config.engine().meta_mutant());
         assert_eq!(
@@ -237,6 +1287,201 @@ mod tests {
                 ("abcd".into(), "abcd".into())
             ]
         );
+
+        assert!(Config::parse("[engine]\ntimeout_warning_threshold = 150").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn runtime_config() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [engine]
+            runtime = "wasmi"
+            "#,
+        )?;
+        assert_eq!(config.engine().runtime(), RuntimeKind::Wasmi);
+
+        let result = Config::parse(
+            r#"
+            [engine]
+            runtime = "wasmi"
+            map_dirs = [["a", "b"]]
+            "#,
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn hybrid_runtime_config() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [engine]
+            runtime = "hybrid"
+            hybrid_threshold = 500
+            "#,
+        )?;
+        assert_eq!(config.engine().runtime(), RuntimeKind::Hybrid);
+        assert_eq!(config.engine().hybrid_threshold(), 500);
+
+        let result = Config::parse(
+            r#"
+            [engine]
+            runtime = "hybrid"
+            meta_mutant = false
+            "#,
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn expensive_functions_config() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [engine]
+            expensive_function_threshold = 40.0
+            expensive_function_action = "shorten_timeout"
+            expensive_function_timeout_multiplier = 1.5
+            "#,
+        )?;
+        assert_eq!(config.engine().expensive_function_threshold(), Some(40.0));
+        assert_eq!(
+            config.engine().expensive_function_action(),
+            ExpensiveFunctionAction::ShortenTimeout
+        );
+        assert_eq!(config.engine().expensive_function_timeout_multiplier(), 1.5);
+
+        let invalid = [
+            "expensive_function_threshold = 0.0",
+            "expensive_function_action = \"skip\"",
+            "expensive_function_threshold = 40.0\n\
+            expensive_function_action = \"shorten_timeout\"\n\
+            expensive_function_timeout_multiplier = 3.0",
+        ];
+        for engine in invalid {
+            let error = Config::parse(&format!("[engine]\n{engine}")).err().unwrap();
+            assert_eq!(error.downcast_ref(), Some(&ErrorKind::Config));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn execution_cost_units() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [engine]
+            instructions_per_second = 1000000
+
+            [report]
+            cost_unit = "time"
+            "#,
+        )?;
+        assert_eq!(config.engine().instructions_per_second(), Some(1000000));
+        assert_eq!(config.report().cost_unit(), CostUnit::Time);
+
+        let result = Config::parse(
+            r#"
+            [engine]
+            instructions_per_second = 0
+            "#,
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn report_grouped_variants() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [report]
+            group_variants = true
+            "#,
+        )?;
+        assert!(config.report().group_variants());
+        Ok(())
+    }
+
+    #[test]
+    fn report_explain_skipped() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [report]
+            explain_skipped = true
+            "#,
+        )?;
+        assert!(config.report().explain_skipped());
+        Ok(())
+    }
+
+    #[test]
+    fn report_summary_interval() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [report]
+            summary_interval_minutes = 10
+            summary_interval_mutants = 5000
+            "#,
+        )?;
+        assert_eq!(config.report().summary_interval_minutes(), Some(10));
+        assert_eq!(config.report().summary_interval_mutants(), Some(5000));
+
+        let error = Config::parse("[report]\nsummary_interval_minutes = 0")
+            .err()
+            .unwrap();
+        assert_eq!(error.downcast_ref(), Some(&ErrorKind::Config));
+        Ok(())
+    }
+
+    #[test]
+    fn report_syntax_dirs() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [report]
+            syntax_dirs = ["syntaxes/zig", "syntaxes/assemblyscript"]
+            "#,
+        )?;
+        assert_eq!(
+            config.report().syntax_dirs(),
+            ["syntaxes/zig", "syntaxes/assemblyscript"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn report_context_lines() -> Result<()> {
+        let config = Config::parse("[report]\ncontext_lines = 2")?;
+        assert_eq!(config.report().context_lines(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn report_output_pattern() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [report]
+            output_pattern = "wasmut-report/{module}-{timestamp}"
+            keep_last = 5
+            "#,
+        )?;
+        assert_eq!(
+            config.report().output_pattern(),
+            Some("wasmut-report/{module}-{timestamp}")
+        );
+        assert_eq!(config.report().keep_last(), Some(5));
+
+        for invalid in [
+            r#"output_pattern = "{module}/report""#,
+            r#"keep_last = 5"#,
+            r#"output_pattern = "reports/{module}"
+            keep_last = 5"#,
+            r#"output_pattern = "reports/{timestamp}"
+            keep_last = 0"#,
+        ] {
+            let result = Config::parse(&format!("[report]\n{invalid}"));
+            assert!(result.is_err(), "{invalid} should be rejected");
+        }
         Ok(())
     }
 
@@ -254,6 +1499,69 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn operator_config_disabled() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [operators]
+            enabled_operators = ["binop_"]
+            disabled_operators = ["binop_or_to_xor"]
+            "#,
+        )?;
+        let expected: Vec<String> = vec!["binop_or_to_xor".into()];
+        assert_eq!(config.operators().disabled_operators(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn operator_config_preset() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [operators]
+            preset = "light"
+            enabled_operators = ["binop_add_to_sub"]
+            disabled_operators = ["const_replace_zero"]
+            "#,
+        )?;
+        assert_eq!(config.operators().preset(), Some(OperatorPreset::Light));
+        assert_eq!(
+            config.operators().enabled_operators(),
+            vec!["^relop_", "^const_", "binop_add_to_sub"]
+        );
+
+        // The effective configuration keeps the preset
+        let reparsed = Config::parse(&config.effective().to_toml()?)?;
+        assert_eq!(
+            reparsed.operators().enabled_operators(),
+            config.operators().enabled_operators()
+        );
+
+        let config = Config::parse("[operators]\npreset = \"aggressive\"")?;
+        assert_eq!(config.operators().enabled_operators(), vec![""]);
+        assert!(Config::parse("[operators]\npreset = \"extreme\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn suggest_config() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [suggest]
+            args = ["--count", "10"]
+            input_files = ["files/input.txt"]
+            attempts = 20
+            max_input_size = 128
+            seed = 42
+            "#,
+        )?;
+        assert_eq!(config.suggest().args(), ["--count", "10"]);
+        assert_eq!(config.suggest().input_files(), ["files/input.txt"]);
+        assert_eq!(config.suggest().attempts(), 20);
+        assert_eq!(config.suggest().max_input_size(), 128);
+        assert_eq!(config.suggest().seed(), 42);
+        Ok(())
+    }
+
     #[test]
     fn report_config() -> Result<()> {
         let config = Config::parse(
@@ -266,6 +1574,82 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn report_badge_config() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [report]
+            badge = true
+            thresholds = { warning = 60, success = 90 }
+            "#,
+        )?;
+        assert!(config.report().badge());
+        assert_eq!(
+            config.report().thresholds(),
+            ScoreThresholds {
+                warning: 60.0,
+                success: 90.0
+            }
+        );
+
+        let config = Config::parse(
+            r#"
+            [report]
+            thresholds = { success = 90.5 }
+            "#,
+        )?;
+        assert_eq!(
+            config.report().thresholds(),
+            ScoreThresholds {
+                warning: 50.0,
+                success: 90.5
+            }
+        );
+
+        let invalid = Config::parse(
+            r#"
+            [report]
+            thresholds = { warning = 90, success = 60 }
+            "#,
+        );
+        assert!(invalid.is_err());
+
+        let invalid = Config::parse(
+            r#"
+            [report]
+            thresholds = { warning = 50, success = 150 }
+            "#,
+        );
+        assert!(invalid.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cache_config() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [cache]
+            directory = "/tmp/wasmut-cache"
+            address_index = true
+            "#,
+        )?;
+        assert_eq!(
+            config.cache().directory(),
+            PathBuf::from("/tmp/wasmut-cache")
+        );
+        assert!(config.cache().address_index());
+
+        let config = Config::parse("[cache]\nresults = true");
+        if cfg!(feature = "sqlite") {
+            assert!(config?.cache().results());
+        } else {
+            let error = config.err().unwrap();
+            assert!(format!("{error:?}").contains("--features sqlite"));
+            assert_eq!(crate::error::exit_code(&error), 2);
+        }
+        Ok(())
+    }
+
     #[test]
     fn save_default_config_is_created() -> Result<()> {
         let dir = tempfile::tempdir()?;
@@ -285,6 +1669,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn configuration_directory() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file_path = dir.path().join("wasmut.toml");
+        Config::save_default_config(&file_path)?;
+
+        let config = Config::parse_file(&file_path)?;
+        assert_eq!(config.directory(), dir.path());
+        assert_eq!(config.effective().directory(), dir.path());
+
+        assert_eq!(Config::default().directory(), Path::new("."));
+        let config = Config::default().with_directory(Path::new("wasmut.toml").parent().unwrap());
+        assert_eq!(config.directory(), Path::new("."));
+        Ok(())
+    }
+
     #[test]
     fn default_config() -> Result<()> {
         let config = Config::parse(
@@ -292,16 +1692,206 @@ mod tests {
             "#,
         )?;
         assert_eq!(config.engine().timeout_multiplier(), 2.0);
+        assert_eq!(
+            config.engine().timeout_warning_threshold(),
+            TIMEOUT_WARNING_THRESHOLD
+        );
         assert!(config.engine().coverage_based_execution());
         assert!(config.engine().meta_mutant());
+        assert!(!config.engine().trace_survivors());
+        assert_eq!(
+            config.engine().meta_mutant_chunk_size(),
+            META_MUTANT_CHUNK_SIZE
+        );
+        assert_eq!(
+            config.engine().meta_mutant_activation(),
+            MutantActivation::Global
+        );
         assert_eq!(config.engine().map_dirs(), []);
+        assert_eq!(config.engine().runtime(), RuntimeKind::Wasmer);
+        assert_eq!(config.engine().hybrid_threshold(), HYBRID_THRESHOLD);
+        assert_eq!(config.engine().expensive_function_threshold(), None);
+        assert_eq!(
+            config.engine().expensive_function_action(),
+            ExpensiveFunctionAction::Report
+        );
+        assert_eq!(
+            config.engine().expensive_function_timeout_multiplier(),
+            EXPENSIVE_FUNCTION_TIMEOUT_MULTIPLIER
+        );
+        assert_eq!(config.engine().instructions_per_second(), None);
         assert_eq!(config.filter().allowed_files(), None);
         assert_eq!(config.filter().allowed_functions(), None);
+        assert!(!config.filter().require_source_info());
+        assert_eq!(config.filter().min_function_instructions(), 0);
+        assert_eq!(config.filter().only_changed_files(), None);
+        assert_eq!(config.filter().most_complex_functions(), None);
+        assert_eq!(config.filter().max_mutants_per_function(), None);
+        assert!(config.filter().overrides().is_empty());
+        assert_eq!(config.filter().preset(), None);
         assert_eq!(config.report().path_rewrite(), None);
+        assert!(!config.report().badge());
+        assert!(!config.cache().address_index());
+        assert!(!config.cache().results());
+        assert_eq!(config.report().thresholds(), ScoreThresholds::default());
+        assert_eq!(config.report().cost_unit(), CostUnit::Both);
+        assert!(!config.report().group_variants());
+        assert!(!config.report().explain_skipped());
+        assert!(config.report().syntax_dirs().is_empty());
+        assert_eq!(config.report().context_lines(), 0);
+        assert_eq!(config.report().summary_interval_minutes(), None);
+        assert_eq!(config.report().summary_interval_mutants(), None);
+        assert_eq!(config.report().output_pattern(), None);
+        assert_eq!(config.report().keep_last(), None);
         assert_eq!(
             config.operators().enabled_operators(),
             vec![String::from("")]
         );
+        assert!(config.operators().disabled_operators().is_empty());
+        assert_eq!(config.operators().preset(), None);
+        assert!(config.suggest().args().is_empty());
+        assert!(config.suggest().input_files().is_empty());
+        assert_eq!(config.suggest().attempts(), 100);
+        assert_eq!(config.database().path(), None);
+        assert_eq!(
+            config.kill_criteria().criteria(),
+            vec![CriterionKind::ExitCode]
+        );
+        assert_eq!(config.kill_criteria().combine(), Combination::Any);
+        assert_eq!(config.kill_criteria().golden_file(), None);
+        assert!(!config.notify().enabled());
+        assert_eq!(config.notify().report_url(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn effective_config() -> Result<()> {
+        let mut config = Config::parse(
+            r#"
+            [engine]
+            timeout_multiplier = 4.0
+
+            [filter]
+            allowed_files = ["src/"]
+            preset = "rust"
+
+            [[filter.overrides]]
+            files = ["vendor/"]
+            sample_threshold = 10
+            "#,
+        )?;
+        config.engine_mut().override_meta_mutant(false);
+
+        let toml = config.effective().to_toml()?;
+        assert!(toml.contains("timeout_multiplier = 4.0"));
+        assert!(toml.contains("meta_mutant = false"));
+        assert!(toml.contains("runtime = \"wasmer\""));
+        assert!(toml.contains("enabled_operators = [\"\"]"));
+
+        let reparsed = Config::parse(&toml)?;
+        assert_eq!(reparsed.engine().timeout_multiplier(), 4.0);
+        assert!(!reparsed.engine().meta_mutant());
+        assert_eq!(
+            reparsed.filter().allowed_files(),
+            Some(&vec![String::from("src/")])
+        );
+        assert_eq!(reparsed.filter().preset(), Some(FilterPreset::Rust));
+        assert_eq!(
+            reparsed.filter().overrides()[0].sample_threshold(),
+            Some(10)
+        );
+        assert_eq!(reparsed.cache().directory(), config.cache().directory());
+        Ok(())
+    }
+
+    #[test]
+    fn kill_criteria_config() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [kill_criteria]
+            criteria = ["exit_code", "golden_file"]
+            combine = "all"
+            golden_file = "expected.txt"
+            "#,
+        )?;
+        assert_eq!(
+            config.kill_criteria().criteria(),
+            vec![CriterionKind::ExitCode, CriterionKind::GoldenFile]
+        );
+        assert_eq!(config.kill_criteria().combine(), Combination::All);
+        assert_eq!(config.kill_criteria().golden_file(), Some("expected.txt"));
+
+        let error = Config::parse("[kill_criteria]\ncriteria = [\"golden_file\"]")
+            .err()
+            .unwrap();
+        assert_eq!(crate::error::exit_code(&error), 2);
+        assert!(Config::parse("[kill_criteria]\ncriteria = [\"coverage\"]").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn database_config() {
+        let config = Config::parse(
+            r#"
+            [database]
+            path = "wasmut.db"
+            "#,
+        );
+
+        if cfg!(feature = "sqlite") {
+            assert_eq!(config.unwrap().database().path(), Some("wasmut.db"));
+        } else {
+            let error = config.err().unwrap();
+            assert!(format!("{error:?}").contains("--features sqlite"));
+            assert_eq!(crate::error::exit_code(&error), 2);
+        }
+    }
+
+    #[test]
+    fn notify_config() -> Result<()> {
+        let config = Config::parse(
+            r#"
+            [notify]
+            webhook_url = "https://example.com/hooks/wasmut"
+            slack_webhook_url = "https://hooks.slack.com/services/T0/B0/secret"
+            report_url = "https://ci.example.com/artifacts/report/index.html"
+            "#,
+        );
+
+        if cfg!(feature = "notify") {
+            let config = config?;
+            assert!(config.notify().enabled());
+            assert_eq!(
+                config.notify().webhook_url(),
+                Some("https://example.com/hooks/wasmut")
+            );
+            assert_eq!(
+                config.notify().slack_webhook_url(),
+                Some("https://hooks.slack.com/services/T0/B0/secret")
+            );
+
+            // Webhook URLs are not written into the run manifest
+            let toml = config.effective().to_toml()?;
+            assert!(!toml.contains("secret"));
+            assert!(toml.contains("report_url"));
+
+            assert!(Config::parse(
+                "[notify]
+webhook_url = \"example.com\""
+            )
+            .is_err());
+        } else {
+            let error = config.err().unwrap();
+            assert!(format!("{error:?}").contains("--features notify"));
+            assert_eq!(crate::error::exit_code(&error), 2);
+        }
+
+        let config = Config::parse(
+            "[notify]
+report_url = \"https://example.com\"",
+        )?;
+        assert!(!config.notify().enabled());
+        assert_eq!(config.notify().report_url(), Some("https://example.com"));
         Ok(())
     }
 }
@@ -0,0 +1,124 @@
+//! Conversion of execution costs (cycles, i.e. executed instructions) into
+//! approximate wall-clock time, which is easier to interpret when choosing timeouts.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Units in which execution costs are shown in logs and summaries
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostUnit {
+    /// Only the number of cycles
+    Cycles,
+
+    /// Only the approximate time
+    Time,
+
+    /// Cycles, followed by the approximate time
+    #[default]
+    Both,
+}
+
+/// Number of instructions executed per second, measured by executing a module
+/// for `elapsed`. `None` if the run was too short to be measured.
+pub fn calibrate(cycles: u64, elapsed: Duration) -> Option<f64> {
+    let seconds = elapsed.as_secs_f64();
+    if cycles == 0 || seconds <= 0.0 {
+        return None;
+    }
+    Some(cycles as f64 / seconds)
+}
+
+/// Formats execution costs in the configured unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostFormatter {
+    unit: CostUnit,
+
+    /// Conversion rate, `None` if no calibration is available
+    instructions_per_second: Option<f64>,
+}
+
+impl CostFormatter {
+    pub fn new(unit: CostUnit, instructions_per_second: Option<f64>) -> Self {
+        Self {
+            unit,
+            instructions_per_second,
+        }
+    }
+
+    /// Conversion rate, `None` if no calibration is available
+    pub fn instructions_per_second(&self) -> Option<f64> {
+        self.instructions_per_second
+    }
+
+    /// Approximate time in microseconds needed to execute `cycles`
+    pub fn micros(&self, cycles: u64) -> Option<f64> {
+        self.instructions_per_second
+            .map(|rate| cycles as f64 / rate * 1_000_000.0)
+    }
+
+    /// Format `cycles`, e.g. "1200 cycles (~3.4 ms)".
+    /// Falls back to cycles if no calibration is available.
+    pub fn format(&self, cycles: u64) -> String {
+        match (self.unit, self.micros(cycles)) {
+            (CostUnit::Time, Some(micros)) => format!("~{}", format_micros(micros)),
+            (CostUnit::Both, Some(micros)) => {
+                format!("{cycles} cycles (~{})", format_micros(micros))
+            }
+            _ => format!("{cycles} cycles"),
+        }
+    }
+}
+
+/// Format a time in microseconds with a suitable unit
+fn format_micros(micros: f64) -> String {
+    if micros < 1000.0 {
+        format!("{micros:.1} µs")
+    } else if micros < 1_000_000.0 {
+        format!("{:.1} ms", micros / 1000.0)
+    } else {
+        format!("{:.2} s", micros / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration() {
+        assert_eq!(calibrate(1000, Duration::from_millis(1)), Some(1_000_000.0));
+        assert_eq!(calibrate(0, Duration::from_millis(1)), None);
+        assert_eq!(calibrate(1000, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn format_units() {
+        let rate = Some(2_000_000.0);
+
+        assert_eq!(
+            CostFormatter::new(CostUnit::Both, rate).format(3),
+            "3 cycles (~1.5 µs)"
+        );
+        assert_eq!(
+            CostFormatter::new(CostUnit::Time, rate).format(5000),
+            "~2.5 ms"
+        );
+        assert_eq!(
+            CostFormatter::new(CostUnit::Time, rate).format(9_000_000),
+            "~4.50 s"
+        );
+        assert_eq!(
+            CostFormatter::new(CostUnit::Cycles, rate).format(5000),
+            "5000 cycles"
+        );
+    }
+
+    #[test]
+    fn format_without_calibration() {
+        let formatter = CostFormatter::new(CostUnit::Time, None);
+        assert_eq!(formatter.micros(5000), None);
+        assert_eq!(formatter.format(5000), "5000 cycles");
+    }
+}
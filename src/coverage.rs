@@ -0,0 +1,205 @@
+//! Per-function coverage and execution cost of the baseline run.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use wasmut_wasm::elements::Instruction;
+
+use crate::{policy::MutationPolicy, runtime::TracePoints, wasmmodule::WasmModule};
+
+/// Name used for instructions without function information
+const UNKNOWN_FUNCTION: &str = "<unknown>";
+
+/// Coverage and execution cost of a single function in the baseline run
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FunctionCoverage {
+    /// Name of the function
+    pub function: String,
+
+    /// Whether the function may be mutated according to the filter configuration
+    pub allowed: bool,
+
+    /// Number of instructions of the function
+    pub instructions: u64,
+
+    /// Number of instructions that were executed at least once
+    pub covered_instructions: u64,
+
+    /// Total number of executed instructions
+    pub executed_instructions: u64,
+
+    /// Number of branch edges, two for every `if` and `br_if`
+    pub branches: u64,
+
+    /// Number of branch edges that were taken at least once
+    pub covered_branches: u64,
+}
+
+impl FunctionCoverage {
+    /// Percentage of covered instructions
+    pub fn coverage(&self) -> f64 {
+        if self.instructions == 0 {
+            0.0
+        } else {
+            100.0 * self.covered_instructions as f64 / self.instructions as f64
+        }
+    }
+
+    /// Percentage of taken branch edges, `None` if the function has no conditional branches
+    pub fn branch_coverage(&self) -> Option<f64> {
+        if self.branches == 0 {
+            None
+        } else {
+            Some(100.0 * self.covered_branches as f64 / self.branches as f64)
+        }
+    }
+}
+
+/// Coverage of all functions, as printed by the `coverage --format json` command
+#[derive(Serialize)]
+pub struct CoverageReport<'a> {
+    pub functions: &'a [FunctionCoverage],
+    pub total_executed_instructions: u64,
+}
+
+/// Aggregate trace points per function.
+///
+/// Branch coverage counts the taken edges (condition zero or non-zero)
+/// of every conditional branch (`if` and `br_if`).
+///
+/// Functions are identified by their name in the debug information, like in
+/// the filter configuration. Instructions of inlined functions are attributed
+/// to the inlined function. The result is sorted by executed instructions, descending.
+pub fn function_coverage(
+    module: &WasmModule,
+    trace_points: &TracePoints,
+    policy: &MutationPolicy,
+) -> Result<Vec<FunctionCoverage>> {
+    let instructions = module.instruction_walker(&|instruction, location| {
+        vec![(
+            location.function.map(String::from),
+            location.instruction_offset,
+            matches!(instruction, Instruction::If(_) | Instruction::BrIf(_)),
+        )]
+    })?;
+
+    Ok(aggregate(instructions, trace_points, policy))
+}
+
+/// Aggregate `(function, offset, is conditional branch)` triples of all instructions
+fn aggregate(
+    instructions: Vec<(Option<String>, u64, bool)>,
+    trace_points: &TracePoints,
+    policy: &MutationPolicy,
+) -> Vec<FunctionCoverage> {
+    let mut functions: BTreeMap<String, FunctionCoverage> = BTreeMap::new();
+
+    for (function, offset, is_branch) in instructions {
+        let function = function.unwrap_or_else(|| UNKNOWN_FUNCTION.into());
+
+        let entry = functions
+            .entry(function.clone())
+            .or_insert_with(|| FunctionCoverage {
+                allowed: policy.check_function(&function),
+                function,
+                instructions: 0,
+                covered_instructions: 0,
+                executed_instructions: 0,
+                branches: 0,
+                covered_branches: 0,
+            });
+
+        let count = trace_points.count(offset);
+        entry.instructions += 1;
+        entry.executed_instructions += count;
+        if count > 0 {
+            entry.covered_instructions += 1;
+        }
+
+        if is_branch {
+            entry.branches += 2;
+            entry.covered_branches += trace_points
+                .branch(offset)
+                .map(|branch| branch.covered_edges() as u64)
+                .unwrap_or(0);
+        }
+    }
+
+    let mut functions: Vec<_> = functions.into_values().collect();
+    functions.sort_by_key(|f| std::cmp::Reverse(f.executed_instructions));
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::MutationPolicyBuilder;
+
+    #[test]
+    fn aggregate_per_function() {
+        let mut trace_points = TracePoints::default();
+        for _ in 0..10 {
+            trace_points.add_point(1);
+        }
+        trace_points.add_point(2);
+        trace_points.add_point(20);
+        trace_points.add_branch(2, true);
+        trace_points.add_branch(20, true);
+        trace_points.add_branch(20, false);
+
+        let policy = MutationPolicyBuilder::default()
+            .allow_function("^add$")
+            .build()
+            .unwrap();
+
+        let functions = aggregate(
+            vec![
+                (Some("add".into()), 1, false),
+                (Some("add".into()), 2, true),
+                (Some("add".into()), 3, true),
+                (Some("main".into()), 20, true),
+                (None, 30, false),
+            ],
+            &trace_points,
+            &policy,
+        );
+
+        assert_eq!(
+            functions,
+            vec![
+                FunctionCoverage {
+                    function: "add".into(),
+                    allowed: true,
+                    instructions: 3,
+                    covered_instructions: 2,
+                    executed_instructions: 11,
+                    branches: 4,
+                    covered_branches: 1,
+                },
+                FunctionCoverage {
+                    function: "main".into(),
+                    allowed: false,
+                    instructions: 1,
+                    covered_instructions: 1,
+                    executed_instructions: 1,
+                    branches: 2,
+                    covered_branches: 2,
+                },
+                FunctionCoverage {
+                    function: UNKNOWN_FUNCTION.into(),
+                    allowed: false,
+                    instructions: 1,
+                    covered_instructions: 0,
+                    executed_instructions: 0,
+                    branches: 0,
+                    covered_branches: 0,
+                },
+            ]
+        );
+        assert!((functions[0].coverage() - 66.67).abs() < 0.01);
+        assert_eq!(functions[0].branch_coverage(), Some(25.0));
+        assert_eq!(functions[2].branch_coverage(), None);
+    }
+}
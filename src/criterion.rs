@@ -0,0 +1,362 @@
+//! Kill criteria decide whether a mutant was killed by the tests.
+//!
+//! By default, a mutant is killed if the module returns a non-zero exit code.
+//! Other criteria compare the output of the mutant to the output of the
+//! unmutated module or to a golden file. Criteria are selected and combined
+//! in the `[kill_criteria]` section of the configuration. Custom criteria
+//! implement `KillCriterion` and are passed to `Executor::with_kill_criterion`.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    baseline::Baseline,
+    config::KillCriteriaConfig,
+    error::ErrorKind,
+    executor,
+    runtime::{ExecutionResult, ExecutionTrace},
+};
+
+/// Everything a kill criterion can base its decision on
+pub struct MutantRun<'a> {
+    /// Result of executing the mutant
+    pub result: &'a ExecutionResult,
+
+    /// Captured output of the mutant.
+    /// Empty, unless `KillCriterion::needs_output` returns true
+    pub output: &'a ExecutionTrace,
+
+    /// Baseline run of the unmutated module.
+    /// Only available if `KillCriterion::needs_baseline` returns true
+    /// or if a recorded baseline is used
+    pub baseline: Option<&'a Baseline>,
+}
+
+/// Decides whether a mutant was killed.
+///
+/// Criteria are only consulted for mutants that terminated normally,
+/// mutants that timed out or trapped always count as killed.
+pub trait KillCriterion: Send + Sync {
+    /// Name of the criterion, as used in the configuration
+    fn name(&self) -> String;
+
+    /// True if the criterion inspects the output of the mutant
+    fn needs_output(&self) -> bool {
+        false
+    }
+
+    /// True if the criterion compares the mutant against the baseline run
+    fn needs_baseline(&self) -> bool {
+        false
+    }
+
+    /// Return true if the mutant was killed
+    fn is_killed(&self, run: &MutantRun) -> bool;
+
+    /// Names of the criteria that killed the mutant, empty if it survived.
+    /// Combined criteria return every criterion that considers the mutant killed,
+    /// so that reports can show which checks detect a mutant.
+    fn killed_by(&self, run: &MutantRun) -> Vec<String> {
+        if self.is_killed(run) {
+            vec![self.name()]
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// Human readable name of a kill criterion, e.g. `stdout diff` for `output_diff`.
+/// Names of custom criteria are returned as they are.
+pub fn describe(name: &str) -> &str {
+    match name {
+        "exit_code" => "exit code",
+        "output_diff" => "stdout diff",
+        "golden_file" => "golden file",
+        name => name,
+    }
+}
+
+/// Built-in kill criteria, as named in the configuration
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CriterionKind {
+    ExitCode,
+    OutputDiff,
+    GoldenFile,
+}
+
+/// How multiple kill criteria are combined
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Combination {
+    /// The mutant is killed if any criterion considers it killed
+    Any,
+    /// The mutant is killed only if all criteria consider it killed
+    All,
+}
+
+/// Killed if the module returns a non-zero exit code
+pub struct ExitCode;
+
+impl KillCriterion for ExitCode {
+    fn name(&self) -> String {
+        "exit_code".into()
+    }
+
+    fn is_killed(&self, run: &MutantRun) -> bool {
+        !executor::is_alive(run.result)
+    }
+}
+
+/// Killed if stdout differs from the stdout of the baseline run
+pub struct OutputDiff;
+
+impl KillCriterion for OutputDiff {
+    fn name(&self) -> String {
+        "output_diff".into()
+    }
+
+    fn needs_output(&self) -> bool {
+        true
+    }
+
+    fn needs_baseline(&self) -> bool {
+        true
+    }
+
+    fn is_killed(&self, run: &MutantRun) -> bool {
+        match run.baseline {
+            Some(baseline) => run.output.stdout != baseline.stdout,
+            None => false,
+        }
+    }
+}
+
+/// Killed if stdout differs from the content of a golden file
+pub struct GoldenFile {
+    expected: String,
+}
+
+impl GoldenFile {
+    /// Read the expected output from `path`
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let expected = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read golden file {path:?}"))
+            .context(ErrorKind::Config)?;
+
+        Ok(Self { expected })
+    }
+}
+
+impl KillCriterion for GoldenFile {
+    fn name(&self) -> String {
+        "golden_file".into()
+    }
+
+    fn needs_output(&self) -> bool {
+        true
+    }
+
+    fn is_killed(&self, run: &MutantRun) -> bool {
+        run.output.stdout != self.expected
+    }
+}
+
+/// Several criteria, combined with `Combination`
+pub struct Combined {
+    criteria: Vec<Box<dyn KillCriterion>>,
+    combination: Combination,
+}
+
+impl KillCriterion for Combined {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.criteria.iter().map(|c| c.name()).collect();
+        let separator = match self.combination {
+            Combination::Any => " | ",
+            Combination::All => " & ",
+        };
+        names.join(separator)
+    }
+
+    fn needs_output(&self) -> bool {
+        self.criteria.iter().any(|c| c.needs_output())
+    }
+
+    fn needs_baseline(&self) -> bool {
+        self.criteria.iter().any(|c| c.needs_baseline())
+    }
+
+    fn is_killed(&self, run: &MutantRun) -> bool {
+        match self.combination {
+            Combination::Any => self.criteria.iter().any(|c| c.is_killed(run)),
+            Combination::All => self.criteria.iter().all(|c| c.is_killed(run)),
+        }
+    }
+
+    fn killed_by(&self, run: &MutantRun) -> Vec<String> {
+        let killed_by: Vec<String> = self
+            .criteria
+            .iter()
+            .flat_map(|c| c.killed_by(run))
+            .collect();
+
+        match self.combination {
+            Combination::All if killed_by.len() < self.criteria.len() => vec![],
+            _ => killed_by,
+        }
+    }
+}
+
+/// Create the kill criterion selected in the configuration
+pub fn from_config(config: &KillCriteriaConfig) -> Result<Box<dyn KillCriterion>> {
+    let mut criteria: Vec<Box<dyn KillCriterion>> = Vec::new();
+
+    for kind in config.criteria() {
+        let criterion: Box<dyn KillCriterion> = match kind {
+            CriterionKind::ExitCode => Box::new(ExitCode),
+            CriterionKind::OutputDiff => Box::new(OutputDiff),
+            CriterionKind::GoldenFile => {
+                // The configuration ensures that a golden file is set
+                let path = config.golden_file().unwrap_or_default();
+                Box::new(GoldenFile::new(path)?)
+            }
+        };
+        criteria.push(criterion);
+    }
+
+    if criteria.len() == 1 {
+        return Ok(criteria.remove(0));
+    }
+
+    Ok(Box::new(Combined {
+        criteria,
+        combination: config.combine(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn baseline(stdout: &str) -> Baseline {
+        Baseline {
+            version: "1.0.0".into(),
+            module_hash: "abc".into(),
+            exit_code: 0,
+            execution_cost: 100,
+            duration: 1,
+            coverage: None,
+            stdout: stdout.into(),
+            stderr: String::new(),
+        }
+    }
+
+    /// Decide about a mutant that exited with `exit_code` and printed `stdout`,
+    /// with a baseline that printed `42`
+    fn decide<T>(exit_code: u32, stdout: &str, decision: impl FnOnce(&MutantRun) -> T) -> T {
+        let baseline = baseline("42\n");
+        decision(&MutantRun {
+            result: &ExecutionResult::ProcessExit {
+                exit_code,
+                execution_cost: 100,
+            },
+            output: &ExecutionTrace {
+                stdout: stdout.into(),
+                ..Default::default()
+            },
+            baseline: Some(&baseline),
+        })
+    }
+
+    fn is_killed(criterion: &dyn KillCriterion, exit_code: u32, stdout: &str) -> bool {
+        decide(exit_code, stdout, |run| criterion.is_killed(run))
+    }
+
+    fn killed_by(criterion: &dyn KillCriterion, exit_code: u32, stdout: &str) -> Vec<String> {
+        decide(exit_code, stdout, |run| criterion.killed_by(run))
+    }
+
+    fn criterion(toml: &str) -> Result<Box<dyn KillCriterion>> {
+        from_config(Config::parse(toml)?.kill_criteria())
+    }
+
+    #[test]
+    fn builtin_criteria() -> Result<()> {
+        let exit_code = criterion("")?;
+        assert_eq!(exit_code.name(), "exit_code");
+        assert!(!exit_code.needs_output());
+        assert!(is_killed(&*exit_code, 1, "42\n"));
+        assert!(!is_killed(&*exit_code, 0, "43\n"));
+
+        let output_diff = criterion("[kill_criteria]\ncriteria = [\"output_diff\"]")?;
+        assert!(output_diff.needs_baseline());
+        assert!(is_killed(&*output_diff, 0, "43\n"));
+        assert!(!is_killed(&*output_diff, 1, "42\n"));
+
+        let dir = tempfile::tempdir()?;
+        let golden = dir.path().join("expected.txt");
+        std::fs::write(&golden, "43\n")?;
+        let golden_file = criterion(&format!(
+            "[kill_criteria]\ncriteria = [\"golden_file\"]\ngolden_file = {:?}",
+            golden
+        ))?;
+        assert!(!golden_file.needs_baseline());
+        assert!(is_killed(&*golden_file, 0, "42\n"));
+        assert!(!is_killed(&*golden_file, 0, "43\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn combined_criteria() -> Result<()> {
+        let any = criterion("[kill_criteria]\ncriteria = [\"exit_code\", \"output_diff\"]")?;
+        assert_eq!(any.name(), "exit_code | output_diff");
+        assert!(any.needs_output());
+        assert!(is_killed(&*any, 1, "42\n"));
+        assert!(is_killed(&*any, 0, "43\n"));
+        assert!(!is_killed(&*any, 0, "42\n"));
+
+        let all = criterion(
+            "[kill_criteria]\ncriteria = [\"exit_code\", \"output_diff\"]\ncombine = \"all\"",
+        )?;
+        assert_eq!(all.name(), "exit_code & output_diff");
+        assert!(is_killed(&*all, 1, "43\n"));
+        assert!(!is_killed(&*all, 1, "42\n"));
+        assert!(!is_killed(&*all, 0, "43\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn killing_criteria() -> Result<()> {
+        let exit_code = criterion("")?;
+        assert_eq!(killed_by(&*exit_code, 1, "42\n"), ["exit_code"]);
+        assert!(killed_by(&*exit_code, 0, "43\n").is_empty());
+
+        let any = criterion("[kill_criteria]\ncriteria = [\"exit_code\", \"output_diff\"]")?;
+        assert_eq!(killed_by(&*any, 0, "43\n"), ["output_diff"]);
+        assert_eq!(killed_by(&*any, 1, "43\n"), ["exit_code", "output_diff"]);
+        assert!(killed_by(&*any, 0, "42\n").is_empty());
+
+        let all = criterion(
+            "[kill_criteria]\ncriteria = [\"exit_code\", \"output_diff\"]\ncombine = \"all\"",
+        )?;
+        assert_eq!(killed_by(&*all, 1, "43\n"), ["exit_code", "output_diff"]);
+        assert!(killed_by(&*all, 1, "42\n").is_empty());
+
+        assert_eq!(describe("output_diff"), "stdout diff");
+        assert_eq!(describe("custom"), "custom");
+        Ok(())
+    }
+
+    #[test]
+    fn missing_golden_file() {
+        let missing = criterion(
+            "[kill_criteria]\ncriteria = [\"golden_file\"]\ngolden_file = \"/nonexistent\"",
+        );
+        let error = missing.err().unwrap();
+        assert_eq!(error.downcast_ref::<ErrorKind>(), Some(&ErrorKind::Config));
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt::Display;
+
+/// Category of a user-facing failure.
+///
+/// Errors are tagged with an `ErrorKind` by attaching it as context,
+/// e.g. `.context(ErrorKind::Config)`. The kind determines the exit code of wasmut,
+/// so that scripts can react to specific failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Configuration could not be loaded or is invalid
+    Config,
+
+    /// Baseline run of the unmutated module failed
+    Baseline,
+
+    /// WebAssembly module could not be loaded
+    Module,
+
+    /// Mutation run was aborted by the user
+    Aborted,
+
+    /// Mutation score is below the threshold given by `--fail-under`
+    ScoreBelowThreshold,
+
+    /// Mutants re-executed by `--verify-sample` had a different outcome
+    SampleMismatch,
+
+    /// Runtime returned a result that is impossible for the execution
+    Runtime,
+}
+
+impl ErrorKind {
+    /// Exit code used when wasmut fails with this kind of error.
+    ///
+    /// Exit code 1 is used for all other errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Config => 2,
+            ErrorKind::Baseline => 3,
+            ErrorKind::ScoreBelowThreshold => 4,
+            ErrorKind::Module => 5,
+            ErrorKind::Aborted => 6,
+            ErrorKind::SampleMismatch => 7,
+            ErrorKind::Runtime => 8,
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::Config => write!(f, "Invalid configuration"),
+            ErrorKind::Baseline => write!(f, "Baseline run of the unmutated module failed"),
+            ErrorKind::Module => write!(f, "Could not load WebAssembly module"),
+            ErrorKind::Aborted => write!(f, "Mutation run aborted by user"),
+            ErrorKind::ScoreBelowThreshold => write!(f, "Mutation score below threshold"),
+            ErrorKind::SampleMismatch => {
+                write!(f, "Meta-mutant outcomes differ from one-by-one execution")
+            }
+            ErrorKind::Runtime => write!(f, "Runtime returned an unexpected result"),
+        }
+    }
+}
+
+impl std::error::Error for ErrorKind {}
+
+/// Determine the exit code for an error
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    error
+        .downcast_ref::<ErrorKind>()
+        .map(ErrorKind::exit_code)
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, bail, Context, Result};
+
+    #[test]
+    fn exit_code_of_untagged_error() {
+        assert_eq!(exit_code(&anyhow!("some error")), 1);
+    }
+
+    #[test]
+    fn exit_code_of_tagged_error() {
+        let result: Result<()> = Err(anyhow!("parse error")).context(ErrorKind::Config);
+        assert_eq!(exit_code(&result.unwrap_err()), 2);
+    }
+
+    #[test]
+    fn exit_code_of_nested_context() {
+        fn inner() -> Result<()> {
+            bail!(ErrorKind::Baseline)
+        }
+
+        let result = inner().context("Mutation run failed");
+        assert_eq!(exit_code(&result.unwrap_err()), 3);
+    }
+}
@@ -1,20 +1,211 @@
+use std::any::Any;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 use indicatif::{ParallelProgressIterator, ProgressBar};
 
-use crate::mutation::MutationLocation;
+use crate::baseline::Baseline;
+use crate::config::KillCriteriaConfig;
+use crate::cost::{self, CostFormatter, CostUnit};
+use crate::criterion::{self, CriterionKind, KillCriterion, MutantRun};
+use crate::hook::ExecutionHook;
+use crate::interrupt;
+use crate::mutation::{Mutation, MutationLocation};
 use crate::operator::InstructionReplacement;
 use crate::policy::ExecutionPolicy;
-use crate::runtime::wasmer::{WasmerRuntime, WasmerRuntimeFactory};
-use crate::runtime::{ExecutionResult, TracePoints};
-use crate::{config::Config, wasmmodule::WasmModule};
-use anyhow::{bail, Result};
+use crate::progress::Progress;
+use crate::reporter::{MutationOutcome, RunMetadata};
+use crate::resultcache::{self, ResultCache};
+use crate::runtime::wasmer::WasmerRuntime;
+use crate::runtime::{
+    create_runtime, ComparisonProfile, Compiler, ExecutionResult, ExecutionTrace, Runtime,
+    RuntimeFactory, RuntimeKind, TracePoints,
+};
+use crate::warnings::{self, WarningCategory};
+use crate::{
+    config::Config,
+    error::ErrorKind,
+    wasmmodule::{MetaMutantSize, ModuleSize, MutantActivation, WasmModule},
+};
+use anyhow::{anyhow, bail, Context, Result};
 
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Callback used to confirm a mutation run, based on its estimated cost
+pub type ConfirmationCallback<'a> = Box<dyn Fn(&RunEstimate) -> bool + Send + Sync + 'a>;
+
+/// Estimated cost of a mutation run.
+///
+/// The estimate is calculated after the baseline run, by
+/// multiplying the time it took to prepare and execute the baseline
+/// with the number of mutants and dividing by the number of threads.
+#[derive(Debug)]
+pub struct RunEstimate {
+    /// Number of mutants that will actually be executed
+    pub mutants: usize,
+
+    /// Wall-clock time needed to prepare and execute a single mutant.
+    /// In one-by-one mode, this includes compiling the module, in meta-mutant
+    /// mode only the instantiation and execution.
+    pub time_per_mutant: Duration,
+
+    /// Number of worker threads
+    pub threads: usize,
+
+    /// Whether a meta-mutant is used
+    pub meta_mutant: bool,
+
+    /// Instrumentation overhead factor of the meta-mutant, see `instrumentation_overhead`
+    pub overhead: Option<f64>,
+}
+
+impl RunEstimate {
+    /// Estimated wall-clock time needed to execute all mutants
+    pub fn total_time(&self) -> Duration {
+        let threads = self.threads.max(1) as f64;
+        self.time_per_mutant.mul_f64(self.mutants as f64 / threads)
+    }
+}
+
+impl Display for RunEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total = self.total_time().as_secs();
+
+        write!(
+            f,
+            "Estimated execution time for {} mutants ({} mode, {} threads): {}h {:02}m {:02}s",
+            self.mutants,
+            engine_mode(self.meta_mutant),
+            self.threads,
+            total / 3600,
+            (total / 60) % 60,
+            total % 60
+        )?;
+
+        if let Some(overhead) = self.overhead {
+            write!(
+                f,
+                ", instrumentation overhead factor: {overhead:.2} (the meta-mutant with all \
+                mutations disabled executes {overhead:.2} times the instructions of the \
+                original module, timeouts are based on the meta-mutant)"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Name of the execution mode, as shown in logs and reports
+pub fn engine_mode(meta_mutant: bool) -> &'static str {
+    if meta_mutant {
+        "meta-mutant"
+    } else {
+        "one-by-one"
+    }
+}
 
 #[derive(Debug)]
 pub struct ExecutedMutant {
     pub offset: u64,
     pub result: ExecutionResult,
     pub mutation_operator: Box<dyn InstructionReplacement>,
+    /// True if the mutant terminated normally and was not killed
+    /// according to the kill criterion
+    pub survived: bool,
+    /// Execution trace, only recorded for surviving mutants
+    /// if `trace_survivors` is enabled
+    pub trace: Option<ExecutionTrace>,
+    /// True if the mutant survived, but cannot change the behaviour of the
+    /// module according to the comparison profiles of the coverage run
+    pub likely_equivalent: bool,
+    /// Observed results of the mutated comparison in the coverage run,
+    /// only recorded for mutants of comparisons
+    pub condition: Option<ComparisonProfile>,
+    /// Why the mutant was skipped because of missing coverage,
+    /// only recorded if `explain_skipped` is enabled
+    pub skip_reason: Option<SkipReason>,
+    /// Names of the kill criteria that killed the mutant,
+    /// empty unless the mutant terminated normally and was killed
+    pub killed_by: Vec<String>,
+}
+
+/// Why a mutant was skipped because of missing coverage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The mutated instruction was never executed. Contains the offset of the
+    /// executed instruction of the same function closest to it, if there is one.
+    NotExecuted { nearest_covered: Option<u64> },
+
+    /// The mutated instruction was executed, but the edge of the
+    /// conditional branch on which the mutant differs was never taken
+    EdgeNotTaken,
+
+    /// The mutant was not run, because its function exceeded
+    /// `expensive_function_threshold` and `expensive_function_action` is `skip`
+    TooExpensive,
+}
+
+/// What happens to mutants of functions exceeding `expensive_function_threshold`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpensiveFunctionAction {
+    /// The expensive functions are only reported
+    #[default]
+    Report,
+
+    /// Mutants of expensive functions are not run
+    Skip,
+
+    /// Mutants of expensive functions are executed with
+    /// `expensive_function_timeout_multiplier`
+    ShortenTimeout,
+}
+
+impl ExecutedMutant {
+    /// Outcome of the mutant. The kill criterion decides about mutants
+    /// that terminated normally.
+    pub fn outcome(&self) -> MutationOutcome {
+        match &self.result {
+            ExecutionResult::ProcessExit { .. } if self.survived => MutationOutcome::Alive,
+            ExecutionResult::ProcessExit { .. } => MutationOutcome::Killed,
+            ExecutionResult::Timeout => MutationOutcome::Timeout,
+            ExecutionResult::Error { .. } => MutationOutcome::Error,
+            ExecutionResult::InternalError { .. } => MutationOutcome::InternalError,
+            ExecutionResult::Skipped => MutationOutcome::Skipped,
+            ExecutionResult::NotRun => MutationOutcome::NotRun,
+        }
+    }
+
+    /// Mutant that was skipped or not run, with `result` describing why
+    fn not_executed(
+        location: &MutationLocation,
+        mutation: &Mutation,
+        result: ExecutionResult,
+    ) -> Self {
+        ExecutedMutant {
+            offset: location.offset,
+            result,
+            mutation_operator: mutation.operator.clone(),
+            survived: false,
+            trace: None,
+            likely_equivalent: false,
+            condition: None,
+            skip_reason: None,
+            killed_by: Vec::new(),
+        }
+    }
+
+    /// Mutant that was not run, because its function is too expensive
+    fn too_expensive(location: &MutationLocation, mutation: &Mutation) -> Self {
+        ExecutedMutant {
+            skip_reason: Some(SkipReason::TooExpensive),
+            ..Self::not_executed(location, mutation, ExecutionResult::NotRun)
+        }
+    }
 }
 
 /// Execution engine for WebAssembly modules
@@ -24,6 +215,10 @@ pub struct Executor<'a> {
     /// this factor with the measured number of cycles
     timeout_multiplier: f64,
 
+    /// If more than this percentage of the executed mutants time out,
+    /// a warning suggests raising the timeout multiplier
+    timeout_warning_threshold: f64,
+
     /// List of directory mappings
     mapped_dirs: &'a [(String, String)],
 
@@ -34,6 +229,83 @@ pub struct Executor<'a> {
     /// If true, only a single mutant containing all possible mutations
     /// will be generated, reducing compilation time.
     meta_mutant: bool,
+
+    /// Maximum number of mutations in a single meta-mutant
+    meta_mutant_chunk_size: usize,
+
+    /// How the meta-mutant checks whether a mutation is activated
+    meta_mutant_activation: MutantActivation,
+
+    /// If true, surviving mutants are executed again, recording
+    /// their output and the sequence of called functions.
+    trace_survivors: bool,
+
+    /// Called with the estimated run time before any mutant is executed.
+    /// If the callback returns false, the run is aborted.
+    confirmation: Option<ConfirmationCallback<'a>>,
+
+    /// Previously recorded baseline run. If set, its execution cost and
+    /// coverage are used instead of executing the unmutated module again.
+    baseline: Option<&'a Baseline>,
+
+    /// No new mutants are started after this point in time
+    deadline: Option<Instant>,
+
+    /// If true, the first Ctrl-C stops starting new mutants instead of
+    /// terminating wasmut
+    interruptible: bool,
+
+    /// Criteria deciding whether a mutant was killed
+    kill_criteria: &'a KillCriteriaConfig,
+
+    /// Custom kill criterion, replacing the configured criteria
+    kill_criterion: Option<Box<dyn KillCriterion + 'a>>,
+
+    /// Hooks notified before and after every executed mutant
+    hooks: Vec<Box<dyn ExecutionHook + 'a>>,
+
+    /// Sizes of the meta-mutants generated so far, reported in the run metadata
+    meta_mutant_sizes: Mutex<Vec<MetaMutantSize>>,
+
+    /// WebAssembly runtime executing the module and its mutants.
+    /// Tracing of survivors and basic blocks always uses wasmer.
+    runtime: RuntimeKind,
+
+    /// With the hybrid runtime, mutants in functions that executed at most
+    /// this many instructions in the baseline run are interpreted
+    hybrid_threshold: u64,
+
+    /// Configured number of instructions executed per second
+    instructions_per_second: Option<u64>,
+
+    /// Number of instructions per second measured in the first baseline run
+    calibration: Mutex<Option<f64>>,
+
+    /// Unit in which execution costs are logged
+    cost_unit: CostUnit,
+
+    /// If true, the reason is recorded for every mutant skipped because of missing coverage
+    explain_skipped: bool,
+
+    /// Log a progress summary in this interval
+    summary_interval: Option<Duration>,
+
+    /// Log a progress summary after this many mutants
+    summary_mutants: Option<usize>,
+
+    /// Functions that executed more than this percentage of all instructions
+    /// in the coverage run are expensive
+    expensive_threshold: Option<f64>,
+
+    /// What happens to mutants of expensive functions
+    expensive_action: ExpensiveFunctionAction,
+
+    /// Timeout multiplier for mutants of expensive functions,
+    /// if `expensive_action` is `ShortenTimeout`
+    expensive_timeout_multiplier: f64,
+
+    /// Cache directory, if outcomes of mutants are reused across runs
+    result_cache: Option<PathBuf>,
 }
 
 impl<'a> Executor<'a> {
@@ -41,9 +313,184 @@ impl<'a> Executor<'a> {
     pub fn new(config: &'a Config) -> Self {
         Executor {
             timeout_multiplier: config.engine().timeout_multiplier(),
+            timeout_warning_threshold: config.engine().timeout_warning_threshold(),
             mapped_dirs: config.engine().map_dirs(),
             coverage: config.engine().coverage_based_execution(),
             meta_mutant: config.engine().meta_mutant(),
+            meta_mutant_chunk_size: config.engine().meta_mutant_chunk_size(),
+            meta_mutant_activation: config.engine().meta_mutant_activation(),
+            trace_survivors: config.engine().trace_survivors(),
+            confirmation: None,
+            baseline: None,
+            deadline: None,
+            interruptible: false,
+            kill_criteria: config.kill_criteria(),
+            kill_criterion: None,
+            hooks: Vec::new(),
+            meta_mutant_sizes: Default::default(),
+            runtime: config.engine().runtime(),
+            hybrid_threshold: config.engine().hybrid_threshold(),
+            instructions_per_second: config.engine().instructions_per_second(),
+            calibration: Default::default(),
+            cost_unit: config.report().cost_unit(),
+            explain_skipped: config.report().explain_skipped(),
+            summary_interval: config
+                .report()
+                .summary_interval_minutes()
+                .map(|minutes| Duration::from_secs(minutes * 60)),
+            summary_mutants: config.report().summary_interval_mutants(),
+            expensive_threshold: config.engine().expensive_function_threshold(),
+            expensive_action: config.engine().expensive_function_action(),
+            expensive_timeout_multiplier: config.engine().expensive_function_timeout_multiplier(),
+            result_cache: None,
+        }
+    }
+
+    /// Ask for confirmation via `callback` before mutants are executed
+    pub fn with_confirmation(self, callback: ConfirmationCallback<'a>) -> Self {
+        Self {
+            confirmation: Some(callback),
+            ..self
+        }
+    }
+
+    /// Reuse a baseline recorded by `record_baseline`.
+    /// The baseline must have been checked against the module.
+    pub fn with_baseline(self, baseline: &'a Baseline) -> Self {
+        Self {
+            baseline: Some(baseline),
+            ..self
+        }
+    }
+
+    /// Stop starting new mutants at `deadline`.
+    /// Mutants that were not started are reported as `ExecutionResult::NotRun`.
+    pub fn with_deadline(self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Execute mutants in a meta-mutant or one by one, regardless of the configuration
+    pub fn with_meta_mutant(self, meta_mutant: bool) -> Self {
+        Self {
+            meta_mutant,
+            ..self
+        }
+    }
+
+    /// Stop starting new mutants on the first Ctrl-C after the run was confirmed.
+    /// Mutants that were not started are reported as `ExecutionResult::NotRun`.
+    pub fn with_interruption(self) -> Self {
+        Self {
+            interruptible: true,
+            ..self
+        }
+    }
+
+    /// Decide whether mutants were killed using `criterion`
+    /// instead of the criteria selected in the configuration
+    pub fn with_kill_criterion(self, criterion: Box<dyn KillCriterion + 'a>) -> Self {
+        Self {
+            kill_criterion: Some(criterion),
+            ..self
+        }
+    }
+
+    /// Reuse outcomes of identical mutants of previous runs, which are cached in
+    /// `directory`, and cache the outcomes of all executed mutants
+    pub fn with_result_cache(self, directory: PathBuf) -> Self {
+        Self {
+            result_cache: Some(directory),
+            ..self
+        }
+    }
+
+    /// Notify `hook` before and after every executed mutant.
+    /// Can be called multiple times, hooks are called in the order they were added.
+    pub fn with_hook(mut self, hook: Box<dyn ExecutionHook + 'a>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Formatter for execution costs, using the configured number of instructions
+    /// per second or, if not configured, the calibration of the first baseline run
+    pub fn cost_formatter(&self) -> CostFormatter {
+        let rate = match self.instructions_per_second {
+            Some(rate) => Some(rate as f64),
+            None => self.calibration.lock().ok().and_then(|rate| *rate),
+        };
+        CostFormatter::new(self.cost_unit, rate)
+    }
+
+    /// True if the deadline of the run has passed
+    fn out_of_time(&self) -> bool {
+        match self.deadline {
+            Some(deadline) => Instant::now() >= deadline,
+            None => false,
+        }
+    }
+
+    /// True if the run was interrupted
+    fn interrupted(&self) -> bool {
+        self.interruptible && interrupt::interrupted()
+    }
+
+    /// True if no new mutants should be started
+    fn should_stop(&self) -> bool {
+        self.interrupted() || self.out_of_time()
+    }
+
+    /// Remember the number of instructions per second of the first baseline run,
+    /// so that all costs of a run are converted with the same rate
+    fn calibrate(&self, execution_cost: u64, elapsed: Duration) {
+        if let Some(rate) = cost::calibrate(execution_cost, elapsed) {
+            if let Ok(mut calibration) = self.calibration.lock() {
+                calibration.get_or_insert(rate);
+            }
+        }
+    }
+
+    /// Metadata of a mutation run performed by this executor, taking `duration`
+    pub fn run_metadata(&self, duration: Duration) -> RunMetadata {
+        // Meta-mutants are compiled with Cranelift, single mutants with Singlepass
+        let compiler = match self.runtime {
+            RuntimeKind::Wasmi => Compiler::Interpreter.to_string(),
+            RuntimeKind::Hybrid => format!("{} and {}", Compiler::Cranelift, Compiler::Interpreter),
+            RuntimeKind::Wasmer if self.meta_mutant => Compiler::Cranelift.to_string(),
+            RuntimeKind::Wasmer => Compiler::Singlepass.to_string(),
+        };
+
+        RunMetadata {
+            version: String::from(env!("CARGO_PKG_VERSION")),
+            duration: duration.as_millis() as u64,
+            threads: rayon::current_num_threads(),
+            engine_mode: engine_mode(self.meta_mutant).into(),
+            compiler,
+            partial: false,
+            filtered_mutants: 0,
+            scope: None,
+            meta_mutant_sizes: self
+                .meta_mutant_sizes
+                .lock()
+                .map(|sizes| sizes.clone())
+                .unwrap_or_default(),
+            instructions_per_second: self
+                .cost_formatter()
+                .instructions_per_second()
+                .map(|rate| rate as u64),
+        }
+    }
+
+    /// Number of module compilations needed to execute all mutants in `locations`,
+    /// not including the baseline runs. In one-by-one mode, fewer compilations
+    /// may be needed if mutants are skipped because of missing coverage.
+    pub fn compilations(&self, locations: &[MutationLocation]) -> usize {
+        if self.meta_mutant {
+            chunk_locations(locations, self.meta_mutant_chunk_size).len()
+        } else {
+            locations.iter().map(|l| l.mutations.len()).sum()
         }
     }
 
@@ -51,30 +498,223 @@ impl<'a> Executor<'a> {
     ///
     /// The stdout/stderr output of the module will not be supressed
     pub fn execute(&self, module: &WasmModule) -> Result<()> {
-        let mut runtime = WasmerRuntime::new(module, false, self.mapped_dirs)?;
-        let execution_cost = self.calculate_execution_cost(&mut runtime)?;
-        log::info!("Module executed in {execution_cost} cycles");
+        let mut runtime = self.create_runtime(module, false)?;
+        let execution_cost = self
+            .calculate_execution_cost(runtime.as_mut())
+            .context(ErrorKind::Baseline)?;
+        log::info!(
+            "Module executed in {}",
+            self.cost_formatter().format(execution_cost)
+        );
 
         Ok(())
     }
 
+    /// Execute the unmutated module and record its exit code, execution cost
+    /// and output. If `coverage` is true, the module is executed a second time
+    /// with tracing enabled, recording how often every instruction was executed.
+    pub fn record_baseline(&self, module: &WasmModule, coverage: bool) -> Result<Baseline> {
+        let mut runtime = self.create_runtime(module, true)?;
+        let start = Instant::now();
+        let result = runtime.call_test_function(ExecutionPolicy::RunUntilReturn)?;
+        let duration = start.elapsed();
+        let output = runtime.execution_trace();
+
+        let (exit_code, execution_cost) = match result {
+            ExecutionResult::ProcessExit {
+                exit_code,
+                execution_cost,
+            } => (exit_code, execution_cost),
+            ExecutionResult::Timeout => {
+                return Err(anyhow!(
+                    "Execution limit exceeded even though no limit was set"
+                ))
+                .context(ErrorKind::Runtime);
+            }
+            ExecutionResult::Error { .. } => bail!("Module failed to execute"),
+            other @ (ExecutionResult::Skipped
+            | ExecutionResult::NotRun
+            | ExecutionResult::InternalError { .. }) => {
+                return Err(anyhow!("Runtime returned {other:?}")).context(ErrorKind::Runtime);
+            }
+        };
+        self.calibrate(execution_cost, duration);
+
+        // Tracing requires a successful run of the module
+        let coverage = if coverage && exit_code == 0 {
+            Some(self.get_trace_points(module)?)
+        } else {
+            None
+        };
+
+        Ok(Baseline {
+            version: String::from(env!("CARGO_PKG_VERSION")),
+            module_hash: Baseline::hash_module(module.bytes()),
+            exit_code,
+            execution_cost,
+            duration: duration.as_millis() as u64,
+            coverage,
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+
     pub fn execute_mutants(
         &self,
         module: &WasmModule,
         locations: &[MutationLocation],
     ) -> Result<Vec<ExecutedMutant>> {
-        let trace_points = if self.coverage {
-            self.get_trace_points(module)?
-        } else {
-            TracePoints::default()
+        let recorded_coverage = self.baseline.and_then(|b| b.coverage.as_ref());
+
+        // Hybrid execution and expensive functions are based on the coverage run,
+        // even if mutants are not skipped based on coverage
+        let needs_coverage = self.coverage
+            || self.runtime == RuntimeKind::Hybrid
+            || self.expensive_threshold.is_some();
+        let trace_points = match (needs_coverage, recorded_coverage) {
+            (true, Some(trace_points)) => {
+                log::info!("Using coverage of the recorded baseline");
+                trace_points.clone()
+            }
+            (true, None) => self.get_trace_points(module).context(ErrorKind::Baseline)?,
+            (false, _) => TracePoints::default(),
+        };
+
+        if self.coverage && !trace_points.has_branch_coverage() {
+            log::info!("No branch coverage available, using statement coverage only");
+        }
+
+        let coverage = Coverage {
+            enabled: self.coverage,
+            branches: module.conditional_branches(locations),
+            expensive: self.expensive_functions(module, &trace_points),
+            trace_points,
+        };
+
+        let configured_criterion;
+        let criterion = match &self.kill_criterion {
+            Some(criterion) => criterion.as_ref(),
+            None => {
+                configured_criterion = criterion::from_config(self.kill_criteria)?;
+                configured_criterion.as_ref()
+            }
+        };
+        log::debug!("Using kill criterion {}", criterion.name());
+
+        let recorded_baseline;
+        let baseline = match self.baseline {
+            Some(baseline) => Some(baseline),
+            None if criterion.needs_baseline() => {
+                recorded_baseline = self
+                    .record_baseline(module, false)
+                    .context(ErrorKind::Baseline)?;
+                Some(&recorded_baseline)
+            }
+            None => None,
+        };
+        let judge = Judge {
+            criterion,
+            baseline,
+        };
+
+        let mut result_cache = match &self.result_cache {
+            Some(directory) => {
+                let inputs = self.result_cache_inputs(criterion, baseline)?;
+                Some(ResultCache::load(directory, &inputs, module)?)
+            }
+            None => None,
+        };
+        let (uncached, cached) = match &result_cache {
+            Some(result_cache) => {
+                let (uncached, cached) = coverage.split_cached(result_cache, locations);
+                log::info!("Reusing {} cached mutant outcomes", cached.len());
+                (Some(uncached), cached)
+            }
+            None => (None, Vec::new()),
         };
+        let locations = uncached.as_deref().unwrap_or(locations);
 
         let outcomes = if self.meta_mutant {
-            self.execute_mutants_meta(module, locations, trace_points)
+            self.execute_mutants_meta(module, locations, &coverage, &judge)
         } else {
-            self.execute_mutants_one_by_one(module, locations, trace_points)
+            self.execute_mutants_one_by_one(module, locations, &coverage, &judge)
         }?;
 
+        if let Some(result_cache) = &mut result_cache {
+            let cacheable: Vec<MutationLocation> = locations
+                .iter()
+                .filter(|location| !coverage.expensive.affects(location))
+                .cloned()
+                .collect();
+            result_cache.insert(&cacheable, &outcomes);
+            if let Err(e) = result_cache.save() {
+                log::warn!("Could not save cached mutant outcomes: {e}");
+            }
+        }
+
+        let mut outcomes = if self.trace_survivors {
+            self.trace_surviving_mutants(module, locations, outcomes)?
+        } else {
+            outcomes
+        };
+        outcomes.extend(cached);
+
+        for outcome in outcomes.iter_mut() {
+            outcome.condition = coverage.comparison_profile(outcome.offset);
+            outcome.likely_equivalent = outcome.survived
+                && coverage.is_likely_equivalent(outcome.offset, &*outcome.mutation_operator);
+        }
+
+        if self.explain_skipped {
+            coverage.explain_skipped(module, &mut outcomes);
+        }
+
+        let internal_errors = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome.result, ExecutionResult::InternalError { .. }))
+            .count();
+        if internal_errors > 0 {
+            warnings::warn(
+                WarningCategory::InternalError,
+                format!(
+                    "{internal_errors} mutants could not be executed because of internal errors, \
+                    they are reported as errors"
+                ),
+            );
+        }
+
+        let too_expensive = outcomes
+            .iter()
+            .filter(|outcome| outcome.skip_reason == Some(SkipReason::TooExpensive))
+            .count();
+        if too_expensive > 0 {
+            log::info!("Did not run {too_expensive} mutants of expensive functions");
+        }
+
+        let not_run = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome.result, ExecutionResult::NotRun))
+            .count()
+            - too_expensive;
+        if not_run > 0 {
+            let reason = if self.interrupted() {
+                "the run was interrupted"
+            } else {
+                "the time budget of the run was exhausted"
+            };
+            warnings::warn(
+                WarningCategory::RunStopped,
+                format!(
+                    "{not_run} mutants were not run because {reason}, \
+                    they are excluded from the mutation score"
+                ),
+            );
+        }
+
+        if let Some(message) = self.timeout_warning(&outcomes) {
+            warnings::warn(WarningCategory::ManyTimeouts, message);
+        }
+
         if self.coverage {
             let skipped = count_skipped_mutants(&outcomes);
 
@@ -88,6 +728,96 @@ impl<'a> Executor<'a> {
         Ok(outcomes)
     }
 
+    /// Everything besides the mutated function that the outcomes of cached mutants
+    /// depend on: the wasmut version, the timeout, mapped directories and their content,
+    /// the runtime and the kill criterion with the output it expects
+    fn result_cache_inputs(
+        &self,
+        criterion: &dyn KillCriterion,
+        baseline: Option<&Baseline>,
+    ) -> Result<String> {
+        let mut expected: Vec<&str> = self
+            .mapped_dirs
+            .iter()
+            .map(|(host, _)| host.as_str())
+            .collect();
+        if self.kill_criterion.is_none()
+            && self
+                .kill_criteria
+                .criteria()
+                .contains(&CriterionKind::GoldenFile)
+        {
+            expected.extend(self.kill_criteria.golden_file());
+        }
+
+        Ok(format!(
+            "{} {} {:?} {} {:?} {} {} {:?}",
+            env!("CARGO_PKG_VERSION"),
+            self.timeout_multiplier,
+            self.mapped_dirs,
+            resultcache::content_hash(&expected)?,
+            self.runtime,
+            criterion.name(),
+            serde_json::to_string(self.kill_criteria)?,
+            baseline.map(|baseline| md5::compute(&baseline.stdout))
+        ))
+    }
+
+    /// Suggestion to raise the timeout multiplier, if more than the configured
+    /// percentage of the executed mutants timed out. Includes the execution costs
+    /// of the other mutants, showing how close they came to the timeout.
+    fn timeout_warning(&self, outcomes: &[ExecutedMutant]) -> Option<String> {
+        let executed = outcomes
+            .iter()
+            .filter(|outcome| {
+                !matches!(
+                    outcome.result,
+                    ExecutionResult::Skipped | ExecutionResult::NotRun
+                )
+            })
+            .count();
+        let timeouts = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome.result, ExecutionResult::Timeout))
+            .count();
+        if timeouts == 0 {
+            return None;
+        }
+
+        let percentage = timeouts as f64 / executed as f64 * 100.0;
+        if percentage <= self.timeout_warning_threshold {
+            return None;
+        }
+
+        let mut message = format!(
+            "{timeouts} of {executed} executed mutants ({percentage:.1}%) timed out, \
+            consider raising timeout_multiplier (currently {})",
+            self.timeout_multiplier
+        );
+
+        let mut costs: Vec<u64> = outcomes
+            .iter()
+            .filter_map(|outcome| match outcome.result {
+                ExecutionResult::ProcessExit { execution_cost, .. } => Some(execution_cost),
+                _ => None,
+            })
+            .collect();
+        costs.sort_unstable();
+
+        if let Some(maximum) = costs.last() {
+            let formatter = self.cost_formatter();
+            let percentile = |p: f64| costs[((costs.len() - 1) as f64 * p).round() as usize];
+            message.push_str(&format!(
+                ". Execution cost of the other mutants: median {}, 90th percentile {}, maximum {}",
+                formatter.format(percentile(0.5)),
+                formatter.format(percentile(0.9)),
+                formatter.format(*maximum),
+            ));
+        }
+
+        Some(message)
+    }
+
     /// Execute mutants and gather results
     ///
     /// During execution, stdout and stderr are supressed
@@ -95,21 +825,52 @@ impl<'a> Executor<'a> {
         &self,
         module: &WasmModule,
         locations: &[MutationLocation],
-        trace_points: TracePoints,
+        coverage: &Coverage,
+        judge: &Judge,
     ) -> Result<Vec<ExecutedMutant>> {
-        let mut runtime = WasmerRuntime::new(module, true, self.mapped_dirs)?;
+        // Every mutant has to be compiled on its own, so
+        // compilation is part of the cost of a single mutant
+        let start = Instant::now();
+        let mut runtime = self.create_runtime(module, true)?;
         log::info!(
             "Using the {} compiler for code generation",
             runtime.compiler()
         );
 
-        let execution_cost = self.calculate_execution_cost(&mut runtime)?;
+        let (execution_cost, time_per_mutant) = match self.baseline {
+            Some(baseline) => {
+                let duration = Duration::from_millis(baseline.duration);
+                self.calibrate(baseline.execution_cost, duration);
+                (baseline.execution_cost, start.elapsed() + duration)
+            }
+            None => {
+                let execution_cost = self
+                    .calculate_execution_cost(runtime.as_mut())
+                    .context(ErrorKind::Baseline)?;
+                (execution_cost, start.elapsed())
+            }
+        };
 
-        log::info!("Original module executed in {execution_cost} cycles");
-        let limit = (execution_cost as f64 * self.timeout_multiplier).ceil() as u64;
-        log::info!("Setting timeout to {limit} cycles");
+        let costs = self.cost_formatter();
+        log::info!(
+            "Original module executed in {}",
+            costs.format(execution_cost)
+        );
+        let limit = self.timeout(execution_cost);
+        log::info!("Setting timeout to {}", costs.format(limit));
+
+        let to_execute = coverage.count_executed_mutants(locations);
+        self.confirm_run(RunEstimate {
+            mutants: to_execute,
+            time_per_mutant,
+            threads: rayon::current_num_threads(),
+            meta_mutant: false,
+            overhead: None,
+        })?;
 
         let pb = ProgressBar::new(locations.len() as u64);
+        let progress = self.progress(locations, to_execute, &pb);
+        progress.start_execution();
 
         let outcomes: Vec<ExecutedMutant> = locations
             .par_iter()
@@ -120,30 +881,36 @@ impl<'a> Executor<'a> {
                     .iter()
                     .enumerate()
                     .map(|(cnt, mutation)| {
-                        if self.coverage && !trace_points.is_covered(location.offset) {
-                            return ExecutedMutant {
-                                offset: location.offset,
-                                result: ExecutionResult::Skipped,
-                                mutation_operator: mutation.operator.clone(),
-                            };
+                        if !coverage.is_covered(location, mutation) {
+                            return ExecutedMutant::not_executed(
+                                location,
+                                mutation,
+                                ExecutionResult::Skipped,
+                            );
+                        }
+                        if coverage.expensive.skips(location) {
+                            return ExecutedMutant::too_expensive(location, mutation);
+                        }
+                        if self.should_stop() {
+                            return ExecutedMutant::not_executed(
+                                location,
+                                mutation,
+                                ExecutionResult::NotRun,
+                            );
                         }
 
-                        let module = module.clone_and_mutate(location, cnt);
-
-                        let mut runtime = WasmerRuntime::new(&module, true, self.mapped_dirs)
-                            .expect("Failed to create runtime");
-
-                        let policy = ExecutionPolicy::RunUntilLimit { limit };
-                        let result = runtime
-                            .call_test_function(policy)
-                            .expect("Failed to execute module after applying mutation");
+                        self.run_mutant(location, mutation, || {
+                            let module = module.clone_and_mutate(location, cnt);
+                            let mut runtime = self.create_runtime(&module, true)?;
 
-                        ExecutedMutant {
-                            offset: location.offset,
-                            result,
-                            mutation_operator: mutation.operator.clone(),
-                        }
+                            let limit = coverage.expensive.limit(location, execution_cost, limit);
+                            let policy = ExecutionPolicy::RunUntilLimit { limit };
+                            let result = runtime.call_test_function(policy)?;
+                            let verdict = judge.verdict(&result, runtime.as_ref());
+                            Ok((result, verdict))
+                        })
                     })
+                    .inspect(|outcome| progress.record(outcome))
                     .collect::<Vec<ExecutedMutant>>()
             })
             .collect();
@@ -157,26 +924,293 @@ impl<'a> Executor<'a> {
         &self,
         module: &WasmModule,
         locations: &[MutationLocation],
-        trace_points: TracePoints,
+        coverage: &Coverage,
+        judge: &Judge,
     ) -> Result<Vec<ExecutedMutant>> {
-        let meta_mutant = module.clone_and_mutate_all(locations)?;
-        let factory = WasmerRuntimeFactory::new(&meta_mutant, true, self.mapped_dirs)?;
+        let routed;
+        let groups: Vec<(RuntimeKind, &[MutationLocation])> = match self.runtime {
+            RuntimeKind::Hybrid => {
+                routed = self.route_locations(module, locations, coverage);
+                routed
+                    .iter()
+                    .map(|(runtime, locations)| (*runtime, locations.as_slice()))
+                    .collect()
+            }
+            runtime => vec![(runtime, locations)],
+        };
+
+        let chunks: Vec<(RuntimeKind, &[MutationLocation])> = groups
+            .iter()
+            .flat_map(|(runtime, locations)| {
+                chunk_locations(locations, self.meta_mutant_chunk_size)
+                    .into_iter()
+                    .map(|chunk| (*runtime, chunk))
+            })
+            .collect();
+
+        if chunks.len() > 1 {
+            log::info!(
+                "Splitting {} mutation locations into {} meta-mutants",
+                locations.len(),
+                chunks.len()
+            );
+        }
 
-        let mut runtime = factory.instantiate_mutant(0).unwrap();
+        let mut chunks = chunks.into_iter();
+
+        // There is always at least one chunk, even if there are no locations
+        let Some(first_chunk) = chunks.next() else {
+            bail!(
+                "No meta-mutant was created for {} mutation locations",
+                locations.len()
+            );
+        };
+        let original_size = module.size()?;
+        let meta_mutant = self.prepare_meta_mutant(module, original_size, first_chunk)?;
+        let mut compiler = meta_mutant.compiler;
 
         log::info!(
             "Using the {} compiler for code generation",
-            runtime.compiler()
+            meta_mutant.compiler
+        );
+        log::info!(
+            "Original module executed in {}",
+            self.cost_formatter().format(meta_mutant.execution_cost)
+        );
+        let overhead = self.measure_instrumentation_overhead(
+            module,
+            first_chunk.0,
+            meta_mutant.execution_cost,
+        )?;
+        log::info!(
+            "Setting timeout to {}",
+            self.cost_formatter().format(meta_mutant.limit)
         );
-        let execution_cost = self.calculate_execution_cost(&mut runtime)?;
 
-        log::info!("Original module executed in {execution_cost} cycles");
-        let limit = (execution_cost as f64 * self.timeout_multiplier).ceil() as u64;
-        log::info!("Setting timeout to {limit} cycles");
+        let to_execute = coverage.count_executed_mutants(locations);
+        self.confirm_run(RunEstimate {
+            mutants: to_execute,
+            time_per_mutant: meta_mutant.time_per_mutant,
+            threads: rayon::current_num_threads(),
+            meta_mutant: true,
+            overhead: Some(overhead),
+        })?;
 
         let pb = ProgressBar::new(locations.len() as u64);
+        let progress = self.progress(locations, to_execute, &pb);
 
-        let outcomes: Vec<ExecutedMutant> = locations
+        let mut outcomes = Vec::with_capacity(locations.len());
+        let chunks: Vec<(RuntimeKind, &[MutationLocation])> = chunks.collect();
+        let depth = prefetch_depth(rayon::current_num_threads());
+        let mut executed_chunks = 0;
+
+        std::thread::scope(|scope| -> Result<()> {
+            // The next meta-mutants are compiled (each in its own store) while the
+            // mutants of the current one are executed, so that compilation time is
+            // hidden behind execution time. The queue is bounded, so that at most
+            // `depth` compiled meta-mutants wait for their execution.
+            let (sender, receiver) = std::sync::mpsc::sync_channel(depth);
+            let chunks = &chunks;
+            scope.spawn(move || {
+                for chunk in chunks {
+                    // Once the run is stopped, no further meta-mutants are prepared
+                    if self.should_stop() {
+                        break;
+                    }
+                    let prepared = self.prepare_meta_mutant(module, original_size, *chunk);
+                    let failed = prepared.is_err();
+                    if sender
+                        .send(prepared.map(|meta_mutant| (*chunk, meta_mutant)))
+                        .is_err()
+                        || failed
+                    {
+                        break;
+                    }
+                }
+            });
+
+            let mut current = (first_chunk, meta_mutant);
+            loop {
+                let (chunk, meta_mutant) = current;
+
+                progress.start_execution();
+                outcomes.extend(self.execute_meta_mutant(
+                    &meta_mutant,
+                    chunk.1,
+                    coverage,
+                    judge,
+                    (&pb, &progress),
+                ));
+                progress.finish_execution();
+                drop(meta_mutant);
+
+                // Time for which the compilation of the next meta-mutant
+                // was not hidden behind the execution of the current one
+                let executed = Instant::now();
+                let next = match receiver.recv() {
+                    Ok(next) => next?,
+                    Err(_) => break,
+                };
+                executed_chunks += 1;
+                progress.record_compile_wait(executed.elapsed(), chunks.len() - executed_chunks);
+
+                if next.1.compiler != compiler {
+                    // Execution costs of different runtimes are not comparable
+                    compiler = next.1.compiler;
+                    log::info!(
+                        "Using the {} compiler for the next meta-mutant, \
+                        setting timeout to {}",
+                        compiler,
+                        self.cost_formatter().format(next.1.limit)
+                    );
+                } else {
+                    log::debug!(
+                        "Setting timeout to {} for next meta-mutant",
+                        self.cost_formatter().format(next.1.limit)
+                    );
+                }
+                current = next;
+            }
+            Ok(())
+        })?;
+
+        for (_, chunk) in &chunks[executed_chunks..] {
+            outcomes.extend(not_run_mutants(chunk));
+            pb.inc(chunk.len() as u64);
+        }
+
+        pb.finish_and_clear();
+
+        Ok(outcomes)
+    }
+
+    /// Progress of a run executing the mutants in `locations`, of which
+    /// `to_execute` are not skipped, showing the remaining time next to `pb`
+    fn progress(
+        &self,
+        locations: &[MutationLocation],
+        to_execute: usize,
+        pb: &ProgressBar,
+    ) -> Progress {
+        let total = locations
+            .iter()
+            .map(|location| location.mutations.len())
+            .sum();
+        Progress::new(
+            total,
+            to_execute,
+            self.summary_interval,
+            self.summary_mutants,
+        )
+        .with_bar(pb.clone())
+    }
+
+    /// Measure the execution cost of the uninstrumented module and return
+    /// how much the checks of the meta-mutant increase the cost.
+    fn measure_instrumentation_overhead(
+        &self,
+        module: &WasmModule,
+        runtime: RuntimeKind,
+        meta_cost: u64,
+    ) -> Result<f64> {
+        // Recorded baselines of hybrid runs are executed by wasmer
+        let baseline_runtime = match self.runtime {
+            RuntimeKind::Hybrid => RuntimeKind::Wasmer,
+            runtime => runtime,
+        };
+        let original_cost = match self.baseline {
+            Some(baseline) if runtime == baseline_runtime => baseline.execution_cost,
+            _ => {
+                let mut runtime = create_runtime(runtime, module, true, self.mapped_dirs)?;
+                self.calculate_execution_cost(runtime.as_mut())
+                    .context(ErrorKind::Baseline)?
+            }
+        };
+
+        log::info!(
+            "Uninstrumented module executed in {}",
+            self.cost_formatter().format(original_cost)
+        );
+
+        Ok(instrumentation_overhead(original_cost, meta_cost))
+    }
+
+    /// Compile a meta-mutant containing all mutations in `locations`
+    /// for `runtime` and determine its timeout in a baseline run.
+    fn prepare_meta_mutant(
+        &self,
+        module: &WasmModule,
+        original_size: ModuleSize,
+        (runtime, locations): (RuntimeKind, &[MutationLocation]),
+    ) -> Result<MetaMutant<'a>> {
+        let meta_mutant = module.clone_and_mutate_all(locations, self.meta_mutant_activation)?;
+
+        let size = MetaMutantSize {
+            original: original_size,
+            meta_mutant: meta_mutant.size()?,
+        };
+        log::info!("Meta-mutant size: {size}");
+        if let Ok(mut sizes) = self.meta_mutant_sizes.lock() {
+            sizes.push(size);
+        }
+
+        let factory = RuntimeFactory::new(runtime, &meta_mutant, true, self.mapped_dirs)?;
+
+        // The meta-mutant is only compiled once, so a single mutant
+        // only costs instantiation and execution
+        let start = Instant::now();
+        let mut runtime = factory
+            .instantiate_mutant(0, None)
+            .context(ErrorKind::Baseline)?;
+
+        let execution_cost = self
+            .calculate_execution_cost(runtime.as_mut())
+            .context(ErrorKind::Baseline)?;
+        let time_per_mutant = start.elapsed();
+
+        let limit = self.timeout(execution_cost);
+
+        // With a function gate, the checks of a mutant only execute in its own
+        // function, so every mutated function gets a baseline run of its own
+        let function_costs = match self.meta_mutant_activation {
+            MutantActivation::HostCall => HashMap::new(),
+            MutantActivation::Global => locations
+                .iter()
+                .map(|location| location.function_number)
+                .collect::<BTreeSet<u64>>()
+                .into_par_iter()
+                .map(|function_number| {
+                    let mut runtime = factory
+                        .instantiate_mutant(0, Some(function_number))
+                        .context(ErrorKind::Baseline)?;
+                    let cost = self
+                        .calculate_execution_cost(runtime.as_mut())
+                        .context(ErrorKind::Baseline)?;
+                    Ok((function_number, cost))
+                })
+                .collect::<Result<HashMap<u64, u64>>>()?,
+        };
+
+        Ok(MetaMutant {
+            compiler: runtime.compiler(),
+            factory,
+            execution_cost,
+            limit,
+            function_costs,
+            time_per_mutant,
+        })
+    }
+
+    /// Execute all mutations in `locations`, which must be contained in `meta_mutant`
+    fn execute_meta_mutant(
+        &self,
+        meta_mutant: &MetaMutant,
+        locations: &[MutationLocation],
+        coverage: &Coverage,
+        judge: &Judge,
+        (pb, progress): (&ProgressBar, &Progress),
+    ) -> Vec<ExecutedMutant> {
+        locations
             .par_iter()
             .progress_with(pb.clone())
             .flat_map(|location| {
@@ -184,30 +1218,118 @@ impl<'a> Executor<'a> {
                     .mutations
                     .iter()
                     .map(|mutation| {
-                        if self.coverage && !trace_points.is_covered(location.offset) {
-                            return ExecutedMutant {
-                                offset: location.offset,
-                                result: ExecutionResult::Skipped,
-                                mutation_operator: mutation.operator.clone(),
-                            };
+                        if !coverage.is_covered(location, mutation) {
+                            return ExecutedMutant::not_executed(
+                                location,
+                                mutation,
+                                ExecutionResult::Skipped,
+                            );
                         }
-
-                        let policy = ExecutionPolicy::RunUntilLimit { limit };
-                        let mut runtime = factory
-                            .instantiate_mutant(mutation.id)
-                            .expect("Failed to create runtime");
-                        let result = runtime
-                            .call_test_function(policy)
-                            .expect("Failed to execute module after applying mutation");
-
-                        ExecutedMutant {
-                            offset: location.offset,
-                            result,
-                            mutation_operator: mutation.operator.clone(),
+                        if coverage.expensive.skips(location) {
+                            return ExecutedMutant::too_expensive(location, mutation);
                         }
+                        if self.should_stop() {
+                            return ExecutedMutant::not_executed(
+                                location,
+                                mutation,
+                                ExecutionResult::NotRun,
+                            );
+                        }
+
+                        self.run_mutant(location, mutation, || {
+                            let execution_cost = meta_mutant.execution_cost(location);
+                            let limit = coverage.expensive.limit(
+                                location,
+                                execution_cost,
+                                self.timeout(execution_cost),
+                            );
+                            let policy = ExecutionPolicy::RunUntilLimit { limit };
+                            let mut runtime = meta_mutant
+                                .factory
+                                .instantiate_mutant(mutation.id, Some(location.function_number))?;
+                            let result = runtime.call_test_function(policy)?;
+                            let verdict = judge.verdict(&result, runtime.as_ref());
+                            Ok((result, verdict))
+                        })
                     })
+                    .inspect(|outcome| progress.record(outcome))
                     .collect::<Vec<ExecutedMutant>>()
             })
+            .collect()
+    }
+
+    /// Execute `mutation` at `location` using `execute`, notifying the hooks
+    /// before and after the execution
+    fn run_mutant<F>(
+        &self,
+        location: &MutationLocation,
+        mutation: &Mutation,
+        execute: F,
+    ) -> ExecutedMutant
+    where
+        F: FnOnce() -> Result<(ExecutionResult, Verdict)>,
+    {
+        for hook in &self.hooks {
+            hook.before_mutant(location, mutation);
+        }
+
+        let start = Instant::now();
+        let (result, verdict) = guarded_execution(location.offset, execute);
+        let mutant = ExecutedMutant {
+            offset: location.offset,
+            survived: verdict.survived,
+            result,
+            mutation_operator: mutation.operator.clone(),
+            trace: None,
+            likely_equivalent: false,
+            condition: None,
+            skip_reason: None,
+            killed_by: verdict.killed_by,
+        };
+        let duration = start.elapsed();
+
+        for hook in &self.hooks {
+            hook.after_mutant(location, &mutant, duration);
+        }
+        mutant
+    }
+
+    /// Execute all surviving mutants again, recording their output
+    /// and the sequence of called functions.
+    fn trace_surviving_mutants(
+        &self,
+        module: &WasmModule,
+        locations: &[MutationLocation],
+        outcomes: Vec<ExecutedMutant>,
+    ) -> Result<Vec<ExecutedMutant>> {
+        let locations: HashMap<u64, &MutationLocation> = locations
+            .iter()
+            .map(|location| (location.offset, location))
+            .collect();
+
+        let survivors = outcomes.iter().filter(|outcome| outcome.survived).count();
+        log::info!("Tracing {survivors} surviving mutants");
+
+        let pb = ProgressBar::new(survivors as u64);
+
+        let outcomes = outcomes
+            .into_par_iter()
+            .map(|mut outcome| {
+                if outcome.survived && !self.should_stop() {
+                    match find_mutation(&locations, &outcome) {
+                        Some((location, cnt)) => match self.trace_mutant(module, location, cnt) {
+                            Ok(trace) => outcome.trace = Some(trace),
+                            Err(e) => log::warn!("Could not trace mutant: {e:?}"),
+                        },
+                        None => log::warn!(
+                            "Could not trace mutant at offset {:#x}: mutation not found",
+                            outcome.offset
+                        ),
+                    }
+                    pb.inc(1);
+                }
+                outcome
+            })
             .collect();
 
         pb.finish_and_clear();
@@ -215,8 +1337,144 @@ impl<'a> Executor<'a> {
         Ok(outcomes)
     }
 
-    fn calculate_execution_cost(&self, runtime: &mut WasmerRuntime) -> Result<u64> {
-        let execution_cost = match runtime.call_test_function(ExecutionPolicy::RunUntilReturn)? {
+    /// Execute a single mutant with call tracing and output capture
+    fn trace_mutant(
+        &self,
+        module: &WasmModule,
+        location: &MutationLocation,
+        mutation_index: usize,
+    ) -> Result<ExecutionTrace> {
+        let mut mutant = module.clone_and_mutate(location, mutation_index);
+        mutant.insert_call_trace_points()?;
+
+        let mut runtime = WasmerRuntime::new_traced(&mutant, self.mapped_dirs)?;
+        runtime.call_test_function(ExecutionPolicy::RunUntilReturn)?;
+        Ok(runtime.execution_trace())
+    }
+
+    /// Log the run estimate and ask for confirmation, if requested
+    fn confirm_run(&self, estimate: RunEstimate) -> Result<()> {
+        log::info!("{estimate}");
+
+        if let Some(confirmation) = &self.confirmation {
+            if !confirmation(&estimate) {
+                bail!(ErrorKind::Aborted);
+            }
+        }
+
+        if self.interruptible {
+            interrupt::install_handler();
+            log::info!("Press Ctrl-C to stop starting new mutants, twice to abort immediately");
+        }
+
+        Ok(())
+    }
+
+    /// Split `locations` for hybrid execution. Locations in functions that executed
+    /// at most `hybrid_threshold` instructions in the coverage run are interpreted,
+    /// all others are executed by a compiled meta-mutant. Empty groups are omitted,
+    /// but at least one group is returned.
+    ///
+    /// This is a heuristic: every mutant executes the whole program, so the mutants of a
+    /// cheap function in an expensive program are interpreted, although they execute as
+    /// many instructions as the compiled ones. It targets the mutants of hot functions,
+    /// which are the ones likely to run until the timeout.
+    fn route_locations(
+        &self,
+        module: &WasmModule,
+        locations: &[MutationLocation],
+        coverage: &Coverage,
+    ) -> Vec<(RuntimeKind, Vec<MutationLocation>)> {
+        let executed = module.executed_instructions(&coverage.trace_points);
+        let (interpreted, compiled) =
+            split_by_executed_instructions(locations, &executed, self.hybrid_threshold);
+
+        log::info!(
+            "Hybrid execution: interpreting {} and compiling {} mutation locations \
+            (threshold: {} executed instructions per function)",
+            interpreted.len(),
+            compiled.len(),
+            self.hybrid_threshold
+        );
+
+        let mut groups = Vec::new();
+        if !interpreted.is_empty() {
+            groups.push((RuntimeKind::Wasmi, interpreted));
+        }
+        if !compiled.is_empty() || groups.is_empty() {
+            groups.push((RuntimeKind::Wasmer, compiled));
+        }
+        groups
+    }
+
+    /// Functions that executed more than `expensive_threshold` percent of the
+    /// instructions in the coverage run. Expensive functions are reported in a warning.
+    fn expensive_functions(
+        &self,
+        module: &WasmModule,
+        trace_points: &TracePoints,
+    ) -> ExpensiveFunctions {
+        let threshold = match self.expensive_threshold {
+            Some(threshold) => threshold,
+            None => return ExpensiveFunctions::default(),
+        };
+
+        let executed = module.executed_instructions(trace_points);
+        let expensive = find_expensive_functions(&executed, threshold);
+        if expensive.is_empty() {
+            return ExpensiveFunctions::default();
+        }
+
+        let names = module.function_offsets();
+        let functions: Vec<String> = expensive
+            .iter()
+            .map(|(number, share)| {
+                let name = names
+                    .get(*number as usize)
+                    .map(|(_, name)| name.clone())
+                    .unwrap_or_else(|| format!("function #{number}"));
+                format!("{name} ({share:.1}%)")
+            })
+            .collect();
+        let consequence = match self.expensive_action {
+            ExpensiveFunctionAction::Report => "consider excluding them".into(),
+            ExpensiveFunctionAction::Skip => "their mutants are not run".into(),
+            ExpensiveFunctionAction::ShortenTimeout => format!(
+                "their mutants are executed with timeout multiplier {}",
+                self.expensive_timeout_multiplier
+            ),
+        };
+        warnings::warn(
+            WarningCategory::ExpensiveFunctions,
+            format!(
+                "Functions executing more than {threshold}% of the instructions \
+                of the baseline run: {}, {consequence}",
+                functions.join(", ")
+            ),
+        );
+
+        ExpensiveFunctions {
+            functions: expensive.into_iter().map(|(number, _)| number).collect(),
+            action: self.expensive_action,
+            timeout_multiplier: self.expensive_timeout_multiplier,
+        }
+    }
+
+    /// Create a runtime of the configured kind for `module`
+    fn create_runtime(
+        &self,
+        module: &WasmModule,
+        discard_output: bool,
+    ) -> Result<Box<dyn Runtime>> {
+        create_runtime(self.runtime, module, discard_output, self.mapped_dirs)
+    }
+
+    fn calculate_execution_cost(&self, runtime: &mut dyn Runtime) -> Result<u64> {
+        let start = Instant::now();
+        let result = runtime.call_test_function(ExecutionPolicy::RunUntilReturn)?;
+        let elapsed = start.elapsed();
+
+        let execution_cost = match result {
             ExecutionResult::ProcessExit {
                 exit_code,
                 execution_cost,
@@ -228,34 +1486,479 @@ impl<'a> Executor<'a> {
                 }
             }
             ExecutionResult::Timeout => {
-                panic!("Execution limit exceeded even though we set no limit!")
+                return Err(anyhow!(
+                    "Execution limit exceeded even though no limit was set"
+                ))
+                .context(ErrorKind::Runtime);
+            }
+            ExecutionResult::Error { .. } => bail!("Module failed to execute"),
+            other @ (ExecutionResult::Skipped
+            | ExecutionResult::NotRun
+            | ExecutionResult::InternalError { .. }) => {
+                return Err(anyhow!("Runtime returned {other:?}")).context(ErrorKind::Runtime);
+            }
+        };
+        self.calibrate(execution_cost, elapsed);
+
+        Ok(execution_cost)
+    }
+
+    /// Execute the module, or a mutant of it, recording the order in which
+    /// basic blocks are executed. Returns the result of the execution and the trace.
+    pub fn trace_blocks(
+        &self,
+        module: &WasmModule,
+        mutation: Option<(&MutationLocation, usize)>,
+        policy: ExecutionPolicy,
+    ) -> Result<(ExecutionResult, TracePoints)> {
+        let traced = module.clone_with_block_trace_points(mutation)?;
+        let mut runtime = WasmerRuntime::new_with_ordered_trace(&traced, self.mapped_dirs)?;
+        let result = runtime.call_test_function(policy)?;
+        Ok((result, runtime.trace_points()))
+    }
+
+    /// Timeout in cycles for mutants of a module that executed in `execution_cost` cycles
+    pub fn timeout(&self, execution_cost: u64) -> u64 {
+        (execution_cost as f64 * self.timeout_multiplier).ceil() as u64
+    }
+
+    /// Execute the unmutated module with tracing enabled and
+    /// return how often every instruction was executed.
+    pub fn get_trace_points(&self, module: &WasmModule) -> Result<TracePoints> {
+        let mut module = module.clone();
+        module.insert_trace_points()?;
+        let mut runtime = self.create_runtime(&module, true)?;
+
+        let trace_points = match runtime.call_test_function(ExecutionPolicy::RunUntilReturn)? {
+            ExecutionResult::ProcessExit { exit_code, .. } => {
+                if exit_code != 0 {
+                    bail!("Module without any mutations returned exit code {exit_code}");
+                }
+                runtime.trace_points()
+            }
+            ExecutionResult::Timeout => {
+                return Err(anyhow!(
+                    "Execution limit exceeded even though no limit was set"
+                ))
+                .context(ErrorKind::Runtime);
+            }
+            ExecutionResult::Error { .. } => bail!("Module failed to execute"),
+            other @ (ExecutionResult::Skipped
+            | ExecutionResult::NotRun
+            | ExecutionResult::InternalError { .. }) => {
+                return Err(anyhow!("Runtime returned {other:?}")).context(ErrorKind::Runtime);
+            }
+        };
+        Ok(trace_points)
+    }
+}
+
+/// Coverage of the unmutated module, decides which mutants are skipped
+struct Coverage {
+    /// If not set, no mutant is skipped
+    enabled: bool,
+
+    trace_points: TracePoints,
+
+    /// Offsets of the conditional branches consuming the result of a
+    /// mutated instruction, by offset of the mutation location
+    branches: HashMap<u64, u64>,
+
+    /// Functions exceeding `expensive_function_threshold` in the coverage run
+    expensive: ExpensiveFunctions,
+}
+
+impl Coverage {
+    /// Return false if the mutant cannot change the behaviour of the tests,
+    /// because the mutated instruction was not executed by the unmutated module
+    /// or, for boundary mutations of conditions, because the branch edge on
+    /// which the mutant differs was never taken.
+    fn is_covered(&self, location: &MutationLocation, mutation: &Mutation) -> bool {
+        if !self.enabled {
+            return true;
+        }
+
+        if !self.trace_points.is_covered(location.offset) {
+            return false;
+        }
+
+        let branch = self
+            .branches
+            .get(&location.offset)
+            .and_then(|offset| self.trace_points.branch(*offset));
+
+        match (mutation.operator.changed_condition(), branch) {
+            (Some(condition), Some(branch)) => branch.has_taken(condition),
+            // Without branch coverage, fall back to statement coverage
+            _ => true,
+        }
+    }
+
+    /// Comparison profile of the mutated instruction, if it is a comparison
+    fn comparison_profile(&self, offset: u64) -> Option<ComparisonProfile> {
+        if !self.enabled {
+            return None;
+        }
+        self.trace_points.comparison(offset)
+    }
+
+    /// Return true if the comparison profile of the mutated instruction shows that
+    /// the mutant cannot change its result, e.g. if a boundary mutation of a
+    /// relational operator (`<` to `<=`) was applied, but both operands were never equal
+    fn is_likely_equivalent(&self, offset: u64, operator: &dyn InstructionReplacement) -> bool {
+        if operator.changed_condition().is_none() {
+            return false;
+        }
+
+        match self.comparison_profile(offset) {
+            Some(profile) => !profile.equal_operands,
+            None => false,
+        }
+    }
+
+    /// Record why the skipped mutants in `outcomes` were skipped
+    fn explain_skipped(&self, module: &WasmModule, outcomes: &mut [ExecutedMutant]) {
+        let covered = self.trace_points.covered_offsets();
+        let functions: Vec<u64> = module
+            .function_offsets()
+            .into_iter()
+            .map(|(offset, _)| offset)
+            .collect();
+
+        for outcome in outcomes
+            .iter_mut()
+            .filter(|outcome| matches!(outcome.result, ExecutionResult::Skipped))
+        {
+            outcome.skip_reason = Some(if self.trace_points.is_covered(outcome.offset) {
+                SkipReason::EdgeNotTaken
+            } else {
+                SkipReason::NotExecuted {
+                    nearest_covered: nearest_covered(outcome.offset, &covered, &functions),
+                }
+            });
+        }
+    }
+
+    /// Count the mutants that will be executed, i.e. those that are not skipped
+    /// because of missing coverage or because their function is too expensive
+    fn count_executed_mutants(&self, locations: &[MutationLocation]) -> usize {
+        locations
+            .iter()
+            .filter(|location| !self.expensive.skips(location))
+            .flat_map(|location| {
+                location
+                    .mutations
+                    .iter()
+                    .filter(move |mutation| self.is_covered(location, mutation))
+            })
+            .count()
+    }
+
+    /// Split `locations` into the mutations that have to be executed and the cached
+    /// outcomes of the others. Only mutants that would be executed with the regular
+    /// timeout are taken from the cache.
+    fn split_cached(
+        &self,
+        result_cache: &ResultCache,
+        locations: &[MutationLocation],
+    ) -> (Vec<MutationLocation>, Vec<ExecutedMutant>) {
+        let mut uncached = Vec::with_capacity(locations.len());
+        let mut cached = Vec::new();
+
+        for location in locations {
+            let mut mutations = Vec::new();
+            for mutation in &location.mutations {
+                let outcome =
+                    if self.is_covered(location, mutation) && !self.expensive.affects(location) {
+                        result_cache.get(location, mutation)
+                    } else {
+                        None
+                    };
+                match outcome {
+                    Some(outcome) => cached.push(outcome),
+                    None => mutations.push(mutation.clone()),
+                }
+            }
+
+            if !mutations.is_empty() {
+                uncached.push(MutationLocation {
+                    mutations,
+                    ..location.clone()
+                });
             }
-            ExecutionResult::Error => bail!("Module failed to execute"),
-            ExecutionResult::Skipped => panic!("Runtime returned ExecutionResult::Skipped"),
+        }
+
+        (uncached, cached)
+    }
+}
+
+/// Functions exceeding `expensive_function_threshold`, and how their mutants are executed
+#[derive(Default)]
+struct ExpensiveFunctions {
+    /// Numbers of the expensive functions
+    functions: HashSet<u64>,
+
+    action: ExpensiveFunctionAction,
+
+    /// Timeout multiplier for mutants of expensive functions,
+    /// if `action` is `ShortenTimeout`
+    timeout_multiplier: f64,
+}
+
+impl ExpensiveFunctions {
+    /// Return true if the mutants at `location` are not run or executed with a shorter timeout
+    fn affects(&self, location: &MutationLocation) -> bool {
+        self.action != ExpensiveFunctionAction::Report
+            && self.functions.contains(&location.function_number)
+    }
+
+    /// Return true if the mutants at `location` are not run
+    fn skips(&self, location: &MutationLocation) -> bool {
+        self.action == ExpensiveFunctionAction::Skip
+            && self.functions.contains(&location.function_number)
+    }
+
+    /// Timeout in cycles for mutants at `location`, if the mutated module executed in
+    /// `execution_cost` cycles and `limit` is the timeout of all other mutants
+    fn limit(&self, location: &MutationLocation, execution_cost: u64, limit: u64) -> u64 {
+        if self.action == ExpensiveFunctionAction::ShortenTimeout
+            && self.functions.contains(&location.function_number)
+        {
+            let shortened = (execution_cost as f64 * self.timeout_multiplier).ceil() as u64;
+            shortened.min(limit)
+        } else {
+            limit
+        }
+    }
+}
+
+/// Decides whether executed mutants survived, using the kill criterion
+struct Judge<'j> {
+    criterion: &'j dyn KillCriterion,
+
+    /// Baseline run, if the criterion needs one or a recorded baseline is used
+    baseline: Option<&'j Baseline>,
+}
+
+/// Decision of the kill criterion about an executed mutant
+#[derive(Default)]
+struct Verdict {
+    /// True if the mutant terminated normally and was not killed
+    survived: bool,
+    /// Names of the criteria that killed the mutant
+    killed_by: Vec<String>,
+}
+
+impl Judge<'_> {
+    /// Decide whether a mutant that was executed by `runtime` survived
+    fn verdict(&self, result: &ExecutionResult, runtime: &dyn Runtime) -> Verdict {
+        if !matches!(result, ExecutionResult::ProcessExit { .. }) {
+            return Verdict::default();
+        }
+
+        let output = if self.criterion.needs_output() {
+            runtime.execution_trace()
+        } else {
+            ExecutionTrace::default()
         };
 
-        Ok(execution_cost)
+        let killed_by = self.criterion.killed_by(&MutantRun {
+            result,
+            output: &output,
+            baseline: self.baseline,
+        });
+        Verdict {
+            survived: killed_by.is_empty(),
+            killed_by,
+        }
+    }
+}
+
+/// Execute a single mutant with `execute`, returning its result and the verdict
+/// about it, e.g. whether it survived.
+/// Errors and panics of wasmut or the runtime only affect this mutant,
+/// they are reported as `ExecutionResult::InternalError` with a default verdict.
+fn guarded_execution<T, F>(offset: u64, execute: F) -> (ExecutionResult, T)
+where
+    T: Default,
+    F: FnOnce() -> Result<(ExecutionResult, T)>,
+{
+    let message = match std::panic::catch_unwind(AssertUnwindSafe(execute)) {
+        Ok(Ok(outcome)) => return outcome,
+        Ok(Err(e)) => format!("{e:#}"),
+        Err(payload) => panic_message(payload.as_ref()),
+    };
+
+    log::debug!("Internal error while executing mutant at offset {offset:#x}: {message}");
+    (ExecutionResult::InternalError { message }, T::default())
+}
+
+/// Message of a caught panic
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".into()
+    }
+}
+
+/// A compiled meta-mutant, ready for execution
+struct MetaMutant<'a> {
+    factory: RuntimeFactory<'a>,
+    compiler: Compiler,
+
+    /// Cycles needed to execute the meta-mutant with all mutations disabled
+    execution_cost: u64,
+
+    /// Timeout in cycles for mutants of this meta-mutant
+    limit: u64,
+
+    /// Cycles needed to execute the meta-mutant with the function gate set to
+    /// each mutated function. Empty if mutants are not gated by function.
+    function_costs: HashMap<u64, u64>,
+
+    /// Time needed to instantiate and execute a single mutant
+    time_per_mutant: Duration,
+}
+
+impl MetaMutant<'_> {
+    /// Cycles needed to execute the meta-mutant with all mutations at `location` disabled
+    fn execution_cost(&self, location: &MutationLocation) -> u64 {
+        self.function_costs
+            .get(&location.function_number)
+            .copied()
+            .unwrap_or(self.execution_cost)
+    }
+}
+
+/// Split `locations` into those in functions that executed at most `threshold`
+/// instructions and all others. `executed` is indexed by function number.
+fn split_by_executed_instructions(
+    locations: &[MutationLocation],
+    executed: &[u64],
+    threshold: u64,
+) -> (Vec<MutationLocation>, Vec<MutationLocation>) {
+    locations.iter().cloned().partition(|location| {
+        executed
+            .get(location.function_number as usize)
+            .copied()
+            .unwrap_or(0)
+            <= threshold
+    })
+}
+
+/// Numbers of the functions that executed more than `threshold` percent of all
+/// instructions, with their percentage. `executed` is indexed by function number.
+fn find_expensive_functions(executed: &[u64], threshold: f64) -> Vec<(u64, f64)> {
+    let total: u64 = executed.iter().sum();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    executed
+        .iter()
+        .enumerate()
+        .map(|(number, count)| (number as u64, 100.0 * *count as f64 / total as f64))
+        .filter(|(_, share)| *share > threshold)
+        .collect()
+}
+
+/// Ratio between the execution cost of the meta-mutant and the
+/// execution cost of the uninstrumented module
+fn instrumentation_overhead(original_cost: u64, meta_cost: u64) -> f64 {
+    if original_cost == 0 {
+        1.0
+    } else {
+        meta_cost as f64 / original_cost as f64
+    }
+}
+
+/// Split `locations` into chunks containing at most `max_mutations` mutations.
+///
+/// Locations are never split, so a chunk may exceed the limit if a single
+/// location has more mutations. At least one (possibly empty) chunk is returned.
+fn chunk_locations(
+    locations: &[MutationLocation],
+    max_mutations: usize,
+) -> Vec<&[MutationLocation]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut mutations = 0;
+
+    for (index, location) in locations.iter().enumerate() {
+        if mutations > 0 && mutations + location.mutations.len() > max_mutations {
+            chunks.push(&locations[start..index]);
+            start = index;
+            mutations = 0;
+        }
+        mutations += location.mutations.len();
     }
 
-    fn get_trace_points(&self, module: &WasmModule) -> Result<TracePoints> {
-        let mut module = module.clone();
-        module.insert_trace_points()?;
-        let mut runtime = WasmerRuntime::new(&module, true, self.mapped_dirs)?;
+    if start < locations.len() || chunks.is_empty() {
+        chunks.push(&locations[start..]);
+    }
 
-        let trace_points = match runtime.call_test_function(ExecutionPolicy::RunUntilReturn)? {
-            ExecutionResult::ProcessExit { exit_code, .. } => {
-                if exit_code != 0 {
-                    bail!("Module without any mutations returned exit code {exit_code}");
-                }
-                runtime.trace_points()
-            }
-            ExecutionResult::Timeout => {
-                panic!("Execution limit exceeded even though we set no limit!")
-            }
-            ExecutionResult::Error => bail!("Module failed to execute"),
-            ExecutionResult::Skipped => panic!("Runtime returned ExecutionResult::Skipped"),
-        };
-        Ok(trace_points)
+    chunks
+}
+
+/// Number of compiled meta-mutants that may wait for their execution, with
+/// `workers` threads executing mutants. Every meta-mutant keeps its compiled
+/// module in memory, so only one is prefetched for every 8 workers, at most 4.
+fn prefetch_depth(workers: usize) -> usize {
+    (workers / 8).clamp(1, 4)
+}
+
+/// Results of all mutations in `locations`, reported as not run
+pub fn not_run_mutants(locations: &[MutationLocation]) -> Vec<ExecutedMutant> {
+    locations
+        .iter()
+        .flat_map(|location| {
+            location.mutations.iter().map(move |mutation| {
+                ExecutedMutant::not_executed(location, mutation, ExecutionResult::NotRun)
+            })
+        })
+        .collect()
+}
+
+/// Location and index of the mutation that produced `outcome`, by offset and operator
+fn find_mutation<'l>(
+    locations: &HashMap<u64, &'l MutationLocation>,
+    outcome: &ExecutedMutant,
+) -> Option<(&'l MutationLocation, usize)> {
+    let location = *locations.get(&outcome.offset)?;
+    let index = location.mutations.iter().position(|mutation| {
+        mutation.operator.dyn_name() == outcome.mutation_operator.dyn_name()
+            && mutation.operator.description() == outcome.mutation_operator.description()
+    })?;
+    Some((location, index))
+}
+
+/// A mutant survived, if the tests passed
+pub fn is_alive(result: &ExecutionResult) -> bool {
+    matches!(result, ExecutionResult::ProcessExit { exit_code: 0, .. })
+}
+
+/// Offset of the executed instruction closest to `offset`, within the function
+/// containing `offset`. `covered` are the sorted offsets of all executed instructions,
+/// `functions` the sorted start offsets of all functions.
+fn nearest_covered(offset: u64, covered: &[u64], functions: &[u64]) -> Option<u64> {
+    let function = functions.partition_point(|start| *start <= offset);
+    let start = functions[function.checked_sub(1)?];
+    let end = functions.get(function).copied().unwrap_or(u64::MAX);
+
+    let position = covered.partition_point(|covered| *covered < offset);
+    let before = position
+        .checked_sub(1)
+        .map(|index| covered[index])
+        .filter(|before| *before >= start);
+    let after = covered.get(position).copied().filter(|after| *after < end);
+
+    match (before, after) {
+        (Some(before), Some(after)) if after - offset < offset - before => Some(after),
+        (Some(before), _) => Some(before),
+        (None, after) => after,
     }
 }
 
@@ -276,7 +1979,7 @@ mod tests {
     use wasmut_wasm::elements::Instruction;
 
     use crate::{
-        mutation::Mutation,
+        mutation::{Mutation, MutationEngine},
         operator::ops::{
             BinaryOperatorAddToSub, ConstReplaceNonZero, RelationalOperatorLtToGe,
             RelationalOperatorLtToLe,
@@ -382,6 +2085,778 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn mutants_are_not_run_after_deadline() -> Result<()> {
+        let module = crate::fixtures::start_function(vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::I32Add,
+            Instruction::Drop,
+        ])?;
+
+        for meta_mutant in [false, true] {
+            let config = Config::parse(&format!(
+                r#"
+                [engine]
+                runtime = "wasmi"
+                coverage_based_execution = false
+                meta_mutant = {meta_mutant}
+            "#
+            ))?;
+            let locations = MutationEngine::new(&config, 100)?
+                .discover_mutation_positions(&module)?
+                .locations;
+            assert!(!locations.is_empty());
+
+            let executor = Executor::new(&config).with_deadline(Instant::now());
+            let outcomes = executor.execute_mutants(&module, &locations)?;
+
+            assert!(!outcomes.is_empty());
+            assert!(outcomes
+                .iter()
+                .all(|outcome| matches!(outcome.result, ExecutionResult::NotRun)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_are_called_for_executed_mutants() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct RecordingHook {
+            started: AtomicUsize,
+            finished: Mutex<Vec<(u64, MutationOutcome)>>,
+        }
+
+        impl ExecutionHook for &RecordingHook {
+            fn before_mutant(&self, _location: &MutationLocation, _mutation: &Mutation) {
+                self.started.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn after_mutant(
+                &self,
+                location: &MutationLocation,
+                mutant: &ExecutedMutant,
+                _duration: Duration,
+            ) {
+                assert_eq!(location.offset, mutant.offset);
+                self.finished
+                    .lock()
+                    .unwrap()
+                    .push((mutant.offset, mutant.outcome()));
+            }
+        }
+
+        let module = crate::fixtures::start_function(vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::I32Add,
+            Instruction::Drop,
+        ])?;
+
+        for meta_mutant in [false, true] {
+            let config = Config::parse(&format!(
+                r#"
+                [engine]
+                runtime = "wasmi"
+                coverage_based_execution = false
+                meta_mutant = {meta_mutant}
+            "#
+            ))?;
+            let locations = MutationEngine::new(&config, 100)?
+                .discover_mutation_positions(&module)?
+                .locations;
+
+            let hook = RecordingHook::default();
+            let outcomes = Executor::new(&config)
+                .with_hook(Box::new(&hook))
+                .execute_mutants(&module, &locations)?;
+
+            let mut finished = hook.finished.into_inner().unwrap();
+            finished.sort_by_key(|(offset, _)| *offset);
+            let mut expected: Vec<_> = outcomes
+                .iter()
+                .map(|mutant| (mutant.offset, mutant.outcome()))
+                .collect();
+            expected.sort_by_key(|(offset, _)| *offset);
+            assert_eq!(hook.started.into_inner(), outcomes.len());
+            assert_eq!(finished, expected);
+
+            // Mutants that are not run do not trigger the hooks
+            let hook = RecordingHook::default();
+            Executor::new(&config)
+                .with_hook(Box::new(&hook))
+                .with_deadline(Instant::now())
+                .execute_mutants(&module, &locations)?;
+            assert_eq!(hook.started.into_inner(), 0);
+            assert!(hook.finished.into_inner().unwrap().is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn custom_kill_criterion() -> Result<()> {
+        /// Kills every mutant that terminated normally
+        struct AlwaysKilled;
+
+        impl KillCriterion for AlwaysKilled {
+            fn name(&self) -> String {
+                "always".into()
+            }
+
+            fn is_killed(&self, run: &MutantRun) -> bool {
+                matches!(run.result, ExecutionResult::ProcessExit { .. })
+            }
+        }
+
+        let module = crate::fixtures::start_function(vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::I32Add,
+            Instruction::Drop,
+        ])?;
+
+        let config = Config::parse(
+            r#"
+            [engine]
+            runtime = "wasmi"
+            coverage_based_execution = false
+            timeout_multiplier = 10
+        "#,
+        )?;
+        let locations = MutationEngine::new(&config, 100)?
+            .discover_mutation_positions(&module)?
+            .locations;
+        assert!(!locations.is_empty());
+
+        // The result of the addition is dropped, so mutants that terminate
+        // normally do not change the exit code
+        let terminated = |outcomes: &[ExecutedMutant]| -> Vec<(MutationOutcome, Vec<String>)> {
+            outcomes
+                .iter()
+                .filter(|mutant| matches!(mutant.result, ExecutionResult::ProcessExit { .. }))
+                .map(|mutant| (mutant.outcome(), mutant.killed_by.clone()))
+                .collect()
+        };
+
+        let outcomes = Executor::new(&config).execute_mutants(&module, &locations)?;
+        let survived = terminated(&outcomes);
+        assert!(!survived.is_empty());
+        assert!(survived
+            .iter()
+            .all(|(outcome, _)| *outcome == MutationOutcome::Alive));
+
+        let outcomes = Executor::new(&config)
+            .with_kill_criterion(Box::new(AlwaysKilled))
+            .execute_mutants(&module, &locations)?;
+        let killed = terminated(&outcomes);
+        assert_eq!(killed.len(), survived.len());
+        assert!(killed.iter().all(|(outcome, killed_by)| {
+            *outcome == MutationOutcome::Killed && killed_by == &["always"]
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn condition_outcomes_are_recorded() -> Result<()> {
+        // 1 < 2 is always true, the result is dropped
+        let module = crate::fixtures::start_function(vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::I32LtS,
+            Instruction::Drop,
+        ])?;
+
+        let config = Config::parse(
+            r#"
+            [engine]
+            runtime = "wasmi"
+            timeout_multiplier = 10
+
+            [operators]
+            enabled_operators = ["relop_.*"]
+        "#,
+        )?;
+        let locations = MutationEngine::new(&config, 100)?
+            .discover_mutation_positions(&module)?
+            .locations;
+        assert!(!locations.is_empty());
+
+        let outcomes = Executor::new(&config).execute_mutants(&module, &locations)?;
+        assert!(!outcomes.is_empty());
+        for outcome in &outcomes {
+            let profile = outcome.condition.expect("condition was profiled");
+            assert_eq!(profile.outcome(), "always true");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unordered_comparisons_differ_for_nan() -> Result<()> {
+        use wasmut_wasm::elements::BlockType;
+
+        // Traps if NaN < 1.0 (ordered) or unless !(NaN >= 1.0) (unordered),
+        // the results of 1.0 < 2.0 and !(2.0 <= 1.0) are dropped
+        let module = crate::fixtures::start_function(vec![
+            Instruction::F32Const(f32::NAN.to_bits()),
+            Instruction::F32Const(1f32.to_bits()),
+            Instruction::F32Lt,
+            Instruction::If(BlockType::NoResult),
+            Instruction::Unreachable,
+            Instruction::End,
+            Instruction::F32Const(1f32.to_bits()),
+            Instruction::F32Const(2f32.to_bits()),
+            Instruction::F32Lt,
+            Instruction::Drop,
+            Instruction::Block(BlockType::NoResult),
+            Instruction::F64Const(f64::NAN.to_bits()),
+            Instruction::F64Const(1f64.to_bits()),
+            Instruction::F64Ge,
+            Instruction::I32Eqz,
+            Instruction::BrIf(0),
+            Instruction::Unreachable,
+            Instruction::End,
+            Instruction::F64Const(2f64.to_bits()),
+            Instruction::F64Const(1f64.to_bits()),
+            Instruction::F64Le,
+            Instruction::I32Eqz,
+            Instruction::Drop,
+        ])?;
+
+        // The checks activating mutations of the meta-mutant exceed
+        // the default timeout for such a short module
+        for meta_mutant in [false, true] {
+            let config = Config::parse(&format!(
+                r#"
+                [engine]
+                runtime = "wasmi"
+                coverage_based_execution = false
+                meta_mutant = {meta_mutant}
+                timeout_multiplier = 10
+
+                [operators]
+                enabled_operators = ["relop_ordered_to_unordered", "relop_unordered_to_ordered"]
+            "#
+            ))?;
+            let locations = MutationEngine::new(&config, 100)?
+                .discover_mutation_positions(&module)?
+                .locations;
+            assert_eq!(locations.len(), 4);
+
+            let mut outcomes = Executor::new(&config).execute_mutants(&module, &locations)?;
+            outcomes.sort_by_key(|outcome| outcome.offset);
+
+            let operators: Vec<&str> = outcomes
+                .iter()
+                .map(|outcome| outcome.mutation_operator.dyn_name())
+                .collect();
+            assert_eq!(
+                operators,
+                [
+                    "relop_ordered_to_unordered",
+                    "relop_ordered_to_unordered",
+                    "relop_unordered_to_ordered",
+                    "relop_unordered_to_ordered",
+                ]
+            );
+            assert!(matches!(outcomes[0].result, ExecutionResult::Error { .. }));
+            assert!(outcomes[1].survived);
+            assert!(matches!(outcomes[2].result, ExecutionResult::Error { .. }));
+            assert!(outcomes[3].survived);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn table_entry_replace_calls_other_function() -> Result<()> {
+        use wasmut_wasm::{
+            builder,
+            elements::{BlockType, Instructions, ValueType},
+        };
+
+        // Traps unless the function in table slot 0 returns 1
+        let module = builder::module()
+            .function()
+            .signature()
+            .with_result(ValueType::I32)
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                Instruction::I32Const(1),
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .with_result(ValueType::I32)
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                Instruction::I32Const(2),
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                Instruction::I32Const(0),
+                Instruction::CallIndirect(0, 0),
+                Instruction::I32Const(1),
+                Instruction::I32Ne,
+                Instruction::If(BlockType::NoResult),
+                Instruction::Unreachable,
+                Instruction::End,
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .table()
+            .with_min(2)
+            .with_element(0, vec![0, 1])
+            .build()
+            .export()
+            .field("_start")
+            .internal()
+            .func(2)
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        for meta_mutant in [false, true] {
+            let config = Config::parse(&format!(
+                r#"
+                [engine]
+                runtime = "wasmi"
+                coverage_based_execution = false
+                meta_mutant = {meta_mutant}
+                timeout_multiplier = 10
+
+                [operators]
+                enabled_operators = ["table_entry_replace"]
+            "#
+            ))?;
+            let locations = MutationEngine::new(&config, 100)?
+                .discover_mutation_positions(&module)?
+                .locations;
+            assert_eq!(locations.len(), 1);
+
+            let outcomes = Executor::new(&config).execute_mutants(&module, &locations)?;
+            assert!(matches!(outcomes[0].result, ExecutionResult::Error { .. }));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn nearest_covered_instruction() {
+        let functions = [10, 50, 100];
+        let covered = [12, 20, 40, 55, 120];
+
+        assert_eq!(nearest_covered(25, &covered, &functions), Some(20));
+        assert_eq!(nearest_covered(35, &covered, &functions), Some(40));
+        assert_eq!(nearest_covered(30, &covered, &functions), Some(20));
+        // Covered instructions of other functions are ignored
+        assert_eq!(nearest_covered(48, &covered, &functions), Some(40));
+        assert_eq!(nearest_covered(70, &covered, &functions), Some(55));
+        assert_eq!(nearest_covered(101, &covered, &functions), Some(120));
+        assert_eq!(nearest_covered(5, &covered, &functions), None);
+        assert_eq!(nearest_covered(60, &[12, 120], &functions), None);
+    }
+
+    #[test]
+    fn warning_for_many_timeouts() -> Result<()> {
+        let outcome = |result: ExecutionResult| ExecutedMutant {
+            offset: 0,
+            result,
+            mutation_operator: Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap()),
+            survived: false,
+            trace: None,
+            likely_equivalent: false,
+            condition: None,
+            skip_reason: None,
+            killed_by: Vec::new(),
+        };
+        let exit = |execution_cost| {
+            outcome(ExecutionResult::ProcessExit {
+                exit_code: 1,
+                execution_cost,
+            })
+        };
+
+        let config = Config::parse(
+            r#"
+            [engine]
+            timeout_multiplier = 1.5
+            timeout_warning_threshold = 25
+
+            [report]
+            cost_unit = "cycles"
+            "#,
+        )?;
+        let executor = Executor::new(&config);
+
+        let mut outcomes = vec![
+            exit(100),
+            exit(120),
+            exit(140),
+            outcome(ExecutionResult::Timeout),
+            outcome(ExecutionResult::Skipped),
+        ];
+        assert_eq!(executor.timeout_warning(&outcomes), None);
+
+        outcomes.push(outcome(ExecutionResult::Timeout));
+        assert_eq!(
+            executor.timeout_warning(&outcomes).unwrap(),
+            "2 of 5 executed mutants (40.0%) timed out, consider raising timeout_multiplier \
+            (currently 1.5). Execution cost of the other mutants: median 120 cycles, \
+            90th percentile 140 cycles, maximum 140 cycles"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn likely_equivalent_boundary_mutants() {
+        use crate::operator::ops::{RelationalOperatorLtToGe, RelationalOperatorLtToLe};
+
+        let mut trace_points = TracePoints::default();
+        trace_points.add_point(10);
+        trace_points.add_comparison(10, true, false);
+        trace_points.add_comparison(10, false, false);
+        trace_points.add_point(20);
+        trace_points.add_comparison(20, false, true);
+
+        let coverage = Coverage {
+            enabled: true,
+            trace_points,
+            branches: HashMap::new(),
+            expensive: ExpensiveFunctions::default(),
+        };
+
+        let lt_to_le = RelationalOperatorLtToLe::new(&Instruction::I32LtS).unwrap();
+        let lt_to_ge = RelationalOperatorLtToGe::new(&Instruction::I32LtS).unwrap();
+
+        // Both operands were never equal at offset 10, so `<` and `<=` behave the same
+        assert!(coverage.is_likely_equivalent(10, &lt_to_le));
+        assert!(!coverage.is_likely_equivalent(20, &lt_to_le));
+        assert!(!coverage.is_likely_equivalent(10, &lt_to_ge));
+
+        // Without a comparison profile, nothing is known about the operands
+        assert!(!coverage.is_likely_equivalent(30, &lt_to_le));
+    }
+
+    fn location_with_mutations(offset: u64, count: usize) -> MutationLocation {
+        MutationLocation {
+            function_number: 1,
+            statement_number: 0,
+            offset,
+            mutations: (0..count)
+                .map(|id| Mutation {
+                    id: id as i64,
+                    operator: Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap()),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn hybrid_split_by_executed_instructions() {
+        let mut locations = [
+            location_with_mutations(1, 1),
+            location_with_mutations(2, 1),
+            location_with_mutations(3, 1),
+        ];
+        locations[0].function_number = 0;
+        locations[2].function_number = 5;
+
+        let offsets = |locations: Vec<MutationLocation>| -> Vec<u64> {
+            locations.iter().map(|l| l.offset).collect()
+        };
+
+        // Function 0 is cheap, function 1 is expensive, function 5 was not traced
+        let (interpreted, compiled) = split_by_executed_instructions(&locations, &[10, 1000], 100);
+        assert_eq!(offsets(interpreted), vec![1, 3]);
+        assert_eq!(offsets(compiled), vec![2]);
+
+        let (interpreted, compiled) = split_by_executed_instructions(&locations, &[10, 1000], 1000);
+        assert_eq!(offsets(interpreted), vec![1, 2, 3]);
+        assert!(compiled.is_empty());
+    }
+
+    #[test]
+    fn hybrid_routing_of_cheap_function_in_expensive_program() -> Result<()> {
+        use wasmut_wasm::{
+            builder,
+            elements::{BlockType, Instructions, Local, ValueType},
+        };
+
+        // `_start` calls the cheap function 0 once, then counts down from 1000
+        let module = builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                Instruction::I32Const(1),
+                Instruction::Drop,
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_locals(vec![Local::new(1, ValueType::I32)])
+            .with_instructions(Instructions::new(vec![
+                Instruction::Call(0),
+                Instruction::I32Const(1000),
+                Instruction::SetLocal(0),
+                Instruction::Loop(BlockType::NoResult),
+                Instruction::GetLocal(0),
+                Instruction::I32Const(1),
+                Instruction::I32Sub,
+                Instruction::TeeLocal(0),
+                Instruction::BrIf(0),
+                Instruction::End,
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .memory()
+            .with_min(1)
+            .build()
+            .export()
+            .field("_start")
+            .internal()
+            .func(1)
+            .build()
+            .export()
+            .field("memory")
+            .internal()
+            .memory(0)
+            .build()
+            .build();
+        let module = WasmModule::from_bytes(module.into_bytes()?, "module.wasm")?;
+
+        let config = Config::parse(
+            r#"
+            [engine]
+            runtime = "hybrid"
+            hybrid_threshold = 100
+            "#,
+        )?;
+        let executor = Executor::new(&config);
+        let baseline = executor.record_baseline(&module, true)?;
+        assert!(baseline.execution_cost > 1000);
+
+        let coverage = Coverage {
+            enabled: true,
+            trace_points: baseline.coverage.unwrap(),
+            branches: HashMap::new(),
+            expensive: ExpensiveFunctions::default(),
+        };
+        let mut locations = [location_with_mutations(1, 1), location_with_mutations(2, 1)];
+        locations[0].function_number = 0;
+
+        // Although every mutant executes the whole, expensive program,
+        // the mutant of the cheap function is interpreted
+        let groups = executor.route_locations(&module, &locations, &coverage);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, RuntimeKind::Wasmi);
+        assert_eq!(groups[0].1[0].function_number, 0);
+        assert_eq!(groups[1].0, RuntimeKind::Wasmer);
+        assert_eq!(groups[1].1[0].function_number, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn expensive_functions() {
+        assert!(find_expensive_functions(&[0, 0], 50.0).is_empty());
+        assert_eq!(
+            find_expensive_functions(&[10, 70, 20], 50.0),
+            vec![(1, 70.0)]
+        );
+        assert_eq!(find_expensive_functions(&[10, 70, 20], 15.0).len(), 2);
+
+        let mut cheap = location_with_mutations(1, 2);
+        cheap.function_number = 0;
+        let expensive = location_with_mutations(2, 2);
+        let functions = |action| ExpensiveFunctions {
+            functions: [1].into_iter().collect(),
+            action,
+            timeout_multiplier: 1.5,
+        };
+
+        let skipped = functions(ExpensiveFunctionAction::Skip);
+        assert!(skipped.skips(&expensive));
+        assert!(!skipped.skips(&cheap));
+        assert_eq!(skipped.limit(&expensive, 100, 200), 200);
+
+        let shortened = functions(ExpensiveFunctionAction::ShortenTimeout);
+        assert!(!shortened.skips(&expensive));
+        assert_eq!(shortened.limit(&expensive, 100, 200), 150);
+        assert_eq!(shortened.limit(&cheap, 100, 200), 200);
+
+        let reported = functions(ExpensiveFunctionAction::Report);
+        assert!(!reported.skips(&expensive));
+        assert_eq!(reported.limit(&expensive, 100, 200), 200);
+
+        let coverage = Coverage {
+            enabled: false,
+            trace_points: TracePoints::default(),
+            branches: HashMap::new(),
+            expensive: skipped,
+        };
+        assert_eq!(coverage.count_executed_mutants(&[cheap, expensive]), 2);
+    }
+
+    #[test]
+    fn number_of_compilations() {
+        let locations = [
+            location_with_mutations(1, 2),
+            location_with_mutations(2, 2),
+            location_with_mutations(3, 5),
+        ];
+
+        let compilations =
+            |toml| Executor::new(&Config::parse(toml).unwrap()).compilations(&locations);
+
+        assert_eq!(compilations("[engine]\nmeta_mutant = false"), 9);
+        assert_eq!(compilations("[engine]\nmeta_mutant = true"), 1);
+        assert_eq!(
+            compilations("[engine]\nmeta_mutant = true\nmeta_mutant_chunk_size = 4"),
+            2
+        );
+    }
+
+    #[test]
+    fn chunk_locations_works() {
+        let locations = [
+            location_with_mutations(1, 2),
+            location_with_mutations(2, 2),
+            location_with_mutations(3, 5),
+            location_with_mutations(4, 1),
+        ];
+
+        let chunk_offsets = |max_mutations| -> Vec<Vec<u64>> {
+            chunk_locations(&locations, max_mutations)
+                .iter()
+                .map(|chunk| chunk.iter().map(|l| l.offset).collect())
+                .collect()
+        };
+
+        assert_eq!(chunk_offsets(100), vec![vec![1, 2, 3, 4]]);
+        assert_eq!(chunk_offsets(4), vec![vec![1, 2], vec![3], vec![4]]);
+        assert_eq!(chunk_offsets(6), vec![vec![1, 2], vec![3, 4]]);
+        assert_eq!(chunk_offsets(1), vec![vec![1], vec![2], vec![3], vec![4]]);
+
+        assert_eq!(chunk_locations(&[], 10).len(), 1);
+    }
+
+    #[test]
+    fn instrumentation_overhead_factor() {
+        assert_eq!(instrumentation_overhead(100, 250), 2.5);
+        assert_eq!(instrumentation_overhead(100, 100), 1.0);
+        assert_eq!(instrumentation_overhead(0, 100), 1.0);
+    }
+
+    #[test]
+    fn internal_errors_only_affect_a_single_mutant() {
+        let (result, survived) = guarded_execution(0, || {
+            Ok((
+                ExecutionResult::ProcessExit {
+                    exit_code: 0,
+                    execution_cost: 10,
+                },
+                true,
+            ))
+        });
+        assert!(matches!(result, ExecutionResult::ProcessExit { .. }));
+        assert!(survived);
+
+        let (result, survived): (_, bool) =
+            guarded_execution(0, || Err(anyhow::anyhow!("Failed to create runtime")));
+        assert!(matches!(
+            result,
+            ExecutionResult::InternalError { message } if message == "Failed to create runtime"
+        ));
+        assert!(!survived);
+
+        let (result, survived): (_, bool) =
+            guarded_execution(0, || panic!("runtime panicked: {}", 42));
+        assert!(matches!(
+            result,
+            ExecutionResult::InternalError { message } if message == "runtime panicked: 42"
+        ));
+        assert!(!survived);
+    }
+
+    #[test]
+    fn find_mutations_of_outcomes() {
+        let location = MutationLocation {
+            function_number: 1,
+            statement_number: 2,
+            offset: 20,
+            mutations: vec![
+                Mutation {
+                    id: 1,
+                    operator: Box::new(
+                        RelationalOperatorLtToLe::new(&Instruction::I32LtS).unwrap(),
+                    ),
+                },
+                Mutation {
+                    id: 2,
+                    operator: Box::new(
+                        RelationalOperatorLtToGe::new(&Instruction::I32LtS).unwrap(),
+                    ),
+                },
+            ],
+        };
+        let locations = HashMap::from([(location.offset, &location)]);
+
+        // Outcomes need not be in the order of the mutations
+        let mut outcomes = not_run_mutants(std::slice::from_ref(&location));
+        outcomes.reverse();
+        let (found, index) = find_mutation(&locations, &outcomes[0]).unwrap();
+        assert_eq!(found.offset, 20);
+        assert_eq!(index, 1);
+        assert_eq!(find_mutation(&locations, &outcomes[1]).unwrap().1, 0);
+
+        outcomes[0].offset = 30;
+        assert!(find_mutation(&locations, &outcomes[0]).is_none());
+    }
+
+    #[test]
+    fn prefetch_depth_depends_on_workers() {
+        assert_eq!(prefetch_depth(1), 1);
+        assert_eq!(prefetch_depth(16), 2);
+        assert_eq!(prefetch_depth(128), 4);
+    }
+
+    #[test]
+    fn run_estimate() {
+        let estimate = RunEstimate {
+            mutants: 100,
+            time_per_mutant: Duration::from_secs(2),
+            threads: 4,
+            meta_mutant: true,
+            overhead: Some(2.5),
+        };
+
+        assert_eq!(estimate.total_time(), Duration::from_secs(50));
+        let estimate = format!("{estimate}");
+        assert!(estimate.contains("0h 00m 50s"));
+        assert!(estimate.contains("instrumentation overhead factor: 2.50"));
+    }
+
     #[test]
     fn meta_results_should_be_equal() {
         let locations = [
@@ -438,7 +2913,32 @@ mod tests {
         let executor = Executor::new(&config);
         let meta_results = executor.execute_mutants(&module, &locations).unwrap();
 
+        let config = Config::parse(
+            r#"
+            [engine]
+            coverage_based_execution = false
+            meta_mutant = true
+            meta_mutant_chunk_size = 1
+        "#,
+        )
+        .unwrap();
+        let executor = Executor::new(&config);
+        let chunked_results = executor.execute_mutants(&module, &locations).unwrap();
+
+        let config = Config::parse(
+            r#"
+            [engine]
+            coverage_based_execution = false
+            meta_mutant = true
+            meta_mutant_activation = "host_call"
+        "#,
+        )
+        .unwrap();
+        let executor = Executor::new(&config);
+        let host_call_results = executor.execute_mutants(&module, &locations).unwrap();
+
         assert_eq!(no_meta_results.len(), meta_results.len());
+        assert_eq!(no_meta_results.len(), chunked_results.len());
 
         impl PartialEq for ExecutionResult {
             fn eq(&self, other: &Self) -> bool {
@@ -461,5 +2961,13 @@ mod tests {
         for (a, b) in no_meta_results.iter().zip(&meta_results) {
             assert_eq!(a.result, b.result);
         }
+
+        for (a, b) in no_meta_results.iter().zip(&chunked_results) {
+            assert_eq!(a.result, b.result);
+        }
+
+        for (a, b) in no_meta_results.iter().zip(&host_call_results) {
+            assert_eq!(a.result, b.result);
+        }
     }
 }
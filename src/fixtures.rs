@@ -0,0 +1,241 @@
+//! Modules with debug information for the unit tests, built in memory
+//! instead of being compiled from the C sources in `testdata`.
+
+use anyhow::Result;
+use gimli::write::{
+    Address, AttributeValue, DwarfUnit, EndianVec, LineProgram, LineString, Sections,
+};
+use wasmut_wasm::{
+    builder,
+    elements::{
+        CustomSection, FunctionNameSubsection, Instruction, Instructions, Module, NameSection,
+        Section, ValueType,
+    },
+};
+
+use crate::wasmmodule::WasmModule;
+
+/// Compilation directory recorded in the debug information
+const COMP_DIR: &str = "/build";
+
+/// Equivalent of `testdata/simple_add`: `_start` in `test.c` checks that `add`
+/// in `simple_add.c` returns 3 for the arguments 1 and 2, so the module exits with 0.
+///
+/// Every instruction has its own line, starting at line 1 of each file.
+pub fn simple_add() -> Result<WasmModule<'static>> {
+    let module = builder::module()
+        .function()
+        .signature()
+        .with_params(vec![ValueType::I32, ValueType::I32])
+        .with_result(ValueType::I32)
+        .build()
+        .body()
+        .with_instructions(Instructions::new(vec![
+            Instruction::GetLocal(0),
+            Instruction::GetLocal(1),
+            Instruction::I32Add,
+            Instruction::End,
+        ]))
+        .build()
+        .build()
+        .function()
+        .signature()
+        .build()
+        .body()
+        .with_instructions(Instructions::new(vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::Call(0),
+            Instruction::I32Const(3),
+            Instruction::I32Ne,
+            Instruction::If(wasmut_wasm::elements::BlockType::NoResult),
+            Instruction::Unreachable,
+            Instruction::End,
+            Instruction::End,
+        ]))
+        .build()
+        .build()
+        .export()
+        .field("_start")
+        .internal()
+        .func(1)
+        .build()
+        .build();
+
+    with_debug_info(module, &[("add", "simple_add.c"), ("_start", "test.c")])
+}
+
+/// Module consisting of functions with the given parameters and results, one per
+/// entry of `bodies`. The final `end` of every function is appended. The module
+/// has no exports and no debug information.
+pub fn functions(
+    params: &[ValueType],
+    results: &[ValueType],
+    bodies: Vec<Vec<Instruction>>,
+) -> Result<WasmModule<'static>> {
+    let mut module = builder::module();
+    for body in bodies {
+        module.push_function(
+            builder::function()
+                .signature()
+                .with_params(params.to_vec())
+                .with_results(results.to_vec())
+                .build()
+                .body()
+                .with_instructions(Instructions::new(
+                    body.into_iter().chain([Instruction::End]).collect(),
+                ))
+                .build()
+                .build(),
+        );
+    }
+
+    WasmModule::from_bytes(module.build().into_bytes()?, "module.wasm")
+}
+
+/// Module whose exported `_start` function executes `body`, followed by the final `end`
+pub fn start_function(body: Vec<Instruction>) -> Result<WasmModule<'static>> {
+    let module = builder::module()
+        .function()
+        .signature()
+        .build()
+        .body()
+        .with_instructions(Instructions::new(
+            body.into_iter().chain([Instruction::End]).collect(),
+        ))
+        .build()
+        .build()
+        .export()
+        .field("_start")
+        .internal()
+        .func(0)
+        .build()
+        .build();
+
+    WasmModule::from_bytes(module.into_bytes()?, "module.wasm")
+}
+
+/// Add a name section and DWARF line information to `module`, which must
+/// not import functions. `functions` contains the name and the source file
+/// of every function.
+fn with_debug_info(mut module: Module, functions: &[(&str, &str)]) -> Result<WasmModule<'static>> {
+    let mut names = FunctionNameSubsection::default();
+    for (index, (name, _)) in functions.iter().enumerate() {
+        names.names_mut().insert(index as u32, name.to_string());
+    }
+    module
+        .sections_mut()
+        .push(Section::Name(NameSection::new(None, Some(names), None)));
+
+    // Offsets of the instructions, relative to the start of the code section,
+    // are only known once the module was serialized
+    let parsed: Module = wasmut_wasm::elements::deserialize_buffer(&module.clone().into_bytes()?)?;
+    let code_section = parsed.code_section().expect("module has a code section");
+
+    let encoding = gimli::Encoding {
+        format: gimli::Format::Dwarf32,
+        version: 4,
+        address_size: 4,
+    };
+    let mut program = LineProgram::new(
+        encoding,
+        gimli::LineEncoding::default(),
+        LineString::String(COMP_DIR.into()),
+        LineString::String(functions[0].1.into()),
+        None,
+    );
+    let mut dwarf = DwarfUnit::new(encoding);
+    let root = dwarf.unit.root();
+    let mut code_end = 0;
+
+    for ((name, file), body) in functions.iter().zip(code_section.bodies()) {
+        let offsets: Vec<u64> = body
+            .code()
+            .offsets()
+            .iter()
+            .map(|offset| offset - code_section.offset())
+            .collect();
+        let start = offsets[0];
+        // The final `end` instruction is a single byte
+        let end = offsets[offsets.len() - 1] + 1;
+        code_end = end;
+
+        let directory = program.default_directory();
+        let file = program.add_file(LineString::String(file.as_bytes().into()), directory, None);
+        program.begin_sequence(Some(Address::Constant(start)));
+        for (line, offset) in offsets.iter().enumerate() {
+            program.row().address_offset = offset - start;
+            program.row().file = file;
+            program.row().line = line as u64 + 1;
+            program.generate_row();
+        }
+        program.end_sequence(end - start);
+
+        let subprogram = dwarf.unit.add(root, gimli::DW_TAG_subprogram);
+        let subprogram = dwarf.unit.get_mut(subprogram);
+        subprogram.set(
+            gimli::DW_AT_name,
+            AttributeValue::String(name.as_bytes().into()),
+        );
+        subprogram.set(
+            gimli::DW_AT_low_pc,
+            AttributeValue::Address(Address::Constant(start)),
+        );
+        subprogram.set(gimli::DW_AT_high_pc, AttributeValue::Udata(end - start));
+    }
+
+    let unit = dwarf.unit.get_mut(root);
+    unit.set(
+        gimli::DW_AT_name,
+        AttributeValue::String(functions[0].1.as_bytes().into()),
+    );
+    unit.set(
+        gimli::DW_AT_comp_dir,
+        AttributeValue::String(COMP_DIR.into()),
+    );
+    unit.set(
+        gimli::DW_AT_low_pc,
+        AttributeValue::Address(Address::Constant(0)),
+    );
+    unit.set(gimli::DW_AT_high_pc, AttributeValue::Udata(code_end));
+    dwarf.unit.line_program = program;
+
+    let mut sections = Sections::new(EndianVec::new(gimli::LittleEndian));
+    dwarf.write(&mut sections)?;
+    sections.for_each(|id, data| -> Result<()> {
+        if !data.slice().is_empty() {
+            module
+                .sections_mut()
+                .push(Section::Custom(CustomSection::new(
+                    id.name().into(),
+                    data.slice().into(),
+                )));
+        }
+        Ok(())
+    })?;
+
+    WasmModule::from_bytes(module.into_bytes()?, "simple_add.wasm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_add_has_debug_info() -> Result<()> {
+        let module = simple_add()?;
+
+        let mut files: Vec<String> = module.source_files().into_iter().collect();
+        files.sort();
+        assert_eq!(files, ["/build/simple_add.c", "/build/test.c"]);
+
+        let functions = module.function_offsets();
+        let (offset, name) = &functions[0];
+        assert_eq!(name, "add");
+        let location = module.locator().lookup_address(*offset).unwrap();
+        assert_eq!(location.function.as_deref(), Some("add"));
+        assert_eq!(location.file.as_deref(), Some("/build/simple_add.c"));
+        assert_eq!(location.line, Some(1));
+        Ok(())
+    }
+}
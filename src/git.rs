@@ -0,0 +1,102 @@
+//! Determine changed source files using git.
+
+use std::{path::Path, process::Command};
+
+use anyhow::{bail, Context, Result};
+
+use crate::error::ErrorKind;
+
+/// Run git in `directory` and return its standard output
+fn run_git(directory: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()
+        .context("Could not execute git")?;
+
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    String::from_utf8(output.stdout).context("git returned invalid UTF-8")
+}
+
+/// Return all files of the repository containing `directory` that were changed
+/// since `revision`, including uncommitted and untracked files.
+/// Paths are relative to the root of the repository.
+pub fn changed_files(directory: &Path, revision: &str) -> Result<Vec<String>> {
+    let changed = run_git(directory, &["diff", "--name-only", revision, "--"])
+        .with_context(|| format!("Could not determine files changed since {revision}"))
+        .context(ErrorKind::Config)?;
+
+    let untracked = run_git(
+        directory,
+        &["ls-files", "--others", "--exclude-standard", "--full-name"],
+    )
+    .context("Could not determine untracked files")
+    .context(ErrorKind::Config)?;
+
+    let mut files: Vec<String> = changed
+        .lines()
+        .chain(untracked.lines())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    files.sort();
+    files.dedup();
+
+    Ok(files)
+}
+
+/// Regular expression matching source file paths that end with the
+/// repository-relative path `file`.
+///
+/// Paths in the debug information are usually absolute, so the
+/// repository root is not part of the pattern.
+pub fn file_pattern(file: &str) -> String {
+    format!("(^|/){}$", regex::escape(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn file_pattern_matches_suffix() {
+        let pattern = Regex::new(&file_pattern("src/add.c")).unwrap();
+
+        assert!(pattern.is_match("src/add.c"));
+        assert!(pattern.is_match("/home/user/project/src/add.c"));
+        assert!(!pattern.is_match("/home/user/project/src/add.cpp"));
+        assert!(!pattern.is_match("/home/user/project/mysrc/add.c"));
+    }
+
+    #[test]
+    fn changed_files_in_repository() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let git = |args: &[&str]| run_git(dir.path(), args);
+
+        git(&["init", "-q"])?;
+        git(&["config", "user.email", "test@example.com"])?;
+        git(&["config", "user.name", "test"])?;
+
+        std::fs::create_dir(dir.path().join("src"))?;
+        std::fs::write(dir.path().join("src/a.c"), "int a;\n")?;
+        std::fs::write(dir.path().join("src/b.c"), "int b;\n")?;
+        git(&["add", "."])?;
+        git(&["commit", "-q", "-m", "initial"])?;
+
+        std::fs::write(dir.path().join("src/a.c"), "int a = 1;\n")?;
+        std::fs::write(dir.path().join("src/c.c"), "int c;\n")?;
+
+        assert_eq!(changed_files(dir.path(), "HEAD")?, ["src/a.c", "src/c.c"]);
+        assert!(changed_files(dir.path(), "does-not-exist").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+//! Hooks into the execution of mutants.
+//!
+//! An `ExecutionHook` registered with `Executor::with_hook` is notified before
+//! and after every executed mutant, e.g. to export custom metrics, write the
+//! outcomes into a database or report long-running survivors, without changing
+//! wasmut itself.
+//!
+//! Mutants are executed in parallel, so hooks are called concurrently from the
+//! worker threads and have to synchronize any state they keep. Mutants that are
+//! skipped because of missing coverage, or not run because the run was stopped,
+//! are not executed and do not trigger any hook.
+
+use std::time::Duration;
+
+use crate::{
+    executor::ExecutedMutant,
+    mutation::{Mutation, MutationLocation},
+};
+
+/// Callbacks invoked around the execution of every mutant
+pub trait ExecutionHook: Send + Sync {
+    /// Called before `mutation` of the instruction at `location` is executed
+    fn before_mutant(&self, _location: &MutationLocation, _mutation: &Mutation) {}
+
+    /// Called after the mutant at `location` was executed, with its outcome
+    /// and the wall-clock time it took to prepare and execute the mutant
+    fn after_mutant(
+        &self,
+        _location: &MutationLocation,
+        _mutant: &ExecutedMutant,
+        _duration: Duration,
+    ) {
+    }
+}
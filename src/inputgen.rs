@@ -0,0 +1,558 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use anyhow::{anyhow, Context, Result};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::{
+    addressresolver::CodeLocation,
+    config::Config,
+    error::ErrorKind,
+    executor::{self, ExecutedMutant},
+    mutation::{Mutation, MutationLocation},
+    output,
+    policy::ExecutionPolicy,
+    runtime::{wasmer::WasmerRuntimeFactory, ExecutionResult, Runtime},
+    wasmmodule::WasmModule,
+};
+
+/// Maximum number of perturbations applied to a single input file
+const MAX_PERTURBATIONS: usize = 4;
+
+/// Generates inputs by randomly perturbing existing inputs
+pub struct InputGenerator {
+    rng: StdRng,
+    max_input_size: usize,
+}
+
+impl InputGenerator {
+    pub fn new(seed: u64, max_input_size: usize) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            max_input_size,
+        }
+    }
+
+    /// Apply a few random byte-level perturbations to `input`.
+    ///
+    /// The result is never larger than `max_input_size`.
+    pub fn perturb_bytes(&mut self, input: &[u8]) -> Vec<u8> {
+        let mut data = input.to_vec();
+        data.truncate(self.max_input_size);
+
+        let perturbations = self.rng.gen_range(1..=MAX_PERTURBATIONS);
+
+        for _ in 0..perturbations {
+            if data.is_empty() {
+                if self.max_input_size > 0 {
+                    data.push(self.rng.gen());
+                }
+                continue;
+            }
+
+            let position = self.rng.gen_range(0..data.len());
+
+            match self.rng.gen_range(0..5) {
+                // Flip a single bit
+                0 => data[position] ^= 1 << self.rng.gen_range(0..8),
+                // Replace a byte
+                1 => data[position] = self.rng.gen(),
+                // Insert a byte
+                2 if data.len() < self.max_input_size => data.insert(position, self.rng.gen()),
+                // Remove a byte
+                3 => {
+                    data.remove(position);
+                }
+                // Truncate
+                _ => data.truncate(position),
+            }
+        }
+
+        data
+    }
+
+    /// Perturb command line arguments.
+    ///
+    /// Integer arguments are replaced by a nearby or boundary value,
+    /// other arguments are perturbed character-wise.
+    pub fn perturb_args(&mut self, args: &[String]) -> Vec<String> {
+        args.iter()
+            .map(|arg| {
+                if !self.rng.gen_bool(0.5) {
+                    return arg.clone();
+                }
+
+                if let Ok(number) = arg.parse::<i64>() {
+                    let candidates = [
+                        number.saturating_add(1),
+                        number.saturating_sub(1),
+                        0,
+                        number.saturating_neg(),
+                        number.saturating_mul(2),
+                    ];
+                    candidates[self.rng.gen_range(0..candidates.len())].to_string()
+                } else {
+                    self.perturb_string(arg)
+                }
+            })
+            .collect()
+    }
+
+    fn perturb_string(&mut self, s: &str) -> String {
+        let mut chars: Vec<char> = s.chars().collect();
+
+        if chars.is_empty() {
+            return self.random_char().to_string();
+        }
+
+        let position = self.rng.gen_range(0..chars.len());
+        if self.rng.gen_bool(0.5) {
+            chars[position] = self.random_char();
+        } else {
+            chars.remove(position);
+        }
+
+        chars.into_iter().collect()
+    }
+
+    fn random_char(&mut self) -> char {
+        self.rng.gen_range(b'!'..=b'~') as char
+    }
+}
+
+/// Copy of all mapped directories, in which input files can be
+/// modified without changing the originals.
+///
+/// The copy is removed when the sandbox is dropped.
+pub struct Sandbox {
+    root: PathBuf,
+    map_dirs: Vec<(String, String)>,
+}
+
+impl Sandbox {
+    /// Copy all host directories of `map_dirs` into a temporary directory
+    pub fn new(map_dirs: &[(String, String)]) -> Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let root = std::env::temp_dir().join(format!(
+            "wasmut-sandbox-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut sandbox = Self {
+            root,
+            map_dirs: Vec::new(),
+        };
+
+        for (index, (host, guest)) in map_dirs.iter().enumerate() {
+            let copy = sandbox.root.join(index.to_string());
+            copy_dir(Path::new(host), &copy)
+                .with_context(|| format!("Could not copy {host} into sandbox"))?;
+
+            sandbox
+                .map_dirs
+                .push((copy.to_string_lossy().into(), guest.clone()));
+        }
+
+        Ok(sandbox)
+    }
+
+    /// Directory mappings pointing to the copies in the sandbox
+    pub fn map_dirs(&self) -> &[(String, String)] {
+        &self.map_dirs
+    }
+
+    /// Return the path of the copy of `file`, which
+    /// must be located in one of the mapped directories.
+    pub fn path_of(&self, file: &str, original_map_dirs: &[(String, String)]) -> Result<PathBuf> {
+        for ((host, _), (copy, _)) in original_map_dirs.iter().zip(&self.map_dirs) {
+            if let Ok(relative) = Path::new(file).strip_prefix(host) {
+                return Ok(Path::new(copy).join(relative));
+            }
+        }
+
+        Err(anyhow!(
+            "Input file {file} is not located in any directory of map_dirs"
+        ))
+        .context(ErrorKind::Config)
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A generated input that kills a surviving mutant
+pub struct Suggestion {
+    pub location: CodeLocation,
+    pub operator: String,
+    pub attempt: usize,
+    pub args: Vec<String>,
+
+    /// Paths of the saved input files
+    pub input_files: Vec<PathBuf>,
+}
+
+/// Generate inputs for surviving mutants, reporting inputs that kill them.
+pub struct Suggester<'a> {
+    config: &'a Config,
+    module: &'a WasmModule<'a>,
+    output_directory: PathBuf,
+}
+
+impl<'a> Suggester<'a> {
+    pub fn new(config: &'a Config, module: &'a WasmModule<'a>, output_directory: &Path) -> Self {
+        Self {
+            config,
+            module,
+            output_directory: output_directory.into(),
+        }
+    }
+
+    /// Try to find inputs that kill the surviving mutants in `outcomes`,
+    /// which were executed for the mutations in `locations`.
+    ///
+    /// All surviving mutants are compiled once into a meta-mutant, and the
+    /// directories in `map_dirs` are copied once into a sandbox. Every attempt
+    /// only writes its perturbed input files into the sandbox.
+    pub fn suggest(
+        &self,
+        locations: &[MutationLocation],
+        outcomes: &[ExecutedMutant],
+    ) -> Result<Vec<Suggestion>> {
+        let suggest_config = self.config.suggest();
+        let map_dirs = self.config.engine().map_dirs();
+
+        let surviving = surviving_locations(locations, outcomes);
+        let mut survivors: Vec<(&MutationLocation, &Mutation)> = surviving
+            .iter()
+            .flat_map(|location| {
+                location
+                    .mutations
+                    .iter()
+                    .map(move |mutation| (location, mutation))
+            })
+            .collect();
+
+        log::info!(
+            "Generating up to {} inputs for {} surviving mutants",
+            suggest_config.attempts(),
+            survivors.len()
+        );
+        if survivors.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sandbox = Sandbox::new(map_dirs)?;
+        let input_files = suggest_config
+            .input_files()
+            .iter()
+            .map(|file| {
+                let content =
+                    fs::read(file).with_context(|| format!("Could not read input file {file}"))?;
+                Ok((sandbox.path_of(file, map_dirs)?, content))
+            })
+            .collect::<Result<Vec<(PathBuf, Vec<u8>)>>>()?;
+
+        let meta_mutant = self
+            .module
+            .clone_and_mutate_all(&surviving, self.config.engine().meta_mutant_activation())?;
+        let factory = WasmerRuntimeFactory::new(&meta_mutant, true, sandbox.map_dirs())?;
+
+        let resolver = self.module.locator();
+        let mut generator =
+            InputGenerator::new(suggest_config.seed(), suggest_config.max_input_size());
+        let mut suggestions = Vec::new();
+
+        for attempt in 0..suggest_config.attempts() {
+            if survivors.is_empty() {
+                break;
+            }
+
+            let args = generator.perturb_args(suggest_config.args());
+            for (path, content) in &input_files {
+                fs::write(path, generator.perturb_bytes(content))?;
+            }
+
+            // Inputs that make the original module fail are of no use
+            let Some(limit) = self.original_limit(&factory, &args)? else {
+                log::debug!("Original module fails with input {attempt}, skipping");
+                continue;
+            };
+
+            let killed: Vec<bool> = survivors
+                .par_iter()
+                .map(|(location, mutation)| {
+                    let result = factory
+                        .instantiate_mutant_with_args(
+                            mutation.id,
+                            Some(location.function_number),
+                            &args,
+                        )
+                        .and_then(|mut runtime| {
+                            runtime.call_test_function(ExecutionPolicy::RunUntilLimit { limit })
+                        });
+
+                    matches!(result, Ok(result) if !executor::is_alive(&result))
+                })
+                .collect();
+
+            if !killed.contains(&true) {
+                continue;
+            }
+
+            let paths: Vec<PathBuf> = input_files.iter().map(|(path, _)| path.clone()).collect();
+            let saved_files = self.save_inputs(attempt, &paths)?;
+
+            let mut remaining = Vec::new();
+            for ((location, mutation), killed) in survivors.into_iter().zip(killed) {
+                if killed {
+                    suggestions.push(Suggestion {
+                        location: resolver.lookup_address(location.offset).unwrap_or_default(),
+                        operator: mutation.operator.description(),
+                        attempt,
+                        args: args.clone(),
+                        input_files: saved_files.clone(),
+                    });
+                } else {
+                    remaining.push((location, mutation));
+                }
+            }
+            survivors = remaining;
+        }
+
+        Ok(suggestions)
+    }
+
+    /// Execute the meta-mutant with all mutations disabled with the given input
+    /// and return the timeout for mutants, or `None` if the module failed.
+    fn original_limit(
+        &self,
+        factory: &WasmerRuntimeFactory,
+        args: &[String],
+    ) -> Result<Option<u64>> {
+        let mut runtime = factory.instantiate_mutant_with_args(0, None, args)?;
+
+        match runtime.call_test_function(ExecutionPolicy::RunUntilReturn)? {
+            ExecutionResult::ProcessExit {
+                exit_code: 0,
+                execution_cost,
+            } => {
+                let multiplier = self.config.engine().timeout_multiplier();
+                Ok(Some((execution_cost as f64 * multiplier).ceil() as u64))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Save the perturbed input files of an attempt to the output directory
+    fn save_inputs(&self, attempt: usize, input_files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        let directory = self.output_directory.join(format!("input-{attempt}"));
+        fs::create_dir_all(&directory)
+            .with_context(|| format!("Could not create directory {directory:?}"))?;
+
+        input_files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| {
+                let name = file
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| index.to_string());
+                let target = directory.join(format!("{index}-{name}"));
+                fs::copy(file, &target)
+                    .with_context(|| format!("Could not save input file to {target:?}"))?;
+                Ok(target)
+            })
+            .collect()
+    }
+}
+
+/// Locations of `locations`, containing only the mutations whose outcome in
+/// `outcomes` survived. Outcomes are matched to mutations by offset and operator.
+fn surviving_locations(
+    locations: &[MutationLocation],
+    outcomes: &[ExecutedMutant],
+) -> Vec<MutationLocation> {
+    locations
+        .iter()
+        .filter_map(|location| {
+            let mutations: Vec<Mutation> = location
+                .mutations
+                .iter()
+                .filter(|mutation| {
+                    outcomes.iter().any(|outcome| {
+                        outcome.survived
+                            && outcome.offset == location.offset
+                            && outcome.mutation_operator.dyn_name() == mutation.operator.dyn_name()
+                            && outcome.mutation_operator.description()
+                                == mutation.operator.description()
+                    })
+                })
+                .cloned()
+                .collect();
+
+            (!mutations.is_empty()).then(|| MutationLocation {
+                mutations,
+                ..location.clone()
+            })
+        })
+        .collect()
+}
+
+/// Print all suggestions
+pub fn report(suggestions: &[Suggestion]) {
+    if suggestions.is_empty() {
+        output::output_string("No input killed any of the surviving mutants\n");
+        return;
+    }
+
+    for suggestion in suggestions {
+        let mut text = format!(
+            "{}: {}\n    killed by input {}\n    args: {:?}\n",
+            suggestion.location, suggestion.operator, suggestion.attempt, suggestion.args
+        );
+
+        for file in &suggestion.input_files {
+            text += &format!("    input file: {}\n", file.display());
+        }
+
+        output::output_string(text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::ops::{RelationalOperatorLtToGe, RelationalOperatorLtToLe};
+    use wasmut_wasm::elements::Instruction;
+
+    #[test]
+    fn perturb_bytes_respects_max_size() {
+        let mut generator = InputGenerator::new(0, 8);
+
+        for _ in 0..100 {
+            let result = generator.perturb_bytes(b"0123456789abcdef");
+            assert!(result.len() <= 8);
+        }
+
+        let mut generator = InputGenerator::new(0, 0);
+        assert!(generator.perturb_bytes(b"").is_empty());
+    }
+
+    #[test]
+    fn perturb_is_deterministic() {
+        let mut a = InputGenerator::new(42, 1024);
+        let mut b = InputGenerator::new(42, 1024);
+
+        for _ in 0..10 {
+            assert_eq!(a.perturb_bytes(b"hello"), b.perturb_bytes(b"hello"));
+        }
+
+        let args = vec![String::from("10"), String::from("foo")];
+        assert_eq!(a.perturb_args(&args), b.perturb_args(&args));
+    }
+
+    #[test]
+    fn perturb_numeric_args() {
+        let mut generator = InputGenerator::new(1, 1024);
+        let args = vec![String::from("10")];
+
+        for _ in 0..50 {
+            let result = generator.perturb_args(&args);
+            assert!(["10", "11", "9", "0", "-10", "20"].contains(&result[0].as_str()));
+        }
+    }
+
+    #[test]
+    fn sandbox_copies_directories() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let host = dir.path().join("files");
+        fs::create_dir_all(host.join("nested"))?;
+        fs::write(host.join("nested/input.txt"), "original")?;
+
+        let host = host.to_string_lossy().into_owned();
+        let map_dirs = vec![(host.clone(), String::from("files"))];
+
+        let sandbox = Sandbox::new(&map_dirs)?;
+        let copy = sandbox.path_of(&format!("{host}/nested/input.txt"), &map_dirs)?;
+        assert_eq!(fs::read_to_string(&copy)?, "original");
+
+        fs::write(&copy, "perturbed")?;
+        assert_eq!(
+            fs::read_to_string(dir.path().join("files/nested/input.txt"))?,
+            "original"
+        );
+
+        assert!(sandbox.path_of("/somewhere/else.txt", &map_dirs).is_err());
+
+        let root = sandbox.root.clone();
+        drop(sandbox);
+        assert!(!root.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn only_surviving_mutations_are_kept() {
+        let location = MutationLocation {
+            function_number: 1,
+            statement_number: 2,
+            offset: 20,
+            mutations: vec![
+                Mutation {
+                    id: 1,
+                    operator: Box::new(
+                        RelationalOperatorLtToLe::new(&Instruction::I32LtS).unwrap(),
+                    ),
+                },
+                Mutation {
+                    id: 2,
+                    operator: Box::new(
+                        RelationalOperatorLtToGe::new(&Instruction::I32LtS).unwrap(),
+                    ),
+                },
+            ],
+        };
+        let killed = location.clone();
+        let killed = MutationLocation {
+            offset: 30,
+            ..killed
+        };
+
+        let mut outcomes = executor::not_run_mutants(&[location.clone(), killed.clone()]);
+        outcomes[1].survived = true;
+        // Outcomes need not be in the order of the mutations
+        outcomes.reverse();
+
+        let surviving = surviving_locations(&[location, killed], &outcomes);
+        assert_eq!(surviving.len(), 1);
+        assert_eq!(surviving[0].offset, 20);
+        assert_eq!(surviving[0].mutations.len(), 1);
+        assert_eq!(surviving[0].mutations[0].id, 2);
+    }
+}
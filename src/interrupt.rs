@@ -0,0 +1,37 @@
+//! Handling of Ctrl-C during mutation runs. The first interrupt stops starting
+//! new mutants, so that reports of the mutants executed so far are still
+//! generated. A second interrupt terminates wasmut immediately.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code after a second interrupt, as used by shells for SIGINT
+#[cfg(unix)]
+const INTERRUPT_EXIT_CODE: i32 = 130;
+
+/// Set by the signal handler on the first interrupt
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Install the handler for SIGINT. On other platforms than unix,
+/// interrupts keep terminating wasmut immediately.
+pub fn install_handler() {
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            handle_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn handle_interrupt(_signal: libc::c_int) {
+    // Only async-signal-safe operations are allowed here
+    if INTERRUPTED.swap(true, Ordering::SeqCst) {
+        unsafe { libc::_exit(INTERRUPT_EXIT_CODE) };
+    }
+}
+
+/// True if the run was interrupted
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
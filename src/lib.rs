@@ -0,0 +1,97 @@
+//! Mutation testing engine for WebAssembly/WASI modules.
+//!
+//! The engine discovers mutation candidates in a module (`MutationEngine`),
+//! executes the resulting mutants (`Executor`) and prepares the outcomes for
+//! reports (`reporter::prepare_results`). The `wasmut` command line interface
+//! is built on top of this library.
+//!
+//! ```no_run
+//! use wasmut::{Config, Executor, MutationEngine, WasmModule};
+//!
+//! let config = Config::default();
+//! let module = WasmModule::from_file("module.wasm")?;
+//! let discovery = MutationEngine::new(&config, 100)?.discover_mutation_positions(&module)?;
+//! let outcomes = Executor::new(&config).execute_mutants(&module, &discovery.locations)?;
+//! let mutants = wasmut::reporter::prepare_results(&module, outcomes)?;
+//! println!("{}", wasmut::reporter::accumulate_outcomes(&mutants).mutation_score);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! The items re-exported at the crate root, and the modules documented here,
+//! form the stable API. Hidden modules support the command line interface
+//! and may change in any release.
+
+pub mod addressresolver;
+pub mod config;
+pub mod criterion;
+pub mod error;
+pub mod executor;
+pub mod hook;
+pub mod mutation;
+pub mod operator;
+pub mod policy;
+pub mod reporter;
+pub mod runtime;
+pub mod wasmmodule;
+
+#[doc(hidden)]
+pub mod archive;
+#[doc(hidden)]
+pub mod baseline;
+#[doc(hidden)]
+pub mod cost;
+#[doc(hidden)]
+pub mod coverage;
+#[cfg(test)]
+mod fixtures;
+#[doc(hidden)]
+pub mod git;
+#[doc(hidden)]
+pub mod inputgen;
+#[doc(hidden)]
+pub mod interrupt;
+#[doc(hidden)]
+pub mod lockfile;
+#[doc(hidden)]
+pub mod notify;
+#[doc(hidden)]
+pub mod output;
+#[doc(hidden)]
+pub mod plan;
+#[doc(hidden)]
+pub mod preview;
+#[doc(hidden)]
+pub mod progress;
+#[doc(hidden)]
+pub mod reportdir;
+#[doc(hidden)]
+pub mod resultcache;
+#[doc(hidden)]
+pub mod server;
+#[doc(hidden)]
+pub mod templates;
+#[cfg(feature = "testgen")]
+#[doc(hidden)]
+pub mod testgen;
+#[doc(hidden)]
+pub mod tracediff;
+#[doc(hidden)]
+pub mod verifyoperators;
+#[doc(hidden)]
+pub mod verifysample;
+#[doc(hidden)]
+pub mod warnings;
+#[doc(hidden)]
+pub mod workspace;
+
+pub use baseline::Baseline;
+pub use config::Config;
+pub use criterion::{KillCriterion, MutantRun};
+pub use executor::{ExecutedMutant, Executor};
+pub use hook::ExecutionHook;
+pub use mutation::{Discovery, FilterScope, Mutation, MutationEngine, MutationLocation};
+pub use operator::{InstructionReplacement, OperatorRegistry};
+pub use policy::{MutationPolicy, MutationPolicyBuilder};
+pub use reporter::{AccumulatedOutcomes, MutationOutcome, ReportableMutant, RunMetadata};
+pub use runtime::{ExecutionResult, RuntimeKind};
+pub use wasmmodule::WasmModule;
@@ -0,0 +1,120 @@
+//! Machine-readable output of the `list-*` commands (`--format json`).
+
+use anyhow::Result;
+use serde::Serialize;
+
+use wasmut::{output, wasmmodule::FunctionDetails};
+
+/// A function of the module, as listed by `list-functions`
+#[derive(Serialize)]
+pub struct FunctionEntry {
+    pub name: String,
+    pub allowed: bool,
+    /// The `allowed_functions` regex that matched the function
+    pub matched_by: Option<String>,
+}
+
+/// A function of the module, as listed by `list-functions --detailed`
+#[derive(Serialize)]
+pub struct DetailedFunctionEntry {
+    pub index: u32,
+    pub name: Option<String>,
+    pub params: Vec<String>,
+    pub results: Vec<String>,
+    pub code_size: u64,
+    pub exported: bool,
+    pub allowed: bool,
+    /// The `allowed_functions` regex that matched the function
+    pub matched_by: Option<String>,
+}
+
+impl DetailedFunctionEntry {
+    pub fn new(details: FunctionDetails, matched_by: Option<String>, allowed: bool) -> Self {
+        let to_strings = |types: &[_]| types.iter().map(ToString::to_string).collect();
+
+        Self {
+            index: details.index,
+            params: to_strings(&details.params),
+            results: to_strings(&details.results),
+            name: details.name,
+            code_size: details.code_size,
+            exported: details.exported,
+            allowed,
+            matched_by,
+        }
+    }
+}
+
+/// A source file of the module, as listed by `list-files`
+#[derive(Serialize)]
+pub struct FileEntry {
+    pub name: String,
+    pub allowed: bool,
+    /// The `allowed_files` regex that matched the file
+    pub matched_by: Option<String>,
+    /// Number of mutation candidates in this file
+    pub candidates: usize,
+}
+
+/// Output of `list-files`
+#[derive(Serialize)]
+pub struct FileListing {
+    pub files: Vec<FileEntry>,
+    /// Number of mutation candidates at locations without source file information
+    pub candidates_without_source_info: usize,
+    pub total_candidates: usize,
+}
+
+/// A mutation operator, as listed by `list-operators`
+#[derive(Serialize)]
+pub struct OperatorEntry {
+    pub name: String,
+    pub enabled: bool,
+    /// The `enabled_operators` regex that matched the operator
+    pub enabled_by: Option<String>,
+    /// The `disabled_operators` regex that matched the operator
+    pub disabled_by: Option<String>,
+}
+
+/// Print a listing as pretty-printed JSON
+pub fn output_json<T: Serialize + ?Sized>(listing: &T) -> Result<()> {
+    output::output_string(format!("{}\n", serde_json::to_string_pretty(listing)?));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmut_wasm::elements::ValueType;
+
+    #[test]
+    fn detailed_function_entry() {
+        let entry = DetailedFunctionEntry::new(
+            FunctionDetails {
+                index: 3,
+                name: Some("add".into()),
+                params: vec![ValueType::I32, ValueType::I64],
+                results: vec![ValueType::I32],
+                code_size: 12,
+                exported: true,
+            },
+            Some("^add$".into()),
+            true,
+        );
+
+        let json = serde_json::to_value(entry).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "index": 3,
+                "name": "add",
+                "params": ["i32", "i64"],
+                "results": ["i32"],
+                "code_size": 12,
+                "exported": true,
+                "allowed": true,
+                "matched_by": "^add$",
+            })
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Lock files preventing concurrent runs (e.g. parallel CI jobs) from
+//! writing to the same output or cache directory.
+//!
+//! Locks are advisory locks of the operating system (`flock`) on the lock file,
+//! so they are released automatically when the owning process terminates.
+//! The lock file contains the PID of its owner, which is only used in messages.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+/// Name of the lock file inside a locked directory
+pub const LOCK_FILE_NAME: &str = ".wasmut.lock";
+
+/// Lock on a lock file, released when dropped
+#[derive(Debug)]
+pub struct LockFile {
+    path: PathBuf,
+    /// Open lock file, `None` if the lock of another run was ignored
+    file: Option<File>,
+}
+
+impl LockFile {
+    /// Lock `directory` with a lock file inside of it
+    pub fn lock_directory<P: AsRef<Path>>(directory: P, force: bool) -> Result<Self> {
+        Self::acquire(directory.as_ref().join(LOCK_FILE_NAME), force)
+    }
+
+    /// Lock the lock file `path`, creating it and its parent directories if
+    /// necessary. Fails if another process holds the lock, unless `force` is set.
+    pub fn acquire<P: Into<PathBuf>>(path: P, force: bool) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Could not create directory {parent:?}"))?;
+        }
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            // The PID of the current owner is only replaced once the lock is held
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Could not open lock file {path:?}"))?;
+
+        let locked =
+            try_lock(&file).with_context(|| format!("Could not lock lock file {path:?}"))?;
+        if !locked {
+            let mut owner = String::new();
+            let owner = match file.read_to_string(&mut owner) {
+                Ok(_) if !owner.trim().is_empty() => format!(" (PID {})", owner.trim()),
+                _ => String::new(),
+            };
+
+            if !force {
+                return Err(anyhow!(
+                    "{path:?} is held by another wasmut run{owner}, \
+                     use --force to ignore the lock"
+                ));
+            }
+            log::warn!("Ignoring lock {path:?} of another wasmut run{owner}");
+            return Ok(Self { path, file: None });
+        }
+
+        file.set_len(0)
+            .and_then(|_| file.rewind())
+            .and_then(|_| write!(file, "{}", std::process::id()))
+            .with_context(|| format!("Could not write lock file {path:?}"))?;
+
+        Ok(Self {
+            path,
+            file: Some(file),
+        })
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        // The file stays in place, removing it would allow a second run to lock
+        // a new file while a third one still waits for the old one. Closing the
+        // file releases the lock.
+        if let Some(file) = &self.file {
+            if let Err(e) = file.set_len(0) {
+                log::warn!("Could not clear lock file {:?}: {e}", self.path);
+            }
+        }
+    }
+}
+
+/// Try to lock `file` exclusively, without waiting.
+/// Returns false if another open file description holds a lock.
+#[cfg(unix)]
+fn try_lock(file: &File) -> std::io::Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    let result = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    if result == 0 {
+        return Ok(true);
+    }
+
+    let error = std::io::Error::last_os_error();
+    if error.raw_os_error() == Some(libc::EWOULDBLOCK) {
+        Ok(false)
+    } else {
+        Err(error)
+    }
+}
+
+/// Without advisory locks, concurrent runs are not detected
+#[cfg(not(unix))]
+fn try_lock(_file: &File) -> std::io::Result<bool> {
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owner(dir: &Path) -> String {
+        std::fs::read_to_string(dir.join(LOCK_FILE_NAME)).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lock_is_exclusive() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let lock = LockFile::lock_directory(dir.path(), false)?;
+        assert_eq!(owner(dir.path()), std::process::id().to_string());
+
+        let error = LockFile::lock_directory(dir.path(), false).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains(&format!("(PID {})", std::process::id())));
+
+        drop(lock);
+        assert_eq!(owner(dir.path()), "");
+        LockFile::lock_directory(dir.path(), false)?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_one_concurrent_run_gets_the_lock() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let barrier = std::sync::Barrier::new(8);
+
+        let locks: Vec<Option<LockFile>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        LockFile::lock_directory(dir.path(), false).ok()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        assert_eq!(locks.iter().filter(|lock| lock.is_some()).count(), 1);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn force_ignores_lock() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let _lock = LockFile::lock_directory(dir.path(), false)?;
+        let forced = LockFile::lock_directory(dir.path().join("."), true)?;
+        drop(forced);
+
+        // The forced run did not own the lock, so it is still held
+        assert_eq!(owner(dir.path()), std::process::id().to_string());
+        assert!(LockFile::lock_directory(dir.path(), false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn stale_lock_is_taken_over() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        // A lock file left behind by a terminated process is not locked
+        std::fs::write(dir.path().join(LOCK_FILE_NAME), "4294967295")?;
+        let _lock = LockFile::lock_directory(dir.path(), false)?;
+        assert_eq!(owner(dir.path()), std::process::id().to_string());
+        Ok(())
+    }
+}
@@ -1,42 +1,113 @@
-mod addressresolver;
 mod cliarguments;
-mod config;
-mod executor;
-mod mutation;
-mod operator;
-mod output;
-mod policy;
-mod reporter;
-mod runtime;
-mod templates;
-mod wasmmodule;
+// The in-memory test modules of the library, which refer to `crate::wasmmodule`
+#[cfg(test)]
+#[path = "fixtures.rs"]
+#[allow(dead_code)]
+mod fixtures;
+mod listing;
+#[cfg(test)]
+use wasmut::wasmmodule;
 
 use env_logger::Builder;
 use log::{error, LevelFilter};
 
-use anyhow::{bail, Context, Result};
-use cliarguments::Output;
-use operator::OperatorRegistry;
+use anyhow::{anyhow, bail, Context, Result};
+use cliarguments::{CLIArguments, CLICommand, Format, Output, WorkspaceCommand};
+use wasmut::operator::OperatorRegistry;
+#[cfg(feature = "testgen")]
+use wasmut::testgen;
 
-use crate::{
-    cliarguments::{CLIArguments, CLICommand},
-    reporter::json::JSONReporter,
-};
 use colored::*;
+use is_terminal::IsTerminal;
 use log::*;
-use reporter::{cli::CLIReporter, html::HTMLReporter};
-use std::{path::Path, time::Instant};
+use regex::RegexSet;
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+use wasmut::reporter::{
+    self, advice::AdviceContext, cli::CLIReporter, github::GitHubReporter, gitlab::GitLabReporter,
+    html::HTMLReporter, redact,
+};
+use wasmut::{
+    archive,
+    baseline::Baseline,
+    coverage, error,
+    error::ErrorKind,
+    executor,
+    executor::RunEstimate,
+    inputgen, mutation, output, plan, policy, preview, reportdir,
+    reporter::{
+        json::JSONReporter,
+        manifest::{HostInfo, ModuleProvenance, RunManifest, RunProvenance},
+        workspace::TargetSummary,
+        AccumulatedOutcomes, ReportableMutant, RunMetadata,
+    },
+    server, tracediff, verifyoperators, verifysample, warnings,
+    warnings::WarningCategory,
+    workspace::{Target, Workspace},
+};
 
-use crate::{
-    config::Config, executor::Executor, mutation::MutationEngine, policy::MutationPolicy,
+use wasmut::{
+    config::Config,
+    executor::Executor,
+    lockfile::LockFile,
+    mutation::{MutantIds, MutationEngine},
+    policy::{ExecutionPolicy, MutationPolicy},
+    reportdir::ReportDirectory,
+    runtime::ExecutionResult,
     wasmmodule::WasmModule,
 };
 
+/// Load a module. If enabled, source locations are resolved using the cached address index.
+fn load_module<'a>(wasmfile: &'a str, config: &Config) -> Result<WasmModule<'a>> {
+    let mut module = WasmModule::from_file(wasmfile)?;
+    module.require_uninstrumented()?;
+
+    if config.cache().address_index() {
+        module.load_address_index(&config.cache().directory())?;
+    }
+
+    Ok(module)
+}
+
+/// Lock the cache directory while it may be written to, i.e. if the address index
+/// or the result cache is enabled
+fn lock_cache(config: &Config, force: bool) -> Result<Option<LockFile>> {
+    if !config.cache().address_index() && !config.cache().results() {
+        return Ok(None);
+    }
+
+    LockFile::lock_directory(config.cache().directory(), force).map(Some)
+}
+
 /// List all functions of a given WebAssembly module.
-fn list_functions(wasmfile: &str, config: &Config) -> Result<()> {
-    let module = WasmModule::from_file(wasmfile)?;
+fn list_functions(wasmfile: &str, config: &Config, detailed: bool, format: &Format) -> Result<()> {
+    let module = load_module(wasmfile, config)?;
     let policy = MutationPolicy::from_config(config)?;
 
+    if detailed {
+        return list_functions_detailed(&module, &policy, format);
+    }
+
+    if let Format::Json = format {
+        let mut functions: Vec<String> = module.functions().into_iter().collect();
+        functions.sort();
+
+        let entries: Vec<listing::FunctionEntry> = functions
+            .into_iter()
+            .map(|function| listing::FunctionEntry {
+                allowed: policy.check_function(&function),
+                matched_by: policy
+                    .matching_function_pattern(&function)
+                    .map(String::from),
+                name: function,
+            })
+            .collect();
+        return listing::output_json(&entries);
+    }
+
     for function in module.functions() {
         let check_result_str = if policy.check_function(&function) {
             "allowed: ".green()
@@ -51,31 +122,457 @@ fn list_functions(wasmfile: &str, config: &Config) -> Result<()> {
     Ok(())
 }
 
-/// List all source files that were used to build a given WebAssembly module.
-fn list_files(wasmfile: &str, config: &Config) -> Result<()> {
-    let module = WasmModule::from_file(wasmfile)?;
-    let policy = MutationPolicy::from_config(config)?;
+/// List all functions defined in a module, with their index, signature, code size
+/// and whether they are exported.
+fn list_functions_detailed(
+    module: &WasmModule,
+    policy: &MutationPolicy,
+    format: &Format,
+) -> Result<()> {
+    let check = |name: &Option<String>| match name {
+        Some(name) => policy.check_function(name),
+        None => false,
+    };
+
+    if let Format::Json = format {
+        let entries: Vec<listing::DetailedFunctionEntry> = module
+            .function_details()?
+            .into_iter()
+            .map(|function| {
+                let matched_by = function
+                    .name
+                    .as_ref()
+                    .and_then(|name| policy.matching_function_pattern(name))
+                    .map(String::from);
+                let allowed = check(&function.name);
+                listing::DetailedFunctionEntry::new(function, matched_by, allowed)
+            })
+            .collect();
+        return listing::output_json(&entries);
+    }
 
-    for file in module.source_files() {
-        let check_result_str = if policy.check_file(&file) {
+    output::output_string(format!(
+        "{0:9}{1:>6} {2:>10}  {3:8}  {4}\n",
+        "", "Index", "Size", "Exported", "Function"
+    ));
+
+    for function in module.function_details()? {
+        let check_result_str = if check(&function.name) {
             "allowed: ".green()
         } else {
             "denied:  ".red()
         };
+        let exported = if function.exported { "yes" } else { "" };
+
+        output::output_string(format!(
+            "{check_result_str}{0:>6} {1:>8} B  {exported:8}  {2} {3}\n",
+            function.index,
+            function.code_size,
+            function.name.as_deref().unwrap_or("<unnamed>"),
+            function.signature()
+        ));
+    }
 
-        // Use our own output method so that we can capture it in unit tests
-        output::output_string(format!("{check_result_str}{file}\n"));
+    Ok(())
+}
+
+/// List all source files that were used to build a given WebAssembly module.
+fn list_files(wasmfile: &str, config: &Config, format: &Format) -> Result<()> {
+    let module = load_module(wasmfile, config)?;
+    let policy = MutationPolicy::from_config(config)?;
+    let candidates = MutationEngine::new(config, 100)?.count_candidates_per_file(&module)?;
+
+    let without_source_info = candidates.get(&None).copied().unwrap_or(0);
+    let total: usize = candidates.values().sum();
+
+    if let Format::Json = format {
+        let mut files: Vec<String> = module.source_files().into_iter().collect();
+        files.sort();
+
+        let files = files
+            .into_iter()
+            .map(|file| listing::FileEntry {
+                allowed: policy.check_file(&file),
+                matched_by: policy.matching_file_pattern(&file).map(String::from),
+                candidates: candidates.get(&Some(file.clone())).copied().unwrap_or(0),
+                name: file,
+            })
+            .collect();
+
+        return listing::output_json(&listing::FileListing {
+            files,
+            candidates_without_source_info: without_source_info,
+            total_candidates: total,
+        });
     }
 
+    for file in module.source_files() {
+        if policy.check_file(&file) {
+            let count = candidates.get(&Some(file.clone())).copied().unwrap_or(0);
+            output::output_string(format!(
+                "{}{file} ({count} mutation candidates)\n",
+                "allowed: ".green()
+            ));
+        } else {
+            // Use our own output method so that we can capture it in unit tests
+            output::output_string(format!("{}{file}\n", "denied:  ".red()));
+        }
+    }
+
+    if without_source_info > 0 {
+        output::output_string(format!(
+            "{without_source_info} mutation candidates at locations without source file information\n"
+        ));
+    }
+
+    output::output_string(format!(
+        "Total: {total} mutation candidates with the current operator configuration\n"
+    ));
+
     Ok(())
 }
 
-/// List all mutation operators.
-fn list_operators(config: &Config) -> Result<()> {
-    let enabled_ops = config.operators().enabled_operators();
-    let ops = enabled_ops.iter().map(String::as_str).collect::<Vec<_>>();
+/// Show per-function coverage and execution cost of the unmutated module.
+fn coverage(wasmfile: &str, config: &Config, format: &Format) -> Result<()> {
+    let module = load_module(wasmfile, config)?;
+    let policy = MutationPolicy::from_config(config)?;
+    let executor = Executor::new(config);
 
-    let registry = OperatorRegistry::new(&ops)?;
+    let trace_points = executor
+        .get_trace_points(&module)
+        .context(ErrorKind::Baseline)?;
+    let functions = coverage::function_coverage(&module, &trace_points, &policy)?;
+    let total: u64 = functions.iter().map(|f| f.executed_instructions).sum();
+
+    match format {
+        Format::Text => {
+            output::output_string(format!(
+                "{0:>14} {1:>9} {2:>9}  {3}\n",
+                "Executed", "Coverage", "Branches", "Function"
+            ));
+            for function in &functions {
+                let check_result_str = if function.allowed {
+                    "allowed: ".green()
+                } else {
+                    "denied:  ".red()
+                };
+
+                let branch_coverage = match function.branch_coverage() {
+                    Some(coverage) => format!("{coverage:.1}%"),
+                    None => "-".into(),
+                };
+
+                output::output_string(format!(
+                    "{0:>14} {1:>8.1}% {branch_coverage:>9}  {check_result_str}{2}\n",
+                    function.executed_instructions,
+                    function.coverage(),
+                    function.function
+                ));
+            }
+            output::output_string(format!("{total:>14} {0:>9} {0:>9}  Total\n", ""));
+        }
+        Format::Json => {
+            let report = coverage::CoverageReport {
+                functions: &functions,
+                total_executed_instructions: total,
+            };
+            output::output_string(format!("{}\n", serde_json::to_string_pretty(&report)?));
+        }
+    }
+
+    Ok(())
+}
+
+/// Number of functions shown by `trace-diff` in text format
+const TRACE_DIFF_FUNCTIONS: usize = 20;
+
+/// Compare the basic blocks executed by a single mutant with the unmutated module
+fn trace_diff(wasmfile: &str, config: &Config, mutant_id: &str, format: &Format) -> Result<()> {
+    let module = load_module(wasmfile, config)?;
+    let mutator = MutationEngine::new(config, 100)?;
+    let locations = mutator.discover_mutation_positions(&module)?.locations;
+
+    let mutant_ids = MutantIds::new(&module)?;
+    let (selected, _, _) = mutation::select_mutants(locations, &mutant_ids, &[mutant_id.into()]);
+    let location = match selected.first() {
+        Some(location) => location,
+        None => bail!("Mutant ID {mutant_id} does not match any mutant"),
+    };
+    let operator = &location.mutations[0].operator;
+
+    let executor = Executor::new(config);
+    let (result, baseline) = executor
+        .trace_blocks(&module, None, ExecutionPolicy::RunUntilReturn)
+        .context(ErrorKind::Baseline)?;
+    let limit = match result {
+        ExecutionResult::ProcessExit {
+            exit_code: 0,
+            execution_cost,
+        } => executor.timeout(execution_cost),
+        _ => {
+            return Err(anyhow!("Module without any mutations failed: {result:?}"))
+                .context(ErrorKind::Baseline)
+        }
+    };
+
+    let (result, mutant) = executor.trace_blocks(
+        &module,
+        Some((location, 0)),
+        ExecutionPolicy::RunUntilLimit { limit },
+    )?;
+
+    let diff = tracediff::TraceDiff::new(&module, &baseline, &mutant);
+
+    let outcome = match result {
+        ExecutionResult::ProcessExit { exit_code: 0, .. } => "terminated normally".to_string(),
+        ExecutionResult::ProcessExit { exit_code, .. } => format!("exited with code {exit_code}"),
+        ExecutionResult::Timeout => "timed out".into(),
+        ExecutionResult::Error { .. } => "trapped".into(),
+        ExecutionResult::Skipped => "was skipped".into(),
+        ExecutionResult::NotRun => "was not run".into(),
+        ExecutionResult::InternalError { message } => format!("failed internally: {message}"),
+    };
+
+    match format {
+        Format::Text => {
+            output::output_string(format!(
+                "Mutant {mutant_id}: {}, {outcome}\n",
+                operator.description()
+            ));
+            output::output_string(format!(
+                "Executed blocks: {} (baseline), {} (mutant){}\n\n",
+                diff.baseline_blocks,
+                diff.mutant_blocks,
+                if diff.truncated { ", truncated" } else { "" }
+            ));
+
+            match &diff.first_divergence {
+                Some(divergence) => {
+                    output::output_string(format!(
+                        "First divergence after {} blocks:\n",
+                        divergence.index
+                    ));
+                    for (name, block) in [
+                        ("baseline", &divergence.baseline),
+                        ("mutant", &divergence.mutant),
+                    ] {
+                        let block = match block {
+                            Some(block) => format!("{:#x} {}", block.offset, block.location),
+                            None => "<terminated>".into(),
+                        };
+                        output::output_string(format!("  {name:<9} {block}\n"));
+                    }
+                }
+                None => output::output_string("Mutant executed the same blocks as the baseline\n"),
+            }
+
+            if !diff.functions.is_empty() {
+                // Only show the functions with the largest differences
+                let mut functions: Vec<_> = diff.functions.iter().collect();
+                functions.sort_by_key(|f| std::cmp::Reverse(f.baseline.abs_diff(f.mutant)));
+
+                output::output_string(format!(
+                    "\n{0:>12} {1:>12}  {2}\n",
+                    "Baseline", "Mutant", "Function"
+                ));
+                for function in functions.iter().take(TRACE_DIFF_FUNCTIONS) {
+                    output::output_string(format!(
+                        "{0:>12} {1:>12}  {2}\n",
+                        function.baseline, function.mutant, function.function
+                    ));
+                }
+                if functions.len() > TRACE_DIFF_FUNCTIONS {
+                    output::output_string(format!(
+                        "{0:>26}  ... {1} more, use --format json to show all\n",
+                        "",
+                        functions.len() - TRACE_DIFF_FUNCTIONS
+                    ));
+                }
+            }
+        }
+        Format::Json => {
+            output::output_string(format!("{}\n", serde_json::to_string_pretty(&diff)?));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate every mutant of the module on its own
+fn verify_operators(wasmfile: &str, config: &Config, format: &Format) -> Result<()> {
+    let module = load_module(wasmfile, config)?;
+    let mutator = MutationEngine::new(config, 100)?;
+    let locations = mutator.discover_mutation_positions(&module)?.locations;
+
+    let verification = verifyoperators::Verification::new(&module, &locations)?;
+
+    match format {
+        Format::Text => {
+            output::output_string(format!(
+                "Validated {} mutants, {} invalid\n\n",
+                verification.mutants,
+                verification.invalid_mutants.len()
+            ));
+            output::output_string(format!("{:>10}  {:>10}  Operator\n", "Mutants", "Invalid"));
+            for operator in &verification.operators {
+                let invalid = format!("{:>10}", operator.invalid);
+                output::output_string(format!(
+                    "{:>10}  {}  {}\n",
+                    operator.mutants,
+                    if operator.invalid > 0 {
+                        invalid.red()
+                    } else {
+                        invalid.normal()
+                    },
+                    operator.operator
+                ));
+            }
+
+            if !verification.is_valid() {
+                output::output_string("\nInvalid mutants:\n");
+            }
+            for mutant in &verification.invalid_mutants {
+                output::output_string(format!(
+                    "  [{}] {:#x} {}: {}\n      {}\n",
+                    mutant.id, mutant.offset, mutant.location, mutant.description, mutant.error
+                ));
+            }
+        }
+        Format::Json => {
+            output::output_string(format!(
+                "{}\n",
+                serde_json::to_string_pretty(&verification)?
+            ));
+        }
+    }
+
+    if !verification.is_valid() {
+        bail!(
+            "{} mutants produce invalid modules, disable the affected operators",
+            verification.invalid_mutants.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Show the mutants of the instructions of a source line
+fn preview(wasmfile: &str, config: &Config, file: &str, line: u64, format: &Format) -> Result<()> {
+    let module = load_module(wasmfile, config)?;
+    let locations = MutationEngine::new(config, 100)?
+        .discover_mutation_positions(&module)?
+        .locations;
+
+    let preview = preview::Preview::new(&module, &locations, &module.line_index()?, file, line)?;
+
+    match format {
+        Format::Text => {
+            if preview.instructions.is_empty() {
+                output::output_string(format!(
+                    "No instructions are mapped to {}:{}\n",
+                    preview.file, preview.line
+                ));
+                return Ok(());
+            }
+
+            output::output_string(format!(
+                "{}:{}: {} instructions, {} mutants\n",
+                preview.file,
+                preview.line,
+                preview.instructions.len(),
+                preview.mutants()
+            ));
+
+            for instruction in &preview.instructions {
+                output::output_string(format!(
+                    "\n{:#x}  {}  ({})\n",
+                    instruction.offset,
+                    instruction.instruction,
+                    instruction.function.as_deref().unwrap_or("<unknown>")
+                ));
+                for mutant in &instruction.mutants {
+                    output::output_string(format!(
+                        "  [{}] {} -> {}\n",
+                        mutant.id,
+                        mutant.operator,
+                        mutant.replacement.join(" ")
+                    ));
+                }
+            }
+        }
+        Format::Json => {
+            output::output_string(format!("{}\n", serde_json::to_string_pretty(&preview)?));
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute the unmutated module and save the results to `output`
+fn record_baseline(wasmfile: &str, config: &Config, output: &str, coverage: bool) -> Result<()> {
+    let module = load_module(wasmfile, config)?;
+    let executor = Executor::new(config);
+
+    let baseline = executor
+        .record_baseline(&module, coverage)
+        .context(ErrorKind::Baseline)?;
+    baseline.save(output)?;
+
+    info!("Exit code       {}", baseline.exit_code);
+    info!(
+        "Execution cost  {}",
+        executor.cost_formatter().format(baseline.execution_cost)
+    );
+    info!("Execution time  {} ms", baseline.duration);
+    if let Some(coverage) = &baseline.coverage {
+        info!(
+            "Coverage        {} instructions executed",
+            coverage.covered_instructions()
+        );
+    }
+    info!("Baseline saved to {output}");
+
+    if baseline.exit_code != 0 {
+        return Err(anyhow!(
+            "Module without any mutations returned exit code {}",
+            baseline.exit_code
+        ))
+        .context(ErrorKind::Baseline);
+    }
+
+    Ok(())
+}
+
+/// List all mutation operators.
+fn list_operators(config: &Config, format: &Format) -> Result<()> {
+    let registry = OperatorRegistry::from_config(config.operators())?;
+
+    if let Format::Json = format {
+        // Patterns have already been validated by the registry
+        let enabled_patterns = RegexSet::new(config.operators().enabled_operators())?;
+        let disabled_patterns = RegexSet::new(config.operators().disabled_operators())?;
+
+        let mut entries: Vec<listing::OperatorEntry> = registry
+            .enabled_operators()
+            .iter()
+            .map(|name| (name, true))
+            .chain(
+                registry
+                    .disabled_operators()
+                    .iter()
+                    .map(|name| (name, false)),
+            )
+            .map(|(name, enabled)| listing::OperatorEntry {
+                name: name.clone(),
+                enabled,
+                enabled_by: policy::first_match(&enabled_patterns, name).map(String::from),
+                disabled_by: policy::first_match(&disabled_patterns, name).map(String::from),
+            })
+            .collect();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        return listing::output_json(&entries);
+    }
 
     for op_name in registry.enabled_operators() {
         let check_result_str = "enabled:  ".green();
@@ -92,43 +589,630 @@ fn list_operators(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Ask the user whether a mutation run with the given estimate should be started.
+///
+/// If stdin is not a terminal (e.g. in CI), the run is started without asking.
+fn confirm_run(estimate: &RunEstimate) -> bool {
+    let stdin = std::io::stdin();
+
+    if !stdin.is_terminal() {
+        return true;
+    }
+
+    eprint!("{estimate}. Continue? [y/N] ");
+    let _ = std::io::stderr().flush();
+
+    let mut answer = String::new();
+    if stdin.lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Results of executing all mutants of a module
+struct MutationRun {
+    executed_mutants: Vec<ReportableMutant>,
+    unused_operators: Vec<String>,
+    metadata: RunMetadata,
+    provenance: RunProvenance,
+}
+
+/// Which of the discovered mutants are executed
+struct MutantSelection<'a> {
+    /// Percentage of mutants that are executed
+    sample_threshold: i32,
+
+    /// Seed for selecting the executed mutants, random if not set
+    seed: Option<u32>,
+
+    /// Stable IDs of the mutants to execute. If empty, all mutants are executed
+    mutant_ids: &'a [String],
+}
+
+impl MutantSelection<'_> {
+    /// Execute all mutants
+    fn all() -> Self {
+        Self {
+            sample_threshold: 100,
+            seed: None,
+            mutant_ids: &[],
+        }
+    }
+}
+
+/// How mutants are executed
+struct ExecutionOptions<'a> {
+    /// Do not ask for confirmation before executing mutants
+    yes: bool,
+
+    /// Baseline recorded by `wasmut baseline`, replacing the baseline run
+    baseline: Option<&'a Baseline>,
+
+    /// Abort if the module has no line information
+    require_debug_info: bool,
+
+    /// Do not start new mutants once this duration has passed since the start of the run
+    max_runtime: Option<Duration>,
+
+    /// Stop starting new mutants on the first Ctrl-C
+    interruptible: bool,
+
+    /// Ignore locks of other runs on the output and cache directories
+    force: bool,
+
+    /// Number of mutants re-executed one by one after a meta-mutant run
+    verify_sample: Option<usize>,
+}
+
+/// How the report of a run is written
+struct ReportOptions<'a> {
+    /// Package the report into this zip archive
+    archive: Option<&'a str>,
+
+    /// Replace source paths and function names by pseudonyms
+    redact_paths: bool,
+}
+
+/// Discover and execute all selected mutants of a module.
+fn run_mutants(
+    module: &WasmModule,
+    config: &Config,
+    selection: &MutantSelection,
+    options: &ExecutionOptions,
+    start: Instant,
+) -> Result<MutationRun> {
+    let started = chrono::Local::now();
+    let mut mutator = MutationEngine::new(config, selection.sample_threshold)?;
+    if let Some(seed) = selection.seed {
+        mutator = mutator.with_seed(seed);
+    }
+    let discovery = mutator.discover_mutation_positions(module)?;
+    let provenance = RunProvenance {
+        wasmut_version: env!("CARGO_PKG_VERSION").into(),
+        started: started.to_rfc3339(),
+        seed: mutator.seed(),
+        sample_threshold: selection.sample_threshold,
+        mutant_ids: selection.mutant_ids.to_vec(),
+        operators: discovery
+            .operator_usage
+            .counts()
+            .map(|(name, _)| name.into())
+            .collect(),
+        module: ModuleProvenance::of(module),
+        host: HostInfo::current(),
+    };
+    let mut mutations = discovery.locations;
+    let unused_operators = discovery.operator_usage.unused();
+    let filtered = discovery.filtered;
+    let scope = discovery.scope;
+
+    let partial = !selection.mutant_ids.is_empty();
+    let mut unselected = Vec::new();
+    if partial {
+        let (selected, others, unmatched) =
+            mutation::select_mutants(mutations, &MutantIds::new(module)?, selection.mutant_ids);
+
+        for id in unmatched {
+            warnings::warn(
+                WarningCategory::UnknownMutantId,
+                format!("Mutant ID {id} does not match any mutant"),
+            );
+        }
+
+        if selected.is_empty() {
+            bail!("None of the given mutant IDs matches a mutant");
+        }
+
+        mutations = selected;
+        unselected = others;
+    }
+
+    let mut executor = Executor::new(config);
+    if !options.yes {
+        executor = executor.with_confirmation(Box::new(confirm_run));
+    }
+    if let Some(baseline) = options.baseline {
+        baseline.check(module.bytes())?;
+        executor = executor.with_baseline(baseline);
+    }
+    if let Some(max_runtime) = options.max_runtime {
+        executor = executor.with_deadline(start + max_runtime);
+    }
+    if options.interruptible {
+        executor = executor.with_interruption();
+    }
+    if config.cache().results() {
+        executor = executor.with_result_cache(config.cache().directory());
+    }
+    let mut results = executor.execute_mutants(module, &mutations)?;
+
+    if let Some(sample_size) = options.verify_sample {
+        check_sample(config, module, &mutations, &results, sample_size)?;
+    }
+
+    // Mutants that were not selected are reported as not run
+    results.extend(executor::not_run_mutants(&unselected));
+
+    let mut executed_mutants = reporter::prepare_results(module, results)?;
+    if config.report().group_variants() {
+        executed_mutants = reporter::group_variants(executed_mutants);
+    }
+
+    Ok(MutationRun {
+        executed_mutants,
+        unused_operators,
+        metadata: executor
+            .run_metadata(start.elapsed())
+            .with_partial(partial)
+            .with_filtered_mutants(filtered)
+            .with_scope(scope),
+        provenance,
+    })
+}
+
+/// Execute a random sample of the mutants of a meta-mutant run again one by one,
+/// and fail if any of their outcomes differs.
+fn check_sample(
+    config: &Config,
+    module: &WasmModule,
+    locations: &[mutation::MutationLocation],
+    results: &[executor::ExecutedMutant],
+    sample_size: usize,
+) -> Result<()> {
+    if !config.engine().meta_mutant() {
+        warn!("--verify-sample only applies to meta-mutant runs, ignoring");
+        return Ok(());
+    }
+
+    info!("Executing {sample_size} randomly picked mutants again one by one");
+    let verification =
+        verifysample::verify_sample(config, module, locations, results, sample_size)?;
+
+    for mismatch in &verification.mismatches {
+        error!(
+            "Mutant {} ({} at offset {:#x}): {:?} in the meta-mutant, {:?} when executed one by one",
+            mismatch.id, mismatch.operator, mismatch.offset, mismatch.meta_mutant, mismatch.one_by_one
+        );
+    }
+
+    if !verification.mismatches.is_empty() {
+        return Err(anyhow!(
+            "{} of {} sampled mutants have a different outcome when executed one by one",
+            verification.mismatches.len(),
+            verification.checked
+        ))
+        .context(ErrorKind::SampleMismatch);
+    }
+
+    info!(
+        "Outcomes of all {} sampled mutants confirmed by one-by-one execution",
+        verification.checked
+    );
+    Ok(())
+}
+
 /// Find, apply and execute mutations.
 fn mutate(
     wasmfile: &str,
     config: &Config,
     report_type: &Output,
-    output_directory: &str,
-    sample_threshold: i32,
-) -> Result<()> {
+    output_pattern: &str,
+    selection: &MutantSelection,
+    options: &ExecutionOptions,
+    report_options: &ReportOptions,
+) -> Result<AccumulatedOutcomes> {
     let start = Instant::now();
 
-    let module = WasmModule::from_file(wasmfile)?;
-    let mutator = MutationEngine::new(config, sample_threshold)?;
-    let mutations = mutator.discover_mutation_positions(&module)?;
+    let output_directory = ReportDirectory::new(output_pattern, wasmfile, chrono::Local::now())?;
+    let _output_lock = match report_type {
+        Output::Html => Some(LockFile::acquire(
+            output_directory.lock_path(),
+            options.force,
+        )?),
+        _ => None,
+    };
+
+    // Held until the outcomes of the mutants are cached
+    let cache_lock = lock_cache(config, options.force)?;
+    let module = load_module(wasmfile, config)?;
+
+    let debug_info = module.debug_info();
+    if options.require_debug_info {
+        debug_info.require()?;
+    } else if debug_info.is_stripped() && !config.filter().require_source_info() {
+        warnings::warn(
+            WarningCategory::MissingSourceInfo,
+            "Module has no line information, mutants are reported by function and code offset",
+        );
+    }
 
-    let executor = Executor::new(config);
-    let results = executor.execute_mutants(&module, &mutations)?;
+    let MutationRun {
+        mut executed_mutants,
+        unused_operators,
+        metadata,
+        provenance,
+    } = run_mutants(&module, config, selection, options, start)?;
+    drop(cache_lock);
 
-    let executed_mutants = reporter::prepare_results(&module, results)?;
+    #[cfg(feature = "sqlite")]
+    store_results(config, &module, &metadata, &executed_mutants)?;
 
-    let duration = start.elapsed();
+    let filtered = metadata.filtered_mutants;
+    let archive = report_options.archive;
+
+    // Paths are only redacted in reports that are meant to be shared
+    let redact = report_options.redact_paths && matches!(report_type, Output::Html | Output::Json);
+    if report_options.redact_paths && !redact {
+        warn!("--redact-paths is only supported for html and json reports, ignoring");
+    }
+    let module_name = if redact {
+        executed_mutants = redact::redact_mutants(executed_mutants);
+        redact::redact_file(wasmfile)
+    } else {
+        wasmfile.to_string()
+    };
+
+    // The CLI report includes the metadata in its summary
+    if !matches!(report_type, Output::Console) {
+        log::info!("Run: {}", metadata.summary());
+    }
 
     match report_type {
         Output::Console => {
-            let reporter = CLIReporter::new(config.report())?;
+            let reporter = CLIReporter::new(config.report())?
+                .with_unused_operators(unused_operators)
+                .with_metadata(metadata)
+                .with_advice(AdviceContext::from_config(config));
+            reporter.report(&executed_mutants)?;
+
+            if archive.is_some() {
+                warn!("--archive is only supported for html, json and gitlab reports, ignoring");
+            }
+        }
+        Output::Github => {
+            GitHubReporter::new(config.report())?.report(&executed_mutants)?;
+
+            if archive.is_some() {
+                warn!("--archive is only supported for html, json and gitlab reports, ignoring");
+            }
+        }
+        Output::Gitlab => {
+            let reporter = GitLabReporter::new(config.report(), wasmfile)?;
             reporter.report(&executed_mutants)?;
+
+            if let Some(archive) = archive {
+                let report = reporter.report_to_string(&executed_mutants)?;
+                archive::archive_content(
+                    reporter::gitlab::REPORT_FILE_NAME,
+                    report.as_bytes(),
+                    archive,
+                )?;
+            }
         }
         Output::Html => {
-            let reporter = HTMLReporter::new(config.report(), Path::new(output_directory))?;
+            let mut reporter = HTMLReporter::new(
+                config.report(),
+                output_directory.path(),
+                &module_name,
+                &metadata,
+            )?
+            .with_unused_operators(unused_operators)
+            .with_advice(AdviceContext::from_config(config));
+            if redact {
+                reporter = reporter.with_redacted_paths();
+            }
             reporter.report(&executed_mutants)?;
+
+            // The configuration contains paths as well
+            if redact {
+                info!("Not writing the run manifest into a report with redacted paths");
+            } else {
+                RunManifest::new(provenance, config).save(output_directory.path())?;
+            }
+
+            output_directory.link_latest()?;
+            if let Some(keep_last) = config.report().keep_last() {
+                output_directory.prune(keep_last)?;
+            }
+
+            if let Some(archive) = archive {
+                archive::archive_directory(output_directory.path(), archive)?;
+            }
         }
         Output::Json => {
-            let reporter = JSONReporter::new(config.report(), wasmfile, &duration)?;
+            let mut reporter = JSONReporter::new(config.report(), &module_name, &metadata)?
+                .with_unused_operators(unused_operators);
+            if redact {
+                reporter = reporter.with_redacted_paths();
+            }
             reporter.report(&executed_mutants)?;
+
+            if let Some(archive) = archive {
+                let report = reporter.report_to_string(&executed_mutants)?;
+                archive::archive_content("report.json", report.as_bytes(), archive)?;
+            }
+        }
+    }
+
+    Ok(reporter::accumulate_outcomes(&executed_mutants).with_filtered(filtered))
+}
+
+/// Fail if the mutation score is below `threshold`
+fn check_mutation_score(outcomes: &AccumulatedOutcomes, threshold: f32) -> Result<()> {
+    let score = outcomes.mutation_score;
+
+    // Without any scored mutant, the score says nothing about the tests
+    let scored =
+        outcomes.alive + outcomes.timeout + outcomes.killed + outcomes.error + outcomes.skipped;
+    if scored == 0 || score.is_nan() {
+        return Err(anyhow!(
+            "No mutant was executed, the mutation score cannot be checked \
+            against the required {threshold:.1}%"
+        ))
+        .context(ErrorKind::ScoreBelowThreshold);
+    }
+
+    if score < threshold {
+        return Err(anyhow!(
+            "Mutation score {score:.1}% is below the required {threshold:.1}%"
+        ))
+        .context(ErrorKind::ScoreBelowThreshold);
+    }
+
+    Ok(())
+}
+
+/// Post a summary of a finished run to the configured webhooks
+#[cfg(feature = "notify")]
+fn notify_run(config: &Config, wasmfile: &str, result: &Result<AccumulatedOutcomes>) {
+    use wasmut::notify::{self, RunNotification};
+
+    if !config.notify().enabled() {
+        return;
+    }
+
+    let notification = match result {
+        Ok(outcomes) => RunNotification::completed(wasmfile, outcomes),
+        // Runs aborted before any mutant was executed are not reported
+        Err(e) if e.downcast_ref::<ErrorKind>() == Some(&ErrorKind::Aborted) => return,
+        Err(e) => RunNotification::failed(wasmfile, e),
+    };
+    notify::send(config.notify(), &notification);
+}
+
+/// IDs of the mutants that survived in the latest run of `wasmfile` stored in the result database
+#[cfg(feature = "sqlite")]
+fn stored_survivors(config: &Config, wasmfile: &str) -> Result<Vec<String>> {
+    use reporter::{database::ResultStore, MutationOutcome};
+
+    let path = match config.database().path() {
+        Some(path) => path,
+        None => {
+            return Err(anyhow!(
+                "--rerun-survivors requires a result database (database.path)"
+            ))
+            .context(ErrorKind::Config)
         }
+    };
+
+    let store = ResultStore::open(path)?;
+    let run = store
+        .latest_run(wasmfile)?
+        .with_context(|| format!("No run of {wasmfile} is stored in {path}"))?;
+    let survivors = store.mutant_ids(run.id, MutationOutcome::Alive)?;
+    if survivors.is_empty() {
+        bail!(
+            "No mutant survived in run {} of {wasmfile} ({})",
+            run.id,
+            run.date
+        );
     }
 
-    log::info!("Execution time  {:?}s", duration.as_secs());
+    info!(
+        "Re-checking {} mutants that survived in run {} ({})",
+        survivors.len(),
+        run.id,
+        run.date
+    );
+    Ok(survivors)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn stored_survivors(_config: &Config, _wasmfile: &str) -> Result<Vec<String>> {
+    Err(anyhow!(
+        "--rerun-survivors requires SQLite support, rebuild wasmut with `--features sqlite`"
+    ))
+    .context(ErrorKind::Config)
+}
+
+/// Store the results of a run in the result database, if one is configured
+#[cfg(feature = "sqlite")]
+fn store_results(
+    config: &Config,
+    module: &WasmModule,
+    metadata: &RunMetadata,
+    executed_mutants: &[ReportableMutant],
+) -> Result<()> {
+    use reporter::database::{ResultStore, RunModule};
+
+    if let Some(path) = config.database().path() {
+        // Hash the bytecode that was tested, the file may have changed since
+        let module = RunModule {
+            path: module.path(),
+            hash: &Baseline::hash_module(module.bytes()),
+        };
+
+        let run_id = ResultStore::open(path)?.record_run(&module, metadata, executed_mutants)?;
+        info!("Stored results as run {run_id} in {path}");
+    }
+
+    Ok(())
+}
+
+/// Mutate a single target of a workspace and write its report.
+fn mutate_target(
+    target: &Target,
+    report_type: &Output,
+    output_directory: &Path,
+    yes: bool,
+    force: bool,
+) -> Result<reporter::AccumulatedOutcomes> {
+    let start = Instant::now();
+
+    let config = target.config()?;
+    let wasmfile = target.module()?;
+    let cache_lock = lock_cache(&config, force)?;
+    let module = load_module(wasmfile, &config)?;
+    target
+        .check_entry_points(&module)
+        .context(ErrorKind::Module)?;
+
+    let options = ExecutionOptions {
+        yes,
+        baseline: None,
+        require_debug_info: false,
+        max_runtime: None,
+        interruptible: true,
+        force,
+        verify_sample: None,
+    };
+    let run = run_mutants(&module, &config, &MutantSelection::all(), &options, start)?;
+    drop(cache_lock);
+
+    #[cfg(feature = "sqlite")]
+    store_results(&config, &module, &run.metadata, &run.executed_mutants)?;
+
+    let filtered = run.metadata.filtered_mutants;
+
+    match report_type {
+        Output::Html => {
+            HTMLReporter::new(config.report(), output_directory, wasmfile, &run.metadata)?
+                .with_unused_operators(run.unused_operators)
+                .with_advice(AdviceContext::from_config(&config))
+                .report(&run.executed_mutants)?;
+            RunManifest::new(run.provenance, &config).save(output_directory)?;
+        }
+        _ => {
+            CLIReporter::new(config.report())?
+                .with_unused_operators(run.unused_operators)
+                .with_metadata(run.metadata)
+                .with_advice(AdviceContext::from_config(&config))
+                .report(&run.executed_mutants)?;
+        }
+    }
+
+    Ok(reporter::accumulate_outcomes(&run.executed_mutants).with_filtered(filtered))
+}
+
+/// Mutate all targets of a workspace and create a combined dashboard.
+fn workspace_mutate(
+    manifest: &str,
+    report_type: &Output,
+    output_directory: &str,
+    yes: bool,
+    force: bool,
+) -> Result<()> {
+    if !matches!(report_type, Output::Console | Output::Html) {
+        return Err(anyhow!(
+            "Only console and html reports are supported for workspaces"
+        ))
+        .context(ErrorKind::Config);
+    }
+
+    let workspace = Workspace::load(manifest)?;
+    let output_directory = Path::new(output_directory);
+    let _output_lock = match report_type {
+        Output::Html => Some(LockFile::lock_directory(output_directory, force)?),
+        _ => None,
+    };
+
+    let mut summaries = Vec::new();
+
+    for target in workspace.targets() {
+        info!("Mutating workspace target {}", target.name());
+        warnings::clear();
+
+        let result = mutate_target(
+            target,
+            report_type,
+            &output_directory.join(target.name()),
+            yes,
+            force,
+        );
+
+        let (outcomes, error) = match result {
+            Ok(outcomes) => (Some(outcomes), None),
+            Err(e) if e.downcast_ref::<ErrorKind>() == Some(&ErrorKind::Aborted) => return Err(e),
+            Err(e) => {
+                error!("Workspace target {} failed: {e:?}", target.name());
+                (None, Some(format!("{e:#}")))
+            }
+        };
+
+        let link = match (report_type, &outcomes) {
+            (Output::Html, Some(_)) => Some(format!("{}/index.html", target.name())),
+            _ => None,
+        };
+
+        summaries.push(TargetSummary {
+            name: target.name().into(),
+            module: target.module()?.into(),
+            outcomes,
+            error,
+            link,
+        });
+    }
+
+    reporter::workspace::log_dashboard(&summaries);
+
+    if let Output::Html = report_type {
+        reporter::workspace::write_dashboard(output_directory, &summaries, workspace.thresholds())?;
+    }
+
+    let failed = summaries.iter().filter(|s| s.error.is_some()).count();
+    if failed > 0 {
+        bail!("{failed} of {} workspace targets failed", summaries.len());
+    }
+
+    Ok(())
+}
+
+/// Execute mutants and generate inputs that kill the surviving ones.
+fn suggest(wasmfile: &str, config: &Config, output_directory: &str, yes: bool) -> Result<()> {
+    let module = load_module(wasmfile, config)?;
+    let mutator = MutationEngine::new(config, 100)?;
+    let mutations = mutator.discover_mutation_positions(&module)?.locations;
+
+    let mut executor = Executor::new(config);
+    if !yes {
+        executor = executor.with_confirmation(Box::new(confirm_run));
+    }
+    let results = executor.execute_mutants(&module, &mutations)?;
+
+    let suggester = inputgen::Suggester::new(config, &module, Path::new(output_directory));
+    let suggestions = suggester.suggest(&mutations, &results)?;
+    inputgen::report(&suggestions);
 
     Ok(())
 }
@@ -143,6 +1227,22 @@ fn new_config(path: Option<String>) -> Result<()> {
     Ok(())
 }
 
+/// Write the generated test module corpus to a directory.
+#[cfg(feature = "testgen")]
+fn testgen(output_directory: &str) -> Result<()> {
+    for (generated, path) in testgen::write_corpus(Path::new(output_directory))? {
+        if generated.supported {
+            info!("Generated {path:?}: {}", generated.description);
+        } else {
+            info!(
+                "Generated {path:?}: {} (not supported by wasmut yet)",
+                generated.description
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Run a WebAssembly file without any mutations.
 fn run(wasmfile: &str, config: &Config) -> Result<()> {
     let module = WasmModule::from_file(wasmfile)?;
@@ -190,7 +1290,11 @@ fn load_config(
         } else {
             // No config found, using defaults
             info!("No configuration file found or specified, using default config");
-            Ok(Config::default())
+            let config = Config::default();
+            match module.and_then(|module| Path::new(module).parent()) {
+                Some(module_directory) => Ok(config.with_directory(module_directory)),
+                None => Ok(config),
+            }
         }
     }
 }
@@ -213,22 +1317,93 @@ fn init_rayon(threads: Option<usize>) {
 
 /// Implementation of main
 fn run_main(cli: CLIArguments) -> Result<()> {
+    if let Some(output_file) = &cli.output_file {
+        output::set_writers(vec![Box::new(output::FileWriter::create(output_file)?)]);
+    }
+
     match cli.command {
         CLICommand::ListFunctions {
             config,
             wasmfile,
             config_samedir,
+            detailed,
+            format,
         } => {
             let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
-            list_functions(&wasmfile, &config)?;
+            list_functions(&wasmfile, &config, detailed, &format)?;
         }
         CLICommand::ListFiles {
             config,
             wasmfile,
             config_samedir,
+            format,
         } => {
             let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
-            list_files(&wasmfile, &config)?;
+            list_files(&wasmfile, &config, &format)?;
+        }
+        CLICommand::Coverage {
+            config,
+            config_samedir,
+            format,
+            wasmfile,
+        } => {
+            let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
+            coverage(&wasmfile, &config, &format)?;
+        }
+        CLICommand::TraceDiff {
+            config,
+            config_samedir,
+            format,
+            mutant_id,
+            wasmfile,
+        } => {
+            let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
+            trace_diff(&wasmfile, &config, &mutant_id, &format)?;
+        }
+        CLICommand::VerifyOperators {
+            config,
+            config_samedir,
+            format,
+            wasmfile,
+        } => {
+            let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
+            verify_operators(&wasmfile, &config, &format)?;
+        }
+        CLICommand::Preview {
+            config,
+            config_samedir,
+            format,
+            file,
+            line,
+            wasmfile,
+        } => {
+            let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
+            preview(&wasmfile, &config, &file, line, &format)?;
+        }
+        CLICommand::Baseline {
+            config,
+            config_samedir,
+            output,
+            no_coverage,
+            wasmfile,
+        } => {
+            let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
+            record_baseline(&wasmfile, &config, &output, !no_coverage)?;
+        }
+        CLICommand::Plan {
+            config,
+            config_samedir,
+            format,
+            wasmfile,
+        } => {
+            let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
+            let module = load_module(&wasmfile, &config)?;
+            let plan = plan::Plan::new(&module, &config)?;
+
+            match format {
+                Format::Text => output::output_string(plan.explain()),
+                Format::Json => listing::output_json(&plan)?,
+            }
         }
         CLICommand::Mutate {
             config,
@@ -236,13 +1411,120 @@ fn run_main(cli: CLIArguments) -> Result<()> {
             threads,
             config_samedir,
             sample_threshold,
+            seed,
             report,
             output,
+            yes,
+            functions,
+            files,
+            changed_since,
+            archive,
+            redact_paths,
+            fail_under,
+            mutant_ids,
+            rerun_survivors,
+            use_baseline,
+            require_debug_info,
+            max_runtime,
+            force,
+            verify_sample,
+        } => {
+            let mut config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
+            if !functions.is_empty() || !files.is_empty() {
+                info!("Overriding filter configuration with command line arguments");
+                config.filter_mut().override_allowed(files, functions);
+            }
+            if let Some(revision) = changed_since {
+                config.filter_mut().override_only_changed_files(revision);
+            }
+            let mut mutant_ids = mutant_ids;
+            if rerun_survivors {
+                mutant_ids.extend(stored_survivors(&config, &wasmfile)?);
+            }
+            let baseline = use_baseline.map(Baseline::load).transpose()?;
+            let output = output
+                .as_deref()
+                .or(config.report().output_pattern())
+                .unwrap_or(reportdir::DEFAULT_OUTPUT_DIRECTORY)
+                .to_string();
+
+            init_rayon(threads);
+            let result = mutate(
+                &wasmfile,
+                &config,
+                &report,
+                &output,
+                &MutantSelection {
+                    sample_threshold,
+                    seed,
+                    mutant_ids: &mutant_ids,
+                },
+                &ExecutionOptions {
+                    yes,
+                    baseline: baseline.as_ref(),
+                    require_debug_info,
+                    max_runtime,
+                    interruptible: true,
+                    force,
+                    verify_sample,
+                },
+                &ReportOptions {
+                    archive: archive.as_deref(),
+                    redact_paths,
+                },
+            );
+
+            #[cfg(feature = "notify")]
+            notify_run(&config, &wasmfile, &result);
+            let outcomes = result?;
+
+            if let Some(fail_under) = fail_under {
+                let threshold = fail_under.unwrap_or(config.report().thresholds().warning);
+                check_mutation_score(&outcomes, threshold)?;
+            }
+        }
+        CLICommand::Suggest {
+            config,
+            config_samedir,
+            threads,
+            output,
+            yes,
+            wasmfile,
         } => {
             let config = load_config(config.as_deref(), Some(&wasmfile), config_samedir)?;
             init_rayon(threads);
-            mutate(&wasmfile, &config, &report, &output, sample_threshold)?;
+            suggest(&wasmfile, &config, &output, yes)?;
         }
+        CLICommand::ServeApi {
+            address,
+            token,
+            threads,
+        } => {
+            init_rayon(threads);
+            let token = match token.or_else(|| std::env::var("WASMUT_API_TOKEN").ok()) {
+                Some(token) if !token.is_empty() => token,
+                _ => {
+                    let token = server::generate_token();
+                    info!("Requests have to send the header \"Authorization: Bearer {token}\"");
+                    token
+                }
+            };
+            let api = server::Api::new(Box::new(server::execute_run), &token);
+            server::serve(&address, &api)?;
+        }
+        CLICommand::Workspace { command } => match command {
+            WorkspaceCommand::Mutate {
+                workspace,
+                threads,
+                report,
+                output,
+                yes,
+                force,
+            } => {
+                init_rayon(threads);
+                workspace_mutate(&workspace, &report, &output, yes, force)?;
+            }
+        },
         CLICommand::NewConfig { path } => {
             new_config(path)?;
         }
@@ -257,10 +1539,15 @@ fn run_main(cli: CLIArguments) -> Result<()> {
         CLICommand::ListOperators {
             config,
             config_samedir,
+            format,
             wasmfile,
         } => {
             let config = load_config(config.as_deref(), wasmfile.as_deref(), config_samedir)?;
-            list_operators(&config)?;
+            list_operators(&config, &format)?;
+        }
+        #[cfg(feature = "testgen")]
+        CLICommand::Testgen { output } => {
+            testgen(&output)?;
         }
     }
 
@@ -285,7 +1572,7 @@ fn main() {
         Ok(_) => {}
         Err(e) => {
             error!("{e:?}");
-            std::process::exit(1);
+            std::process::exit(error::exit_code(&e));
         }
     }
 }
@@ -300,6 +1587,7 @@ mod tests {
     fn new_config_is_created_standard_path() {
         let args = CLIArguments {
             command: CLICommand::NewConfig { path: None },
+            output_file: None,
         };
 
         assert!(run_main(args).is_ok());
@@ -310,6 +1598,52 @@ mod tests {
         std::fs::remove_file("wasmut.toml").unwrap();
     }
 
+    #[test]
+    fn invalid_config_exit_code() {
+        let args = CLIArguments::parse_args_from(vec![
+            "wasmut",
+            "list-operators",
+            "-c",
+            "testdata/does_not_exist.toml",
+        ]);
+
+        let error = run_main(args).unwrap_err();
+        assert_eq!(error::exit_code(&error), 2);
+    }
+
+    #[test]
+    fn score_below_threshold_exit_code() {
+        let outcomes = AccumulatedOutcomes {
+            total: 4,
+            alive: 2,
+            timeout: 0,
+            killed: 2,
+            error: 0,
+            skipped: 0,
+            filtered: 0,
+            not_run: 0,
+            internal_error: 0,
+            mutation_score: 50.0,
+        };
+
+        assert!(check_mutation_score(&outcomes, 50.0).is_ok());
+        let error = check_mutation_score(&outcomes, 60.0).unwrap_err();
+        assert_eq!(error::exit_code(&error), 4);
+
+        // Runs in which no mutant was executed fail even with a threshold of 0
+        let not_run = AccumulatedOutcomes {
+            total: 4,
+            alive: 0,
+            killed: 0,
+            not_run: 4,
+            mutation_score: 0.0,
+            ..outcomes
+        };
+        let error = check_mutation_score(&not_run, 0.0).unwrap_err();
+        assert_eq!(error::exit_code(&error), 4);
+        assert!(format!("{error:#}").contains("No mutant was executed"));
+    }
+
     #[test]
     fn new_config_is_created_custom_path() {
         let dir = tempfile::tempdir().unwrap();
@@ -335,6 +1669,7 @@ mod tests {
             "wasmut",
             "mutate",
             "-C",
+            "--yes",
             "-r",
             "html",
             "-o",
@@ -345,21 +1680,22 @@ mod tests {
 
         assert!(result.is_ok());
         assert!(output_dir.path().join("index.html").exists());
+        assert!(output_dir.path().join("report.json").exists());
 
         let args = CLIArguments::parse_args_from(vec![
             "wasmut",
             "mutate",
             "-C",
+            "--yes",
             "-r",
             "json",
             module_path.to_str().unwrap(),
         ]);
-        let result = run_main(args);
+        let (result, output) = output::capture(|| run_main(args));
         assert!(result.is_ok());
 
-        let json_report: JSONReport = serde_json::from_str(&output::get_output()).unwrap();
+        let json_report: JSONReport = serde_json::from_str(&output).unwrap();
 
-        output::clear_output();
         assert_eq!(module_path.to_str().unwrap(), json_report.file);
     }
 
@@ -382,10 +1718,8 @@ mod tests {
             module_path.to_str().unwrap(),
         ]);
 
-        output::clear_output();
-        assert!(run_main(args).is_ok());
-
-        let command_output = output::get_output();
+        let (result, command_output) = output::capture(|| run_main(args));
+        assert!(result.is_ok());
         let a = command_output.split('\n');
 
         for line in a {
@@ -396,6 +1730,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_coverage() -> Result<()> {
+        let module = crate::fixtures::simple_add()?;
+        let dir = tempfile::tempdir()?;
+        let module_path = dir.path().join("test.wasm");
+        std::fs::write(&module_path, module.bytes())?;
+        let config_path = dir.path().join("wasmut.toml");
+        std::fs::write(
+            &config_path,
+            "[engine]\nruntime = \"wasmi\"\n\n[filter]\nallowed_functions = [\"^add$\"]\n",
+        )?;
+
+        let args = CLIArguments::parse_args_from(vec![
+            "wasmut",
+            "coverage",
+            "-c",
+            config_path.to_str().unwrap(),
+            "--format",
+            "json",
+            module_path.to_str().unwrap(),
+        ]);
+
+        let (result, command_output) = output::capture(|| run_main(args));
+        assert!(result.is_ok());
+
+        let report: serde_json::Value = serde_json::from_str(&command_output).unwrap();
+        let function = |name: &str| {
+            report["functions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|f| f["function"] == name)
+                .unwrap()
+                .clone()
+        };
+
+        let add = function("add");
+        assert_eq!(add["allowed"], true);
+        assert!(add["executed_instructions"].as_u64().unwrap() > 0);
+        assert_eq!(function("_start")["allowed"], false);
+        assert!(report["total_executed_instructions"].as_u64().unwrap() > 0);
+        Ok(())
+    }
+
     #[test]
     fn test_list_files() {
         let config_path = Path::new("testdata/simple_add/wasmut_files.toml");
@@ -408,10 +1786,8 @@ mod tests {
             config_path.to_str().unwrap(),
             module_path.to_str().unwrap(),
         ]);
-        output::clear_output();
-        assert!(run_main(args).is_ok());
-
-        let command_output = output::get_output();
+        let (result, command_output) = output::capture(|| run_main(args));
+        assert!(result.is_ok());
         let a = command_output.split('\n');
 
         let mut hits = 0;
@@ -457,6 +1833,58 @@ mod tests {
         assert!(run_module("count_words").is_ok());
     }
 
+    #[test]
+    fn test_list_operators_json() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_path = dir.path().join("wasmut.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+            [operators]
+            enabled_operators = ["binop_", "relop_eq"]
+            disabled_operators = ["binop_add_to_sub"]
+            "#,
+        )?;
+
+        let args = CLIArguments::parse_args_from(vec![
+            "wasmut",
+            "list-operators",
+            "-c",
+            config_path.to_str().unwrap(),
+            "--format",
+            "json",
+        ]);
+        let (result, command_output) = output::capture(|| run_main(args));
+        assert!(result.is_ok());
+
+        let operators: serde_json::Value = serde_json::from_str(&command_output)?;
+        let operator = |name: &str| {
+            operators
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|op| op["name"] == name)
+                .unwrap()
+                .clone()
+        };
+
+        let sub_to_add = operator("binop_sub_to_add");
+        assert_eq!(sub_to_add["enabled"], true);
+        assert_eq!(sub_to_add["enabled_by"], "binop_");
+
+        let add_to_sub = operator("binop_add_to_sub");
+        assert_eq!(add_to_sub["enabled"], false);
+        assert_eq!(add_to_sub["disabled_by"], "binop_add_to_sub");
+
+        let eq_to_ne = operator("relop_eq_to_ne");
+        assert_eq!(eq_to_ne["enabled_by"], "relop_eq");
+
+        let unop = operator("unop_neg_to_nop");
+        assert_eq!(unop["enabled"], false);
+        assert_eq!(unop["enabled_by"], serde_json::Value::Null);
+        Ok(())
+    }
+
     #[test]
     fn test_list_operators() {
         let config_path = Path::new("testdata/count_words/wasmut_call.toml");
@@ -467,11 +1895,15 @@ mod tests {
             "-c",
             config_path.to_str().unwrap(),
         ]);
-        output::clear_output();
-        assert!(run_main(args).is_ok());
+        let (result, command_output) = output::capture(|| run_main(args));
+        assert!(result.is_ok());
+        let lines: Vec<&str> = command_output.split('\n').collect();
 
-        let command_output = output::get_output();
-        let lines = command_output.split('\n');
+        for name in ["relop_ordered_to_unordered", "relop_unordered_to_ordered"] {
+            assert!(lines
+                .iter()
+                .any(|line| line.contains("disabled") && line.ends_with(name)));
+        }
 
         let mut counted_operators = 0;
 
@@ -487,6 +1919,6 @@ mod tests {
             };
         }
 
-        assert_eq!(counted_operators, 31);
+        assert_eq!(counted_operators, 33);
     }
 }
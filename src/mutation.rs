@@ -1,12 +1,18 @@
 use crate::operator::InstructionContext;
 use crate::operator::InstructionReplacement;
 use crate::operator::OperatorRegistry;
-use crate::wasmmodule::CallbackType;
-use crate::{config::Config, policy::MutationPolicy, wasmmodule::WasmModule};
-use anyhow::Result;
+use crate::warnings::{self, WarningCategory};
+use crate::wasmmodule::{CallbackType, InstructionWalkerLocation};
+use crate::{config::Config, error::ErrorKind, policy::MutationPolicy, wasmmodule::WasmModule};
+use anyhow::{Context, Result};
 use atomic_counter::AtomicCounter;
 use atomic_counter::RelaxedCounter;
 use rand::distributions::{Distribution, Uniform};
+use rand::{rngs::StdRng, SeedableRng};
+use regex::RegexSet;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 /// Definition of a position where and how a module is mutated.
 #[derive(Debug, Clone)]
@@ -34,6 +40,268 @@ pub struct MutationLocation {
     pub mutations: Vec<Mutation>,
 }
 
+/// Identifier of the mutant of `operator` at the instruction with index
+/// `instruction_index` of `function`, see `MutantIds`
+pub fn mutant_id(
+    function: &str,
+    instruction_index: u64,
+    operator: &dyn InstructionReplacement,
+) -> String {
+    let hash = md5::compute(format!(
+        "{function}:{instruction_index}:{}:{}",
+        operator.dyn_name(),
+        operator.description()
+    ));
+    format!("{hash:?}")[..12].into()
+}
+
+/// Stable identifiers of the mutants of a module.
+///
+/// A mutant is identified by its function, the index of the mutated instruction
+/// within the function and its operator. Unlike offsets, IDs do not change if
+/// code is added to or removed from other functions, e.g. when tests are added.
+pub struct MutantIds {
+    /// Identity of every function: its name, or the hash of its body if the
+    /// function has no unique name
+    functions: Vec<String>,
+
+    /// Function number and instruction index of every instruction, by offset
+    instructions: HashMap<u64, (u64, u64)>,
+}
+
+impl MutantIds {
+    pub fn new(module: &WasmModule) -> Result<Self> {
+        // Modules without code sections do not have any mutants
+        let functions = module.functions_detailed().unwrap_or_default();
+        let hashes = module.function_hashes()?;
+
+        let mut names: HashMap<&str, usize> = HashMap::new();
+        for function in &functions {
+            if let Some(name) = &function.details.name {
+                *names.entry(name).or_default() += 1;
+            }
+        }
+
+        let mut identities: Vec<String> = functions
+            .iter()
+            .zip(&hashes)
+            .map(|(function, hash)| match &function.details.name {
+                Some(name) if names[name.as_str()] == 1 => name.clone(),
+                _ => format!("#{hash}"),
+            })
+            .collect();
+
+        // Functions with identical bodies and without a unique
+        // name can only be told apart by their position
+        let mut seen = HashSet::new();
+        let duplicates: HashSet<String> = identities
+            .iter()
+            .filter(|identity| !seen.insert(*identity))
+            .cloned()
+            .collect();
+        for (number, identity) in identities.iter_mut().enumerate() {
+            if duplicates.contains(identity) {
+                identity.push_str(&format!(":{number}"));
+            }
+        }
+
+        let instructions = functions
+            .iter()
+            .enumerate()
+            .flat_map(|(number, function)| {
+                function
+                    .instruction_offsets
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, &offset)| (offset, (number as u64, index as u64)))
+            })
+            .collect();
+
+        Ok(Self {
+            functions: identities,
+            instructions,
+        })
+    }
+
+    /// ID of the mutant of `operator` at `location`
+    pub fn for_location(
+        &self,
+        location: &MutationLocation,
+        operator: &dyn InstructionReplacement,
+    ) -> String {
+        self.id(
+            location.function_number,
+            location.statement_number,
+            operator,
+        )
+    }
+
+    /// ID of the mutant of `operator` at the instruction at `offset`
+    pub fn for_offset(&self, offset: u64, operator: &dyn InstructionReplacement) -> String {
+        match self.instructions.get(&offset) {
+            Some(&(function, index)) => self.id(function, index, operator),
+            // Not the offset of an instruction of the module, e.g. of a test fixture
+            None => mutant_id("", offset, operator),
+        }
+    }
+
+    fn id(&self, function: u64, index: u64, operator: &dyn InstructionReplacement) -> String {
+        let function = self
+            .functions
+            .get(function as usize)
+            .map(String::as_str)
+            .unwrap_or_default();
+        mutant_id(function, index, operator)
+    }
+}
+
+/// Split mutants into those whose stable ID is contained in `ids` and all others.
+///
+/// Locations without any remaining mutant are removed from either list.
+/// Returns the selected locations, the remaining locations and all IDs
+/// that did not match any mutant.
+pub fn select_mutants(
+    locations: Vec<MutationLocation>,
+    mutant_ids: &MutantIds,
+    ids: &[String],
+) -> (Vec<MutationLocation>, Vec<MutationLocation>, Vec<String>) {
+    let mut unmatched: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    let mut selected = Vec::new();
+    let mut unselected = Vec::new();
+
+    for mut location in locations {
+        let mutations = std::mem::take(&mut location.mutations);
+        let (chosen, others): (Vec<Mutation>, Vec<Mutation>) =
+            mutations.into_iter().partition(|mutation| {
+                let id = mutant_ids.for_location(&location, &*mutation.operator);
+                let chosen = ids.contains(&id);
+                if chosen {
+                    unmatched.remove(id.as_str());
+                }
+                chosen
+            });
+
+        for (list, mutations) in [(&mut selected, chosen), (&mut unselected, others)] {
+            if !mutations.is_empty() {
+                list.push(MutationLocation {
+                    function_number: location.function_number,
+                    statement_number: location.statement_number,
+                    offset: location.offset,
+                    mutations,
+                });
+            }
+        }
+    }
+
+    // Keep the order of the command line
+    let unmatched = ids
+        .iter()
+        .filter(|id| unmatched.contains(id.as_str()))
+        .cloned()
+        .collect();
+
+    (selected, unselected, unmatched)
+}
+
+/// Number of mutation candidates produced by each enabled operator
+#[derive(Debug, Default, Clone)]
+pub struct OperatorUsage {
+    counts: BTreeMap<String, usize>,
+}
+
+impl OperatorUsage {
+    /// Iterate over all enabled operators and their number of candidates
+    pub fn counts(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.counts
+            .iter()
+            .map(|(name, &count)| (name.as_str(), count))
+    }
+
+    /// Return all enabled operators that did not produce any mutation candidate
+    pub fn unused(&self) -> Vec<String> {
+        self.counts
+            .iter()
+            .filter(|(_, &count)| count == 0)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+/// Result of the mutant discovery
+pub struct Discovery {
+    /// All discovered mutation locations
+    pub locations: Vec<MutationLocation>,
+
+    /// Number of discovered mutants per source file, `None` for
+    /// locations without source file information
+    pub files: BTreeMap<Option<String>, usize>,
+
+    /// Candidates per enabled operator
+    pub operator_usage: OperatorUsage,
+
+    /// Number of candidates excluded by the filter configuration
+    /// (`allowed_*`, `only_changed_files`, `min_function_instructions`
+    /// and `most_complex_functions`)
+    pub filtered: usize,
+
+    /// Part of the module in the scope of the filter configuration
+    pub scope: FilterScope,
+}
+
+/// Part of the module in the scope of the filter configuration,
+/// so that the mutation score can be put into perspective
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FilterScope {
+    /// Number of functions defined in the module
+    pub functions: usize,
+
+    /// Number of functions with at least one instruction in scope
+    pub functions_in_scope: usize,
+
+    /// Number of instructions of all functions
+    pub instructions: u64,
+
+    /// Number of instructions that may be mutated
+    pub instructions_in_scope: u64,
+}
+
+impl FilterScope {
+    /// True if the filter configuration excludes a part of the module
+    pub fn is_restricted(&self) -> bool {
+        self.instructions_in_scope < self.instructions
+    }
+
+    /// Percentage of the instructions in scope
+    pub fn percentage(&self) -> f64 {
+        if self.instructions == 0 {
+            return 100.0;
+        }
+        100.0 * self.instructions_in_scope as f64 / self.instructions as f64
+    }
+
+    /// Human readable summary, e.g. "3.0% of the instructions (2 of 40 functions)"
+    pub fn summary(&self) -> String {
+        format!(
+            "{:.1}% of the instructions ({} of {} functions)",
+            self.percentage(),
+            self.functions_in_scope,
+            self.functions
+        )
+    }
+}
+
+/// Operators and sampling rate for the files matched by a `[[filter.overrides]]` entry
+struct FileOverride {
+    /// Files the override applies to
+    files: RegexSet,
+
+    /// Operators used instead of the `[operators]` section, if they were overridden
+    registry: Option<OperatorRegistry>,
+
+    /// Percentage of mutants that are executed, if it was overridden
+    sample_threshold: Option<i32>,
+}
+
 /// Used for discovering possible mutants based on
 /// the module and a set of operators.
 pub struct MutationEngine {
@@ -43,81 +311,452 @@ pub struct MutationEngine {
     /// A list of all operators that are to be enabled.
     enabled_operators: Vec<String>,
 
+    /// A list of operators that are disabled, even if enabled.
+    disabled_operators: Vec<String>,
+
     /// Percentage of mutants that are to be executed
     sample_threshold: i32,
+
+    /// Functions with fewer instructions are not mutated
+    min_function_instructions: u64,
+
+    /// Per-file overrides of operators and sampling rate
+    overrides: Vec<FileOverride>,
+
+    /// If set, only this number of the most complex functions is mutated
+    most_complex_functions: Option<usize>,
+
+    /// If set, at most this number of mutants is generated per function
+    max_mutants_per_function: Option<usize>,
+
+    /// Seed for selecting the executed mutants, if `sample_threshold` is below 100
+    seed: u32,
 }
 
 impl MutationEngine {
     /// Create a new `MutationEngine`, based on a configuration.
     pub fn new(config: &Config, sample_threshold: i32) -> Result<Self> {
+        let enabled_operators = config.operators().enabled_operators();
+        let disabled_operators = config.operators().disabled_operators();
+
+        let overrides = config
+            .filter()
+            .overrides()
+            .iter()
+            .map(|o| {
+                let files = RegexSet::new(o.files())
+                    .context("Could not build regex set of filter override")
+                    .context(ErrorKind::Config)?;
+
+                let registry =
+                    if o.enabled_operators().is_some() || o.disabled_operators().is_some() {
+                        Some(OperatorRegistry::with_disabled(
+                            o.enabled_operators().unwrap_or(&enabled_operators),
+                            o.disabled_operators().unwrap_or(&disabled_operators),
+                        )?)
+                    } else {
+                        None
+                    };
+
+                Ok(FileOverride {
+                    files,
+                    registry,
+                    sample_threshold: o.sample_threshold(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(Self {
             mutation_policy: MutationPolicy::from_config(config)?,
-            enabled_operators: config.operators().enabled_operators(),
+            enabled_operators,
+            disabled_operators,
             sample_threshold,
+            min_function_instructions: config.filter().min_function_instructions(),
+            overrides,
+            most_complex_functions: config.filter().most_complex_functions(),
+            max_mutants_per_function: config.filter().max_mutants_per_function(),
+            seed: rand::random(),
         })
     }
 
-    /// Discover all mutation candidates in a module.
+    /// Use `seed` instead of a random seed for selecting the executed mutants,
+    /// e.g. to reproduce an earlier run
+    pub fn with_seed(self, seed: u32) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Seed for selecting the executed mutants
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Select the most complex of `functions`, if `most_complex_functions` is set.
     ///
-    /// This method will return a vector of `Mutation` structs, representing the
-    /// candidates.
-    pub fn discover_mutation_positions(
+    /// Functions with equal complexity are ordered by their index.
+    fn select_complex_functions(
+        &self,
+        module: &WasmModule,
+        functions: impl Iterator<Item = u64>,
+    ) -> Option<HashSet<u64>> {
+        let limit = self.most_complex_functions?;
+        let complexities = module.function_complexities();
+        let complexity = |function: u64| complexities.get(function as usize).copied();
+
+        let mut functions: Vec<u64> = functions.collect::<BTreeSet<u64>>().into_iter().collect();
+        functions.sort_by_key(|&function| Reverse(complexity(function)));
+        functions.truncate(limit);
+
+        if let Some(&least_complex) = functions.last() {
+            log::debug!(
+                "Selected the {} most complex functions (complexity >= {})",
+                functions.len(),
+                complexity(least_complex).unwrap_or_default()
+            );
+        }
+
+        Some(functions.into_iter().collect())
+    }
+
+    /// Return the first override matching `file`
+    fn file_override(&self, location: &InstructionWalkerLocation) -> Option<&FileOverride> {
+        let paths = source_paths(location);
+        self.overrides
+            .iter()
+            .find(|o| paths.iter().any(|path| o.files.is_match(path)))
+    }
+
+    /// Check if the instruction at `location` may be mutated according to the
+    /// filter configuration. File patterns may match either the normalized
+    /// or the recorded source path.
+    fn in_scope(&self, location: &InstructionWalkerLocation) -> bool {
+        match source_paths(location)[..] {
+            [] => self.mutation_policy.check(None, location.function),
+            ref paths => paths
+                .iter()
+                .any(|path| self.mutation_policy.check(Some(*path), location.function)),
+        }
+    }
+
+    /// Operators and sampling rate used for the instruction at `location`
+    fn site_settings<'r>(
+        &'r self,
+        location: &InstructionWalkerLocation,
+        registry: &'r OperatorRegistry,
+    ) -> (&'r OperatorRegistry, i32) {
+        match self.file_override(location) {
+            Some(file_override) => (
+                file_override.registry.as_ref().unwrap_or(registry),
+                file_override
+                    .sample_threshold
+                    .unwrap_or(self.sample_threshold),
+            ),
+            None => (registry, self.sample_threshold),
+        }
+    }
+
+    /// Instantiate the operator registry and the instruction context for a module
+    fn operator_registry(
         &self,
         module: &WasmModule,
-    ) -> Result<Vec<MutationLocation>> {
-        // Instantiate operator registry
-        let registry = OperatorRegistry::new(&self.enabled_operators)?;
+    ) -> Result<(OperatorRegistry, InstructionContext)> {
+        let registry =
+            OperatorRegistry::with_disabled(&self.enabled_operators, &self.disabled_operators)?;
 
         // Find functions with no return / scalar return value.
         // Calls to those functions may be removed by call_remove* operators
         let call_removal_candidates = module.call_removal_candidates()?;
-        let context = InstructionContext::new(call_removal_candidates);
+
+        // Table slots of functions with identical types, which
+        // the table_entry_replace operator may call instead
+        let table_entry_candidates = module.table_entry_candidates()?;
+        let context = InstructionContext::new(call_removal_candidates)
+            .with_table_entry_candidates(table_entry_candidates);
+
+        Ok((registry, context))
+    }
+
+    /// Count the mutation candidates of every source file.
+    ///
+    /// Uses the same discovery pass as the actual run, but counts the
+    /// candidates before sampling. Candidates at locations without
+    /// source file information are counted under `None`.
+    pub fn count_candidates_per_file(
+        &self,
+        module: &WasmModule,
+    ) -> Result<BTreeMap<Option<String>, usize>> {
+        Ok(self.discover(module, false)?.files)
+    }
+
+    /// Discover all mutation candidates in a module.
+    ///
+    /// This method will return a vector of `Mutation` structs, representing the
+    /// candidates, as well as the number of candidates per enabled operator.
+    pub fn discover_mutation_positions(&self, module: &WasmModule) -> Result<Discovery> {
+        self.discover(module, true)
+    }
+
+    /// Discover all mutation candidates, selecting the executed
+    /// mutants by the sampling rate if `sample` is true
+    fn discover(&self, module: &WasmModule, sample: bool) -> Result<Discovery> {
+        let (registry, context) = self.operator_registry(module)?;
 
         let id_counter = RelaxedCounter::new(1);
 
+        // Number of mutation candidates that were denied by the policy,
+        // because their locations have no source file information
+        let excluded_without_source_info = RelaxedCounter::new(0);
+
+        // Number of functions skipped because of `min_function_instructions`
+        let skipped_functions = RelaxedCounter::new(0);
+
+        // Number of mutation candidates excluded by the filter configuration
+        let filtered = RelaxedCounter::new(0);
+
+        // Number of instructions in scope of the filter configuration, by function
+        let function_lengths = module.function_lengths();
+        let in_scope: Vec<RelaxedCounter> = function_lengths
+            .iter()
+            .map(|_| RelaxedCounter::new(0))
+            .collect();
+
+        // Number of candidates per enabled operator, before sampling.
+        // Operators enabled by overrides are counted as well.
+        let operator_counts: BTreeMap<&str, RelaxedCounter> = registry
+            .enabled_operators()
+            .iter()
+            .chain(
+                self.overrides
+                    .iter()
+                    .filter_map(|o| o.registry.as_ref())
+                    .flat_map(|r| r.enabled_operators()),
+            )
+            .map(|name| (name.as_str(), RelaxedCounter::new(0)))
+            .collect();
+
         // Define a callback function that is used by wasmmodule::instruction_walker
         // The callback is called for every single instruction of the module
         // and is passed the instruction and the location within
         // the module.
         // TODO: Refactor so that we do not return a vec?
-        let callback: CallbackType<MutationLocation> = &|instruction, location| {
-            if self.mutation_policy.check(location.file, location.function) {
-                let mutations: Vec<Mutation> = registry
-                    .mutants_for_instruction(instruction, &context)
-                    .into_iter()
-                    .filter(|_| {
-                        let mut rng = rand::thread_rng();
-                        let die = Uniform::from(0..=100i32);
-                        let roll = die.sample(&mut rng);
-                        roll <= self.sample_threshold
-                    })
-                    .map(|operator| Mutation {
-                        id: id_counter.inc() as i64,
-                        operator,
-                    })
-                    .collect();
-
-                if mutations.is_empty() {
-                    vec![]
+        let callback: CallbackType<(MutationLocation, Option<String>)> =
+            &|instruction, location| {
+                let (registry, sample_threshold) = self.site_settings(location, &registry);
+
+                // Skip trivial functions (e.g. accessors), they mostly generate noise
+                if location.function_length < self.min_function_instructions {
+                    if location.instruction_index == 0 {
+                        skipped_functions.inc();
+                    }
+                    filtered.add(
+                        registry
+                            .mutants_for_location(instruction, &context, location)
+                            .len(),
+                    );
+                    return vec![];
+                }
+
+                if self.in_scope(location) {
+                    if let Some(counter) = in_scope.get(location.function_index as usize) {
+                        counter.inc();
+                    }
+
+                    // Seeded per instruction, so that the selection does not depend
+                    // on the order in which the worker threads visit the instructions
+                    let mut rng = StdRng::seed_from_u64(
+                        (u64::from(self.seed) << 32) ^ location.instruction_offset,
+                    );
+                    let die = Uniform::from(0..=100i32);
+
+                    let mutations: Vec<Mutation> = registry
+                        .mutants_for_location(instruction, &context, location)
+                        .into_iter()
+                        .inspect(|operator| {
+                            if let Some(counter) = operator_counts.get(operator.dyn_name()) {
+                                counter.inc();
+                            }
+                        })
+                        .filter(|_| !sample || die.sample(&mut rng) <= sample_threshold)
+                        .map(|operator| Mutation {
+                            id: id_counter.inc() as i64,
+                            operator,
+                        })
+                        .collect();
+
+                    if mutations.is_empty() {
+                        vec![]
+                    } else {
+                        let mutation_location = MutationLocation {
+                            function_number: location.function_index,
+                            statement_number: location.instruction_index,
+                            offset: location.instruction_offset,
+                            mutations,
+                        };
+                        vec![(mutation_location, location.file.map(String::from))]
+                    }
                 } else {
-                    let mutation_location = MutationLocation {
-                        function_number: location.function_index,
-                        statement_number: location.instruction_index,
-                        offset: location.instruction_offset,
-                        mutations,
-                    };
-                    vec![mutation_location]
+                    let candidates = registry
+                        .mutants_for_location(instruction, &context, location)
+                        .len();
+                    filtered.add(candidates);
+                    if location.file.is_none() && self.mutation_policy.requires_source_info() {
+                        excluded_without_source_info.add(candidates);
+                    }
+                    vec![]
                 }
-            } else {
-                vec![]
+            };
+
+        let (mut mutations, files): (Vec<MutationLocation>, Vec<Option<String>>) =
+            module.instruction_walker(callback)?.into_iter().unzip();
+        let files: HashMap<u64, Option<String>> = mutations
+            .iter()
+            .map(|location| location.offset)
+            .zip(files)
+            .collect();
+        let mut in_scope: Vec<u64> = in_scope
+            .into_iter()
+            .map(|counter| counter.into_inner() as u64)
+            .collect();
+
+        if let Some(selected) =
+            self.select_complex_functions(module, mutations.iter().map(|l| l.function_number))
+        {
+            log::info!("Mutating the {} most complex functions", selected.len());
+            let before = count_mutants(&mutations);
+            mutations.retain(|location| selected.contains(&location.function_number));
+            filtered.add((before - count_mutants(&mutations)) as usize);
+
+            for (function, instructions) in in_scope.iter_mut().enumerate() {
+                if !selected.contains(&(function as u64)) {
+                    *instructions = 0;
+                }
+            }
+        }
+
+        if let Some(limit) = self.max_mutants_per_function {
+            let before = count_mutants(&mutations);
+            let capped = cap_mutants_per_function(&mut mutations, limit);
+            filtered.add((before - count_mutants(&mutations)) as usize);
+
+            if capped > 0 {
+                warnings::warn(
+                    WarningCategory::CappedFunctions,
+                    format!(
+                        "Limited the mutants of {capped} functions to {limit} \
+                        (max_mutants_per_function)"
+                    ),
+                );
             }
+        }
+
+        let scope = FilterScope {
+            functions: function_lengths.len(),
+            functions_in_scope: in_scope.iter().filter(|&&count| count > 0).count(),
+            instructions: function_lengths.iter().sum(),
+            instructions_in_scope: in_scope.iter().sum(),
         };
+        if scope.is_restricted() {
+            log::info!("Filters restrict mutation to {}", scope.summary());
+        }
 
-        let mutations = module.instruction_walker::<MutationLocation>(callback)?;
         log::info!("Generated {} mutations", count_mutants(&mutations));
 
-        Ok(mutations)
+        let excluded = excluded_without_source_info.get();
+        if excluded > 0 {
+            warnings::warn(
+                WarningCategory::MissingSourceInfo,
+                format!(
+                    "Excluded {excluded} mutations at locations without source file information, \
+                    make sure that the module contains DWARF debug information"
+                ),
+            );
+        }
+
+        let skipped = skipped_functions.get();
+        if skipped > 0 {
+            warnings::warn(
+                WarningCategory::SkippedFunctions,
+                format!(
+                    "Skipped {skipped} functions with fewer than {} instructions",
+                    self.min_function_instructions
+                ),
+            );
+        }
+
+        let operator_usage = OperatorUsage {
+            counts: operator_counts
+                .iter()
+                .map(|(name, counter)| (name.to_string(), counter.get()))
+                .collect(),
+        };
+
+        let unused = operator_usage.unused();
+        if !unused.is_empty() {
+            warnings::warn(
+                WarningCategory::UnusedOperators,
+                format!(
+                    "The following enabled operators did not produce any mutants: {}",
+                    unused.join(", ")
+                ),
+            );
+        }
+
+        let mut per_file = BTreeMap::new();
+        for location in &mutations {
+            *per_file.entry(files[&location.offset].clone()).or_insert(0) +=
+                location.mutations.len();
+        }
+
+        Ok(Discovery {
+            locations: mutations,
+            files: per_file,
+            operator_usage,
+            filtered: filtered.get(),
+            scope,
+        })
+    }
+}
+
+/// Normalized and, if different, recorded source path of an instruction
+fn source_paths<'a>(location: &InstructionWalkerLocation<'a>) -> Vec<&'a str> {
+    location
+        .file
+        .into_iter()
+        .chain(
+            location
+                .raw_file
+                .filter(|raw_file| Some(*raw_file) != location.file),
+        )
+        .collect()
+}
+
+/// Keep at most `limit` mutants of every function, spread evenly over the function.
+///
+/// `locations` must be ordered by function and instruction, as returned by the
+/// instruction walker. Returns the number of functions whose mutants were limited.
+fn cap_mutants_per_function(locations: &mut Vec<MutationLocation>, limit: usize) -> usize {
+    let mut totals: HashMap<u64, usize> = HashMap::new();
+    for location in locations.iter() {
+        *totals.entry(location.function_number).or_default() += location.mutations.len();
     }
+
+    let mut seen: HashMap<u64, usize> = HashMap::new();
+    for location in locations.iter_mut() {
+        let total = totals[&location.function_number];
+        if total <= limit {
+            continue;
+        }
+
+        // Keep the k-th mutant if it is the first of one of `limit` equally sized intervals
+        let seen = seen.entry(location.function_number).or_default();
+        location.mutations.retain(|_| {
+            let k = *seen;
+            *seen += 1;
+            k * limit % total < limit
+        });
+    }
+    locations.retain(|location| !location.mutations.is_empty());
+
+    totals.values().filter(|&&total| total > limit).count()
 }
 
 fn count_mutants(locations: &[MutationLocation]) -> i32 {
@@ -172,13 +811,168 @@ mod tests {
         );
     }
 
+    #[test]
+    fn cap_mutants_of_large_functions() {
+        let location = |function_number, statement_number| MutationLocation {
+            function_number,
+            statement_number,
+            offset: function_number * 100 + statement_number,
+            mutations: vec![
+                Mutation {
+                    id: 1,
+                    operator: Box::new(BinaryOperatorMulToDivS::new(&Instruction::I32Mul).unwrap()),
+                };
+                2
+            ],
+        };
+
+        // Function 0 has 20 mutants, function 1 has 2
+        let mut locations: Vec<MutationLocation> = (0..10).map(|i| location(0, i)).collect();
+        locations.push(location(1, 0));
+
+        assert_eq!(cap_mutants_per_function(&mut locations, 5), 1);
+        assert_eq!(count_mutants(&locations), 7);
+
+        // The remaining mutants of function 0 are spread over the whole function
+        let statements: Vec<u64> = locations
+            .iter()
+            .filter(|location| location.function_number == 0)
+            .map(|location| location.statement_number)
+            .collect();
+        assert_eq!(statements, vec![0, 2, 4, 6, 8]);
+
+        assert_eq!(cap_mutants_per_function(&mut locations, 5), 0);
+        assert_eq!(count_mutants(&locations), 7);
+    }
+
+    #[test]
+    fn select_mutants_by_id() {
+        let location = |offset, statement_number| MutationLocation {
+            function_number: 1,
+            statement_number,
+            offset,
+            mutations: vec![
+                Mutation {
+                    id: 1,
+                    operator: Box::new(BinaryOperatorMulToDivS::new(&Instruction::I32Mul).unwrap()),
+                },
+                Mutation {
+                    id: 2,
+                    operator: Box::new(BinaryOperatorMulToDivS::new(&Instruction::I64Mul).unwrap()),
+                },
+            ],
+        };
+
+        let mutant_ids = MutantIds {
+            functions: vec!["a".into(), "b".into()],
+            instructions: HashMap::new(),
+        };
+        let locations = vec![location(10, 1), location(20, 2)];
+        let selected_id = mutant_id("b", 2, &*locations[1].mutations[1].operator);
+        assert_eq!(selected_id.len(), 12);
+        assert_eq!(
+            mutant_ids.for_location(&locations[1], &*locations[1].mutations[1].operator),
+            selected_id
+        );
+
+        let ids = vec![String::from("000000000000"), selected_id];
+        let (selected, unselected, unmatched) = select_mutants(locations, &mutant_ids, &ids);
+
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].offset, 20);
+        assert_eq!(selected[0].mutations.len(), 1);
+        assert_eq!(selected[0].mutations[0].id, 2);
+        assert_eq!(unmatched, ["000000000000"]);
+
+        assert_eq!(unselected.len(), 2);
+        assert_eq!(unselected[0].mutations.len(), 2);
+        assert_eq!(unselected[1].offset, 20);
+        assert_eq!(unselected[1].mutations.len(), 1);
+        assert_eq!(unselected[1].mutations[0].id, 1);
+    }
+
+    #[test]
+    fn mutant_ids_do_not_depend_on_offsets() -> Result<()> {
+        use Instruction::*;
+
+        let function = |constants: &[i32]| {
+            let mut instructions = Vec::new();
+            for constant in constants {
+                instructions.extend([I32Const(*constant), I32Const(2), I32Add, Drop]);
+            }
+            instructions
+        };
+
+        let config = Config::parse("[operators]\nenabled_operators = [\"binop_add_to_sub\"]")?;
+        let ids = |functions: Vec<&[i32]>| -> Result<Vec<(u64, String)>> {
+            let bodies = functions.into_iter().map(function).collect();
+            let module = crate::fixtures::functions(&[], &[], bodies)?;
+
+            let mutant_ids = MutantIds::new(&module)?;
+            let mut ids: Vec<(u64, String)> = MutationEngine::new(&config, 100)?
+                .discover_mutation_positions(&module)?
+                .locations
+                .iter()
+                .map(|location| {
+                    let operator = &*location.mutations[0].operator;
+                    let id = mutant_ids.for_location(location, operator);
+                    assert_eq!(mutant_ids.for_offset(location.offset, operator), id);
+                    (location.offset, id)
+                })
+                .collect();
+            ids.sort();
+            Ok(ids)
+        };
+
+        let before = ids(vec![&[1, 3]])?;
+        // Another function in front of the mutated one moves all offsets
+        let after = ids(vec![&[4, 5, 6], &[1, 3]])?;
+        assert_eq!(before.len(), 2);
+        assert_eq!(after.len(), 5);
+        assert_ne!(before[0].0, after[3].0);
+        assert_eq!(before[0].1, after[3].1);
+        assert_eq!(before[1].1, after[4].1);
+
+        // Mutants of functions with identical bodies are told apart
+        let duplicates = ids(vec![&[1], &[1]])?;
+        assert_ne!(duplicates[0].1, duplicates[1].1);
+        Ok(())
+    }
+
+    #[test]
+    fn count_filtered_candidates() -> Result<()> {
+        let module = crate::fixtures::simple_add()?;
+
+        let all =
+            MutationEngine::new(&Config::default(), 100)?.discover_mutation_positions(&module)?;
+        assert_eq!(all.filtered, 0);
+
+        let config = Config::parse(
+            r#"
+            [filter]
+            allowed_functions = ["^add$"]
+            "#,
+        )?;
+        let filtered = MutationEngine::new(&config, 100)?.discover_mutation_positions(&module)?;
+
+        assert!(filtered.filtered > 0);
+        assert_eq!(
+            count_mutants(&filtered.locations) as usize + filtered.filtered,
+            count_mutants(&all.locations) as usize
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_discover_mutation_positions() -> Result<()> {
         let module = WasmModule::from_file("testdata/simple_add/test.wasm")?;
 
         let config = Config::default();
         let engine = MutationEngine::new(&config, 100)?;
-        let positions = engine.discover_mutation_positions(&module).unwrap();
+        let positions = engine
+            .discover_mutation_positions(&module)
+            .unwrap()
+            .locations;
 
         assert!(!positions.is_empty());
         Ok(())
@@ -190,7 +984,10 @@ mod tests {
         let config = Config::default();
         let engine = MutationEngine::new(&config, 100)?;
 
-        let locations = engine.discover_mutation_positions(&module).unwrap();
+        let locations = engine
+            .discover_mutation_positions(&module)
+            .unwrap()
+            .locations;
         dbg!(&locations);
 
         let mutant = module.clone_and_mutate(&locations[0], 0);
@@ -208,7 +1005,11 @@ mod tests {
             let module = WasmModule::from_file("testdata/count_words/test.wasm").unwrap();
             let config = Config::parse_file(format!("testdata/count_words/{config}")).unwrap();
             let engine = MutationEngine::new(&config, 100).unwrap();
-            engine.discover_mutation_positions(&module).unwrap().len()
+            engine
+                .discover_mutation_positions(&module)
+                .unwrap()
+                .locations
+                .len()
         }
 
         assert_eq!(check_number_of_mutants("wasmut_call.toml"), 7);
@@ -223,7 +1024,11 @@ mod tests {
             let module = WasmModule::from_file("testdata/count_words/test.wasm").unwrap();
             let config = Config::parse_file("testdata/count_words/wasmut.toml").unwrap();
             let engine = MutationEngine::new(&config, threshold).unwrap();
-            engine.discover_mutation_positions(&module).unwrap().len()
+            engine
+                .discover_mutation_positions(&module)
+                .unwrap()
+                .locations
+                .len()
         }
 
         assert_eq!(check_number_of_mutants(100), 23);
@@ -235,4 +1040,280 @@ mod tests {
         assert!(check_number_of_mutants(50) < 23);
         Ok(())
     }
+
+    #[test]
+    fn file_overrides() -> Result<()> {
+        let module = crate::fixtures::simple_add()?;
+        let candidates = |toml: &str| -> Result<usize> {
+            let config = Config::parse(toml)?;
+            let counts = MutationEngine::new(&config, 100)?.count_candidates_per_file(&module)?;
+            Ok(counts
+                .iter()
+                .filter(|(file, _)| {
+                    file.as_deref()
+                        .unwrap_or_default()
+                        .ends_with("simple_add.c")
+                })
+                .map(|(_, count)| count)
+                .sum())
+        };
+
+        let all = candidates("")?;
+        assert!(all > 0);
+
+        let disabled = r#"
+            [[filter.overrides]]
+            files = ["simple_add\\.c$"]
+            enabled_operators = ["^$"]
+            "#;
+        assert_eq!(candidates(disabled)?, 0);
+        assert_eq!(candidates(&disabled.replace("simple_add", "other"))?, all);
+
+        let mutations = |toml: &str| -> Result<usize> {
+            let config = Config::parse(toml)?;
+            let discovery =
+                MutationEngine::new(&config, 100)?.discover_mutation_positions(&module)?;
+            Ok(count_mutants(&discovery.locations) as usize)
+        };
+        let sampled = r#"
+            [[filter.overrides]]
+            files = ["simple_add\\.c$"]
+            sample_threshold = -1
+            "#;
+        assert_eq!(mutations(sampled)?, mutations("")? - all);
+        Ok(())
+    }
+
+    #[test]
+    fn most_complex_functions() -> Result<()> {
+        use wasmut_wasm::elements::BlockType;
+        use Instruction::*;
+
+        let function = |branches: usize| {
+            let mut instructions = vec![Block(BlockType::NoResult)];
+            for _ in 0..branches {
+                instructions.extend([I32Const(1), BrIf(0)]);
+            }
+            instructions.push(End);
+            instructions
+        };
+
+        let bodies = [1, 3, 0, 3].into_iter().map(function).collect();
+        let module = crate::fixtures::functions(&[], &[], bodies)?;
+
+        let select = |toml: &str| -> Result<Option<Vec<u64>>> {
+            let engine = MutationEngine::new(&Config::parse(toml)?, 100)?;
+            Ok(engine
+                .select_complex_functions(&module, [0, 1, 2, 3, 0].into_iter())
+                .map(|selected| selected.into_iter().collect::<BTreeSet<u64>>())
+                .map(|selected| selected.into_iter().collect()))
+        };
+
+        assert_eq!(select("")?, None);
+        assert_eq!(
+            select("[filter]\nmost_complex_functions = 2")?,
+            Some(vec![1, 3])
+        );
+        assert_eq!(
+            select("[filter]\nmost_complex_functions = 3")?,
+            Some(vec![0, 1, 3])
+        );
+        assert_eq!(
+            select("[filter]\nmost_complex_functions = 10")?,
+            Some(vec![0, 1, 2, 3])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn seeded_sampling() -> Result<()> {
+        use Instruction::*;
+
+        let mut instructions = Vec::new();
+        for _ in 0..100 {
+            instructions.extend([I32Const(1), I32Const(2), I32Add, Drop]);
+        }
+        let module = crate::fixtures::functions(&[], &[], vec![instructions])?;
+
+        let config = Config::parse("[operators]\nenabled_operators = [\"binop_add_to_sub\"]")?;
+        let offsets = |seed: u32| -> Result<Vec<u64>> {
+            let mut offsets: Vec<u64> = MutationEngine::new(&config, 50)?
+                .with_seed(seed)
+                .discover_mutation_positions(&module)?
+                .locations
+                .iter()
+                .map(|location| location.offset)
+                .collect();
+            offsets.sort_unstable();
+            Ok(offsets)
+        };
+
+        let selected = offsets(42)?;
+        assert!(!selected.is_empty() && selected.len() < 100);
+        assert_eq!(offsets(42)?, selected);
+        assert_ne!(offsets(43)?, selected);
+        Ok(())
+    }
+
+    #[test]
+    fn candidates_per_file_match_discovery() -> Result<()> {
+        use Instruction::*;
+
+        let mut instructions = Vec::new();
+        for _ in 0..100 {
+            instructions.extend([I32Const(1), I32Const(2), I32Add, Drop]);
+        }
+        let module = crate::fixtures::functions(&[], &[], vec![instructions])?;
+
+        let config = Config::parse(
+            r#"
+            [operators]
+            enabled_operators = ["binop_add_to_sub"]
+
+            [filter]
+            max_mutants_per_function = 30
+            "#,
+        )?;
+
+        // Sampling does not influence the number of candidates
+        let counts = MutationEngine::new(&config, 50)?.count_candidates_per_file(&module)?;
+        assert_eq!(counts, BTreeMap::from([(None, 30)]));
+
+        let discovery = MutationEngine::new(&config, 100)?.discover_mutation_positions(&module)?;
+        assert_eq!(discovery.files, counts);
+        assert_eq!(count_mutants(&discovery.locations), 30);
+        Ok(())
+    }
+
+    #[test]
+    fn raw_source_paths() -> Result<()> {
+        let location = InstructionWalkerLocation {
+            file: Some("/build/src/add.c"),
+            raw_file: Some("/build/out/../src/add.c"),
+            function: Some("add"),
+            function_index: 0,
+            function_length: 10,
+            instruction_index: 0,
+            instruction_offset: 0,
+            branch_carries_values: false,
+            negated_result: false,
+            signedness: None,
+        };
+        let engine = |files: &str| {
+            let toml = format!("[filter]\nallowed_files = [{files:?}]");
+            MutationEngine::new(&Config::parse(&toml)?, 100)
+        };
+
+        // File patterns may match the normalized or the recorded path
+        assert!(engine("^/build/src/")?.in_scope(&location));
+        assert!(engine(r"/out/\.\./")?.in_scope(&location));
+        assert!(!engine("^/src/")?.in_scope(&location));
+
+        let config = Config::parse(
+            r#"
+            [[filter.overrides]]
+            files = ["^/build/out/"]
+            sample_threshold = 10
+            "#,
+        )?;
+        let engine = MutationEngine::new(&config, 100)?;
+        let registry = OperatorRegistry::default();
+        assert_eq!(engine.site_settings(&location, &registry).1, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn filter_scope() -> Result<()> {
+        use wasmut_wasm::elements::BlockType;
+        use Instruction::*;
+
+        // Functions with 5, 9, 3 and 9 instructions
+        let bodies = [1, 3, 0, 3]
+            .into_iter()
+            .map(|branches| {
+                let mut instructions = vec![Block(BlockType::NoResult)];
+                for _ in 0..branches {
+                    instructions.extend([I32Const(1), BrIf(0)]);
+                }
+                instructions.push(End);
+                instructions
+            })
+            .collect();
+        let module = crate::fixtures::functions(&[], &[], bodies)?;
+
+        let scope = MutationEngine::new(&Config::default(), 100)?
+            .discover_mutation_positions(&module)?
+            .scope;
+        assert!(!scope.is_restricted());
+        assert_eq!(scope.instructions, 26);
+
+        let config = Config::parse("[filter]\nmost_complex_functions = 2")?;
+        let scope = MutationEngine::new(&config, 100)?
+            .discover_mutation_positions(&module)?
+            .scope;
+        assert_eq!(
+            scope,
+            FilterScope {
+                functions: 4,
+                functions_in_scope: 2,
+                instructions: 26,
+                instructions_in_scope: 18,
+            }
+        );
+        assert!(scope.is_restricted());
+        assert_eq!(
+            scope.summary(),
+            "69.2% of the instructions (2 of 4 functions)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn operator_usage() -> Result<()> {
+        let module = crate::fixtures::simple_add()?;
+        let config = Config::parse(
+            r#"
+            [operators]
+            enabled_operators = ["binop_add_to_sub", "binop_rotl_to_rotr"]
+            "#,
+        )?;
+        let engine = MutationEngine::new(&config, 100)?;
+        let usage = engine.discover_mutation_positions(&module)?.operator_usage;
+
+        let counts: BTreeMap<&str, usize> = usage.counts().collect();
+        assert_eq!(counts.len(), 2);
+        assert!(counts["binop_add_to_sub"] > 0);
+        assert_eq!(counts["binop_rotl_to_rotr"], 0);
+        assert_eq!(usage.unused(), vec![String::from("binop_rotl_to_rotr")]);
+        Ok(())
+    }
+
+    #[test]
+    fn candidates_per_file() -> Result<()> {
+        let module = crate::fixtures::simple_add()?;
+        let config = Config::parse(
+            r#"
+            [filter]
+            allowed_files = ["simple_add.c"]
+            "#,
+        )?;
+        let engine = MutationEngine::new(&config, 100)?;
+
+        let counts = engine.count_candidates_per_file(&module)?;
+        let mutants = count_mutants(&engine.discover_mutation_positions(&module)?.locations);
+
+        assert_eq!(counts.len(), 1);
+        let (file, count) = counts.iter().next().unwrap();
+        assert!(file.as_ref().unwrap().ends_with("simple_add.c"));
+        assert_eq!(*count as i32, mutants);
+        Ok(())
+    }
+
+    #[test]
+    fn unused_operators() {
+        let usage = OperatorUsage {
+            counts: BTreeMap::from([("a".into(), 0), ("b".into(), 3), ("c".into(), 0)]),
+        };
+        assert_eq!(usage.unused(), vec![String::from("a"), String::from("c")]);
+    }
 }
@@ -0,0 +1,249 @@
+//! Notifications about finished `mutate` runs (`[notify]` section).
+//!
+//! When a run completes or fails, a summary is posted to the configured
+//! webhooks: a JSON object to `webhook_url`, and a message in the format of
+//! Slack incoming webhooks to `slack_webhook_url`. Sending requires the
+//! `notify` feature, which adds a minimal HTTP client. Notifications are sent
+//! after the report was written, and failing to send one only logs a warning.
+
+use serde::Serialize;
+
+use crate::reporter::AccumulatedOutcomes;
+
+/// Whether the run completed or failed
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Completed,
+    Failed,
+}
+
+/// Summary of a run, posted as JSON to `webhook_url`
+#[derive(Serialize, Clone)]
+pub struct RunNotification {
+    pub status: RunStatus,
+    /// Path of the mutated module
+    pub module: String,
+    /// Outcomes of the run, if it completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcomes: Option<AccumulatedOutcomes>,
+    /// Error message, if the run failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Link to the report, `notify.report_url`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report_url: Option<String>,
+}
+
+/// Message posted to Slack incoming webhooks
+#[derive(Serialize)]
+struct SlackMessage {
+    text: String,
+}
+
+impl RunNotification {
+    /// Notification about a completed run of `module`
+    pub fn completed(module: &str, outcomes: &AccumulatedOutcomes) -> Self {
+        Self {
+            status: RunStatus::Completed,
+            module: module.into(),
+            outcomes: Some(outcomes.clone()),
+            error: None,
+            report_url: None,
+        }
+    }
+
+    /// Notification about a failed run of `module`
+    pub fn failed(module: &str, error: &anyhow::Error) -> Self {
+        Self {
+            status: RunStatus::Failed,
+            module: module.into(),
+            outcomes: None,
+            error: Some(format!("{error:#}")),
+            report_url: None,
+        }
+    }
+
+    /// Link the notification to the report at `report_url`
+    pub fn with_report_url(self, report_url: Option<&str>) -> Self {
+        Self {
+            report_url: report_url.map(String::from),
+            ..self
+        }
+    }
+
+    /// Human readable summary of the run
+    pub fn message(&self) -> String {
+        let mut message = match (&self.outcomes, &self.error) {
+            (Some(outcomes), _) => format!(
+                "wasmut run of {} completed: mutation score {:.1}%, {} of {} mutants survived",
+                self.module, outcomes.mutation_score, outcomes.alive, outcomes.total
+            ),
+            (None, error) => format!(
+                "wasmut run of {} failed: {}",
+                self.module,
+                error.as_deref().unwrap_or("unknown error")
+            ),
+        };
+
+        if let Some(report_url) = &self.report_url {
+            message.push_str(&format!("\nReport: {report_url}"));
+        }
+        message
+    }
+
+    /// Body of the request to `webhook_url`
+    pub fn webhook_body(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Body of the request to `slack_webhook_url`
+    pub fn slack_body(&self) -> String {
+        serde_json::to_string(&SlackMessage {
+            text: self.message(),
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// Post `notification` to the webhooks configured in `config`.
+/// Failures are logged, but do not fail the run.
+#[cfg(feature = "notify")]
+pub fn send(config: &crate::config::NotifyConfig, notification: &RunNotification) {
+    let notification = notification.clone().with_report_url(config.report_url());
+
+    let requests = [
+        (config.webhook_url(), notification.webhook_body()),
+        (config.slack_webhook_url(), notification.slack_body()),
+    ];
+    for (url, body) in requests {
+        if let Some(url) = url {
+            match post(url, &body) {
+                Ok(()) => log::info!("Sent notification about the run"),
+                Err(e) => log::warn!("Could not send notification: {e:#}"),
+            }
+        }
+    }
+}
+
+/// Post the JSON `body` to `url`
+#[cfg(feature = "notify")]
+fn post(url: &str, body: &str) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    /// Notifications must not delay the end of a run for long
+    const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+    ureq::AgentBuilder::new()
+        .timeout(TIMEOUT)
+        .build()
+        .post(url)
+        .set("Content-Type", "application/json")
+        .send_string(body)
+        .map(|_| ())
+        .context("Webhook request failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcomes() -> AccumulatedOutcomes {
+        AccumulatedOutcomes {
+            total: 10,
+            alive: 2,
+            timeout: 1,
+            killed: 6,
+            error: 1,
+            skipped: 0,
+            filtered: 0,
+            not_run: 0,
+            internal_error: 0,
+            mutation_score: 80.0,
+        }
+    }
+
+    #[test]
+    fn completed_run() {
+        let notification = RunNotification::completed("test.wasm", &outcomes())
+            .with_report_url(Some("https://ci.example.com/report/index.html"));
+
+        assert_eq!(
+            notification.message(),
+            "wasmut run of test.wasm completed: mutation score 80.0%, 2 of 10 mutants survived\n\
+            Report: https://ci.example.com/report/index.html"
+        );
+        assert_eq!(
+            notification.slack_body(),
+            "{\"text\":\"wasmut run of test.wasm completed: mutation score 80.0%, \
+            2 of 10 mutants survived\\nReport: https://ci.example.com/report/index.html\"}"
+        );
+
+        let body: serde_json::Value = serde_json::from_str(&notification.webhook_body()).unwrap();
+        assert_eq!(body["status"], "completed");
+        assert_eq!(body["module"], "test.wasm");
+        assert_eq!(body["outcomes"]["alive"], 2);
+        assert_eq!(body["outcomes"]["mutation_score"], 80.0);
+        assert_eq!(
+            body["report_url"],
+            "https://ci.example.com/report/index.html"
+        );
+        assert!(body.get("error").is_none());
+    }
+
+    #[test]
+    fn failed_run() {
+        let error = anyhow::anyhow!("Module failed to execute").context("Baseline run failed");
+        let notification = RunNotification::failed("test.wasm", &error);
+
+        assert_eq!(
+            notification.message(),
+            "wasmut run of test.wasm failed: Baseline run failed: Module failed to execute"
+        );
+
+        let body: serde_json::Value = serde_json::from_str(&notification.webhook_body()).unwrap();
+        assert_eq!(body["status"], "failed");
+        assert!(body.get("outcomes").is_none());
+        assert!(body.get("report_url").is_none());
+    }
+
+    #[cfg(feature = "notify")]
+    #[test]
+    fn post_to_webhooks() -> anyhow::Result<()> {
+        use crate::config::Config;
+
+        let server = tiny_http::Server::http("127.0.0.1:0").map_err(|e| anyhow::anyhow!(e))?;
+        let address = server.server_addr().to_ip().unwrap();
+        let receiver = std::thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for mut request in server.incoming_requests().take(2) {
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                bodies.push((request.url().to_string(), body));
+                request.respond(tiny_http::Response::empty(200)).unwrap();
+            }
+            bodies
+        });
+
+        let config = Config::parse(&format!(
+            r#"
+            [notify]
+            webhook_url = "http://{address}/webhook"
+            slack_webhook_url = "http://{address}/slack"
+            "#
+        ))?;
+        send(
+            config.notify(),
+            &RunNotification::completed("test.wasm", &outcomes()),
+        );
+
+        let bodies = receiver.join().unwrap();
+        assert_eq!(bodies[0].0, "/webhook");
+        assert!(bodies[0].1.contains("\"status\":\"completed\""));
+        assert_eq!(bodies[1].0, "/slack");
+        assert!(bodies[1]
+            .1
+            .starts_with("{\"text\":\"wasmut run of test.wasm completed"));
+        Ok(())
+    }
+}
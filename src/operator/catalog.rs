@@ -0,0 +1,232 @@
+//! Machine-readable description of the mutation operators, so that consumers
+//! of reports can interpret operator names without consulting the source code.
+//!
+//! The instruction mappings are not maintained by hand, but generated by
+//! applying the operators of a registry to a set of probe instructions.
+
+use std::io::Cursor;
+
+use serde::{Deserialize, Serialize};
+use wasmut_wasm::elements::{BlockType, BrTableData, Deserialize as _, Instruction, ValueType};
+
+use super::{InstructionContext, OperatorRegistry};
+use crate::{
+    reporter::source_description,
+    wasmmodule::{CallRemovalCandidate, TableEntryCandidate},
+};
+
+/// An instruction and the instructions it is replaced with by an operator
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InstructionMapping {
+    /// Mnemonic of the original instruction, e.g. `i32.add`
+    pub from: String,
+    /// Replacement instructions, in text format
+    pub to: Vec<String>,
+}
+
+/// Catalog entry of a mutation operator
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OperatorCatalogEntry {
+    pub name: String,
+    /// Kind of instructions mutated by the operator, e.g. `relational`
+    pub category: String,
+    /// Description of the mutation in the notation of C-like languages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub mappings: Vec<InstructionMapping>,
+}
+
+/// Category of an operator, derived from the prefix of its name
+pub fn category(operator_name: &str) -> &'static str {
+    const CATEGORIES: &[(&str, &str)] = &[
+        ("binop_", "binary"),
+        ("unop_", "unary"),
+        ("relop_", "relational"),
+        ("conv_", "conversion"),
+        ("memory_", "memory"),
+        ("br_", "control flow"),
+        ("const_", "constant"),
+        ("call_", "call"),
+        ("table_", "call"),
+    ];
+
+    CATEGORIES
+        .iter()
+        .find(|(prefix, _)| operator_name.starts_with(prefix))
+        .map(|(_, category)| *category)
+        .unwrap_or("other")
+}
+
+/// Catalog of all operators known to wasmut, regardless of the configuration
+pub fn catalog() -> Vec<OperatorCatalogEntry> {
+    OperatorRegistry::new(&[""])
+        .expect("Empty pattern is valid")
+        .catalog()
+}
+
+impl OperatorRegistry {
+    /// Catalog of the enabled operators, in registration order
+    pub fn catalog(&self) -> Vec<OperatorCatalogEntry> {
+        let probes = probe_instructions();
+        let context = probe_context();
+
+        self.operators
+            .iter()
+            .zip(&self.enabled_operator_names)
+            .map(|(factory, name)| {
+                let mut mappings: Vec<InstructionMapping> = Vec::new();
+                for probe in &probes {
+                    let Some(mutant) = factory(probe, &context) else {
+                        continue;
+                    };
+                    let mapping = InstructionMapping {
+                        from: mnemonic(probe),
+                        to: mutant.replacement().iter().map(|i| i.to_string()).collect(),
+                    };
+                    // Probes with different immediates may map identically
+                    if !mappings.contains(&mapping) {
+                        mappings.push(mapping);
+                    }
+                }
+
+                OperatorCatalogEntry {
+                    name: name.clone(),
+                    category: category(name).into(),
+                    description: source_description(name).map(String::from),
+                    mappings,
+                }
+            })
+            .collect()
+    }
+}
+
+fn mnemonic(instruction: &Instruction) -> String {
+    let text = instruction.to_string();
+    text.split_whitespace().next().unwrap_or_default().into()
+}
+
+/// All instructions without immediates in the numeric opcode range,
+/// plus one representative of each instruction with immediates that
+/// is targeted by an operator.
+fn probe_instructions() -> Vec<Instruction> {
+    // i32.eqz up to f64.reinterpret/i64
+    let mut probes: Vec<Instruction> = (0x45u8..=0xbf)
+        .filter_map(|opcode| Instruction::deserialize(&mut Cursor::new([opcode])).ok())
+        .collect();
+
+    probes.extend([
+        Instruction::I32Const(0),
+        Instruction::I32Const(1),
+        Instruction::I64Const(0),
+        Instruction::I64Const(1),
+        Instruction::F32Const(0f32.to_bits()),
+        Instruction::F32Const(1f32.to_bits()),
+        Instruction::F64Const(0f64.to_bits()),
+        Instruction::F64Const(1f64.to_bits()),
+        Instruction::GrowMemory(0),
+        Instruction::BrTable(Box::new(BrTableData {
+            table: vec![0, 1].into_boxed_slice(),
+            default: 2,
+        })),
+        Instruction::Call(0),
+        Instruction::Call(1),
+        Instruction::CallIndirect(0, 0),
+    ]);
+
+    probes
+}
+
+/// Context in which the probe calls are removable, and the probe
+/// indirect call can be redirected
+fn probe_context() -> InstructionContext {
+    InstructionContext::new(vec![
+        CallRemovalCandidate::FuncReturningVoid {
+            index: 0,
+            params: vec![],
+        },
+        CallRemovalCandidate::FuncReturningScalar {
+            index: 1,
+            params: vec![],
+            return_type: ValueType::I32,
+        },
+    ])
+    .with_table_entry_candidates(vec![TableEntryCandidate {
+        type_index: 0,
+        params: vec![],
+        result: BlockType::NoResult,
+        slots: (0, 1),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> OperatorCatalogEntry {
+        catalog()
+            .into_iter()
+            .find(|entry| entry.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn catalog_covers_all_operators() {
+        let registry = OperatorRegistry::new(&[""]).unwrap();
+        let catalog = catalog();
+
+        assert_eq!(catalog.len(), registry.enabled_operators().len());
+        for entry in catalog {
+            assert!(!entry.mappings.is_empty(), "{} has no mappings", entry.name);
+            assert_ne!(entry.category, "other", "{} has no category", entry.name);
+        }
+    }
+
+    #[test]
+    fn catalog_mappings() {
+        let add_to_sub = entry("binop_add_to_sub");
+        assert_eq!(add_to_sub.category, "binary");
+        assert_eq!(
+            add_to_sub.description.as_deref(),
+            Some("`+` replaced by `-`")
+        );
+        assert_eq!(
+            add_to_sub.mappings,
+            vec![
+                InstructionMapping {
+                    from: "i32.add".into(),
+                    to: vec!["i32.sub".into()],
+                },
+                InstructionMapping {
+                    from: "i64.add".into(),
+                    to: vec!["i64.sub".into()],
+                },
+                InstructionMapping {
+                    from: "f32.add".into(),
+                    to: vec!["f32.sub".into()],
+                },
+                InstructionMapping {
+                    from: "f64.add".into(),
+                    to: vec!["f64.sub".into()],
+                },
+            ]
+        );
+
+        let remove_void_call = entry("call_remove_void_call");
+        assert_eq!(remove_void_call.category, "call");
+        assert_eq!(remove_void_call.mappings.len(), 1);
+        assert_eq!(remove_void_call.mappings[0].from, "call");
+    }
+
+    #[test]
+    fn catalog_of_registry_contains_enabled_operators() {
+        let registry = OperatorRegistry::new(&["relop_eq"]).unwrap();
+        let names: Vec<String> = registry
+            .catalog()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+
+        assert_eq!(names, vec!["relop_eq_to_ne"]);
+    }
+}
@@ -1,13 +1,25 @@
+pub mod catalog;
 pub mod ops;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dyn_clone::DynClone;
 use ops::*;
+use serde::{Deserialize, Serialize};
 #[allow(unused_imports)]
 use wasmut_wasm::elements::Instruction::{self, *};
 use wasmut_wasm::elements::{BlockType, ValueType};
 
-use crate::wasmmodule::CallRemovalCandidate;
+use crate::{
+    config::OperatorConfig,
+    error::ErrorKind,
+    warnings::{self, WarningCategory},
+    wasmmodule::{
+        CallRemovalCandidate, InstructionWalkerLocation, Signedness, TableEntryCandidate,
+    },
+};
+
+/// Maximum edit distance of operator names suggested for unmatched patterns
+const MAX_SUGGESTION_DISTANCE: usize = 4;
 
 pub trait InstructionReplacement: Send + Sync + std::fmt::Debug + DynClone {
     fn old_instruction(&self) -> &Instruction;
@@ -21,16 +33,55 @@ pub trait InstructionReplacement: Send + Sync + std::fmt::Debug + DynClone {
 
     fn description(&self) -> String;
 
-    fn apply(&self, instructions: &mut Vec<Instruction>, instr_index: u64) {
-        assert_eq!(instructions[instr_index as usize], *self.old_instruction());
+    /// True if the replacement contains branch instructions.
+    ///
+    /// Meta-mutants move replacements into additional blocks, which is only possible
+    /// for branches that do not carry values, see `OperatorRegistry::mutants_for_location`.
+    fn contains_branch(&self) -> bool {
+        false
+    }
+
+    /// Whether the operator only applies to instructions whose result is negated by
+    /// an immediately following `i32.eqz` (`Some(true)`), only to instructions whose
+    /// result is not negated (`Some(false)`), or to both (`None`).
+    ///
+    /// Compilers emit unordered float comparisons as the negation of the inverse
+    /// ordered comparison, so swapping ordered and unordered comparisons depends on it,
+    /// see `OperatorRegistry::mutants_for_location`.
+    fn applies_to_negated_result(&self) -> Option<bool> {
+        None
+    }
 
-        instructions.remove(instr_index as usize);
+    /// Signedness of the operand the operator applies to, or `None` if it applies
+    /// regardless of the signedness. Operators requiring a signedness are only applied
+    /// at locations where the surrounding instructions tell the signedness,
+    /// see `OperatorRegistry::mutants_for_location`.
+    fn applies_to_signedness(&self) -> Option<Signedness> {
+        None
+    }
 
-        for replacement_instr in self.replacement().iter().rev() {
-            instructions.insert(instr_index as usize, replacement_instr.clone());
+    /// Result of the original instruction for which the mutant behaves differently,
+    /// if the mutant only differs for one of the two possible results.
+    ///
+    /// Boundary mutations of relational operators (e.g. `<` to `<=`) only change
+    /// the result if both operands are equal. If the result is consumed by a
+    /// conditional branch, the mutant can only be killed if the corresponding
+    /// edge of the branch is taken.
+    fn changed_condition(&self) -> Option<bool> {
+        match self.dyn_name() {
+            "relop_lt_to_le" | "relop_gt_to_ge" => Some(false),
+            "relop_le_to_lt" | "relop_ge_to_gt" => Some(true),
+            _ => None,
         }
     }
 
+    fn apply(&self, instructions: &mut Vec<Instruction>, instr_index: u64) {
+        let index = instr_index as usize;
+        assert_eq!(instructions[index], *self.old_instruction());
+
+        instructions.splice(index..=index, self.replacement());
+    }
+
     fn name() -> &'static str
     where
         Self: Sized + 'static;
@@ -47,18 +98,77 @@ dyn_clone::clone_trait_object!(InstructionReplacement);
 #[derive(Default)]
 pub struct InstructionContext {
     call_removal_candidates: Vec<CallRemovalCandidate>,
+    table_entry_candidates: Vec<TableEntryCandidate>,
 }
 
 impl InstructionContext {
     pub fn new(call_removal_candidates: Vec<CallRemovalCandidate>) -> Self {
         Self {
             call_removal_candidates,
+            table_entry_candidates: Vec::new(),
         }
     }
 
+    /// Table slots `call_indirect` instructions may be redirected to
+    pub fn with_table_entry_candidates(
+        mut self,
+        table_entry_candidates: Vec<TableEntryCandidate>,
+    ) -> Self {
+        self.table_entry_candidates = table_entry_candidates;
+        self
+    }
+
     fn call_removal_candidates(&self) -> &[CallRemovalCandidate] {
         &self.call_removal_candidates
     }
+
+    fn table_entry_candidates(&self) -> &[TableEntryCandidate] {
+        &self.table_entry_candidates
+    }
+}
+
+/// Curated sets of operators, selected by `[operators] preset`, so that
+/// newcomers do not have to learn operator names to get started
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperatorPreset {
+    /// Relational operators and constants, producing few mutants
+    Light,
+
+    /// Arithmetic, logical, relational and conversion operators, constants and
+    /// removed void calls. Operators on memory growth, branch tables, scalar calls
+    /// and function tables are left out, since their mutants rarely point to missing tests.
+    Standard,
+
+    /// All operators, including those added in future versions
+    Aggressive,
+}
+
+/// Operators enabled by `OperatorPreset::Light`
+const LIGHT_PRESET: &[&str] = &["^relop_", "^const_"];
+
+/// Operators enabled by `OperatorPreset::Standard`
+const STANDARD_PRESET: &[&str] = &[
+    "^binop_",
+    "^unop_",
+    "^relop_",
+    "^conv_",
+    "^const_",
+    "^call_remove_void_call$",
+];
+
+/// Operators enabled by `OperatorPreset::Aggressive`
+const AGGRESSIVE_PRESET: &[&str] = &[""];
+
+impl OperatorPreset {
+    /// Regexes of all operators enabled by the preset
+    pub fn enabled_operators(self) -> &'static [&'static str] {
+        match self {
+            OperatorPreset::Light => LIGHT_PRESET,
+            OperatorPreset::Standard => STANDARD_PRESET,
+            OperatorPreset::Aggressive => AGGRESSIVE_PRESET,
+        }
+    }
 }
 
 type FactoryFunction =
@@ -72,8 +182,8 @@ pub struct OperatorRegistry {
 }
 
 macro_rules! register_operator {
-    ($operator:ident, $v:ident, $regex_set:ident) => {
-        if $regex_set.is_match(&$operator::name()) {
+    ($operator:ident, $v:ident, $regex_set:ident, $deny_set:ident) => {
+        if $regex_set.is_match(&$operator::name()) && !$deny_set.is_match(&$operator::name()) {
             $v.operators.push($operator::factory());
             $v.enabled_operator_names
                 .push(String::from($operator::name()))
@@ -85,59 +195,169 @@ macro_rules! register_operator {
 }
 
 impl OperatorRegistry {
+    /// Create a registry with all operators matched by `enabled_ops`
     pub fn new<S: AsRef<str>>(enabled_ops: &[S]) -> Result<Self> {
+        Self::with_disabled(enabled_ops, &[] as &[&str])
+    }
+
+    /// Create a registry based on the `[operators]` section of the configuration
+    pub fn from_config(config: &OperatorConfig) -> Result<Self> {
+        Self::with_disabled(&config.enabled_operators(), &config.disabled_operators())
+    }
+
+    /// Create a registry with all operators matched by `enabled_ops`,
+    /// except those matched by `disabled_ops`.
+    pub fn with_disabled<S: AsRef<str>, T: AsRef<str>>(
+        enabled_ops: &[S],
+        disabled_ops: &[T],
+    ) -> Result<Self> {
         let mut registry: OperatorRegistry = Default::default();
 
-        let regex_set = regex::RegexSet::new(enabled_ops).unwrap();
+        let regex_set = Self::compile_patterns(enabled_ops)?;
+        let deny_set = Self::compile_patterns(disabled_ops)?;
+
+        register_operator!(BinaryOperatorSubToAdd, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorAddToSub, registry, regex_set, deny_set);
 
-        register_operator!(BinaryOperatorSubToAdd, registry, regex_set);
-        register_operator!(BinaryOperatorAddToSub, registry, regex_set);
+        register_operator!(BinaryOperatorMulToDivS, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorMulToDivU, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorDivXToMul, registry, regex_set, deny_set);
 
-        register_operator!(BinaryOperatorMulToDivS, registry, regex_set);
-        register_operator!(BinaryOperatorMulToDivU, registry, regex_set);
-        register_operator!(BinaryOperatorDivXToMul, registry, regex_set);
+        register_operator!(BinaryOperatorShlToShrS, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorShlToShrU, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorShrXToShl, registry, regex_set, deny_set);
 
-        register_operator!(BinaryOperatorShlToShrS, registry, regex_set);
-        register_operator!(BinaryOperatorShlToShrU, registry, regex_set);
-        register_operator!(BinaryOperatorShrXToShl, registry, regex_set);
+        register_operator!(BinaryOperatorRemToDiv, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorDivToRem, registry, regex_set, deny_set);
 
-        register_operator!(BinaryOperatorRemToDiv, registry, regex_set);
-        register_operator!(BinaryOperatorDivToRem, registry, regex_set);
+        register_operator!(BinaryOperatorAndToOr, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorOrToAnd, registry, regex_set, deny_set);
 
-        register_operator!(BinaryOperatorAndToOr, registry, regex_set);
-        register_operator!(BinaryOperatorOrToAnd, registry, regex_set);
+        register_operator!(BinaryOperatorXorToOr, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorOrToXor, registry, regex_set, deny_set);
 
-        register_operator!(BinaryOperatorXorToOr, registry, regex_set);
-        register_operator!(BinaryOperatorOrToXor, registry, regex_set);
+        register_operator!(BinaryOperatorRotlToRotr, registry, regex_set, deny_set);
+        register_operator!(BinaryOperatorRotrToRotl, registry, regex_set, deny_set);
 
-        register_operator!(BinaryOperatorRotlToRotr, registry, regex_set);
-        register_operator!(BinaryOperatorRotrToRotl, registry, regex_set);
+        register_operator!(UnaryOperatorNegToNop, registry, regex_set, deny_set);
 
-        register_operator!(UnaryOperatorNegToNop, registry, regex_set);
+        register_operator!(RelationalOperatorEqToNe, registry, regex_set, deny_set);
+        register_operator!(RelationalOperatorNeToEq, registry, regex_set, deny_set);
 
-        register_operator!(RelationalOperatorEqToNe, registry, regex_set);
-        register_operator!(RelationalOperatorNeToEq, registry, regex_set);
+        register_operator!(RelationalOperatorLeToGt, registry, regex_set, deny_set);
+        register_operator!(RelationalOperatorLeToLt, registry, regex_set, deny_set);
+
+        register_operator!(RelationalOperatorLtToGe, registry, regex_set, deny_set);
+        register_operator!(RelationalOperatorLtToLe, registry, regex_set, deny_set);
+
+        register_operator!(RelationalOperatorGeToGt, registry, regex_set, deny_set);
+        register_operator!(RelationalOperatorGeToLt, registry, regex_set, deny_set);
+
+        register_operator!(RelationalOperatorGtToGe, registry, regex_set, deny_set);
+        register_operator!(RelationalOperatorGtToLe, registry, regex_set, deny_set);
+
+        register_operator!(
+            RelationalOperatorOrderedToUnordered,
+            registry,
+            regex_set,
+            deny_set
+        );
+        register_operator!(
+            RelationalOperatorUnorderedToOrdered,
+            registry,
+            regex_set,
+            deny_set
+        );
 
-        register_operator!(RelationalOperatorLeToGt, registry, regex_set);
-        register_operator!(RelationalOperatorLeToLt, registry, regex_set);
+        register_operator!(ConversionExtendSToU, registry, regex_set, deny_set);
+        register_operator!(ConversionExtendUToS, registry, regex_set, deny_set);
+        register_operator!(ConversionWrapToCheckedS, registry, regex_set, deny_set);
+        register_operator!(ConversionWrapToCheckedU, registry, regex_set, deny_set);
 
-        register_operator!(RelationalOperatorLtToGe, registry, regex_set);
-        register_operator!(RelationalOperatorLtToLe, registry, regex_set);
+        register_operator!(MemoryGrowForceFailure, registry, regex_set, deny_set);
+        register_operator!(MemoryGrowForceSuccess, registry, regex_set, deny_set);
 
-        register_operator!(RelationalOperatorGeToGt, registry, regex_set);
-        register_operator!(RelationalOperatorGeToLt, registry, regex_set);
+        register_operator!(BrTableRotateTargets, registry, regex_set, deny_set);
+        register_operator!(BrTableSwapDefault, registry, regex_set, deny_set);
 
-        register_operator!(RelationalOperatorGtToGe, registry, regex_set);
-        register_operator!(RelationalOperatorGtToLe, registry, regex_set);
+        register_operator!(ConstReplaceZero, registry, regex_set, deny_set);
+        register_operator!(ConstReplaceNonZero, registry, regex_set, deny_set);
+        register_operator!(CallRemoveVoidCall, registry, regex_set, deny_set);
+        register_operator!(CallRemoveScalarCall, registry, regex_set, deny_set);
+        register_operator!(TableEntryReplace, registry, regex_set, deny_set);
 
-        register_operator!(ConstReplaceZero, registry, regex_set);
-        register_operator!(ConstReplaceNonZero, registry, regex_set);
-        register_operator!(CallRemoveVoidCall, registry, regex_set);
-        register_operator!(CallRemoveScalarCall, registry, regex_set);
+        registry.check_unmatched_patterns(enabled_ops);
+        registry.check_unmatched_patterns(disabled_ops);
 
         Ok(registry)
     }
 
+    fn compile_patterns<S: AsRef<str>>(patterns: &[S]) -> Result<regex::RegexSet> {
+        for pattern in patterns {
+            let pattern = pattern.as_ref();
+            regex::Regex::new(pattern)
+                .with_context(|| format!("Invalid operator pattern \"{pattern}\""))
+                .context(ErrorKind::Config)?;
+        }
+
+        regex::RegexSet::new(patterns).context(ErrorKind::Config)
+    }
+
+    /// Emit a warning for every pattern that does not match any operator,
+    /// suggesting operator names that are similar to the pattern.
+    fn check_unmatched_patterns<S: AsRef<str>>(&self, enabled_ops: &[S]) {
+        for pattern in enabled_ops {
+            if let Some(message) = self.unmatched_pattern_message(pattern.as_ref()) {
+                warnings::warn(WarningCategory::UnmatchedOperatorPattern, message);
+            }
+        }
+    }
+
+    fn unmatched_pattern_message(&self, pattern: &str) -> Option<String> {
+        // Patterns were validated before the registry was populated
+        let regex = regex::Regex::new(pattern).ok()?;
+
+        if self.all_operators().any(|name| regex.is_match(name)) {
+            return None;
+        }
+
+        let suggestions = self.suggestions(pattern);
+
+        let message = if suggestions.is_empty() {
+            format!("Operator pattern \"{pattern}\" does not match any operator")
+        } else {
+            format!(
+                "Operator pattern \"{pattern}\" does not match any operator, did you mean {}?",
+                suggestions.join(", ")
+            )
+        };
+
+        Some(message)
+    }
+
+    /// Return operator names that are close to `pattern`, closest first
+    fn suggestions(&self, pattern: &str) -> Vec<String> {
+        let mut candidates: Vec<(usize, &str)> = self
+            .all_operators()
+            .map(|name| (strsim::levenshtein(pattern, name), name.as_str()))
+            .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+            .collect();
+
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates
+            .into_iter()
+            .map(|(_, name)| format!("\"{name}\""))
+            .collect()
+    }
+
+    fn all_operators(&self) -> impl Iterator<Item = &String> {
+        self.enabled_operator_names
+            .iter()
+            .chain(self.disabled_operator_names.iter())
+    }
+
     pub fn mutants_for_instruction(
         &self,
         instruction: &Instruction,
@@ -153,6 +373,37 @@ impl OperatorRegistry {
         results
     }
 
+    /// Mutants for an instruction at `location`.
+    ///
+    /// Mutations containing branches are skipped if the branch carries values,
+    /// since the values cannot be passed into the blocks of a meta-mutant.
+    /// Operators depending on whether the result of the instruction is negated
+    /// are only applied if `location` matches, see `applies_to_negated_result`,
+    /// as are operators depending on the signedness of the operand,
+    /// see `applies_to_signedness`.
+    pub fn mutants_for_location(
+        &self,
+        instruction: &Instruction,
+        context: &InstructionContext,
+        location: &InstructionWalkerLocation,
+    ) -> Vec<Box<dyn InstructionReplacement>> {
+        let mut mutants = self.mutants_for_instruction(instruction, context);
+        if location.branch_carries_values {
+            mutants.retain(|mutant| !mutant.contains_branch());
+        }
+        mutants.retain(|mutant| {
+            mutant
+                .applies_to_negated_result()
+                .unwrap_or(location.negated_result)
+                == location.negated_result
+        });
+        mutants.retain(|mutant| match mutant.applies_to_signedness() {
+            Some(signedness) => location.signedness == Some(signedness),
+            None => true,
+        });
+        mutants
+    }
+
     #[allow(dead_code)]
     fn number_of_operators(&self) -> usize {
         self.operators.len()
@@ -174,7 +425,7 @@ mod tests {
 
     use super::*;
     use concat_idents::concat_idents;
-    use wasmut_wasm::elements::ValueType;
+    use wasmut_wasm::elements::{BrTableData, ValueType};
 
     macro_rules! generate_test {
         ($operator:ident, $original:ident, $replacement:ident, $block_type:expr) => {
@@ -895,6 +1146,319 @@ mod tests {
         BlockType::Value(ValueType::I32)
     );
 
+    generate_test!(
+        conv_extend_s_to_u,
+        I64ExtendSI32,
+        I64ExtendUI32,
+        BlockType::Value(ValueType::I64)
+    );
+    generate_test!(
+        conv_extend_u_to_s,
+        I64ExtendUI32,
+        I64ExtendSI32,
+        BlockType::Value(ValueType::I64)
+    );
+
+    #[test]
+    fn conv_wrap_to_checked() {
+        let registry = OperatorRegistry::new(&["conv_wrap_to_checked"]).unwrap();
+        let ops = registry.mutants_for_instruction(&I32WrapI64, &Default::default());
+        assert_eq!(ops.len(), 2);
+
+        let replacements: Vec<Vec<Instruction>> = ops
+            .iter()
+            .map(|op| {
+                assert_eq!(op.parameters(), &[ValueType::I64]);
+                assert_eq!(op.result(), BlockType::Value(ValueType::I32));
+
+                let mut instr = vec![GetLocal(0), I32WrapI64, End];
+                op.apply(&mut instr, 1);
+                instr
+            })
+            .collect();
+
+        assert_eq!(
+            replacements,
+            vec![
+                vec![GetLocal(0), F64ConvertSI64, I32TruncSF64, End],
+                vec![GetLocal(0), F64ConvertUI64, I32TruncUF64, End],
+            ]
+        );
+
+        assert!(registry
+            .mutants_for_instruction(&I64ExtendUI32, &Default::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn conv_wrap_to_checked_by_signedness() {
+        let registry = OperatorRegistry::new(&["conv_wrap_to_checked"]).unwrap();
+        let mut location = InstructionWalkerLocation {
+            file: None,
+            raw_file: None,
+            function: None,
+            function_index: 0,
+            function_length: 0,
+            instruction_index: 0,
+            instruction_offset: 0,
+            branch_carries_values: false,
+            negated_result: false,
+            signedness: None,
+        };
+        let context = Default::default();
+        let replacements = |location: &InstructionWalkerLocation| -> Vec<Vec<Instruction>> {
+            registry
+                .mutants_for_location(&I32WrapI64, &context, location)
+                .iter()
+                .map(|mutant| mutant.replacement())
+                .collect()
+        };
+
+        assert!(replacements(&location).is_empty());
+        location.signedness = Some(Signedness::Signed);
+        assert_eq!(
+            replacements(&location),
+            [vec![F64ConvertSI64, I32TruncSF64]]
+        );
+        location.signedness = Some(Signedness::Unsigned);
+        assert_eq!(
+            replacements(&location),
+            [vec![F64ConvertUI64, I32TruncUF64]]
+        );
+    }
+
+    #[test]
+    fn relop_ordered_to_unordered() {
+        let registry = OperatorRegistry::new(&["relop_ordered_to_unordered"]).unwrap();
+
+        for (original, inverse, operand) in [
+            (F32Lt, F32Ge, ValueType::F32),
+            (F32Le, F32Gt, ValueType::F32),
+            (F32Gt, F32Le, ValueType::F32),
+            (F32Ge, F32Lt, ValueType::F32),
+            (F64Lt, F64Ge, ValueType::F64),
+            (F64Le, F64Gt, ValueType::F64),
+            (F64Gt, F64Le, ValueType::F64),
+            (F64Ge, F64Lt, ValueType::F64),
+        ] {
+            let ops = registry.mutants_for_instruction(&original, &Default::default());
+            assert_eq!(ops.len(), 1);
+            assert_eq!(ops[0].parameters(), &[operand, operand]);
+            assert_eq!(ops[0].result(), BlockType::Value(ValueType::I32));
+            assert_eq!(ops[0].changed_condition(), None);
+
+            let mut instr = vec![GetLocal(0), GetLocal(1), original, End];
+            ops[0].apply(&mut instr, 2);
+            assert_eq!(instr, vec![GetLocal(0), GetLocal(1), inverse, I32Eqz, End]);
+        }
+
+        for instr in [F32Eq, F32Ne, F64Eq, F64Ne, I32LtS, I64GeU] {
+            assert!(registry
+                .mutants_for_instruction(&instr, &Default::default())
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn relop_unordered_to_ordered() {
+        let registry = OperatorRegistry::new(&["relop_unordered_to_ordered"]).unwrap();
+
+        // `!(a >= b)` becomes `!!(a < b)`, i.e. `a < b`
+        for (negated, ordered, operand) in [
+            (F32Ge, F32Lt, ValueType::F32),
+            (F32Gt, F32Le, ValueType::F32),
+            (F32Le, F32Gt, ValueType::F32),
+            (F32Lt, F32Ge, ValueType::F32),
+            (F64Ge, F64Lt, ValueType::F64),
+            (F64Gt, F64Le, ValueType::F64),
+            (F64Le, F64Gt, ValueType::F64),
+            (F64Lt, F64Ge, ValueType::F64),
+        ] {
+            let ops = registry.mutants_for_instruction(&negated, &Default::default());
+            assert_eq!(ops.len(), 1);
+            assert_eq!(ops[0].parameters(), &[operand, operand]);
+            assert_eq!(ops[0].applies_to_negated_result(), Some(true));
+
+            let mut instr = vec![GetLocal(0), GetLocal(1), negated, I32Eqz, End];
+            ops[0].apply(&mut instr, 2);
+            assert_eq!(
+                instr,
+                vec![GetLocal(0), GetLocal(1), ordered, I32Eqz, I32Eqz, End]
+            );
+        }
+    }
+
+    #[test]
+    fn ordered_and_unordered_comparisons() {
+        let registry = OperatorRegistry::new(&["relop_.*ordered"]).unwrap();
+        let mut location = InstructionWalkerLocation {
+            file: None,
+            raw_file: None,
+            function: None,
+            function_index: 0,
+            function_length: 0,
+            instruction_index: 0,
+            instruction_offset: 0,
+            branch_carries_values: false,
+            negated_result: false,
+            signedness: None,
+        };
+        let context = Default::default();
+        let names = |location: &InstructionWalkerLocation| -> Vec<&'static str> {
+            registry
+                .mutants_for_location(&F64Lt, &context, location)
+                .iter()
+                .map(|mutant| mutant.dyn_name())
+                .collect()
+        };
+
+        assert_eq!(names(&location), ["relop_ordered_to_unordered"]);
+        location.negated_result = true;
+        assert_eq!(names(&location), ["relop_unordered_to_ordered"]);
+    }
+
+    #[test]
+    fn table_entry_replace() {
+        let registry = OperatorRegistry::new(&["table_entry_replace"]).unwrap();
+        let context =
+            InstructionContext::default().with_table_entry_candidates(vec![TableEntryCandidate {
+                type_index: 1,
+                params: vec![ValueType::F32],
+                result: BlockType::Value(ValueType::I64),
+                slots: (3, 5),
+            }]);
+
+        let ops = registry.mutants_for_instruction(&CallIndirect(1, 0), &context);
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].parameters(), &[ValueType::F32, ValueType::I32]);
+        assert_eq!(ops[0].result(), BlockType::Value(ValueType::I64));
+
+        let mut instr = vec![GetLocal(0), GetLocal(1), CallIndirect(1, 0), End];
+        ops[0].apply(&mut instr, 2);
+        assert_eq!(
+            instr,
+            vec![
+                GetLocal(0),
+                GetLocal(1),
+                I32Const(3),
+                I32Eq,
+                If(BlockType::Value(ValueType::I32)),
+                I32Const(5),
+                Else,
+                I32Const(3),
+                End,
+                CallIndirect(1, 0),
+                End
+            ]
+        );
+
+        // Types without two distinct functions in the table
+        assert!(registry
+            .mutants_for_instruction(&CallIndirect(0, 0), &context)
+            .is_empty());
+    }
+
+    #[test]
+    fn memory_grow_force_result() {
+        let registry = OperatorRegistry::new(&["memory_grow_force_result"]).unwrap();
+        let ops = registry.mutants_for_instruction(&GrowMemory(0), &Default::default());
+        assert_eq!(ops.len(), 2);
+
+        let replacements: Vec<Vec<Instruction>> = ops
+            .iter()
+            .map(|op| {
+                assert_eq!(op.parameters(), &[ValueType::I32]);
+                assert_eq!(op.result(), BlockType::Value(ValueType::I32));
+
+                let mut instr = vec![I32Const(1), GrowMemory(0), End];
+                op.apply(&mut instr, 1);
+                instr
+            })
+            .collect();
+
+        assert_eq!(
+            replacements,
+            vec![
+                vec![I32Const(1), Drop, I32Const(-1), End],
+                vec![I32Const(1), Drop, CurrentMemory(0), End],
+            ]
+        );
+
+        let registry = OperatorRegistry::new([].as_slice() as &[&str]).unwrap();
+        assert!(registry
+            .mutants_for_instruction(&GrowMemory(0), &Default::default())
+            .is_empty());
+    }
+
+    fn br_table(table: &[u32], default: u32) -> Instruction {
+        BrTable(Box::new(BrTableData {
+            table: table.into(),
+            default,
+        }))
+    }
+
+    #[test]
+    fn br_table_ops() {
+        let registry = OperatorRegistry::new(&["br_table_"]).unwrap();
+        let ops = registry.mutants_for_instruction(&br_table(&[0, 1, 2], 3), &Default::default());
+        assert_eq!(ops.len(), 2);
+
+        let replacements: Vec<Instruction> = ops
+            .iter()
+            .map(|op| {
+                assert!(op.contains_branch());
+                assert_eq!(op.parameters(), &[ValueType::I32]);
+                assert_eq!(op.result(), BlockType::NoResult);
+
+                let mut instr = vec![br_table(&[0, 1, 2], 3)];
+                op.apply(&mut instr, 0);
+                instr.remove(0)
+            })
+            .collect();
+        assert_eq!(
+            replacements,
+            vec![br_table(&[1, 2, 0], 3), br_table(&[3, 1, 2], 0)]
+        );
+
+        // Mutations that would not change the behavior are skipped
+        assert!(registry
+            .mutants_for_instruction(&br_table(&[1, 1], 1), &Default::default())
+            .is_empty());
+        assert!(registry
+            .mutants_for_instruction(&br_table(&[], 0), &Default::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn br_table_carrying_values() {
+        let registry = OperatorRegistry::new(&["br_table_"]).unwrap();
+        let mut location = InstructionWalkerLocation {
+            file: None,
+            raw_file: None,
+            function: None,
+            function_index: 0,
+            function_length: 0,
+            instruction_index: 0,
+            instruction_offset: 0,
+            branch_carries_values: false,
+            negated_result: false,
+            signedness: None,
+        };
+        let instruction = br_table(&[0, 1], 2);
+        let context = Default::default();
+        assert_eq!(
+            registry
+                .mutants_for_location(&instruction, &context, &location)
+                .len(),
+            2
+        );
+
+        location.branch_carries_values = true;
+        assert!(registry
+            .mutants_for_location(&instruction, &context, &location)
+            .is_empty());
+    }
+
     generate_const_test!(
         const_replace_zero,
         i32,
@@ -953,6 +1517,33 @@ mod tests {
         BlockType::Value(ValueType::F64)
     );
 
+    #[test]
+    fn changed_condition_of_boundary_relops() {
+        let registry = OperatorRegistry::new(["relop_.*"].as_slice()).unwrap();
+        let context = InstructionContext::default();
+
+        let changed_conditions = |instruction: &Instruction| -> Vec<(&str, Option<bool>)> {
+            registry
+                .mutants_for_instruction(instruction, &context)
+                .iter()
+                .map(|op| (op.dyn_name(), op.changed_condition()))
+                .collect()
+        };
+
+        // `<` and `<=` only differ if both operands are equal
+        let lt = changed_conditions(&I32LtS);
+        assert!(lt.contains(&("relop_lt_to_le", Some(false))));
+        assert!(lt.contains(&("relop_lt_to_ge", None)));
+
+        let ge = changed_conditions(&F64Ge);
+        assert!(ge.contains(&("relop_ge_to_gt", Some(true))));
+        assert!(ge.contains(&("relop_ge_to_lt", None)));
+
+        assert!(changed_conditions(&I32Add)
+            .iter()
+            .all(|(_, condition)| condition.is_none()));
+    }
+
     #[test]
     fn call_remove_void_call_enabled() {
         let registry = OperatorRegistry::new(["call_remove_void_call"].as_slice()).unwrap();
@@ -1077,9 +1668,80 @@ mod tests {
                 .number_of_operators(),
             2
         );
+        assert_eq!(
+            OperatorRegistry::new(&["conv_"])
+                .unwrap()
+                .number_of_operators(),
+            4
+        );
         assert_eq!(
             OperatorRegistry::new(&[""]).unwrap().number_of_operators(),
-            31
+            42
         );
     }
+
+    #[test]
+    fn registry_disabled_operators() {
+        let registry =
+            OperatorRegistry::with_disabled(&["binop_"], &["binop_or_to_xor", "binop_shl"])
+                .unwrap();
+        assert_eq!(registry.number_of_operators(), 13);
+        assert!(!registry
+            .enabled_operators()
+            .contains(&String::from("binop_or_to_xor")));
+        assert!(registry
+            .disabled_operators()
+            .contains(&String::from("binop_or_to_xor")));
+
+        assert!(OperatorRegistry::with_disabled(&["binop_"], &["binop_(or"]).is_err());
+    }
+
+    #[test]
+    fn registry_presets() {
+        let registry = |preset: OperatorPreset| {
+            OperatorRegistry::new(preset.enabled_operators())
+                .unwrap()
+                .enabled_operators()
+                .to_vec()
+        };
+
+        let light = registry(OperatorPreset::Light);
+        assert_eq!(light.len(), 14);
+        assert!(light
+            .iter()
+            .all(|name| name.starts_with("relop_") || name.starts_with("const_")));
+
+        let standard = registry(OperatorPreset::Standard);
+        assert!(light.iter().all(|name| standard.contains(name)));
+        assert!(standard.contains(&String::from("binop_add_to_sub")));
+        assert!(standard.contains(&String::from("call_remove_void_call")));
+        assert!(!standard.contains(&String::from("call_remove_scalar_call")));
+        assert!(!standard.contains(&String::from("memory_grow_force_result")));
+
+        let aggressive = registry(OperatorPreset::Aggressive);
+        assert_eq!(aggressive.len(), 42);
+    }
+
+    #[test]
+    fn registry_invalid_pattern() {
+        let error = OperatorRegistry::new(&["binop_(add"]).err().unwrap();
+        assert!(format!("{error:?}").contains("binop_(add"));
+        assert_eq!(crate::error::exit_code(&error), 2);
+    }
+
+    #[test]
+    fn registry_unmatched_pattern_suggestions() {
+        let registry = OperatorRegistry::new(&["binop_add_to_sbu"]).unwrap();
+        assert_eq!(registry.number_of_operators(), 0);
+
+        let message = registry
+            .unmatched_pattern_message("binop_add_to_sbu")
+            .unwrap();
+        assert!(message.contains("did you mean \"binop_add_to_sub\""));
+
+        let message = registry.unmatched_pattern_message("foobar").unwrap();
+        assert!(!message.contains("did you mean"));
+
+        assert!(registry.unmatched_pattern_message("binop_").is_none());
+    }
 }
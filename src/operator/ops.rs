@@ -1,6 +1,6 @@
-use crate::wasmmodule::CallRemovalCandidate;
+use crate::wasmmodule::{CallRemovalCandidate, Signedness};
 use wasmut_wasm::elements::Instruction::{self, *};
-use wasmut_wasm::elements::{BlockType, ValueType};
+use wasmut_wasm::elements::{BlockType, BrTableData, ValueType};
 
 use super::{InstructionContext, InstructionReplacement};
 
@@ -329,6 +329,378 @@ implement_replacement_op! {
     F64Gt  => F64Le > [F64, F64] => Value(I32),
 }
 
+/// Inverse of a float comparison, which is its negation unless an operand is NaN
+fn inverse_float_comparison(instr: &Instruction) -> Option<(Instruction, ValueType)> {
+    Some(match instr {
+        F32Lt => (F32Ge, F32),
+        F32Le => (F32Gt, F32),
+        F32Gt => (F32Le, F32),
+        F32Ge => (F32Lt, F32),
+        F64Lt => (F64Ge, F64),
+        F64Le => (F64Gt, F64),
+        F64Gt => (F64Le, F64),
+        F64Ge => (F64Lt, F64),
+        _ => return None,
+    })
+}
+
+/// Replace a float comparison by the negation of its inverse (`cmp` by `inverse; i32.eqz`),
+/// which only differs from the original comparison if an operand is NaN.
+/// `$negated_result` selects whether the operator applies to comparisons whose
+/// result is negated by a following `i32.eqz`, see `applies_to_negated_result`.
+macro_rules! implement_float_negation_op {
+    ($(#[$meta:meta])* $op_name:ident, $name:expr, $negated_result:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $op_name {
+            pub old: Instruction,
+            pub new: Instruction,
+            pub result_type: BlockType,
+            pub parameters: Vec<ValueType>,
+        }
+
+        impl InstructionReplacement for $op_name {
+            common_functions!();
+
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn replacement(&self) -> Vec<Instruction> {
+                vec![self.new_instruction().clone(), I32Eqz]
+            }
+
+            fn applies_to_negated_result(&self) -> Option<bool> {
+                Some($negated_result)
+            }
+
+            fn factory(
+            ) -> fn(&Instruction, &InstructionContext) -> Option<Box<dyn InstructionReplacement>>
+            where
+                Self: Sized + Send + Sync + 'static,
+            {
+                fn make(
+                    instr: &Instruction,
+                    _: &InstructionContext,
+                ) -> Option<Box<dyn InstructionReplacement>> {
+                    $op_name::new(instr).map(|f| Box::new(f) as Box<dyn InstructionReplacement>)
+                }
+                make
+            }
+        }
+
+        impl $op_name {
+            pub fn new(instr: &Instruction) -> Option<Self> {
+                let (new, operand) = inverse_float_comparison(instr)?;
+                Some(Self {
+                    old: instr.clone(),
+                    new,
+                    result_type: Value(I32),
+                    parameters: vec![operand, operand],
+                })
+            }
+        }
+    };
+}
+
+implement_float_negation_op! {
+    /// Replace an ordered float comparison by its unordered counterpart, which
+    /// additionally returns true if one of the operands is NaN. The unordered comparison
+    /// is the negation of the inverse ordered comparison:
+    ///
+    /// | Original | Replacement         | Source-level equivalent |
+    /// | -------- | ------------------- | ----------------------- |
+    /// | `lt`     | `ge`, `i32.eqz`     | `!(a >= b)`             |
+    /// | `le`     | `gt`, `i32.eqz`     | `!(a > b)`              |
+    /// | `gt`     | `le`, `i32.eqz`     | `!(a <= b)`             |
+    /// | `ge`     | `lt`, `i32.eqz`     | `!(a < b)`              |
+    ///
+    /// The mutant only behaves differently if an operand is NaN, so it survives
+    /// unless the tests check how NaN inputs are handled. `eq` and `ne` are not mutated,
+    /// since their counterparts cannot be expressed without duplicating the operands.
+    /// Comparisons followed by `i32.eqz` are unordered comparisons themselves and
+    /// are mutated by `RelationalOperatorUnorderedToOrdered` instead.
+    RelationalOperatorOrderedToUnordered,
+    "relop_ordered_to_unordered",
+    false
+}
+
+implement_float_negation_op! {
+    /// Replace an unordered float comparison by its ordered counterpart, which
+    /// returns false if one of the operands is NaN. WebAssembly has no unordered
+    /// comparisons, compilers emit the negation of the inverse ordered comparison
+    /// instead. The negated comparison is replaced by the negation of its own inverse,
+    /// so the following `i32.eqz` cancels out:
+    ///
+    /// | Original                  | Replacement of the comparison | Source-level equivalent |
+    /// | ------------------------- | ----------------------------- | ----------------------- |
+    /// | `ge`, `i32.eqz` (`!(a >= b)`) | `lt`, `i32.eqz`           | `a < b`                 |
+    /// | `gt`, `i32.eqz` (`!(a > b)`)  | `le`, `i32.eqz`           | `a <= b`                |
+    /// | `le`, `i32.eqz` (`!(a <= b)`) | `gt`, `i32.eqz`           | `a > b`                 |
+    /// | `lt`, `i32.eqz` (`!(a < b)`)  | `ge`, `i32.eqz`           | `a >= b`                |
+    ///
+    /// Like `RelationalOperatorOrderedToUnordered`, the mutant only behaves
+    /// differently if an operand is NaN.
+    RelationalOperatorUnorderedToOrdered,
+    "relop_unordered_to_ordered",
+    true
+}
+
+implement_replacement_op! {
+    ConversionExtendSToU,
+    "conv_extend_s_to_u",
+    I64ExtendSI32 => I64ExtendUI32 > [I32] => Value(I64),
+}
+
+implement_replacement_op! {
+    ConversionExtendUToS,
+    "conv_extend_u_to_s",
+    I64ExtendUI32 => I64ExtendSI32 > [I32] => Value(I64),
+}
+
+/// Replace `i32.wrap_i64` by a checked conversion, which traps instead of
+/// discarding the upper 32 bits. The conversion takes a detour via `f64`,
+/// which represents all values within the range of `i32`/`u32` exactly.
+///
+/// The signed conversion traps for values outside of the range of `i32`, including
+/// valid `u32` values of at least 2^31, and the unsigned conversion traps for all
+/// negative values. Each variant is therefore only applied where the surrounding
+/// instructions treat the value with the same `$signedness`, so that the mutant
+/// only behaves differently for values that are actually truncated.
+macro_rules! implement_checked_wrap_op {
+    ($op_name:ident, $convert:expr, $truncate:expr, $signedness:expr) => {
+        #[derive(Debug, Clone)]
+        pub struct $op_name {
+            pub old: Instruction,
+            pub new: Instruction,
+            pub result_type: BlockType,
+            pub parameters: Vec<ValueType>,
+        }
+
+        impl InstructionReplacement for $op_name {
+            common_functions!();
+
+            fn name() -> &'static str
+            where
+                Self: Sized + 'static,
+            {
+                "conv_wrap_to_checked"
+            }
+
+            fn replacement(&self) -> Vec<Instruction> {
+                vec![$convert, self.new_instruction().clone()]
+            }
+
+            fn applies_to_signedness(&self) -> Option<Signedness> {
+                Some($signedness)
+            }
+
+            fn factory(
+            ) -> fn(&Instruction, &InstructionContext) -> Option<Box<dyn InstructionReplacement>>
+            where
+                Self: Sized + Send + Sync + 'static,
+            {
+                fn make(
+                    instr: &Instruction,
+                    _: &InstructionContext,
+                ) -> Option<Box<dyn InstructionReplacement>> {
+                    $op_name::new(instr).map(|f| Box::new(f) as Box<dyn InstructionReplacement>)
+                }
+
+                make
+            }
+        }
+
+        impl $op_name {
+            pub fn new(instr: &Instruction) -> Option<Self> {
+                match instr {
+                    I32WrapI64 => Some(Self {
+                        old: I32WrapI64,
+                        new: $truncate,
+                        result_type: Value(I32),
+                        parameters: [I64].into(),
+                    }),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+implement_checked_wrap_op!(
+    ConversionWrapToCheckedS,
+    F64ConvertSI64,
+    I32TruncSF64,
+    Signedness::Signed
+);
+implement_checked_wrap_op!(
+    ConversionWrapToCheckedU,
+    F64ConvertUI64,
+    I32TruncUF64,
+    Signedness::Unsigned
+);
+
+/// Replace the result of `memory.grow`, dropping the number of pages to grow by.
+/// The memory is never grown by the mutant.
+macro_rules! implement_memory_grow_op {
+    ($op_name:ident, $new:expr) => {
+        #[derive(Debug, Clone)]
+        pub struct $op_name {
+            pub old: Instruction,
+            pub new: Instruction,
+            pub result_type: BlockType,
+            pub parameters: Vec<ValueType>,
+        }
+
+        impl InstructionReplacement for $op_name {
+            common_functions!();
+
+            fn name() -> &'static str
+            where
+                Self: Sized + 'static,
+            {
+                "memory_grow_force_result"
+            }
+
+            fn replacement(&self) -> Vec<Instruction> {
+                vec![Drop, self.new_instruction().clone()]
+            }
+
+            fn factory(
+            ) -> fn(&Instruction, &InstructionContext) -> Option<Box<dyn InstructionReplacement>>
+            where
+                Self: Sized + Send + Sync + 'static,
+            {
+                fn make(
+                    instr: &Instruction,
+                    _: &InstructionContext,
+                ) -> Option<Box<dyn InstructionReplacement>> {
+                    $op_name::new(instr).map(|f| Box::new(f) as Box<dyn InstructionReplacement>)
+                }
+
+                make
+            }
+        }
+
+        impl $op_name {
+            pub fn new(instr: &Instruction) -> Option<Self> {
+                match *instr {
+                    GrowMemory(memory) => Some(Self {
+                        old: GrowMemory(memory),
+                        new: $new(memory),
+                        result_type: Value(I32),
+                        parameters: [I32].into(),
+                    }),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+// `memory.grow` fails and returns -1
+implement_memory_grow_op!(MemoryGrowForceFailure, |_| I32Const(-1));
+
+// `memory.grow` pretends to succeed, by returning the current size
+// of the memory (the result of a successful call), without growing it
+implement_memory_grow_op!(MemoryGrowForceSuccess, CurrentMemory);
+
+/// Change the targets of a `br_table` instruction. Since all targets of
+/// a table carry the same values, the arity of the branch is preserved.
+/// `$transform` returns `None` if the mutant would be equivalent.
+macro_rules! implement_br_table_op {
+    ($op_name:ident, $name:expr, $transform:expr) => {
+        #[derive(Debug, Clone)]
+        pub struct $op_name {
+            pub old: Instruction,
+            pub new: Instruction,
+            pub result_type: BlockType,
+            pub parameters: Vec<ValueType>,
+        }
+
+        impl InstructionReplacement for $op_name {
+            common_functions!();
+
+            fn name() -> &'static str
+            where
+                Self: Sized + 'static,
+            {
+                $name
+            }
+
+            fn replacement(&self) -> Vec<Instruction> {
+                vec![self.new_instruction().clone()]
+            }
+
+            fn contains_branch(&self) -> bool {
+                true
+            }
+
+            fn factory(
+            ) -> fn(&Instruction, &InstructionContext) -> Option<Box<dyn InstructionReplacement>>
+            where
+                Self: Sized + Send + Sync + 'static,
+            {
+                fn make(
+                    instr: &Instruction,
+                    _: &InstructionContext,
+                ) -> Option<Box<dyn InstructionReplacement>> {
+                    $op_name::new(instr).map(|f| Box::new(f) as Box<dyn InstructionReplacement>)
+                }
+
+                make
+            }
+        }
+
+        impl $op_name {
+            pub fn new(instr: &Instruction) -> Option<Self> {
+                match instr {
+                    BrTable(data) => {
+                        let transform: fn(&BrTableData) -> Option<BrTableData> = $transform;
+                        Some(Self {
+                            old: instr.clone(),
+                            new: BrTable(Box::new(transform(data)?)),
+                            result_type: BlockType::NoResult,
+                            parameters: [I32].into(),
+                        })
+                    }
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+// Every case jumps to the target of the next case, the last case
+// to the target of the first one. The default target is unchanged.
+implement_br_table_op!(BrTableRotateTargets, "br_table_rotate_targets", |data| {
+    if data.table.iter().all(|&target| target == data.table[0]) {
+        return None;
+    }
+
+    let mut table = data.table.clone();
+    table.rotate_left(1);
+    Some(BrTableData {
+        table,
+        default: data.default,
+    })
+});
+
+// The first case jumps to the default target and vice versa
+implement_br_table_op!(BrTableSwapDefault, "br_table_swap_default", |data| {
+    let first = *data.table.first()?;
+    if first == data.default {
+        return None;
+    }
+
+    let mut table = data.table.clone();
+    table[0] = data.default;
+    Some(BrTableData {
+        table,
+        default: first,
+    })
+});
+
 #[derive(Debug, Clone)]
 pub struct ConstReplaceZero {
     pub old: Instruction,
@@ -607,3 +979,107 @@ impl CallRemoveScalarCall {
         }
     }
 }
+
+/// Redirect a `call_indirect` to another function of an identical type,
+/// simulating a wrong function pointer or callback. The call is dispatched to
+/// the first table slot of `slots`, or to the second one if the original
+/// index already referred to the first slot.
+#[derive(Debug, Clone)]
+pub struct TableEntryReplace {
+    pub old: Instruction,
+    pub new: Instruction,
+    pub result_type: BlockType,
+    pub parameters: Vec<ValueType>,
+    pub slots: (u32, u32),
+}
+
+impl InstructionReplacement for TableEntryReplace {
+    fn old_instruction(&self) -> &Instruction {
+        &self.old
+    }
+
+    fn new_instruction(&self) -> &Instruction {
+        &self.new
+    }
+
+    fn replacement(&self) -> Vec<Instruction> {
+        let (first, second) = self.slots;
+        vec![
+            I32Const(first as i32),
+            I32Eq,
+            If(Value(I32)),
+            I32Const(second as i32),
+            Else,
+            I32Const(first as i32),
+            End,
+            self.new.clone(),
+        ]
+    }
+
+    fn result(&self) -> BlockType {
+        self.result_type
+    }
+
+    fn parameters(&self) -> &[ValueType] {
+        &self.parameters
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "{}: Redirected {:?} to table slot {} (slot {} if it called slot {})",
+            Self::name(),
+            self.old,
+            self.slots.0,
+            self.slots.1,
+            self.slots.0
+        )
+    }
+
+    fn name() -> &'static str {
+        "table_entry_replace"
+    }
+
+    fn dyn_name(&self) -> &'static str {
+        Self::name()
+    }
+
+    fn factory() -> fn(&Instruction, &InstructionContext) -> Option<Box<dyn InstructionReplacement>>
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        fn make(
+            instr: &Instruction,
+            ctx: &InstructionContext,
+        ) -> Option<Box<dyn InstructionReplacement>> {
+            TableEntryReplace::new(instr, ctx)
+                .map(|f| Box::new(f) as Box<dyn InstructionReplacement>)
+        }
+
+        make
+    }
+}
+
+impl TableEntryReplace {
+    pub fn new(instr: &Instruction, ctx: &InstructionContext) -> Option<Self> {
+        match *instr {
+            CallIndirect(type_ref, 0) => {
+                let candidate = ctx
+                    .table_entry_candidates()
+                    .iter()
+                    .find(|candidate| candidate.type_index == type_ref)?;
+
+                let mut parameters = candidate.params.clone();
+                parameters.push(I32);
+
+                Some(Self {
+                    old: instr.clone(),
+                    new: instr.clone(),
+                    result_type: candidate.result,
+                    parameters,
+                    slots: candidate.slots,
+                })
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,75 +1,245 @@
-use std::thread_local;
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+};
 
-use std::fmt::Write;
+use anyhow::{Context, Result};
 
-#[derive(Default)]
-pub struct OutputCapture {
-    #[cfg(test)]
-    output: String,
+/// Destination for the output of wasmut
+pub trait OutputWriter: Send {
+    fn write_str(&mut self, s: &str) -> std::io::Result<()>;
 }
 
-impl OutputCapture {
-    // pub fn new() -> Self {
-    //     Self {
-    //         #[cfg(test)]
-    //         output: String::new()
-    //     }
-    // }
+/// Writes output to stdout
+pub struct StdoutWriter;
 
-    #[cfg(test)]
-    fn get_output(&self) -> String {
-        self.output.clone()
+impl OutputWriter for StdoutWriter {
+    fn write_str(&mut self, s: &str) -> std::io::Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(s.as_bytes())?;
+        stdout.flush()
     }
+}
+
+/// Writes output to stderr
+pub struct StderrWriter;
 
-    #[cfg(test)]
-    fn clear_output(&mut self) {
-        self.output = String::new();
+impl OutputWriter for StderrWriter {
+    fn write_str(&mut self, s: &str) -> std::io::Result<()> {
+        let mut stderr = std::io::stderr().lock();
+        stderr.write_all(s.as_bytes())?;
+        stderr.flush()
     }
 }
 
-impl std::fmt::Write for OutputCapture {
-    fn write_str(&mut self, s: &str) -> std::fmt::Result {
-        #[cfg(test)]
-        {
-            self.output += s;
-        }
+/// Collects output in a shared in-memory buffer
+#[derive(Clone, Default)]
+pub struct BufferWriter {
+    buffer: Arc<Mutex<String>>,
+}
+
+impl BufferWriter {
+    /// Return everything that was written so far
+    pub fn contents(&self) -> String {
+        lock(&self.buffer).clone()
+    }
+}
 
-        print!("{s}");
+impl OutputWriter for BufferWriter {
+    fn write_str(&mut self, s: &str) -> std::io::Result<()> {
+        lock(&self.buffer).push_str(s);
         Ok(())
     }
 }
 
+/// Writes output to a file
+pub struct FileWriter {
+    writer: BufWriter<File>,
+}
+
+impl FileWriter {
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::create(path).with_context(|| format!("Could not create output file {path:?}"))?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+}
+
+impl OutputWriter for FileWriter {
+    fn write_str(&mut self, s: &str) -> std::io::Result<()> {
+        self.writer.write_all(s.as_bytes())?;
+        self.writer.flush()
+    }
+}
+
+/// Global output sink, shared by all threads
+struct Sink {
+    /// Writers for the output, `None` means that output is written to stdout
+    output: Option<Vec<Box<dyn OutputWriter>>>,
+
+    /// Writer for progress messages, `None` means that they are written to stderr
+    progress: Option<Box<dyn OutputWriter>>,
+}
+
+static SINK: Mutex<Sink> = Mutex::new(Sink {
+    output: None,
+    progress: None,
+});
+
 thread_local! {
-    static STDOUT: std::cell::RefCell<OutputCapture> = Default::default();
+    /// Buffer capturing the output of the current thread, see `capture`
+    static CAPTURE: RefCell<Option<BufferWriter>> = const { RefCell::new(None) };
+}
+
+/// Lock a mutex, ignoring poisoning. A panic while writing output
+/// must not prevent other threads from writing output.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Replace all output writers
+pub fn set_writers(writers: Vec<Box<dyn OutputWriter>>) {
+    lock(&SINK).output = Some(writers);
+}
+
+/// Write output to stdout again
+pub fn reset_writers() {
+    lock(&SINK).output = None;
+}
+
+/// Replace the writer for progress messages
+pub fn set_progress_writer(writer: Box<dyn OutputWriter>) {
+    lock(&SINK).progress = Some(writer);
 }
 
+/// Write a string to all output writers, or to the capture
+/// buffer of the current thread, if there is one.
+///
+/// This function may be called from any thread, output
+/// of concurrent calls is never interleaved.
 pub fn output_string<S: AsRef<str>>(s: S) {
-    STDOUT.with(|writer| {
-        write!(writer.borrow_mut(), "{}", s.as_ref()).unwrap();
+    let s = s.as_ref();
+    let captured = CAPTURE.with(|capture| match capture.borrow_mut().as_mut() {
+        Some(buffer) => {
+            let _ = buffer.write_str(s);
+            true
+        }
+        None => false,
     });
+    if captured {
+        return;
+    }
+
+    let mut sink = lock(&SINK);
+    let result = match sink.output.as_mut() {
+        Some(writers) => writers
+            .iter_mut()
+            .try_for_each(|writer| writer.write_str(s)),
+        None => StdoutWriter.write_str(s),
+    };
+
+    if let Err(e) = result {
+        log::error!("Failed to write output: {e}");
+    }
 }
 
-#[cfg(test)]
-pub fn get_output() -> String {
-    STDOUT.with(|output_capture| output_capture.borrow().get_output())
+/// Write a progress message, e.g. a summary of the run so far.
+///
+/// Progress messages are not part of the output, so that
+/// reports written to stdout are not interrupted by them.
+pub fn output_progress<S: AsRef<str>>(s: S) {
+    let s = format!("{}\n", s.as_ref());
+
+    let mut sink = lock(&SINK);
+    let result = match sink.progress.as_mut() {
+        Some(writer) => writer.write_str(&s),
+        None => StderrWriter.write_str(&s),
+    };
+
+    if let Err(e) = result {
+        log::error!("Failed to write progress: {e}");
+    }
 }
 
-#[cfg(test)]
-pub fn clear_output() {
-    STDOUT.with(|output_capture| output_capture.borrow_mut().clear_output())
+/// Run `f` and capture all output it produces, including output of
+/// rayon workers it uses. Output of other threads is not captured,
+/// so that concurrent captures (e.g. by tests) do not interfere.
+///
+/// `f` runs in a dedicated rayon thread pool, whose threads
+/// write their output to the capture buffer.
+pub fn capture<R: Send, F: FnOnce() -> R + Send>(f: F) -> (R, String) {
+    let buffer = BufferWriter::default();
+    let workers = buffer.clone();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .start_handler(move |_| {
+            CAPTURE.with(|capture| *capture.borrow_mut() = Some(workers.clone()));
+        })
+        .build()
+        .expect("Failed to create thread pool for output capture");
+
+    let result = pool.install(f);
+    (result, buffer.contents())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rayon::prelude::*;
 
     #[test]
     fn output_capture_works() {
-        clear_output();
-        output_string("foobar");
-        assert_eq!(get_output(), String::from("foobar"));
+        let (_, output) = capture(|| output_string("foobar"));
+        assert_eq!(output, String::from("foobar"));
 
-        clear_output();
-        assert_eq!(get_output(), String::from(""));
+        let (_, output) = capture(|| {});
+        assert_eq!(output, String::from(""));
+    }
+
+    #[test]
+    fn output_capture_from_worker_threads() {
+        let (_, output) = capture(|| {
+            (0..100)
+                .into_par_iter()
+                .for_each(|i| output_string(format!("{i}\n")));
+        });
+
+        let mut lines: Vec<u32> = output.lines().map(|l| l.parse().unwrap()).collect();
+        lines.sort_unstable();
+        assert_eq!(lines, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn concurrent_captures_are_separate() {
+        let outputs: Vec<String> = (0..4)
+            .into_par_iter()
+            .map(|i| capture(|| output_string(format!("{i}"))).1)
+            .collect();
+        assert_eq!(outputs, ["0", "1", "2", "3"]);
+    }
+
+    #[test]
+    fn progress_is_not_captured() {
+        let (_, output) = capture(|| output_progress("Progress: 1/2 mutants"));
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn file_writer() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("output.txt");
+
+        let mut writer = FileWriter::create(&path)?;
+        writer.write_str("foo")?;
+        writer.write_str("bar")?;
+
+        assert_eq!(std::fs::read_to_string(&path)?, "foobar");
+        Ok(())
     }
 }
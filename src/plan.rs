@@ -0,0 +1,193 @@
+//! Explanation of what a mutation run would do (`wasmut plan`),
+//! without executing any mutants.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    config::Config,
+    executor::{engine_mode, Executor},
+    mutation::MutationEngine,
+    wasmmodule::WasmModule,
+};
+
+/// Name used for mutants at locations without source file information
+const UNKNOWN_FILE: &str = "<unknown>";
+
+/// Number of mutants in a single source file
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FilePlan {
+    pub file: String,
+    pub mutants: usize,
+}
+
+/// An enabled mutation operator and the number of mutants it produces
+#[derive(Serialize, Debug, PartialEq)]
+pub struct OperatorPlan {
+    pub name: String,
+    pub mutants: usize,
+}
+
+/// What a mutation run with the given configuration would do
+#[derive(Serialize, Debug)]
+pub struct Plan {
+    /// Enabled operators
+    pub operators: Vec<OperatorPlan>,
+
+    /// Mutants per source file, sorted by file name
+    pub files: Vec<FilePlan>,
+
+    /// Total number of mutants
+    pub mutants: usize,
+
+    /// Whether mutants that are not covered by the baseline run are skipped
+    pub coverage_based_execution: bool,
+
+    /// Execution mode, either "meta-mutant" or "one-by-one"
+    pub engine_mode: String,
+
+    /// Number of module compilations needed to execute all mutants,
+    /// not including the baseline runs
+    pub compilations: usize,
+
+    /// Mutants are stopped after executing this many times
+    /// the instructions of the baseline run
+    pub timeout_multiplier: f64,
+}
+
+impl Plan {
+    /// Discover the mutants of `module` and determine how they would be executed
+    pub fn new(module: &WasmModule, config: &Config) -> Result<Self> {
+        let engine = MutationEngine::new(config, 100)?;
+        let discovery = engine.discover_mutation_positions(module)?;
+        let executor = Executor::new(config);
+
+        let operators = discovery
+            .operator_usage
+            .counts()
+            .map(|(name, mutants)| OperatorPlan {
+                name: name.into(),
+                mutants,
+            })
+            .collect();
+
+        Ok(Self {
+            operators,
+            files: files(discovery.files.clone()),
+            mutants: discovery.locations.iter().map(|l| l.mutations.len()).sum(),
+            coverage_based_execution: config.engine().coverage_based_execution(),
+            engine_mode: engine_mode(config.engine().meta_mutant()).into(),
+            compilations: executor.compilations(&discovery.locations),
+            timeout_multiplier: config.engine().timeout_multiplier(),
+        })
+    }
+
+    /// Human readable explanation of the plan
+    pub fn explain(&self) -> String {
+        let mut s = String::new();
+
+        s.push_str(&format!("Mutants: {}\n", self.mutants));
+        for file in &self.files {
+            s.push_str(&format!("{0:>8}  {1}\n", file.mutants, file.file));
+        }
+
+        s.push_str(&format!("\nEnabled operators: {}\n", self.operators.len()));
+        for operator in &self.operators {
+            s.push_str(&format!("{0:>8}  {1}\n", operator.mutants, operator.name));
+        }
+
+        s.push_str(&format!("\nEngine mode: {}\n", self.engine_mode));
+        s.push_str(&format!("Compilations: {}\n", self.compilations));
+
+        if self.coverage_based_execution {
+            s.push_str(
+                "Coverage: the module is executed once with tracing enabled, \
+                mutants that are never reached are skipped\n",
+            );
+        } else {
+            s.push_str("Coverage: disabled, all mutants are executed\n");
+        }
+
+        s.push_str(&format!(
+            "Timeout: {} times the instructions executed by the baseline run\n",
+            self.timeout_multiplier
+        ));
+
+        s
+    }
+}
+
+fn files(counts: BTreeMap<Option<String>, usize>) -> Vec<FilePlan> {
+    // `None` is sorted first, list mutants without source information last
+    let mut files: Vec<FilePlan> = counts
+        .into_iter()
+        .map(|(file, mutants)| FilePlan {
+            file: file.unwrap_or_else(|| UNKNOWN_FILE.into()),
+            mutants,
+        })
+        .collect();
+    if files.first().map(|f| f.file.as_str()) == Some(UNKNOWN_FILE) {
+        files.rotate_left(1);
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_without_source_info_last() {
+        let files = files(BTreeMap::from([
+            (None, 3),
+            (Some("b.c".into()), 2),
+            (Some("a.c".into()), 1),
+        ]));
+
+        assert_eq!(
+            files,
+            vec![
+                FilePlan {
+                    file: "a.c".into(),
+                    mutants: 1
+                },
+                FilePlan {
+                    file: "b.c".into(),
+                    mutants: 2
+                },
+                FilePlan {
+                    file: UNKNOWN_FILE.into(),
+                    mutants: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn explain_plan() {
+        let plan = Plan {
+            operators: vec![OperatorPlan {
+                name: "binop_add_to_sub".into(),
+                mutants: 4,
+            }],
+            files: vec![FilePlan {
+                file: "a.c".into(),
+                mutants: 4,
+            }],
+            mutants: 4,
+            coverage_based_execution: true,
+            engine_mode: "meta-mutant".into(),
+            compilations: 1,
+            timeout_multiplier: 2.0,
+        };
+
+        let explanation = plan.explain();
+        assert!(explanation.contains("Mutants: 4\n       4  a.c\n"));
+        assert!(explanation.contains("       4  binop_add_to_sub\n"));
+        assert!(explanation.contains("Engine mode: meta-mutant\n"));
+        assert!(explanation.contains("Compilations: 1\n"));
+        assert!(explanation.contains("Timeout: 2 times"));
+    }
+}
@@ -1,8 +1,9 @@
-use crate::config::Config;
+use crate::{config::Config, error::ErrorKind, git};
 
 use anyhow::{Context, Result};
 
 use regex::RegexSet;
+use serde::{Deserialize, Serialize};
 
 /// Policy used when executing a WebAssembly module
 pub enum ExecutionPolicy {
@@ -15,6 +16,59 @@ pub enum ExecutionPolicy {
     RunUntilReturn,
 }
 
+/// Built-in sets of runtime support functions that are never mutated,
+/// selected by `[filter] preset`. Mutating allocators and panic machinery
+/// mostly produces noise, since tests rarely exercise these paths.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterPreset {
+    /// Panic, unwinding and allocator support of Rust programs
+    Rust,
+
+    /// Allocator, abort and startup support of C/C++ programs (wasi-libc, emscripten)
+    C,
+}
+
+/// Functions excluded by `FilterPreset::Rust`
+const RUST_PRESET: &[&str] = &[
+    "^rust_begin_unwind$",
+    "^rust_panic",
+    "^__rust_",
+    "^__rdl_",
+    "^__rg_",
+    "^(core|std)::panicking::",
+    "^std::panic::",
+    "^core::(option|result)::(expect_failed|unwrap_failed)",
+    "^alloc::alloc::handle_alloc_error",
+    "^alloc::raw_vec::(capacity_overflow|handle_error|finish_grow)",
+    "^std::alloc::",
+    "^std::process::abort",
+    "^<?dlmalloc::",
+    "^<?wee_alloc::",
+];
+
+/// Functions excluded by `FilterPreset::C`
+const C_PRESET: &[&str] = &[
+    "^(malloc|calloc|realloc|free|aligned_alloc|posix_memalign|memalign)$",
+    "^dl(malloc|calloc|realloc|free|memalign|posix_memalign)$",
+    "^(internal_memalign|prepend_alloc|tmalloc_large|tmalloc_small|dispose_chunk|sys_alloc|sbrk)$",
+    "^(abort|exit|_exit|_Exit)$",
+    "^__assert_fail$",
+    "^__stack_chk_fail$",
+    "^__cxa_",
+    "^__wasm_call_(ctors|dtors)$",
+];
+
+impl FilterPreset {
+    /// Regexes of all functions excluded by the preset
+    pub fn denied_functions(self) -> &'static [&'static str] {
+        match self {
+            FilterPreset::Rust => RUST_PRESET,
+            FilterPreset::C => C_PRESET,
+        }
+    }
+}
+
 /// Builder used to construct a `MutationPolicy`
 pub struct MutationPolicyBuilder {
     /// List of regular expressions used to determine which functions are allowed
@@ -25,8 +79,19 @@ pub struct MutationPolicyBuilder {
     /// to be mutated
     allowed_files: Vec<String>,
 
+    /// List of regular expressions of functions that are never mutated,
+    /// regardless of any other rule
+    denied_functions: Vec<String>,
+
     /// If set, there are no restrictions
     anything_allowed: bool,
+
+    /// If set, locations without source file information are never allowed
+    require_source_info: bool,
+
+    /// If set, only files matching one of these regular expressions
+    /// are allowed, regardless of any other rule
+    restricted_files: Option<Vec<String>>,
 }
 
 /// Policy used when discovering mutant candidates
@@ -39,8 +104,19 @@ pub struct MutationPolicy {
     /// to be mutated
     allowed_files: RegexSet,
 
+    /// List of regular expressions of functions that are never mutated,
+    /// regardless of any other rule
+    denied_functions: RegexSet,
+
     /// If set, there are no restrictions
     anything_allowed: bool,
+
+    /// If set, locations without source file information are never allowed
+    require_source_info: bool,
+
+    /// If set, only files matching one of these regular expressions
+    /// are allowed, regardless of any other rule
+    restricted_files: Option<RegexSet>,
 }
 
 impl MutationPolicyBuilder {
@@ -62,17 +138,56 @@ impl MutationPolicyBuilder {
         }
     }
 
+    /// Add a regex of functions that are never mutated
+    pub fn deny_function<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.denied_functions.push(String::from(name.as_ref()));
+        self
+    }
+
+    /// Only allow locations with source file information
+    pub fn require_source_info(self, require_source_info: bool) -> Self {
+        Self {
+            require_source_info,
+            ..self
+        }
+    }
+
+    /// Only allow files matching one of the given regexes, e.g. files changed
+    /// in version control. Locations without source file information are never allowed.
+    pub fn restrict_files<T: AsRef<str>>(self, files: &[T]) -> Self {
+        Self {
+            restricted_files: Some(files.iter().map(|f| String::from(f.as_ref())).collect()),
+            ..self
+        }
+    }
+
     /// Build the final `MutationPolicy`
     pub fn build(self) -> Result<MutationPolicy> {
         let allowed_functions = RegexSet::new(&self.allowed_functions)
-            .context("Could not build allowed_functions regex set")?;
+            .context("Could not build allowed_functions regex set")
+            .context(ErrorKind::Config)?;
         let allowed_files = RegexSet::new(&self.allowed_files)
-            .context("Could not build allowed_files regex set")?;
+            .context("Could not build allowed_files regex set")
+            .context(ErrorKind::Config)?;
+        let denied_functions = RegexSet::new(&self.denied_functions)
+            .context("Could not build denied functions regex set")
+            .context(ErrorKind::Config)?;
+        let restricted_files = self
+            .restricted_files
+            .map(|files| {
+                RegexSet::new(files)
+                    .context("Could not build restricted files regex set")
+                    .context(ErrorKind::Config)
+            })
+            .transpose()?;
 
         Ok(MutationPolicy {
             allowed_functions,
             allowed_files,
+            denied_functions,
             anything_allowed: self.anything_allowed,
+            require_source_info: self.require_source_info,
+            restricted_files,
         })
     }
 }
@@ -83,7 +198,10 @@ impl Default for MutationPolicyBuilder {
         Self {
             allowed_functions: Default::default(),
             allowed_files: Default::default(),
+            denied_functions: Default::default(),
             anything_allowed: true,
+            require_source_info: false,
+            restricted_files: None,
         }
     }
 }
@@ -91,7 +209,8 @@ impl Default for MutationPolicyBuilder {
 impl MutationPolicy {
     /// Construct a mutation policy from `Config`
     pub fn from_config(config: &Config) -> Result<Self> {
-        let mut builder = MutationPolicyBuilder::default();
+        let mut builder = MutationPolicyBuilder::default()
+            .require_source_info(config.filter().require_source_info());
 
         if let Some(files) = config.filter().allowed_files() {
             for file in files {
@@ -105,21 +224,85 @@ impl MutationPolicy {
             }
         }
 
+        if let Some(preset) = config.filter().preset() {
+            for function in preset.denied_functions() {
+                builder = builder.deny_function(function);
+            }
+        }
+
+        if let Some(revision) = config.filter().only_changed_files() {
+            let changed_files = git::changed_files(config.directory(), revision)?;
+            log::info!(
+                "Restricting mutations to {} files changed since {revision}",
+                changed_files.len()
+            );
+
+            let patterns: Vec<String> = changed_files
+                .iter()
+                .map(|file| git::file_pattern(file))
+                .collect();
+            builder = builder.restrict_files(&patterns);
+        }
+
         builder.build()
     }
 
     /// Check if a function is allowed to be mutated
     pub fn check_function<T: AsRef<str>>(&self, name: T) -> bool {
-        self.anything_allowed || self.allowed_functions.is_match(name.as_ref())
+        !self.denied_functions.is_match(name.as_ref())
+            && (self.anything_allowed || self.allowed_functions.is_match(name.as_ref()))
     }
 
     /// Check if a file is allowed to be mutated
     pub fn check_file<T: AsRef<str>>(&self, name: T) -> bool {
-        self.anything_allowed || self.allowed_files.is_match(name.as_ref())
+        self.file_in_scope(&name)
+            && (self.anything_allowed || self.allowed_files.is_match(name.as_ref()))
+    }
+
+    /// Return the first `allowed_functions` regex matching a function name
+    pub fn matching_function_pattern<T: AsRef<str>>(&self, name: T) -> Option<&str> {
+        first_match(&self.allowed_functions, name.as_ref())
+    }
+
+    /// Return the first `allowed_files` regex matching a file name
+    pub fn matching_file_pattern<T: AsRef<str>>(&self, name: T) -> Option<&str> {
+        first_match(&self.allowed_files, name.as_ref())
+    }
+
+    /// Check if a file is not excluded by the file restriction
+    fn file_in_scope<T: AsRef<str>>(&self, name: T) -> bool {
+        match &self.restricted_files {
+            Some(restricted_files) => restricted_files.is_match(name.as_ref()),
+            None => true,
+        }
+    }
+
+    /// True if locations without file information are denied, regardless of their function
+    pub fn requires_source_info(&self) -> bool {
+        self.require_source_info || self.restricted_files.is_some()
     }
 
     /// Check if a function/file is allowed
+    ///
+    /// If there is no file information, the decision is based
+    /// on the function name alone, unless source information is required.
     pub fn check<T: AsRef<str>>(&self, file: Option<T>, func: Option<T>) -> bool {
+        if file.is_none() && self.requires_source_info() {
+            return false;
+        }
+
+        if let Some(file) = &file {
+            if !self.file_in_scope(file) {
+                return false;
+            }
+        }
+
+        if let Some(func) = &func {
+            if self.denied_functions.is_match(func.as_ref()) {
+                return false;
+            }
+        }
+
         let file_allowed = file.map_or(false, |file| self.check_file(file));
         let func_allowed = func.map_or(false, |func| self.check_function(func));
 
@@ -127,13 +310,22 @@ impl MutationPolicy {
     }
 }
 
+/// Return the first pattern of `set` that matches `name`
+pub fn first_match<'a>(set: &'a RegexSet, name: &str) -> Option<&'a str> {
+    let index = set.matches(name).into_iter().next()?;
+    Some(set.patterns()[index].as_str())
+}
+
 impl Default for MutationPolicy {
     /// Create default `MutationPolicy`, where everything is allowed
     fn default() -> Self {
         Self {
             allowed_functions: RegexSet::new(&[] as &[&str]).unwrap(),
             allowed_files: RegexSet::new(&[] as &[&str]).unwrap(),
+            denied_functions: RegexSet::new(&[] as &[&str]).unwrap(),
             anything_allowed: true,
+            require_source_info: false,
+            restricted_files: None,
         }
     }
 }
@@ -178,6 +370,70 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn missing_source_info() -> Result<()> {
+        let policy = MutationPolicyBuilder::default()
+            .allow_function("^test_")
+            .build()?;
+        assert!(policy.check(None, Some("test_func1")));
+        assert!(!policy.check(None, Some("func1")));
+        assert!(!policy.requires_source_info());
+
+        let policy = MutationPolicyBuilder::default()
+            .allow_function("^test_")
+            .require_source_info(true)
+            .build()?;
+        assert!(!policy.check(None, Some("test_func1")));
+        assert!(policy.check(Some("src/foo.rs"), Some("test_func1")));
+        assert!(policy.requires_source_info());
+
+        Ok(())
+    }
+
+    #[test]
+    fn restricted_files() -> Result<()> {
+        let policy = MutationPolicyBuilder::default()
+            .allow_function("^test_")
+            .restrict_files(&[git::file_pattern("src/a.c")])
+            .build()?;
+
+        assert!(policy.check(Some("/home/user/src/a.c"), Some("test_func1")));
+        assert!(!policy.check(Some("/home/user/src/b.c"), Some("test_func1")));
+        assert!(!policy.check(None, Some("test_func1")));
+
+        let policy = MutationPolicyBuilder::default()
+            .restrict_files(&[git::file_pattern("src/a.c")])
+            .build()?;
+        assert!(policy.check_file("/home/user/src/a.c"));
+        assert!(!policy.check_file("/home/user/src/b.c"));
+        assert!(policy.check(Some("src/a.c"), Some("func1")));
+        assert!(!policy.check(Some("src/b.c"), Some("func1")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn matching_patterns() -> Result<()> {
+        let policy = MutationPolicyBuilder::default()
+            .allow_function("^test_")
+            .allow_function("func")
+            .allow_file("^src/")
+            .build()?;
+
+        assert_eq!(
+            policy.matching_function_pattern("test_func1"),
+            Some("^test_")
+        );
+        assert_eq!(policy.matching_function_pattern("my_func"), Some("func"));
+        assert_eq!(policy.matching_function_pattern("main"), None);
+        assert_eq!(policy.matching_file_pattern("src/main.rs"), Some("^src/"));
+        assert_eq!(policy.matching_file_pattern("test/main.rs"), None);
+
+        let policy = MutationPolicy::default();
+        assert_eq!(policy.matching_function_pattern("main"), None);
+        Ok(())
+    }
+
     #[test]
     fn empty_policy_allows_all() -> Result<()> {
         let policy = MutationPolicy::default();
@@ -189,4 +445,30 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn preset_denies_runtime_functions() -> Result<()> {
+        let mut builder = MutationPolicyBuilder::default();
+        for function in FilterPreset::Rust.denied_functions() {
+            builder = builder.deny_function(function);
+        }
+        let policy = builder.build()?;
+
+        assert!(!policy.check_function("rust_begin_unwind"));
+        assert!(!policy.check_function("__rust_alloc_error_handler"));
+        assert!(!policy.check_function("dlmalloc::dlmalloc::Dlmalloc<A>::malloc"));
+        assert!(!policy.check_function("core::panicking::panic_fmt"));
+        assert!(!policy.check(Some("src/lib.rs"), Some("rust_begin_unwind")));
+        assert!(policy.check_function("factorial::factorial"));
+        assert!(policy.check(Some("src/lib.rs"), Some("factorial::factorial")));
+
+        let policy = MutationPolicyBuilder::default()
+            .allow_file("^src/")
+            .deny_function("^malloc$")
+            .build()?;
+        assert!(!policy.check(Some("src/malloc.c"), Some("malloc")));
+        assert!(policy.check(Some("src/malloc.c"), Some("malloc_usable_size")));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,223 @@
+//! Preview of the mutants of a single source line (`wasmut preview`), showing
+//! every instruction mapped to the line, the operators applicable to it and the
+//! instructions it would be replaced with, without executing anything.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::{
+    addressresolver::LineIndex,
+    mutation::{MutantIds, MutationLocation},
+    wasmmodule::WasmModule,
+};
+
+/// A mutant of an instruction
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PreviewMutant {
+    pub id: String,
+    pub operator: String,
+    pub description: String,
+    /// Instructions replacing the original instruction, in text format
+    pub replacement: Vec<String>,
+}
+
+/// An instruction mapped to the previewed line
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PreviewInstruction {
+    pub offset: u64,
+    pub function: Option<String>,
+    /// The instruction in text format, e.g. `i32.add`
+    pub instruction: String,
+    /// Empty if no enabled operator applies to the instruction,
+    /// or if it is excluded by the filter configuration
+    pub mutants: Vec<PreviewMutant>,
+}
+
+/// Mutants of all instructions of a source line
+#[derive(Serialize, Debug)]
+pub struct Preview {
+    /// Source file as recorded in the debug information
+    pub file: String,
+    pub line: u64,
+    /// Instructions of the line, sorted by offset
+    pub instructions: Vec<PreviewInstruction>,
+}
+
+impl Preview {
+    /// Preview the mutants of `locations` that belong to `line` of `file`.
+    ///
+    /// `file` may be a suffix of the path in the debug information, but must
+    /// match a single source file.
+    pub fn new(
+        module: &WasmModule,
+        locations: &[MutationLocation],
+        lines: &LineIndex,
+        file: &str,
+        line: u64,
+    ) -> Result<Self> {
+        let file = match lines.matching_files(file)[..] {
+            [path] => path,
+            [] => bail!(
+                "{file} does not match any source file of the module, see `wasmut list-files`"
+            ),
+            ref paths => bail!(
+                "{file} matches multiple source files, use a longer path: {}",
+                paths.join(", ")
+            ),
+        };
+
+        let offsets = lines.offsets(file, line);
+        let mutant_ids = MutantIds::new(module)?;
+        let mutants: HashMap<u64, &MutationLocation> = locations
+            .iter()
+            .filter(|location| offsets.contains(&location.offset))
+            .map(|location| (location.offset, location))
+            .collect();
+
+        let mut instructions = module
+            .instruction_walker(&|instruction, location| {
+                if !offsets.contains(&location.instruction_offset) {
+                    return vec![];
+                }
+
+                vec![(
+                    location.instruction_offset,
+                    location.function.map(String::from),
+                    instruction.to_string(),
+                )]
+            })
+            .context("Could not read the instructions of the module")?
+            .into_iter()
+            .map(|(offset, function, instruction)| PreviewInstruction {
+                offset,
+                function,
+                instruction,
+                mutants: mutants
+                    .get(&offset)
+                    .map(|location| preview_mutants(location, &mutant_ids))
+                    .unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+        instructions.sort_by_key(|instruction| instruction.offset);
+
+        Ok(Self {
+            file: file.into(),
+            line,
+            instructions,
+        })
+    }
+
+    /// Number of mutants of the line
+    pub fn mutants(&self) -> usize {
+        self.instructions
+            .iter()
+            .map(|instruction| instruction.mutants.len())
+            .sum()
+    }
+}
+
+fn preview_mutants(location: &MutationLocation, mutant_ids: &MutantIds) -> Vec<PreviewMutant> {
+    location
+        .mutations
+        .iter()
+        .map(|mutation| PreviewMutant {
+            id: mutant_ids.for_location(location, &*mutation.operator),
+            operator: mutation.operator.dyn_name().into(),
+            description: mutation.operator.description(),
+            replacement: mutation
+                .operator
+                .replacement()
+                .iter()
+                .map(|instruction| instruction.to_string())
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        addressresolver::{AddressIndex, CodeLocation},
+        config::Config,
+        mutation::MutationEngine,
+    };
+    use wasmut_wasm::elements::Instruction;
+
+    #[test]
+    fn preview_line() -> Result<()> {
+        let module = crate::fixtures::functions(
+            &[],
+            &[],
+            vec![vec![
+                Instruction::I32Const(1),
+                Instruction::I32Const(2),
+                Instruction::I32Add,
+                Instruction::Drop,
+            ]],
+        )?;
+
+        let config =
+            Config::parse("[operators]\nenabled_operators = [\"binop_add_to_sub\", \"const\"]")?;
+        let locations = MutationEngine::new(&config, 100)?
+            .discover_mutation_positions(&module)?
+            .locations;
+        let addition = locations
+            .iter()
+            .find(|location| location.statement_number == 2)
+            .unwrap();
+        let operator = &addition.mutations[0].operator;
+        let addition = addition.offset;
+
+        // The constant 2 and the addition are on line 3, the constant 1 on line 2
+        let at = |line: u64| {
+            Some(CodeLocation {
+                file: Some("/src/add.c".into()),
+                line: Some(line),
+                ..Default::default()
+            })
+        };
+        let lines = AddressIndex::from_entries(vec![
+            (addition - 4, at(2)),
+            (addition - 2, at(3)),
+            (addition, at(3)),
+            (addition + 1, at(4)),
+        ])
+        .line_index();
+
+        let preview = Preview::new(&module, &locations, &lines, "add.c", 3)?;
+        assert_eq!(preview.file, "/src/add.c");
+        assert_eq!(preview.instructions.len(), 2);
+
+        let constant = &preview.instructions[0];
+        assert_eq!(constant.instruction, "i32.const 2");
+        assert!(!constant.mutants.is_empty());
+
+        let add = &preview.instructions[1];
+        assert_eq!(add.offset, addition);
+        assert_eq!(add.instruction, "i32.add");
+        assert_eq!(
+            add.mutants,
+            vec![PreviewMutant {
+                id: MutantIds::new(&module)?.for_offset(addition, &**operator),
+                operator: "binop_add_to_sub".into(),
+                description: operator.description(),
+                replacement: vec!["i32.sub".into()],
+            }]
+        );
+        assert_eq!(preview.mutants(), constant.mutants.len() + 1);
+
+        let drop = Preview::new(&module, &locations, &lines, "add.c", 4)?;
+        assert_eq!(drop.instructions[0].instruction, "drop");
+        assert_eq!(drop.mutants(), 0);
+
+        assert!(Preview::new(&module, &locations, &lines, "add.c", 10)?
+            .instructions
+            .is_empty());
+
+        assert!(Preview::new(&module, &locations, &lines, "sub.c", 3).is_err());
+        Ok(())
+    }
+}
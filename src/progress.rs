@@ -0,0 +1,376 @@
+//! Progress of the mutant execution.
+//!
+//! The executor records every finished mutant. The remaining time is estimated from
+//! the observed throughput and shown next to the progress bar. Skipped mutants are
+//! not counted, since they take no time, and in meta-mutant mode, the time spent
+//! waiting for the next meta-mutant to be compiled is estimated separately from the
+//! execution of the mutants.
+//!
+//! Every `summary_interval_minutes` minutes or `summary_interval_mutants` mutants,
+//! a summary with the outcomes so far, the current mutation score and the estimated
+//! remaining time is written to stderr, so that runs can be followed in CI logs,
+//! where no progress bar is shown.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::{executor::ExecutedMutant, output, reporter::MutationOutcome};
+
+/// Outcomes of the mutants finished so far
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ProgressCounts {
+    finished: usize,
+    killed: usize,
+    alive: usize,
+    timeout: usize,
+    error: usize,
+    skipped: usize,
+    not_run: usize,
+    internal_error: usize,
+}
+
+impl ProgressCounts {
+    fn add(&mut self, outcome: &MutationOutcome) {
+        self.finished += 1;
+        match outcome {
+            MutationOutcome::Killed => self.killed += 1,
+            MutationOutcome::Alive => self.alive += 1,
+            MutationOutcome::Timeout => self.timeout += 1,
+            MutationOutcome::Error => self.error += 1,
+            MutationOutcome::Skipped => self.skipped += 1,
+            MutationOutcome::NotRun => self.not_run += 1,
+            MutationOutcome::InternalError => self.internal_error += 1,
+        }
+    }
+
+    /// Number of mutants that were actually executed
+    fn executed(&self) -> usize {
+        self.killed + self.alive + self.timeout + self.error
+    }
+
+    /// Mutation score of the finished mutants, computed like `accumulate_outcomes`
+    fn mutation_score(&self) -> f64 {
+        let detected = self.killed + self.timeout + self.error;
+        let scored = detected + self.alive + self.skipped;
+        if scored == 0 {
+            return 0.0;
+        }
+        100.0 * detected as f64 / scored as f64
+    }
+}
+
+/// Observed throughput of a run, separating the execution of mutants from
+/// the compilation of meta-mutants that is not hidden behind the execution
+#[derive(Debug, Default)]
+struct Throughput {
+    /// Time spent in finished execution phases
+    execution_time: Duration,
+
+    /// Start of the current execution phase
+    phase_start: Option<Instant>,
+
+    /// Time spent waiting for compiled meta-mutants, and the number of waits
+    compile_waits: (Duration, usize),
+
+    /// Number of meta-mutants that still have to be compiled
+    remaining_compilations: usize,
+}
+
+impl Throughput {
+    fn start_execution(&mut self, now: Instant) {
+        self.phase_start = Some(now);
+    }
+
+    fn finish_execution(&mut self, now: Instant) {
+        if let Some(start) = self.phase_start.take() {
+            self.execution_time += now.duration_since(start);
+        }
+    }
+
+    fn record_compile_wait(&mut self, wait: Duration, remaining_compilations: usize) {
+        self.compile_waits.0 += wait;
+        self.compile_waits.1 += 1;
+        self.remaining_compilations = remaining_compilations;
+    }
+
+    /// Remaining time, if `executed` of `to_execute` mutants were executed by `now`.
+    /// `None` if no mutant was executed yet.
+    fn remaining(&self, executed: usize, to_execute: usize, now: Instant) -> Option<Duration> {
+        let elapsed = self.execution_time
+            + self
+                .phase_start
+                .map(|start| now.duration_since(start))
+                .unwrap_or_default();
+        if executed == 0 || elapsed.is_zero() {
+            return None;
+        }
+
+        let execution =
+            elapsed.mul_f64(to_execute.saturating_sub(executed) as f64 / executed as f64);
+        let compilation = match self.compile_waits {
+            (_, 0) => Duration::ZERO,
+            (wait, waits) => wait.mul_f64(self.remaining_compilations as f64 / waits as f64),
+        };
+        Some(execution + compilation)
+    }
+}
+
+struct ProgressState {
+    counts: ProgressCounts,
+    throughput: Throughput,
+    /// Time and number of finished mutants of the last summary
+    last_summary: (Instant, usize),
+}
+
+/// Tracks the progress of a run, showing the estimated remaining time next to
+/// a progress bar and logging a summary every `interval` or every `every` mutants
+pub struct Progress {
+    total: usize,
+    to_execute: usize,
+    interval: Option<Duration>,
+    every: Option<usize>,
+    started: Instant,
+    bar: Option<ProgressBar>,
+    state: Mutex<ProgressState>,
+}
+
+impl Progress {
+    /// Progress of a run with `total` mutants, of which `to_execute` are not skipped.
+    /// No summaries are logged if neither `interval` nor `every` is set.
+    pub fn new(
+        total: usize,
+        to_execute: usize,
+        interval: Option<Duration>,
+        every: Option<usize>,
+    ) -> Self {
+        let started = Instant::now();
+        Self {
+            total,
+            to_execute,
+            interval,
+            every,
+            started,
+            bar: None,
+            state: Mutex::new(ProgressState {
+                counts: ProgressCounts::default(),
+                throughput: Throughput::default(),
+                last_summary: (started, 0),
+            }),
+        }
+    }
+
+    /// Show the estimated remaining time next to `bar`
+    pub fn with_bar(self, bar: ProgressBar) -> Self {
+        if let Ok(style) = ProgressStyle::with_template("{wide_bar} {pos}/{len} {msg}") {
+            bar.set_style(style);
+        }
+        Self {
+            bar: Some(bar),
+            ..self
+        }
+    }
+
+    /// Start executing mutants
+    pub fn start_execution(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.throughput.start_execution(Instant::now());
+        }
+    }
+
+    /// Stop executing mutants, e.g. to wait for the next meta-mutant
+    pub fn finish_execution(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.throughput.finish_execution(Instant::now());
+        }
+    }
+
+    /// Record that the execution waited `wait` for a meta-mutant to be compiled,
+    /// with `remaining` meta-mutants left to compile
+    pub fn record_compile_wait(&self, wait: Duration, remaining: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            state.throughput.record_compile_wait(wait, remaining);
+        }
+    }
+
+    /// Record a finished mutant, update the remaining time and log a summary, if one is due
+    pub fn record(&self, mutant: &ExecutedMutant) {
+        let now = Instant::now();
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.counts.add(&mutant.outcome());
+
+        if let Some(bar) = &self.bar {
+            if let Some(remaining) = self.remaining(&state, now) {
+                bar.set_message(format!("ETA {}", format_duration(remaining)));
+            }
+        }
+
+        if let Some(summary) = self.summary(&mut state, now) {
+            output::output_progress(summary);
+        }
+    }
+
+    fn remaining(&self, state: &ProgressState, now: Instant) -> Option<Duration> {
+        state
+            .throughput
+            .remaining(state.counts.executed(), self.to_execute, now)
+    }
+
+    /// Summary of the progress at `now`, if one is due
+    fn summary(&self, state: &mut ProgressState, now: Instant) -> Option<String> {
+        let (last_time, last_finished) = state.last_summary;
+        let finished = state.counts.finished;
+        let time_due = matches!(
+            self.interval,
+            Some(interval) if now.duration_since(last_time) >= interval
+        );
+        let count_due = matches!(self.every, Some(every) if finished - last_finished >= every);
+        if !(time_due || count_due) || finished >= self.total {
+            return None;
+        }
+        state.last_summary = (now, finished);
+
+        let counts = &state.counts;
+        let percentage = 100.0 * counts.finished as f64 / self.total.max(1) as f64;
+        let remaining = match self.remaining(state, now) {
+            Some(remaining) => format!("~{}", format_duration(remaining)),
+            None => "unknown".into(),
+        };
+
+        Some(format!(
+            "Progress: {}/{} mutants ({percentage:.1}%), killed: {}, alive: {}, timeout: {}, \
+            error: {}, skipped: {}, mutation score: {:.1}%, elapsed: {}, remaining: {remaining}",
+            counts.finished,
+            self.total,
+            counts.killed,
+            counts.alive,
+            counts.timeout,
+            counts.error,
+            counts.skipped,
+            counts.mutation_score(),
+            format_duration(now.duration_since(self.started)),
+        ))
+    }
+}
+
+/// Format a duration as `1h 02m 03s`
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{}h {:02}m {:02}s",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Record `outcome` at `now`, and return the summary if one is due
+    fn record(progress: &Progress, outcome: MutationOutcome, now: Instant) -> Option<String> {
+        let mut state = progress.state.lock().unwrap();
+        state.counts.add(&outcome);
+        progress.summary(&mut state, now)
+    }
+
+    #[test]
+    fn summary_every_n_mutants() {
+        let progress = Progress::new(10, 8, None, Some(4));
+        let start = progress.started;
+        let at = |seconds| start + Duration::from_secs(seconds);
+        progress
+            .state
+            .lock()
+            .unwrap()
+            .throughput
+            .start_execution(start);
+
+        let outcomes = [
+            MutationOutcome::Killed,
+            MutationOutcome::Alive,
+            MutationOutcome::Timeout,
+        ];
+        for (i, outcome) in outcomes.into_iter().enumerate() {
+            assert_eq!(record(&progress, outcome, at(i as u64)), None);
+        }
+
+        // 3 mutants executed in 30s, 5 remaining
+        assert_eq!(
+            record(&progress, MutationOutcome::Skipped, at(30)).unwrap(),
+            "Progress: 4/10 mutants (40.0%), killed: 1, alive: 1, timeout: 1, error: 0, \
+            skipped: 1, mutation score: 50.0%, elapsed: 0h 00m 30s, remaining: ~0h 00m 50s"
+        );
+        assert_eq!(record(&progress, MutationOutcome::Killed, at(50)), None);
+    }
+
+    #[test]
+    fn summary_every_interval() {
+        let progress = Progress::new(100, 100, Some(Duration::from_secs(60)), None);
+        let start = progress.started;
+        let at = |seconds| start + Duration::from_secs(seconds);
+
+        assert_eq!(record(&progress, MutationOutcome::Killed, at(30)), None);
+        let summary = record(&progress, MutationOutcome::Killed, at(61)).unwrap();
+        assert!(summary.starts_with("Progress: 2/100 mutants (2.0%), killed: 2,"));
+        assert!(summary.ends_with("remaining: unknown"));
+        assert_eq!(record(&progress, MutationOutcome::Killed, at(90)), None);
+        assert!(record(&progress, MutationOutcome::Killed, at(122)).is_some());
+    }
+
+    #[test]
+    fn no_summary_if_disabled_or_finished() {
+        let progress = Progress::new(2, 2, None, None);
+        assert_eq!(
+            record(&progress, MutationOutcome::Killed, Instant::now()),
+            None
+        );
+
+        // The final result is reported anyway
+        let progress = Progress::new(2, 2, None, Some(1));
+        let now = Instant::now();
+        assert!(record(&progress, MutationOutcome::Killed, now).is_some());
+        assert_eq!(record(&progress, MutationOutcome::Killed, now), None);
+    }
+
+    #[test]
+    fn remaining_time() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut throughput = Throughput::default();
+        assert_eq!(throughput.remaining(0, 100, at(0)), None);
+
+        // 10 mutants in 20s
+        throughput.start_execution(at(0));
+        assert_eq!(
+            throughput.remaining(10, 100, at(20)),
+            Some(Duration::from_secs(180))
+        );
+
+        // Waiting 5s for the next meta-mutant does not slow down the execution,
+        // but is expected for each of the remaining meta-mutants
+        throughput.finish_execution(at(20));
+        throughput.record_compile_wait(Duration::from_secs(5), 3);
+        throughput.start_execution(at(25));
+        assert_eq!(
+            throughput.remaining(20, 100, at(45)),
+            Some(Duration::from_secs(160 + 15))
+        );
+        assert_eq!(
+            throughput.remaining(100, 100, at(45)),
+            Some(Duration::from_secs(15))
+        );
+    }
+
+    #[test]
+    fn duration_format() {
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1h 02m 03s");
+        assert_eq!(format_duration(Duration::ZERO), "0h 00m 00s");
+    }
+}
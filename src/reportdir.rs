@@ -0,0 +1,297 @@
+//! Report directories created from `[report] output_pattern`, so that repeated
+//! runs do not overwrite each other. Old reports are pruned (`keep_last`) and a
+//! `latest` symlink points to the report of the most recent run.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use regex::Regex;
+
+use crate::lockfile::LOCK_FILE_NAME;
+
+/// Output directory, if neither the command line nor the configuration set one
+pub const DEFAULT_OUTPUT_DIRECTORY: &str = "wasmut-report";
+
+/// Replaced by the start time of the run, e.g. `20230415-093012`
+pub const TIMESTAMP_PLACEHOLDER: &str = "{timestamp}";
+
+/// Replaced by the file name of the module, without extension
+pub const MODULE_PLACEHOLDER: &str = "{module}";
+
+/// Format of `TIMESTAMP_PLACEHOLDER`, sorting chronologically
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Regex matching an expanded timestamp, including the suffix of runs
+/// started within the same second. Captures the timestamp and the suffix.
+const TIMESTAMP_REGEX: &str = r"(\d{8}-\d{6})(?:-(\d+))?";
+
+/// Name of the symlink pointing to the most recent report
+const LATEST_LINK: &str = "latest";
+
+/// Report directory of a single run
+#[derive(Debug)]
+pub struct ReportDirectory {
+    path: PathBuf,
+
+    /// Matches the directory names of all runs, if the pattern contains a timestamp
+    runs: Option<Regex>,
+}
+
+impl ReportDirectory {
+    /// Expand the placeholders in `pattern` for a run of `wasmfile` started at `start`.
+    /// Placeholders are only allowed in the last component of the pattern.
+    pub fn new(pattern: &str, wasmfile: &str, start: DateTime<Local>) -> Result<Self> {
+        let module = Path::new(wasmfile)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let pattern = pattern.replace(MODULE_PLACEHOLDER, &module);
+
+        if !pattern.contains(TIMESTAMP_PLACEHOLDER) {
+            return Ok(Self {
+                path: pattern.into(),
+                runs: None,
+            });
+        }
+
+        let pattern = Path::new(&pattern);
+        let name = pattern
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let runs = Regex::new(&format!(
+            "^{}$",
+            regex::escape(&name).replace(&regex::escape(TIMESTAMP_PLACEHOLDER), TIMESTAMP_REGEX)
+        ))?;
+
+        // Runs started within the same second get a numeric suffix
+        let timestamp = start.format(TIMESTAMP_FORMAT).to_string();
+        let mut path = pattern.with_file_name(name.replace(TIMESTAMP_PLACEHOLDER, &timestamp));
+        let mut suffix = 1;
+        while path.exists() {
+            let timestamp = format!("{timestamp}-{suffix}");
+            path = pattern.with_file_name(name.replace(TIMESTAMP_PLACEHOLDER, &timestamp));
+            suffix += 1;
+        }
+
+        Ok(Self {
+            path,
+            runs: Some(runs),
+        })
+    }
+
+    /// Directory the report is written to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Lock file protecting the report directory from concurrent runs. With a
+    /// timestamp, the directory containing all runs is locked, since old reports are pruned.
+    pub fn lock_path(&self) -> PathBuf {
+        if self.runs.is_some() {
+            return self.parent().join(LOCK_FILE_NAME);
+        }
+
+        match self.path.file_name() {
+            Some(name) => self
+                .path
+                .with_file_name(format!("{}.lock", name.to_string_lossy())),
+            None => self.path.join(LOCK_FILE_NAME),
+        }
+    }
+
+    /// Directory containing the reports of all runs
+    fn parent(&self) -> &Path {
+        match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        }
+    }
+
+    /// Names of the report directories of all runs, oldest first
+    fn runs(&self) -> Result<Vec<String>> {
+        let runs = match &self.runs {
+            Some(runs) => runs,
+            None => return Ok(Vec::new()),
+        };
+
+        let parent = self.parent();
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(parent)
+            .with_context(|| format!("Could not list reports in {parent:?}"))?
+        {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // Symlinks are never removed
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            if let Some(captures) = runs.captures(&name) {
+                // Runs of the same second are ordered by their suffix numerically,
+                // so that `-10` comes after `-2`
+                let timestamp = captures[1].to_string();
+                let suffix = captures
+                    .get(2)
+                    .and_then(|suffix| suffix.as_str().parse::<u64>().ok())
+                    .unwrap_or(0);
+                names.push(((timestamp, suffix), name));
+            }
+        }
+
+        names.sort();
+        Ok(names.into_iter().map(|(_, name)| name).collect())
+    }
+
+    /// Delete all but the `keep_last` most recent reports created from the same pattern.
+    /// Does nothing if the pattern does not contain a timestamp.
+    pub fn prune(&self, keep_last: usize) -> Result<()> {
+        let runs = self.runs()?;
+        let obsolete = runs.len().saturating_sub(keep_last);
+
+        for name in &runs[..obsolete] {
+            let path = self.parent().join(name);
+            log::info!("Removing old report {path:?}");
+            std::fs::remove_dir_all(&path)
+                .with_context(|| format!("Could not remove old report {path:?}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Point the `latest` symlink next to the report directory to it.
+    /// Does nothing if the pattern does not contain a timestamp.
+    pub fn link_latest(&self) -> Result<()> {
+        if self.runs.is_none() {
+            return Ok(());
+        }
+
+        let link = self.parent().join(LATEST_LINK);
+        if let Ok(metadata) = std::fs::symlink_metadata(&link) {
+            if !metadata.file_type().is_symlink() {
+                log::warn!("Not updating {link:?}, since it is not a symlink");
+                return Ok(());
+            }
+            std::fs::remove_file(&link)
+                .with_context(|| format!("Could not remove symlink {link:?}"))?;
+        }
+
+        #[cfg(unix)]
+        {
+            let target = self
+                .path
+                .file_name()
+                .context("Report directory has no name")?;
+            std::os::unix::fs::symlink(target, &link)
+                .with_context(|| format!("Could not create symlink {link:?}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn start(second: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2023, 4, 15, 9, 30, second).unwrap()
+    }
+
+    #[test]
+    fn expand_placeholders() -> Result<()> {
+        let directory = ReportDirectory::new("reports/{module}", "/tmp/add.wasm", start(12))?;
+        assert_eq!(directory.path(), Path::new("reports/add"));
+
+        let directory =
+            ReportDirectory::new("reports/{module}-{timestamp}", "add.wasm", start(12))?;
+        assert_eq!(directory.path(), Path::new("reports/add-20230415-093012"));
+        Ok(())
+    }
+
+    #[test]
+    fn lock_paths() -> Result<()> {
+        let directory = ReportDirectory::new("reports/{module}", "add.wasm", start(12))?;
+        assert_eq!(directory.lock_path(), Path::new("reports/add.lock"));
+
+        let directory = ReportDirectory::new("reports/{timestamp}", "add.wasm", start(12))?;
+        assert_eq!(
+            directory.lock_path(),
+            Path::new("reports").join(LOCK_FILE_NAME)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unique_directories() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let pattern = dir.path().join("{timestamp}");
+        let pattern = pattern.to_str().unwrap();
+
+        let first = ReportDirectory::new(pattern, "add.wasm", start(12))?;
+        std::fs::create_dir_all(first.path())?;
+        let second = ReportDirectory::new(pattern, "add.wasm", start(12))?;
+
+        assert_eq!(second.path(), dir.path().join("20230415-093012-1"));
+        Ok(())
+    }
+
+    #[test]
+    fn runs_of_the_same_second_are_ordered_by_suffix() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let pattern = dir.path().join("{timestamp}");
+        let pattern = pattern.to_str().unwrap();
+
+        let mut last = None;
+        for _ in 0..12 {
+            let directory = ReportDirectory::new(pattern, "add.wasm", start(12))?;
+            std::fs::create_dir_all(directory.path())?;
+            last = Some(directory);
+        }
+        let last = last.unwrap();
+        assert_eq!(last.path(), dir.path().join("20230415-093012-11"));
+
+        let runs = last.runs()?;
+        assert_eq!(runs[0], "20230415-093012");
+        assert_eq!(runs[2], "20230415-093012-2");
+        assert_eq!(runs[11], "20230415-093012-11");
+
+        last.prune(1)?;
+        assert_eq!(last.runs()?, ["20230415-093012-11"]);
+        Ok(())
+    }
+
+    #[test]
+    fn prune_and_link_latest() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let pattern = dir.path().join("{timestamp}");
+        let pattern = pattern.to_str().unwrap();
+        std::fs::create_dir(dir.path().join("unrelated"))?;
+
+        let mut last = None;
+        for second in 0..4 {
+            let directory = ReportDirectory::new(pattern, "add.wasm", start(second))?;
+            std::fs::create_dir_all(directory.path())?;
+            directory.link_latest()?;
+            directory.prune(2)?;
+            last = Some(directory);
+        }
+
+        let mut names: Vec<String> = std::fs::read_dir(dir.path())?
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            ["20230415-093002", "20230415-093003", "latest", "unrelated"]
+        );
+
+        #[cfg(unix)]
+        assert_eq!(
+            std::fs::read_link(dir.path().join("latest"))?,
+            last.unwrap().path().file_name().unwrap()
+        );
+        Ok(())
+    }
+}
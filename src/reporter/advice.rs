@@ -0,0 +1,370 @@
+//! Suggestions derived from the outcomes of a run, printed at the end of the
+//! CLI report and shown in the HTML report.
+//!
+//! Every rule looks at the accumulated outcomes, the surviving mutants per file
+//! or per operator, and the settings of the run, and returns a suggestion if
+//! it applies, e.g. if most survivors are in a single file or many mutants
+//! timed out. Rules are ordered by how much they are expected to help.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    accumulate_outcomes, rewriter::PathRewriter, AccumulatedOutcomes, MutationOutcome,
+    ReportableMutant,
+};
+use crate::config::Config;
+
+/// Survivors are concentrated in a file, if it contains this percentage of them
+const CONCENTRATED_SURVIVORS: f32 = 50.0;
+
+/// Minimum number of survivors for rules about their distribution
+const MIN_SURVIVORS: i32 = 5;
+
+/// Coverage is poor, if this percentage of the mutants was skipped
+const POOR_COVERAGE: f32 = 50.0;
+
+/// An operator is ineffective, if this percentage of its executed mutants survived
+const INEFFECTIVE_OPERATOR: f32 = 80.0;
+
+/// Minimum number of executed mutants of an operator for it to be ineffective
+const MIN_OPERATOR_MUTANTS: i32 = 10;
+
+/// Settings of the run that the rules refer to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdviceContext {
+    /// `engine.coverage_based_execution`
+    pub coverage_based_execution: bool,
+
+    /// `engine.timeout_multiplier`
+    pub timeout_multiplier: f64,
+
+    /// `engine.timeout_warning_threshold`
+    pub timeout_warning_threshold: f64,
+}
+
+impl AdviceContext {
+    /// Settings of a run with `config`
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            coverage_based_execution: config.engine().coverage_based_execution(),
+            timeout_multiplier: config.engine().timeout_multiplier(),
+            timeout_warning_threshold: config.engine().timeout_warning_threshold(),
+        }
+    }
+}
+
+impl Default for AdviceContext {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+
+/// What a suggestion is about
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdviceTopic {
+    /// Most surviving mutants are in a single file
+    ConcentratedSurvivors,
+
+    /// Many mutants are not executed by the tests
+    Coverage,
+
+    /// Many mutants timed out
+    Timeouts,
+
+    /// Most mutants of an operator survived
+    IneffectiveOperator,
+}
+
+/// A suggestion about the tested module or the configuration
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Advice {
+    pub topic: AdviceTopic,
+    pub message: String,
+}
+
+impl Advice {
+    fn new(topic: AdviceTopic, message: String) -> Self {
+        Self { topic, message }
+    }
+}
+
+/// Percentage of `part` in `total`
+fn percentage(part: i32, total: i32) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        100.0 * part as f32 / total as f32
+    }
+}
+
+/// Surviving mutants per (rewritten) source file, most survivors first
+fn survivors_per_file(
+    mutants: &[ReportableMutant],
+    path_rewriter: Option<&PathRewriter>,
+) -> Vec<(String, i32)> {
+    let mut files: BTreeMap<String, i32> = BTreeMap::new();
+    for mutant in mutants
+        .iter()
+        .filter(|mutant| mutant.outcome == MutationOutcome::Alive)
+    {
+        if let Some(file) = &mutant.location.file {
+            let file = match path_rewriter {
+                Some(path_rewriter) => path_rewriter.rewrite(file),
+                None => file.clone(),
+            };
+            *files.entry(file).or_default() += 1;
+        }
+    }
+
+    // Sorting is stable, so ties keep the alphabetical order
+    let mut files: Vec<(String, i32)> = files.into_iter().collect();
+    files.sort_by_key(|(_, alive)| std::cmp::Reverse(*alive));
+    files
+}
+
+/// Executed and surviving mutants per operator
+fn outcomes_per_operator(mutants: &[ReportableMutant]) -> BTreeMap<&str, (i32, i32)> {
+    let mut operators: BTreeMap<&str, (i32, i32)> = BTreeMap::new();
+    for mutant in mutants.iter().filter(|mutant| {
+        !matches!(
+            mutant.outcome,
+            MutationOutcome::Skipped | MutationOutcome::NotRun | MutationOutcome::InternalError
+        )
+    }) {
+        let (executed, alive) = operators.entry(mutant.operator.dyn_name()).or_default();
+        *executed += 1;
+        if mutant.outcome == MutationOutcome::Alive {
+            *alive += 1;
+        }
+    }
+    operators
+}
+
+/// Most survivors are in a single file
+fn concentrated_survivors(
+    outcomes: &AccumulatedOutcomes,
+    files: &[(String, i32)],
+) -> Option<Advice> {
+    if outcomes.alive < MIN_SURVIVORS || files.len() < 2 {
+        return None;
+    }
+
+    let (file, alive) = files.first()?;
+    let share = percentage(*alive, outcomes.alive);
+    (share >= CONCENTRATED_SURVIVORS).then(|| {
+        Advice::new(
+            AdviceTopic::ConcentratedSurvivors,
+            format!(
+                "{share:.0}% of the surviving mutants ({alive} of {}) are in {file}, \
+                additional tests for it are most effective",
+                outcomes.alive
+            ),
+        )
+    })
+}
+
+/// Coverage-based execution is disabled, or many mutants were skipped
+fn coverage(outcomes: &AccumulatedOutcomes, context: &AdviceContext) -> Option<Advice> {
+    if !context.coverage_based_execution {
+        return (outcomes.alive > 0).then(|| {
+            Advice::new(
+                AdviceTopic::Coverage,
+                "coverage_based_execution is disabled, so mutants of code that the tests \
+                never execute are executed and survive. Enable it to skip them and \
+                shorten the run"
+                    .into(),
+            )
+        });
+    }
+
+    let share = percentage(
+        outcomes.skipped,
+        outcomes.total - outcomes.not_run - outcomes.internal_error,
+    );
+    (share >= POOR_COVERAGE).then(|| {
+        Advice::new(
+            AdviceTopic::Coverage,
+            format!(
+                "{share:.0}% of the mutants were skipped, because the tests never execute them. \
+                Add tests for the uncovered code, or restrict the [filter] section to the \
+                code under test"
+            ),
+        )
+    })
+}
+
+/// Many mutants timed out
+fn timeouts(outcomes: &AccumulatedOutcomes, context: &AdviceContext) -> Option<Advice> {
+    let executed = outcomes.alive + outcomes.killed + outcomes.timeout + outcomes.error;
+    let share = percentage(outcomes.timeout, executed);
+    (outcomes.timeout > 0 && share as f64 > context.timeout_warning_threshold).then(|| {
+        Advice::new(
+            AdviceTopic::Timeouts,
+            format!(
+                "{share:.0}% of the executed mutants timed out, timeout_multiplier \
+                (currently {}) is likely too low",
+                context.timeout_multiplier
+            ),
+        )
+    })
+}
+
+/// Operators with most of their mutants surviving
+fn ineffective_operators(operators: &BTreeMap<&str, (i32, i32)>) -> Vec<Advice> {
+    operators
+        .iter()
+        .filter(|(_, (executed, alive))| {
+            *executed >= MIN_OPERATOR_MUTANTS
+                && percentage(*alive, *executed) >= INEFFECTIVE_OPERATOR
+        })
+        .map(|(operator, (executed, alive))| {
+            Advice::new(
+                AdviceTopic::IneffectiveOperator,
+                format!(
+                    "{:.0}% of the mutants of {operator} ({alive} of {executed}) survived. \
+                    If they are mostly equivalent, add it to disabled_operators",
+                    percentage(*alive, *executed)
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Suggestions for the outcomes of `mutants`, most helpful first
+pub fn advise(
+    mutants: &[ReportableMutant],
+    context: &AdviceContext,
+    path_rewriter: Option<&PathRewriter>,
+) -> Vec<Advice> {
+    if mutants.is_empty() {
+        return Vec::new();
+    }
+
+    let outcomes = accumulate_outcomes(mutants);
+    let files = survivors_per_file(mutants, path_rewriter);
+    let operators = outcomes_per_operator(mutants);
+
+    concentrated_survivors(&outcomes, &files)
+        .into_iter()
+        .chain(coverage(&outcomes, context))
+        .chain(timeouts(&outcomes, context))
+        .chain(ineffective_operators(&operators))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporter::MutantBuilder;
+
+    fn topics(advice: &[Advice]) -> Vec<AdviceTopic> {
+        advice.iter().map(|advice| advice.topic).collect()
+    }
+
+    #[test]
+    fn no_advice_for_good_runs() {
+        let mutants: Vec<_> = (0..20)
+            .map(|i| {
+                let outcome = if i < 18 {
+                    MutationOutcome::Killed
+                } else {
+                    MutationOutcome::Alive
+                };
+                let mutant = MutantBuilder::new()
+                    .with_file(if i % 2 == 0 { "a.c" } else { "b.c" })
+                    .with_outcome(outcome);
+                if i % 3 == 0 {
+                    mutant.build()
+                } else {
+                    mutant.with_sub_to_add().build()
+                }
+            })
+            .collect();
+
+        assert!(advise(&mutants, &AdviceContext::default(), None).is_empty());
+        assert!(advise(&[], &AdviceContext::default(), None).is_empty());
+    }
+
+    #[test]
+    fn concentrated_survivors_and_ineffective_operator() {
+        let mut mutants: Vec<_> = (0..10)
+            .map(|_| {
+                MutantBuilder::new()
+                    .with_file("src/parser.c")
+                    .with_outcome(MutationOutcome::Alive)
+                    .build()
+            })
+            .collect();
+        mutants.push(
+            MutantBuilder::new()
+                .with_file("src/main.c")
+                .with_outcome(MutationOutcome::Alive)
+                .with_sub_to_add()
+                .build(),
+        );
+        mutants.push(
+            MutantBuilder::new()
+                .with_file("src/main.c")
+                .with_outcome(MutationOutcome::Killed)
+                .with_sub_to_add()
+                .build(),
+        );
+
+        let advice = advise(&mutants, &AdviceContext::default(), None);
+        assert_eq!(
+            topics(&advice),
+            [
+                AdviceTopic::ConcentratedSurvivors,
+                AdviceTopic::IneffectiveOperator
+            ]
+        );
+        assert_eq!(
+            advice[0].message,
+            "91% of the surviving mutants (10 of 11) are in src/parser.c, \
+            additional tests for it are most effective"
+        );
+        assert!(advice[1]
+            .message
+            .starts_with("100% of the mutants of binop_add_to_sub (10 of 10) survived"));
+    }
+
+    #[test]
+    fn coverage_and_timeouts() {
+        let mut mutants: Vec<_> = [
+            MutationOutcome::Skipped,
+            MutationOutcome::Skipped,
+            MutationOutcome::Skipped,
+            MutationOutcome::Timeout,
+        ]
+        .into_iter()
+        .map(|outcome| MutantBuilder::new().with_outcome(outcome).build())
+        .collect();
+        let context = AdviceContext::default();
+        let advice = advise(&mutants, &context, None);
+        assert_eq!(
+            topics(&advice),
+            [AdviceTopic::Coverage, AdviceTopic::Timeouts]
+        );
+        assert!(advice[0]
+            .message
+            .starts_with("75% of the mutants were skipped"));
+        assert_eq!(
+            advice[1].message,
+            "100% of the executed mutants timed out, \
+            timeout_multiplier (currently 2) is likely too low"
+        );
+
+        mutants.push(MutantBuilder::new().build());
+        let context = AdviceContext {
+            coverage_based_execution: false,
+            ..context
+        };
+        let advice = advise(&mutants, &context, None);
+        assert!(advice[0]
+            .message
+            .starts_with("coverage_based_execution is disabled"));
+    }
+}
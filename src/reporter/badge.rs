@@ -0,0 +1,124 @@
+//! Rendering of shields.io style SVG badges, showing the mutation score.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use super::{ScoreLevel, ScoreThresholds};
+
+/// File name of the badge within the report directory
+pub const BADGE_FILE_NAME: &str = "badge.svg";
+
+const LABEL: &str = "mutation score";
+
+const RED: &str = "#e05d44";
+const YELLOW: &str = "#dfb317";
+const GREEN: &str = "#4c1";
+
+/// Approximate width of a character in the badge font (11px Verdana)
+const CHARACTER_WIDTH: usize = 7;
+
+/// Horizontal padding on each side of label and message
+const PADDING: usize = 6;
+
+/// Choose the badge color for the given score
+fn color(score: f32, thresholds: &ScoreThresholds) -> &'static str {
+    match thresholds.level(score) {
+        ScoreLevel::Danger => RED,
+        ScoreLevel::Warning => YELLOW,
+        ScoreLevel::Success => GREEN,
+    }
+}
+
+/// Width of a badge section containing `text`
+fn section_width(text: &str) -> usize {
+    text.chars().count() * CHARACTER_WIDTH + 2 * PADDING
+}
+
+/// Render the SVG badge for a given mutation score
+pub fn render(score: f32, thresholds: &ScoreThresholds) -> String {
+    let message = format!("{score:.1}%");
+    let color = color(score, thresholds);
+
+    let label_width = section_width(LABEL);
+    let message_width = section_width(&message);
+    let width = label_width + message_width;
+
+    let label_x = label_width / 2;
+    let message_x = label_width + message_width / 2;
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{LABEL}: {message}">
+  <title>{LABEL}: {message}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r">
+    <rect width="{width}" height="20" rx="3" fill="#fff"/>
+  </clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{LABEL}</text>
+    <text x="{label_x}" y="14">{LABEL}</text>
+    <text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>
+    <text x="{message_x}" y="14">{message}</text>
+  </g>
+</svg>
+"##
+    )
+}
+
+/// Write the badge for a given mutation score into `directory`
+pub fn write(directory: &Path, score: f32, thresholds: &ScoreThresholds) -> Result<()> {
+    let path = directory.join(BADGE_FILE_NAME);
+    std::fs::write(&path, render(score, thresholds))
+        .with_context(|| format!("Could not write badge to {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn badge_color_thresholds() {
+        let thresholds = &ScoreThresholds::default();
+        assert_eq!(color(0.0, thresholds), RED);
+        assert_eq!(color(49.9, thresholds), RED);
+        assert_eq!(color(50.0, thresholds), YELLOW);
+        assert_eq!(color(74.9, thresholds), YELLOW);
+        assert_eq!(color(75.0, thresholds), GREEN);
+        assert_eq!(color(100.0, thresholds), GREEN);
+
+        let thresholds = &ScoreThresholds {
+            warning: 60.0,
+            success: 80.0,
+        };
+        assert_eq!(color(59.9, thresholds), RED);
+        assert_eq!(color(75.0, thresholds), YELLOW);
+        assert_eq!(color(80.0, thresholds), GREEN);
+    }
+
+    #[test]
+    fn render_badge() {
+        let svg = render(87.5, &ScoreThresholds::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">87.5%</text>"));
+        assert!(svg.contains(">mutation score</text>"));
+        assert!(svg.contains(GREEN));
+    }
+
+    #[test]
+    fn write_badge() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write(dir.path(), 10.0, &ScoreThresholds::default())?;
+
+        let svg = std::fs::read_to_string(dir.path().join(BADGE_FILE_NAME))?;
+        assert!(svg.contains(RED));
+        Ok(())
+    }
+}
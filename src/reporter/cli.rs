@@ -1,17 +1,33 @@
 use colored::*;
 
 use super::{
-    rewriter::PathRewriter, MutationOutcome, ReportableMutant, SyntectContext, SyntectFileContext,
+    advice::{self, AdviceContext},
+    impact::ImpactRanking,
+    rewriter::PathRewriter,
+    MutationOutcome, ReportableMutant, RunMetadata, ScoreLevel, ScoreThresholds, SyntectContext,
+    SyntectFileContext,
 };
 use crate::config::ReportConfig;
 use crate::output;
 
 use anyhow::{bail, Result};
+use unicode_width::UnicodeWidthChar;
+
+/// Number of columns a tab character advances to, when printing source lines
+const TAB_WIDTH: usize = 4;
+
+/// Resets the terminal colors after a highlighted source line
+const COLOR_RESET: &str = "\x1b[0m";
 
 pub struct CLIReporter {
     path_rewriter: Option<PathRewriter>,
     highlighter_context: SyntectContext,
     should_colorize: bool,
+    unused_operators: Vec<String>,
+    thresholds: ScoreThresholds,
+    metadata: Option<RunMetadata>,
+    context_lines: usize,
+    advice: AdviceContext,
 }
 
 impl From<MutationOutcome> for ColoredString {
@@ -22,6 +38,8 @@ impl From<MutationOutcome> for ColoredString {
             MutationOutcome::Killed => "KILLED".green(),
             MutationOutcome::Timeout => "TIMEOUT".yellow(),
             MutationOutcome::Error => "ERROR".yellow(),
+            MutationOutcome::NotRun => "NOT_RUN".yellow(),
+            MutationOutcome::InternalError => "INTERNAL_ERROR".yellow(),
         }
     }
 }
@@ -36,13 +54,47 @@ impl CLIReporter {
 
         Ok(CLIReporter {
             path_rewriter,
-            highlighter_context: SyntectContext::new("Solarized (dark)"),
+            highlighter_context: SyntectContext::new(
+                "Solarized (dark)",
+                super::load_syntax_set(config.syntax_dirs())?,
+            ),
             should_colorize: control::ShouldColorize::from_env().should_colorize(),
+            unused_operators: Vec::new(),
+            thresholds: config.thresholds(),
+            metadata: None,
+            context_lines: config.context_lines(),
+            advice: AdviceContext::default(),
         })
     }
 
+    /// Show enabled operators that did not produce any mutants in the summary
+    pub fn with_unused_operators(self, unused_operators: Vec<String>) -> Self {
+        Self {
+            unused_operators,
+            ..self
+        }
+    }
+
+    /// Base the advice at the end of the report on the settings of the run
+    pub fn with_advice(self, advice: AdviceContext) -> Self {
+        Self { advice, ..self }
+    }
+
+    /// Show information about the run in the summary
+    pub fn with_metadata(self, metadata: RunMetadata) -> Self {
+        Self {
+            metadata: Some(metadata),
+            ..self
+        }
+    }
+
     fn summary(&self, executed_mutants: &[ReportableMutant]) {
-        let acc = super::accumulate_outcomes(executed_mutants);
+        let filtered = self
+            .metadata
+            .as_ref()
+            .map(|m| m.filtered_mutants)
+            .unwrap_or(0);
+        let acc = super::accumulate_outcomes(executed_mutants).with_filtered(filtered);
 
         let alive_str: ColoredString = MutationOutcome::Alive.into();
         let skipped_str: ColoredString = MutationOutcome::Skipped.into();
@@ -50,12 +102,117 @@ impl CLIReporter {
         let error_str: ColoredString = MutationOutcome::Error.into();
         let killed_str: ColoredString = MutationOutcome::Killed.into();
 
-        log::info!("{0:15} {1}", alive_str, acc.alive);
-        log::info!("{0:15} {1}", skipped_str, acc.skipped);
+        let likely_equivalent = executed_mutants
+            .iter()
+            .filter(|mutant| mutant.likely_equivalent)
+            .count();
+        if likely_equivalent > 0 {
+            log::info!(
+                "{0:15} {1} ({likely_equivalent} likely equivalent)",
+                alive_str,
+                acc.alive
+            );
+        } else {
+            log::info!("{0:15} {1}", alive_str, acc.alive);
+        }
+        log::info!(
+            "{0:15} {1} (not covered by the tests)",
+            skipped_str,
+            acc.skipped
+        );
         log::info!("{0:15} {1}", timeout_str, acc.timeout);
         log::info!("{0:15} {1}", error_str, acc.error);
         log::info!("{0:15} {1}", killed_str, acc.killed);
-        log::info!("{0:15} {1:.1}%", "Mutation score", acc.mutation_score);
+        if acc.internal_error > 0 {
+            let internal_error_str: ColoredString = MutationOutcome::InternalError.into();
+            log::info!(
+                "{0:15} {1} (not included in the mutation score)",
+                internal_error_str,
+                acc.internal_error
+            );
+        }
+        if acc.not_run > 0 {
+            let not_run_str: ColoredString = MutationOutcome::NotRun.into();
+            log::info!(
+                "{0:15} {1} (not included in the mutation score)",
+                not_run_str,
+                acc.not_run
+            );
+        }
+        log::info!(
+            "{0:15} {1} (excluded by the filter configuration)",
+            "FILTERED",
+            acc.filtered
+        );
+        let score = format!("{:.1}%", acc.mutation_score);
+        let score = match self.thresholds.level(acc.mutation_score) {
+            ScoreLevel::Danger => score.red(),
+            ScoreLevel::Warning => score.yellow(),
+            ScoreLevel::Success => score.green(),
+        };
+        let mut notes = Vec::new();
+        if self.metadata.as_ref().map(|m| m.partial).unwrap_or(false) {
+            notes.push(String::from(
+                "partial, only the selected mutants were executed",
+            ));
+        }
+        if let Some(scope) = self.metadata.as_ref().and_then(|m| m.scope) {
+            notes.push(format!("on {}", scope.summary()));
+        }
+        if notes.is_empty() {
+            log::info!("{0:15} {1}", "Mutation score", score);
+        } else {
+            log::info!(
+                "{0:15} {1} ({2})",
+                "Mutation score",
+                score,
+                notes.join(", ")
+            );
+        }
+
+        let impact = ImpactRanking::new(executed_mutants, self.path_rewriter.as_ref());
+        if !impact.is_empty() {
+            let operators: Vec<String> = impact
+                .operators
+                .iter()
+                .map(|o| {
+                    format!(
+                        "{} ({:.1}%, {}/{})",
+                        o.operator, o.survival_rate, o.alive, o.mutants
+                    )
+                })
+                .collect();
+            log::info!(
+                "{0:15} {1}",
+                "Most surviving operators",
+                operators.join(", ")
+            );
+
+            let files: Vec<String> = impact
+                .files
+                .iter()
+                .map(|f| format!("{} ({})", f.file, f.alive))
+                .collect();
+            if !files.is_empty() {
+                log::info!("{0:15} {1}", "Most surviving files", files.join(", "));
+            }
+        }
+
+        if !self.unused_operators.is_empty() {
+            log::info!(
+                "{0:15} {1}",
+                "Unused operators",
+                self.unused_operators.join(", ")
+            );
+        }
+
+        if let Some(metadata) = &self.metadata {
+            log::info!("{0:15} {1}", "Run", metadata.summary());
+        }
+
+        for advice in advice::advise(executed_mutants, &self.advice, self.path_rewriter.as_ref()) {
+            log::info!("{0:15} {1}", "Advice", advice.message);
+        }
     }
 
     fn enumerate_mutants(&self, executed_mutants: &[ReportableMutant]) -> Result<()> {
@@ -78,6 +235,25 @@ impl CLIReporter {
             }
         }
 
+        // Mutants without line information (e.g. in stripped modules)
+        // are listed by function and code offset
+        let unlocated = super::mutants_without_source_location(executed_mutants);
+        if !unlocated.is_empty() {
+            output::output_string(format!(
+                "Mutants without source location: {}\n\n",
+                unlocated.len()
+            ));
+        }
+        for mutant in unlocated {
+            let outcome = Self::outcome(mutant);
+            output::output_string(format!(
+                "{}: \n{outcome} [{}]: {}\n\n",
+                mutant.offset_location(),
+                mutant.id(),
+                mutant.description()
+            ));
+        }
+
         Ok(())
     }
 
@@ -88,44 +264,174 @@ impl CLIReporter {
         highlighter: &SyntectFileContext,
     ) {
         let mut file_line_col = String::new();
-
-        let mut line_in_file = String::new();
-        let mut column_indicator = String::new();
+        let mut source = format!("{COLOR_RESET}\n\n");
 
         if mutant.location.file.as_deref().is_some() {
             file_line_col += file;
 
             if let Some(line_nr) = mutant.location.line {
                 file_line_col += &format!(":{line_nr}");
-
-                match Self::get_line_from_file(file, line_nr) {
-                    Ok(line) => {
-                        line_in_file = if self.should_colorize {
-                            highlighter.terminal_string(&line).unwrap_or(line)
-                        } else {
-                            line
-                        };
-                    }
-                    Err(e) => {
-                        log::warn!("Could not read from file: {:?}", e);
-                    }
+                let column = mutant.location.column;
+                if let Some(column) = column {
+                    file_line_col += &format!(":{column}");
                 }
 
-                if let Some(column) = mutant.location.column {
-                    file_line_col += &format!(":{column}");
+                source = if self.context_lines > 0 {
+                    self.source_context(file, line_nr, column, highlighter)
+                } else {
+                    self.source_line(file, line_nr, column, highlighter)
+                };
+            }
+        }
 
-                    column_indicator = " ".repeat(column as usize) + "^";
-                }
+        let description = mutant.description();
+        let outcome = Self::outcome(mutant);
+        let id = mutant.id();
+
+        let permalink = match mutant.location.file {
+            Some(_) => mutant
+                .permalink(file)
+                .map(|link| format!("HTML report: {link}\n"))
+                .unwrap_or_default(),
+            None => String::new(),
+        };
+
+        output::output_string(format!(
+            "{file_line_col}: \n{outcome} [{id}]: {description}\n{source}{permalink}"
+        ));
+    }
+
+    /// Line `line_nr` of `file`, followed by the column indicator
+    fn source_line(
+        &self,
+        file: &str,
+        line_nr: u64,
+        column: Option<u64>,
+        highlighter: &SyntectFileContext,
+    ) -> String {
+        let line = match Self::get_line_from_file(file, line_nr) {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Could not read from file: {:?}", e);
+                String::new()
+            }
+        };
+
+        let column_indicator = column
+            .map(|column| Self::column_indicator(&line, column))
+            .unwrap_or_default();
+
+        // Tabs are expanded before highlighting, so that
+        // the printed line matches the column indicator
+        let line = Self::expand_tabs(&line);
+        let line = if self.should_colorize {
+            highlighter.terminal_string(&line).unwrap_or(line)
+        } else {
+            line
+        };
+
+        format!("{line}{COLOR_RESET}\n{column_indicator}\n")
+    }
+
+    /// Lines `line_nr - context_lines` to `line_nr + context_lines` of `file`,
+    /// prefixed by their line numbers, with the mutated line marked by `>`
+    /// and followed by the column indicator
+    fn source_context(
+        &self,
+        file: &str,
+        line_nr: u64,
+        column: Option<u64>,
+        highlighter: &SyntectFileContext,
+    ) -> String {
+        let context = self.context_lines as u64;
+        let first = line_nr.saturating_sub(context).max(1);
+
+        let lines = match Self::get_lines_from_file(file, first, line_nr + context) {
+            Ok(lines) => lines,
+            Err(e) => {
+                log::warn!("Could not read from file: {:?}", e);
+                return "\n".into();
+            }
+        };
+
+        let expanded: Vec<String> = lines.iter().map(|line| Self::expand_tabs(line)).collect();
+        let printed = if self.should_colorize {
+            highlighter
+                .terminal_lines(&expanded)
+                .unwrap_or_else(|_| expanded.clone())
+        } else {
+            expanded
+        };
+
+        let last = first + lines.len().saturating_sub(1) as u64;
+        let width = last.to_string().len();
+        let mut source = String::new();
+
+        for ((nr, line), printed) in (first..).zip(&lines).zip(&printed) {
+            let marker = if nr == line_nr { '>' } else { ' ' };
+            source += &format!("{marker} {nr:>width$} | {printed}{COLOR_RESET}\n");
+
+            if let (true, Some(column)) = (nr == line_nr, column) {
+                let indicator = Self::column_indicator(line, column);
+                source += &format!("  {:width$} | {indicator}\n", "");
             }
         }
 
-        let description = mutant.operator.description();
+        source
+    }
+
+    /// Outcome of a mutant, as shown in the list of mutants
+    fn outcome(mutant: &ReportableMutant) -> String {
         let outcome: ColoredString = mutant.outcome.clone().into();
+        if mutant.likely_equivalent {
+            match mutant.condition_explanation() {
+                Some(condition) => format!("{outcome} (likely equivalent, {condition})"),
+                None => format!("{outcome} (likely equivalent)"),
+            }
+        } else if let Some(condition) = mutant.condition_explanation() {
+            format!("{outcome} ({condition})")
+        } else if let Some(message) = &mutant.internal_error {
+            format!("{outcome} (internal error: {message})")
+        } else if let Some(explanation) = mutant.skip_explanation() {
+            format!("{outcome} ({explanation})")
+        } else {
+            outcome.to_string()
+        }
+    }
 
-        let color_reset = "\x1b[0m";
-        output::output_string(
-            format!("{file_line_col}: \n{outcome}: {description}\n{line_in_file}{color_reset}\n{column_indicator}\n")
-        );
+    /// Replace tabs by spaces, up to the next multiple of `TAB_WIDTH`
+    fn expand_tabs(line: &str) -> String {
+        let mut expanded = String::with_capacity(line.len());
+        let mut width = 0;
+
+        for c in line.chars() {
+            if c == '\t' {
+                let spaces = TAB_WIDTH - width % TAB_WIDTH;
+                expanded.push_str(&" ".repeat(spaces));
+                width += spaces;
+            } else {
+                expanded.push(c);
+                width += c.width().unwrap_or(0);
+            }
+        }
+
+        expanded
+    }
+
+    /// Build a caret pointing to `column` (1-based byte offset, as in the
+    /// debug information) of `line`, once `line` is printed with expanded tabs.
+    fn column_indicator(line: &str, column: u64) -> String {
+        let mut offset = (column.saturating_sub(1) as usize).min(line.len());
+        while !line.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let width: usize = Self::expand_tabs(&line[..offset])
+            .chars()
+            .map(|c| c.width().unwrap_or(0))
+            .sum();
+
+        " ".repeat(width) + "^"
     }
 
     fn get_line_from_file(file: &str, line_nr: u64) -> Result<String> {
@@ -142,6 +448,20 @@ impl CLIReporter {
         bail!("Could not read line {line_nr} from file {file}");
     }
 
+    /// Lines `first` to `last` (inclusive, starting at 1) of `file`.
+    /// Fewer lines are returned if the file ends before `last`.
+    fn get_lines_from_file(file: &str, first: u64, last: u64) -> Result<Vec<String>> {
+        let lines = super::read_lines(file)?
+            .skip(first.saturating_sub(1) as usize)
+            .take((last + 1).saturating_sub(first) as usize)
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        if lines.is_empty() {
+            bail!("Could not read line {first} from file {file}");
+        }
+        Ok(lines)
+    }
+
     pub fn report(&self, executed_mutants: &[ReportableMutant]) -> Result<()> {
         self.enumerate_mutants(executed_mutants)?;
         self.summary(executed_mutants);
@@ -151,10 +471,7 @@ impl CLIReporter {
 
 #[cfg(test)]
 mod tests {
-    use crate::{
-        addressresolver::CodeLocation, config::Config, operator::ops::BinaryOperatorAddToSub,
-    };
-    use wasmut_wasm::elements::Instruction;
+    use crate::{config::Config, reporter::MutantBuilder};
 
     use super::*;
     #[test]
@@ -181,30 +498,150 @@ mod tests {
         .unwrap();
 
         let reporter = CLIReporter::new(config.report()).unwrap();
-        output::clear_output();
-        reporter.report(&executed_mutants).unwrap();
+        let (result, output) = output::capture(|| reporter.report(&executed_mutants));
+        result.unwrap();
 
-        output::get_output()
+        output
     }
 
     #[test]
     fn cli_reporter_single_mutant() {
-        let executed_mutants = vec![ReportableMutant {
-            location: CodeLocation {
-                file: Some("/home/user/Repos/wasmut/testdata/simple_add/simple_add.c".into()),
-                function: Some("add".into()),
-                line: Some(3),
-                column: Some(14),
-            },
-            outcome: MutationOutcome::Timeout,
-            operator: Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap()),
-        }];
+        let executed_mutants = vec![MutantBuilder::new()
+            .with_file("/home/user/Repos/wasmut/testdata/simple_add/simple_add.c")
+            .with_function("add")
+            .with_line(3)
+            .with_column(14)
+            .with_offset(42)
+            .with_outcome(MutationOutcome::Timeout)
+            .build()];
 
         let output = report_to_string(executed_mutants);
 
         assert!(output.contains("testdata/simple_add/simple_add.c:3:14"));
         assert!(output.contains("return"));
         assert!(output.contains("TIMEOUT"));
+        assert!(output.contains("`+` replaced by `-`"));
+        assert!(output.contains("HTML report: simple_add.c-"));
+        assert!(output.contains(".html#mutant-"));
+    }
+
+    #[test]
+    fn cli_reporter_context_lines() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("add.c");
+        std::fs::write(
+            &file,
+            "// Adds two numbers\nint add(int a, int b) {\n\treturn a + b;\n}\n",
+        )?;
+
+        let config = Config::parse("[report]\ncontext_lines = 2")?;
+        let reporter = CLIReporter::new(config.report())?;
+        let executed_mutants = vec![MutantBuilder::new()
+            .with_file(file.to_str().unwrap())
+            .with_function("add")
+            .with_line(3)
+            .with_column(11)
+            .with_offset(42)
+            .with_outcome(MutationOutcome::Alive)
+            .build()];
+
+        let (result, output) = output::capture(|| reporter.report(&executed_mutants));
+        result?;
+        let lines: Vec<&str> = output
+            .lines()
+            .map(|l| l.trim_end_matches(COLOR_RESET))
+            .collect();
+        let first = lines.iter().position(|l| l.contains("Adds")).unwrap();
+
+        // Only one line after the mutated line, since the file ends there
+        assert_eq!(
+            lines[first..first + 5],
+            [
+                "  1 | // Adds two numbers",
+                "  2 | int add(int a, int b) {",
+                "> 3 |     return a + b;",
+                "    |              ^",
+                "  4 | }",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn get_lines_from_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("lines.c");
+        std::fs::write(&file, "a\nb\nc\n")?;
+        let file = file.to_str().unwrap();
+
+        assert_eq!(CLIReporter::get_lines_from_file(file, 2, 5)?, ["b", "c"]);
+        assert_eq!(CLIReporter::get_lines_from_file(file, 1, 1)?, ["a"]);
+        assert!(CLIReporter::get_lines_from_file(file, 4, 6).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn expand_tabs() {
+        assert_eq!(CLIReporter::expand_tabs("\treturn a;"), "    return a;");
+        assert_eq!(CLIReporter::expand_tabs("ab\tc"), "ab  c");
+        assert_eq!(CLIReporter::expand_tabs("abcd\tc"), "abcd    c");
+        assert_eq!(CLIReporter::expand_tabs("äö\tc"), "äö  c");
+    }
+
+    #[test]
+    fn column_indicator() {
+        let indicator = CLIReporter::column_indicator;
+
+        assert_eq!(
+            indicator("    return a + b;", 14),
+            format!("{}^", " ".repeat(13))
+        );
+        assert_eq!(
+            indicator("\treturn a + b;", 11),
+            format!("{}^", " ".repeat(13))
+        );
+        assert_eq!(indicator("\t\tx++;", 4), format!("{}^", " ".repeat(9)));
+
+        // Multi-byte and wide characters
+        assert_eq!(
+            indicator("/* ä */ a + b", 12),
+            format!("{}^", " ".repeat(10))
+        );
+        assert_eq!(
+            indicator("s = \"日本\" + t", 14),
+            format!("{}^", " ".repeat(11))
+        );
+
+        // Columns inside a character or past the end of the line
+        assert_eq!(indicator("ä+", 2), "^");
+        assert_eq!(indicator("a", 10), " ^");
+        assert_eq!(indicator("", 0), "^");
+    }
+
+    #[test]
+    fn cli_reporter_tabbed_source() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("tabs.c");
+        std::fs::write(&file, "int add(int a, int b) {\n\treturn a + b;\n}\n")?;
+
+        let executed_mutants = vec![MutantBuilder::new()
+            .with_file(file.to_str().unwrap())
+            .with_function("add")
+            .with_line(2)
+            .with_column(11)
+            .with_offset(42)
+            .with_outcome(MutationOutcome::Alive)
+            .build()];
+
+        let output = report_to_string(executed_mutants);
+        let lines: Vec<&str> = output.lines().collect();
+        let source_line = lines.iter().position(|l| l.contains("return")).unwrap();
+        let printed = lines[source_line].trim_end_matches("\x1b[0m");
+        let caret = lines[source_line + 1];
+
+        assert!(!printed.contains('\t'));
+        assert_eq!(printed.find('+'), caret.find('^'));
+        Ok(())
     }
 
     // #[test]
@@ -0,0 +1,478 @@
+//! SQLite database storing the results of mutation runs.
+//!
+//! Every run is stored in the `runs` table, its mutants in the `mutants` table.
+//! The `cached_results` table contains the outcomes cached by `resultcache::ResultCache`.
+//! The database is created on first use and shared by all runs. Its schema
+//! is versioned in the `schema_version` table and migrated on open.
+
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::{accumulate_outcomes, MutationOutcome, ReportableMutant, RunMetadata};
+
+/// Migrations of the database schema. `MIGRATIONS[n]` migrates a database
+/// from version `n` to version `n + 1`. Databases created before the schema
+/// was versioned have version 0, but may already contain the tables of the
+/// first migration.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE IF NOT EXISTS runs (
+        id INTEGER PRIMARY KEY,
+        date TEXT NOT NULL,
+        module TEXT NOT NULL,
+        module_hash TEXT NOT NULL,
+        version TEXT NOT NULL,
+        duration INTEGER NOT NULL,
+        threads INTEGER NOT NULL,
+        engine_mode TEXT NOT NULL,
+        compiler TEXT NOT NULL,
+        partial INTEGER NOT NULL,
+        filtered INTEGER NOT NULL,
+        mutation_score REAL NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS mutants (
+        run_id INTEGER NOT NULL REFERENCES runs(id) ON DELETE CASCADE,
+        mutant_id TEXT NOT NULL,
+        operator TEXT NOT NULL,
+        description TEXT NOT NULL,
+        file TEXT,
+        function TEXT,
+        line INTEGER,
+        offset INTEGER NOT NULL,
+        outcome TEXT NOT NULL,
+        execution_cost INTEGER
+    );
+
+    CREATE INDEX IF NOT EXISTS mutants_by_run ON mutants(run_id);
+    CREATE INDEX IF NOT EXISTS mutants_by_id ON mutants(mutant_id);
+    ",
+    "
+    -- Comma-separated names of the kill criteria that killed the mutant
+    ALTER TABLE mutants ADD COLUMN killed_by TEXT;
+    -- Source path as recorded in the debug information, if it differs from `file`
+    ALTER TABLE mutants ADD COLUMN raw_file TEXT;
+    ",
+    "
+    -- Runs without executed mutants have no mutation score. SQLite cannot
+    -- drop a NOT NULL constraint, so the table is recreated.
+    CREATE TABLE runs_new (
+        id INTEGER PRIMARY KEY,
+        date TEXT NOT NULL,
+        module TEXT NOT NULL,
+        module_hash TEXT NOT NULL,
+        version TEXT NOT NULL,
+        duration INTEGER NOT NULL,
+        threads INTEGER NOT NULL,
+        engine_mode TEXT NOT NULL,
+        compiler TEXT NOT NULL,
+        partial INTEGER NOT NULL,
+        filtered INTEGER NOT NULL,
+        mutation_score REAL
+    );
+    INSERT INTO runs_new SELECT id, date, module, module_hash, version, duration,
+        threads, engine_mode, compiler, partial, filtered, mutation_score FROM runs;
+    DROP TABLE runs;
+    ALTER TABLE runs_new RENAME TO runs;
+    ",
+    "
+    -- Outcomes of executed mutants, reused by later runs (`[cache] results`).
+    -- `inputs` identifies the settings of the run, `key` the mutant.
+    CREATE TABLE cached_results (
+        inputs TEXT NOT NULL,
+        key TEXT NOT NULL,
+        result TEXT NOT NULL,
+        PRIMARY KEY (inputs, key)
+    );
+    ",
+];
+
+/// Version of the schema created by this version of wasmut
+const SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+/// Module whose mutants were executed in a run
+pub struct RunModule<'a> {
+    pub path: &'a str,
+    /// Hash of the bytecode, as computed by `Baseline::hash_module`
+    pub hash: &'a str,
+}
+
+/// Results store backed by an SQLite database
+pub struct ResultStore {
+    connection: Connection,
+}
+
+impl ResultStore {
+    /// Open the database at `path`, creating it if it does not exist
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut connection = Connection::open(path)
+            .with_context(|| format!("Could not open result database {path:?}"))?;
+        migrate(&mut connection)
+            .with_context(|| format!("Could not migrate result database {path:?}"))?;
+        // Enabled after migrating, recreating the runs table must not
+        // cascade to the mutants of existing runs
+        connection.pragma_update(None, "foreign_keys", true)?;
+
+        Ok(Self { connection })
+    }
+
+    /// Most recent run of the module at `module`, if there is one
+    pub fn latest_run(&self, module: &str) -> Result<Option<StoredRun>> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT id, date, module_hash, partial, mutation_score FROM runs
+                WHERE module = ?1 ORDER BY id DESC LIMIT 1",
+                [module],
+                |row| {
+                    Ok(StoredRun {
+                        id: row.get(0)?,
+                        date: row.get(1)?,
+                        module_hash: row.get(2)?,
+                        partial: row.get(3)?,
+                        mutation_score: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// IDs of the mutants of run `run_id` with the given outcome, ordered by offset
+    pub fn mutant_ids(&self, run_id: i64, outcome: MutationOutcome) -> Result<Vec<String>> {
+        let mut statement = self.connection.prepare(
+            "SELECT mutant_id FROM mutants WHERE run_id = ?1 AND outcome = ?2 ORDER BY offset",
+        )?;
+        let ids = statement
+            .query_map(params![run_id, outcome_name(outcome)], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(ids)
+    }
+
+    /// Store a run and all of its mutants, returning the ID of the run
+    pub fn record_run(
+        &mut self,
+        module: &RunModule,
+        metadata: &RunMetadata,
+        executed_mutants: &[ReportableMutant],
+    ) -> Result<i64> {
+        let outcomes = accumulate_outcomes(executed_mutants);
+        // Reports show a score of 0 if no mutant was scored, store none instead
+        let score = (outcomes.total > outcomes.not_run + outcomes.internal_error)
+            .then_some(outcomes.mutation_score);
+        let transaction = self.connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO runs (date, module, module_hash, version, duration, threads,
+                engine_mode, compiler, partial, filtered, mutation_score)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                chrono::Local::now().to_rfc3339(),
+                module.path,
+                module.hash,
+                metadata.version,
+                metadata.duration,
+                metadata.threads,
+                metadata.engine_mode,
+                metadata.compiler,
+                metadata.partial,
+                metadata.filtered_mutants,
+                score,
+            ],
+        )?;
+        let run_id = transaction.last_insert_rowid();
+
+        {
+            let mut statement = transaction.prepare(
+                "INSERT INTO mutants (run_id, mutant_id, operator, description, file,
+                    function, line, offset, outcome, execution_cost, killed_by, raw_file)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            )?;
+
+            for mutant in executed_mutants {
+                let killed_by = (!mutant.killed_by.is_empty()).then(|| mutant.killed_by.join(","));
+                statement.execute(params![
+                    run_id,
+                    mutant.id(),
+                    mutant.operator.dyn_name(),
+                    mutant.description(),
+                    mutant.location.file,
+                    mutant.location.function,
+                    mutant.location.line,
+                    mutant.offset,
+                    outcome_name(mutant.outcome.clone()),
+                    mutant.execution_cost,
+                    killed_by,
+                    mutant.location.raw_file,
+                ])?;
+            }
+        }
+
+        transaction
+            .commit()
+            .context("Could not store run in result database")?;
+        Ok(run_id)
+    }
+
+    /// Serialized outcomes cached for `inputs`, by key of the mutant
+    pub fn cached_results(&self, inputs: &str) -> Result<HashMap<String, String>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT key, result FROM cached_results WHERE inputs = ?1")?;
+        let results = statement
+            .query_map([inputs], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<HashMap<String, String>>>()?;
+        Ok(results)
+    }
+
+    /// Cache serialized outcomes for `inputs`, replacing outcomes cached for the same keys
+    pub fn cache_results<'a, I>(&mut self, inputs: &str, results: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let transaction = self.connection.transaction()?;
+        {
+            let mut statement = transaction.prepare(
+                "INSERT OR REPLACE INTO cached_results (inputs, key, result) VALUES (?1, ?2, ?3)",
+            )?;
+            for (key, result) in results {
+                statement.execute(params![inputs, key, result])?;
+            }
+        }
+
+        transaction
+            .commit()
+            .context("Could not store cached outcomes in result database")?;
+        Ok(())
+    }
+}
+
+/// Summary of a stored run
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredRun {
+    pub id: i64,
+    pub date: String,
+    /// Hash of the bytecode, as computed by `Baseline::hash_module`
+    pub module_hash: String,
+    /// Only a part of the mutants was executed, e.g. with `--mutant-ids`
+    pub partial: bool,
+    /// `None` if no executed mutant counts towards the score
+    pub mutation_score: Option<f64>,
+}
+
+/// Name of `outcome` in the `mutants` table
+fn outcome_name(outcome: MutationOutcome) -> String {
+    String::from(outcome).to_lowercase()
+}
+
+/// Bring the schema of the database up to `SCHEMA_VERSION`
+fn migrate(connection: &mut Connection) -> Result<()> {
+    let transaction = connection.transaction()?;
+    transaction
+        .execute_batch("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)")?;
+
+    let version: Option<usize> =
+        transaction.query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+            row.get(0)
+        })?;
+    let version = version.unwrap_or(0);
+
+    if version > SCHEMA_VERSION {
+        bail!(
+            "Database has schema version {version}, but this version of wasmut only \
+            supports up to version {SCHEMA_VERSION}, use a newer version of wasmut"
+        );
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        log::debug!("Migrating result database from schema version {from}");
+        transaction.execute_batch(migration)?;
+    }
+
+    transaction.execute("DELETE FROM schema_version", [])?;
+    transaction.execute(
+        "INSERT INTO schema_version (version) VALUES (?1)",
+        [SCHEMA_VERSION],
+    )?;
+    transaction.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{addressresolver::CodeLocation, reporter::MutantBuilder};
+
+    fn mutant(
+        offset: u64,
+        outcome: MutationOutcome,
+        execution_cost: Option<u64>,
+    ) -> ReportableMutant {
+        MutantBuilder::new()
+            .with_file("main.c")
+            .with_function("main")
+            .with_line(3)
+            .with_offset(offset)
+            .with_outcome(outcome)
+            .with_execution_cost(execution_cost)
+            .build()
+    }
+
+    #[test]
+    fn record_runs() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("wasmut.db");
+        let module = RunModule {
+            path: "test.wasm",
+            hash: "abc",
+        };
+        let metadata = RunMetadata {
+            version: "1.2.3".into(),
+            duration: 42,
+            threads: 4,
+            engine_mode: "meta-mutant".into(),
+            compiler: "Cranelift".into(),
+            ..Default::default()
+        };
+        let mutants = vec![
+            mutant(10, MutationOutcome::Killed, Some(1337)),
+            mutant(20, MutationOutcome::Alive, Some(1000)),
+            mutant(30, MutationOutcome::Timeout, None),
+        ];
+
+        let first = ResultStore::open(&path)?.record_run(&module, &metadata, &mutants)?;
+        let second = ResultStore::open(&path)?.record_run(&module, &metadata, &mutants[..1])?;
+        assert_ne!(first, second);
+
+        let store = ResultStore::open(&path)?;
+        let (runs, score): (i64, f64) = store.connection.query_row(
+            "SELECT COUNT(*), MIN(mutation_score) FROM runs",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(runs, 2);
+        assert!((score - 200.0 / 3.0).abs() < 0.01);
+
+        let (outcome, cost): (String, Option<u64>) = store.connection.query_row(
+            "SELECT outcome, execution_cost FROM mutants WHERE run_id = ?1 AND offset = 30",
+            [first],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(outcome, "timeout");
+        assert_eq!(cost, None);
+
+        let mutants_of_second: i64 = store.connection.query_row(
+            "SELECT COUNT(*) FROM mutants WHERE run_id = ?1",
+            [second],
+            |row| row.get(0),
+        )?;
+        assert_eq!(mutants_of_second, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn query_runs() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut store = ResultStore::open(dir.path().join("wasmut.db"))?;
+        let module = RunModule {
+            path: "test.wasm",
+            hash: "abc",
+        };
+        assert_eq!(store.latest_run("test.wasm")?, None);
+
+        let killed = MutantBuilder::new()
+            .with_location(CodeLocation {
+                file: Some("/src/main.c".into()),
+                raw_file: Some("/build/../src/main.c".into()),
+                ..Default::default()
+            })
+            .with_offset(10)
+            .with_outcome(MutationOutcome::Killed)
+            .with_killed_by(&["exit_code", "output_diff"])
+            .build();
+        let mutants = vec![
+            killed,
+            mutant(20, MutationOutcome::Alive, None),
+            mutant(30, MutationOutcome::Alive, None),
+        ];
+        store.record_run(&module, &RunMetadata::default(), &mutants)?;
+        let latest = store.record_run(&module, &RunMetadata::default(), &mutants[..2])?;
+
+        let run = store.latest_run("test.wasm")?.unwrap();
+        assert_eq!(run.id, latest);
+        assert_eq!(run.module_hash, "abc");
+        assert_eq!(
+            store.mutant_ids(run.id, MutationOutcome::Alive)?,
+            [mutants[1].id()]
+        );
+
+        let (killed_by, raw_file): (String, String) = store.connection.query_row(
+            "SELECT killed_by, raw_file FROM mutants WHERE run_id = ?1 AND offset = 10",
+            [latest],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(killed_by, "exit_code,output_diff");
+        assert_eq!(raw_file, "/build/../src/main.c");
+
+        // Runs without a mutation score store NULL
+        let not_run = store.record_run(
+            &module,
+            &RunMetadata::default(),
+            &[mutant(40, MutationOutcome::NotRun, None)],
+        )?;
+        let run = store.latest_run("test.wasm")?.unwrap();
+        assert_eq!(run.id, not_run);
+        assert_eq!(run.mutation_score, None);
+
+        // Deleting a run deletes its mutants
+        store
+            .connection
+            .execute("DELETE FROM runs WHERE id = ?1", [latest])?;
+        let mutants_of_latest: i64 = store.connection.query_row(
+            "SELECT COUNT(*) FROM mutants WHERE run_id = ?1",
+            [latest],
+            |row| row.get(0),
+        )?;
+        assert_eq!(mutants_of_latest, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_unversioned_database() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("wasmut.db");
+
+        // Databases created before the schema was versioned
+        let connection = Connection::open(&path)?;
+        connection.execute_batch(MIGRATIONS[0])?;
+        connection.execute(
+            "INSERT INTO runs (date, module, module_hash, version, duration, threads,
+                engine_mode, compiler, partial, filtered, mutation_score)
+            VALUES ('', 'test.wasm', 'abc', '0.1.0', 1, 1, '', '', 0, 0, 50.0)",
+            [],
+        )?;
+        drop(connection);
+
+        let store = ResultStore::open(&path)?;
+        let version: usize =
+            store
+                .connection
+                .query_row("SELECT version FROM schema_version", [], |row| row.get(0))?;
+        assert_eq!(version, SCHEMA_VERSION);
+        let run = store.latest_run("test.wasm")?.unwrap();
+        assert_eq!(run.mutation_score, Some(50.0));
+        drop(store);
+
+        // Opening a migrated database again does not change it
+        ResultStore::open(&path)?;
+
+        let connection = Connection::open(&path)?;
+        connection.execute("UPDATE schema_version SET version = version + 1", [])?;
+        drop(connection);
+        let error = ResultStore::open(&path).err().unwrap();
+        assert!(format!("{error:#}").contains("newer version of wasmut"));
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+/// Source-level descriptions of all mutation operators.
+///
+/// The descriptions use the notation of C-like languages (C, C++, Rust, Go),
+/// so that developers can understand a mutant without knowing the names
+/// of WebAssembly instructions.
+const SOURCE_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("binop_sub_to_add", "`-` replaced by `+`"),
+    ("binop_add_to_sub", "`+` replaced by `-`"),
+    ("binop_mul_to_div", "`*` replaced by `/`"),
+    ("binop_div_to_mul", "`/` replaced by `*`"),
+    ("binop_shl_to_shr", "`<<` replaced by `>>`"),
+    ("binop_shr_to_shl", "`>>` replaced by `<<`"),
+    ("binop_rem_to_div", "`%` replaced by `/`"),
+    ("binop_div_to_rem", "`/` replaced by `%`"),
+    ("binop_and_to_or", "`&` replaced by `|`"),
+    ("binop_or_to_and", "`|` replaced by `&`"),
+    ("binop_xor_to_or", "`^` replaced by `|`"),
+    ("binop_or_to_xor", "`|` replaced by `^`"),
+    (
+        "binop_rotl_to_rotr",
+        "Left rotation replaced by right rotation",
+    ),
+    (
+        "binop_rotr_to_rotl",
+        "Right rotation replaced by left rotation",
+    ),
+    ("unop_neg_to_nop", "Negation `-x` replaced by `x`"),
+    ("relop_eq_to_ne", "`==` replaced by `!=`"),
+    ("relop_ne_to_eq", "`!=` replaced by `==`"),
+    ("relop_le_to_gt", "`<=` replaced by `>`"),
+    ("relop_le_to_lt", "`<=` replaced by `<`"),
+    ("relop_lt_to_ge", "`<` replaced by `>=`"),
+    ("relop_lt_to_le", "`<` replaced by `<=`"),
+    ("relop_ge_to_gt", "`>=` replaced by `>`"),
+    ("relop_ge_to_lt", "`>=` replaced by `<`"),
+    ("relop_gt_to_ge", "`>` replaced by `>=`"),
+    ("relop_gt_to_le", "`>` replaced by `<=`"),
+    (
+        "relop_ordered_to_unordered",
+        "Float comparison `a < b` replaced by `!(a >= b)`, which differs for NaN",
+    ),
+    (
+        "relop_unordered_to_ordered",
+        "Float comparison `!(a >= b)` replaced by `a < b`, which differs for NaN",
+    ),
+    (
+        "conv_extend_s_to_u",
+        "Sign extension to 64 bit replaced by zero extension",
+    ),
+    (
+        "conv_extend_u_to_s",
+        "Zero extension to 64 bit replaced by sign extension",
+    ),
+    (
+        "conv_wrap_to_checked",
+        "Truncation to 32 bit replaced by a conversion that traps on overflow",
+    ),
+    (
+        "memory_grow_force_result",
+        "Memory growth replaced by failure (`-1`) or by success without growing",
+    ),
+    (
+        "br_table_rotate_targets",
+        "Cases of a `switch` jump to the target of the next case",
+    ),
+    (
+        "br_table_swap_default",
+        "First case of a `switch` swapped with the default case",
+    ),
+    ("const_replace_zero", "Constant `0` replaced by `42`"),
+    ("const_replace_nonzero", "Non-zero constant replaced by `0`"),
+    ("call_remove_void_call", "Function call removed"),
+    (
+        "call_remove_scalar_call",
+        "Function call removed, result replaced by `42`",
+    ),
+    (
+        "table_entry_replace",
+        "Function pointer call dispatched to another function of the same type",
+    ),
+];
+
+/// Return the source-level description of an operator, if there is one
+pub fn source_description(operator_name: &str) -> Option<&'static str> {
+    SOURCE_DESCRIPTIONS
+        .iter()
+        .find(|(name, _)| *name == operator_name)
+        .map(|(_, description)| *description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::OperatorRegistry;
+
+    #[test]
+    fn all_operators_have_descriptions() {
+        let registry = OperatorRegistry::new(&[""]).unwrap();
+
+        for name in registry.enabled_operators() {
+            assert!(
+                source_description(name).is_some(),
+                "{name} has no source-level description"
+            );
+        }
+    }
+
+    #[test]
+    fn lookup_description() {
+        assert_eq!(
+            source_description("relop_lt_to_ge"),
+            Some("`<` replaced by `>=`")
+        );
+        assert_eq!(source_description("foo"), None);
+    }
+}
@@ -0,0 +1,244 @@
+//! Detection of source files that the debug information contains under
+//! different paths, e.g. once with an absolute and once with a relative path.
+//!
+//! Two paths refer to the same file, if they are identical after removing `.`
+//! and `..` components, or if one of them is relative and matches the trailing
+//! components of the other. If both files can be read, their contents must be
+//! identical as well, otherwise files sharing a suffix by chance (e.g. two
+//! `src/main.c` of different libraries) would be merged.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Component, Path},
+};
+
+/// Path of a source file, split into normalized components
+struct SourcePath<'p> {
+    name: &'p str,
+    absolute: bool,
+    components: Vec<String>,
+}
+
+impl<'p> SourcePath<'p> {
+    fn new(name: &'p str) -> Self {
+        let mut absolute = false;
+        let mut components: Vec<String> = Vec::new();
+        for component in Path::new(name).components() {
+            match component {
+                Component::Prefix(_) | Component::RootDir => absolute = true,
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    // Leading `..` of relative paths cannot be resolved lexically
+                    if matches!(components.last(), Some(last) if last != "..") {
+                        components.pop();
+                    } else {
+                        components.push("..".into());
+                    }
+                }
+                Component::Normal(part) => components.push(part.to_string_lossy().into()),
+            }
+        }
+
+        Self {
+            name,
+            absolute,
+            components,
+        }
+    }
+
+    /// True if `self` and `other` may name the same file, judging by the paths alone
+    fn matches(&self, other: &SourcePath) -> bool {
+        let (short, long) = if self.components.len() <= other.components.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        if short.components.len() == long.components.len() {
+            return short.absolute == long.absolute && short.components == long.components;
+        }
+
+        !short.absolute
+            && !short.components.is_empty()
+            && !short.components.contains(&"..".to_string())
+            && long.components.ends_with(&short.components)
+    }
+}
+
+/// MD5 hashes of source files, read at most once. `None` if a file cannot be read.
+#[derive(Default)]
+struct ContentHashes(HashMap<String, Option<md5::Digest>>);
+
+impl ContentHashes {
+    fn get(&mut self, file: &str) -> Option<md5::Digest> {
+        *self
+            .0
+            .entry(file.into())
+            .or_insert_with(|| std::fs::read(file).ok().map(md5::compute))
+    }
+
+    /// True if `a` and `b` have the same contents, or if any of them cannot be read
+    fn compatible(&mut self, a: &str, b: &str) -> bool {
+        match (self.get(a), self.get(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+/// Groups of files in `files` that refer to the same source file. The first file of
+/// every group is the one to show in reports: a readable one, preferring short paths.
+/// Files are only grouped if all candidates of both of them refer to the same file,
+/// so that e.g. `main.c` is not merged if both `/a/main.c` and `/b/main.c` exist.
+pub(super) fn duplicate_files<'f>(files: &[&'f str]) -> Vec<Vec<&'f str>> {
+    let paths: Vec<SourcePath> = files.iter().map(|file| SourcePath::new(file)).collect();
+    let mut hashes = ContentHashes::default();
+    let mut same_file =
+        |a: &SourcePath, b: &SourcePath| a.matches(b) && hashes.compatible(a.name, b.name);
+
+    // Union-find over the indices of `paths`
+    let mut parents: Vec<usize> = (0..paths.len()).collect();
+    fn root(parents: &mut [usize], mut index: usize) -> usize {
+        while parents[index] != index {
+            parents[index] = parents[parents[index]];
+            index = parents[index];
+        }
+        index
+    }
+
+    // Candidates of every file, `None` if they do not all refer to the same file
+    let candidates: Vec<Option<Vec<usize>>> = paths
+        .iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let candidates: Vec<usize> = (0..paths.len())
+                .filter(|other| *other != index && same_file(path, &paths[*other]))
+                .collect();
+            let consistent = candidates.iter().enumerate().all(|(n, a)| {
+                candidates[n + 1..]
+                    .iter()
+                    .all(|b| same_file(&paths[*a], &paths[*b]))
+            });
+            consistent.then_some(candidates)
+        })
+        .collect();
+
+    for (index, others) in candidates.iter().enumerate() {
+        for other in others.iter().flatten() {
+            if candidates[*other].is_some() {
+                let (a, b) = (root(&mut parents, index), root(&mut parents, *other));
+                parents[a] = b;
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+    for (index, path) in paths.iter().enumerate() {
+        let group = root(&mut parents, index);
+        groups.entry(group).or_default().push(path.name);
+    }
+
+    groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort_by_key(|file| {
+                (
+                    hashes.get(file).is_none(),
+                    SourcePath::new(file).components.len(),
+                    *file,
+                )
+            });
+            group
+        })
+        .collect()
+}
+
+/// Merge the entries of `files` that refer to the same source file into the entry of
+/// the file shown in reports, see `duplicate_files`
+pub(super) fn merge_duplicate_files<T>(files: &mut BTreeMap<String, BTreeMap<u64, Vec<T>>>) {
+    let names: Vec<&str> = files.keys().map(String::as_str).collect();
+    let groups: Vec<Vec<String>> = duplicate_files(&names)
+        .into_iter()
+        .map(|group| group.into_iter().map(String::from).collect())
+        .collect();
+
+    for group in groups {
+        log::debug!(
+            "Source files {} are the same file, reporting them as {}",
+            group.join(", "),
+            group[0]
+        );
+
+        let mut merged = BTreeMap::new();
+        for file in &group {
+            for (line, mut entries) in files.remove(file).unwrap_or_default() {
+                merged
+                    .entry(line)
+                    .or_insert_with(Vec::new)
+                    .append(&mut entries);
+            }
+        }
+        files.insert(group[0].clone(), merged);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_file_by_path() {
+        let matches = |a, b| SourcePath::new(a).matches(&SourcePath::new(b));
+
+        assert!(matches("src/main.c", "/home/user/project/src/main.c"));
+        assert!(matches("./src/main.c", "src/main.c"));
+        assert!(matches("src/../src/main.c", "/project/src/main.c"));
+        assert!(!matches("/a/src/main.c", "/b/src/main.c"));
+        assert!(!matches("/src/main.c", "/project/src/main.c"));
+        assert!(!matches("c/main.c", "/project/src/main.c"));
+        assert!(!matches("../main.c", "/project/main.c"));
+    }
+
+    #[test]
+    fn merge_files_with_different_prefixes() {
+        let mut files: BTreeMap<String, BTreeMap<u64, Vec<u64>>> = BTreeMap::new();
+        files.insert("/nonexistent/src/main.c".into(), [(1, vec![10])].into());
+        files.insert("src/main.c".into(), [(1, vec![20]), (2, vec![30])].into());
+        files.insert("/nonexistent/lib.c".into(), [(5, vec![40])].into());
+
+        merge_duplicate_files(&mut files);
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["/nonexistent/lib.c", "src/main.c"]
+        );
+        // Entries of the file shown in reports come first
+        assert_eq!(files["src/main.c"][&1], [20, 10]);
+        assert_eq!(files["src/main.c"][&2], [30]);
+    }
+
+    #[test]
+    fn ambiguous_files_are_kept() {
+        let files = ["/a/src/main.c", "/b/src/main.c", "src/main.c"];
+        assert!(duplicate_files(&files).is_empty());
+    }
+
+    #[test]
+    fn compare_contents_of_readable_files() -> std::io::Result<()> {
+        // Tests are executed in the crate root, so `src/main.rs` is readable
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("src"))?;
+        let copy = dir.path().join("src/main.rs");
+        let copy = copy.to_str().unwrap();
+
+        std::fs::write(copy, "fn main() {}")?;
+        assert!(duplicate_files(&["src/main.rs", copy]).is_empty());
+
+        std::fs::copy("src/main.rs", copy)?;
+        assert_eq!(
+            duplicate_files(&[copy, "src/main.rs"]),
+            [["src/main.rs", copy]]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+
+use super::{rewriter::PathRewriter, MutationOutcome, ReportableMutant};
+use crate::{config::ReportConfig, output};
+
+/// Emits GitHub Actions workflow commands for surviving mutants,
+/// which are shown as inline annotations on pull requests.
+///
+/// GitHub expects file paths relative to the repository root,
+/// use `path_rewrite` to convert the paths from the debug information.
+pub struct GitHubReporter {
+    path_rewriter: Option<PathRewriter>,
+}
+
+impl GitHubReporter {
+    pub fn new(config: &ReportConfig) -> Result<Self> {
+        let path_rewriter = if let Some((regex, replacement)) = &config.path_rewrite() {
+            Some(PathRewriter::new(regex, replacement)?)
+        } else {
+            None
+        };
+
+        Ok(Self { path_rewriter })
+    }
+
+    pub fn report(&self, executed_mutants: &[ReportableMutant]) -> Result<()> {
+        output::output_string(self.report_to_string(executed_mutants));
+        Ok(())
+    }
+
+    /// Render workflow commands for all surviving mutants and a summary notice
+    fn report_to_string(&self, executed_mutants: &[ReportableMutant]) -> String {
+        let mut commands = String::new();
+
+        for mutant in executed_mutants
+            .iter()
+            .filter(|mutant| mutant.outcome == MutationOutcome::Alive)
+        {
+            commands.push_str(&self.annotation(mutant));
+            commands.push('\n');
+        }
+
+        let acc = super::accumulate_outcomes(executed_mutants);
+        let mut summary = format!(
+            "{:.1}% ({} of {} mutants killed, {} alive",
+            acc.mutation_score,
+            acc.killed,
+            acc.total - acc.not_run - acc.internal_error,
+            acc.alive
+        );
+        if acc.not_run > 0 {
+            summary.push_str(&format!(", {} not run", acc.not_run));
+        }
+        if acc.internal_error > 0 {
+            summary.push_str(&format!(", {} internal errors", acc.internal_error));
+        }
+        summary.push(')');
+        commands.push_str(&format!(
+            "::notice title=Mutation score::{}\n",
+            escape_data(&summary)
+        ));
+
+        commands
+    }
+
+    /// Render the `::warning` command for a single mutant
+    fn annotation(&self, mutant: &ReportableMutant) -> String {
+        let mut properties = Vec::new();
+
+        if let Some(file) = &mutant.location.file {
+            let file = match &self.path_rewriter {
+                Some(path_rewriter) => path_rewriter.rewrite(file),
+                None => file.clone(),
+            };
+            properties.push(format!("file={}", escape_property(&file)));
+
+            if let Some(line) = mutant.location.line {
+                properties.push(format!("line={line}"));
+
+                if let Some(column) = mutant.location.column {
+                    properties.push(format!("col={column}"));
+                }
+            }
+        }
+        properties.push(format!(
+            "title={}",
+            escape_property(&format!("Surviving mutant {}", mutant.id()))
+        ));
+
+        format!(
+            "::warning {}::{}",
+            properties.join(","),
+            escape_data(&format!("Surviving mutant: {}", mutant.description()))
+        )
+    }
+}
+
+/// Escape the message of a workflow command
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value of a workflow command
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporter::MutantBuilder;
+
+    /// Surviving mutant at line 3, column 14 of `add`, without a file
+    fn in_add() -> MutantBuilder {
+        MutantBuilder::new()
+            .with_function("add")
+            .with_line(3)
+            .with_column(14)
+            .with_offset(42)
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape_data("50%\nok"), "50%25%0Aok");
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+    }
+
+    #[test]
+    fn github_annotations() {
+        let config = ReportConfig::default();
+        let reporter = GitHubReporter::new(&config).unwrap();
+
+        let output = reporter.report_to_string(&[
+            in_add().with_file("src/add.c").build(),
+            in_add()
+                .with_file("src/add.c")
+                .with_outcome(MutationOutcome::Killed)
+                .build(),
+            in_add().build(),
+        ]);
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(
+            lines[0].starts_with("::warning file=src/add.c,line=3,col=14,title=Surviving mutant ")
+        );
+        assert!(lines[0].contains("::Surviving mutant: `+` replaced by `-`"));
+        assert!(lines[1].starts_with("::warning title=Surviving mutant "));
+        assert!(lines[2].starts_with("::notice title=Mutation score::33.3%25"));
+    }
+
+    #[test]
+    fn not_run_mutants_in_summary() {
+        let config = ReportConfig::default();
+        let reporter = GitHubReporter::new(&config).unwrap();
+
+        let output = reporter.report_to_string(&[
+            in_add()
+                .with_file("src/add.c")
+                .with_outcome(MutationOutcome::Killed)
+                .build(),
+            in_add()
+                .with_file("src/add.c")
+                .with_outcome(MutationOutcome::NotRun)
+                .build(),
+        ]);
+
+        assert_eq!(
+            output,
+            "::notice title=Mutation score::100.0%25 (1 of 1 mutants killed, 0 alive, 1 not run)\n"
+        );
+    }
+}
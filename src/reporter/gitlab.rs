@@ -0,0 +1,141 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{rewriter::PathRewriter, MutationOutcome, ReportableMutant};
+use crate::{config::ReportConfig, output};
+
+/// Name of the report file, as expected by GitLab
+pub const REPORT_FILE_NAME: &str = "gl-code-quality-report.json";
+
+#[derive(Serialize)]
+pub struct CodeQualityLines {
+    pub begin: u64,
+}
+
+#[derive(Serialize)]
+pub struct CodeQualityLocation {
+    pub path: String,
+    pub lines: CodeQualityLines,
+}
+
+/// A single issue in the GitLab Code Quality format
+#[derive(Serialize)]
+pub struct CodeQualityIssue {
+    pub description: String,
+    pub check_name: String,
+    pub fingerprint: String,
+    pub severity: String,
+    pub location: CodeQualityLocation,
+}
+
+/// Emits surviving mutants in the GitLab Code Quality format,
+/// so that they are shown in the merge request widget.
+pub struct GitLabReporter {
+    path_rewriter: Option<PathRewriter>,
+    file: String,
+}
+
+impl GitLabReporter {
+    pub fn new(config: &ReportConfig, wasmfile: &str) -> Result<Self> {
+        let path_rewriter = if let Some((regex, replacement)) = &config.path_rewrite() {
+            Some(PathRewriter::new(regex, replacement)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            path_rewriter,
+            file: wasmfile.into(),
+        })
+    }
+
+    /// Create issues for all surviving mutants
+    pub fn create_report(&self, executed_mutants: &[ReportableMutant]) -> Vec<CodeQualityIssue> {
+        executed_mutants
+            .iter()
+            .filter(|mutant| mutant.outcome == MutationOutcome::Alive)
+            .map(|mutant| self.issue(mutant))
+            .collect()
+    }
+
+    /// Serialize the report to a JSON string
+    pub fn report_to_string(&self, executed_mutants: &[ReportableMutant]) -> Result<String> {
+        let report = self.create_report(executed_mutants);
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+
+    pub fn report(&self, executed_mutants: &[ReportableMutant]) -> Result<()> {
+        let s = self.report_to_string(executed_mutants)?;
+
+        output::output_string(s);
+
+        Ok(())
+    }
+
+    fn issue(&self, mutant: &ReportableMutant) -> CodeQualityIssue {
+        // Mutants without debug information are attributed to the module itself
+        let path = match (&mutant.location.file, &self.path_rewriter) {
+            (Some(file), Some(path_rewriter)) => path_rewriter.rewrite(file),
+            (Some(file), None) => file.clone(),
+            (None, _) => self.file.clone(),
+        };
+
+        let id = mutant.id();
+        let fingerprint = format!("{:?}", md5::compute(format!("{path}:{id}")));
+
+        CodeQualityIssue {
+            description: format!("Surviving mutant: {}", mutant.description()),
+            check_name: format!("wasmut/{}", mutant.operator.dyn_name()),
+            fingerprint,
+            severity: "minor".into(),
+            location: CodeQualityLocation {
+                path,
+                lines: CodeQualityLines {
+                    begin: mutant.location.line.unwrap_or(1),
+                },
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporter::MutantBuilder;
+
+    /// Surviving mutant in `add`, without a source location
+    fn in_add() -> MutantBuilder {
+        MutantBuilder::new().with_function("add").with_offset(42)
+    }
+
+    #[test]
+    fn gitlab_code_quality_report() -> Result<()> {
+        let reporter = GitLabReporter::new(&ReportConfig::default(), "test.wasm")?;
+
+        let report = reporter.create_report(&[
+            in_add().with_file("src/add.c").with_line(3).build(),
+            in_add()
+                .with_file("src/add.c")
+                .with_line(3)
+                .with_outcome(MutationOutcome::Killed)
+                .build(),
+            in_add().build(),
+        ]);
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].location.path, "src/add.c");
+        assert_eq!(report[0].location.lines.begin, 3);
+        assert_eq!(report[0].check_name, "wasmut/binop_add_to_sub");
+        assert!(report[0].description.contains("`+` replaced by `-`"));
+        assert_eq!(report[1].location.path, "test.wasm");
+        assert_eq!(report[1].location.lines.begin, 1);
+        assert_ne!(report[0].fingerprint, report[1].fingerprint);
+
+        let json: serde_json::Value = serde_json::from_str(
+            &reporter.report_to_string(&[in_add().with_file("src/add.c").with_line(3).build()])?,
+        )?;
+        assert_eq!(json[0]["severity"], "minor");
+        assert_eq!(json[0]["location"]["lines"]["begin"], 3);
+        Ok(())
+    }
+}
@@ -2,7 +2,10 @@ use std::{collections::BTreeMap, fs::File, io::BufWriter, path::Path};
 
 use anyhow::{Context, Result};
 use chrono::prelude::*;
-use handlebars::{handlebars_helper, Handlebars};
+use handlebars::{
+    handlebars_helper, Context as HandlebarsContext, Handlebars, Helper, HelperDef, RenderContext,
+    RenderError, ScopedJson,
+};
 
 use serde::Serialize;
 use syntect::{
@@ -10,13 +13,22 @@ use syntect::{
     parsing::SyntaxSet,
 };
 
-use crate::{config::ReportConfig, templates};
+use crate::{config::ReportConfig, operator::catalog, templates, warnings};
 
 use super::{
-    rewriter::PathRewriter, AccumulatedOutcomes, LineNumberMutantMap, MutationOutcome,
-    ReportableMutant,
+    advice::{self, AdviceContext},
+    impact::ImpactRanking,
+    json::JSONReporter,
+    redact,
+    rewriter::PathRewriter,
+    slowest::SlowestRanking,
+    AccumulatedOutcomes, LineNumberMutantMap, MutationOutcome, ReportableMutant, RunMetadata,
+    ScoreLevel, ScoreThresholds, SourceFileInfo,
 };
 
+/// Directory within the report, containing execution traces of surviving mutants
+const TRACE_DIRECTORY: &str = "traces";
+
 impl From<MutationOutcome> for String {
     /// Convert `MutationOutcome` to `String`
     fn from(m: MutationOutcome) -> Self {
@@ -26,6 +38,8 @@ impl From<MutationOutcome> for String {
             MutationOutcome::Killed => "KILLED".into(),
             MutationOutcome::Timeout => "TIMEOUT".into(),
             MutationOutcome::Error => "ERROR".into(),
+            MutationOutcome::NotRun => "NOT_RUN".into(),
+            MutationOutcome::InternalError => "INTERNAL_ERROR".into(),
         }
     }
 }
@@ -40,12 +54,15 @@ enum BulmaClass {
 
 impl BulmaClass {
     /// Deterine `BulmaClass` from mutation score
-    fn from_mutation_score(score: f32) -> Self {
-        match score {
-            x if (0.0..50.0).contains(&x) => BulmaClass::Danger,
-            x if (50.0..75.0).contains(&x) => BulmaClass::Warning,
-            x if (75.0..=100.0).contains(&x) => BulmaClass::Success,
-            _ => BulmaClass::Invalid,
+    fn from_mutation_score(score: f32, thresholds: &ScoreThresholds) -> Self {
+        if !(0.0..=100.0).contains(&score) {
+            return BulmaClass::Invalid;
+        }
+
+        match thresholds.level(score) {
+            ScoreLevel::Danger => BulmaClass::Danger,
+            ScoreLevel::Warning => BulmaClass::Warning,
+            ScoreLevel::Success => BulmaClass::Success,
         }
     }
 }
@@ -71,6 +88,9 @@ impl From<AccumulatedOutcomes> for BulmaClass {
         if a.alive > 0 {
             // If any mutant is alive, show red
             BulmaClass::Danger
+        } else if total == 0 && a.not_run + a.internal_error > 0 {
+            // Nothing is known about mutants that were not run
+            BulmaClass::Invalid
         } else if a.killed == total {
             // If all mutants were killed, green
             BulmaClass::Success
@@ -85,10 +105,26 @@ pub struct HTMLReporter<'a> {
     output_directory: &'a Path,
     syntax_set: SyntaxSet,
     path_rewriter: Option<PathRewriter>,
+    json_reporter: JSONReporter,
+    /// Mutation score thresholds, used for coloring and the badge
+    thresholds: ScoreThresholds,
+    /// Whether an SVG badge should be written
+    badge: bool,
+    /// Information about the run, shown on the index page
+    metadata: RunMetadata,
+    /// Paths were redacted, source files are not rendered
+    redacted: bool,
+    /// Settings of the run, used for the advice on the index page
+    advice: AdviceContext,
 }
 
 impl<'a> HTMLReporter<'a> {
-    pub fn new(config: &ReportConfig, output_directory: &'a Path) -> Result<Self> {
+    pub fn new(
+        config: &ReportConfig,
+        output_directory: &'a Path,
+        wasmfile: &str,
+        metadata: &RunMetadata,
+    ) -> Result<Self> {
         let path_rewriter = if let Some((regex, replacement)) = &config.path_rewrite() {
             Some(PathRewriter::new(regex, replacement)?)
         } else {
@@ -97,8 +133,14 @@ impl<'a> HTMLReporter<'a> {
 
         Ok(Self {
             output_directory,
-            syntax_set: SyntaxSet::load_defaults_newlines(),
+            syntax_set: super::load_syntax_set(config.syntax_dirs())?,
             path_rewriter,
+            json_reporter: JSONReporter::new(config, wasmfile, metadata)?,
+            metadata: metadata.clone(),
+            thresholds: config.thresholds(),
+            badge: config.badge(),
+            redacted: false,
+            advice: AdviceContext::default(),
         })
     }
 
@@ -108,7 +150,7 @@ impl<'a> HTMLReporter<'a> {
         self.create_static_files()?;
 
         // Initialize template engine
-        let template_engine = create_template_engine();
+        let template_engine = create_template_engine(self.thresholds);
 
         // Create general report info (program version, date, etc.)
         let report_info = ReportInfo::new();
@@ -117,14 +159,31 @@ impl<'a> HTMLReporter<'a> {
         let source_files =
             self.render_source_files(executed_mutants, &report_info, &template_engine)?;
 
+        // Write execution traces of surviving mutants
+        self.write_traces(executed_mutants)?;
+
+        // Write report.json, so that external tools can consume the report directory
+        let json_report = self.json_reporter.report_to_string(executed_mutants)?;
+        std::fs::write(self.output_directory.join("report.json"), &json_report)?;
+
+        // Write mutation score badge
+        if self.badge {
+            let score = super::accumulate_outcomes(executed_mutants).mutation_score;
+            super::badge::write(self.output_directory, score, &self.thresholds)?;
+        }
+
         // Render index.html
         self.render_index(
             executed_mutants,
             &source_files,
             &report_info,
+            &json_report,
             &template_engine,
         )?;
 
+        // Render operators.html, the legend of the operator names
+        self.render_operators(&report_info, &template_engine)?;
+
         Ok(())
     }
 
@@ -164,6 +223,7 @@ impl<'a> HTMLReporter<'a> {
                 &line,
                 mutants_in_given_line,
                 html_generator,
+                self.path_rewriter.as_ref(),
             )?)
         }
 
@@ -172,19 +232,52 @@ impl<'a> HTMLReporter<'a> {
 
     /// Create all static files needed for our HTML report
     fn create_static_files(&self) -> Result<()> {
-        let ts = syntect::highlighting::ThemeSet::load_defaults();
-        let theme = ts.themes["InspiredGitHub"].clone();
-        let css = syntect::html::css_for_theme_with_class_style(&theme, ClassStyle::Spaced)?;
-        std::fs::write(self.output_directory.join("syntax.css"), css)?;
-        std::fs::write(self.output_directory.join("style.css"), templates::CSS)?;
-        std::fs::write(
-            self.output_directory.join("bulma.min.css"),
-            templates::BULMA,
-        )?;
-        std::fs::write(
-            self.output_directory.join("BULMA-LICENSE"),
-            templates::BULMA_LICENSE,
-        )?;
+        write_static_files(self.output_directory)
+    }
+
+    /// Include enabled operators that did not produce any mutants in the report
+    pub fn with_unused_operators(self, unused_operators: Vec<String>) -> Self {
+        Self {
+            json_reporter: self.json_reporter.with_unused_operators(unused_operators),
+            ..self
+        }
+    }
+
+    /// Base the advice on the index page on the settings of the run
+    pub fn with_advice(self, advice: AdviceContext) -> Self {
+        Self { advice, ..self }
+    }
+
+    /// Report mutants whose paths were redacted with `redact::redact_mutants`.
+    /// Source files are not rendered, and the messages of warnings are left out.
+    pub fn with_redacted_paths(self) -> Self {
+        Self {
+            json_reporter: self.json_reporter.with_redacted_paths(),
+            redacted: true,
+            ..self
+        }
+    }
+
+    /// Write execution traces of all traced mutants into the `traces` directory
+    fn write_traces(&self, executed_mutants: &[ReportableMutant]) -> Result<()> {
+        let traced: Vec<_> = executed_mutants
+            .iter()
+            .filter_map(|mutant| mutant.trace.as_ref().map(|trace| (mutant, trace)))
+            .collect();
+
+        if traced.is_empty() {
+            return Ok(());
+        }
+
+        let trace_directory = self.output_directory.join(TRACE_DIRECTORY);
+        std::fs::create_dir_all(&trace_directory)?;
+
+        for (mutant, trace) in traced {
+            std::fs::write(
+                trace_directory.join(trace.file_name()),
+                trace.render(mutant),
+            )?;
+        }
 
         Ok(())
     }
@@ -208,6 +301,20 @@ impl<'a> HTMLReporter<'a> {
         for (file, line_number_map) in file_mapping {
             // line_number_map is map line_nr -> Vec<ExecutedMutants>
 
+            let accumulated_outcomes = super::accumulate_outcomes_for_file(&line_number_map);
+            if self.redacted {
+                source_files.push(SourceFile {
+                    name: file,
+                    link: None,
+                    stale: false,
+                    accumulated_outcomes,
+                });
+                continue;
+            }
+
+            let max_line = line_number_map.keys().last().copied().unwrap_or(0);
+            let source_info = SourceFileInfo::collect(&file, max_line);
+
             let link = match self.generate_source_lines(&file, &line_number_map) {
                 Ok(lines) => {
                     let html_filename = generate_html_filename(&file)?;
@@ -218,6 +325,7 @@ impl<'a> HTMLReporter<'a> {
                     let data = BTreeMap::from([
                         ("filename", handlebars::to_json(&file)),
                         ("lines", handlebars::to_json(lines)),
+                        ("source_info", handlebars::to_json(&source_info)),
                         ("report_info", handlebars::to_json(report_info)),
                     ]);
 
@@ -231,12 +339,11 @@ impl<'a> HTMLReporter<'a> {
                 }
             };
 
-            let accumulated_outcomes = super::accumulate_outcomes_for_file(&line_number_map);
-
             source_files.push(SourceFile {
                 name: file,
                 link,
-                accumulated_outcomes: accumulated_outcomes.clone(),
+                stale: source_info.stale,
+                accumulated_outcomes,
             });
         }
         Ok(source_files)
@@ -248,14 +355,43 @@ impl<'a> HTMLReporter<'a> {
         executed_mutants: &[ReportableMutant],
         source_files: &[SourceFile],
         report_info: &ReportInfo,
+        json_report: &str,
         template_engine: &Handlebars,
     ) -> Result<()> {
-        let stats = super::accumulate_outcomes(executed_mutants);
+        let stats = super::accumulate_outcomes(executed_mutants)
+            .with_filtered(self.metadata.filtered_mutants);
+        let warnings = if self.redacted {
+            redact::redact_warnings(warnings::collected())
+        } else {
+            warnings::collected()
+        };
+        let impact = ImpactRanking::new(executed_mutants, self.path_rewriter.as_ref());
+        let slowest = SlowestRanking::new(executed_mutants, self.path_rewriter.as_ref());
+        let advice = advice::advise(executed_mutants, &self.advice, self.path_rewriter.as_ref());
+        let unlocated_mutants: Vec<UnlocatedMutant> =
+            super::mutants_without_source_location(executed_mutants)
+                .into_iter()
+                .map(UnlocatedMutant::from)
+                .collect();
         let data = BTreeMap::from([
+            ("unlocated_mutants", handlebars::to_json(unlocated_mutants)),
             ("source_files", handlebars::to_json(source_files)),
+            ("impact", handlebars::to_json(impact)),
+            ("slowest", handlebars::to_json(slowest)),
+            ("metadata", handlebars::to_json(&self.metadata)),
+            (
+                "scope",
+                handlebars::to_json(self.metadata.scope.map(|scope| scope.summary())),
+            ),
             ("file", handlebars::to_json::<Option<String>>(None)),
             ("report_info", handlebars::to_json(report_info)),
             ("stats", handlebars::to_json(stats)),
+            ("warnings", handlebars::to_json(warnings)),
+            ("advice", handlebars::to_json(advice)),
+            (
+                "report_data",
+                handlebars::to_json(embeddable_json(json_report)),
+            ),
         ]);
         let writer = BufWriter::new(File::create(self.output_directory.join("index.html"))?);
         template_engine
@@ -263,11 +399,47 @@ impl<'a> HTMLReporter<'a> {
             .unwrap();
         Ok(())
     }
+
+    /// Render the legend of all operators.
+    fn render_operators(
+        &self,
+        report_info: &ReportInfo,
+        template_engine: &Handlebars,
+    ) -> Result<()> {
+        let data = BTreeMap::from([
+            ("operators", handlebars::to_json(catalog::catalog())),
+            ("file", handlebars::to_json("Operators")),
+            ("report_info", handlebars::to_json(report_info)),
+        ]);
+        let writer = BufWriter::new(File::create(self.output_directory.join("operators.html"))?);
+        template_engine
+            .render_to_write("operators", &data, writer)
+            .unwrap();
+        Ok(())
+    }
+}
+
+/// Write all static files (stylesheets, licenses) needed by the HTML templates
+pub(super) fn write_static_files(directory: &Path) -> Result<()> {
+    let ts = syntect::highlighting::ThemeSet::load_defaults();
+    let theme = ts.themes["InspiredGitHub"].clone();
+    let css = syntect::html::css_for_theme_with_class_style(&theme, ClassStyle::Spaced)?;
+    std::fs::write(directory.join("syntax.css"), css)?;
+    std::fs::write(directory.join("style.css"), templates::CSS)?;
+    std::fs::write(directory.join("bulma.min.css"), templates::BULMA)?;
+    std::fs::write(directory.join("BULMA-LICENSE"), templates::BULMA_LICENSE)?;
+
+    Ok(())
+}
+
+/// Make a JSON string safe for embedding into a `<script>` tag
+fn embeddable_json(json: &str) -> String {
+    json.replace("</", "<\\/")
 }
 
 /// Generate filename by taking the filename of a
 /// given path and appending the hash of the full path.
-fn generate_html_filename(file: &str) -> Result<String> {
+pub(super) fn generate_html_filename(file: &str) -> Result<String> {
     let file_name = Path::new(file)
         .file_name()
         .context("File has no filename")?;
@@ -280,11 +452,29 @@ fn generate_html_filename(file: &str) -> Result<String> {
 }
 
 handlebars_helper!(float_format: |x: f64| format!("{x:.1}"));
-handlebars_helper!(score_to_class: |s: f64| {
-    String::from(BulmaClass::from_mutation_score(s as f32))
-});
 
-fn create_template_engine() -> Handlebars<'static> {
+/// Helper converting a mutation score to a bulma class, based on the configured thresholds
+struct ScoreToClass(ScoreThresholds);
+
+impl HelperDef for ScoreToClass {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc HandlebarsContext,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'reg, 'rc>, RenderError> {
+        let score = h
+            .param(0)
+            .and_then(|param| param.value().as_f64())
+            .ok_or_else(|| RenderError::new("score_to_class expects a numeric parameter"))?;
+
+        let class = String::from(BulmaClass::from_mutation_score(score as f32, &self.0));
+        Ok(ScopedJson::Derived(class.into()))
+    }
+}
+
+pub(super) fn create_template_engine(thresholds: ScoreThresholds) -> Handlebars<'static> {
     let mut handlebars = Handlebars::new();
 
     handlebars.set_strict_mode(true);
@@ -297,23 +487,78 @@ fn create_template_engine() -> Handlebars<'static> {
     handlebars
         .register_template_string("index", templates::INDEX)
         .unwrap();
+    handlebars
+        .register_template_string("workspace", templates::WORKSPACE)
+        .unwrap();
+    handlebars
+        .register_template_string("operators", templates::OPERATORS)
+        .unwrap();
 
     handlebars.register_helper("float_format", Box::new(float_format));
-    handlebars.register_helper("score_to_class", Box::new(score_to_class));
+    handlebars.register_helper("score_to_class", Box::new(ScoreToClass(thresholds)));
 
     handlebars
 }
 
 #[derive(Serialize)]
 struct InlineMutantDescription {
+    id: String,
     outcome: String,
     text: String,
+    stack_trace: Vec<String>,
+    trace: Option<String>,
+    skip_reason: Option<String>,
+    killed_by: Option<String>,
+    condition: Option<String>,
+}
+
+/// Mutants of a single line sharing the same outcome
+#[derive(Serialize)]
+struct OutcomeGroup {
+    outcome: String,
+    count: usize,
+    mutants: Vec<InlineMutantDescription>,
+}
+
+/// Order in which outcome groups are displayed, most relevant first
+const OUTCOME_ORDER: [MutationOutcome; 7] = [
+    MutationOutcome::Alive,
+    MutationOutcome::Timeout,
+    MutationOutcome::Error,
+    MutationOutcome::Killed,
+    MutationOutcome::Skipped,
+    MutationOutcome::InternalError,
+    MutationOutcome::NotRun,
+];
+
+/// Group mutant descriptions by their outcome, omitting empty groups
+fn group_by_outcome(mutants: Vec<InlineMutantDescription>) -> Vec<OutcomeGroup> {
+    let mut groups: Vec<OutcomeGroup> = OUTCOME_ORDER
+        .iter()
+        .map(|outcome| OutcomeGroup {
+            outcome: outcome.clone().into(),
+            count: 0,
+            mutants: Vec::new(),
+        })
+        .collect();
+
+    for mutant in mutants {
+        let group = groups
+            .iter_mut()
+            .find(|group| group.outcome == mutant.outcome)
+            .expect("Every outcome has a group");
+        group.count += 1;
+        group.mutants.push(mutant);
+    }
+
+    groups.retain(|group| group.count > 0);
+    groups
 }
 
 #[derive(Serialize)]
 struct SourceLine {
     line_number: u64,
-    mutants: Vec<InlineMutantDescription>,
+    groups: Vec<OutcomeGroup>,
     code: String,
     mutant_tag_class: String,
     accumulated_outcomes: AccumulatedOutcomes,
@@ -325,6 +570,7 @@ impl SourceLine {
         line_content: &str,
         mutants: &[&ReportableMutant],
         mut html_generator: ClassedHTMLGenerator,
+        path_rewriter: Option<&PathRewriter>,
     ) -> Result<Self> {
         // Generate HTML code for a line of source code
         let line_including_newline = format!("{line_content}\n");
@@ -334,34 +580,80 @@ impl SourceLine {
         // Accumulate mutants for the given line
         let accumulated_outcomes = super::accumulate_outcomes(mutants);
 
-        // Generate inline mutant descriptions
-        let inline_mutants = mutants
+        // Generate inline mutant descriptions, grouped by outcome
+        let inline_mutants: Vec<InlineMutantDescription> = mutants
             .iter()
             .map(|mutant| InlineMutantDescription {
+                id: mutant.id(),
                 outcome: mutant.outcome.clone().into(),
-                text: mutant.operator.description(),
+                text: mutant.description(),
+                stack_trace: mutant
+                    .stack_trace
+                    .iter()
+                    .map(|frame| super::rewrite_location(frame, path_rewriter).to_string())
+                    .collect(),
+                trace: mutant
+                    .trace
+                    .as_ref()
+                    .map(|trace| format!("{TRACE_DIRECTORY}/{}", trace.file_name())),
+                skip_reason: mutant.skip_explanation(),
+                killed_by: mutant.kill_explanation(),
+                condition: mutant.condition_explanation(),
             })
             .collect();
 
         Ok(SourceLine {
             line_number: line_nr,
             code: html,
-            mutants: inline_mutants,
+            groups: group_by_outcome(inline_mutants),
             mutant_tag_class: BulmaClass::from(accumulated_outcomes.clone()).into(),
             accumulated_outcomes,
         })
     }
 }
 
+/// Mutant without source location, listed by function and code offset
+#[derive(Serialize)]
+struct UnlocatedMutant {
+    id: String,
+    function: String,
+    offset: String,
+    outcome: String,
+    description: String,
+}
+
+impl From<&ReportableMutant> for UnlocatedMutant {
+    fn from(mutant: &ReportableMutant) -> Self {
+        Self {
+            id: mutant.id(),
+            function: mutant
+                .location
+                .function
+                .clone()
+                .unwrap_or_else(|| "<unknown>".into()),
+            offset: format!("0x{:x}", mutant.offset),
+            outcome: mutant.outcome.clone().into(),
+            description: match mutant
+                .skip_explanation()
+                .or_else(|| mutant.kill_explanation())
+            {
+                Some(explanation) => format!("{} ({explanation})", mutant.description()),
+                None => mutant.description(),
+            },
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct SourceFile {
     name: String,
     link: Option<String>,
+    stale: bool,
     accumulated_outcomes: AccumulatedOutcomes,
 }
 
 #[derive(Serialize)]
-struct ReportInfo {
+pub(super) struct ReportInfo {
     program_name: String,
     program_version: String,
     date: String,
@@ -369,7 +661,7 @@ struct ReportInfo {
 }
 
 impl ReportInfo {
-    fn new() -> Self {
+    pub(super) fn new() -> Self {
         let current_time = Local::now();
 
         ReportInfo {
@@ -384,6 +676,7 @@ impl ReportInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reporter::MutantBuilder;
     use anyhow::Result;
     use quickcheck::quickcheck;
     use tempfile::tempdir;
@@ -392,7 +685,12 @@ mod tests {
     fn generate_source_lines_no_mutants() -> Result<()> {
         let output = tempdir()?;
 
-        let reporter = HTMLReporter::new(&ReportConfig::default(), output.path())?;
+        let reporter = HTMLReporter::new(
+            &ReportConfig::default(),
+            output.path(),
+            "test.wasm",
+            &RunMetadata::default(),
+        )?;
 
         let result =
             reporter.generate_source_lines("testdata/simple_add/simple_add.c", &BTreeMap::new())?;
@@ -404,13 +702,228 @@ mod tests {
     fn generate_source_lines_invalid_file() -> Result<()> {
         let output = tempdir()?;
 
-        let reporter = HTMLReporter::new(&ReportConfig::default(), output.path())?;
+        let reporter = HTMLReporter::new(
+            &ReportConfig::default(),
+            output.path(),
+            "test.wasm",
+            &RunMetadata::default(),
+        )?;
 
         let result = reporter.generate_source_lines("testdata/invalid/invalid.c", &BTreeMap::new());
         assert!(result.is_err());
         Ok(())
     }
 
+    #[test]
+    fn index_contains_run_metadata() -> Result<()> {
+        let output = tempdir()?;
+        let metadata = RunMetadata {
+            version: "1.2.3".into(),
+            duration: 4200,
+            threads: 8,
+            engine_mode: "meta-mutant".into(),
+            compiler: "Cranelift".into(),
+            partial: false,
+            filtered_mutants: 3,
+            scope: None,
+            meta_mutant_sizes: Vec::new(),
+            instructions_per_second: Some(1000000),
+        };
+
+        let mutant = MutantBuilder::new()
+            .with_outcome(MutationOutcome::Killed)
+            .with_execution_cost(Some(1234))
+            .build();
+
+        HTMLReporter::new(
+            &ReportConfig::default(),
+            output.path(),
+            "test.wasm",
+            &metadata,
+        )?
+        .report(&[mutant])?;
+
+        let html = std::fs::read_to_string(output.path().join("index.html"))?;
+        assert!(html.contains("<td>4200 ms</td>"));
+        assert!(html.contains("<td>meta-mutant</td>"));
+        assert!(html.contains("<td>Cranelift</td>"));
+        assert!(html.contains("<td>1000000</td>"));
+        assert!(html.contains("3 mutation candidates were excluded"));
+        assert!(html.contains("Mutants without source location"));
+        assert!(html.contains("<td class=\"is-family-code\">0x0</td>"));
+        assert!(html.contains("Slowest mutants"));
+        assert!(html.contains("<td>1234</td>"));
+
+        let json = std::fs::read_to_string(output.path().join("report.json"))?;
+        let report: super::super::json::JSONReport = serde_json::from_str(&json)?;
+        assert_eq!(report.metadata, metadata);
+        assert_eq!(report.summary.execution_time, 4200);
+        assert_eq!(report.summary.filtered, 3);
+        assert_eq!(report.slowest.mutants[0].execution_cost, 1234);
+        Ok(())
+    }
+
+    #[test]
+    fn operator_legend() -> Result<()> {
+        let output = tempdir()?;
+        let mutant = MutantBuilder::new()
+            .with_outcome(MutationOutcome::Killed)
+            .build();
+
+        HTMLReporter::new(
+            &ReportConfig::default(),
+            output.path(),
+            "test.wasm",
+            &RunMetadata::default(),
+        )?
+        .report(&[mutant])?;
+
+        let html = std::fs::read_to_string(output.path().join("operators.html"))?;
+        assert!(html.contains("<tr id=\"binop_add_to_sub\">"));
+        assert!(html.contains("i32.add &rarr; i32.sub"));
+
+        let index = std::fs::read_to_string(output.path().join("index.html"))?;
+        assert!(index.contains("href=\"operators.html\""));
+
+        let json = std::fs::read_to_string(output.path().join("report.json"))?;
+        let report: super::super::json::JSONReport = serde_json::from_str(&json)?;
+        let add_to_sub = report
+            .operators
+            .iter()
+            .find(|operator| operator.name == "binop_add_to_sub")
+            .unwrap();
+        assert_eq!(add_to_sub.category, "binary");
+        assert_eq!(add_to_sub.mappings[0].from, "i32.add");
+        Ok(())
+    }
+
+    #[test]
+    fn not_run_mutants_are_reported_separately() -> Result<()> {
+        let output = tempdir()?;
+        let mutant = |offset, outcome| {
+            MutantBuilder::new()
+                .with_offset(offset)
+                .with_outcome(outcome)
+                .build()
+        };
+
+        HTMLReporter::new(
+            &ReportConfig::default(),
+            output.path(),
+            "test.wasm",
+            &RunMetadata::default(),
+        )?
+        .report(&[
+            mutant(0, MutationOutcome::Killed),
+            mutant(4, MutationOutcome::NotRun),
+        ])?;
+
+        let html = std::fs::read_to_string(output.path().join("index.html"))?;
+        assert!(html.contains("<th>Not run</th>"));
+        assert!(html.contains("1 mutants were not run"));
+        assert!(html.contains("NOT_RUN"));
+
+        let json = std::fs::read_to_string(output.path().join("report.json"))?;
+        let report: super::super::json::JSONReport = serde_json::from_str(&json)?;
+        assert_eq!(report.summary.mutants, 2);
+        assert_eq!(report.summary.not_run, 1);
+        assert_eq!(report.summary.mutation_score, 100.0);
+        assert_eq!(report.mutants[1].outcome, "not_run");
+        Ok(())
+    }
+
+    #[test]
+    fn redacted_report() -> Result<()> {
+        let sources = tempdir()?;
+        let file = sources.path().join("secret_algorithm.c");
+        std::fs::write(
+            &file,
+            "int secret_add(int a, int b) {\n    return a + b;\n}\n",
+        )?;
+        let file = file.to_str().unwrap().to_string();
+
+        let mutant = MutantBuilder::new()
+            .with_file(&file)
+            .with_function("secret_add")
+            .with_line(2)
+            .build();
+
+        let output = tempdir()?;
+        HTMLReporter::new(
+            &ReportConfig::default(),
+            output.path(),
+            &redact::redact_file("/src/secret.wasm"),
+            &RunMetadata::default(),
+        )?
+        .with_redacted_paths()
+        .report(&redact::redact_mutants(vec![mutant]))?;
+
+        // No source views are rendered
+        let pages: Vec<_> = std::fs::read_dir(output.path())?
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.ends_with(".html"))
+            .collect();
+        assert_eq!(pages.len(), 2);
+
+        let html = std::fs::read_to_string(output.path().join("index.html"))?;
+        let json = std::fs::read_to_string(output.path().join("report.json"))?;
+        for content in [&html, &json] {
+            assert!(!content.contains("secret"));
+            assert!(content.contains(&redact::redact_file(&file)));
+        }
+
+        let report: super::super::json::JSONReport = serde_json::from_str(&json)?;
+        assert_eq!(report.file, redact::redact_file("/src/secret.wasm"));
+        assert!(report.sources.is_empty());
+        assert_eq!(report.mutants[0].link, None);
+        assert_eq!(report.mutants[0].line, Some(2));
+        assert_eq!(
+            report.mutants[0].function,
+            Some(redact::redact_function("secret_add"))
+        );
+        Ok(())
+    }
+
+    fn description(outcome: MutationOutcome, text: &str) -> InlineMutantDescription {
+        InlineMutantDescription {
+            id: text.into(),
+            outcome: outcome.into(),
+            text: text.into(),
+            stack_trace: Vec::new(),
+            trace: None,
+            skip_reason: None,
+            killed_by: None,
+            condition: None,
+        }
+    }
+
+    #[test]
+    fn group_mutants_by_outcome() {
+        let groups = group_by_outcome(vec![
+            description(MutationOutcome::Killed, "a"),
+            description(MutationOutcome::Alive, "b"),
+            description(MutationOutcome::Killed, "c"),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].outcome, "ALIVE");
+        assert_eq!(groups[0].count, 1);
+        assert_eq!(groups[1].outcome, "KILLED");
+        assert_eq!(groups[1].count, 2);
+        assert_eq!(groups[1].mutants[0].text, "a");
+        assert_eq!(groups[1].mutants[1].text, "c");
+
+        assert!(group_by_outcome(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn embeddable_json_escapes_script_end() {
+        assert_eq!(
+            embeddable_json(r#"{"a": "</script>"}"#),
+            r#"{"a": "<\/script>"}"#
+        );
+    }
+
     #[test]
     fn generate_filename_for_simple_add() -> Result<()> {
         let s =
@@ -421,7 +934,8 @@ mod tests {
 
     quickcheck! {
         fn test_bulma_class(mutation_score: f32) -> bool {
-            let class: String = BulmaClass::from_mutation_score(mutation_score).into();
+            let class: String =
+                BulmaClass::from_mutation_score(mutation_score, &ScoreThresholds::default()).into();
             if (0.0..50.0).contains(&mutation_score) {
                 class == "is-danger"
             } else if (50.0..75.0).contains(&mutation_score) {
@@ -436,9 +950,42 @@ mod tests {
 
     #[test]
     fn test_bulma_class_boundaries() {
-        assert_eq!(BulmaClass::from_mutation_score(0.0), BulmaClass::Danger);
-        assert_eq!(BulmaClass::from_mutation_score(50.0), BulmaClass::Warning);
-        assert_eq!(BulmaClass::from_mutation_score(75.0), BulmaClass::Success);
-        assert_eq!(BulmaClass::from_mutation_score(100.0), BulmaClass::Success);
+        let thresholds = &ScoreThresholds::default();
+        assert_eq!(
+            BulmaClass::from_mutation_score(0.0, thresholds),
+            BulmaClass::Danger
+        );
+        assert_eq!(
+            BulmaClass::from_mutation_score(50.0, thresholds),
+            BulmaClass::Warning
+        );
+        assert_eq!(
+            BulmaClass::from_mutation_score(75.0, thresholds),
+            BulmaClass::Success
+        );
+        assert_eq!(
+            BulmaClass::from_mutation_score(100.0, thresholds),
+            BulmaClass::Success
+        );
+    }
+
+    #[test]
+    fn score_to_class_uses_thresholds() -> Result<()> {
+        let template_engine = create_template_engine(ScoreThresholds {
+            warning: 60.0,
+            success: 80.0,
+        });
+
+        let render = |score: f32| {
+            template_engine.render_template(
+                "{{(score_to_class score)}}",
+                &BTreeMap::from([("score", score)]),
+            )
+        };
+
+        assert_eq!(render(55.0)?, "is-danger");
+        assert_eq!(render(75.0)?, "is-warning");
+        assert_eq!(render(80.0)?, "is-success");
+        Ok(())
     }
 }
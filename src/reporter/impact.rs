@@ -0,0 +1,178 @@
+//! Ranking of operators and files by surviving mutants, showing
+//! where additional tests would be most effective.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{rewriter::PathRewriter, MutationOutcome, ReportableMutant};
+
+/// Maximum number of operators and files in the ranking
+const RANKING_LENGTH: usize = 5;
+
+/// Surviving mutants of a single operator
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OperatorImpact {
+    pub operator: String,
+    /// Number of executed (i.e. not skipped) mutants
+    pub mutants: i32,
+    pub alive: i32,
+    /// Percentage of executed mutants that survived
+    pub survival_rate: f32,
+}
+
+/// Surviving mutants of a single source file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileImpact {
+    pub file: String,
+    pub alive: i32,
+}
+
+/// Operators with the highest survival rate and files with the most surviving mutants
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ImpactRanking {
+    pub operators: Vec<OperatorImpact>,
+    pub files: Vec<FileImpact>,
+}
+
+impl ImpactRanking {
+    /// Rank operators and files of the executed mutants.
+    /// Only operators and files with surviving mutants are included.
+    pub fn new(
+        executed_mutants: &[ReportableMutant],
+        path_rewriter: Option<&PathRewriter>,
+    ) -> Self {
+        let mut operators: BTreeMap<&str, (i32, i32)> = BTreeMap::new();
+        let mut files: BTreeMap<String, i32> = BTreeMap::new();
+
+        for mutant in executed_mutants {
+            if matches!(
+                mutant.outcome,
+                MutationOutcome::Skipped | MutationOutcome::NotRun | MutationOutcome::InternalError
+            ) {
+                continue;
+            }
+
+            let alive = mutant.outcome == MutationOutcome::Alive;
+
+            let (mutants, alive_mutants) = operators.entry(mutant.operator.dyn_name()).or_default();
+            *mutants += 1;
+
+            if alive {
+                *alive_mutants += 1;
+
+                if let Some(file) = &mutant.location.file {
+                    let file = match path_rewriter {
+                        Some(path_rewriter) => path_rewriter.rewrite(file),
+                        None => file.clone(),
+                    };
+                    *files.entry(file).or_default() += 1;
+                }
+            }
+        }
+
+        let mut operators: Vec<OperatorImpact> = operators
+            .into_iter()
+            .filter(|(_, (_, alive))| *alive > 0)
+            .map(|(operator, (mutants, alive))| OperatorImpact {
+                operator: operator.into(),
+                mutants,
+                alive,
+                survival_rate: 100.0 * alive as f32 / mutants as f32,
+            })
+            .collect();
+        // Sorting is stable, so ties keep the alphabetical order
+        operators.sort_by(|a, b| {
+            b.survival_rate
+                .total_cmp(&a.survival_rate)
+                .then(b.alive.cmp(&a.alive))
+        });
+        operators.truncate(RANKING_LENGTH);
+
+        let mut files: Vec<FileImpact> = files
+            .into_iter()
+            .map(|(file, alive)| FileImpact { file, alive })
+            .collect();
+        files.sort_by_key(|file| std::cmp::Reverse(file.alive));
+        files.truncate(RANKING_LENGTH);
+
+        Self { operators, files }
+    }
+
+    /// Return whether there are no surviving mutants
+    pub fn is_empty(&self) -> bool {
+        self.operators.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporter::MutantBuilder;
+
+    fn mutant(file: &str, add_to_sub: bool, outcome: MutationOutcome) -> ReportableMutant {
+        let mutant = MutantBuilder::new()
+            .with_file(file)
+            .with_line(1)
+            .with_outcome(outcome);
+        match add_to_sub {
+            true => mutant.build(),
+            false => mutant.with_sub_to_add().build(),
+        }
+    }
+
+    #[test]
+    fn rank_operators_and_files() {
+        use MutationOutcome::*;
+
+        let mutants = vec![
+            mutant("/src/a.c", true, Alive),
+            mutant("/src/a.c", true, Killed),
+            mutant("/src/b.c", true, Alive),
+            mutant("/src/b.c", true, Killed),
+            mutant("/src/b.c", false, Alive),
+            mutant("/src/b.c", false, Skipped),
+            mutant("/src/c.c", false, Killed),
+        ];
+
+        let path_rewriter = PathRewriter::new("^/src/", "").unwrap();
+        let ranking = ImpactRanking::new(&mutants, Some(&path_rewriter));
+
+        assert_eq!(
+            ranking.operators,
+            vec![
+                OperatorImpact {
+                    operator: "binop_add_to_sub".into(),
+                    mutants: 4,
+                    alive: 2,
+                    survival_rate: 50.0,
+                },
+                OperatorImpact {
+                    operator: "binop_sub_to_add".into(),
+                    mutants: 2,
+                    alive: 1,
+                    survival_rate: 50.0,
+                },
+            ]
+        );
+        assert_eq!(
+            ranking.files,
+            vec![
+                FileImpact {
+                    file: "b.c".into(),
+                    alive: 2,
+                },
+                FileImpact {
+                    file: "a.c".into(),
+                    alive: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn no_surviving_mutants() {
+        let mutants = vec![mutant("a.c", true, MutationOutcome::Killed)];
+        assert!(ImpactRanking::new(&mutants, None).is_empty());
+    }
+}
@@ -1,19 +1,73 @@
-use std::time::Duration;
-
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
-use crate::{config::ReportConfig, output};
+use crate::{
+    addressresolver::CodeLocation,
+    config::ReportConfig,
+    operator::catalog::{self, OperatorCatalogEntry},
+    output,
+    warnings::{self, Warning},
+};
 
-use super::{rewriter::PathRewriter, ReportableMutant};
+use super::{
+    impact::ImpactRanking, redact, rewriter::PathRewriter, slowest::SlowestRanking,
+    ReportableMutant, RunMetadata, SourceFileInfo,
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct JSONMutant {
+    /// Stable identifier of the mutant
+    #[serde(default)]
+    pub id: String,
     pub operator: String,
+    /// Normalized source path, after path rewriting
     pub file: Option<String>,
+    /// Source path as recorded in the debug information, if it differs from
+    /// the normalized path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_file: Option<String>,
     pub function: Option<String>,
     pub line: Option<u64>,
+    /// Offset of the mutated instruction, relative to the start of the code section
+    #[serde(default)]
+    pub offset: u64,
     pub outcome: String,
+    /// Number of executed instructions, if the mutant terminated normally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_cost: Option<u64>,
+    /// Human readable description of the mutation
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stack_trace: Vec<CodeLocation>,
+    /// Link to the mutant in the HTML report, relative to the report directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    /// The mutant survived, but cannot change the behaviour of the module
+    /// according to the comparison profiles of the coverage run
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub likely_equivalent: bool,
+    /// Observed results of the mutated comparison in the coverage run:
+    /// `always true`, `always false` or `mixed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    /// Error message, if the mutant could not be executed because of
+    /// an error of wasmut or the runtime
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_error: Option<String>,
+    /// Number of operator variants merged into this mutant, if variants are grouped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<usize>,
+    /// Why the mutant was skipped because of missing coverage, if `explain_skipped` is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
+    /// Line of the executed instruction closest to the mutated one,
+    /// if the mutated instruction was never executed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nearest_covered_line: Option<u64>,
+    /// Names of the kill criteria that killed the mutant
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub killed_by: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -24,7 +78,19 @@ pub struct JSONSummary {
     pub alive: i32,
     pub timeout: i32,
     pub error: i32,
+    /// Mutants skipped because they are not covered by the tests
     pub skipped: i32,
+    /// Mutation candidates excluded by the filter configuration
+    #[serde(default)]
+    pub filtered: i32,
+    /// Mutants that could not be executed because of an error of wasmut
+    /// or the runtime, included in `mutants` but not in the mutation score
+    #[serde(default)]
+    pub internal_errors: i32,
+    /// Mutants not executed because the time budget of the run was exhausted,
+    /// included in `mutants` but not in the mutation score
+    #[serde(default)]
+    pub not_run: i32,
     pub mutation_score: f32,
 }
 
@@ -33,16 +99,38 @@ pub struct JSONReport {
     pub file: String,
     pub mutants: Vec<JSONMutant>,
     pub summary: JSONSummary,
+    #[serde(default)]
+    pub sources: Vec<SourceFileInfo>,
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+    /// Enabled operators that did not produce any mutants
+    #[serde(default)]
+    pub unused_operators: Vec<String>,
+    /// Operators and files with the most surviving mutants
+    #[serde(default)]
+    pub impact: ImpactRanking,
+    /// Mutants and functions with the highest execution cost
+    #[serde(default)]
+    pub slowest: SlowestRanking,
+    /// Information about the run that created the report
+    #[serde(default)]
+    pub metadata: RunMetadata,
+    /// Name, category and instruction mappings of all operators
+    #[serde(default)]
+    pub operators: Vec<OperatorCatalogEntry>,
 }
 
 pub struct JSONReporter {
     path_rewriter: Option<PathRewriter>,
     file: String,
-    execution_time: u64,
+    metadata: RunMetadata,
+    unused_operators: Vec<String>,
+    /// Paths were redacted, source files are not read
+    redacted: bool,
 }
 
 impl JSONReporter {
-    pub fn new(config: &ReportConfig, wasmfile: &str, duration: &Duration) -> Result<Self> {
+    pub fn new(config: &ReportConfig, wasmfile: &str, metadata: &RunMetadata) -> Result<Self> {
         let path_rewriter = if let Some((regex, replacement)) = &config.path_rewrite() {
             Some(PathRewriter::new(regex, replacement)?)
         } else {
@@ -52,31 +140,80 @@ impl JSONReporter {
         Ok(Self {
             path_rewriter,
             file: wasmfile.into(),
-            execution_time: duration.as_millis() as u64,
+            metadata: metadata.clone(),
+            unused_operators: Vec::new(),
+            redacted: false,
         })
     }
 
-    pub fn report(&self, executed_mutants: &[ReportableMutant]) -> Result<()> {
+    /// Include enabled operators that did not produce any mutants in the report
+    pub fn with_unused_operators(self, unused_operators: Vec<String>) -> Self {
+        Self {
+            unused_operators,
+            ..self
+        }
+    }
+
+    /// Report mutants whose paths were redacted with `redact::redact_mutants`.
+    /// Source files are not read, and the messages of warnings are left out.
+    pub fn with_redacted_paths(self) -> Self {
+        Self {
+            redacted: true,
+            ..self
+        }
+    }
+
+    /// Create the JSON report data structure
+    pub fn create_report(&self, executed_mutants: &[ReportableMutant]) -> JSONReport {
         let mutants = self.map_to_json_mutants(executed_mutants);
 
-        let accumulated_outcomes = super::accumulate_outcomes(executed_mutants);
+        let accumulated_outcomes = super::accumulate_outcomes(executed_mutants)
+            .with_filtered(self.metadata.filtered_mutants);
 
-        let report = JSONReport {
+        JSONReport {
             file: self.file.clone(),
             mutants,
             summary: JSONSummary {
-                execution_time: self.execution_time,
+                execution_time: self.metadata.duration,
                 mutants: accumulated_outcomes.total,
                 killed: accumulated_outcomes.killed,
                 alive: accumulated_outcomes.alive,
                 timeout: accumulated_outcomes.timeout,
                 error: accumulated_outcomes.error,
                 skipped: accumulated_outcomes.skipped,
+                filtered: accumulated_outcomes.filtered,
+                internal_errors: accumulated_outcomes.internal_error,
+                not_run: accumulated_outcomes.not_run,
                 mutation_score: accumulated_outcomes.mutation_score,
             },
-        };
+            sources: if self.redacted {
+                Vec::new()
+            } else {
+                super::collect_source_file_infos(executed_mutants, self.path_rewriter.as_ref())
+            },
+            // Collected last, so that warnings emitted while
+            // creating the report are included as well
+            warnings: if self.redacted {
+                redact::redact_warnings(warnings::collected())
+            } else {
+                warnings::collected()
+            },
+            unused_operators: self.unused_operators.clone(),
+            impact: ImpactRanking::new(executed_mutants, self.path_rewriter.as_ref()),
+            slowest: SlowestRanking::new(executed_mutants, self.path_rewriter.as_ref()),
+            metadata: self.metadata.clone(),
+            operators: catalog::catalog(),
+        }
+    }
 
-        let s = serde_json::to_string_pretty(&report)?;
+    /// Serialize the report to a JSON string
+    pub fn report_to_string(&self, executed_mutants: &[ReportableMutant]) -> Result<String> {
+        let report = self.create_report(executed_mutants);
+        Ok(serde_json::to_string_pretty(&report)?)
+    }
+
+    pub fn report(&self, executed_mutants: &[ReportableMutant]) -> Result<()> {
+        let s = self.report_to_string(executed_mutants)?;
 
         output::output_string(s);
 
@@ -84,8 +221,12 @@ impl JSONReporter {
     }
 
     fn map_to_json_mutants(&self, executed_mutants: &[super::ReportableMutant]) -> Vec<JSONMutant> {
+        // Sort, so that reports of different runs can be diffed
+        let mut executed_mutants: Vec<&ReportableMutant> = executed_mutants.iter().collect();
+        executed_mutants.sort_by(|a, b| a.report_order(b));
+
         let mutants = executed_mutants
-            .iter()
+            .into_iter()
             .map(|em| {
                 let file = em.location.file.as_deref().map(|f| {
                     if let Some(path_rewriter) = &self.path_rewriter {
@@ -97,12 +238,36 @@ impl JSONReporter {
 
                 let outcome: String = em.outcome.clone().into();
 
+                // Redacted reports do not contain source views
+                let link = file
+                    .as_deref()
+                    .filter(|_| !self.redacted)
+                    .and_then(|file| em.permalink(file));
+
                 JSONMutant {
+                    id: em.id(),
                     operator: em.operator.dyn_name().into(),
                     file,
+                    raw_file: em.location.raw_file.clone(),
                     function: em.location.function.clone(),
                     line: em.location.line,
+                    offset: em.offset,
                     outcome: outcome.to_lowercase(),
+                    execution_cost: em.execution_cost,
+                    description: em.description(),
+                    stack_trace: em
+                        .stack_trace
+                        .iter()
+                        .map(|frame| super::rewrite_location(frame, self.path_rewriter.as_ref()))
+                        .collect(),
+                    link,
+                    likely_equivalent: em.likely_equivalent,
+                    condition: em.condition.map(|profile| profile.outcome().into()),
+                    internal_error: em.internal_error.clone(),
+                    variants: (em.variants > 1).then_some(em.variants),
+                    skip_reason: em.skip_explanation(),
+                    nearest_covered_line: em.nearest_covered.as_ref().and_then(|l| l.line),
+                    killed_by: em.killed_by.clone(),
                 }
             })
             .collect::<Vec<_>>();
@@ -0,0 +1,152 @@
+//! Provenance of a mutation run. The run manifest (`run-manifest.toml`) is
+//! written into the report directory and records the module, the effective
+//! configuration, the enabled operators and the sampling seed, so that a run
+//! can be audited and reproduced later.
+//!
+//! The configuration sections are stored at the top level of the manifest,
+//! so the manifest can be passed to `wasmut mutate --config` as it is.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{baseline::Baseline, config::Config, wasmmodule::WasmModule};
+
+/// File name of the manifest within the report directory
+pub const MANIFEST_FILE_NAME: &str = "run-manifest.toml";
+
+/// Module that was mutated
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ModuleProvenance {
+    pub path: String,
+    /// MD5 hash of the bytecode
+    pub hash: String,
+}
+
+impl ModuleProvenance {
+    /// Path and hash of `module`
+    pub fn of(module: &WasmModule) -> Self {
+        Self {
+            path: module.path().into(),
+            hash: Baseline::hash_module(module.bytes()),
+        }
+    }
+}
+
+/// Machine that performed the run
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HostInfo {
+    /// Operating system, e.g. `linux`
+    pub os: String,
+    /// CPU architecture, e.g. `x86_64`
+    pub arch: String,
+    /// Number of available CPUs
+    pub cpus: usize,
+    /// Number of worker threads
+    pub threads: usize,
+}
+
+impl HostInfo {
+    /// Information about the current machine
+    pub fn current() -> Self {
+        Self {
+            os: std::env::consts::OS.into(),
+            arch: std::env::consts::ARCH.into(),
+            cpus: std::thread::available_parallelism()
+                .map(|cpus| cpus.get())
+                .unwrap_or(1),
+            threads: rayon::current_num_threads(),
+        }
+    }
+}
+
+/// Everything besides the configuration that is needed to reproduce a run
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunProvenance {
+    /// Version of wasmut that performed the run
+    pub wasmut_version: String,
+    /// Start of the run (RFC 3339)
+    pub started: String,
+    /// Seed for selecting the executed mutants (`--seed`)
+    pub seed: u32,
+    /// Percentage of the mutants that were executed (`--sample-threshold`)
+    pub sample_threshold: i32,
+    /// IDs of the executed mutants (`--mutant-ids`), empty if all mutants were executed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mutant_ids: Vec<String>,
+    /// Enabled mutation operators
+    pub operators: Vec<String>,
+    pub module: ModuleProvenance,
+    pub host: HostInfo,
+}
+
+/// Content of `run-manifest.toml`
+#[derive(Serialize, Deserialize)]
+pub struct RunManifest {
+    pub run: RunProvenance,
+    /// Effective configuration, with command line overrides and defaults
+    #[serde(flatten)]
+    pub config: Config,
+}
+
+impl RunManifest {
+    /// Manifest of a run with `config`
+    pub fn new(run: RunProvenance, config: &Config) -> Self {
+        Self {
+            run,
+            config: config.effective(),
+        }
+    }
+
+    /// Write the manifest into `directory`
+    pub fn save(&self, directory: &Path) -> Result<()> {
+        let path = directory.join(MANIFEST_FILE_NAME);
+        let content = toml::to_string(self).context("Could not serialize run manifest")?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Could not write run manifest {path:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provenance() -> RunProvenance {
+        RunProvenance {
+            wasmut_version: env!("CARGO_PKG_VERSION").into(),
+            started: "2023-04-15T09:30:12+02:00".into(),
+            seed: 42,
+            sample_threshold: 50,
+            mutant_ids: vec![],
+            operators: vec!["binop_add_to_sub".into(), "relop_eq_to_ne".into()],
+            module: ModuleProvenance {
+                path: "test.wasm".into(),
+                hash: "d41d8cd98f00b204e9800998ecf8427e".into(),
+            },
+            host: HostInfo::current(),
+        }
+    }
+
+    #[test]
+    fn manifest_round_trip() -> Result<()> {
+        let config = Config::parse("[engine]\ntimeout_multiplier = 4.0")?;
+        let dir = tempfile::tempdir()?;
+        RunManifest::new(provenance(), &config).save(dir.path())?;
+
+        let content = std::fs::read_to_string(dir.path().join(MANIFEST_FILE_NAME))?;
+        let manifest: RunManifest = toml::from_str(&content)?;
+        assert_eq!(manifest.run, provenance());
+        assert_eq!(manifest.config.engine().timeout_multiplier(), 4.0);
+
+        // The manifest is a valid configuration file
+        let config = Config::parse(&content)?;
+        assert_eq!(config.engine().timeout_multiplier(), 4.0);
+        assert_eq!(
+            config.engine().hybrid_threshold(),
+            crate::config::HYBRID_THRESHOLD
+        );
+        assert!(content.contains("hybrid_threshold = "));
+        Ok(())
+    }
+}
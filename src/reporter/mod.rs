@@ -1,9 +1,23 @@
+pub mod advice;
+mod badge;
 pub mod cli;
+#[cfg(feature = "sqlite")]
+pub mod database;
+mod description;
+mod duplicates;
+pub mod github;
+pub mod gitlab;
 pub mod html;
+pub mod impact;
 pub mod json;
+pub mod manifest;
+pub mod redact;
 mod rewriter;
+pub mod slowest;
+pub mod workspace;
 
 use std::{
+    cmp::Ordering,
     collections::BTreeMap,
     convert::AsRef,
     fs::File,
@@ -12,16 +26,21 @@ use std::{
     path::Path,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 
 use crate::{
-    addressresolver::{AddressResolver, CodeLocation},
-    executor::ExecutedMutant,
+    addressresolver::{CodeLocation, Locator},
+    criterion,
+    error::ErrorKind,
+    executor::{ExecutedMutant, SkipReason},
+    mutation::{FilterScope, MutantIds},
     operator::InstructionReplacement,
-    runtime::ExecutionResult,
-    wasmmodule::WasmModule,
+    runtime::{ComparisonProfile, ExecutionResult, ExecutionTrace, StackFrame},
+    warnings::{self, WarningCategory},
+    wasmmodule::{MetaMutantSize, WasmModule},
 };
-use serde::Serialize;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
 use syntect::{
     easy::HighlightLines,
     highlighting::Theme,
@@ -30,6 +49,8 @@ use syntect::{
 
 use self::rewriter::PathRewriter;
 
+pub use description::source_description;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum MutationOutcome {
     Alive,
@@ -37,6 +58,28 @@ pub enum MutationOutcome {
     Timeout,
     Error,
     Skipped,
+    /// Not executed, because the time budget of the run was exhausted.
+    /// Excluded from the mutation score.
+    NotRun,
+    /// Could not be executed because of an error of wasmut or the runtime.
+    /// Excluded from the mutation score, since nothing is known about the tests.
+    InternalError,
+}
+
+impl MutationOutcome {
+    /// Priority of the outcome when merging variants of a mutant, lower is preferred.
+    /// A mutant is killed if any of its variants was killed.
+    fn variant_priority(&self) -> u8 {
+        match self {
+            MutationOutcome::Killed => 0,
+            MutationOutcome::Timeout => 1,
+            MutationOutcome::Error => 2,
+            MutationOutcome::Alive => 3,
+            MutationOutcome::Skipped => 4,
+            MutationOutcome::InternalError => 5,
+            MutationOutcome::NotRun => 6,
+        }
+    }
 }
 
 impl From<ExecutionResult> for MutationOutcome {
@@ -50,37 +93,355 @@ impl From<ExecutionResult> for MutationOutcome {
                 }
             }
             ExecutionResult::Timeout => MutationOutcome::Timeout,
-            ExecutionResult::Error => MutationOutcome::Error,
+            ExecutionResult::Error { .. } => MutationOutcome::Error,
+            ExecutionResult::InternalError { .. } => MutationOutcome::InternalError,
             ExecutionResult::Skipped => MutationOutcome::Skipped,
+            ExecutionResult::NotRun => MutationOutcome::NotRun,
         }
     }
 }
 
 #[derive(Debug)]
 pub struct ReportableMutant {
+    /// Stable identifier, see `mutation::MutantIds`
+    id: String,
     location: CodeLocation,
+    /// Offset of the mutated instruction, relative to the start of the code section
+    offset: u64,
     outcome: MutationOutcome,
     operator: Box<dyn InstructionReplacement>,
+    /// Number of executed instructions, if the mutant terminated normally
+    execution_cost: Option<u64>,
+    /// Symbolicated stack trace, if the mutant trapped
+    stack_trace: Vec<CodeLocation>,
+    /// Symbolicated execution trace, if the mutant survived and was traced
+    trace: Option<MutantTrace>,
+    /// The mutant survived, but cannot change the behaviour of the module
+    /// according to the comparison profiles of the coverage run
+    likely_equivalent: bool,
+    /// Observed results of the mutated comparison in the coverage run
+    condition: Option<ComparisonProfile>,
+    /// Error message, if the mutant could not be executed because of
+    /// an error of wasmut or the runtime
+    internal_error: Option<String>,
+    /// Number of operator variants at the same instruction represented
+    /// by this mutant, more than 1 only if variants are grouped
+    variants: usize,
+    /// Why the mutant was skipped because of missing coverage, if `explain_skipped` is enabled
+    skip_reason: Option<SkipReason>,
+    /// Source location of the executed instruction closest to the mutated one,
+    /// if the mutant was skipped because the mutated instruction was never executed
+    nearest_covered: Option<CodeLocation>,
+    /// Names of the kill criteria that killed the mutant
+    killed_by: Vec<String>,
+}
+
+impl ReportableMutant {
+    /// Human readable description of the mutation, including a source-level
+    /// description of the operator, if there is one.
+    pub fn description(&self) -> String {
+        let mut description = self.operator.description();
+        if self.variants > 1 {
+            description = format!("{description}, {} variants", self.variants);
+        }
+
+        match description::source_description(self.operator.dyn_name()) {
+            Some(source_description) => format!("{source_description} ({description})"),
+            None => description,
+        }
+    }
+
+    /// Identifier of the mutant, which is stable across runs as long as its
+    /// function and the enabled operators do not change
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Link to the mutant in the HTML report, relative to the report directory.
+    /// `file` is the (rewritten) source file of the mutant.
+    pub fn permalink(&self, file: &str) -> Option<String> {
+        let html_filename = html::generate_html_filename(file).ok()?;
+        Some(format!("{html_filename}#mutant-{}", self.id()))
+    }
+
+    /// Function and code offset of the mutant, used instead of a
+    /// source location for modules without line information
+    /// Why the mutant was skipped because of missing coverage, if it was recorded
+    pub fn skip_explanation(&self) -> Option<String> {
+        let explanation = match self.skip_reason? {
+            SkipReason::EdgeNotTaken => {
+                "the branch edge on which the mutant differs was never taken".into()
+            }
+            SkipReason::TooExpensive => "too expensive".into(),
+            SkipReason::NotExecuted {
+                nearest_covered: None,
+            } => "no instruction of the function was executed".into(),
+            SkipReason::NotExecuted {
+                nearest_covered: Some(offset),
+            } => match self.nearest_covered.as_ref().and_then(|l| l.line) {
+                Some(line) => format!("not executed, nearest executed line: {line}"),
+                None => format!("not executed, nearest executed instruction: 0x{offset:x}"),
+            },
+        };
+
+        Some(explanation)
+    }
+
+    /// Which kill criteria killed the mutant, e.g. `killed by stdout diff`
+    /// Observed results of the mutated comparison in the coverage run,
+    /// e.g. `condition always true`
+    pub fn condition_explanation(&self) -> Option<String> {
+        let profile = self.condition?;
+        Some(format!("condition {}", profile.outcome()))
+    }
+
+    pub fn kill_explanation(&self) -> Option<String> {
+        if self.outcome != MutationOutcome::Killed || self.killed_by.is_empty() {
+            return None;
+        }
+
+        let criteria: Vec<&str> = self
+            .killed_by
+            .iter()
+            .map(|name| criterion::describe(name))
+            .collect();
+        Some(format!("killed by {}", criteria.join(" and ")))
+    }
+
+    pub fn offset_location(&self) -> String {
+        format!(
+            "{} @ 0x{:x}",
+            self.location.function.as_deref().unwrap_or("<unknown>"),
+            self.offset
+        )
+    }
+}
+
+/// Execution trace of a surviving mutant
+#[derive(Debug)]
+pub struct MutantTrace {
+    /// Unique identifier, used to name the trace file
+    id: usize,
+    calls: Vec<CodeLocation>,
+    truncated: bool,
+    stdout: String,
+    stderr: String,
+}
+
+impl MutantTrace {
+    fn new(id: usize, trace: ExecutionTrace, resolver: &Locator) -> Self {
+        Self {
+            id,
+            calls: trace
+                .calls
+                .iter()
+                .map(|&address| resolver.lookup_address(address).unwrap_or_default())
+                .collect(),
+            truncated: trace.truncated,
+            stdout: trace.stdout,
+            stderr: trace.stderr,
+        }
+    }
+
+    /// Name of the trace file
+    fn file_name(&self) -> String {
+        format!("mutant-{}.txt", self.id)
+    }
+
+    /// Render trace file content
+    fn render(&self, mutant: &ReportableMutant) -> String {
+        let mut content = String::new();
+
+        content.push_str(&format!("Mutant: {}\n", mutant.description()));
+        content.push_str(&format!("Location: {}\n\n", mutant.location));
+
+        content.push_str("Called functions:\n");
+        for (i, call) in self.calls.iter().enumerate() {
+            content.push_str(&format!("{i:>6}: {call}\n"));
+        }
+        if self.truncated {
+            content.push_str("        ... (truncated)\n");
+        }
+
+        content.push_str(&format!("\nstdout:\n{}\n", self.stdout));
+        content.push_str(&format!("\nstderr:\n{}\n", self.stderr));
+        content
+    }
 }
 
 pub fn prepare_results(
     module: &WasmModule,
     results: Vec<ExecutedMutant>,
 ) -> Result<Vec<ReportableMutant>> {
-    let bytes = std::fs::read(module.path()).context("Could not read bytecode from file")?;
+    let resolver = module.locator();
+    let functions = module.function_offsets();
+    let mutant_ids = MutantIds::new(module)?;
 
-    let resolver = AddressResolver::new(&bytes);
+    // Results arrive in execution order, which depends on thread scheduling.
+    // Sort them, so that reports (and the names of trace files) are reproducible.
+    let mut results = results;
+    results.sort_by(|a, b| {
+        mutant_order(
+            a.offset,
+            &*a.mutation_operator,
+            b.offset,
+            &*b.mutation_operator,
+        )
+    });
 
     Ok(results
         .into_iter()
-        .map(|result| ReportableMutant {
-            location: resolver.lookup_address(result.offset).unwrap_or_default(),
-            outcome: result.result.into(),
-            operator: result.mutation_operator,
+        .enumerate()
+        .map(|(id, result)| {
+            let outcome = result.outcome();
+
+            let stack_trace = match &result.result {
+                ExecutionResult::Error { stack_trace } => stack_trace
+                    .iter()
+                    .map(|frame| symbolicate_frame(module, &resolver, frame))
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            let trace = result
+                .trace
+                .map(|trace| MutantTrace::new(id, trace, &resolver));
+
+            let execution_cost = match result.result {
+                ExecutionResult::ProcessExit { execution_cost, .. } => Some(execution_cost),
+                _ => None,
+            };
+
+            let internal_error = match &result.result {
+                ExecutionResult::InternalError { message } => Some(message.clone()),
+                _ => None,
+            };
+
+            let mut location = resolver.lookup_address(result.offset).unwrap_or_default();
+            if location.function.as_deref().unwrap_or_default().is_empty() {
+                location.function = function_at(&functions, result.offset);
+            }
+
+            let nearest_covered = match result.skip_reason {
+                Some(SkipReason::NotExecuted {
+                    nearest_covered: Some(offset),
+                }) => resolver.lookup_address(offset),
+                _ => None,
+            };
+
+            ReportableMutant {
+                id: mutant_ids.for_offset(result.offset, &*result.mutation_operator),
+                location,
+                offset: result.offset,
+                outcome,
+                operator: result.mutation_operator,
+                execution_cost,
+                stack_trace,
+                trace,
+                likely_equivalent: result.likely_equivalent,
+                condition: result.condition,
+                internal_error,
+                variants: 1,
+                skip_reason: result.skip_reason,
+                nearest_covered,
+                killed_by: result.killed_by,
+            }
         })
         .collect())
 }
 
+/// Name of the function containing `offset`.
+/// `functions` are the function offsets as returned by `WasmModule::function_offsets`.
+pub fn function_at(functions: &[(u64, String)], offset: u64) -> Option<String> {
+    let index = functions.partition_point(|(start, _)| *start <= offset);
+    index.checked_sub(1).map(|index| functions[index].1.clone())
+}
+
+/// Mutants that cannot be shown in a source file, because the module
+/// lacks line information for them. Sorted by offset.
+fn mutants_without_source_location(
+    executed_mutants: &[ReportableMutant],
+) -> Vec<&ReportableMutant> {
+    let mut mutants: Vec<_> = executed_mutants
+        .iter()
+        .filter(|mutant| mutant.location.file.is_none() || mutant.location.line.is_none())
+        .collect();
+    mutants.sort_by(|a, b| mutant_order(a.offset, &*a.operator, b.offset, &*b.operator));
+    mutants
+}
+
+/// Order of mutants within reports: by offset, operator name and description
+/// Merge variants of an operator at the same instruction (e.g. signed and unsigned
+/// division replacing a multiplication) into a single mutant, which is represented
+/// by the variant with the most relevant outcome. The merged mutant is killed if
+/// any of its variants was killed.
+///
+/// `mutants` must be in the order returned by `prepare_results`.
+pub fn group_variants(mutants: Vec<ReportableMutant>) -> Vec<ReportableMutant> {
+    let mut grouped: Vec<ReportableMutant> = Vec::with_capacity(mutants.len());
+
+    for mutant in mutants {
+        match grouped.last_mut() {
+            Some(group)
+                if group.offset == mutant.offset
+                    && group.operator.dyn_name() == mutant.operator.dyn_name() =>
+            {
+                let variants = group.variants + mutant.variants;
+                if mutant.outcome.variant_priority() < group.outcome.variant_priority() {
+                    *group = mutant;
+                }
+                group.variants = variants;
+            }
+            _ => grouped.push(mutant),
+        }
+    }
+
+    grouped
+}
+
+fn mutant_order(
+    a_offset: u64,
+    a_operator: &dyn InstructionReplacement,
+    b_offset: u64,
+    b_operator: &dyn InstructionReplacement,
+) -> Ordering {
+    a_offset
+        .cmp(&b_offset)
+        .then_with(|| a_operator.dyn_name().cmp(b_operator.dyn_name()))
+        .then_with(|| a_operator.description().cmp(&b_operator.description()))
+}
+
+impl ReportableMutant {
+    /// Deterministic order of mutants in reports, independent of the execution order.
+    /// Mutants are ordered by file and line first, and by `mutant_order` within a line.
+    fn report_order(&self, other: &Self) -> Ordering {
+        (&self.location.file, self.location.line)
+            .cmp(&(&other.location.file, other.location.line))
+            .then_with(|| {
+                mutant_order(self.offset, &*self.operator, other.offset, &*other.operator)
+            })
+    }
+}
+
+/// Resolve a stack frame to a location in the original module.
+///
+/// Offsets in the executed module may differ from the original one, since
+/// mutations and instrumentation change the size of the function body.
+/// Consequently, the offset is clamped to the function, so that the function
+/// is always resolved correctly, while the line number is only an approximation.
+fn symbolicate_frame(module: &WasmModule, resolver: &Locator, frame: &StackFrame) -> CodeLocation {
+    module
+        .function_code_range(frame.function_number)
+        .and_then(|(first, last)| {
+            let address = (first + frame.function_offset).min(last);
+
+            resolver
+                .lookup_address(address)
+                .or_else(|| resolver.lookup_address(first))
+        })
+        .unwrap_or_default()
+}
+
 // pub trait Reporter {
 //     fn report(&self, executed_mutants: &[ReportableMutant]) -> Result<()>;
 // }
@@ -108,9 +469,99 @@ fn map_mutants_to_files<'a>(
             entry.push(mutant);
         }
     }
+
+    duplicates::merge_duplicate_files(&mut file_mapping);
+
+    for line_map in file_mapping.values_mut() {
+        for mutants in line_map.values_mut() {
+            mutants.sort_by(|a, b| a.report_order(b));
+        }
+    }
+
     file_mapping
 }
 
+/// Apply the path rewriter to the file of a location
+fn rewrite_location(location: &CodeLocation, path_rewriter: Option<&PathRewriter>) -> CodeLocation {
+    let mut location = location.clone();
+    if let (Some(path_rewriter), Some(file)) = (path_rewriter, &location.file) {
+        location.file = Some(path_rewriter.rewrite(file));
+    }
+    location
+}
+
+/// Checksum and metadata of a source file at the time of report generation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SourceFileInfo {
+    pub file: String,
+    pub md5: Option<String>,
+    pub modified: Option<String>,
+    pub lines: Option<u64>,
+    /// Mutants refer to lines that do not exist in the current version of
+    /// the file, so it was changed after the module was compiled.
+    pub stale: bool,
+}
+
+impl SourceFileInfo {
+    /// Collect checksum and metadata of `file`. `max_line` is the highest line number
+    /// referenced by the debug information of the module.
+    fn collect(file: &str, max_line: u64) -> Self {
+        let content = std::fs::read(file).ok();
+
+        let md5 = content
+            .as_ref()
+            .map(|content| format!("{:?}", md5::compute(content)));
+
+        let lines = content
+            .as_ref()
+            .map(|content| content.lines().count() as u64);
+
+        let modified = std::fs::metadata(file)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(|modified| DateTime::<Local>::from(modified).to_rfc3339());
+
+        let stale = matches!(lines, Some(lines) if max_line > lines);
+
+        if content.is_none() {
+            warnings::warn(
+                WarningCategory::UnresolvableSourceFile,
+                format!("Could not read source file {file}"),
+            );
+        } else if stale {
+            warnings::warn(
+                WarningCategory::StaleSourceFile,
+                format!(
+                    "Source file {file} was modified after the module was compiled, \
+                    line attributions in the report may be wrong"
+                ),
+            );
+        }
+
+        Self {
+            file: file.into(),
+            md5,
+            modified,
+            lines,
+            stale,
+        }
+    }
+}
+
+/// Collect checksums and metadata of all source files that contain mutants
+fn collect_source_file_infos(
+    executed_mutants: &[ReportableMutant],
+    path_rewriter: Option<&PathRewriter>,
+) -> Vec<SourceFileInfo> {
+    map_mutants_to_files(executed_mutants, path_rewriter)
+        .iter()
+        .map(|(file, line_number_map)| {
+            let max_line = line_number_map.keys().last().copied().unwrap_or(0);
+            SourceFileInfo::collect(file, max_line)
+        })
+        .collect()
+}
+
 fn read_lines<P>(filename: P) -> Result<Lines<BufReader<File>>>
 where
     P: AsRef<Path>,
@@ -119,6 +570,140 @@ where
     Ok(BufReader::new(file).lines())
 }
 
+/// Rating of a mutation score, based on `ScoreThresholds`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ScoreLevel {
+    Danger,
+    Warning,
+    Success,
+}
+
+/// Mutation score thresholds, used for coloring reports and badges
+/// and as the default of `--fail-under`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoreThresholds {
+    /// Scores below this value are considered bad (red)
+    pub warning: f32,
+
+    /// Scores below this value are considered mediocre (yellow),
+    /// all others are considered good (green)
+    pub success: f32,
+}
+
+impl Default for ScoreThresholds {
+    fn default() -> Self {
+        Self {
+            warning: 50.0,
+            success: 75.0,
+        }
+    }
+}
+
+impl ScoreThresholds {
+    /// Check that both thresholds are percentages and `warning <= success`
+    pub fn validate(&self) -> Result<()> {
+        let ScoreThresholds { warning, success } = *self;
+
+        if !(0.0..=100.0).contains(&warning)
+            || !(0.0..=100.0).contains(&success)
+            || warning > success
+        {
+            return Err(anyhow!(
+                "thresholds must be values between 0 and 100, with warning <= success, \
+                got warning = {warning}, success = {success}"
+            ))
+            .context(ErrorKind::Config);
+        }
+
+        Ok(())
+    }
+
+    /// Rate a mutation score
+    pub fn level(&self, score: f32) -> ScoreLevel {
+        if score < self.warning {
+            ScoreLevel::Danger
+        } else if score < self.success {
+            ScoreLevel::Warning
+        } else {
+            ScoreLevel::Success
+        }
+    }
+}
+
+/// Information about a mutation run, included in all reports
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct RunMetadata {
+    /// Version of wasmut that performed the run
+    pub version: String,
+    /// Duration of the complete run in milliseconds
+    pub duration: u64,
+    /// Number of worker threads
+    pub threads: usize,
+    /// Execution mode, either "meta-mutant" or "one-by-one"
+    pub engine_mode: String,
+    /// Compiler backend used for code generation
+    pub compiler: String,
+    /// Whether only a selection of the mutants was executed (`--mutant-ids`),
+    /// so that the mutation score does not cover the whole module
+    #[serde(default)]
+    pub partial: bool,
+    /// Number of mutation candidates excluded by the filter configuration
+    #[serde(default)]
+    pub filtered_mutants: usize,
+    /// Part of the module in the scope of the filter configuration,
+    /// `None` if the filters do not exclude any instructions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<FilterScope>,
+    /// Size of every meta-mutant compared to the original module.
+    /// Empty in one-by-one mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub meta_mutant_sizes: Vec<MetaMutantSize>,
+    /// Number of instructions executed per second, used to convert execution
+    /// costs into approximate time. `None` if no calibration is available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions_per_second: Option<u64>,
+}
+
+impl RunMetadata {
+    /// Single line summary, e.g. for the CLI report
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "wasmut {}, {:.1}s, {} threads, {} mode, {} compiler",
+            self.version,
+            self.duration as f64 / 1000.0,
+            self.threads,
+            self.engine_mode,
+            self.compiler
+        );
+        if let Some(rate) = self.instructions_per_second {
+            summary += &format!(", ~{:.1}M instructions/s", rate as f64 / 1e6);
+        }
+        summary
+    }
+
+    /// Mark the run as partial, i.e. only a selection of the mutants was executed
+    pub fn with_partial(self, partial: bool) -> Self {
+        Self { partial, ..self }
+    }
+
+    /// Set the number of mutation candidates excluded by the filter configuration
+    pub fn with_filtered_mutants(self, filtered_mutants: usize) -> Self {
+        Self {
+            filtered_mutants,
+            ..self
+        }
+    }
+
+    /// Set the part of the module in scope, if the filters exclude any instructions
+    pub fn with_scope(self, scope: FilterScope) -> Self {
+        Self {
+            scope: scope.is_restricted().then_some(scope),
+            ..self
+        }
+    }
+}
+
 #[derive(Serialize, Clone)]
 pub struct AccumulatedOutcomes {
     pub total: i32,
@@ -126,42 +711,113 @@ pub struct AccumulatedOutcomes {
     pub timeout: i32,
     pub killed: i32,
     pub error: i32,
+    /// Mutants skipped because they are not covered by the tests
     pub skipped: i32,
+    /// Mutation candidates excluded by the filter configuration, not included in `total`
+    pub filtered: i32,
+    /// Mutants not executed because the time budget of the run was exhausted,
+    /// included in `total` but not in the mutation score
+    pub not_run: i32,
+    /// Mutants that could not be executed because of an error of wasmut
+    /// or the runtime, included in `total` but not in the mutation score
+    pub internal_error: i32,
     pub mutation_score: f32,
 }
 
+impl AccumulatedOutcomes {
+    /// Combine the outcomes of several runs
+    pub fn combine<'b, I: IntoIterator<Item = &'b AccumulatedOutcomes>>(outcomes: I) -> Self {
+        let mut combined = outcomes.into_iter().fold(
+            AccumulatedOutcomes {
+                total: 0,
+                alive: 0,
+                timeout: 0,
+                killed: 0,
+                error: 0,
+                skipped: 0,
+                filtered: 0,
+                not_run: 0,
+                internal_error: 0,
+                mutation_score: 0.0,
+            },
+            |acc, o| AccumulatedOutcomes {
+                total: acc.total + o.total,
+                alive: acc.alive + o.alive,
+                timeout: acc.timeout + o.timeout,
+                killed: acc.killed + o.killed,
+                error: acc.error + o.error,
+                skipped: acc.skipped + o.skipped,
+                filtered: acc.filtered + o.filtered,
+                not_run: acc.not_run + o.not_run,
+                internal_error: acc.internal_error + o.internal_error,
+                mutation_score: 0.0,
+            },
+        );
+
+        combined.mutation_score = mutation_score(
+            combined.timeout + combined.killed + combined.error,
+            combined.total - combined.not_run - combined.internal_error,
+        );
+        combined
+    }
+
+    /// Set the number of mutation candidates excluded by the filter configuration
+    pub fn with_filtered(self, filtered: usize) -> Self {
+        Self {
+            filtered: filtered as i32,
+            ..self
+        }
+    }
+}
+
 impl AsRef<ReportableMutant> for ReportableMutant {
     fn as_ref(&self) -> &ReportableMutant {
         self
     }
 }
 
+/// Percentage of `detected` in `scored` mutants, 0 if no mutant was scored
+fn mutation_score(detected: i32, scored: i32) -> f32 {
+    if scored == 0 {
+        0.0
+    } else {
+        100f32 * detected as f32 / scored as f32
+    }
+}
+
 pub fn accumulate_outcomes<E: AsRef<ReportableMutant>>(
     executed_mutants: &[E],
 ) -> AccumulatedOutcomes {
-    let (alive, timeout, killed, error, skipped) =
-        executed_mutants.iter().map(|e| e.as_ref()).fold(
-            (0, 0, 0, 0, 0),
-            |(alive, timeout, killed, error, skipped), outcome| match outcome.outcome {
-                MutationOutcome::Alive => (alive + 1, timeout, killed, error, skipped),
-                MutationOutcome::Killed => (alive, timeout, killed + 1, error, skipped),
-                MutationOutcome::Timeout => (alive, timeout + 1, killed, error, skipped),
-                MutationOutcome::Error => (alive, timeout, killed, error + 1, skipped),
-                MutationOutcome::Skipped => (alive, timeout, killed, error, skipped + 1),
-            },
-        );
-    let mutation_score = 100f32 * (timeout + killed + error) as f32
-        / (alive + timeout + killed + error + skipped) as f32;
-
-    AccumulatedOutcomes {
+    let mut outcomes = AccumulatedOutcomes {
         total: executed_mutants.len() as i32,
-        alive,
-        timeout,
-        killed,
-        error,
-        skipped,
-        mutation_score,
+        alive: 0,
+        timeout: 0,
+        killed: 0,
+        error: 0,
+        skipped: 0,
+        filtered: 0,
+        not_run: 0,
+        internal_error: 0,
+        mutation_score: 0.0,
+    };
+
+    for mutant in executed_mutants.iter().map(|e| e.as_ref()) {
+        match mutant.outcome {
+            MutationOutcome::Alive => outcomes.alive += 1,
+            MutationOutcome::Killed => outcomes.killed += 1,
+            MutationOutcome::Timeout => outcomes.timeout += 1,
+            MutationOutcome::Error => outcomes.error += 1,
+            MutationOutcome::Skipped => outcomes.skipped += 1,
+            MutationOutcome::NotRun => outcomes.not_run += 1,
+            MutationOutcome::InternalError => outcomes.internal_error += 1,
+        }
     }
+
+    outcomes.mutation_score = mutation_score(
+        outcomes.timeout + outcomes.killed + outcomes.error,
+        outcomes.alive + outcomes.timeout + outcomes.killed + outcomes.error + outcomes.skipped,
+    );
+    outcomes
 }
 
 pub fn accumulate_outcomes_for_file(mutants: &LineNumberMutantMap) -> AccumulatedOutcomes {
@@ -180,12 +836,10 @@ struct SyntectContext {
 }
 
 impl SyntectContext {
-    fn new(theme_name: &str) -> Self {
+    fn new(theme_name: &str, syntax_set: SyntaxSet) -> Self {
         let ts = syntect::highlighting::ThemeSet::load_defaults();
         let theme = ts.themes[theme_name].clone();
 
-        let syntax_set = syntect::parsing::SyntaxSet::load_defaults_newlines();
-
         Self { syntax_set, theme }
     }
 
@@ -199,10 +853,34 @@ impl SyntectContext {
 
 impl Default for SyntectContext {
     fn default() -> Self {
-        Self::new("InspiredGitHub")
+        Self::new("InspiredGitHub", SyntaxSet::load_defaults_newlines())
     }
 }
 
+/// Load the default syntax definitions of syntect, extended by the
+/// `.sublime-syntax` files in `directories` (`[report] syntax_dirs`).
+///
+/// Definitions from the directories take precedence over the default
+/// definitions for the same file extension.
+pub fn load_syntax_set(directories: &[String]) -> Result<SyntaxSet> {
+    if directories.is_empty() {
+        return Ok(SyntaxSet::load_defaults_newlines());
+    }
+
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+    for directory in directories {
+        if !Path::new(directory).is_dir() {
+            return Err(anyhow!("Syntax directory {directory} does not exist"))
+                .context(ErrorKind::Config);
+        }
+        builder
+            .add_from_folder(directory, true)
+            .with_context(|| format!("Could not load syntax definitions from {directory}"))
+            .context(ErrorKind::Config)?;
+    }
+    Ok(builder.build())
+}
+
 fn create_syntax_reference<P: AsRef<Path>>(
     syntax_set: &SyntaxSet,
     file: P,
@@ -239,13 +917,156 @@ impl<'a> SyntectFileContext<'a> {
             false,
         ))
     }
+
+    /// Highlight consecutive `lines`, so that constructs spanning
+    /// several lines (e.g. block comments) are highlighted correctly
+    fn terminal_lines(&self, lines: &[String]) -> Result<Vec<String>> {
+        let mut highlight = HighlightLines::new(self.syntax, &self.context.theme);
+        lines
+            .iter()
+            .map(|line| {
+                // The default syntaxes expect lines to end with a newline
+                let line = format!("{line}\n");
+                let regions = highlight.highlight_line(&line, &self.context.syntax_set)?;
+                let escaped = syntect::util::as_24_bit_terminal_escaped(&regions[..], false);
+                Ok(escaped.replacen('\n', "", 1))
+            })
+            .collect()
+    }
+}
+
+/// Builder for mutants in tests. Unless changed, the mutant replaces `i32.add`
+/// by `i32.sub` at offset 0, survived and has no source location.
+#[cfg(test)]
+pub(crate) struct MutantBuilder(ReportableMutant);
+
+#[cfg(test)]
+impl MutantBuilder {
+    pub fn new() -> Self {
+        use crate::operator::ops::BinaryOperatorAddToSub;
+        use wasmut_wasm::elements::Instruction;
+
+        Self(ReportableMutant {
+            id: String::new(),
+            location: CodeLocation::default(),
+            offset: 0,
+            outcome: MutationOutcome::Alive,
+            operator: Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap()),
+            execution_cost: None,
+            stack_trace: Vec::new(),
+            trace: None,
+            likely_equivalent: false,
+            condition: None,
+            internal_error: None,
+            variants: 1,
+            skip_reason: None,
+            nearest_covered: None,
+            killed_by: Vec::new(),
+        })
+    }
+
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.0.offset = offset;
+        self
+    }
+
+    pub fn with_outcome(mut self, outcome: MutationOutcome) -> Self {
+        self.0.outcome = outcome;
+        self
+    }
+
+    pub fn with_location(mut self, location: CodeLocation) -> Self {
+        self.0.location = location;
+        self
+    }
+
+    pub fn with_file(mut self, file: &str) -> Self {
+        self.0.location.file = Some(file.into());
+        self
+    }
+
+    pub fn with_function(mut self, function: &str) -> Self {
+        self.0.location.function = Some(function.into());
+        self
+    }
+
+    pub fn with_line(mut self, line: u64) -> Self {
+        self.0.location.line = Some(line);
+        self
+    }
+
+    pub fn with_column(mut self, column: u64) -> Self {
+        self.0.location.column = Some(column);
+        self
+    }
+
+    pub fn with_operator(mut self, operator: Box<dyn InstructionReplacement>) -> Self {
+        self.0.operator = operator;
+        self
+    }
+
+    /// Replace `i32.sub` by `i32.add` instead
+    pub fn with_sub_to_add(self) -> Self {
+        use crate::operator::ops::BinaryOperatorSubToAdd;
+        use wasmut_wasm::elements::Instruction;
+
+        self.with_operator(Box::new(
+            BinaryOperatorSubToAdd::new(&Instruction::I32Sub).unwrap(),
+        ))
+    }
+
+    pub fn with_execution_cost(mut self, execution_cost: Option<u64>) -> Self {
+        self.0.execution_cost = execution_cost;
+        self
+    }
+
+    pub fn with_stack_trace(mut self, stack_trace: Vec<CodeLocation>) -> Self {
+        self.0.stack_trace = stack_trace;
+        self
+    }
+
+    pub fn with_internal_error(mut self, message: &str) -> Self {
+        self.0.internal_error = Some(message.into());
+        self
+    }
+
+    pub fn with_skip_reason(
+        mut self,
+        skip_reason: Option<SkipReason>,
+        nearest_covered: Option<CodeLocation>,
+    ) -> Self {
+        self.0.skip_reason = skip_reason;
+        self.0.nearest_covered = nearest_covered;
+        self
+    }
+
+    pub fn with_condition(mut self, condition: ComparisonProfile) -> Self {
+        self.0.condition = Some(condition);
+        self
+    }
+
+    pub fn with_killed_by(mut self, killed_by: &[&str]) -> Self {
+        self.0.killed_by = killed_by.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// The ID is derived from the function name, the offset and the operator
+    pub fn build(self) -> ReportableMutant {
+        let function = self.0.location.function.as_deref().unwrap_or_default();
+        ReportableMutant {
+            id: crate::mutation::mutant_id(function, self.0.offset, &*self.0.operator),
+            ..self.0
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use wasmut_wasm::elements::Instruction;
 
-    use crate::operator::ops::BinaryOperatorAddToSub;
+    use crate::operator::ops::{
+        BinaryOperatorAddToSub, BinaryOperatorMulToDivS, BinaryOperatorMulToDivU,
+    };
 
     use super::*;
 
@@ -256,6 +1077,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn external_syntax_definitions() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("Zig.sublime-syntax"),
+            "%YAML 1.2\n---\nname: Zig\nfile_extensions: [zig]\nscope: source.zig\n\
+            contexts:\n  main:\n    - match: '\\b(fn|const|return)\\b'\n      \
+            scope: keyword.control.zig\n",
+        )?;
+
+        let default = SyntectContext::default();
+        assert_eq!(&default.file_context("main.zig")?.syntax.name, "Plain Text");
+
+        let directory = dir.path().to_str().unwrap().to_string();
+        let syntax_set = load_syntax_set(&[directory])?;
+        let ctx = SyntectContext::new("InspiredGitHub", syntax_set);
+        assert_eq!(&ctx.file_context("main.zig")?.syntax.name, "Zig");
+        assert_eq!(&ctx.file_context("main.c")?.syntax.name, "C");
+
+        let missing = dir.path().join("missing").to_str().unwrap().to_string();
+        let error = load_syntax_set(&[missing]).unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&ErrorKind::Config));
+        Ok(())
+    }
+
     #[test]
     fn no_extension() -> Result<()> {
         let ctx = SyntectContext::default();
@@ -263,6 +1109,398 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn render_mutant_trace() {
+        let mutant = MutantBuilder::new()
+            .with_file("test.c")
+            .with_function("add")
+            .with_line(3)
+            .with_column(14)
+            .with_offset(42)
+            .build();
+
+        let trace = MutantTrace {
+            id: 42,
+            calls: vec![
+                CodeLocation {
+                    function: Some("main".into()),
+                    ..Default::default()
+                },
+                mutant.location.clone(),
+            ],
+            truncated: true,
+            stdout: "hello".into(),
+            stderr: String::new(),
+        };
+
+        assert_eq!(trace.file_name(), "mutant-42.txt");
+
+        let content = trace.render(&mutant);
+        assert!(content.contains("Location: add at test.c:3"));
+        assert!(content.contains("     0: main\n     1: add at test.c:3\n"));
+        assert!(content.contains("(truncated)"));
+        assert!(content.contains("stdout:\nhello"));
+    }
+
+    #[test]
+    fn internal_errors_in_json_report() -> Result<()> {
+        let mutant = MutantBuilder::new()
+            .with_offset(42)
+            .with_outcome(MutationOutcome::InternalError)
+            .with_internal_error("Failed to create runtime")
+            .build();
+        let killed = MutantBuilder::new()
+            .with_offset(43)
+            .with_outcome(MutationOutcome::Killed)
+            .build();
+
+        let reporter = json::JSONReporter::new(
+            &crate::config::ReportConfig::default(),
+            "test.wasm",
+            &RunMetadata::default(),
+        )?;
+        let report = reporter.create_report(&[mutant, killed]);
+
+        // Internal errors say nothing about the tests, so they are not scored
+        assert_eq!(report.summary.error, 0);
+        assert_eq!(report.summary.internal_errors, 1);
+        assert_eq!(report.summary.mutation_score, 100.0);
+        assert_eq!(report.mutants[0].outcome, "internal_error");
+        assert_eq!(
+            report.mutants[0].internal_error.as_deref(),
+            Some("Failed to create runtime")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn skip_reasons_in_json_report() -> Result<()> {
+        let mutant = |skip_reason, nearest_covered| {
+            MutantBuilder::new()
+                .with_offset(42)
+                .with_outcome(MutationOutcome::Skipped)
+                .with_skip_reason(skip_reason, nearest_covered)
+                .build()
+        };
+
+        let mutants = [
+            mutant(
+                Some(SkipReason::NotExecuted {
+                    nearest_covered: Some(30),
+                }),
+                Some(CodeLocation {
+                    line: Some(12),
+                    ..Default::default()
+                }),
+            ),
+            mutant(
+                Some(SkipReason::NotExecuted {
+                    nearest_covered: Some(30),
+                }),
+                None,
+            ),
+            mutant(Some(SkipReason::EdgeNotTaken), None),
+            mutant(None, None),
+        ];
+
+        let reporter = json::JSONReporter::new(
+            &crate::config::ReportConfig::default(),
+            "test.wasm",
+            &RunMetadata::default(),
+        )?;
+        let report = reporter.create_report(&mutants);
+
+        let reasons: Vec<Option<&str>> = report
+            .mutants
+            .iter()
+            .map(|mutant| mutant.skip_reason.as_deref())
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                Some("not executed, nearest executed line: 12"),
+                Some("not executed, nearest executed instruction: 0x1e"),
+                Some("the branch edge on which the mutant differs was never taken"),
+                None
+            ]
+        );
+        assert_eq!(report.mutants[0].nearest_covered_line, Some(12));
+        Ok(())
+    }
+
+    #[test]
+    fn condition_outcomes_in_json_report() -> Result<()> {
+        use crate::runtime::{BranchCoverage, ComparisonProfile};
+
+        let mutant = |taken, not_taken| {
+            MutantBuilder::new()
+                .with_condition(ComparisonProfile {
+                    results: BranchCoverage { taken, not_taken },
+                    equal_operands: false,
+                })
+                .build()
+        };
+        let mutants = [
+            mutant(true, false),
+            mutant(false, true),
+            mutant(true, true),
+            MutantBuilder::new().build(),
+        ];
+
+        let reporter = json::JSONReporter::new(
+            &crate::config::ReportConfig::default(),
+            "test.wasm",
+            &RunMetadata::default(),
+        )?;
+        let report = reporter.create_report(&mutants);
+
+        let conditions: Vec<Option<&str>> = report
+            .mutants
+            .iter()
+            .map(|mutant| mutant.condition.as_deref())
+            .collect();
+        assert_eq!(
+            conditions,
+            [
+                Some("always true"),
+                Some("always false"),
+                Some("mixed"),
+                None
+            ]
+        );
+        assert_eq!(
+            mutants[0].condition_explanation().as_deref(),
+            Some("condition always true")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn kill_criteria_in_reports() -> Result<()> {
+        let mutant = |outcome, killed_by: &[&str]| {
+            MutantBuilder::new()
+                .with_offset(42)
+                .with_outcome(outcome)
+                .with_execution_cost(Some(10))
+                .with_killed_by(killed_by)
+                .build()
+        };
+
+        let mutants = [
+            mutant(MutationOutcome::Killed, &["output_diff"]),
+            mutant(MutationOutcome::Killed, &["exit_code", "golden_file"]),
+            mutant(MutationOutcome::Alive, &[]),
+        ];
+        let explanations: Vec<Option<String>> =
+            mutants.iter().map(|m| m.kill_explanation()).collect();
+        assert_eq!(
+            explanations,
+            [
+                Some("killed by stdout diff".into()),
+                Some("killed by exit code and golden file".into()),
+                None
+            ]
+        );
+
+        let reporter = json::JSONReporter::new(
+            &crate::config::ReportConfig::default(),
+            "test.wasm",
+            &RunMetadata::default(),
+        )?;
+        let report = reporter.create_report(&mutants[..1]);
+        assert_eq!(report.mutants[0].killed_by, ["output_diff"]);
+        Ok(())
+    }
+
+    #[test]
+    fn group_operator_variants() {
+        let mutant = |offset, operator: Box<dyn InstructionReplacement>, outcome| {
+            MutantBuilder::new()
+                .with_offset(offset)
+                .with_operator(operator)
+                .with_outcome(outcome)
+                .build()
+        };
+        let div_s = || Box::new(BinaryOperatorMulToDivS::new(&Instruction::I32Mul).unwrap());
+        let div_u = || Box::new(BinaryOperatorMulToDivU::new(&Instruction::I32Mul).unwrap());
+        let add_to_sub = || Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap());
+
+        let grouped = group_variants(vec![
+            mutant(10, div_s(), MutationOutcome::Alive),
+            mutant(10, div_u(), MutationOutcome::Killed),
+            mutant(10, add_to_sub(), MutationOutcome::Alive),
+            mutant(20, div_s(), MutationOutcome::Alive),
+            mutant(20, div_u(), MutationOutcome::Skipped),
+            mutant(30, div_s(), MutationOutcome::Alive),
+        ]);
+
+        let summary: Vec<(u64, &str, MutationOutcome, usize)> = grouped
+            .iter()
+            .map(|m| {
+                (
+                    m.offset,
+                    m.operator.dyn_name(),
+                    m.outcome.clone(),
+                    m.variants,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (10, "binop_mul_to_div", MutationOutcome::Killed, 2),
+                (10, "binop_add_to_sub", MutationOutcome::Alive, 1),
+                (20, "binop_mul_to_div", MutationOutcome::Alive, 2),
+                (30, "binop_mul_to_div", MutationOutcome::Alive, 1),
+            ]
+        );
+        assert!(grouped[0].description().ends_with(", 2 variants)"));
+        assert!(!grouped[3].description().contains("variants"));
+
+        let acc = accumulate_outcomes(&grouped);
+        assert_eq!(acc.total, 4);
+        assert_eq!(acc.killed, 1);
+    }
+
+    #[test]
+    fn mutant_id_and_permalink() {
+        let mutant = |offset, instruction| {
+            MutantBuilder::new()
+                .with_offset(offset)
+                .with_operator(Box::new(BinaryOperatorAddToSub::new(&instruction).unwrap()))
+                .build()
+        };
+
+        let a = mutant(42, Instruction::I32Add);
+        let id = a.id();
+        assert_eq!(id.len(), 12);
+        assert_eq!(id, mutant(42, Instruction::I32Add).id());
+        assert_ne!(id, mutant(43, Instruction::I32Add).id());
+        assert_ne!(id, mutant(42, Instruction::I64Add).id());
+
+        let link = a.permalink("src/test.c").unwrap();
+        assert!(link.starts_with("test.c-"));
+        assert!(link.ends_with(&format!(".html#mutant-{id}")));
+    }
+
+    #[test]
+    fn mutants_ordered_independent_of_execution_order() {
+        let mutant = |file: &str, line, offset, add_to_sub| {
+            let mutant = MutantBuilder::new()
+                .with_file(file)
+                .with_line(line)
+                .with_offset(offset);
+            match add_to_sub {
+                true => mutant.build(),
+                false => mutant.with_sub_to_add().build(),
+            }
+        };
+
+        let mutants = vec![
+            mutant("b.c", 1, 10, true),
+            mutant("a.c", 2, 30, false),
+            mutant("a.c", 2, 20, false),
+            mutant("a.c", 2, 20, true),
+            mutant("a.c", 1, 40, true),
+        ];
+
+        let mapping = map_mutants_to_files(&mutants, None);
+        let line: Vec<(u64, &str)> = mapping["a.c"][&2]
+            .iter()
+            .map(|m| (m.offset, m.operator.dyn_name()))
+            .collect();
+        assert_eq!(
+            line,
+            [
+                (20, "binop_add_to_sub"),
+                (20, "binop_sub_to_add"),
+                (30, "binop_sub_to_add")
+            ]
+        );
+
+        let mut sorted: Vec<&ReportableMutant> = mutants.iter().collect();
+        sorted.sort_by(|a, b| a.report_order(b));
+        let offsets: Vec<u64> = sorted.iter().map(|m| m.offset).collect();
+        assert_eq!(offsets, [40, 20, 20, 30, 10]);
+    }
+
+    #[test]
+    fn function_containing_offset() {
+        let functions = vec![(10, "func[1]".to_string()), (20, "main".to_string())];
+
+        assert_eq!(function_at(&functions, 5), None);
+        assert_eq!(function_at(&functions, 10).as_deref(), Some("func[1]"));
+        assert_eq!(function_at(&functions, 19).as_deref(), Some("func[1]"));
+        assert_eq!(function_at(&functions, 25).as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn run_metadata_summary() {
+        let metadata = RunMetadata {
+            version: "1.2.3".into(),
+            duration: 4250,
+            threads: 8,
+            engine_mode: "one-by-one".into(),
+            compiler: "Singlepass".into(),
+            partial: false,
+            filtered_mutants: 0,
+            scope: None,
+            meta_mutant_sizes: Vec::new(),
+            instructions_per_second: None,
+        };
+        assert_eq!(
+            metadata.summary(),
+            "wasmut 1.2.3, 4.2s, 8 threads, one-by-one mode, Singlepass compiler"
+        );
+
+        let metadata = RunMetadata {
+            instructions_per_second: Some(250_000_000),
+            ..metadata
+        };
+        assert_eq!(
+            metadata.summary(),
+            "wasmut 1.2.3, 4.2s, 8 threads, one-by-one mode, Singlepass compiler, \
+            ~250.0M instructions/s"
+        );
+    }
+
+    #[test]
+    fn source_file_info_up_to_date() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("test.c");
+        std::fs::write(&file, "int a;\nint b;\n")?;
+        let file = file.to_str().unwrap();
+
+        let info = SourceFileInfo::collect(file, 2);
+        assert_eq!(info.lines, Some(2));
+        assert_eq!(
+            info.md5.as_deref(),
+            Some(format!("{:?}", md5::compute("int a;\nint b;\n")).as_str())
+        );
+        assert!(info.modified.is_some());
+        assert!(!info.stale);
+        Ok(())
+    }
+
+    #[test]
+    fn source_file_info_stale() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("test.c");
+        std::fs::write(&file, "int a;\n")?;
+
+        let info = SourceFileInfo::collect(file.to_str().unwrap(), 5);
+        assert!(info.stale);
+        Ok(())
+    }
+
+    #[test]
+    fn source_file_info_missing_file() {
+        let info = SourceFileInfo::collect("testdata/invalid/invalid.c", 5);
+        assert_eq!(info.md5, None);
+        assert_eq!(info.lines, None);
+        assert!(!info.stale);
+    }
+
     #[test]
     fn prepare_results_empty_lists() -> Result<()> {
         let module = WasmModule::from_file("testdata/simple_add/test.wasm")?;
@@ -284,6 +1522,12 @@ mod tests {
                 mutation_operator: Box::new(
                     BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap(),
                 ),
+                survived: true,
+                trace: None,
+                likely_equivalent: false,
+                condition: None,
+                skip_reason: None,
+                killed_by: Vec::new(),
             },
             ExecutedMutant {
                 offset: 34,
@@ -294,6 +1538,12 @@ mod tests {
                 mutation_operator: Box::new(
                     BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap(),
                 ),
+                survived: false,
+                trace: None,
+                likely_equivalent: false,
+                condition: None,
+                skip_reason: None,
+                killed_by: Vec::new(),
             },
             ExecutedMutant {
                 offset: 34,
@@ -301,13 +1551,30 @@ mod tests {
                 mutation_operator: Box::new(
                     BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap(),
                 ),
+                survived: false,
+                trace: None,
+                likely_equivalent: false,
+                condition: None,
+                skip_reason: None,
+                killed_by: Vec::new(),
             },
             ExecutedMutant {
                 offset: 34,
-                result: ExecutionResult::Error,
+                result: ExecutionResult::Error {
+                    stack_trace: vec![StackFrame {
+                        function_number: 1,
+                        function_offset: 0,
+                    }],
+                },
                 mutation_operator: Box::new(
                     BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap(),
                 ),
+                survived: false,
+                trace: None,
+                likely_equivalent: false,
+                condition: None,
+                skip_reason: None,
+                killed_by: Vec::new(),
             },
             ExecutedMutant {
                 offset: 34,
@@ -315,6 +1582,12 @@ mod tests {
                 mutation_operator: Box::new(
                     BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap(),
                 ),
+                survived: false,
+                trace: None,
+                likely_equivalent: false,
+                condition: None,
+                skip_reason: None,
+                killed_by: Vec::new(),
             },
         ];
 
@@ -336,6 +1609,27 @@ mod tests {
         assert!(results[1].outcome == MutationOutcome::Killed);
         assert!(results[2].outcome == MutationOutcome::Timeout);
         assert!(results[3].outcome == MutationOutcome::Error);
+        assert_eq!(results[3].stack_trace.len(), 1);
+        assert!(results[0].stack_trace.is_empty());
         assert!(results[4].outcome == MutationOutcome::Skipped);
     }
+
+    #[test]
+    fn mutation_score_without_scored_mutants() {
+        let not_run = [MutantBuilder::new()
+            .with_offset(42)
+            .with_outcome(MutationOutcome::NotRun)
+            .build()];
+        let outcomes = accumulate_outcomes(&not_run);
+        assert_eq!(outcomes.mutation_score, 0.0);
+
+        let combined = AccumulatedOutcomes::combine([
+            &outcomes,
+            &accumulate_outcomes::<ReportableMutant>(&[]),
+        ]);
+        assert_eq!(combined.total, 1);
+        assert_eq!(combined.not_run, 1);
+        assert_eq!(combined.mutation_score, 0.0);
+        assert_eq!(AccumulatedOutcomes::combine([]).mutation_score, 0.0);
+    }
 }
@@ -0,0 +1,149 @@
+//! Redaction of reports (`wasmut mutate --redact-paths`), so that they can be
+//! shared externally, e.g. with the authors of wasmut for debugging, without
+//! revealing the structure of the mutated code.
+//!
+//! Source paths and function names are replaced by pseudonyms derived from
+//! their hash, e.g. `file-1f0e3dad.c` and `function-5d41402a`. The pseudonyms
+//! are stable, so the same file or function has the same pseudonym in every
+//! report. The output of traced mutants and the messages of internal errors
+//! and warnings may contain paths as well, they are left out.
+
+use std::path::Path;
+
+use crate::{addressresolver::CodeLocation, warnings::Warning};
+
+use super::ReportableMutant;
+
+/// Replacement of messages that are left out of redacted reports
+pub const REDACTED: &str = "<redacted>";
+
+/// First 8 hex digits of the MD5 hash of `name`
+fn hash(name: &str) -> String {
+    format!("{:?}", md5::compute(name))[..8].into()
+}
+
+/// Pseudonym of a source file. The extension is kept, so that reports
+/// still show the language of the file.
+pub fn redact_file(path: &str) -> String {
+    match Path::new(path).extension() {
+        Some(extension) => format!("file-{}.{}", hash(path), extension.to_string_lossy()),
+        None => format!("file-{}", hash(path)),
+    }
+}
+
+/// Pseudonym of a function
+pub fn redact_function(name: &str) -> String {
+    format!("function-{}", hash(name))
+}
+
+/// Source location with pseudonyms for the file and function
+fn redact_location(location: &CodeLocation) -> CodeLocation {
+    CodeLocation {
+        file: location.file.as_deref().map(redact_file),
+        function: location.function.as_deref().map(redact_function),
+        line: location.line,
+        column: location.column,
+        raw_file: None,
+    }
+}
+
+/// Replace source paths and function names of `mutants` by pseudonyms,
+/// and leave out the output of traces and internal error messages
+pub fn redact_mutants(mutants: Vec<ReportableMutant>) -> Vec<ReportableMutant> {
+    mutants
+        .into_iter()
+        .map(|mutant| ReportableMutant {
+            location: redact_location(&mutant.location),
+            stack_trace: mutant.stack_trace.iter().map(redact_location).collect(),
+            trace: mutant.trace.map(|trace| super::MutantTrace {
+                calls: trace.calls.iter().map(redact_location).collect(),
+                stdout: String::new(),
+                stderr: String::new(),
+                ..trace
+            }),
+            internal_error: mutant.internal_error.map(|_| REDACTED.into()),
+            nearest_covered: mutant.nearest_covered.as_ref().map(redact_location),
+            ..mutant
+        })
+        .collect()
+}
+
+/// Leave out the messages of `warnings`, keeping their categories
+pub fn redact_warnings(warnings: Vec<Warning>) -> Vec<Warning> {
+    warnings
+        .into_iter()
+        .map(|warning| Warning {
+            message: REDACTED.into(),
+            ..warning
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        reporter::{MutantBuilder, MutationOutcome},
+        warnings::WarningCategory,
+    };
+
+    fn location(file: &str, function: &str, line: u64) -> CodeLocation {
+        CodeLocation {
+            file: Some(file.into()),
+            function: Some(function.into()),
+            line: Some(line),
+            column: Some(3),
+            raw_file: Some(format!("/build/../{file}")),
+        }
+    }
+
+    #[test]
+    fn pseudonyms() {
+        assert_eq!(redact_file("/src/secret/add.c"), "file-90a10279.c");
+        assert_eq!(
+            redact_file("/src/secret/add.c"),
+            redact_file("/src/secret/add.c")
+        );
+        assert_ne!(redact_file("/src/secret/add.c"), redact_file("/src/add.c"));
+        assert!(!redact_file("Makefile").contains('.'));
+        assert!(redact_function("secret_algorithm").starts_with("function-"));
+        assert!(!redact_function("secret_algorithm").contains("secret"));
+    }
+
+    #[test]
+    fn redacted_mutants() {
+        let mutant = MutantBuilder::new()
+            .with_location(location("/src/secret/add.c", "secret_add", 4))
+            .with_offset(42)
+            .with_outcome(MutationOutcome::Error)
+            .with_stack_trace(vec![location("/src/secret/main.c", "main", 10)])
+            .with_internal_error("failed in secret_add")
+            .build();
+
+        let redacted = redact_mutants(vec![mutant]);
+        let mutant = &redacted[0];
+        assert_eq!(mutant.location.file, Some(redact_file("/src/secret/add.c")));
+        assert_eq!(
+            mutant.location.function,
+            Some(redact_function("secret_add"))
+        );
+        assert_eq!(mutant.location.line, Some(4));
+        assert_eq!(mutant.location.raw_file, None);
+        assert_eq!(mutant.offset, 42);
+        assert_eq!(
+            mutant.stack_trace[0].function,
+            Some(redact_function("main"))
+        );
+        assert_eq!(mutant.internal_error.as_deref(), Some(REDACTED));
+
+        let warnings = redact_warnings(vec![Warning {
+            category: WarningCategory::UnresolvableSourceFile,
+            message: "Could not read source file /src/secret/add.c".into(),
+        }]);
+        assert_eq!(
+            warnings[0].category,
+            WarningCategory::UnresolvableSourceFile
+        );
+        assert_eq!(warnings[0].message, REDACTED);
+    }
+}
@@ -0,0 +1,204 @@
+//! Ranking of mutants and functions by execution cost, showing which
+//! filters or test changes would speed up future runs the most.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{rewriter::PathRewriter, ReportableMutant};
+
+/// Maximum number of mutants and functions in the ranking
+const RANKING_LENGTH: usize = 10;
+
+/// Name used for mutants without function information
+const UNKNOWN_FUNCTION: &str = "<unknown>";
+
+/// A single mutant and the number of instructions it executed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SlowMutant {
+    pub id: String,
+    pub description: String,
+    pub file: Option<String>,
+    pub function: Option<String>,
+    pub line: Option<u64>,
+    pub outcome: String,
+    pub execution_cost: u64,
+    /// Link to the mutant in the HTML report, relative to the report directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+/// Accumulated execution cost of all mutants in a function
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExpensiveFunction {
+    pub function: String,
+    /// Number of mutants that terminated normally
+    pub mutants: i32,
+    pub execution_cost: u64,
+}
+
+/// Mutants and functions with the highest execution cost.
+/// Only mutants that terminated normally have an execution cost.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct SlowestRanking {
+    pub mutants: Vec<SlowMutant>,
+    pub functions: Vec<ExpensiveFunction>,
+}
+
+impl SlowestRanking {
+    /// Rank the executed mutants and their functions by execution cost
+    pub fn new(
+        executed_mutants: &[ReportableMutant],
+        path_rewriter: Option<&PathRewriter>,
+    ) -> Self {
+        let mut functions: BTreeMap<&str, (i32, u64)> = BTreeMap::new();
+        let mut mutants: Vec<(&ReportableMutant, u64)> = Vec::new();
+
+        for mutant in executed_mutants {
+            let execution_cost = match mutant.execution_cost {
+                Some(execution_cost) => execution_cost,
+                None => continue,
+            };
+            mutants.push((mutant, execution_cost));
+
+            let function = mutant
+                .location
+                .function
+                .as_deref()
+                .unwrap_or(UNKNOWN_FUNCTION);
+            let (count, cost) = functions.entry(function).or_default();
+            *count += 1;
+            *cost += execution_cost;
+        }
+
+        // Sorting is stable, so ties keep the report order
+        mutants.sort_by(|(a, _), (b, _)| a.report_order(b));
+        mutants.sort_by_key(|(_, execution_cost)| std::cmp::Reverse(*execution_cost));
+        mutants.truncate(RANKING_LENGTH);
+
+        let mutants = mutants
+            .into_iter()
+            .map(|(mutant, execution_cost)| {
+                let file = mutant
+                    .location
+                    .file
+                    .as_deref()
+                    .map(|file| match path_rewriter {
+                        Some(path_rewriter) => path_rewriter.rewrite(file),
+                        None => file.into(),
+                    });
+                let outcome: String = mutant.outcome.clone().into();
+
+                SlowMutant {
+                    id: mutant.id(),
+                    description: mutant.description(),
+                    link: file.as_deref().and_then(|file| mutant.permalink(file)),
+                    file,
+                    function: mutant.location.function.clone(),
+                    line: mutant.location.line,
+                    outcome: outcome.to_lowercase(),
+                    execution_cost,
+                }
+            })
+            .collect();
+
+        let mut functions: Vec<ExpensiveFunction> = functions
+            .into_iter()
+            .map(|(function, (mutants, execution_cost))| ExpensiveFunction {
+                function: function.into(),
+                mutants,
+                execution_cost,
+            })
+            .collect();
+        functions.sort_by_key(|function| std::cmp::Reverse(function.execution_cost));
+        functions.truncate(RANKING_LENGTH);
+
+        Self { mutants, functions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporter::{MutantBuilder, MutationOutcome};
+
+    fn mutant(function: &str, offset: u64, execution_cost: Option<u64>) -> ReportableMutant {
+        MutantBuilder::new()
+            .with_file("/src/a.c")
+            .with_function(function)
+            .with_line(offset)
+            .with_offset(offset)
+            .with_outcome(match execution_cost {
+                Some(_) => MutationOutcome::Alive,
+                None => MutationOutcome::Timeout,
+            })
+            .with_execution_cost(execution_cost)
+            .build()
+    }
+
+    #[test]
+    fn rank_mutants_and_functions() {
+        let mutants = vec![
+            mutant("f", 1, Some(100)),
+            mutant("f", 2, Some(100)),
+            mutant("g", 3, Some(150)),
+            mutant("g", 4, None),
+            mutant("h", 5, Some(10)),
+        ];
+
+        let path_rewriter = PathRewriter::new("^/src/", "").unwrap();
+        let ranking = SlowestRanking::new(&mutants, Some(&path_rewriter));
+
+        let ranked: Vec<(u64, u64)> = ranking
+            .mutants
+            .iter()
+            .map(|mutant| (mutant.line.unwrap(), mutant.execution_cost))
+            .collect();
+        assert_eq!(ranked, vec![(3, 150), (1, 100), (2, 100), (5, 10)]);
+        assert_eq!(ranking.mutants[0].file.as_deref(), Some("a.c"));
+        assert_eq!(ranking.mutants[0].outcome, "alive");
+
+        assert_eq!(
+            ranking.functions,
+            vec![
+                ExpensiveFunction {
+                    function: "f".into(),
+                    mutants: 2,
+                    execution_cost: 200,
+                },
+                ExpensiveFunction {
+                    function: "g".into(),
+                    mutants: 1,
+                    execution_cost: 150,
+                },
+                ExpensiveFunction {
+                    function: "h".into(),
+                    mutants: 1,
+                    execution_cost: 10,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ranking_is_limited() {
+        let mutants: Vec<ReportableMutant> = (0..20)
+            .map(|i| mutant(&format!("f{i}"), i, Some(i)))
+            .collect();
+        let ranking = SlowestRanking::new(&mutants, None);
+
+        assert_eq!(ranking.mutants.len(), RANKING_LENGTH);
+        assert_eq!(ranking.functions.len(), RANKING_LENGTH);
+        assert_eq!(ranking.mutants[0].execution_cost, 19);
+        assert_eq!(ranking.functions[0].function, "f19");
+    }
+
+    #[test]
+    fn no_execution_costs() {
+        let mutants = vec![mutant("f", 1, None)];
+        assert_eq!(
+            SlowestRanking::new(&mutants, None),
+            SlowestRanking::default()
+        );
+    }
+}
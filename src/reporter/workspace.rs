@@ -0,0 +1,165 @@
+use std::{collections::BTreeMap, fs::File, io::BufWriter, path::Path};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{html, AccumulatedOutcomes, ScoreThresholds};
+
+/// Result of a single workspace target
+#[derive(Serialize)]
+pub struct TargetSummary {
+    pub name: String,
+    pub module: String,
+    /// Outcomes of all mutants, if the target was executed successfully
+    pub outcomes: Option<AccumulatedOutcomes>,
+    /// Error message, if the target failed
+    pub error: Option<String>,
+    /// Link to the report of the target, relative to the dashboard
+    pub link: Option<String>,
+}
+
+/// Combined outcomes of all successfully executed targets
+fn combined_outcomes(targets: &[TargetSummary]) -> AccumulatedOutcomes {
+    AccumulatedOutcomes::combine(targets.iter().filter_map(|t| t.outcomes.as_ref()))
+}
+
+/// Log a summary table of all targets
+pub fn log_dashboard(targets: &[TargetSummary]) {
+    log::info!(
+        "{0:20} {1:>8} {2:>8} {3:>8} {4:>8}",
+        "Target",
+        "Mutants",
+        "Killed",
+        "Alive",
+        "Score"
+    );
+
+    for target in targets {
+        match (&target.outcomes, &target.error) {
+            (Some(o), _) => log::info!(
+                "{0:20} {1:>8} {2:>8} {3:>8} {4:>7.1}%",
+                target.name,
+                o.total,
+                o.killed,
+                o.alive,
+                o.mutation_score
+            ),
+            (None, error) => log::info!(
+                "{0:20} failed: {1}",
+                target.name,
+                error.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+
+    let total = combined_outcomes(targets);
+    log::info!(
+        "{0:20} {1:>8} {2:>8} {3:>8} {4:>7.1}%",
+        "Total",
+        total.total,
+        total.killed,
+        total.alive,
+        total.mutation_score
+    );
+}
+
+/// Write an HTML dashboard linking to the reports of all targets
+pub fn write_dashboard(
+    output_directory: &Path,
+    targets: &[TargetSummary],
+    thresholds: ScoreThresholds,
+) -> Result<()> {
+    std::fs::create_dir_all(output_directory)?;
+    html::write_static_files(output_directory)?;
+
+    let template_engine = html::create_template_engine(thresholds);
+
+    let data = BTreeMap::from([
+        ("targets", handlebars::to_json(targets)),
+        ("file", handlebars::to_json::<Option<String>>(None)),
+        ("report_info", handlebars::to_json(html::ReportInfo::new())),
+        ("stats", handlebars::to_json(combined_outcomes(targets))),
+    ]);
+
+    let writer = BufWriter::new(File::create(output_directory.join("index.html"))?);
+    template_engine.render_to_write("workspace", &data, writer)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcomes(killed: i32, alive: i32) -> AccumulatedOutcomes {
+        AccumulatedOutcomes {
+            total: killed + alive,
+            alive,
+            timeout: 0,
+            killed,
+            error: 0,
+            skipped: 0,
+            filtered: 0,
+            not_run: 0,
+            internal_error: 0,
+            mutation_score: 100.0 * killed as f32 / (killed + alive) as f32,
+        }
+    }
+
+    fn targets() -> Vec<TargetSummary> {
+        vec![
+            TargetSummary {
+                name: "a".into(),
+                module: "a.wasm".into(),
+                outcomes: Some(outcomes(3, 1)),
+                error: None,
+                link: Some("a/index.html".into()),
+            },
+            TargetSummary {
+                name: "b".into(),
+                module: "b.wasm".into(),
+                outcomes: Some(outcomes(1, 3)),
+                error: None,
+                link: Some("b/index.html".into()),
+            },
+            TargetSummary {
+                name: "c".into(),
+                module: "c.wasm".into(),
+                outcomes: None,
+                error: Some("Baseline run failed".into()),
+                link: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn combine_target_outcomes() {
+        let total = combined_outcomes(&targets());
+        assert_eq!(total.total, 8);
+        assert_eq!(total.killed, 4);
+        assert_eq!(total.alive, 4);
+        assert_eq!(total.mutation_score, 50.0);
+    }
+
+    #[test]
+    fn render_dashboard() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_dashboard(dir.path(), &targets(), ScoreThresholds::default())?;
+
+        let html = std::fs::read_to_string(dir.path().join("index.html"))?;
+        assert!(html.contains(r#"<a href="a/index.html">a</a>"#));
+        assert!(html.contains("Baseline run failed"));
+        assert!(html.contains("50.0%"));
+        assert!(html.contains("is-warning"));
+        assert!(dir.path().join("bulma.min.css").exists());
+
+        let thresholds = ScoreThresholds {
+            warning: 80.0,
+            success: 90.0,
+        };
+        write_dashboard(dir.path(), &targets(), thresholds)?;
+        let html = std::fs::read_to_string(dir.path().join("index.html"))?;
+        assert!(!html.contains("is-warning"));
+        Ok(())
+    }
+}
@@ -0,0 +1,499 @@
+//! Outcomes of executed mutants, cached across runs (`[cache] results`).
+//!
+//! Mutants are identified by the hash of the body of the mutated function, the position
+//! of the mutated instruction within the function and the operator, not by their offset
+//! in the module. Unchanged functions in builds of different branches therefore reuse
+//! the outcomes of their mutants, even if other functions of the module changed.
+//! Since the tests are run through the exported functions, their bodies are part of the
+//! key as well. Outcomes are stored per set of inputs (wasmut version and the settings
+//! influencing the outcome, see `Executor::with_result_cache`) in the `cached_results`
+//! table of a `ResultStore` in the cache directory, which requires the `sqlite` feature.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "sqlite")]
+use crate::reporter::database::ResultStore;
+use crate::{
+    executor::ExecutedMutant,
+    mutation::{Mutation, MutationLocation},
+    operator::InstructionReplacement,
+    runtime::ExecutionResult,
+    wasmmodule::WasmModule,
+};
+
+/// Result database in the cache directory, see `ResultStore`
+const RESULT_DATABASE: &str = "results.db";
+
+/// Outcome of an executed mutant, as stored in the cache
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "result", rename_all = "snake_case")]
+enum CachedResult {
+    /// The mutant terminated normally
+    ProcessExit {
+        exit_code: u32,
+        execution_cost: u64,
+        survived: bool,
+        killed_by: Vec<String>,
+    },
+
+    /// The mutant timed out
+    Timeout,
+
+    /// The mutant trapped. Stack traces refer to function numbers of a
+    /// specific build, so they are not cached.
+    Error,
+}
+
+impl CachedResult {
+    /// Cacheable outcome of `mutant`. Mutants that were not executed, or could
+    /// not be executed because of internal errors, are not cached.
+    fn new(mutant: &ExecutedMutant) -> Option<Self> {
+        match mutant.result {
+            ExecutionResult::ProcessExit {
+                exit_code,
+                execution_cost,
+            } => Some(CachedResult::ProcessExit {
+                exit_code,
+                execution_cost,
+                survived: mutant.survived,
+                killed_by: mutant.killed_by.clone(),
+            }),
+            ExecutionResult::Timeout => Some(CachedResult::Timeout),
+            ExecutionResult::Error { .. } => Some(CachedResult::Error),
+            ExecutionResult::Skipped
+            | ExecutionResult::NotRun
+            | ExecutionResult::InternalError { .. } => None,
+        }
+    }
+
+    /// Executed mutant of `mutation` at `location` with this outcome
+    fn to_mutant(&self, location: &MutationLocation, mutation: &Mutation) -> ExecutedMutant {
+        let (result, survived, killed_by) = match self {
+            CachedResult::ProcessExit {
+                exit_code,
+                execution_cost,
+                survived,
+                killed_by,
+            } => (
+                ExecutionResult::ProcessExit {
+                    exit_code: *exit_code,
+                    execution_cost: *execution_cost,
+                },
+                *survived,
+                killed_by.clone(),
+            ),
+            CachedResult::Timeout => (ExecutionResult::Timeout, false, Vec::new()),
+            CachedResult::Error => (
+                ExecutionResult::Error {
+                    stack_trace: Vec::new(),
+                },
+                false,
+                Vec::new(),
+            ),
+        };
+
+        ExecutedMutant {
+            offset: location.offset,
+            result,
+            mutation_operator: mutation.operator.clone(),
+            survived,
+            trace: None,
+            likely_equivalent: false,
+            condition: None,
+            skip_reason: None,
+            killed_by,
+        }
+    }
+}
+
+/// Cached outcomes of the mutants of a module, for one set of inputs
+pub struct ResultCache {
+    /// Result database containing the outcomes
+    #[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+    path: PathBuf,
+
+    /// Hash of the inputs, see `Executor::with_result_cache`
+    #[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+    inputs: String,
+
+    /// Outcomes by mutant key, see `ResultCache::key`
+    results: HashMap<String, CachedResult>,
+
+    /// Hash of every function of the module. Functions whose body occurs more than once
+    /// in the module are `None`, since their mutants cannot be told apart.
+    functions: Vec<Option<String>>,
+
+    /// Hash of the names and bodies of the exported functions, which run the tests
+    entry_points: String,
+
+    /// Outcomes added since the cache was loaded
+    added: HashMap<String, CachedResult>,
+}
+
+impl ResultCache {
+    /// Load the outcomes cached in `cache_directory` for `inputs`, to be used for the
+    /// mutants of `module`. A missing or invalid result database results in an empty cache.
+    pub fn load(cache_directory: &Path, inputs: &str, module: &WasmModule) -> Result<Self> {
+        let path = cache_directory.join(RESULT_DATABASE);
+        let inputs = format!("{:?}", md5::compute(inputs));
+        let results = match Self::read(&path, &inputs) {
+            Ok(results) => {
+                log::debug!(
+                    "Using {} cached mutant outcomes from {path:?}",
+                    results.len()
+                );
+                results
+            }
+            Err(e) => {
+                log::debug!("No cached mutant outcomes in {path:?}: {e:#}");
+                HashMap::new()
+            }
+        };
+
+        let hashes = module.function_hashes()?;
+        let mut seen = HashSet::new();
+        let duplicates: HashSet<&String> = hashes.iter().filter(|h| !seen.insert(*h)).collect();
+        let functions = hashes
+            .iter()
+            .map(|hash| (!duplicates.contains(hash)).then(|| hash.clone()))
+            .collect();
+
+        let mut exported = module.exported_function_numbers();
+        exported.sort();
+        let entry_points: String = exported
+            .iter()
+            .filter_map(|(name, number)| Some(format!("{name}={};", hashes.get(*number)?)))
+            .collect();
+
+        Ok(Self {
+            path,
+            inputs,
+            results,
+            functions,
+            entry_points: format!("{:?}", md5::compute(entry_points)),
+            added: HashMap::new(),
+        })
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn read(path: &Path, inputs: &str) -> Result<HashMap<String, CachedResult>> {
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        ResultStore::open(path)?
+            .cached_results(inputs)?
+            .into_iter()
+            .map(|(key, result)| Ok((key, serde_json::from_str(&result)?)))
+            .collect()
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    fn read(_path: &Path, _inputs: &str) -> Result<HashMap<String, CachedResult>> {
+        anyhow::bail!(
+            "cache.results requires SQLite support, rebuild wasmut with `--features sqlite`"
+        )
+    }
+
+    /// Key identifying the mutant of `operator` at `location` independently of the
+    /// position of its function in the module. Besides the mutated function, the key
+    /// depends on the exported functions, so that changed tests invalidate the outcomes.
+    fn key(
+        &self,
+        location: &MutationLocation,
+        operator: &dyn InstructionReplacement,
+    ) -> Option<String> {
+        let function = self
+            .functions
+            .get(location.function_number as usize)?
+            .as_ref()?;
+        let key = md5::compute(format!(
+            "{function}:{}:{}:{}:{}",
+            self.entry_points,
+            location.statement_number,
+            operator.dyn_name(),
+            operator.description()
+        ));
+        Some(format!("{key:?}"))
+    }
+
+    /// Cached outcome of `mutation` at `location`
+    pub fn get(&self, location: &MutationLocation, mutation: &Mutation) -> Option<ExecutedMutant> {
+        let key = self.key(location, mutation.operator.as_ref())?;
+        self.results
+            .get(&key)
+            .map(|result| result.to_mutant(location, mutation))
+    }
+
+    /// Cache the outcomes of `mutants`, which were executed for the mutations in `locations`
+    pub fn insert(&mut self, locations: &[MutationLocation], mutants: &[ExecutedMutant]) {
+        let locations: HashMap<u64, &MutationLocation> = locations
+            .iter()
+            .map(|location| (location.offset, location))
+            .collect();
+
+        for mutant in mutants {
+            let key = locations
+                .get(&mutant.offset)
+                .and_then(|location| self.key(location, mutant.mutation_operator.as_ref()));
+            if let (Some(key), Some(result)) = (key, CachedResult::new(mutant)) {
+                self.results.insert(key.clone(), result.clone());
+                self.added.insert(key, result);
+            }
+        }
+    }
+
+    /// Store the outcomes added since the cache was loaded. Outcomes that other runs
+    /// stored in the meantime are kept, unless this cache contains an outcome for the
+    /// same mutant.
+    #[cfg(feature = "sqlite")]
+    pub fn save(&self) -> Result<()> {
+        if self.added.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let added = self
+            .added
+            .iter()
+            .map(|(key, result)| Ok((key.as_str(), serde_json::to_string(result)?)))
+            .collect::<Result<Vec<_>>>()?;
+        ResultStore::open(&self.path)?.cache_results(
+            &self.inputs,
+            added.iter().map(|(key, result)| (*key, result.as_str())),
+        )?;
+
+        log::debug!(
+            "Cached {} mutant outcomes in {:?}",
+            self.added.len(),
+            self.path
+        );
+        Ok(())
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    pub fn save(&self) -> Result<()> {
+        anyhow::bail!(
+            "cache.results requires SQLite support, rebuild wasmut with `--features sqlite`"
+        )
+    }
+}
+
+/// MD5 hash of the content of the files at `paths`. Directories are hashed recursively,
+/// including the relative paths of their files, so that outcomes cached for a set of
+/// inputs are not reused once an input file changes.
+pub fn content_hash<P: AsRef<Path>>(paths: &[P]) -> Result<String> {
+    let mut context = md5::Context::new();
+    for path in paths {
+        hash_path(&mut context, path.as_ref(), Path::new(""))?;
+    }
+    Ok(format!("{:?}", context.compute()))
+}
+
+fn hash_path(context: &mut md5::Context, path: &Path, relative: &Path) -> Result<()> {
+    if path.is_dir() {
+        let mut entries = std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read directory {path:?}"))?
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            hash_path(context, &entry.path(), &relative.join(entry.file_name()))?;
+        }
+    } else {
+        let content =
+            std::fs::read(path).with_context(|| format!("Failed to read file {path:?}"))?;
+        context.consume(relative.to_string_lossy().as_bytes());
+        context.consume([0]);
+        context.consume((content.len() as u64).to_le_bytes());
+        context.consume(&content);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::ops::BinaryOperatorAddToSub;
+    use wasmut_wasm::elements::Instruction;
+
+    /// Module consisting of `functions`, each adding two constants
+    fn module(functions: &[(i32, i32)]) -> Result<WasmModule<'static>> {
+        let bodies = functions
+            .iter()
+            .map(|(a, b)| {
+                vec![
+                    Instruction::I32Const(*a),
+                    Instruction::I32Const(*b),
+                    Instruction::I32Add,
+                    Instruction::Drop,
+                ]
+            })
+            .collect();
+        crate::fixtures::functions(&[], &[], bodies)
+    }
+
+    /// Location of the addition in function `function_number`
+    fn location(function_number: u64, offset: u64) -> MutationLocation {
+        MutationLocation {
+            function_number,
+            statement_number: 2,
+            offset,
+            mutations: vec![Mutation {
+                id: 1,
+                operator: Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap()),
+            }],
+        }
+    }
+
+    fn killed(location: &MutationLocation) -> ExecutedMutant {
+        ExecutedMutant {
+            offset: location.offset,
+            result: ExecutionResult::ProcessExit {
+                exit_code: 1,
+                execution_cost: 42,
+            },
+            mutation_operator: location.mutations[0].operator.clone(),
+            survived: false,
+            trace: None,
+            likely_equivalent: false,
+            condition: None,
+            skip_reason: None,
+            killed_by: vec!["exit_code".into()],
+        }
+    }
+
+    /// Module whose exported `_start` function drops `start`, followed by a
+    /// function adding two constants
+    #[cfg(feature = "sqlite")]
+    fn with_entry_point(start: i32) -> Result<WasmModule<'static>> {
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(wasmut_wasm::elements::Instructions::new(vec![
+                Instruction::I32Const(start),
+                Instruction::Drop,
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(wasmut_wasm::elements::Instructions::new(vec![
+                Instruction::I32Const(1),
+                Instruction::I32Const(2),
+                Instruction::I32Add,
+                Instruction::Drop,
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .export()
+            .field("_start")
+            .internal()
+            .func(0)
+            .build()
+            .build();
+        WasmModule::from_bytes(module.into_bytes()?, "module.wasm")
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn reuse_outcomes_of_moved_functions() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let original = module(&[(1, 2), (3, 4)])?;
+        let mut cache = ResultCache::load(dir.path(), "inputs", &original)?;
+        let executed = location(1, 20);
+        assert!(cache.get(&executed, &executed.mutations[0]).is_none());
+
+        cache.insert(std::slice::from_ref(&executed), &[killed(&executed)]);
+        cache.save()?;
+
+        // The function was moved to the front, another function was changed
+        let changed = module(&[(3, 4), (5, 6)])?;
+        let cache = ResultCache::load(dir.path(), "inputs", &changed)?;
+        let moved = location(0, 8);
+        let reused = cache.get(&moved, &moved.mutations[0]).unwrap();
+        assert_eq!(reused.offset, 8);
+        assert!(matches!(
+            reused.result,
+            ExecutionResult::ProcessExit {
+                exit_code: 1,
+                execution_cost: 42
+            }
+        ));
+        assert_eq!(reused.killed_by, vec!["exit_code".to_string()]);
+
+        let other = location(1, 20);
+        assert!(cache.get(&other, &other.mutations[0]).is_none());
+
+        let cache = ResultCache::load(dir.path(), "other inputs", &changed)?;
+        assert!(cache.get(&moved, &moved.mutations[0]).is_none());
+        Ok(())
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn changed_entry_points_invalidate_outcomes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let mut cache = ResultCache::load(dir.path(), "inputs", &with_entry_point(1)?)?;
+        let executed = location(1, 20);
+        cache.insert(std::slice::from_ref(&executed), &[killed(&executed)]);
+        cache.save()?;
+
+        let cache = ResultCache::load(dir.path(), "inputs", &with_entry_point(1)?)?;
+        assert!(cache.get(&executed, &executed.mutations[0]).is_some());
+
+        let cache = ResultCache::load(dir.path(), "inputs", &with_entry_point(2)?)?;
+        assert!(cache.get(&executed, &executed.mutations[0]).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_functions_are_not_cached() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let module = module(&[(1, 2), (1, 2)])?;
+        let mut cache = ResultCache::load(dir.path(), "inputs", &module)?;
+        let executed = location(0, 8);
+        cache.insert(std::slice::from_ref(&executed), &[killed(&executed)]);
+        assert!(cache.added.is_empty());
+
+        let skipped = ExecutedMutant {
+            result: ExecutionResult::Skipped,
+            ..killed(&executed)
+        };
+        assert!(CachedResult::new(&skipped).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn content_hash_changes_with_input_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("sub"))?;
+        std::fs::write(dir.path().join("sub/input.txt"), "1 2 3")?;
+
+        let original = content_hash(&[dir.path()])?;
+        assert_eq!(content_hash(&[dir.path()])?, original);
+
+        std::fs::write(dir.path().join("sub/input.txt"), "1 2 4")?;
+        assert_ne!(content_hash(&[dir.path()])?, original);
+        Ok(())
+    }
+}
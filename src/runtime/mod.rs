@@ -1,9 +1,212 @@
 pub mod wasmer;
+pub mod wasmi;
 
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
 
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::policy::ExecutionPolicy;
 use crate::wasmmodule::WasmModule;
 
+use self::wasmer::{WasmerRuntime, WasmerRuntimeFactory};
+use self::wasmi::{WasmiRuntime, WasmiRuntimeFactory};
+
+/// WebAssembly runtime used to execute modules
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeKind {
+    /// Compile modules to native code with wasmer
+    #[default]
+    Wasmer,
+
+    /// Interpret modules with wasmi, avoiding compilation of each mutant
+    Wasmi,
+
+    /// Interpret mutants in rarely executed functions with wasmi, execute all
+    /// other mutants with a meta-mutant compiled by wasmer
+    Hybrid,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Compiler {
+    Singlepass,
+    Cranelift,
+    Interpreter,
+}
+
+impl Display for Compiler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Compiler::Singlepass => write!(f, "Singlepass"),
+            Compiler::Cranelift => write!(f, "Cranelift"),
+            Compiler::Interpreter => write!(f, "wasmi"),
+        }
+    }
+}
+
+/// An instantiated module, ready to execute its `_start` function
+pub trait Runtime {
+    /// Execute the `_start` function, stopping once `policy` says so
+    fn call_test_function(&mut self, policy: ExecutionPolicy) -> Result<ExecutionResult>;
+
+    /// Trace points reached so far
+    fn trace_points(&self) -> TracePoints;
+
+    /// Recorded calls and captured output of the module
+    fn execution_trace(&self) -> ExecutionTrace;
+
+    /// Compiler used to generate the executed code
+    fn compiler(&self) -> Compiler;
+}
+
+/// Create a runtime of the given kind for `module`
+pub fn create_runtime(
+    kind: RuntimeKind,
+    module: &WasmModule,
+    discard_output: bool,
+    map_dirs: &[(String, String)],
+) -> Result<Box<dyn Runtime>> {
+    Ok(match kind {
+        // Apart from meta-mutants, hybrid execution uses wasmer
+        RuntimeKind::Wasmer | RuntimeKind::Hybrid => {
+            Box::new(WasmerRuntime::new(module, discard_output, map_dirs)?)
+        }
+        RuntimeKind::Wasmi => Box::new(WasmiRuntime::new(module, discard_output, map_dirs)?),
+    })
+}
+
+/// Prepared meta-mutant, from which runtimes with a single mutant
+/// activated are instantiated
+pub enum RuntimeFactory<'a> {
+    Wasmer(WasmerRuntimeFactory<'a>),
+    Wasmi(Box<WasmiRuntimeFactory<'a>>),
+}
+
+impl<'a> RuntimeFactory<'a> {
+    pub fn new(
+        kind: RuntimeKind,
+        module: &WasmModule,
+        discard_output: bool,
+        map_dirs: &'a [(String, String)],
+    ) -> Result<Self> {
+        Ok(match kind {
+            RuntimeKind::Wasmer | RuntimeKind::Hybrid => {
+                Self::Wasmer(WasmerRuntimeFactory::new(module, discard_output, map_dirs)?)
+            }
+            RuntimeKind::Wasmi => Self::Wasmi(Box::new(WasmiRuntimeFactory::new(
+                module,
+                discard_output,
+                map_dirs,
+            )?)),
+        })
+    }
+
+    /// Instantiate the meta-mutant with mutant `mutant_id` activated.
+    ///
+    /// `function` is the number of the function containing the mutant,
+    /// or `None` if no mutant is activated.
+    pub fn instantiate_mutant(
+        &self,
+        mutant_id: i64,
+        function: Option<u64>,
+    ) -> Result<Box<dyn Runtime>> {
+        Ok(match self {
+            Self::Wasmer(factory) => Box::new(factory.instantiate_mutant(mutant_id, function)?),
+            Self::Wasmi(factory) => Box::new(factory.instantiate_mutant(mutant_id, function)?),
+        })
+    }
+}
+
+/// State shared by the functions of the `wasmut_api` namespace
+#[derive(Clone, Default)]
+struct MutantEnv {
+    points: Arc<Mutex<TracePoints>>,
+    activated_mutant_id: i64,
+
+    /// If set, the sequence of traced addresses is recorded as well
+    calls: Option<Arc<Mutex<Vec<u64>>>>,
+}
+
+impl MutantEnv {
+    fn new(activated_mutant_id: i64) -> Self {
+        Self {
+            points: Default::default(),
+            activated_mutant_id,
+            calls: None,
+        }
+    }
+
+    fn with_call_recording(self) -> Self {
+        Self {
+            calls: Some(Default::default()),
+            ..self
+        }
+    }
+
+    fn with_ordered_trace(self) -> Self {
+        Self {
+            points: Arc::new(Mutex::new(TracePoints::ordered())),
+            ..self
+        }
+    }
+
+    fn trace(&self, address: i64) {
+        self.points.lock().unwrap().add_point(address as u64);
+
+        if let Some(calls) = &self.calls {
+            let mut calls = calls.lock().unwrap();
+            // Record one more than the maximum, so that truncation can be detected
+            if calls.len() <= MAX_TRACED_CALLS {
+                calls.push(address as u64);
+            }
+        }
+    }
+
+    fn trace_branch(&self, condition: i32, address: i64) -> i32 {
+        self.points
+            .lock()
+            .unwrap()
+            .add_branch(address as u64, condition != 0);
+
+        // The condition is passed through to the branch instruction
+        condition
+    }
+
+    fn trace_comparison<T: PartialEq>(&self, result: i32, lhs: T, rhs: T, address: i64) -> i32 {
+        self.points
+            .lock()
+            .unwrap()
+            .add_comparison(address as u64, result != 0, lhs == rhs);
+
+        // The result is passed through to the next instruction
+        result
+    }
+
+    fn check_mutant_id(&self, mutant_id: i64) -> i32 {
+        (self.activated_mutant_id == mutant_id) as i32
+    }
+
+    fn trace_points(&self) -> TracePoints {
+        self.points.lock().unwrap().clone()
+    }
+
+    /// Recorded calls, at most `MAX_TRACED_CALLS`, and whether they were truncated
+    fn calls(&self) -> (Vec<u64>, bool) {
+        let mut calls = self
+            .calls
+            .as_ref()
+            .map(|calls| calls.lock().unwrap().clone())
+            .unwrap_or_default();
+
+        let truncated = calls.len() > MAX_TRACED_CALLS;
+        calls.truncate(MAX_TRACED_CALLS);
+        (calls, truncated)
+    }
+}
+
 /// Result of an executed module
 #[derive(Debug)]
 pub enum ExecutionResult {
@@ -15,22 +218,215 @@ pub enum ExecutionResult {
     /// Execution was skipped
     Skipped,
 
+    /// Mutant was not started, because the time budget of the run was exhausted.
+    /// Never returned by a runtime, only by the executor.
+    NotRun,
+
     /// Other error (e.g. module trapped)
-    Error,
+    Error { stack_trace: Vec<StackFrame> },
+
+    /// wasmut or the runtime failed to prepare or execute the mutant.
+    /// Never returned by a runtime, only by the executor.
+    InternalError { message: String },
 }
 
-#[derive(Default, Clone)]
+/// Stack frame of a trapped module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    /// Index of the function in the code section
+    pub function_number: u32,
+
+    /// Offset of the instruction, relative to the start of the function
+    pub function_offset: u64,
+}
+
+/// Recorded execution of a module
+#[derive(Debug, Default, Clone)]
+pub struct ExecutionTrace {
+    /// Offsets of all called functions, in the order they were called
+    pub calls: Vec<u64>,
+
+    /// True if the number of recorded calls exceeded `MAX_TRACED_CALLS`
+    pub truncated: bool,
+
+    /// Captured stdout
+    pub stdout: String,
+
+    /// Captured stderr
+    pub stderr: String,
+}
+
+/// Maximum number of calls recorded in an `ExecutionTrace`
+pub const MAX_TRACED_CALLS: usize = 10000;
+
+/// Maximum number of trace points recorded in the sequence of an ordered trace
+pub const MAX_ORDERED_TRACE_POINTS: usize = 1_000_000;
+
+/// Instructions executed during a traced run, and how often they were executed
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct TracePoints {
-    points: HashSet<u64>,
+    points: HashMap<u64, u64>,
+
+    /// Trace points in the order they were reached, only recorded by ordered traces
+    #[serde(skip)]
+    sequence: Option<Vec<u64>>,
+
+    /// Taken edges of conditional branches, by offset of the `if`/`br_if`
+    #[serde(skip)]
+    branches: HashMap<u64, BranchCoverage>,
+
+    /// Observed results and operands of comparisons, by offset of the comparison
+    #[serde(skip)]
+    comparisons: HashMap<u64, ComparisonProfile>,
+}
+
+/// Edges of a conditional branch (`if`/`br_if`) that were taken
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BranchCoverage {
+    /// The condition was non-zero at least once
+    pub taken: bool,
+
+    /// The condition was zero at least once
+    pub not_taken: bool,
+}
+
+/// Observed results and operands of a comparison (e.g. `i32.lt_s`)
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComparisonProfile {
+    /// Observed results, `taken` for true and `not_taken` for false
+    pub results: BranchCoverage,
+
+    /// Both operands were equal at least once
+    pub equal_operands: bool,
+}
+
+impl ComparisonProfile {
+    /// Observed results, as shown in reports
+    pub fn outcome(&self) -> &'static str {
+        match (self.results.taken, self.results.not_taken) {
+            (true, true) => "mixed",
+            (true, false) => "always true",
+            (false, true) => "always false",
+            (false, false) => "never evaluated",
+        }
+    }
+}
+
+impl BranchCoverage {
+    /// True if the edge for the given outcome of the condition was taken
+    pub fn has_taken(&self, condition: bool) -> bool {
+        if condition {
+            self.taken
+        } else {
+            self.not_taken
+        }
+    }
+
+    /// Number of covered edges, between 0 and 2
+    pub fn covered_edges(&self) -> usize {
+        self.taken as usize + self.not_taken as usize
+    }
 }
 
 impl TracePoints {
-    fn add_point(&mut self, offset: u64) {
-        self.points.insert(offset);
+    /// Create trace points that also record the order in which they were reached
+    pub fn ordered() -> Self {
+        Self {
+            points: Default::default(),
+            sequence: Some(Vec::new()),
+            branches: Default::default(),
+            comparisons: Default::default(),
+        }
+    }
+
+    pub(crate) fn add_point(&mut self, offset: u64) {
+        *self.points.entry(offset).or_insert(0) += 1;
+
+        if let Some(sequence) = &mut self.sequence {
+            // Record one more than the maximum, so that truncation can be detected
+            if sequence.len() <= MAX_ORDERED_TRACE_POINTS {
+                sequence.push(offset);
+            }
+        }
+    }
+
+    pub(crate) fn add_branch(&mut self, offset: u64, condition: bool) {
+        let branch = self.branches.entry(offset).or_default();
+        if condition {
+            branch.taken = true;
+        } else {
+            branch.not_taken = true;
+        }
+    }
+
+    pub(crate) fn add_comparison(&mut self, offset: u64, result: bool, equal_operands: bool) {
+        let comparison = self.comparisons.entry(offset).or_default();
+        if result {
+            comparison.results.taken = true;
+        } else {
+            comparison.results.not_taken = true;
+        }
+        comparison.equal_operands |= equal_operands;
+    }
+
+    /// Trace points in the order they were reached, at most `MAX_ORDERED_TRACE_POINTS`.
+    /// Empty, unless the trace points were created with `TracePoints::ordered`
+    pub fn sequence(&self) -> &[u64] {
+        match &self.sequence {
+            Some(sequence) => &sequence[..sequence.len().min(MAX_ORDERED_TRACE_POINTS)],
+            None => &[],
+        }
+    }
+
+    /// True if more trace points were reached than recorded in `sequence`
+    pub fn is_truncated(&self) -> bool {
+        self.sequence
+            .as_ref()
+            .map(|sequence| sequence.len() > MAX_ORDERED_TRACE_POINTS)
+            .unwrap_or(false)
     }
 
     pub fn is_covered(&self, offset: u64) -> bool {
-        self.points.contains(&offset)
+        self.points.contains_key(&offset)
+    }
+
+    /// Number of times the instruction at `offset` was executed
+    pub fn count(&self, offset: u64) -> u64 {
+        self.points.get(&offset).copied().unwrap_or(0)
+    }
+
+    /// Number of distinct instructions that were executed
+    pub fn covered_instructions(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Sorted offsets of all instructions that were executed
+    pub fn covered_offsets(&self) -> Vec<u64> {
+        let mut offsets: Vec<u64> = self.points.keys().copied().collect();
+        offsets.sort_unstable();
+        offsets
+    }
+
+    /// Taken edges of the conditional branch at `offset`.
+    ///
+    /// `None` if no branch coverage was recorded for `offset`, e.g. because
+    /// the branch was never reached or the trace points were loaded from a baseline file.
+    pub fn branch(&self, offset: u64) -> Option<BranchCoverage> {
+        self.branches.get(&offset).copied()
+    }
+
+    /// True if branch coverage was recorded
+    pub fn has_branch_coverage(&self) -> bool {
+        !self.branches.is_empty()
+    }
+
+    /// Observed results and operands of the comparison at `offset`.
+    ///
+    /// `None` if the comparison was never executed or no profile was
+    /// recorded, e.g. because the trace points were loaded from a baseline file.
+    pub fn comparison(&self, offset: u64) -> Option<ComparisonProfile> {
+        self.comparisons.get(&offset).copied()
     }
 }
 
@@ -46,5 +442,63 @@ mod tests {
 
         trace_points.add_point(10);
         assert!(trace_points.is_covered(10));
+
+        trace_points.add_point(10);
+        assert_eq!(trace_points.count(10), 2);
+        assert_eq!(trace_points.count(1337), 0);
+        assert!(trace_points.sequence().is_empty());
+    }
+
+    #[test]
+    fn branch_coverage() {
+        let mut trace_points = TracePoints::default();
+        assert!(!trace_points.has_branch_coverage());
+        assert_eq!(trace_points.branch(10), None);
+
+        trace_points.add_branch(10, true);
+        trace_points.add_branch(10, true);
+        let branch = trace_points.branch(10).unwrap();
+        assert!(branch.has_taken(true));
+        assert!(!branch.has_taken(false));
+        assert_eq!(branch.covered_edges(), 1);
+
+        trace_points.add_branch(10, false);
+        assert_eq!(trace_points.branch(10).unwrap().covered_edges(), 2);
+        assert!(trace_points.has_branch_coverage());
+    }
+
+    #[test]
+    fn comparison_profile() {
+        let mut trace_points = TracePoints::default();
+        assert_eq!(trace_points.comparison(10), None);
+
+        trace_points.add_comparison(10, true, false);
+        let profile = trace_points.comparison(10).unwrap();
+        assert_eq!(profile.outcome(), "always true");
+        assert!(!profile.equal_operands);
+
+        trace_points.add_comparison(10, false, true);
+        trace_points.add_comparison(10, false, false);
+        let profile = trace_points.comparison(10).unwrap();
+        assert_eq!(profile.outcome(), "mixed");
+        assert!(profile.equal_operands);
+    }
+
+    #[test]
+    fn ordered_trace_points() {
+        let mut trace_points = TracePoints::ordered();
+        trace_points.add_point(10);
+        trace_points.add_point(20);
+        trace_points.add_point(10);
+
+        assert_eq!(trace_points.sequence(), &[10, 20, 10]);
+        assert_eq!(trace_points.count(10), 2);
+        assert!(!trace_points.is_truncated());
+
+        for _ in 0..MAX_ORDERED_TRACE_POINTS {
+            trace_points.add_point(30);
+        }
+        assert_eq!(trace_points.sequence().len(), MAX_ORDERED_TRACE_POINTS);
+        assert!(trace_points.is_truncated());
     }
 }
@@ -1,9 +1,12 @@
-use std::fmt::Display;
-use std::sync::{Arc, Mutex};
+use std::io::Read;
+use std::sync::Arc;
 
-use crate::{policy::ExecutionPolicy, runtime::ExecutionResult};
+use crate::{
+    policy::ExecutionPolicy,
+    runtime::{ExecutionResult, StackFrame},
+};
 use anyhow::{Context, Result};
-use wasmer::{wasmparser::Operator, Exports, Instance, Module, Store};
+use wasmer::{wasmparser::Operator, Exports, Instance, Module, RuntimeError, Store, Value};
 use wasmer::{
     CompilerConfig, Cranelift, Engine, Features, Function, FunctionEnv, FunctionEnvMut, Imports,
     Target,
@@ -13,60 +16,49 @@ use wasmer_middlewares::{
     metering::{get_remaining_points, set_remaining_points, MeteringPoints},
     Metering,
 };
-use wasmer_wasi::{Pipe, WasiError, WasiFunctionEnv, WasiState};
+use wasmer_wasi::{
+    generate_import_object_from_env, get_wasi_version, Pipe, WasiError, WasiFunctionEnv, WasiState,
+    WasiVersion,
+};
 
-#[derive(Copy, Clone)]
-pub enum Compiler {
-    Singlepass,
-    Cranelift,
+fn trace(env: FunctionEnvMut<MutantEnv>, address: i64) {
+    env.data().trace(address);
 }
 
-impl Display for Compiler {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Compiler::Singlepass => write!(f, "Singlepass"),
-            Compiler::Cranelift => write!(f, "Cranelift"),
-        }
-    }
+fn trace_branch(env: FunctionEnvMut<MutantEnv>, condition: i32, address: i64) -> i32 {
+    env.data().trace_branch(condition, address)
 }
 
-#[derive(Clone, Default)]
-struct MutantEnv {
-    points: Arc<Mutex<TracePoints>>,
-    activated_mutant_id: i64,
+fn trace_comparison<T: PartialEq>(
+    env: FunctionEnvMut<MutantEnv>,
+    result: i32,
+    lhs: T,
+    rhs: T,
+    address: i64,
+) -> i32 {
+    env.data().trace_comparison(result, lhs, rhs, address)
 }
 
-impl MutantEnv {
-    fn new(activated_mutant_id: i64) -> Self {
-        Self {
-            points: Default::default(),
-            activated_mutant_id,
-        }
-    }
+fn check_mutant_id(env: FunctionEnvMut<MutantEnv>, mutant_id: i64) -> i32 {
+    env.data().check_mutant_id(mutant_id)
 }
 
-fn trace(mut env: FunctionEnvMut<MutantEnv>, address: i64) {
-    let env = env.data_mut();
-    let mut vec = env.points.lock().unwrap();
-    vec.add_point(address as u64);
-}
+use super::{Compiler, ExecutionTrace, MutantEnv, Runtime, TracePoints, WasmModule};
+use crate::wasmmodule::{MUTANT_FUNCTION_GLOBAL, MUTANT_ID_GLOBAL, WASMUT_API_NAMESPACE};
 
-fn check_mutant_id(env: FunctionEnvMut<MutantEnv>, mutant_id: i64) -> i32 {
-    let env = env.data();
-    if env.activated_mutant_id == mutant_id {
-        1
-    } else {
-        0
-    }
+/// Pipes used to capture stdout and stderr of a module
+#[derive(Clone, Default)]
+struct CapturedOutput {
+    stdout: Pipe,
+    stderr: Pipe,
 }
 
-use super::{TracePoints, WasmModule};
-
 pub struct WasmerRuntime {
     instance: Instance,
     store: Store,
     mutant_env: MutantEnv,
     compiler: Compiler,
+    captured_output: Option<CapturedOutput>,
 }
 
 impl WasmerRuntime {
@@ -74,15 +66,51 @@ impl WasmerRuntime {
         module: &WasmModule,
         discard_output: bool,
         map_dirs: &[(String, String)],
+    ) -> Result<Self> {
+        let captured_output = discard_output.then(CapturedOutput::default);
+        Self::new_with_env(module, captured_output, map_dirs, &[], MutantEnv::new(0))
+    }
+
+    /// Create a runtime that captures the output of the module and records
+    /// the sequence of traced addresses. Use `execution_trace` to retrieve
+    /// the recorded data after execution.
+    pub fn new_traced(module: &WasmModule, map_dirs: &[(String, String)]) -> Result<Self> {
+        Self::new_with_env(
+            module,
+            Some(CapturedOutput::default()),
+            map_dirs,
+            &[],
+            MutantEnv::new(0).with_call_recording(),
+        )
+    }
+
+    /// Create a runtime that records the order in which trace points are reached.
+    /// The output of the module is discarded.
+    pub fn new_with_ordered_trace(
+        module: &WasmModule,
+        map_dirs: &[(String, String)],
+    ) -> Result<Self> {
+        Self::new_with_env(
+            module,
+            Some(CapturedOutput::default()),
+            map_dirs,
+            &[],
+            MutantEnv::new(0).with_ordered_trace(),
+        )
+    }
+
+    fn new_with_env(
+        module: &WasmModule,
+        captured_output: Option<CapturedOutput>,
+        map_dirs: &[(String, String)],
+        args: &[String],
+        trace_env: MutantEnv,
     ) -> Result<Self> {
         let mut store = create_store(Compiler::Singlepass);
-        let trace_env = MutantEnv::new(0);
 
         let wasmer_module = create_module(module, &store)?;
-        let mut wasi_env = create_wasi_env(&mut store, discard_output, map_dirs)?;
-        let mut imports = wasi_env
-            .import_object(&mut store, &wasmer_module)
-            .context("Failed to create import object")?;
+        let mut wasi_env = create_wasi_env(&mut store, captured_output.as_ref(), map_dirs, args)?;
+        let mut imports = wasi_imports(&mut store, &wasi_env, &wasmer_module);
         add_trace_function(&mut store, &mut imports, &trace_env);
         let instance = Instance::new(&mut store, &wasmer_module, &imports)
             .context("Failed to create wasmer instance")?;
@@ -96,6 +124,7 @@ impl WasmerRuntime {
             store,
             mutant_env: trace_env,
             compiler: Compiler::Singlepass,
+            captured_output,
         })
     }
 
@@ -103,23 +132,39 @@ impl WasmerRuntime {
         compiled_code: &[u8],
         discard_output: bool,
         map_dirs: &[(String, String)],
+        args: &[String],
         mutant_id: i64,
+        mutant_function: Option<u64>,
         compiler: Compiler,
     ) -> Result<Self> {
         let mut store = create_store(compiler);
         let mutant_env = MutantEnv::new(mutant_id);
+        let captured_output = discard_output.then(CapturedOutput::default);
 
         let wasmer_module = unsafe { Module::deserialize(&store, compiled_code)? };
 
-        let mut wasi_env = create_wasi_env(&mut store, discard_output, map_dirs)?;
-        let mut imports = wasi_env
-            .import_object(&mut store, &wasmer_module)
-            .context("Failed to create import object")?;
+        let mut wasi_env = create_wasi_env(&mut store, captured_output.as_ref(), map_dirs, args)?;
+        let mut imports = wasi_imports(&mut store, &wasi_env, &wasmer_module);
         add_trace_function(&mut store, &mut imports, &mutant_env);
 
         let instance = Instance::new(&mut store, &wasmer_module, &imports)
             .context("Failed to create wasmer instance")?;
 
+        // Meta-mutants using `MutantActivation::Global` compare
+        // against this global instead of calling `check_mutant_id`
+        if let Ok(global) = instance.exports.get_global(MUTANT_ID_GLOBAL) {
+            global
+                .set(&mut store, Value::I64(mutant_id))
+                .context("Failed to activate mutant")?;
+        }
+
+        if let Ok(global) = instance.exports.get_global(MUTANT_FUNCTION_GLOBAL) {
+            let function = mutant_function.map_or(-1, |function| function as i64);
+            global
+                .set(&mut store, Value::I64(function))
+                .context("Failed to activate mutant")?;
+        }
+
         wasi_env
             .initialize(&mut store, &instance)
             .context("Could not initialize WASI env")?;
@@ -129,10 +174,32 @@ impl WasmerRuntime {
             store,
             mutant_env,
             compiler,
+            captured_output,
         })
     }
 
-    pub fn call_test_function(&mut self, policy: ExecutionPolicy) -> Result<ExecutionResult> {
+    /// Extract the stack frames of a runtime error.
+    ///
+    /// Function indices are converted to indices into the code section,
+    /// so that they refer to the same function in the original module,
+    /// even if additional functions were imported.
+    fn stack_trace(&self, error: &RuntimeError) -> Vec<StackFrame> {
+        let imported_functions = self.instance.module().imports().functions().count() as u32;
+
+        error
+            .trace()
+            .iter()
+            .filter(|frame| frame.func_index() >= imported_functions)
+            .map(|frame| StackFrame {
+                function_number: frame.func_index() - imported_functions,
+                function_offset: frame.func_offset() as u64,
+            })
+            .collect()
+    }
+}
+
+impl Runtime for WasmerRuntime {
+    fn call_test_function(&mut self, policy: ExecutionPolicy) -> Result<ExecutionResult> {
         let execution_limit = match policy {
             ExecutionPolicy::RunUntilLimit { limit } => limit,
             ExecutionPolicy::RunUntilReturn => u64::MAX,
@@ -169,6 +236,8 @@ impl WasmerRuntime {
             Err(e) => match get_remaining_points(&mut self.store, &self.instance) {
                 MeteringPoints::Exhausted => Ok(ExecutionResult::Timeout),
                 MeteringPoints::Remaining(remaining) => {
+                    let stack_trace = self.stack_trace(&e);
+
                     if let Ok(wasi_err) = e.downcast() {
                         match wasi_err {
                             WasiError::Exit(exit_code) => {
@@ -179,26 +248,51 @@ impl WasmerRuntime {
                                     execution_cost,
                                 })
                             }
-                            WasiError::UnknownWasiVersion => Ok(ExecutionResult::Error),
+                            WasiError::UnknownWasiVersion => {
+                                Ok(ExecutionResult::Error { stack_trace })
+                            }
                         }
                     } else {
-                        Ok(ExecutionResult::Error)
+                        Ok(ExecutionResult::Error { stack_trace })
                     }
                 }
             },
         }
     }
 
-    pub fn trace_points(&self) -> TracePoints {
-        let points = self.mutant_env.points.as_ref().lock().unwrap();
-        points.clone()
+    fn trace_points(&self) -> TracePoints {
+        self.mutant_env.trace_points()
+    }
+
+    fn execution_trace(&self) -> ExecutionTrace {
+        let (calls, truncated) = self.mutant_env.calls();
+
+        let (stdout, stderr) = match &self.captured_output {
+            Some(output) => (read_pipe(&output.stdout), read_pipe(&output.stderr)),
+            None => Default::default(),
+        };
+
+        ExecutionTrace {
+            calls,
+            truncated,
+            stdout,
+            stderr,
+        }
     }
 
-    pub fn compiler(&self) -> Compiler {
+    fn compiler(&self) -> Compiler {
         self.compiler
     }
 }
 
+/// Read everything that was written to a pipe so far
+fn read_pipe(pipe: &Pipe) -> String {
+    let mut content = Vec::new();
+    // Reading from an in-memory pipe cannot fail
+    let _ = pipe.clone().read_to_end(&mut content);
+    String::from_utf8_lossy(&content).into()
+}
+
 pub struct WasmerRuntimeFactory<'a> {
     compiled_code: Vec<u8>,
     discard_output: bool,
@@ -222,21 +316,70 @@ impl<'a> WasmerRuntimeFactory<'a> {
         })
     }
 
-    pub fn instantiate_mutant(&self, mutant_id: i64) -> Result<WasmerRuntime> {
+    /// Instantiate the meta-mutant with mutant `mutant_id` activated.
+    ///
+    /// `function` is the number of the function containing the mutant,
+    /// or `None` if no mutant is activated.
+    pub fn instantiate_mutant(
+        &self,
+        mutant_id: i64,
+        function: Option<u64>,
+    ) -> Result<WasmerRuntime> {
+        self.instantiate_mutant_with_args(mutant_id, function, &[])
+    }
+
+    /// Like `instantiate_mutant`, passing `args` as command line arguments to the module
+    pub fn instantiate_mutant_with_args(
+        &self,
+        mutant_id: i64,
+        function: Option<u64>,
+        args: &[String],
+    ) -> Result<WasmerRuntime> {
         WasmerRuntime::new_from_cached_module(
             &self.compiled_code,
             self.discard_output,
             self.map_dirs,
+            args,
             mutant_id,
+            function,
             Compiler::Cranelift,
         )
     }
 }
 
+/// Checks whether modules are valid wasm, without compiling them
+pub struct Validator {
+    store: Store,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self {
+            store: create_store(Compiler::Singlepass),
+        }
+    }
+}
+
+impl Validator {
+    /// Validate the serialized module, the error describes the first violation
+    pub fn validate(&self, module: &WasmModule) -> Result<()> {
+        let bytecode = module.to_bytes()?;
+        Module::validate(&self.store, &bytecode).map_err(|e| anyhow::anyhow!("{e}"))
+    }
+}
+
+/// Create the WASI imports for a module. Modules that do not import
+/// anything from WASI get the current snapshot.
+fn wasi_imports(store: &mut Store, wasi_env: &WasiFunctionEnv, module: &Module) -> Imports {
+    let version = get_wasi_version(module, false).unwrap_or(WasiVersion::Snapshot1);
+    generate_import_object_from_env(store, &wasi_env.env, version)
+}
+
 fn add_trace_function(store: &mut Store, import_object: &mut Imports, trace_env: &MutantEnv) {
     let mut exports = Exports::new();
 
     let trace_function_env = FunctionEnv::new(store, trace_env.clone());
+    let trace_branch_env = FunctionEnv::new(store, trace_env.clone());
     let check_mutant_id_env = FunctionEnv::new(store, trace_env.clone());
 
     exports.insert(
@@ -244,12 +387,38 @@ fn add_trace_function(store: &mut Store, import_object: &mut Imports, trace_env:
         Function::new_typed_with_env(store, &trace_function_env, trace),
     );
 
+    exports.insert(
+        "__wasmut_trace_branch",
+        Function::new_typed_with_env(store, &trace_branch_env, trace_branch),
+    );
+
+    for (name, function) in [
+        (
+            "__wasmut_trace_comparison_i32",
+            Function::new_typed_with_env(store, &trace_branch_env, trace_comparison::<i32>),
+        ),
+        (
+            "__wasmut_trace_comparison_i64",
+            Function::new_typed_with_env(store, &trace_branch_env, trace_comparison::<i64>),
+        ),
+        (
+            "__wasmut_trace_comparison_f32",
+            Function::new_typed_with_env(store, &trace_branch_env, trace_comparison::<f32>),
+        ),
+        (
+            "__wasmut_trace_comparison_f64",
+            Function::new_typed_with_env(store, &trace_branch_env, trace_comparison::<f64>),
+        ),
+    ] {
+        exports.insert(name, function);
+    }
+
     exports.insert(
         "__wasmut_check_mutant_id",
         Function::new_typed_with_env(store, &check_mutant_id_env, check_mutant_id),
     );
 
-    import_object.register_namespace("wasmut_api", exports);
+    import_object.register_namespace(WASMUT_API_NAMESPACE, exports);
 }
 
 fn create_store(compiler: Compiler) -> Store {
@@ -260,6 +429,7 @@ fn create_store(compiler: Compiler) -> Store {
     let mut compiler_config: Box<dyn CompilerConfig> = match compiler {
         Compiler::Singlepass => Box::<Singlepass>::default(),
         Compiler::Cranelift => Box::<Cranelift>::default(),
+        Compiler::Interpreter => unreachable!("wasmer does not use an interpreter"),
     };
 
     compiler_config.push_middleware(metering);
@@ -277,15 +447,17 @@ fn create_module(module: &WasmModule, store: &Store) -> Result<Module> {
 
 fn create_wasi_env(
     store: &mut Store,
-    discard_output: bool,
+    captured_output: Option<&CapturedOutput>,
     map_dirs: &[(String, String)],
+    args: &[String],
 ) -> Result<WasiFunctionEnv> {
     let mut state_builder = WasiState::new("command-name");
+    state_builder.args(args);
 
-    // If the discard_output parameter is set, we discard any outputs of the module
-    if discard_output {
-        let stdout = Box::new(Pipe::new());
-        let stderr = Box::new(Pipe::new());
+    // If output is captured, it is not written to the terminal
+    if let Some(captured_output) = captured_output {
+        let stdout = Box::new(captured_output.stdout.clone());
+        let stderr = Box::new(captured_output.stderr.clone());
         state_builder.stdout(stdout).stderr(stderr);
     }
 
@@ -342,6 +514,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn module_without_wasi_imports() -> Result<()> {
+        use wasmut_wasm::elements::{Instruction, Instructions};
+
+        let bytes = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Instruction::End]))
+            .build()
+            .build()
+            .export()
+            .field("_start")
+            .internal()
+            .func(0)
+            .build()
+            .build()
+            .into_bytes()?;
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        std::fs::write(&path, bytes)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        // Instantiation is not tested, it fails the UB checks of wasmer in debug builds
+        let mut store = create_store(Compiler::Cranelift);
+        let wasmer_module = create_module(&module, &store)?;
+        let wasi_env = create_wasi_env(&mut store, None, &[], &[])?;
+        let imports = wasi_imports(&mut store, &wasi_env, &wasmer_module);
+        assert!(imports
+            .get_export("wasi_snapshot_preview1", "fd_write")
+            .is_some());
+        Ok(())
+    }
+
     #[test]
     fn compiler_display() {
         assert_eq!("Cranelift", format!("{}", Compiler::Cranelift));
@@ -356,7 +564,7 @@ mod tests {
         assert!(matches!(runtime.compiler(), Compiler::Singlepass));
 
         let factory = WasmerRuntimeFactory::new(&module, true, &[])?;
-        let runtime = factory.instantiate_mutant(0)?;
+        let runtime = factory.instantiate_mutant(0, None)?;
 
         assert!(matches!(runtime.compiler(), Compiler::Cranelift));
         Ok(())
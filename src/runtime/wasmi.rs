@@ -0,0 +1,721 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context, Result};
+use wasmi::core::{Trap, TrapCode, F32, F64};
+use wasmi::{Caller, Config, Engine, Extern, ExternType, IntoFunc, Linker, Module, Store, Value};
+
+use super::{Compiler, ExecutionResult, ExecutionTrace, MutantEnv, Runtime, TracePoints};
+use crate::policy::ExecutionPolicy;
+use crate::warnings::{self, WarningCategory};
+use crate::wasmmodule::{
+    WasmModule, MUTANT_FUNCTION_GLOBAL, MUTANT_ID_GLOBAL, WASMUT_API_NAMESPACE,
+};
+
+/// Namespace of the WASI functions emulated by this runtime
+const WASI_NAMESPACE: &str = "wasi_snapshot_preview1";
+
+/// WASI error codes returned by the emulated functions
+const ERRNO_SUCCESS: i32 = 0;
+const ERRNO_BADF: i32 = 8;
+const ERRNO_FAULT: i32 = 21;
+const ERRNO_SPIPE: i32 = 70;
+
+/// WASI file type of stdin, stdout and stderr
+const FILETYPE_CHARACTER_DEVICE: u8 = 2;
+
+/// Buffers used to capture stdout and stderr of a module
+#[derive(Clone, Default)]
+struct CapturedOutput {
+    stdout: Arc<Mutex<Vec<u8>>>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+}
+
+/// Data stored alongside a wasmi instance
+struct HostState {
+    mutant_env: MutantEnv,
+
+    /// Command line arguments, including the program name
+    args: Vec<String>,
+    captured_output: Option<CapturedOutput>,
+
+    /// State of the generator used by `random_get`
+    random_state: u64,
+}
+
+/// Runtime interpreting modules with wasmi.
+///
+/// Only the subset of WASI needed by typical test programs is
+/// provided: command line arguments, environment variables (always empty),
+/// output to stdout/stderr, clocks and random numbers. Other imported
+/// functions trap when called. Execution cost is measured in consumed fuel,
+/// which is roughly the number of executed instructions.
+pub struct WasmiRuntime {
+    instance: wasmi::Instance,
+    store: Store<HostState>,
+    mutant_env: MutantEnv,
+    captured_output: Option<CapturedOutput>,
+
+    /// Fuel added to the store so far
+    fuel_added: u64,
+}
+
+impl WasmiRuntime {
+    pub fn new(
+        module: &WasmModule,
+        discard_output: bool,
+        map_dirs: &[(String, String)],
+    ) -> Result<Self> {
+        let engine = create_engine();
+        let wasmi_module = create_module(module, &engine)?;
+        Self::instantiate(
+            &engine,
+            &wasmi_module,
+            discard_output,
+            map_dirs,
+            MutantEnv::new(0),
+            None,
+        )
+    }
+
+    fn instantiate(
+        engine: &Engine,
+        module: &Module,
+        discard_output: bool,
+        map_dirs: &[(String, String)],
+        mutant_env: MutantEnv,
+        mutant_function: Option<u64>,
+    ) -> Result<Self> {
+        if !map_dirs.is_empty() {
+            bail!("Mapping directories is not supported by the wasmi runtime");
+        }
+
+        let captured_output = discard_output.then(CapturedOutput::default);
+        let mutant_id = mutant_env.activated_mutant_id;
+
+        let mut store = Store::new(
+            engine,
+            HostState {
+                mutant_env: mutant_env.clone(),
+                args: vec![String::from("command-name")],
+                captured_output: captured_output.clone(),
+                random_state: 0x853c_49e6_748f_ea9b,
+            },
+        );
+
+        let linker = create_linker(engine, module)?;
+        let instance = linker
+            .instantiate(&mut store, module)
+            .context("Failed to create wasmi instance")?
+            .start(&mut store)
+            .context("Failed to run start function")?;
+
+        // Meta-mutants using `MutantActivation::Global` compare
+        // against this global instead of calling `check_mutant_id`
+        if let Some(global) = instance.get_global(&store, MUTANT_ID_GLOBAL) {
+            global
+                .set(&mut store, Value::I64(mutant_id))
+                .map_err(|e| anyhow!("{e}"))
+                .context("Failed to activate mutant")?;
+        }
+
+        if let Some(global) = instance.get_global(&store, MUTANT_FUNCTION_GLOBAL) {
+            let function = mutant_function.map_or(-1, |function| function as i64);
+            global
+                .set(&mut store, Value::I64(function))
+                .map_err(|e| anyhow!("{e}"))
+                .context("Failed to activate mutant")?;
+        }
+
+        Ok(Self {
+            instance,
+            store,
+            mutant_env,
+            captured_output,
+            fuel_added: 0,
+        })
+    }
+}
+
+impl Runtime for WasmiRuntime {
+    fn call_test_function(&mut self, policy: ExecutionPolicy) -> Result<ExecutionResult> {
+        let execution_limit = match policy {
+            ExecutionPolicy::RunUntilLimit { limit } => limit,
+            ExecutionPolicy::RunUntilReturn => u64::MAX,
+        };
+
+        // Fuel can only be added, so use up what is left of a previous call first
+        let consumed = self.store.fuel_consumed().unwrap_or(0);
+        let _ = self.store.consume_fuel(self.fuel_added - consumed);
+        let consumed_before = self.fuel_added;
+
+        let execution_limit = execution_limit.min(u64::MAX - self.fuel_added);
+        self.store
+            .add_fuel(execution_limit)
+            .map_err(|e| anyhow!("{e}"))?;
+        self.fuel_added += execution_limit;
+
+        let func = self
+            .instance
+            .get_typed_func::<(), ()>(&self.store, "_start")
+            .context("Failed to resolve _start function")?;
+
+        let result = func.call(&mut self.store, ());
+        let execution_cost = self.store.fuel_consumed().unwrap_or(0) - consumed_before;
+
+        match result {
+            Ok(()) => Ok(ExecutionResult::ProcessExit {
+                exit_code: 0,
+                execution_cost,
+            }),
+            Err(trap) => {
+                if let Some(exit_code) = trap.i32_exit_status() {
+                    Ok(ExecutionResult::ProcessExit {
+                        exit_code: exit_code as u32,
+                        execution_cost,
+                    })
+                } else if matches!(trap.trap_code(), Some(TrapCode::OutOfFuel)) {
+                    Ok(ExecutionResult::Timeout)
+                } else {
+                    // wasmi does not provide stack traces of traps
+                    Ok(ExecutionResult::Error {
+                        stack_trace: vec![],
+                    })
+                }
+            }
+        }
+    }
+
+    fn trace_points(&self) -> TracePoints {
+        self.mutant_env.trace_points()
+    }
+
+    fn execution_trace(&self) -> ExecutionTrace {
+        let (calls, truncated) = self.mutant_env.calls();
+
+        let (stdout, stderr) = match &self.captured_output {
+            Some(output) => (
+                String::from_utf8_lossy(&output.stdout.lock().unwrap()).into(),
+                String::from_utf8_lossy(&output.stderr.lock().unwrap()).into(),
+            ),
+            None => Default::default(),
+        };
+
+        ExecutionTrace {
+            calls,
+            truncated,
+            stdout,
+            stderr,
+        }
+    }
+
+    fn compiler(&self) -> Compiler {
+        Compiler::Interpreter
+    }
+}
+
+/// Meta-mutant, translated once and instantiated for every activated mutant
+pub struct WasmiRuntimeFactory<'a> {
+    engine: Engine,
+    module: Module,
+    discard_output: bool,
+    map_dirs: &'a [(String, String)],
+}
+
+impl<'a> WasmiRuntimeFactory<'a> {
+    pub fn new(
+        module: &WasmModule,
+        discard_output: bool,
+        map_dirs: &'a [(String, String)],
+    ) -> Result<Self> {
+        let engine = create_engine();
+        let module = create_module(module, &engine)?;
+
+        Ok(Self {
+            engine,
+            module,
+            discard_output,
+            map_dirs,
+        })
+    }
+
+    /// Instantiate the meta-mutant with mutant `mutant_id` activated.
+    ///
+    /// `function` is the number of the function containing the mutant,
+    /// or `None` if no mutant is activated.
+    pub fn instantiate_mutant(
+        &self,
+        mutant_id: i64,
+        function: Option<u64>,
+    ) -> Result<WasmiRuntime> {
+        WasmiRuntime::instantiate(
+            &self.engine,
+            &self.module,
+            self.discard_output,
+            self.map_dirs,
+            MutantEnv::new(mutant_id),
+            function,
+        )
+    }
+}
+
+fn create_engine() -> Engine {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+fn create_module(module: &WasmModule, engine: &Engine) -> Result<Module> {
+    let bytecode: Vec<u8> = module.to_bytes()?;
+    let module = Module::new(engine, &bytecode[..]).context("Failed to create wasmi module")?;
+
+    Ok(module)
+}
+
+/// Linker that remembers which functions it defines
+struct HostFunctions {
+    linker: Linker<HostState>,
+    defined: Vec<(&'static str, &'static str)>,
+}
+
+impl HostFunctions {
+    fn define<Params, Args>(
+        &mut self,
+        module: &'static str,
+        name: &'static str,
+        func: impl IntoFunc<HostState, Params, Args>,
+    ) -> Result<()> {
+        self.linker
+            .func_wrap(module, name, func)
+            .map_err(|e| anyhow!("{e}"))?;
+        self.defined.push((module, name));
+        Ok(())
+    }
+
+    fn is_defined(&self, module: &str, name: &str) -> bool {
+        self.defined
+            .iter()
+            .any(|defined| defined.0 == module && defined.1 == name)
+    }
+}
+
+fn create_linker(engine: &Engine, module: &Module) -> Result<Linker<HostState>> {
+    let mut functions = HostFunctions {
+        linker: Linker::new(engine),
+        defined: Vec::new(),
+    };
+    add_trace_functions(&mut functions)?;
+    add_wasi_functions(&mut functions)?;
+
+    // Imports that are not emulated only trap once they are called,
+    // so that modules importing them can still be executed
+    let mut stubbed = Vec::new();
+    for import in module.imports() {
+        if let ExternType::Func(ty) = import.ty() {
+            if !functions.is_defined(import.module(), import.name()) {
+                let name = format!("{}.{}", import.module(), import.name());
+                stubbed.push(name.clone());
+                functions
+                    .linker
+                    .func_new(
+                        import.module(),
+                        import.name(),
+                        ty.clone(),
+                        move |_, _, _| Err(Trap::new(format!("{name} is not supported by wasmut"))),
+                    )
+                    .map_err(|e| anyhow!("{e}"))?;
+            }
+        }
+    }
+
+    if !stubbed.is_empty() {
+        warnings::warn(
+            WarningCategory::StubbedImports,
+            format!(
+                "Imported functions {} are not supported by the wasmi runtime, \
+                calling them traps",
+                stubbed.join(", ")
+            ),
+        );
+    }
+
+    Ok(functions.linker)
+}
+
+fn add_trace_functions(functions: &mut HostFunctions) -> Result<()> {
+    functions.define(
+        WASMUT_API_NAMESPACE,
+        "__wasmut_trace",
+        |caller: Caller<HostState>, address: i64| caller.data().mutant_env.trace(address),
+    )?;
+    functions.define(
+        WASMUT_API_NAMESPACE,
+        "__wasmut_trace_branch",
+        |caller: Caller<HostState>, condition: i32, address: i64| {
+            caller.data().mutant_env.trace_branch(condition, address)
+        },
+    )?;
+    functions.define(
+        WASMUT_API_NAMESPACE,
+        "__wasmut_trace_comparison_i32",
+        |caller: Caller<HostState>, result: i32, lhs: i32, rhs: i32, address: i64| {
+            caller
+                .data()
+                .mutant_env
+                .trace_comparison(result, lhs, rhs, address)
+        },
+    )?;
+    functions.define(
+        WASMUT_API_NAMESPACE,
+        "__wasmut_trace_comparison_i64",
+        |caller: Caller<HostState>, result: i32, lhs: i64, rhs: i64, address: i64| {
+            caller
+                .data()
+                .mutant_env
+                .trace_comparison(result, lhs, rhs, address)
+        },
+    )?;
+    functions.define(
+        WASMUT_API_NAMESPACE,
+        "__wasmut_trace_comparison_f32",
+        |caller: Caller<HostState>, result: i32, lhs: F32, rhs: F32, address: i64| {
+            caller.data().mutant_env.trace_comparison(
+                result,
+                f32::from(lhs),
+                f32::from(rhs),
+                address,
+            )
+        },
+    )?;
+    functions.define(
+        WASMUT_API_NAMESPACE,
+        "__wasmut_trace_comparison_f64",
+        |caller: Caller<HostState>, result: i32, lhs: F64, rhs: F64, address: i64| {
+            caller.data().mutant_env.trace_comparison(
+                result,
+                f64::from(lhs),
+                f64::from(rhs),
+                address,
+            )
+        },
+    )?;
+    functions.define(
+        WASMUT_API_NAMESPACE,
+        "__wasmut_check_mutant_id",
+        |caller: Caller<HostState>, mutant_id: i64| {
+            caller.data().mutant_env.check_mutant_id(mutant_id)
+        },
+    )
+}
+
+fn add_wasi_functions(functions: &mut HostFunctions) -> Result<()> {
+    functions.define(WASI_NAMESPACE, "args_sizes_get", args_sizes_get)?;
+    functions.define(WASI_NAMESPACE, "args_get", args_get)?;
+    functions.define(WASI_NAMESPACE, "environ_sizes_get", environ_sizes_get)?;
+    functions.define(WASI_NAMESPACE, "environ_get", environ_get)?;
+    functions.define(WASI_NAMESPACE, "fd_write", fd_write)?;
+    functions.define(WASI_NAMESPACE, "fd_read", fd_read)?;
+    functions.define(WASI_NAMESPACE, "fd_close", fd_close)?;
+    functions.define(WASI_NAMESPACE, "fd_seek", fd_seek)?;
+    functions.define(WASI_NAMESPACE, "fd_fdstat_get", fd_fdstat_get)?;
+    functions.define(WASI_NAMESPACE, "fd_prestat_get", fd_prestat_get)?;
+    functions.define(WASI_NAMESPACE, "fd_prestat_dir_name", fd_prestat_dir_name)?;
+    functions.define(WASI_NAMESPACE, "proc_exit", proc_exit)?;
+    functions.define(WASI_NAMESPACE, "clock_time_get", clock_time_get)?;
+    functions.define(WASI_NAMESPACE, "random_get", random_get)?;
+    functions.define(WASI_NAMESPACE, "sched_yield", sched_yield)
+}
+
+/// Memory exported by the calling module
+fn memory(caller: &Caller<HostState>) -> Result<wasmi::Memory, Trap> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Trap::new("module does not export its memory"))
+}
+
+fn read_bytes(caller: &Caller<HostState>, address: i32, length: usize) -> Result<Vec<u8>, Trap> {
+    let mut buffer = vec![0; length];
+    memory(caller)?
+        .read(caller, address as u32 as usize, &mut buffer)
+        .map_err(|e| Trap::new(e.to_string()))?;
+    Ok(buffer)
+}
+
+fn write_bytes(caller: &mut Caller<HostState>, address: i32, bytes: &[u8]) -> Result<(), Trap> {
+    memory(caller)?
+        .write(caller, address as u32 as usize, bytes)
+        .map_err(|e| Trap::new(e.to_string()))
+}
+
+fn read_u32(caller: &Caller<HostState>, address: i32) -> Result<u32, Trap> {
+    let bytes = read_bytes(caller, address, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn write_u32(caller: &mut Caller<HostState>, address: i32, value: u32) -> Result<(), Trap> {
+    write_bytes(caller, address, &value.to_le_bytes())
+}
+
+/// Write a list of null-terminated strings and pointers to them
+fn write_strings(
+    caller: &mut Caller<HostState>,
+    strings: &[String],
+    pointers: i32,
+    buffer: i32,
+) -> Result<i32, Trap> {
+    let mut offset = buffer as u32;
+    for (i, string) in strings.iter().enumerate() {
+        write_u32(caller, pointers + 4 * i as i32, offset)?;
+        write_bytes(caller, offset as i32, string.as_bytes())?;
+        write_bytes(caller, (offset as usize + string.len()) as i32, &[0])?;
+        offset += string.len() as u32 + 1;
+    }
+    Ok(ERRNO_SUCCESS)
+}
+
+fn args_sizes_get(mut caller: Caller<HostState>, count: i32, size: i32) -> Result<i32, Trap> {
+    let args = &caller.data().args;
+    let args_count = args.len() as u32;
+    let args_size = args.iter().map(|arg| arg.len() as u32 + 1).sum();
+
+    write_u32(&mut caller, count, args_count)?;
+    write_u32(&mut caller, size, args_size)?;
+    Ok(ERRNO_SUCCESS)
+}
+
+fn args_get(mut caller: Caller<HostState>, pointers: i32, buffer: i32) -> Result<i32, Trap> {
+    let args = caller.data().args.clone();
+    write_strings(&mut caller, &args, pointers, buffer)
+}
+
+fn environ_sizes_get(mut caller: Caller<HostState>, count: i32, size: i32) -> Result<i32, Trap> {
+    write_u32(&mut caller, count, 0)?;
+    write_u32(&mut caller, size, 0)?;
+    Ok(ERRNO_SUCCESS)
+}
+
+fn environ_get(_: Caller<HostState>, _pointers: i32, _buffer: i32) -> Result<i32, Trap> {
+    Ok(ERRNO_SUCCESS)
+}
+
+fn fd_write(
+    mut caller: Caller<HostState>,
+    fd: i32,
+    iovs: i32,
+    iovs_len: i32,
+    written: i32,
+) -> Result<i32, Trap> {
+    if fd != 1 && fd != 2 {
+        return Ok(ERRNO_BADF);
+    }
+
+    let mut content = Vec::new();
+    for i in 0..iovs_len {
+        let iov = iovs + 8 * i;
+        let address = read_u32(&caller, iov)?;
+        let length = read_u32(&caller, iov + 4)?;
+        content.extend(read_bytes(&caller, address as i32, length as usize)?);
+    }
+
+    match &caller.data().captured_output {
+        Some(output) if fd == 1 => output.stdout.lock().unwrap().extend(&content),
+        Some(output) => output.stderr.lock().unwrap().extend(&content),
+        None if fd == 1 => {
+            let _ = std::io::stdout().write_all(&content);
+        }
+        None => {
+            let _ = std::io::stderr().write_all(&content);
+        }
+    }
+
+    write_u32(&mut caller, written, content.len() as u32)?;
+    Ok(ERRNO_SUCCESS)
+}
+
+fn fd_read(
+    mut caller: Caller<HostState>,
+    fd: i32,
+    _iovs: i32,
+    _iovs_len: i32,
+    read: i32,
+) -> Result<i32, Trap> {
+    if fd != 0 {
+        return Ok(ERRNO_BADF);
+    }
+
+    // stdin is always empty
+    write_u32(&mut caller, read, 0)?;
+    Ok(ERRNO_SUCCESS)
+}
+
+fn fd_close(_: Caller<HostState>, fd: i32) -> Result<i32, Trap> {
+    Ok(if (0..=2).contains(&fd) {
+        ERRNO_SUCCESS
+    } else {
+        ERRNO_BADF
+    })
+}
+
+fn fd_seek(
+    _: Caller<HostState>,
+    fd: i32,
+    _offset: i64,
+    _whence: i32,
+    _new_offset: i32,
+) -> Result<i32, Trap> {
+    Ok(if (0..=2).contains(&fd) {
+        ERRNO_SPIPE
+    } else {
+        ERRNO_BADF
+    })
+}
+
+fn fd_fdstat_get(mut caller: Caller<HostState>, fd: i32, stat: i32) -> Result<i32, Trap> {
+    if !(0..=2).contains(&fd) {
+        return Ok(ERRNO_BADF);
+    }
+
+    // File type, flags and rights; all rights are granted
+    let mut fdstat = [0u8; 24];
+    fdstat[0] = FILETYPE_CHARACTER_DEVICE;
+    fdstat[8..24].fill(0xff);
+    write_bytes(&mut caller, stat, &fdstat)?;
+    Ok(ERRNO_SUCCESS)
+}
+
+fn fd_prestat_get(_: Caller<HostState>, _fd: i32, _prestat: i32) -> Result<i32, Trap> {
+    // No directories are preopened
+    Ok(ERRNO_BADF)
+}
+
+fn fd_prestat_dir_name(
+    _: Caller<HostState>,
+    _fd: i32,
+    _path: i32,
+    _path_len: i32,
+) -> Result<i32, Trap> {
+    Ok(ERRNO_BADF)
+}
+
+fn proc_exit(_: Caller<HostState>, exit_code: i32) -> Result<(), Trap> {
+    Err(Trap::i32_exit(exit_code))
+}
+
+fn clock_time_get(
+    mut caller: Caller<HostState>,
+    _clock_id: i32,
+    _precision: i64,
+    time: i32,
+) -> Result<i32, Trap> {
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return Ok(ERRNO_FAULT);
+    };
+    write_bytes(&mut caller, time, &(now.as_nanos() as u64).to_le_bytes())?;
+    Ok(ERRNO_SUCCESS)
+}
+
+fn random_get(mut caller: Caller<HostState>, buffer: i32, length: i32) -> Result<i32, Trap> {
+    // Random numbers are deterministic, so that all mutants see the same sequence
+    let mut bytes = Vec::with_capacity(length as usize);
+    let state = &mut caller.data_mut().random_state;
+    while bytes.len() < length as usize {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        bytes.extend(state.to_le_bytes());
+    }
+    bytes.truncate(length as usize);
+
+    write_bytes(&mut caller, buffer, &bytes)?;
+    Ok(ERRNO_SUCCESS)
+}
+
+fn sched_yield(_: Caller<HostState>) -> Result<i32, Trap> {
+    Ok(ERRNO_SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmut_wasm::elements::{Instruction, Instructions};
+
+    /// Module whose `_start` function adds two constants and returns
+    fn simple_add() -> Result<WasmModule<'static>> {
+        crate::fixtures::start_function(vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::I32Add,
+            Instruction::Drop,
+        ])
+    }
+
+    #[test]
+    fn test_run_entry_point() -> Result<()> {
+        let module = simple_add()?;
+        let mut runtime = WasmiRuntime::new(&module, true, &[])?;
+
+        let result = runtime.call_test_function(ExecutionPolicy::RunUntilReturn)?;
+
+        assert!(matches!(
+            result,
+            ExecutionResult::ProcessExit { exit_code: 0, execution_cost } if execution_cost > 0
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_execution_limit() -> Result<()> {
+        let module = simple_add()?;
+        let mut runtime = WasmiRuntime::new(&module, true, &[])?;
+
+        let result = runtime.call_test_function(ExecutionPolicy::RunUntilLimit { limit: 1 })?;
+
+        assert!(matches!(result, ExecutionResult::Timeout));
+        Ok(())
+    }
+
+    #[test]
+    fn map_dirs_are_rejected() -> Result<()> {
+        let module = simple_add()?;
+        let result = WasmiRuntime::new(&module, true, &[("a".into(), "b".into())]);
+
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn compiler_display() {
+        assert_eq!("wasmi", format!("{}", Compiler::Interpreter));
+    }
+
+    #[test]
+    fn unsupported_imports_are_stubbed() -> Result<()> {
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Instruction::End]))
+            .build()
+            .build()
+            .import()
+            .module("env")
+            .field("unsupported_function")
+            .external()
+            .func(0)
+            .build()
+            .build();
+        let module = WasmModule::from_bytes(module.into_bytes()?, "module.wasm")?;
+
+        let engine = create_engine();
+        create_linker(&engine, &create_module(&module, &engine)?)?;
+
+        assert!(warnings::collected().iter().any(|warning| {
+            warning.category == WarningCategory::StubbedImports
+                && warning.message.contains("env.unsupported_function")
+        }));
+        Ok(())
+    }
+}
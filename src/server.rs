@@ -0,0 +1,664 @@
+//! HTTP API for orchestrating mutation runs (`wasmut serve-api`).
+//!
+//! Submitted runs are queued and executed one after another by a worker thread.
+//! All requests have to authenticate with `Authorization: Bearer <token>`.
+//! Uploaded configurations may only set options that do not access the host,
+//! e.g. mapping directories or configuring databases, caches or webhooks is rejected.
+//! At most `MAX_QUEUED_RUNS` runs wait for their execution, further runs are rejected.
+//! Endpoints:
+//!  - `POST /runs`: Submit a run, the body is a JSON object with the path of the
+//!    module and an optional configuration (TOML). Returns the queued run.
+//!  - `GET /runs`: List all runs and their status.
+//!  - `GET /runs/<id>`: Status of a run.
+//!  - `GET /runs/<id>/report`: JSON report of a finished run.
+
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    net::ToSocketAddrs,
+    sync::{mpsc, Arc, Mutex, MutexGuard},
+    thread,
+    time::Instant,
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ErrorKind, reporter::json::JSONReporter, warnings, Config, Executor, MutationEngine,
+    WasmModule,
+};
+
+/// Default address of the API server
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// Maximum size of a request body in bytes
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Number of finished or failed runs that are kept, older ones are evicted
+const MAX_FINISHED_RUNS: usize = 100;
+
+/// Number of runs that may wait for their execution
+const MAX_QUEUED_RUNS: usize = 16;
+
+/// Sections and options that uploaded configurations may set. Options that access
+/// the host, such as mapped directories, golden files or path rewrites, are missing,
+/// so options added later are rejected unless they are added here.
+const REMOTE_OPTIONS: &[(&str, &[&str])] = &[
+    (
+        "engine",
+        &[
+            "timeout_multiplier",
+            "timeout_warning_threshold",
+            "coverage_based_execution",
+            "meta_mutant",
+            "meta_mutant_chunk_size",
+            "meta_mutant_activation",
+            "trace_survivors",
+            "runtime",
+            "hybrid_threshold",
+            "instructions_per_second",
+            "expensive_function_threshold",
+            "expensive_function_action",
+            "expensive_function_timeout_multiplier",
+        ],
+    ),
+    (
+        "filter",
+        &[
+            "allowed_files",
+            "allowed_functions",
+            "require_source_info",
+            "min_function_instructions",
+            "most_complex_functions",
+            "max_mutants_per_function",
+            "overrides",
+            "preset",
+        ],
+    ),
+    (
+        "report",
+        &[
+            "thresholds",
+            "cost_unit",
+            "group_variants",
+            "explain_skipped",
+            "context_lines",
+            "summary_interval_minutes",
+            "summary_interval_mutants",
+        ],
+    ),
+    (
+        "operators",
+        &["preset", "enabled_operators", "disabled_operators"],
+    ),
+    ("kill_criteria", &["criteria", "combine"]),
+];
+
+/// A run submitted via `POST /runs`
+#[derive(Deserialize, Debug, Clone)]
+pub struct RunRequest {
+    /// Path of the module on the host running the server
+    pub module: String,
+
+    /// Configuration in TOML format. The default configuration is used if not set
+    #[serde(default)]
+    pub config: Option<String>,
+}
+
+impl RunRequest {
+    /// Parse the configuration of the run. Only options in `REMOTE_OPTIONS`
+    /// are accepted, all others could give access to the host running the server.
+    pub fn config(&self) -> Result<Config> {
+        let config = match &self.config {
+            Some(config) => config,
+            None => return Ok(Config::default()),
+        };
+
+        let table: toml::Table = toml::from_str(config).context(ErrorKind::Config)?;
+        for (name, value) in &table {
+            let allowed = REMOTE_OPTIONS
+                .iter()
+                .find(|(section, _)| section == name)
+                .map(|(_, options)| *options)
+                .unwrap_or_default();
+
+            let section = match value.as_table() {
+                Some(section) if !allowed.is_empty() => section,
+                _ => return Err(not_allowed(name)),
+            };
+            if let Some(option) = section.keys().find(|key| !allowed.contains(&key.as_str())) {
+                return Err(not_allowed(&format!("{name}.{option}")));
+            }
+        }
+
+        Config::parse(config)
+    }
+}
+
+fn not_allowed(option: &str) -> anyhow::Error {
+    anyhow!("{option} is not allowed in configurations submitted to the API")
+        .context(ErrorKind::Config)
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Queued,
+    Running,
+    Finished,
+    Failed,
+}
+
+/// Status of a submitted run, as returned by the API
+#[derive(Serialize, Debug, Clone)]
+pub struct RunInfo {
+    pub id: usize,
+    pub module: String,
+    pub status: RunStatus,
+    /// Error message, if the run failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct Run {
+    info: RunInfo,
+    request: RunRequest,
+    /// JSON report, once the run is finished
+    report: Option<String>,
+}
+
+/// Executes a run and returns its JSON report
+pub type RunExecutor = dyn Fn(&RunRequest) -> Result<String> + Send + Sync;
+
+/// Response to an API request
+#[derive(Debug)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl ApiResponse {
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string_pretty(value).unwrap_or_default(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+}
+
+/// Submitted runs by ID
+#[derive(Default)]
+struct Runs {
+    runs: BTreeMap<usize, Run>,
+    next_id: usize,
+}
+
+impl Runs {
+    /// Forget the oldest finished and failed runs, keeping `MAX_FINISHED_RUNS`
+    fn evict(&mut self) {
+        let done: Vec<usize> = self
+            .runs
+            .iter()
+            .filter(|(_, run)| matches!(run.info.status, RunStatus::Finished | RunStatus::Failed))
+            .map(|(&id, _)| id)
+            .collect();
+
+        for id in done
+            .iter()
+            .take(done.len().saturating_sub(MAX_FINISHED_RUNS))
+        {
+            self.runs.remove(id);
+        }
+    }
+}
+
+/// State of the API: all submitted runs, and the queue of the worker thread
+pub struct Api {
+    runs: Arc<Mutex<Runs>>,
+    queue: mpsc::SyncSender<usize>,
+    token: String,
+}
+
+impl Api {
+    /// Create the API and start the worker thread, which executes runs using `executor`.
+    /// Requests have to authenticate with `token`.
+    pub fn new(executor: Box<RunExecutor>, token: &str) -> Self {
+        let runs: Arc<Mutex<Runs>> = Default::default();
+        let (queue, receiver) = mpsc::sync_channel::<usize>(MAX_QUEUED_RUNS);
+
+        let worker_runs = runs.clone();
+        thread::spawn(move || {
+            for id in receiver {
+                let request = {
+                    let mut runs = lock(&worker_runs);
+                    let run = runs.runs.get_mut(&id).expect("Queued runs are not evicted");
+                    run.info.status = RunStatus::Running;
+                    run.request.clone()
+                };
+
+                log::info!("Executing run {id} ({})", request.module);
+                let result = executor(&request);
+
+                let mut runs = lock(&worker_runs);
+                let run = runs
+                    .runs
+                    .get_mut(&id)
+                    .expect("Running runs are not evicted");
+                match result {
+                    Ok(report) => {
+                        run.info.status = RunStatus::Finished;
+                        run.report = Some(report);
+                    }
+                    Err(e) => {
+                        log::error!("Run {id} failed: {e:?}");
+                        run.info.status = RunStatus::Failed;
+                        run.info.error = Some(format!("{e:#}"));
+                    }
+                }
+                runs.evict();
+            }
+        });
+
+        Self {
+            runs,
+            queue,
+            token: token.into(),
+        }
+    }
+
+    /// Handle a request and return the response. `authorization` is the
+    /// value of the `Authorization` header, if there is one.
+    pub fn handle(
+        &self,
+        method: &str,
+        url: &str,
+        authorization: Option<&str>,
+        body: &str,
+    ) -> ApiResponse {
+        let authorized = authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .filter(|token| constant_time_eq(token, &self.token))
+            .is_some();
+        if !authorized {
+            return ApiResponse::error(401, "Missing or invalid token");
+        }
+
+        let path: Vec<&str> = url
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        match (method, path.as_slice()) {
+            ("POST", ["runs"]) => self.submit(body),
+            ("GET", ["runs"]) => {
+                let runs: Vec<RunInfo> = lock(&self.runs)
+                    .runs
+                    .values()
+                    .map(|r| r.info.clone())
+                    .collect();
+                ApiResponse::json(200, &runs)
+            }
+            ("GET", ["runs", id]) => self.with_run(id, |run| ApiResponse::json(200, &run.info)),
+            ("GET", ["runs", id, "report"]) => self.with_run(id, |run| match &run.report {
+                Some(report) => ApiResponse {
+                    status: 200,
+                    body: report.clone(),
+                },
+                None => ApiResponse::error(409, "Run is not finished"),
+            }),
+            (_, ["runs", ..]) => ApiResponse::error(405, "Method not allowed"),
+            _ => ApiResponse::error(404, "Not found"),
+        }
+    }
+
+    fn submit(&self, body: &str) -> ApiResponse {
+        let request: RunRequest = match serde_json::from_str(body) {
+            Ok(request) => request,
+            Err(e) => return ApiResponse::error(400, &format!("Invalid request: {e}")),
+        };
+        if let Err(e) = request.config() {
+            return ApiResponse::error(400, &format!("Invalid configuration: {e:#}"));
+        }
+
+        // The worker only looks up the run once it acquired the lock,
+        // so the run can be inserted after it was queued
+        let mut runs = lock(&self.runs);
+        let info = RunInfo {
+            id: runs.next_id,
+            module: request.module.clone(),
+            status: RunStatus::Queued,
+            error: None,
+        };
+        match self.queue.try_send(info.id) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(_)) => {
+                return ApiResponse::error(
+                    429,
+                    &format!("Too many queued runs, at most {MAX_QUEUED_RUNS} may wait"),
+                )
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                return ApiResponse::error(500, "Worker thread is not running")
+            }
+        }
+
+        runs.next_id += 1;
+        runs.runs.insert(
+            info.id,
+            Run {
+                info: info.clone(),
+                request,
+                report: None,
+            },
+        );
+
+        ApiResponse::json(201, &info)
+    }
+
+    fn with_run<F: FnOnce(&Run) -> ApiResponse>(&self, id: &str, f: F) -> ApiResponse {
+        let runs = lock(&self.runs);
+        match id.parse::<usize>().ok().and_then(|id| runs.runs.get(&id)) {
+            Some(run) => f(run),
+            None => ApiResponse::error(404, "Unknown run"),
+        }
+    }
+}
+
+fn lock(runs: &Mutex<Runs>) -> MutexGuard<'_, Runs> {
+    runs.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Compare two tokens in time independent of the position of the first difference
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Random token for authenticating requests, if none was given
+pub fn generate_token() -> String {
+    (0..32)
+        .map(|_| format!("{:x}", rand::random::<u8>() % 16))
+        .collect()
+}
+
+/// Execute a run and return its JSON report
+pub fn execute_run(request: &RunRequest) -> Result<String> {
+    let start = Instant::now();
+    let config = request.config()?;
+
+    let module = WasmModule::from_file(&request.module)?;
+    module.require_uninstrumented()?;
+
+    // Warnings are collected per run
+    warnings::clear();
+
+    let discovery = MutationEngine::new(&config, 100)?.discover_mutation_positions(&module)?;
+    let executor = Executor::new(&config);
+    let outcomes = executor.execute_mutants(&module, &discovery.locations)?;
+    let mutants = crate::reporter::prepare_results(&module, outcomes)?;
+    let metadata = executor
+        .run_metadata(start.elapsed())
+        .with_filtered_mutants(discovery.filtered)
+        .with_scope(discovery.scope);
+
+    JSONReporter::new(config.report(), &request.module, &metadata)?
+        .with_unused_operators(discovery.operator_usage.unused())
+        .report_to_string(&mutants)
+}
+
+/// Serve the API on `address` until the process is terminated
+pub fn serve(address: &str, api: &Api) -> Result<()> {
+    let loopback = address
+        .to_socket_addrs()
+        .with_context(|| format!("Invalid address {address}"))?
+        .all(|address| address.ip().is_loopback());
+    if !loopback {
+        log::warn!(
+            "Listening on {address}, which is reachable from other hosts. \
+            Anyone with the token can execute modules on this host"
+        );
+    }
+
+    let server = tiny_http::Server::http(address)
+        .map_err(|e| anyhow!("{e}"))
+        .with_context(|| format!("Could not listen on {address}"))?;
+    log::info!("Listening on http://{address}");
+
+    let content_type =
+        tiny_http::Header::from_bytes("Content-Type", "application/json").expect("Header is valid");
+
+    for mut request in server.incoming_requests() {
+        let authorization = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Authorization"))
+            .map(|header| header.value.to_string());
+
+        let response = match read_body(&mut request) {
+            Ok(body) => api.handle(
+                request.method().as_str(),
+                request.url(),
+                authorization.as_deref(),
+                &body,
+            ),
+            Err(response) => response,
+        };
+
+        let response = tiny_http::Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(content_type.clone());
+        if let Err(e) = request.respond(response) {
+            log::warn!("Could not send response: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the body of `request`, which may be at most `MAX_BODY_SIZE` bytes long
+fn read_body(request: &mut tiny_http::Request) -> Result<String, ApiResponse> {
+    let too_large =
+        || ApiResponse::error(413, &format!("Request body exceeds {MAX_BODY_SIZE} bytes"));
+    if request.body_length().unwrap_or_default() > MAX_BODY_SIZE {
+        return Err(too_large());
+    }
+
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_SIZE as u64 + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| ApiResponse::error(400, &format!("Could not read request body: {e}")))?;
+    if body.len() > MAX_BODY_SIZE {
+        return Err(too_large());
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const TOKEN: &str = "secret";
+
+    fn api() -> Api {
+        Api::new(
+            Box::new(|request: &RunRequest| {
+                if request.module == "missing.wasm" {
+                    return Err(anyhow!("No such file"));
+                }
+                Ok(format!("{{\"module\": \"{}\"}}", request.module))
+            }),
+            TOKEN,
+        )
+    }
+
+    /// Send an authenticated request
+    fn handle(api: &Api, method: &str, url: &str, body: &str) -> ApiResponse {
+        api.handle(method, url, Some(&format!("Bearer {TOKEN}")), body)
+    }
+
+    fn wait_for(api: &Api, id: usize, status: RunStatus) -> serde_json::Value {
+        for _ in 0..100 {
+            let response = handle(api, "GET", &format!("/runs/{id}"), "");
+            let info: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+            if info["status"] == serde_json::to_value(status).unwrap() {
+                return info;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("Run {id} did not reach status {status:?}");
+    }
+
+    #[test]
+    fn submit_and_fetch_report() {
+        let api = api();
+
+        let response = handle(&api, "POST", "/runs", r#"{"module": "test.wasm"}"#);
+        assert_eq!(response.status, 201);
+        assert!(response.body.contains("\"id\": 0"));
+
+        wait_for(&api, 0, RunStatus::Finished);
+
+        let response = handle(&api, "GET", "/runs/0/report", "");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, r#"{"module": "test.wasm"}"#);
+
+        let response = handle(&api, "GET", "/runs", "");
+        assert!(response.body.contains("\"status\": \"finished\""));
+    }
+
+    #[test]
+    fn failed_run() {
+        let api = api();
+
+        handle(&api, "POST", "/runs/", r#"{"module": "missing.wasm"}"#);
+        let info = wait_for(&api, 0, RunStatus::Failed);
+        assert_eq!(info["error"], "No such file");
+
+        assert_eq!(handle(&api, "GET", "/runs/0/report", "").status, 409);
+    }
+
+    #[test]
+    fn invalid_requests() {
+        let api = api();
+
+        assert_eq!(handle(&api, "POST", "/runs", "{}").status, 400);
+        assert_eq!(handle(&api, "GET", "/runs/0", "").status, 404);
+        assert_eq!(handle(&api, "GET", "/runs/foo/report", "").status, 404);
+        assert_eq!(handle(&api, "DELETE", "/runs/0", "").status, 405);
+        assert_eq!(handle(&api, "GET", "/", "").status, 404);
+    }
+
+    #[test]
+    fn requests_require_token() {
+        let api = api();
+
+        assert_eq!(api.handle("GET", "/runs", None, "").status, 401);
+        assert_eq!(
+            api.handle("GET", "/runs", Some("Bearer secre"), "").status,
+            401
+        );
+        assert_eq!(api.handle("GET", "/runs", Some("secret"), "").status, 401);
+        assert_eq!(
+            api.handle(
+                "POST",
+                "/runs",
+                Some("Bearer other"),
+                r#"{"module": "a.wasm"}"#
+            )
+            .status,
+            401
+        );
+        assert_eq!(handle(&api, "GET", "/runs", "").status, 200);
+    }
+
+    #[test]
+    fn reject_host_options() {
+        let api = api();
+        let submit = |config: &str| {
+            let body = serde_json::json!({ "module": "test.wasm", "config": config });
+            handle(&api, "POST", "/runs", &body.to_string())
+        };
+
+        for config in [
+            "[engine]\nmap_dirs = [[\"/\", \"host\"]]",
+            "[database]\npath = \"/tmp/wasmut.db\"",
+            "[cache]\nresults = true",
+            "[notify]\nwebhook_url = \"http://169.254.169.254/\"",
+            "[kill_criteria]\ngolden_file = \"/etc/passwd\"",
+            "[report]\npath_rewrite = [\"/src\", \"/etc\"]",
+            "[suggest]\ninput_files = [\"/etc/passwd\"]",
+            "[engine]\nunknown_option = true",
+            "[unknown]\noption = true",
+            "engine = 1",
+        ] {
+            let response = submit(config);
+            assert_eq!(response.status, 400, "{config}");
+            assert!(response.body.contains("not allowed"), "{config}");
+        }
+
+        assert_eq!(submit("[engine]\ntimeout_multiplier = 4.0").status, 201);
+        assert_eq!(
+            submit("[filter]\n[[filter.overrides]]\nfiles = [\"a.c\"]\nsample_threshold = 10")
+                .status,
+            201
+        );
+    }
+
+    #[test]
+    fn reject_runs_when_queue_is_full() {
+        // Runs are blocked until `release` is dropped
+        let (release, blocked) = mpsc::channel::<()>();
+        let blocked = Mutex::new(blocked);
+        let api = Api::new(
+            Box::new(move |_: &RunRequest| {
+                let _ = blocked.lock().unwrap().recv();
+                Ok("{}".into())
+            }),
+            TOKEN,
+        );
+        let submit = || handle(&api, "POST", "/runs", r#"{"module": "test.wasm"}"#);
+
+        assert_eq!(submit().status, 201);
+        wait_for(&api, 0, RunStatus::Running);
+        for _ in 0..MAX_QUEUED_RUNS {
+            assert_eq!(submit().status, 201);
+        }
+        assert_eq!(submit().status, 429);
+
+        drop(release);
+        wait_for(&api, MAX_QUEUED_RUNS, RunStatus::Finished);
+        assert_eq!(submit().status, 201);
+    }
+
+    #[test]
+    fn evict_finished_runs() {
+        let api = api();
+        for id in 0..MAX_FINISHED_RUNS + 2 {
+            handle(&api, "POST", "/runs", r#"{"module": "test.wasm"}"#);
+            wait_for(&api, id, RunStatus::Finished);
+        }
+
+        assert_eq!(handle(&api, "GET", "/runs/0", "").status, 404);
+        assert_eq!(handle(&api, "GET", "/runs/1", "").status, 404);
+        assert_eq!(handle(&api, "GET", "/runs/2/report", "").status, 200);
+
+        let response = handle(&api, "GET", "/runs", "");
+        let runs: Vec<serde_json::Value> = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(runs.len(), MAX_FINISHED_RUNS);
+    }
+}
@@ -1,6 +1,8 @@
 pub(crate) const BASE_TEMPLATE: &str = include_str!("base.hbs");
 pub(crate) const SOURCE_VIEW: &str = include_str!("source_view.hbs");
 pub(crate) const INDEX: &str = include_str!("index.hbs");
+pub(crate) const WORKSPACE: &str = include_str!("workspace.hbs");
+pub(crate) const OPERATORS: &str = include_str!("operators.hbs");
 
 pub(crate) const DEFAULT_CONFIG: &str = include_str!("wasmut.toml");
 
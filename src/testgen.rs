@@ -0,0 +1,1102 @@
+//! Generate a corpus of small, self-checking WebAssembly modules (`wasmut testgen`).
+//!
+//! The modules do not import anything and export a `_start` function calling
+//! test functions, which trap as soon as a computation yields an unexpected
+//! value, so every mutant that changes a result is killed. Expected values are computed in Rust while the
+//! module is generated. The corpus exercises the mutation operators as well as
+//! engine features such as tables, `br_table` and memory growth, without
+//! requiring a compiler toolchain.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use wasmut_wasm::{
+    builder::{self, MemoryBuilder, ModuleBuilder, TableBuilder},
+    elements::{
+        BlockType, BrTableData, ExportEntry, FunctionNameSubsection, GlobalEntry, GlobalType,
+        InitExpr, Instruction, Instructions, Internal, Local, Module, NameSection, Section,
+        ValueType,
+    },
+};
+
+use crate::config::Config;
+
+/// Name of the generated module inside a corpus directory
+pub const MODULE_FILE_NAME: &str = "test.wasm";
+
+/// A generated module and the features it exercises
+pub struct GeneratedModule {
+    /// Name of the module, used as directory name
+    pub name: &'static str,
+
+    /// What the module exercises
+    pub description: &'static str,
+
+    /// False if wasmut is currently unable to load modules of this shape
+    pub supported: bool,
+
+    pub module: Module,
+}
+
+/// Name of the tested function, instruction and its result computed in Rust
+type BinaryOp<T, R> = (&'static str, Instruction, fn(T, T) -> R);
+type UnaryOp<T, R> = (&'static str, Instruction, fn(T) -> R);
+
+/// A constant value of one of the numeric types
+#[derive(Clone, Copy, Debug)]
+enum Value {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+}
+
+impl Value {
+    fn value_type(self) -> ValueType {
+        match self {
+            Value::I32(_) => ValueType::I32,
+            Value::I64(_) => ValueType::I64,
+            Value::F32(_) => ValueType::F32,
+            Value::F64(_) => ValueType::F64,
+        }
+    }
+
+    fn constant(self) -> Instruction {
+        match self {
+            Value::I32(v) => Instruction::I32Const(v),
+            Value::I64(v) => Instruction::I64Const(v),
+            Value::F32(v) => Instruction::F32Const(v.to_bits()),
+            Value::F64(v) => Instruction::F64Const(v.to_bits()),
+        }
+    }
+
+    fn not_equal(self) -> Instruction {
+        match self {
+            Value::I32(_) => Instruction::I32Ne,
+            Value::I64(_) => Instruction::I64Ne,
+            Value::F32(_) => Instruction::F32Ne,
+            Value::F64(_) => Instruction::F64Ne,
+        }
+    }
+}
+
+/// Incrementally builds a module whose `_start` function calls test functions
+struct Generator {
+    builder: ModuleBuilder,
+    names: Vec<String>,
+    data: Vec<(u32, Vec<u8>)>,
+    start: Vec<Instruction>,
+    /// Body and number of checks of the test function being generated
+    checks: Vec<Instruction>,
+    check_count: i32,
+}
+
+impl Generator {
+    fn new() -> Self {
+        Generator {
+            builder: builder::module(),
+            names: Vec::new(),
+            data: Vec::new(),
+            start: Vec::new(),
+            checks: Vec::new(),
+            check_count: 0,
+        }
+    }
+
+    /// Index the next added function will have
+    fn next_function(&self) -> u32 {
+        self.names.len() as u32
+    }
+
+    /// Index of the function type with the given signature
+    fn signature(&mut self, params: &[ValueType], results: &[ValueType]) -> u32 {
+        self.builder.push_signature(
+            builder::signature()
+                .with_params(params.to_vec())
+                .with_results(results.to_vec())
+                .build_sig(),
+        )
+    }
+
+    /// Add a function and return its index. The final `end` is appended.
+    fn function(
+        &mut self,
+        name: &str,
+        params: &[ValueType],
+        results: &[ValueType],
+        locals: &[ValueType],
+        mut body: Vec<Instruction>,
+    ) -> u32 {
+        body.push(Instruction::End);
+        let location = self.builder.push_function(
+            builder::function()
+                .signature()
+                .with_params(params.to_vec())
+                .with_results(results.to_vec())
+                .build()
+                .body()
+                .with_locals(locals.iter().map(|t| Local::new(1, *t)))
+                .with_instructions(Instructions::new(body))
+                .build()
+                .build(),
+        );
+        self.names.push(name.into());
+        location.body
+    }
+
+    /// Initialise linear memory at the given offset
+    fn data(&mut self, offset: u32, bytes: &[u8]) {
+        self.data.push((offset, bytes.to_vec()));
+    }
+
+    /// Add a function `test_{name}` containing the checks added by `checks`,
+    /// and call it from `_start`. Test functions return the number of
+    /// their checks, so that removing a call to them is detected as well.
+    ///
+    /// Keeping the checks out of `_start` keeps the functions small, which
+    /// matters for meta-mutants: a mutated function is executed with the
+    /// checks of all its mutations.
+    fn test(&mut self, name: &str, checks: impl FnOnce(&mut Self)) {
+        let outer = (
+            std::mem::take(&mut self.checks),
+            std::mem::take(&mut self.check_count),
+        );
+        checks(self);
+        let mut body = std::mem::replace(&mut self.checks, outer.0);
+        let count = std::mem::replace(&mut self.check_count, outer.1);
+        body.push(Instruction::I32Const(count));
+
+        let function = self.function(&format!("test_{name}"), &[], &[ValueType::I32], &[], body);
+        self.start.extend([
+            Instruction::Call(function),
+            Instruction::I32Const(count),
+            Instruction::I32Ne,
+            Instruction::If(BlockType::NoResult),
+            Instruction::Unreachable,
+            Instruction::End,
+        ]);
+    }
+
+    /// Trap unless `computation` leaves `expected` on the stack
+    fn check(&mut self, computation: Vec<Instruction>, expected: Value) {
+        self.check_count += 1;
+        self.checks.extend(computation);
+        self.checks.extend([
+            expected.constant(),
+            expected.not_equal(),
+            Instruction::If(BlockType::NoResult),
+            Instruction::Unreachable,
+            Instruction::End,
+        ]);
+    }
+
+    /// Add a function applying `op` to its parameters and check it for all cases
+    fn operation(&mut self, name: &str, op: Instruction, cases: &[(&[Value], Value)]) {
+        let (args, result) = cases[0];
+        let params: Vec<ValueType> = args.iter().map(|v| v.value_type()).collect();
+        let mut body: Vec<Instruction> = (0..params.len() as u32)
+            .map(Instruction::GetLocal)
+            .collect();
+        body.push(op);
+
+        let function = self.function(name, &params, &[result.value_type()], &[], body);
+        self.test(name, |gen| {
+            for (args, expected) in cases {
+                let mut computation: Vec<Instruction> = args.iter().map(|v| v.constant()).collect();
+                computation.push(Instruction::Call(function));
+                gen.check(computation, *expected);
+            }
+        });
+    }
+
+    fn binary<T: Copy, R>(
+        &mut self,
+        name: &str,
+        op: Instruction,
+        args: &[(T, T)],
+        value: fn(T) -> Value,
+        result: fn(R) -> Value,
+        f: impl Fn(T, T) -> R,
+    ) {
+        let cases: Vec<([Value; 2], Value)> = args
+            .iter()
+            .map(|&(a, b)| ([value(a), value(b)], result(f(a, b))))
+            .collect();
+        let cases: Vec<(&[Value], Value)> = cases.iter().map(|(a, r)| (&a[..], *r)).collect();
+        self.operation(name, op, &cases);
+    }
+
+    fn unary<T: Copy, R>(
+        &mut self,
+        name: &str,
+        op: Instruction,
+        args: &[T],
+        value: fn(T) -> Value,
+        result: fn(R) -> Value,
+        f: impl Fn(T) -> R,
+    ) {
+        let cases: Vec<([Value; 1], Value)> =
+            args.iter().map(|&a| ([value(a)], result(f(a)))).collect();
+        let cases: Vec<(&[Value], Value)> = cases.iter().map(|(a, r)| (&a[..], *r)).collect();
+        self.operation(name, op, &cases);
+    }
+
+    /// Finish the module by adding `_start`, the memory and the name section
+    fn build(mut self) -> Module {
+        debug_assert!(self.checks.is_empty(), "checks outside of a test function");
+        let start = std::mem::take(&mut self.start);
+        let start = self.function("_start", &[], &[], &[], start);
+
+        let mut memory = MemoryBuilder::new().with_min(1);
+        for (offset, bytes) in std::mem::take(&mut self.data) {
+            memory = memory.with_data(offset, bytes);
+        }
+        self.builder.push_memory(memory.build());
+        self.builder
+            .push_export(ExportEntry::new("_start".into(), Internal::Function(start)));
+        self.builder
+            .push_export(ExportEntry::new("memory".into(), Internal::Memory(0)));
+
+        let mut functions = FunctionNameSubsection::default();
+        for (index, name) in self.names.into_iter().enumerate() {
+            functions.names_mut().insert(index as u32, name);
+        }
+
+        let mut module = self.builder.build();
+        module
+            .sections_mut()
+            .push(Section::Name(NameSection::new(None, Some(functions), None)));
+        module
+    }
+}
+
+fn i32_value(v: i32) -> Value {
+    Value::I32(v)
+}
+
+fn i64_value(v: i64) -> Value {
+    Value::I64(v)
+}
+
+fn f32_value(v: f32) -> Value {
+    Value::F32(v)
+}
+
+fn f64_value(v: f64) -> Value {
+    Value::F64(v)
+}
+
+fn bool_value(v: bool) -> Value {
+    Value::I32(v as i32)
+}
+
+/// Body of a function returning `(k + 1) * 10` for case `k` of a `br_table`
+/// with `cases` targets, and `(cases + 1) * 10` for the default target
+fn br_table_dispatch(cases: u32) -> Vec<Instruction> {
+    let mut body = vec![Instruction::Block(BlockType::NoResult); cases as usize + 1];
+    body.push(Instruction::GetLocal(0));
+    body.push(Instruction::BrTable(Box::new(BrTableData {
+        table: (0..cases).collect(),
+        default: cases,
+    })));
+    for k in 0..=cases {
+        body.push(Instruction::End);
+        body.push(Instruction::I32Const((k as i32 + 1) * 10));
+        body.push(Instruction::Return);
+    }
+    body
+}
+
+fn arithmetic() -> Module {
+    use Instruction::*;
+
+    let mut gen = Generator::new();
+    let i32_args = [(-12, 5), (100, 7)];
+    let i64_args = [(-12, 5), ((1 << 40) + 3, 7)];
+    let f32_args = [(1.5, -2.25), (6.0, 0.75)];
+    let f64_args = [(1.5, -2.25), (6.0, 0.75)];
+
+    let i32_ops: [BinaryOp<i32, i32>; 15] = [
+        ("i32_add", I32Add, |a, b| a.wrapping_add(b)),
+        ("i32_sub", I32Sub, |a, b| a.wrapping_sub(b)),
+        ("i32_mul", I32Mul, |a, b| a.wrapping_mul(b)),
+        ("i32_div_s", I32DivS, |a, b| a.wrapping_div(b)),
+        ("i32_div_u", I32DivU, |a, b| {
+            ((a as u32) / (b as u32)) as i32
+        }),
+        ("i32_rem_s", I32RemS, |a, b| a.wrapping_rem(b)),
+        ("i32_rem_u", I32RemU, |a, b| {
+            ((a as u32) % (b as u32)) as i32
+        }),
+        ("i32_and", I32And, |a, b| a & b),
+        ("i32_or", I32Or, |a, b| a | b),
+        ("i32_xor", I32Xor, |a, b| a ^ b),
+        ("i32_shl", I32Shl, |a, b| a.wrapping_shl(b as u32)),
+        ("i32_shr_s", I32ShrS, |a, b| a.wrapping_shr(b as u32)),
+        ("i32_shr_u", I32ShrU, |a, b| {
+            (a as u32).wrapping_shr(b as u32) as i32
+        }),
+        ("i32_rotl", I32Rotl, |a, b| a.rotate_left(b as u32)),
+        ("i32_rotr", I32Rotr, |a, b| a.rotate_right(b as u32)),
+    ];
+    for (name, op, f) in i32_ops {
+        gen.binary(name, op, &i32_args, i32_value, i32_value, f);
+    }
+
+    let i64_ops: [BinaryOp<i64, i64>; 15] = [
+        ("i64_add", I64Add, |a, b| a.wrapping_add(b)),
+        ("i64_sub", I64Sub, |a, b| a.wrapping_sub(b)),
+        ("i64_mul", I64Mul, |a, b| a.wrapping_mul(b)),
+        ("i64_div_s", I64DivS, |a, b| a.wrapping_div(b)),
+        ("i64_div_u", I64DivU, |a, b| {
+            ((a as u64) / (b as u64)) as i64
+        }),
+        ("i64_rem_s", I64RemS, |a, b| a.wrapping_rem(b)),
+        ("i64_rem_u", I64RemU, |a, b| {
+            ((a as u64) % (b as u64)) as i64
+        }),
+        ("i64_and", I64And, |a, b| a & b),
+        ("i64_or", I64Or, |a, b| a | b),
+        ("i64_xor", I64Xor, |a, b| a ^ b),
+        ("i64_shl", I64Shl, |a, b| a.wrapping_shl(b as u32)),
+        ("i64_shr_s", I64ShrS, |a, b| a.wrapping_shr(b as u32)),
+        ("i64_shr_u", I64ShrU, |a, b| {
+            (a as u64).wrapping_shr(b as u32) as i64
+        }),
+        ("i64_rotl", I64Rotl, |a, b| a.rotate_left(b as u32)),
+        ("i64_rotr", I64Rotr, |a, b| a.rotate_right(b as u32)),
+    ];
+    for (name, op, f) in i64_ops {
+        gen.binary(name, op, &i64_args, i64_value, i64_value, f);
+    }
+
+    let f32_ops: [BinaryOp<f32, f32>; 6] = [
+        ("f32_add", F32Add, |a, b| a + b),
+        ("f32_sub", F32Sub, |a, b| a - b),
+        ("f32_mul", F32Mul, |a, b| a * b),
+        ("f32_div", F32Div, |a, b| a / b),
+        ("f32_min", F32Min, f32::min),
+        ("f32_max", F32Max, f32::max),
+    ];
+    for (name, op, f) in f32_ops {
+        gen.binary(name, op, &f32_args, f32_value, f32_value, f);
+    }
+
+    let f64_ops: [BinaryOp<f64, f64>; 6] = [
+        ("f64_add", F64Add, |a, b| a + b),
+        ("f64_sub", F64Sub, |a, b| a - b),
+        ("f64_mul", F64Mul, |a, b| a * b),
+        ("f64_div", F64Div, |a, b| a / b),
+        ("f64_min", F64Min, f64::min),
+        ("f64_max", F64Max, f64::max),
+    ];
+    for (name, op, f) in f64_ops {
+        gen.binary(name, op, &f64_args, f64_value, f64_value, f);
+    }
+
+    gen.build()
+}
+
+fn relational() -> Module {
+    use Instruction::*;
+
+    let mut gen = Generator::new();
+    // Both orders, the boundary and a pair where signedness matters
+    let i32_args = [(3, 5), (5, 5), (5, 3), (-1, 1)];
+    let i64_args = [(3, 5), (5, 5), (5, 3), (-1, 1)];
+    let f32_args = [(1.5, 2.5), (2.5, 2.5), (2.5, 1.5)];
+    let f64_args = [(1.5, 2.5), (2.5, 2.5), (2.5, 1.5)];
+
+    let i32_ops: [BinaryOp<i32, bool>; 10] = [
+        ("i32_eq", I32Eq, |a, b| a == b),
+        ("i32_ne", I32Ne, |a, b| a != b),
+        ("i32_lt_s", I32LtS, |a, b| a < b),
+        ("i32_lt_u", I32LtU, |a, b| (a as u32) < (b as u32)),
+        ("i32_gt_s", I32GtS, |a, b| a > b),
+        ("i32_gt_u", I32GtU, |a, b| (a as u32) > (b as u32)),
+        ("i32_le_s", I32LeS, |a, b| a <= b),
+        ("i32_le_u", I32LeU, |a, b| (a as u32) <= (b as u32)),
+        ("i32_ge_s", I32GeS, |a, b| a >= b),
+        ("i32_ge_u", I32GeU, |a, b| (a as u32) >= (b as u32)),
+    ];
+    for (name, op, f) in i32_ops {
+        gen.binary(name, op, &i32_args, i32_value, bool_value, f);
+    }
+
+    let i64_ops: [BinaryOp<i64, bool>; 10] = [
+        ("i64_eq", I64Eq, |a, b| a == b),
+        ("i64_ne", I64Ne, |a, b| a != b),
+        ("i64_lt_s", I64LtS, |a, b| a < b),
+        ("i64_lt_u", I64LtU, |a, b| (a as u64) < (b as u64)),
+        ("i64_gt_s", I64GtS, |a, b| a > b),
+        ("i64_gt_u", I64GtU, |a, b| (a as u64) > (b as u64)),
+        ("i64_le_s", I64LeS, |a, b| a <= b),
+        ("i64_le_u", I64LeU, |a, b| (a as u64) <= (b as u64)),
+        ("i64_ge_s", I64GeS, |a, b| a >= b),
+        ("i64_ge_u", I64GeU, |a, b| (a as u64) >= (b as u64)),
+    ];
+    for (name, op, f) in i64_ops {
+        gen.binary(name, op, &i64_args, i64_value, bool_value, f);
+    }
+
+    let f32_ops: [BinaryOp<f32, bool>; 6] = [
+        ("f32_eq", F32Eq, |a, b| a == b),
+        ("f32_ne", F32Ne, |a, b| a != b),
+        ("f32_lt", F32Lt, |a, b| a < b),
+        ("f32_gt", F32Gt, |a, b| a > b),
+        ("f32_le", F32Le, |a, b| a <= b),
+        ("f32_ge", F32Ge, |a, b| a >= b),
+    ];
+    for (name, op, f) in f32_ops {
+        gen.binary(name, op, &f32_args, f32_value, bool_value, f);
+    }
+
+    let f64_ops: [BinaryOp<f64, bool>; 6] = [
+        ("f64_eq", F64Eq, |a, b| a == b),
+        ("f64_ne", F64Ne, |a, b| a != b),
+        ("f64_lt", F64Lt, |a, b| a < b),
+        ("f64_gt", F64Gt, |a, b| a > b),
+        ("f64_le", F64Le, |a, b| a <= b),
+        ("f64_ge", F64Ge, |a, b| a >= b),
+    ];
+    for (name, op, f) in f64_ops {
+        gen.binary(name, op, &f64_args, f64_value, bool_value, f);
+    }
+
+    gen.build()
+}
+
+fn unary() -> Module {
+    use Instruction::*;
+
+    let mut gen = Generator::new();
+    let i32_args = [0x00f0, 0];
+    let i64_args = [0x0000_00f0_0000_0000, 0];
+    // No ties, `nearest` rounds them to even but `round` away from zero
+    let f32_args = [2.25, -6.75];
+    let f64_args = [2.25, -6.75];
+
+    let i32_ops: [UnaryOp<i32, i32>; 4] = [
+        ("i32_clz", I32Clz, |a| a.leading_zeros() as i32),
+        ("i32_ctz", I32Ctz, |a| a.trailing_zeros() as i32),
+        ("i32_popcnt", I32Popcnt, |a| a.count_ones() as i32),
+        ("i32_eqz", I32Eqz, |a| (a == 0) as i32),
+    ];
+    for (name, op, f) in i32_ops {
+        gen.unary(name, op, &i32_args, i32_value, i32_value, f);
+    }
+
+    let i64_ops: [UnaryOp<i64, i64>; 3] = [
+        ("i64_clz", I64Clz, |a| a.leading_zeros() as i64),
+        ("i64_ctz", I64Ctz, |a| a.trailing_zeros() as i64),
+        ("i64_popcnt", I64Popcnt, |a| a.count_ones() as i64),
+    ];
+    for (name, op, f) in i64_ops {
+        gen.unary(name, op, &i64_args, i64_value, i64_value, f);
+    }
+    gen.unary("i64_eqz", I64Eqz, &i64_args, i64_value, bool_value, |a| {
+        a == 0
+    });
+
+    let f32_ops: [UnaryOp<f32, f32>; 6] = [
+        ("f32_neg", F32Neg, |a| -a),
+        ("f32_abs", F32Abs, f32::abs),
+        ("f32_ceil", F32Ceil, f32::ceil),
+        ("f32_floor", F32Floor, f32::floor),
+        ("f32_trunc", F32Trunc, f32::trunc),
+        ("f32_nearest", F32Nearest, f32::round),
+    ];
+    for (name, op, f) in f32_ops {
+        gen.unary(name, op, &f32_args, f32_value, f32_value, f);
+    }
+    gen.unary(
+        "f32_sqrt",
+        F32Sqrt,
+        &[2.25, 16.0],
+        f32_value,
+        f32_value,
+        f32::sqrt,
+    );
+
+    let f64_ops: [UnaryOp<f64, f64>; 6] = [
+        ("f64_neg", F64Neg, |a| -a),
+        ("f64_abs", F64Abs, f64::abs),
+        ("f64_ceil", F64Ceil, f64::ceil),
+        ("f64_floor", F64Floor, f64::floor),
+        ("f64_trunc", F64Trunc, f64::trunc),
+        ("f64_nearest", F64Nearest, f64::round),
+    ];
+    for (name, op, f) in f64_ops {
+        gen.unary(name, op, &f64_args, f64_value, f64_value, f);
+    }
+    gen.unary(
+        "f64_sqrt",
+        F64Sqrt,
+        &[2.25, 16.0],
+        f64_value,
+        f64_value,
+        f64::sqrt,
+    );
+
+    gen.build()
+}
+
+fn conversion() -> Module {
+    use Instruction::*;
+
+    let mut gen = Generator::new();
+    let i32_args = [-5, 7];
+
+    gen.unary(
+        "i32_wrap_i64",
+        I32WrapI64,
+        &[(1 << 32) + 5, -3],
+        i64_value,
+        i32_value,
+        |a| a as i32,
+    );
+    gen.unary(
+        "i64_extend_i32_s",
+        I64ExtendSI32,
+        &i32_args,
+        i32_value,
+        i64_value,
+        |a| a as i64,
+    );
+    gen.unary(
+        "i64_extend_i32_u",
+        I64ExtendUI32,
+        &i32_args,
+        i32_value,
+        i64_value,
+        |a| a as u32 as i64,
+    );
+    gen.unary(
+        "i32_trunc_f64_s",
+        I32TruncSF64,
+        &[-6.75, 2.25],
+        f64_value,
+        i32_value,
+        |a| a as i32,
+    );
+    gen.unary(
+        "f64_convert_i32_s",
+        F64ConvertSI32,
+        &i32_args,
+        i32_value,
+        f64_value,
+        |a| a as f64,
+    );
+    gen.unary(
+        "f64_convert_i32_u",
+        F64ConvertUI32,
+        &i32_args,
+        i32_value,
+        f64_value,
+        |a| a as u32 as f64,
+    );
+    gen.unary(
+        "f32_demote_f64",
+        F32DemoteF64,
+        &[1.5, -0.25],
+        f64_value,
+        f32_value,
+        |a| a as f32,
+    );
+    gen.unary(
+        "f64_promote_f32",
+        F64PromoteF32,
+        &[1.5, -0.25],
+        f32_value,
+        f64_value,
+        |a| a as f64,
+    );
+    gen.unary(
+        "i32_reinterpret_f32",
+        I32ReinterpretF32,
+        &[1.5, -0.25],
+        f32_value,
+        i32_value,
+        |a| a.to_bits() as i32,
+    );
+
+    gen.build()
+}
+
+fn control_flow() -> Module {
+    use Instruction::*;
+
+    let mut gen = Generator::new();
+
+    let dispatch = gen.function(
+        "dispatch",
+        &[ValueType::I32],
+        &[ValueType::I32],
+        &[],
+        br_table_dispatch(3),
+    );
+    gen.test("dispatch", |gen| {
+        for (x, expected) in [(0, 10), (1, 20), (2, 30), (3, 40), (7, 40)] {
+            gen.check(vec![I32Const(x), Call(dispatch)], Value::I32(expected));
+        }
+    });
+
+    let sum_to = gen.function(
+        "sum_to",
+        &[ValueType::I32],
+        &[ValueType::I32],
+        &[ValueType::I32],
+        vec![
+            Block(BlockType::NoResult),
+            Loop(BlockType::NoResult),
+            GetLocal(0),
+            I32Eqz,
+            BrIf(1),
+            GetLocal(1),
+            GetLocal(0),
+            I32Add,
+            SetLocal(1),
+            GetLocal(0),
+            I32Const(1),
+            I32Sub,
+            SetLocal(0),
+            Br(0),
+            End,
+            End,
+            GetLocal(1),
+        ],
+    );
+    gen.test("sum_to", |gen| {
+        for (n, expected) in [(0, 0), (1, 1), (10, 55)] {
+            gen.check(vec![I32Const(n), Call(sum_to)], Value::I32(expected));
+        }
+    });
+
+    let factorial = gen.next_function();
+    gen.function(
+        "factorial",
+        &[ValueType::I64],
+        &[ValueType::I64],
+        &[],
+        vec![
+            GetLocal(0),
+            I64Const(1),
+            I64LeS,
+            If(BlockType::Value(ValueType::I64)),
+            I64Const(1),
+            Else,
+            GetLocal(0),
+            GetLocal(0),
+            I64Const(1),
+            I64Sub,
+            Call(factorial),
+            I64Mul,
+            End,
+        ],
+    );
+    gen.test("factorial", |gen| {
+        for (n, expected) in [(1, 1), (5, 120), (15, 1_307_674_368_000)] {
+            gen.check(vec![I64Const(n), Call(factorial)], Value::I64(expected));
+        }
+    });
+
+    gen.operation(
+        "choose",
+        Select,
+        &[
+            (
+                &[Value::I32(3), Value::I32(4), Value::I32(1)],
+                Value::I32(3),
+            ),
+            (
+                &[Value::I32(3), Value::I32(4), Value::I32(0)],
+                Value::I32(4),
+            ),
+        ],
+    );
+
+    // A call without result, only observable through the global it modifies
+    gen.builder.push_global(GlobalEntry::new(
+        GlobalType::new(ValueType::I32, true),
+        InitExpr::new(vec![I32Const(0), End]),
+    ));
+    let bump = gen.function(
+        "bump",
+        &[],
+        &[],
+        &[],
+        vec![GetGlobal(0), I32Const(3), I32Add, SetGlobal(0)],
+    );
+    gen.test("bump", |gen| {
+        gen.check(vec![Call(bump), Call(bump), GetGlobal(0)], Value::I32(6));
+    });
+
+    gen.build()
+}
+
+fn memory() -> Module {
+    use Instruction::*;
+
+    let mut gen = Generator::new();
+    gen.data(64, &[1, 2, 3, 4]);
+    gen.test("data", |gen| {
+        gen.check(vec![I32Const(0), I32Load(2, 64)], Value::I32(0x0403_0201));
+    });
+
+    let roundtrips: [(&str, Instruction, Instruction, Value, Value); 6] = [
+        (
+            "i32_8_s",
+            I32Store8(0, 0),
+            I32Load8S(0, 0),
+            Value::I32(-2),
+            Value::I32(-2),
+        ),
+        (
+            "i32_8_u",
+            I32Store8(0, 0),
+            I32Load8U(0, 0),
+            Value::I32(-2),
+            Value::I32(254),
+        ),
+        (
+            "i32_16_s",
+            I32Store16(1, 0),
+            I32Load16S(1, 0),
+            Value::I32(-2),
+            Value::I32(-2),
+        ),
+        (
+            "i64_32_u",
+            I64Store32(2, 0),
+            I64Load32U(2, 0),
+            Value::I64(-2),
+            Value::I64(0xffff_fffe),
+        ),
+        (
+            "i64",
+            I64Store(3, 0),
+            I64Load(3, 0),
+            Value::I64(1 << 40),
+            Value::I64(1 << 40),
+        ),
+        (
+            "f64",
+            F64Store(3, 0),
+            F64Load(3, 0),
+            Value::F64(-2.5),
+            Value::F64(-2.5),
+        ),
+    ];
+    for (name, store, load, stored, loaded) in roundtrips {
+        let function = gen.function(
+            &format!("roundtrip_{name}"),
+            &[stored.value_type()],
+            &[loaded.value_type()],
+            &[],
+            vec![I32Const(16), GetLocal(0), store, I32Const(16), load],
+        );
+        gen.test(&format!("roundtrip_{name}"), |gen| {
+            gen.check(vec![stored.constant(), Call(function)], loaded);
+        });
+    }
+
+    let grow = gen.function(
+        "grow",
+        &[ValueType::I32],
+        &[ValueType::I32],
+        &[],
+        vec![GetLocal(0), GrowMemory(0)],
+    );
+    gen.test("grow", |gen| {
+        gen.check(vec![I32Const(1), Call(grow)], Value::I32(1));
+        gen.check(vec![CurrentMemory(0)], Value::I32(2));
+        // Exceeds the maximum of 65536 pages
+        gen.check(vec![I32Const(0x10000), Call(grow)], Value::I32(-1));
+    });
+
+    gen.build()
+}
+
+fn table() -> Module {
+    use Instruction::*;
+
+    const ENTRIES: u32 = 16;
+
+    let mut gen = Generator::new();
+    let signature = gen.signature(&[ValueType::I32], &[ValueType::I32]);
+    let entries: Vec<u32> = (0..ENTRIES)
+        .map(|k| {
+            gen.function(
+                &format!("entry_{k}"),
+                &[ValueType::I32],
+                &[ValueType::I32],
+                &[],
+                vec![GetLocal(0), I32Const((k * k + 1) as i32), I32Add],
+            )
+        })
+        .collect();
+    gen.builder.push_table(
+        TableBuilder::new()
+            .with_min(ENTRIES)
+            .with_element(0, entries)
+            .build(),
+    );
+
+    let indirect = gen.function(
+        "indirect",
+        &[ValueType::I32, ValueType::I32],
+        &[ValueType::I32],
+        &[],
+        vec![GetLocal(1), GetLocal(0), CallIndirect(signature, 0)],
+    );
+    gen.test("indirect", |gen| {
+        for k in 0..ENTRIES {
+            gen.check(
+                vec![I32Const(k as i32), I32Const(3), Call(indirect)],
+                Value::I32((k * k + 4) as i32),
+            );
+        }
+    });
+
+    let dispatch = gen.function(
+        "dispatch",
+        &[ValueType::I32],
+        &[ValueType::I32],
+        &[],
+        br_table_dispatch(ENTRIES),
+    );
+    gen.test("dispatch", |gen| {
+        for k in 0..=ENTRIES {
+            gen.check(
+                vec![I32Const(k as i32), Call(dispatch)],
+                Value::I32((k as i32 + 1) * 10),
+            );
+        }
+    });
+
+    gen.build()
+}
+
+fn multi_value() -> Module {
+    use Instruction::*;
+
+    let mut gen = Generator::new();
+    let divmod = gen.function(
+        "divmod",
+        &[ValueType::I32, ValueType::I32],
+        &[ValueType::I32, ValueType::I32],
+        &[],
+        vec![
+            GetLocal(0),
+            GetLocal(1),
+            I32DivU,
+            GetLocal(0),
+            GetLocal(1),
+            I32RemU,
+        ],
+    );
+    gen.test("divmod", |gen| {
+        // The first check consumes the remainder, the second one the quotient
+        gen.check(vec![I32Const(17), I32Const(5), Call(divmod)], Value::I32(2));
+        gen.check(vec![], Value::I32(3));
+    });
+
+    gen.build()
+}
+
+/// Generate all modules of the corpus
+pub fn corpus() -> Vec<GeneratedModule> {
+    vec![
+        GeneratedModule {
+            name: "arithmetic",
+            description: "binary operators on all numeric types",
+            supported: true,
+            module: arithmetic(),
+        },
+        GeneratedModule {
+            name: "relational",
+            description: "comparisons at and around their boundaries",
+            supported: true,
+            module: relational(),
+        },
+        GeneratedModule {
+            name: "unary",
+            description: "unary operators on all numeric types",
+            supported: true,
+            module: unary(),
+        },
+        GeneratedModule {
+            name: "conversion",
+            description: "wrapping, extending and numeric conversions",
+            supported: true,
+            module: conversion(),
+        },
+        GeneratedModule {
+            name: "control_flow",
+            description: "br_table, loops, recursion, select and calls without result",
+            supported: true,
+            module: control_flow(),
+        },
+        GeneratedModule {
+            name: "memory",
+            description: "loads, stores, data segments and memory.grow",
+            supported: true,
+            module: memory(),
+        },
+        GeneratedModule {
+            name: "table",
+            description: "call_indirect through a table and a large br_table",
+            supported: true,
+            module: table(),
+        },
+        GeneratedModule {
+            name: "multi_value",
+            description: "functions returning multiple values",
+            supported: false,
+            module: multi_value(),
+        },
+    ]
+}
+
+/// Write the corpus to `directory`, one sub-directory per module containing
+/// the module and a default configuration file. Returns the written modules.
+pub fn write_corpus(directory: &Path) -> Result<Vec<(GeneratedModule, PathBuf)>> {
+    let mut written = Vec::new();
+
+    for generated in corpus() {
+        let module_directory = directory.join(generated.name);
+        std::fs::create_dir_all(&module_directory)
+            .with_context(|| format!("Failed to create directory {module_directory:?}"))?;
+
+        let path = module_directory.join(MODULE_FILE_NAME);
+        wasmut_wasm::serialize_to_file(&path, generated.module.clone())
+            .with_context(|| format!("Failed to write module {path:?}"))?;
+        Config::save_default_config(module_directory.join("wasmut.toml"))?;
+
+        written.push((generated, path));
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        executor::Executor,
+        mutation::MutationEngine,
+        policy::ExecutionPolicy,
+        reporter::MutationOutcome,
+        runtime::{create_runtime, ExecutionResult, RuntimeKind},
+        wasmmodule::WasmModule,
+    };
+
+    fn run(path: &Path, runtime: RuntimeKind) -> Result<ExecutionResult> {
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+        let mut runtime = create_runtime(runtime, &module, true, &[])?;
+        runtime.call_test_function(ExecutionPolicy::RunUntilReturn)
+    }
+
+    #[test]
+    fn supported_modules_pass_their_checks() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let written = write_corpus(dir.path())?;
+        assert_eq!(written.len(), corpus().len());
+
+        for (generated, path) in written.iter().filter(|(g, _)| g.supported) {
+            for runtime in [RuntimeKind::Wasmi, RuntimeKind::Wasmer] {
+                let result = run(path, runtime)?;
+                assert!(
+                    matches!(result, ExecutionResult::ProcessExit { exit_code: 0, .. }),
+                    "{} failed with {runtime:?}: {result:?}",
+                    generated.name
+                );
+            }
+            assert!(path.with_file_name("wasmut.toml").exists());
+        }
+        Ok(())
+    }
+
+    /// Outcome of every mutant of `module`, by offset and description of the mutant
+    fn mutant_outcomes(
+        module: &WasmModule,
+        engine: &str,
+    ) -> Result<Vec<(u64, String, MutationOutcome)>> {
+        let config = Config::parse(&format!("[engine]\n{engine}"))?;
+        let locations = MutationEngine::new(&config, 100)?
+            .discover_mutation_positions(module)?
+            .locations;
+
+        let mut outcomes: Vec<_> = Executor::new(&config)
+            .execute_mutants(module, &locations)?
+            .iter()
+            .map(|mutant| {
+                (
+                    mutant.offset,
+                    mutant.mutation_operator.description(),
+                    mutant.outcome(),
+                )
+            })
+            .collect();
+        outcomes.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        Ok(outcomes)
+    }
+
+    #[test]
+    fn meta_mutants_match_single_mutants() -> Result<()> {
+        for generated in corpus().into_iter().filter(|g| g.supported) {
+            let module = WasmModule::from_bytes(generated.module.into_bytes()?, generated.name)?;
+
+            // Timeouts and traps are reported differently by the runtimes,
+            // so meta-mutants are compared to single mutants of the same runtime
+            for runtime in ["wasmi", "wasmer"] {
+                let single = mutant_outcomes(
+                    &module,
+                    &format!("runtime = \"{runtime}\"\nmeta_mutant = false"),
+                )?;
+                assert!(!single.is_empty(), "{} has no mutants", generated.name);
+                assert_eq!(
+                    mutant_outcomes(
+                        &module,
+                        &format!("runtime = \"{runtime}\"\nmeta_mutant = true")
+                    )?,
+                    single,
+                    "{} with {runtime}",
+                    generated.name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_modules_are_rejected() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let written = write_corpus(dir.path())?;
+
+        for (generated, path) in written.iter().filter(|(g, _)| !g.supported) {
+            assert!(
+                WasmModule::from_file(path.to_str().unwrap()).is_err(),
+                "{} is supported now, update the corpus",
+                generated.name
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_results_trap() -> Result<()> {
+        let mut gen = Generator::new();
+        gen.test("wrong", |gen| {
+            gen.check(vec![Instruction::I32Const(1)], Value::I32(2))
+        });
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(MODULE_FILE_NAME);
+        wasmut_wasm::serialize_to_file(&path, gen.build())?;
+
+        let result = run(&path, RuntimeKind::Wasmi)?;
+        assert!(matches!(result, ExecutionResult::Error { .. }));
+        Ok(())
+    }
+}
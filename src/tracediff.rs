@@ -0,0 +1,196 @@
+//! Compare the basic blocks executed by a mutant with those of the unmutated module
+//! (`wasmut trace-diff`), to explain why a mutant survived or how it was killed.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::{addressresolver::CodeLocation, runtime::TracePoints, wasmmodule::WasmModule};
+
+/// Name used for blocks without function information
+const UNKNOWN_FUNCTION: &str = "<unknown>";
+
+/// A basic block, identified by the offset of its first instruction
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TracedBlock {
+    pub offset: u64,
+    pub location: CodeLocation,
+}
+
+/// First point at which the executions of the mutant and the baseline differ
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// Number of blocks both runs executed identically before diverging
+    pub index: usize,
+
+    /// Block executed by the baseline, `None` if the baseline terminated
+    pub baseline: Option<TracedBlock>,
+
+    /// Block executed by the mutant, `None` if the mutant terminated
+    pub mutant: Option<TracedBlock>,
+}
+
+/// Number of executed blocks in a function, for the baseline and the mutant
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FunctionCounts {
+    pub function: String,
+    pub baseline: u64,
+    pub mutant: u64,
+}
+
+/// Difference between the block traces of a mutant and the baseline
+#[derive(Serialize, Debug)]
+pub struct TraceDiff {
+    /// Number of blocks executed by the baseline
+    pub baseline_blocks: u64,
+
+    /// Number of blocks executed by the mutant
+    pub mutant_blocks: u64,
+
+    /// True if one of the traces was too long to be recorded completely
+    pub truncated: bool,
+
+    /// `None` if both runs executed the same sequence of blocks
+    pub first_divergence: Option<Divergence>,
+
+    /// Functions whose number of executed blocks differs, sorted by offset
+    pub functions: Vec<FunctionCounts>,
+}
+
+impl TraceDiff {
+    /// Compare the ordered traces of the baseline and the mutant
+    pub fn new(module: &WasmModule, baseline: &TracePoints, mutant: &TracePoints) -> Self {
+        let locator = module.locator();
+        let block = |offset: Option<u64>| {
+            offset.map(|offset| TracedBlock {
+                offset,
+                location: locator.lookup_address(offset).unwrap_or_default(),
+            })
+        };
+
+        let first_divergence = first_divergence(baseline.sequence(), mutant.sequence()).map(
+            |(index, baseline, mutant)| Divergence {
+                index,
+                baseline: block(baseline),
+                mutant: block(mutant),
+            },
+        );
+
+        let functions = module.function_offsets();
+        let baseline_counts = function_counts(&functions, baseline.sequence());
+        let mutant_counts = function_counts(&functions, mutant.sequence());
+
+        Self {
+            baseline_blocks: baseline_counts.values().sum(),
+            mutant_blocks: mutant_counts.values().sum(),
+            truncated: baseline.is_truncated() || mutant.is_truncated(),
+            first_divergence,
+            functions: compare_counts(&functions, &baseline_counts, &mutant_counts),
+        }
+    }
+}
+
+/// Find the first index at which both sequences differ, and the
+/// elements at this index. Returns `None` if the sequences are equal.
+fn first_divergence(baseline: &[u64], mutant: &[u64]) -> Option<(usize, Option<u64>, Option<u64>)> {
+    let index = baseline
+        .iter()
+        .zip(mutant)
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| baseline.len().min(mutant.len()));
+
+    if index == baseline.len() && index == mutant.len() {
+        return None;
+    }
+
+    Some((
+        index,
+        baseline.get(index).copied(),
+        mutant.get(index).copied(),
+    ))
+}
+
+/// Count the executed blocks per function, identified by the index
+/// into `functions`, which contains the start offsets of all functions
+fn function_counts(functions: &[(u64, String)], sequence: &[u64]) -> BTreeMap<usize, u64> {
+    let mut counts = BTreeMap::new();
+
+    for offset in sequence {
+        // Index of the last function starting at or before `offset`
+        let index = functions.partition_point(|(start, _)| start <= offset);
+        *counts.entry(index).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+fn compare_counts(
+    functions: &[(u64, String)],
+    baseline: &BTreeMap<usize, u64>,
+    mutant: &BTreeMap<usize, u64>,
+) -> Vec<FunctionCounts> {
+    let indices: BTreeSet<usize> = baseline.keys().chain(mutant.keys()).copied().collect();
+
+    indices
+        .into_iter()
+        .filter_map(|index| {
+            let baseline = baseline.get(&index).copied().unwrap_or(0);
+            let mutant = mutant.get(&index).copied().unwrap_or(0);
+
+            let function = match index.checked_sub(1).and_then(|i| functions.get(i)) {
+                Some((_, name)) => name.clone(),
+                None => UNKNOWN_FUNCTION.into(),
+            };
+
+            (baseline != mutant).then_some(FunctionCounts {
+                function,
+                baseline,
+                mutant,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divergence() {
+        assert_eq!(first_divergence(&[1, 2, 3], &[1, 2, 3]), None);
+        assert_eq!(first_divergence(&[], &[]), None);
+        assert_eq!(
+            first_divergence(&[1, 2, 3], &[1, 4, 3]),
+            Some((1, Some(2), Some(4)))
+        );
+        assert_eq!(
+            first_divergence(&[1, 2, 3], &[1, 2]),
+            Some((2, Some(3), None))
+        );
+        assert_eq!(first_divergence(&[1], &[1, 5]), Some((1, None, Some(5))));
+    }
+
+    #[test]
+    fn counts_per_function() {
+        let functions = vec![(10, "main".to_string()), (20, "f".to_string())];
+
+        let baseline = function_counts(&functions, &[10, 12, 20, 22, 20, 22]);
+        let mutant = function_counts(&functions, &[5, 10, 12, 20, 22]);
+
+        assert_eq!(
+            compare_counts(&functions, &baseline, &mutant),
+            vec![
+                FunctionCounts {
+                    function: UNKNOWN_FUNCTION.into(),
+                    baseline: 0,
+                    mutant: 1
+                },
+                FunctionCounts {
+                    function: "f".into(),
+                    baseline: 4,
+                    mutant: 2
+                },
+            ]
+        );
+    }
+}
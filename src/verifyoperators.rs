@@ -0,0 +1,214 @@
+//! Apply every discovered mutation on its own and validate the resulting
+//! module (`wasmut verify-operators`), to find operators that produce invalid
+//! bytecode for a module before starting a long mutation run.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    addressresolver::CodeLocation,
+    mutation::{MutantIds, MutationLocation},
+    reporter,
+    runtime::wasmer::Validator,
+    wasmmodule::WasmModule,
+};
+
+/// A mutant whose module failed validation
+#[derive(Serialize, Debug, Clone)]
+pub struct InvalidMutant {
+    pub id: String,
+    pub operator: String,
+    pub description: String,
+    pub offset: u64,
+    pub location: CodeLocation,
+
+    /// Reason reported by the validator
+    pub error: String,
+}
+
+/// Number of checked and invalid mutants of an operator
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct OperatorResult {
+    pub operator: String,
+    pub mutants: usize,
+    pub invalid: usize,
+}
+
+/// Result of validating all mutants of a module
+#[derive(Serialize, Debug)]
+pub struct Verification {
+    /// Number of validated mutants
+    pub mutants: usize,
+
+    /// Results per operator, sorted by name
+    pub operators: Vec<OperatorResult>,
+
+    /// Mutants producing invalid modules, sorted by offset
+    pub invalid_mutants: Vec<InvalidMutant>,
+}
+
+impl Verification {
+    /// Validate every mutation of `locations` on its own.
+    pub fn new(module: &WasmModule, locations: &[MutationLocation]) -> Result<Self> {
+        let mutants: Vec<(&MutationLocation, usize)> = locations
+            .iter()
+            .flat_map(|location| (0..location.mutations.len()).map(move |index| (location, index)))
+            .collect();
+
+        let errors: Vec<Option<String>> = mutants
+            .par_iter()
+            .map_init(Validator::default, |validator, (location, index)| {
+                let mutant = module.clone_and_mutate(location, *index);
+                validator.validate(&mutant).err().map(|e| format!("{e:#}"))
+            })
+            .collect();
+
+        let locator = module.locator();
+        let functions = module.function_offsets();
+        let mutant_ids = MutantIds::new(module)?;
+
+        let mut operators: BTreeMap<&str, OperatorResult> = BTreeMap::new();
+        let mut invalid_mutants = Vec::new();
+
+        for ((location, index), error) in mutants.iter().zip(errors) {
+            let operator = &location.mutations[*index].operator;
+            let result = operators
+                .entry(operator.dyn_name())
+                .or_insert_with(|| OperatorResult {
+                    operator: operator.dyn_name().into(),
+                    mutants: 0,
+                    invalid: 0,
+                });
+            result.mutants += 1;
+
+            if let Some(error) = error {
+                result.invalid += 1;
+
+                let mut code_location = locator.lookup_address(location.offset).unwrap_or_default();
+                if code_location.function.is_none() {
+                    code_location.function = reporter::function_at(&functions, location.offset);
+                }
+
+                invalid_mutants.push(InvalidMutant {
+                    id: mutant_ids.for_location(location, &**operator),
+                    operator: operator.dyn_name().into(),
+                    description: operator.description(),
+                    offset: location.offset,
+                    location: code_location,
+                    error,
+                });
+            }
+        }
+
+        invalid_mutants.sort_by_key(|mutant| mutant.offset);
+
+        Ok(Self {
+            mutants: mutants.len(),
+            operators: operators.into_values().collect(),
+            invalid_mutants,
+        })
+    }
+
+    /// True if all mutants are valid modules
+    pub fn is_valid(&self) -> bool {
+        self.invalid_mutants.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        mutation::{Mutation, MutationEngine},
+        operator::{ops::CallRemoveScalarCall, InstructionContext},
+        wasmmodule::CallRemovalCandidate,
+    };
+    use anyhow::Result;
+    use wasmut_wasm::elements::{Instruction, ValueType};
+
+    /// Module with an i64 addition and a function calling it
+    fn addition() -> Result<WasmModule<'static>> {
+        crate::fixtures::functions(
+            &[ValueType::I64, ValueType::I64],
+            &[ValueType::I64],
+            vec![
+                vec![
+                    Instruction::GetLocal(0),
+                    Instruction::GetLocal(1),
+                    Instruction::I64Add,
+                ],
+                vec![
+                    Instruction::GetLocal(0),
+                    Instruction::GetLocal(1),
+                    Instruction::Call(0),
+                ],
+            ],
+        )
+    }
+
+    #[test]
+    fn discovered_mutants_are_valid() -> Result<()> {
+        let module = addition()?;
+        let locations = MutationEngine::new(&Config::default(), 100)?
+            .discover_mutation_positions(&module)?
+            .locations;
+
+        let verification = Verification::new(&module, &locations)?;
+
+        assert!(verification.is_valid());
+        assert!(verification.mutants > 0);
+        assert_eq!(
+            verification.mutants,
+            verification
+                .operators
+                .iter()
+                .map(|o| o.mutants)
+                .sum::<usize>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_mutant_is_reported() -> Result<()> {
+        let module = addition()?;
+
+        // Removing the call based on a wrong signature leaves an i64 on the stack
+        let context = InstructionContext::new(vec![CallRemovalCandidate::FuncReturningScalar {
+            index: 0,
+            params: vec![ValueType::I32],
+            return_type: ValueType::I32,
+        }]);
+        let location = MutationLocation {
+            function_number: 1,
+            statement_number: 2,
+            offset: 42,
+            mutations: vec![Mutation {
+                id: 1,
+                operator: Box::new(
+                    CallRemoveScalarCall::new(&Instruction::Call(0), &context).unwrap(),
+                ),
+            }],
+        };
+
+        let verification = Verification::new(&module, &[location])?;
+
+        assert!(!verification.is_valid());
+        assert_eq!(verification.mutants, 1);
+        assert_eq!(
+            verification.operators,
+            vec![OperatorResult {
+                operator: "call_remove_scalar_call".into(),
+                mutants: 1,
+                invalid: 1
+            }]
+        );
+        let invalid = &verification.invalid_mutants[0];
+        assert_eq!(invalid.offset, 42);
+        assert!(!invalid.error.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! Re-execute a random sample of the mutants of a meta-mutant run one by one
+//! (`wasmut mutate --verify-sample`), to check that the meta-mutant
+//! optimization does not change the outcomes for a module.
+
+use anyhow::Result;
+use rand::seq::SliceRandom;
+
+use crate::{
+    config::Config,
+    executor::{ExecutedMutant, Executor},
+    mutation::{MutantIds, MutationLocation},
+    reporter::MutationOutcome,
+    runtime::ExecutionResult,
+    wasmmodule::WasmModule,
+};
+
+/// A mutant with different outcomes in meta-mutant and one-by-one mode
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+    pub id: String,
+    pub operator: String,
+    pub offset: u64,
+    pub meta_mutant: MutationOutcome,
+    pub one_by_one: MutationOutcome,
+}
+
+/// Result of re-executing a sample of mutants
+#[derive(Debug)]
+pub struct SampleVerification {
+    /// Number of re-executed mutants
+    pub checked: usize,
+
+    /// Mutants with different outcomes, sorted by offset
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Mutants that were skipped, not run, or failed because of an
+/// internal error are not sampled
+fn is_reproducible(mutant: &ExecutedMutant) -> bool {
+    !matches!(
+        mutant.result,
+        ExecutionResult::Skipped | ExecutionResult::NotRun | ExecutionResult::InternalError { .. }
+    )
+}
+
+/// Pick up to `sample_size` of the executed `results` at random, and return
+/// the location of each picked mutant, restricted to this mutant.
+fn pick_sample(
+    locations: &[MutationLocation],
+    results: &[ExecutedMutant],
+    sample_size: usize,
+) -> Vec<MutationLocation> {
+    let candidates: Vec<&ExecutedMutant> = results
+        .iter()
+        .filter(|mutant| is_reproducible(mutant))
+        .collect();
+
+    candidates
+        .choose_multiple(&mut rand::thread_rng(), sample_size)
+        .filter_map(|mutant| {
+            let location = locations
+                .iter()
+                .find(|location| location.offset == mutant.offset)?;
+            let mutation = location.mutations.iter().find(|mutation| {
+                mutation.operator.dyn_name() == mutant.mutation_operator.dyn_name()
+                    && mutation.operator.description() == mutant.mutation_operator.description()
+            })?;
+
+            Some(MutationLocation {
+                mutations: vec![mutation.clone()],
+                ..location.clone()
+            })
+        })
+        .collect()
+}
+
+/// Re-execute up to `sample_size` of the mutants of a meta-mutant run one by one,
+/// and compare their outcomes with `results`.
+pub fn verify_sample(
+    config: &Config,
+    module: &WasmModule,
+    locations: &[MutationLocation],
+    results: &[ExecutedMutant],
+    sample_size: usize,
+) -> Result<SampleVerification> {
+    let sample = pick_sample(locations, results, sample_size);
+    let executor = Executor::new(config).with_meta_mutant(false);
+    let rerun = executor.execute_mutants(module, &sample)?;
+    let mutant_ids = MutantIds::new(module)?;
+
+    let mut mismatches: Vec<Mismatch> = rerun
+        .iter()
+        .filter_map(|one_by_one| {
+            let meta_mutant = results.iter().find(|mutant| {
+                mutant.offset == one_by_one.offset
+                    && mutant.mutation_operator.description()
+                        == one_by_one.mutation_operator.description()
+            })?;
+
+            (meta_mutant.outcome() != one_by_one.outcome()).then(|| Mismatch {
+                id: mutant_ids.for_offset(one_by_one.offset, &*one_by_one.mutation_operator),
+                operator: one_by_one.mutation_operator.dyn_name().into(),
+                offset: one_by_one.offset,
+                meta_mutant: meta_mutant.outcome(),
+                one_by_one: one_by_one.outcome(),
+            })
+        })
+        .collect();
+    mismatches.sort_by_key(|mismatch| mismatch.offset);
+
+    Ok(SampleVerification {
+        checked: rerun.len(),
+        mismatches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutation::MutationEngine;
+    use wasmut_wasm::elements::{BlockType, Instruction};
+
+    /// Module trapping unless `1 + 2 == 3`
+    fn module() -> Result<WasmModule<'static>> {
+        crate::fixtures::start_function(vec![
+            Instruction::I32Const(1),
+            Instruction::I32Const(2),
+            Instruction::I32Add,
+            Instruction::I32Const(3),
+            Instruction::I32Ne,
+            Instruction::If(BlockType::NoResult),
+            Instruction::Unreachable,
+            Instruction::End,
+        ])
+    }
+
+    fn config() -> Result<Config> {
+        Config::parse(
+            r#"
+            [engine]
+            runtime = "wasmi"
+            coverage_based_execution = false
+            timeout_multiplier = 10
+
+            [operators]
+            enabled_operators = ["binop_add_to_sub", "relop_ne_to_eq"]
+            "#,
+        )
+    }
+
+    #[test]
+    fn sample_confirms_meta_mutant_outcomes() -> Result<()> {
+        let module = module()?;
+        let config = config()?;
+
+        let locations = MutationEngine::new(&config, 100)?
+            .discover_mutation_positions(&module)?
+            .locations;
+        let results = Executor::new(&config).execute_mutants(&module, &locations)?;
+        assert_eq!(results.len(), 2);
+
+        let verification = verify_sample(&config, &module, &locations, &results, 10)?;
+        assert_eq!(verification.checked, 2);
+        assert_eq!(verification.mismatches, Vec::new());
+
+        let verification = verify_sample(&config, &module, &locations, &results, 1)?;
+        assert_eq!(verification.checked, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn sample_detects_mismatch() -> Result<()> {
+        let module = module()?;
+        let config = config()?;
+
+        let locations = MutationEngine::new(&config, 100)?
+            .discover_mutation_positions(&module)?
+            .locations;
+        let mut results = Executor::new(&config).execute_mutants(&module, &locations)?;
+
+        // Pretend that the meta-mutant did not trap for one of the mutants
+        let index = results
+            .iter()
+            .position(|mutant| mutant.mutation_operator.dyn_name() == "binop_add_to_sub")
+            .unwrap();
+        results[index].result = ExecutionResult::ProcessExit {
+            exit_code: 0,
+            execution_cost: 1,
+        };
+        results[index].survived = true;
+
+        let verification = verify_sample(&config, &module, &locations, &results, 10)?;
+        assert_eq!(verification.mismatches.len(), 1);
+
+        let mismatch = &verification.mismatches[0];
+        assert_eq!(mismatch.operator, "binop_add_to_sub");
+        assert_eq!(mismatch.meta_mutant, MutationOutcome::Alive);
+        assert_eq!(mismatch.one_by_one, MutationOutcome::Error);
+        Ok(())
+    }
+}
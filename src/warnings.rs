@@ -0,0 +1,121 @@
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+
+/// Category of a non-fatal warning
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCategory {
+    /// The module has no name section
+    MissingNameSection,
+
+    /// Mutations were excluded because of missing source file information
+    MissingSourceInfo,
+
+    /// A source file could not be read
+    UnresolvableSourceFile,
+
+    /// A source file was modified after the module was compiled
+    StaleSourceFile,
+
+    /// Functions were skipped because of the filter configuration
+    SkippedFunctions,
+
+    /// Enabled operators did not produce any mutants
+    UnusedOperators,
+
+    /// A pattern in `enabled_operators` did not match any operator
+    UnmatchedOperatorPattern,
+
+    /// An ID passed to `--mutant-ids` did not match any mutant
+    UnknownMutantId,
+
+    /// Mutants could not be executed because of an error of wasmut or the runtime
+    InternalError,
+
+    /// Mutants were not run because the run was interrupted
+    /// or its time budget was exhausted
+    RunStopped,
+
+    /// Many mutants timed out, the timeout multiplier is likely too low
+    ManyTimeouts,
+
+    /// Mutants of large functions were limited by `max_mutants_per_function`
+    CappedFunctions,
+
+    /// Functions exceeded `expensive_function_threshold` in the baseline run
+    ExpensiveFunctions,
+
+    /// Imported functions are not supported by the runtime and trap when called
+    StubbedImports,
+}
+
+/// A non-fatal problem that occurred during a run
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub category: WarningCategory,
+    pub message: String,
+}
+
+/// Collected warnings of the current run
+static WARNINGS: Mutex<Vec<Warning>> = Mutex::new(Vec::new());
+
+fn warnings() -> MutexGuard<'static, Vec<Warning>> {
+    WARNINGS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Log a warning and add it to the list of collected warnings,
+/// so that it can be included in reports.
+/// Identical warnings are only recorded once.
+pub fn warn<S: Into<String>>(category: WarningCategory, message: S) {
+    let warning = Warning {
+        category,
+        message: message.into(),
+    };
+
+    let mut warnings = warnings();
+    if !warnings.contains(&warning) {
+        log::warn!("{}", warning.message);
+        warnings.push(warning);
+    }
+}
+
+/// Return all warnings collected so far
+pub fn collected() -> Vec<Warning> {
+    warnings().clone()
+}
+
+/// Discard all collected warnings, e.g. before mutating the next module of a workspace
+pub fn clear() {
+    warnings().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warnings_are_collected_once() {
+        warn(WarningCategory::SkippedFunctions, "test warning 1234");
+        warn(WarningCategory::SkippedFunctions, "test warning 1234");
+
+        let count = collected()
+            .iter()
+            .filter(|w| w.message == "test warning 1234")
+            .count();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn warning_serialization() {
+        let warning = Warning {
+            category: WarningCategory::MissingNameSection,
+            message: "foo".into(),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&warning).unwrap(),
+            r#"{"category":"missing_name_section","message":"foo"}"#
+        );
+    }
+}
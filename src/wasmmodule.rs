@@ -1,15 +1,25 @@
-use std::{borrow::Cow, collections::HashSet, path::Path};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
 
 use crate::{
-    addressresolver::AddressResolver,
+    addressresolver::{AddressIndex, AddressResolver, LineIndex, Locator},
+    error::ErrorKind,
     mutation::{Mutation, MutationLocation},
+    runtime::TracePoints,
+    warnings::{self, WarningCategory},
 };
 use wasmut_wasm::elements::{
-    External, FunctionType, GlobalEntry, GlobalSection, GlobalType, ImportEntry, InitExpr,
-    Instruction, Internal, Module, Section, TableElementType, Type, ValueType,
+    BlockType, ExportEntry, External, FuncBody, FunctionType, GlobalEntry, GlobalSection,
+    GlobalType, ImportCountType, ImportEntry, ImportSection, InitExpr, Instruction, Internal,
+    Local, Module, Section, TableElementType, Type, ValueType,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
 
 use rayon::prelude::*;
 
@@ -20,11 +30,83 @@ pub type CallbackType<'a, R> =
 /// Code location passed to `CallbackType`, it represents where
 /// we are when traversing the module.
 pub struct InstructionWalkerLocation<'a> {
+    /// Normalized source path
     pub file: Option<&'a str>,
+    /// Source path as recorded in the debug information
+    pub raw_file: Option<&'a str>,
     pub function: Option<&'a str>,
     pub function_index: u64,
+    pub function_length: u64,
     pub instruction_index: u64,
     pub instruction_offset: u64,
+    /// True if the instruction is a branch that carries values to its target,
+    /// e.g. a `br` out of a block with a result type
+    pub branch_carries_values: bool,
+    /// True if the result of the instruction is negated by an immediately
+    /// following `i32.eqz`, as in unordered float comparisons
+    pub negated_result: bool,
+    /// Whether the operand of the instruction is treated as signed or unsigned,
+    /// if the preceding or following instruction tells, see `operand_signedness`
+    pub signedness: Option<Signedness>,
+}
+
+/// Interpretation of an integer value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signedness {
+    Signed,
+    Unsigned,
+}
+
+/// Namespace of the functions imported by instrumented modules
+pub const WASMUT_API_NAMESPACE: &str = "wasmut_api";
+
+/// Name of the exported global that holds the id of the activated mutant
+pub const MUTANT_ID_GLOBAL: &str = "__wasmut_mutant_id";
+
+/// Name of the exported global that holds the number of the function
+/// that contains the activated mutant, or -1 if no mutant is activated
+pub const MUTANT_FUNCTION_GLOBAL: &str = "__wasmut_mutant_function";
+
+/// How a meta-mutant determines whether a mutation is activated
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MutantActivation {
+    /// Call the `__wasmut_check_mutant_id` host function at every mutation site
+    HostCall,
+
+    /// Compare against an exported global, which is set
+    /// by the runtime before execution. Avoids host calls.
+    ///
+    /// Additionally, every mutated function contains an unmodified copy of
+    /// its body, which is executed if the activated mutant is located in a
+    /// different function, so that no checks are executed in these functions.
+    Global,
+}
+
+/// Instructions used to check whether a mutation is activated
+#[derive(Clone, Copy)]
+enum MutantCheck {
+    /// Call the check function at the given function index
+    Call(u32),
+
+    /// Compare with the global at the given global index
+    Global(u32),
+}
+
+impl MutantCheck {
+    /// Instruction sequence that pushes 1 (i32) if mutant `id` is activated, 0 otherwise
+    fn instructions(&self, id: i64) -> Vec<Instruction> {
+        match self {
+            MutantCheck::Call(func_index) => {
+                vec![Instruction::I64Const(id), Instruction::Call(*func_index)]
+            }
+            MutantCheck::Global(global_index) => vec![
+                Instruction::GetGlobal(*global_index),
+                Instruction::I64Const(id),
+                Instruction::I64Eq,
+            ],
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -40,31 +122,235 @@ pub enum CallRemovalCandidate {
     },
 }
 
+/// Functions in the table that `call_indirect` instructions of a type
+/// may be redirected to by the `table_entry_replace` operator
+#[derive(Debug, PartialEq)]
+pub struct TableEntryCandidate {
+    /// Type index of the `call_indirect` instructions
+    pub type_index: u32,
+    pub params: Vec<ValueType>,
+    pub result: BlockType,
+
+    /// Table slots of the first two distinct functions of an identical type
+    pub slots: (u32, u32),
+}
+
+/// Details of a function defined in the module
+#[derive(Debug, PartialEq)]
+pub struct FunctionDetails {
+    /// Index of the function, including imported functions
+    pub index: u32,
+    /// Name from the name section or the debug information
+    pub name: Option<String>,
+    pub params: Vec<ValueType>,
+    pub results: Vec<ValueType>,
+    /// Size of the function's instructions in bytes
+    pub code_size: u64,
+    pub exported: bool,
+}
+
+/// A function defined in the module, including its body.
+/// Offsets and bodies are not used by wasmut itself, but by analyses built on top of it.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct DetailedFunction<'m> {
+    pub details: FunctionDetails,
+    /// Offset of the first instruction, relative to the start of the code section
+    pub offset: u64,
+    /// Offsets of all instructions, relative to the start of the code section
+    pub instruction_offsets: Vec<u64>,
+    /// Locals and instructions of the function
+    pub body: &'m FuncBody,
+}
+
+impl FunctionDetails {
+    /// Type signature, e.g. `(i32, i32) -> i32`
+    pub fn signature(&self) -> String {
+        let join = |types: &[ValueType]| {
+            types
+                .iter()
+                .map(ValueType::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        match self.results.as_slice() {
+            [] => format!("({})", join(&self.params)),
+            [result] => format!("({}) -> {result}", join(&self.params)),
+            results => format!("({}) -> ({})", join(&self.params), join(results)),
+        }
+    }
+}
+
+/// Size of a module
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ModuleSize {
+    /// Size of the serialized module in bytes
+    pub bytes: u64,
+    /// Number of globals defined in the module (not including imports)
+    pub globals: u64,
+    /// Number of instructions in all function bodies
+    pub instructions: u64,
+}
+
+/// Size of a meta-mutant, compared to the module it was generated from
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MetaMutantSize {
+    pub original: ModuleSize,
+    pub meta_mutant: ModuleSize,
+}
+
+impl MetaMutantSize {
+    /// Size of the code injected into the meta-mutant
+    pub fn added(&self) -> ModuleSize {
+        ModuleSize {
+            bytes: self.meta_mutant.bytes.saturating_sub(self.original.bytes),
+            globals: self
+                .meta_mutant
+                .globals
+                .saturating_sub(self.original.globals),
+            instructions: self
+                .meta_mutant
+                .instructions
+                .saturating_sub(self.original.instructions),
+        }
+    }
+
+    /// Ratio between the size of the meta-mutant and the size of the original module
+    pub fn growth_factor(&self) -> f64 {
+        if self.original.bytes == 0 {
+            1.0
+        } else {
+            self.meta_mutant.bytes as f64 / self.original.bytes as f64
+        }
+    }
+}
+
+impl std::fmt::Display for MetaMutantSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let added = self.added();
+        write!(
+            f,
+            "{} bytes ({:.2}x the original module), {} globals and {} instructions added",
+            self.meta_mutant.bytes,
+            self.growth_factor(),
+            added.globals,
+            added.instructions
+        )
+    }
+}
+
+/// Debug information available in a module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugInfo {
+    /// Module has a name section with function names
+    pub name_section: bool,
+    /// Module has DWARF line information (a `.debug_line` section)
+    pub dwarf: bool,
+}
+
+impl DebugInfo {
+    /// Whether the module lacks the line information needed to map
+    /// mutants to source files, e.g. after `wasm-opt` or `wasm-strip`
+    pub fn is_stripped(&self) -> bool {
+        !self.dwarf
+    }
+
+    /// Fail with guidance on how to build the module, if it has no line information
+    pub fn require(&self) -> Result<()> {
+        if !self.is_stripped() {
+            return Ok(());
+        }
+
+        let missing = if self.name_section {
+            "DWARF line information"
+        } else {
+            "DWARF line information and a name section"
+        };
+
+        Err(anyhow!(
+            "Module has no {missing}, mutants cannot be mapped to source code.\n\
+            Compile with debug information (e.g. `-g` for clang/emcc, `debug = true` \
+            in the cargo profile) and make sure that it is not removed afterwards: \
+            pass `-g` to wasm-opt and do not run wasm-strip."
+        ))
+        .context(ErrorKind::Module)
+    }
+}
+
 /// WasmModule represents a (parsed) WebAssembly module
 #[derive(Clone)]
 pub struct WasmModule<'a> {
     module: wasmut_wasm::elements::Module,
     path: Cow<'a, str>,
+    /// Bytecode the module was loaded from, shared by all clones (and mutants)
+    bytes: Arc<[u8]>,
+    /// Precomputed source locations, if the address index is used
+    address_index: Option<Arc<AddressIndex>>,
 }
 
 impl<'a> WasmModule<'a> {
     /// Construct a new `WasmModule` from a file path
     pub fn from_file(path: &str) -> Result<WasmModule> {
-        // let p: Cow<'_, str> = Cow::Owned(path.to_string());
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Could not read bytecode from {path}"))
+            .context(ErrorKind::Module)?;
+
+        WasmModule::from_bytes(bytes, path)
+    }
 
-        let module: Module = wasmut_wasm::elements::deserialize_file(path)
-            .context("Bytecode deserialization failed")?;
+    /// Construct a new `WasmModule` from bytecode in memory.
+    /// `origin_name` takes the place of the file path in logs and reports.
+    pub fn from_bytes(bytes: Vec<u8>, origin_name: &str) -> Result<WasmModule<'_>> {
+        let module: Module = wasmut_wasm::elements::deserialize_buffer(&bytes)
+            .context("Bytecode deserialization failed")
+            .context(ErrorKind::Module)?;
 
         if !module.has_names_section() {
-            log::warn!("Module has no name section, make sure to enable the debug flag!");
+            warnings::warn(
+                WarningCategory::MissingNameSection,
+                "Module has no name section, make sure to enable the debug flag!",
+            );
         }
 
         Ok(WasmModule {
             module,
-            path: path.into(),
+            path: origin_name.into(),
+            bytes: bytes.into(),
+            address_index: None,
         })
     }
 
+    /// Original bytecode of the module, as it was loaded
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Check whether the module was instrumented by wasmut,
+    /// e.g. a mutant that was saved to disk
+    pub fn is_instrumented(&self) -> bool {
+        self.module
+            .import_section()
+            .into_iter()
+            .flat_map(|imports| imports.entries())
+            .any(|entry| entry.module() == WASMUT_API_NAMESPACE)
+    }
+
+    /// Fail if the module was instrumented by wasmut, since
+    /// mutating it again would instrument it twice
+    pub fn require_uninstrumented(&self) -> Result<()> {
+        if !self.is_instrumented() {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "Module {} imports functions from the `{WASMUT_API_NAMESPACE}` namespace, \
+            it was already instrumented by wasmut. Pass the original module instead.",
+            self.path
+        ))
+        .context(ErrorKind::Module)
+    }
+
     /// Traverse module, and call callback function for every instruction
     pub fn instruction_walker<R: Send>(&self, callback: CallbackType<R>) -> Result<Vec<R>> {
         let code_section = self
@@ -72,21 +358,23 @@ impl<'a> WasmModule<'a> {
             .code_section()
             .context("Module has no code section")?;
 
-        let bytes = std::fs::read(self.path.as_ref())
-            .with_context(|| format!("Could not read bytecode from {}", self.path))?;
-
         Ok(code_section
             .bodies()
             .par_iter()
             .enumerate()
             .map_init(
-                || AddressResolver::new(&bytes),
+                || self.locator(),
                 |resolver, (func_index, func_body)| {
                     let instructions = func_body.code().elements();
                     let offsets = func_body.code().offsets();
 
                     let mut results = Vec::new();
 
+                    // For every enclosing label, whether branches to it carry values.
+                    // The outermost label is the function body itself.
+                    let function_result = self.function_block_type(func_index as u64);
+                    let mut labels = vec![function_result != Some(BlockType::NoResult)];
+
                     for ((instr_index, instruction), offset) in
                         instructions.iter().enumerate().zip(offsets)
                     {
@@ -95,6 +383,8 @@ impl<'a> WasmModule<'a> {
                         let code_offset = *offset - code_section.offset();
 
                         let location = resolver.lookup_address(code_offset);
+                        let branch_carries_values = branch_carries_values(instruction, &labels);
+                        update_labels(instruction, &mut labels);
 
                         results.extend(callback(
                             instruction,
@@ -102,10 +392,16 @@ impl<'a> WasmModule<'a> {
                                 // We need as_ref here because otherwise
                                 // location is moved into the and_then function
                                 file: location.as_ref().and_then(|l| l.file.as_deref()),
+                                raw_file: location.as_ref().and_then(|l| l.raw_file()),
                                 function: location.as_ref().and_then(|l| l.function.as_deref()),
                                 function_index: func_index as u64,
+                                function_length: instructions.len() as u64,
                                 instruction_index: instr_index as u64,
                                 instruction_offset: code_offset,
+                                branch_carries_values,
+                                negated_result: instructions.get(instr_index + 1)
+                                    == Some(&Instruction::I32Eqz),
+                                signedness: operand_signedness(instructions, instr_index),
                             },
                         ))
                     }
@@ -117,6 +413,77 @@ impl<'a> WasmModule<'a> {
             .collect())
     }
 
+    /// Resolve source locations using an address index, which is cached in `cache_directory`.
+    /// The index is built on first use and reused for modules with identical bytecode.
+    pub fn load_address_index(&mut self, cache_directory: &Path) -> Result<()> {
+        let index_path = AddressIndex::cache_path(cache_directory, &self.bytes);
+
+        let index = match AddressIndex::load(&index_path) {
+            Ok(index) => {
+                log::debug!("Using cached address index {index_path:?}");
+                index
+            }
+            Err(_) => {
+                let index = self.build_address_index()?;
+                if let Err(e) = index.save(&index_path) {
+                    log::warn!("Could not save address index to {index_path:?}: {e}");
+                }
+                index
+            }
+        };
+
+        self.address_index = Some(Arc::new(index));
+        Ok(())
+    }
+
+    /// Resolve the source locations of all instructions using DWARF
+    fn build_address_index(&self) -> Result<AddressIndex> {
+        let code_section = self
+            .module
+            .code_section()
+            .context("Module has no code section")?;
+
+        let entries = code_section
+            .bodies()
+            .par_iter()
+            .map_init(
+                || AddressResolver::new(&self.bytes),
+                |resolver, func_body| {
+                    func_body
+                        .code()
+                        .offsets()
+                        .iter()
+                        .map(|offset| {
+                            let code_offset = *offset - code_section.offset();
+                            (code_offset, resolver.lookup_address(code_offset))
+                        })
+                        .collect::<Vec<_>>()
+                },
+            )
+            .flatten_iter()
+            .collect();
+
+        Ok(AddressIndex::from_entries(entries))
+    }
+
+    /// Instructions of every source line. Uses the address index if it
+    /// was loaded, otherwise the locations of all instructions are resolved.
+    pub fn line_index(&self) -> Result<LineIndex> {
+        match &self.address_index {
+            Some(index) => Ok(index.line_index()),
+            None => Ok(self.build_address_index()?.line_index()),
+        }
+    }
+
+    /// Resolver for source locations of code section offsets.
+    /// Uses the DWARF information of the original bytecode if there is no address index.
+    pub fn locator(&self) -> Locator<'_> {
+        match &self.address_index {
+            Some(index) => Locator::Index(index),
+            None => Locator::Dwarf(AddressResolver::new(&self.bytes)),
+        }
+    }
+
     /// Apply a mutation
     fn mutate(&mut self, mutation_location: &MutationLocation, mutation_index: usize) {
         let instructions = self
@@ -139,8 +506,64 @@ impl<'a> WasmModule<'a> {
             .apply(instructions, mutation_location.statement_number);
     }
 
+    /// Offsets of the conditional branches (`if`/`br_if`) directly following a
+    /// mutation location, i.e. consuming the result of the mutated instruction,
+    /// by offset of the mutation location
+    pub fn conditional_branches(&self, locations: &[MutationLocation]) -> HashMap<u64, u64> {
+        let code_section = match self.module.code_section() {
+            Some(code_section) => code_section,
+            None => return HashMap::new(),
+        };
+
+        locations
+            .iter()
+            .filter_map(|location| {
+                let code = code_section
+                    .bodies()
+                    .get(location.function_number as usize)?
+                    .code();
+                let index = location.statement_number as usize + 1;
+
+                match code.elements().get(index)? {
+                    Instruction::If(_) | Instruction::BrIf(_) => {
+                        let offset = code.offsets().get(index)? - code_section.offset();
+                        Some((location.offset, offset))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Number of instructions executed in every function according to
+    /// `trace_points`, indexed by function number
+    pub fn executed_instructions(&self, trace_points: &TracePoints) -> Vec<u64> {
+        let code_section = match self.module.code_section() {
+            Some(code_section) => code_section,
+            None => return Vec::new(),
+        };
+
+        code_section
+            .bodies()
+            .iter()
+            .map(|body| {
+                body.code()
+                    .offsets()
+                    .iter()
+                    .map(|offset| trace_points.count(offset - code_section.offset()))
+                    .sum()
+            })
+            .collect()
+    }
+
     /// Apply all given mutations
-    fn mutate_all(&mut self, locations: &[MutationLocation]) -> Result<()> {
+    fn mutate_all(
+        &mut self,
+        locations: &[MutationLocation],
+        activation: MutantActivation,
+    ) -> Result<()> {
+        // The check function is imported in both cases, so that
+        // function indices are the same, regardless of `activation`
         let type_index = self.find_or_insert_check_mutant_function_signature()?;
         let function_index =
             self.add_trace_function_import("__wasmut_check_mutant_id", type_index)?;
@@ -151,12 +574,45 @@ impl<'a> WasmModule<'a> {
         self.fix_tables();
         self.fix_exports();
 
-        // binary operators have two params, so we need to save at least two parameters
-        let number_of_saved_params = self.max_number_of_params_of_same_type().max(2);
-        let global_section = self.get_or_create_global_section();
+        let (check, function_gate) = match activation {
+            MutantActivation::HostCall => (MutantCheck::Call(function_index), None),
+            MutantActivation::Global => (
+                MutantCheck::Global(self.add_exported_global(MUTANT_ID_GLOBAL, 0)?),
+                Some(self.add_exported_global(MUTANT_FUNCTION_GLOBAL, -1)?),
+            ),
+        };
+
+        // Unmodified bodies of all mutated functions, and their result types.
+        // Functions with multiple results are not gated.
+        let original_bodies: Vec<(u64, BlockType, Vec<Instruction>)> = match function_gate {
+            Some(_) => locations
+                .iter()
+                .map(|location| location.function_number)
+                .collect::<BTreeSet<u64>>()
+                .into_iter()
+                .filter_map(|function_number| {
+                    let block_type = self.function_block_type(function_number)?;
+                    let body = self
+                        .module
+                        .code_section()?
+                        .bodies()
+                        .get(function_number as usize)?;
+                    Some((function_number, block_type, body.code().elements().to_vec()))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        // Only allocate globals for the parameters that are actually saved
+        let saved_parameters = locations
+            .iter()
+            .filter_map(|location| location.mutations.first())
+            .map(|mutation| SavedParameters::of(mutation.operator.parameters()))
+            .fold(SavedParameters::default(), SavedParameters::max);
+        log::debug!("Saving parameters of mutations in {saved_parameters:?}");
 
-        let parameter_saver =
-            ParameterSaver::new(number_of_saved_params, global_section.entries_mut());
+        let global_section = self.get_or_create_global_section()?;
+        let parameter_saver = ParameterSaver::new(saved_parameters, global_section.entries_mut());
 
         let bodies = self
             .module
@@ -164,62 +620,184 @@ impl<'a> WasmModule<'a> {
             .context("Module does not have a code section")?
             .bodies_mut();
 
-        let mut locations = locations.to_vec();
-
-        locations.sort_by(|a, b| b.statement_number.cmp(&a.statement_number));
-
+        // Locations by function and statement, so that every mutated function
+        // is rebuilt in a single pass, even if it contains many mutations
+        let mut functions: BTreeMap<u64, BTreeMap<u64, &MutationLocation>> = BTreeMap::new();
         for location in locations {
+            functions
+                .entry(location.function_number)
+                .or_default()
+                .insert(location.statement_number, location);
+        }
+
+        for (function_number, locations) in functions {
             let instructions = bodies
-                .get_mut(location.function_number as usize)
+                .get_mut(function_number as usize)
                 .context("unexpected funtion index")?
                 .code_mut()
                 .elements_mut();
 
-            let params = location
-                .mutations
-                .get(0)
-                .expect("No mutations in location")
-                .operator
-                .parameters();
-
-            let tail = instructions.split_off(location.statement_number as usize);
-            // Save parameters
-            // let (save_vars, restore_vars) = generate_preamble(globals, &location.mutations);
-
-            let (mut save_sequence, restore_sequence) = parameter_saver.save_sequence(params);
-            let new_sequence =
-                generate_mutant_sequence(function_index, &location.mutations, &restore_sequence);
-
-            // TODO: This is needed, because when discovering mutations,
-            // the check_mutant_id function is not inserted yet.
-            // As a result, when we generate call instructions,
-            // the function index is wrong.
-            // Quick fix is to increment all function indices, except
-            // those of calls to the check_mutant_id function
-            let new_sequence: Vec<Instruction> = new_sequence
-                .iter()
-                .map(|i| match i {
-                    Instruction::Call(func) if *func != 0 => Instruction::Call(func + 1),
-                    e => e.clone(),
-                })
-                .collect();
+            let original = std::mem::take(instructions);
+            instructions.reserve(original.len());
+
+            for (statement_number, instruction) in original.into_iter().enumerate() {
+                let Some(location) = locations.get(&(statement_number as u64)) else {
+                    instructions.push(instruction);
+                    continue;
+                };
+
+                let params = location
+                    .mutations
+                    .first()
+                    .with_context(|| format!("No mutations at offset {:#x}", location.offset))
+                    .context(ErrorKind::Module)?
+                    .operator
+                    .parameters();
+
+                // Save parameters, and replace the instruction with the mutant sequence
+                let (save_sequence, restore_sequence) = parameter_saver.save_sequence(params);
+                instructions.extend(save_sequence);
+                instructions.extend(generate_mutant_sequence(
+                    check,
+                    &location.mutations,
+                    &restore_sequence,
+                ));
+            }
+        }
 
-            instructions.append(&mut save_sequence);
-            instructions.extend_from_slice(&new_sequence);
-            instructions.extend_from_slice(&tail[1..]);
+        if let Some(function_gate) = function_gate {
+            for (function_number, block_type, original) in original_bodies {
+                let instructions = bodies
+                    .get_mut(function_number as usize)
+                    .context("unexpected funtion index")?
+                    .code_mut()
+                    .elements_mut();
+
+                let mutated = std::mem::take(instructions);
+                *instructions = gate_function_body(
+                    function_gate,
+                    function_number,
+                    block_type,
+                    mutated,
+                    original,
+                )
+                .with_context(|| format!("Failed to gate function {function_number}"))?;
+            }
         }
 
         Ok(())
     }
 
+    /// Return the block type matching the results of a function,
+    /// or `None` if the function returns multiple values.
+    fn function_block_type(&self, function_number: u64) -> Option<BlockType> {
+        let type_ref = self
+            .module
+            .function_section()?
+            .entries()
+            .get(function_number as usize)?
+            .type_ref();
+
+        let Type::Function(func_type) =
+            self.module.type_section()?.types().get(type_ref as usize)?;
+
+        match func_type.results() {
+            [] => Some(BlockType::NoResult),
+            [result] => Some(BlockType::Value(*result)),
+            _ => None,
+        }
+    }
+
+    /// Number of parameters of a function
+    fn function_parameters(&self, function_number: u64) -> Option<u32> {
+        let type_ref = self
+            .module
+            .function_section()?
+            .entries()
+            .get(function_number as usize)?
+            .type_ref();
+
+        let Type::Function(func_type) =
+            self.module.type_section()?.types().get(type_ref as usize)?;
+
+        Some(func_type.params().len() as u32)
+    }
+
+    /// Add an exported, mutable i64 global with the initial value `init`
+    /// and return its index.
+    fn add_exported_global(&mut self, name: &str, init: i64) -> Result<u32> {
+        let imported_globals = self
+            .module
+            .import_section()
+            .map(|section| section.globals())
+            .unwrap_or(0);
+
+        let global_section = self.get_or_create_global_section()?;
+        let index = (imported_globals + global_section.entries().len()) as u32;
+
+        global_section.entries_mut().push(GlobalEntry::new(
+            GlobalType::new(ValueType::I64, true),
+            InitExpr::new(vec![Instruction::I64Const(init), Instruction::End]),
+        ));
+
+        self.module
+            .export_section_mut()
+            .context("Module does not have an export section")?
+            .entries_mut()
+            .push(ExportEntry::new(name.into(), Internal::Global(index)));
+
+        Ok(index)
+    }
+
     /// Get reference to global section, or create it if it does not exist.
-    fn get_or_create_global_section(&mut self) -> &mut wasmut_wasm::elements::GlobalSection {
+    fn get_or_create_global_section(&mut self) -> Result<&mut GlobalSection> {
         if self.module.global_section_mut().is_none() {
-            let sections = self.module.sections_mut();
-            sections.push(Section::Global(GlobalSection::default()));
+            // Keep the section order, exports referring to globals follow
+            self.module
+                .insert_section(Section::Global(GlobalSection::default()))
+                .map_err(|e| anyhow!("Failed to insert global section: {e}"))
+                .context(ErrorKind::Module)?;
         }
 
-        self.module.global_section_mut().unwrap()
+        self.module
+            .global_section_mut()
+            .context("Module does not have a global section")
+            .context(ErrorKind::Module)
+    }
+
+    /// Return the offsets of the first and the last instruction of a function,
+    /// relative to the start of the code section
+    pub fn function_code_range(&self, function_number: u32) -> Option<(u64, u64)> {
+        let code_section = self.module.code_section()?;
+        let offsets = code_section
+            .bodies()
+            .get(function_number as usize)?
+            .code()
+            .offsets();
+
+        let first = offsets.first()? - code_section.offset();
+        let last = offsets.last()? - code_section.offset();
+        Some((first, last))
+    }
+
+    /// Hash of the locals and instructions of every function, in the order of the
+    /// code section. Identifies identical functions in different builds of a module.
+    pub fn function_hashes(&self) -> Result<Vec<String>> {
+        let code_section = match self.module.code_section() {
+            Some(code_section) => code_section,
+            None => return Ok(Vec::new()),
+        };
+
+        code_section
+            .bodies()
+            .iter()
+            .map(|body| {
+                let mut bytes = Vec::new();
+                wasmut_wasm::elements::Serialize::serialize(body.clone(), &mut bytes)
+                    .map_err(|e| anyhow!("Failed to serialize function body: {e}"))?;
+                Ok(format!("{:?}", md5::compute(bytes)))
+            })
+            .collect()
     }
 
     /// Return a set of all function names in the module
@@ -250,6 +828,222 @@ impl<'a> WasmModule<'a> {
         results.into_iter().collect()
     }
 
+    /// Return a set of the names of all exported functions
+    pub fn exported_functions(&self) -> HashSet<String> {
+        self.module
+            .export_section()
+            .map(|section| {
+                section
+                    .entries()
+                    .iter()
+                    .filter(|entry| matches!(entry.internal(), Internal::Function(_)))
+                    .map(|entry| entry.field().into())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Return the name and the number within the code section of every exported
+    /// function that is defined in the module
+    pub fn exported_function_numbers(&self) -> Vec<(String, usize)> {
+        let number_of_imports = self.module.import_count(ImportCountType::Function) as u32;
+
+        self.module
+            .export_section()
+            .map(|section| {
+                section
+                    .entries()
+                    .iter()
+                    .filter_map(|entry| match entry.internal() {
+                        Internal::Function(index) if *index >= number_of_imports => {
+                            Some((entry.field().into(), (index - number_of_imports) as usize))
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Return which kinds of debug information the module contains
+    pub fn debug_info(&self) -> DebugInfo {
+        DebugInfo {
+            name_section: self.module.has_names_section(),
+            dwarf: self
+                .module
+                .custom_sections()
+                .any(|section| section.name() == ".debug_line"),
+        }
+    }
+
+    /// Return the offset of the first instruction and the name of every function
+    /// defined in the module, ordered by offset. Offsets are relative to the start
+    /// of the code section.
+    ///
+    /// Names are taken from the name section, functions without a name are
+    /// called `func[index]`, with the index including imported functions.
+    pub fn function_offsets(&self) -> Vec<(u64, String)> {
+        let code_section = match self.module.code_section() {
+            Some(section) => section,
+            None => return Vec::new(),
+        };
+
+        let number_of_imports = self.module.import_count(ImportCountType::Function) as u32;
+
+        let names = self.module.clone().parse_names().unwrap_or_else(|(_, m)| m);
+        let names = names
+            .names_section()
+            .and_then(|section| section.functions());
+
+        code_section
+            .bodies()
+            .iter()
+            .enumerate()
+            .filter_map(|(number, body)| {
+                let index = number as u32 + number_of_imports;
+                let offset = body.code().offsets().first()? - code_section.offset();
+
+                let name = names
+                    .and_then(|names| names.names().get(index))
+                    .filter(|name| !name.is_empty())
+                    .map(|name| addr2line::demangle_auto(Cow::from(name), None).into_owned())
+                    .unwrap_or_else(|| format!("func[{index}]"));
+
+                Some((offset, name))
+            })
+            .collect()
+    }
+
+    /// Number of instructions of every function in the code section
+    pub fn function_lengths(&self) -> Vec<u64> {
+        match self.module.code_section() {
+            Some(section) => section
+                .bodies()
+                .iter()
+                .map(|body| body.code().elements().len() as u64)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Complexity of every function in the code section, see `complexity`
+    pub fn function_complexities(&self) -> Vec<u64> {
+        match self.module.code_section() {
+            Some(section) => section
+                .bodies()
+                .iter()
+                .map(|body| complexity(body.code().elements()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Return details of all functions defined in the module, ordered by index.
+    ///
+    /// Names are taken from the name section. If the module does not have
+    /// a name section, the debug information of the first instruction is used.
+    pub fn function_details(&self) -> Result<Vec<FunctionDetails>> {
+        Ok(self
+            .functions_detailed()?
+            .into_iter()
+            .map(|function| function.details)
+            .collect())
+    }
+
+    /// Return all functions defined in the module with their details, offsets
+    /// and bodies, ordered by index. See `function_details` for the names.
+    pub fn functions_detailed(&self) -> Result<Vec<DetailedFunction<'_>>> {
+        let code_section = self
+            .module
+            .code_section()
+            .context("Module has no code section")?;
+        let function_section = self
+            .module
+            .function_section()
+            .context("Module has no function section")?;
+        let types = self
+            .module
+            .type_section()
+            .map(|section| section.types())
+            .unwrap_or_default();
+
+        let number_of_imports = self.module.import_count(ImportCountType::Function) as u32;
+
+        let names = self.module.clone().parse_names().unwrap_or_else(|(_, m)| m);
+        let names = names
+            .names_section()
+            .and_then(|section| section.functions());
+
+        let exported: HashSet<u32> = self
+            .module
+            .export_section()
+            .map(|section| {
+                section
+                    .entries()
+                    .iter()
+                    .filter_map(|entry| match entry.internal() {
+                        Internal::Function(index) => Some(*index),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let locator = self.locator();
+
+        let functions = function_section
+            .entries()
+            .iter()
+            .zip(code_section.bodies())
+            .enumerate()
+            .map(|(number, (func, body))| {
+                let index = number as u32 + number_of_imports;
+                let offsets: Vec<u64> = body
+                    .code()
+                    .offsets()
+                    .iter()
+                    .map(|offset| offset - code_section.offset())
+                    .collect();
+                let offset = offsets.first().copied().unwrap_or_default();
+
+                let name = names
+                    .and_then(|names| names.names().get(index))
+                    .map(|name| addr2line::demangle_auto(Cow::from(name), None).into_owned())
+                    .or_else(|| locator.lookup_address(*offsets.first()?)?.function)
+                    .filter(|name| !name.is_empty());
+
+                let (params, results) = match types.get(func.type_ref() as usize) {
+                    Some(Type::Function(func_type)) => {
+                        (func_type.params().into(), func_type.results().into())
+                    }
+                    None => (Vec::new(), Vec::new()),
+                };
+
+                // The last instruction is always `end`, which is one byte long
+                let code_size = match (offsets.first(), offsets.last()) {
+                    (Some(first), Some(last)) => last - first + 1,
+                    _ => 0,
+                };
+
+                DetailedFunction {
+                    details: FunctionDetails {
+                        index,
+                        name,
+                        params,
+                        results,
+                        code_size,
+                        exported: exported.contains(&index),
+                    },
+                    offset,
+                    instruction_offsets: offsets,
+                    body,
+                }
+            })
+            .collect();
+
+        Ok(functions)
+    }
+
     /// Examine import section and function section of the module
     /// to check which call instruction may be removed using
     /// the `call_remove_*` operators.
@@ -310,39 +1104,248 @@ impl<'a> WasmModule<'a> {
         Ok(candidates)
     }
 
-    /// Insert calls to our coverage tracing function.
-    pub fn insert_trace_points(&mut self) -> Result<()> {
-        // Make sure that the type signature of the trace function
-        // is contained in the function table
-        let type_index = self.find_or_insert_trace_function_signature()?;
+    /// Examine the element segments of the module to find two table slots
+    /// pointing to distinct functions of an identical type, for every type.
+    /// Types are compared structurally, as done by `call_indirect`.
+    /// Only segments of table 0 with a constant offset are considered.
+    pub fn table_entry_candidates(&self) -> Result<Vec<TableEntryCandidate>> {
+        let types = self
+            .module
+            .type_section()
+            .context("Module has no type section")?
+            .types();
 
-        // Add trace function to the import section
-        let function_index = self.add_trace_function_import("__wasmut_trace", type_index)?;
+        let elements_section = match self.module.elements_section() {
+            Some(elements_section) => elements_section,
+            None => return Ok(Vec::new()),
+        };
 
-        // Increment all function-indices, since the
-        // function section now contains the trace_function at index 0
-        self.fix_call_instructions();
-        self.fix_tables();
-        self.fix_exports();
+        // Type of every function, including imported functions
+        let mut function_types = Vec::new();
+        if let Some(import_section) = self.module.import_section() {
+            for import in import_section.entries() {
+                if let External::Function(type_ref) = import.external() {
+                    function_types.push(*type_ref);
+                }
+            }
+        }
+        if let Some(function_section) = self.module.function_section() {
+            function_types.extend(function_section.entries().iter().map(|f| f.type_ref()));
+        }
 
-        // Finally, insert a call to the trace function before every single
-        // instruction
-        self.insert_trace_calls(function_index);
+        // Function of every initialized slot, later segments overwrite earlier ones
+        let mut table = BTreeMap::new();
+        for segment in elements_section.entries() {
+            if segment.index() != 0 {
+                continue;
+            }
 
-        Ok(())
-    }
+            let offset = match segment.offset().as_ref().map(|offset| offset.code()) {
+                Some([Instruction::I32Const(offset), Instruction::End]) => *offset as u32,
+                _ => continue,
+            };
 
-    fn find_or_insert_trace_function_signature(&mut self) -> Result<u32> {
-        self.find_or_insert_type_signature(&[ValueType::I64], &[])
-    }
+            for (slot, function) in segment.members().iter().enumerate() {
+                table.insert(offset + slot as u32, *function);
+            }
+        }
 
-    fn find_or_insert_check_mutant_function_signature(&mut self) -> Result<u32> {
-        self.find_or_insert_type_signature(&[ValueType::I64], &[ValueType::I32])
-    }
+        let mut candidates = Vec::new();
+        for (type_index, ty) in types.iter().enumerate() {
+            let Type::Function(func_type) = ty;
+            let result = match func_type.results() {
+                [] => BlockType::NoResult,
+                [result] => BlockType::Value(*result),
+                _ => continue,
+            };
 
-    fn find_or_insert_type_signature(
-        &mut self,
-        params: &[ValueType],
+            let mut matching = table.iter().filter(|(_, function)| {
+                function_types
+                    .get(**function as usize)
+                    .and_then(|type_ref| types.get(*type_ref as usize))
+                    == Some(ty)
+            });
+
+            let first = match matching.next() {
+                Some(first) => first,
+                None => continue,
+            };
+
+            if let Some(second) = matching.find(|(_, function)| *function != first.1) {
+                candidates.push(TableEntryCandidate {
+                    type_index: type_index as u32,
+                    params: func_type.params().into(),
+                    result,
+                    slots: (*first.0, *second.0),
+                });
+            }
+        }
+
+        Ok(candidates)
+    }
+
+    /// Insert calls to our coverage tracing function.
+    ///
+    /// Conditional branches additionally pass their condition to a branch trace
+    /// function, and comparisons pass their result and operands to a comparison
+    /// trace function for their operand type, so that branch coverage and
+    /// comparison profiles can be recorded.
+    pub fn insert_trace_points(&mut self) -> Result<()> {
+        let mut functions = vec![
+            (
+                "__wasmut_trace",
+                self.find_or_insert_trace_function_signature()?,
+            ),
+            (
+                "__wasmut_trace_branch",
+                self.find_or_insert_branch_trace_function_signature()?,
+            ),
+        ];
+        for (value_type, name) in COMPARISON_TRACE_FUNCTIONS {
+            let type_index = self.find_or_insert_type_signature(
+                &[ValueType::I32, value_type, value_type, ValueType::I64],
+                &[ValueType::I32],
+            )?;
+            functions.push((name, type_index));
+        }
+
+        for (name, type_index) in &functions {
+            // Add trace function to the import section
+            self.add_trace_function_import(name, *type_index)?;
+
+            // Increment all function-indices, since the
+            // function section now contains the trace_function at index 0
+            self.fix_call_instructions();
+            self.fix_tables();
+            self.fix_exports();
+        }
+
+        // Every import was inserted at index 0, so the last one has index 0
+        let index_of = |position: usize| (functions.len() - 1 - position) as u32;
+        let comparison_functions: Vec<(ValueType, u32)> = COMPARISON_TRACE_FUNCTIONS
+            .iter()
+            .enumerate()
+            .map(|(position, (value_type, _))| (*value_type, index_of(position + 2)))
+            .collect();
+
+        // Finally, insert a call to the trace function before every single
+        // instruction, and calls to the branch and comparison trace functions
+        self.insert_trace_calls(index_of(0), index_of(1), &comparison_functions);
+
+        Ok(())
+    }
+
+    /// Insert a call to the trace function at the start of every function,
+    /// so that the sequence of called functions can be recorded.
+    pub fn insert_call_trace_points(&mut self) -> Result<()> {
+        let type_index = self.find_or_insert_trace_function_signature()?;
+        let function_index = self.add_trace_function_import("__wasmut_trace", type_index)?;
+
+        self.fix_call_instructions();
+        self.fix_tables();
+        self.fix_exports();
+
+        if let Some(code_section) = self.module.code_section_mut() {
+            let code_section_offset = code_section.offset();
+
+            for func_body in code_section.bodies_mut() {
+                let code = func_body.code_mut();
+
+                // The offset of the first instruction identifies the function
+                if let Some(&offset) = code.offsets().first() {
+                    let offset = offset - code_section_offset;
+
+                    code.elements_mut().splice(
+                        0..0,
+                        [
+                            Instruction::I64Const(offset as i64),
+                            Instruction::Call(function_index),
+                        ],
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create a clone that calls the trace function at the start of every basic block,
+    /// optionally applying a mutation first.
+    ///
+    /// Basic blocks are identified by the offset of their first instruction in
+    /// this module, so that the traces of a mutant and of the original module
+    /// can be compared, even if the mutation changed the number of instructions.
+    pub fn clone_with_block_trace_points(
+        &self,
+        mutation: Option<(&MutationLocation, usize)>,
+    ) -> Result<Self> {
+        let code_section = self
+            .module
+            .code_section()
+            .context("Module has no code section")?;
+
+        // Offsets of the first instruction of every basic block, for every function
+        let block_offsets: Vec<Vec<u64>> = code_section
+            .bodies()
+            .iter()
+            .map(|body| {
+                let offsets = body.code().offsets();
+                block_leaders(body.code().elements())
+                    .into_iter()
+                    .filter_map(|index| offsets.get(index))
+                    .map(|offset| offset - code_section.offset())
+                    .collect()
+            })
+            .collect();
+
+        let mut traced = self.clone();
+        if let Some((location, mutation_index)) = mutation {
+            traced.mutate(location, mutation_index);
+        }
+
+        let type_index = traced.find_or_insert_trace_function_signature()?;
+        let function_index = traced.add_trace_function_import("__wasmut_trace", type_index)?;
+
+        traced.fix_call_instructions();
+        traced.fix_tables();
+        traced.fix_exports();
+
+        if let Some(code_section) = traced.module.code_section_mut() {
+            for (func_body, offsets) in code_section.bodies_mut().iter_mut().zip(block_offsets) {
+                let code = func_body.code_mut().elements_mut();
+                let leaders = block_leaders(code);
+
+                // Insert from the back, so that the indices of the leaders stay valid
+                for (index, offset) in leaders.into_iter().zip(offsets).rev() {
+                    code.splice(
+                        index..index,
+                        [
+                            Instruction::I64Const(offset as i64),
+                            Instruction::Call(function_index),
+                        ],
+                    );
+                }
+            }
+        }
+
+        Ok(traced)
+    }
+
+    fn find_or_insert_trace_function_signature(&mut self) -> Result<u32> {
+        self.find_or_insert_type_signature(&[ValueType::I64], &[])
+    }
+
+    fn find_or_insert_branch_trace_function_signature(&mut self) -> Result<u32> {
+        self.find_or_insert_type_signature(&[ValueType::I32, ValueType::I64], &[ValueType::I32])
+    }
+
+    fn find_or_insert_check_mutant_function_signature(&mut self) -> Result<u32> {
+        self.find_or_insert_type_signature(&[ValueType::I64], &[ValueType::I32])
+    }
+
+    fn find_or_insert_type_signature(
+        &mut self,
+        params: &[ValueType],
         results: &[ValueType],
     ) -> Result<u32> {
         let type_section = self
@@ -375,7 +1378,13 @@ impl<'a> WasmModule<'a> {
     }
 
     fn add_trace_function_import(&mut self, func_name: &str, type_index: u32) -> Result<u32> {
-        // TODO: What should happen if there aren't imports there yet?
+        if self.module.import_section_mut().is_none() {
+            // Modules without any imports, the section has to precede the others
+            self.module
+                .insert_section(Section::Import(ImportSection::default()))
+                .context("Failed to create import section")?;
+        }
+
         let import_section = self
             .module
             .import_section_mut()
@@ -385,7 +1394,7 @@ impl<'a> WasmModule<'a> {
         import_section.insert(
             0,
             ImportEntry::new(
-                "wasmut_api".into(),
+                WASMUT_API_NAMESPACE.into(),
                 func_name.into(),
                 External::Function(type_index),
             ),
@@ -444,11 +1453,37 @@ impl<'a> WasmModule<'a> {
         }
     }
 
-    fn insert_trace_calls(&mut self, function_index: u32) {
+    fn insert_trace_calls(
+        &mut self,
+        function_index: u32,
+        branch_function_index: u32,
+        comparison_functions: &[(ValueType, u32)],
+    ) {
+        // Comparisons save their operands in additional locals,
+        // which are appended after the parameters and existing locals
+        let parameters: Vec<u32> = self
+            .module
+            .code_section()
+            .map(|code| code.bodies().len())
+            .map(|bodies| {
+                (0..bodies)
+                    .map(|function_number| {
+                        self.function_parameters(function_number as u64)
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
         if let Some(code_section) = self.module.code_section_mut() {
             let code_section_offset = code_section.offset();
 
-            for func_body in code_section.bodies_mut() {
+            for (func_body, parameters) in code_section.bodies_mut().iter_mut().zip(parameters) {
+                let mut next_local =
+                    parameters + func_body.locals().iter().map(|l| l.count()).sum::<u32>();
+                // First of the two locals holding the operands, for every operand type
+                let mut operand_locals: Vec<(ValueType, u32)> = Vec::new();
+
                 let code = func_body.code_mut();
 
                 let mut instructions = Vec::new();
@@ -458,46 +1493,81 @@ impl<'a> WasmModule<'a> {
 
                     instructions.push(Instruction::I64Const(offset as i64));
                     instructions.push(Instruction::Call(function_index));
-                    instructions.push(instr.clone());
+
+                    if matches!(instr, Instruction::If(_) | Instruction::BrIf(_)) {
+                        instructions.push(Instruction::I64Const(offset as i64));
+                        instructions.push(Instruction::Call(branch_function_index));
+                    }
+
+                    let comparison = comparison_operand_type(instr).and_then(|value_type| {
+                        comparison_functions
+                            .iter()
+                            .find(|(t, _)| *t == value_type)
+                            .map(|(_, index)| (value_type, *index))
+                    });
+
+                    match comparison {
+                        Some((value_type, comparison_function_index)) => {
+                            let lhs = match operand_locals.iter().find(|(t, _)| *t == value_type) {
+                                Some((_, lhs)) => *lhs,
+                                None => {
+                                    operand_locals.push((value_type, next_local));
+                                    next_local += 2;
+                                    next_local - 2
+                                }
+                            };
+                            let rhs = lhs + 1;
+
+                            // Save the operands, compare them and pass the result
+                            // and the operands to the comparison trace function
+                            instructions.extend([
+                                Instruction::SetLocal(rhs),
+                                Instruction::TeeLocal(lhs),
+                                Instruction::GetLocal(rhs),
+                                instr.clone(),
+                                Instruction::GetLocal(lhs),
+                                Instruction::GetLocal(rhs),
+                                Instruction::I64Const(offset as i64),
+                                Instruction::Call(comparison_function_index),
+                            ]);
+                        }
+                        None => instructions.push(instr.clone()),
+                    }
                 }
 
                 *code.elements_mut() = instructions;
+
+                func_body.locals_mut().extend(
+                    operand_locals
+                        .into_iter()
+                        .map(|(value_type, _)| Local::new(2, value_type)),
+                );
             }
         }
     }
 
-    /// Goes through the type signatures and get the maximum number of params of the same type
-    fn max_number_of_params_of_same_type(&self) -> usize {
-        let type_section = self
+    /// Size of the module, when serialized with `to_bytes`
+    pub fn size(&self) -> Result<ModuleSize> {
+        let instructions = self
             .module
-            .type_section()
-            .expect("module does not have a type section");
-
-        let mut max = 0;
-
-        for Type::Function(t) in type_section.types() {
-            let mut i32_params = 0;
-            let mut i64_params = 0;
-            let mut f32_params = 0;
-            let mut f64_params = 0;
-
-            for param in t.params() {
-                match param {
-                    ValueType::I32 => i32_params += 1,
-                    ValueType::I64 => i64_params += 1,
-                    ValueType::F32 => f32_params += 1,
-                    ValueType::F64 => f64_params += 1,
-                }
-            }
-
-            max = max
-                .max(i32_params)
-                .max(i64_params)
-                .max(f32_params)
-                .max(f64_params);
-        }
+            .code_section()
+            .map(|code| {
+                code.bodies()
+                    .iter()
+                    .map(|body| body.code().elements().len() as u64)
+                    .sum()
+            })
+            .unwrap_or_default();
 
-        max
+        Ok(ModuleSize {
+            bytes: self.to_bytes()?.len() as u64,
+            globals: self
+                .module
+                .global_section()
+                .map(|globals| globals.entries().len() as u64)
+                .unwrap_or_default(),
+            instructions,
+        })
     }
 
     /// Serialize module
@@ -516,9 +1586,13 @@ impl<'a> WasmModule<'a> {
     }
 
     /// Create a clone and apply a mutation
-    pub fn clone_and_mutate_all(&self, locations: &[MutationLocation]) -> Result<Self> {
+    pub fn clone_and_mutate_all(
+        &self,
+        locations: &[MutationLocation],
+        activation: MutantActivation,
+    ) -> Result<Self> {
         let mut mutant = self.clone();
-        mutant.mutate_all(locations)?;
+        mutant.mutate_all(locations, activation)?;
         Ok(mutant)
     }
 
@@ -537,9 +1611,32 @@ impl<'a> WasmModule<'a> {
 }
 
 fn generate_mutant_sequence(
-    func_index: u32,
+    check: MutantCheck,
     mutations: &[Mutation],
     restore_sequence: &[Instruction],
+) -> Vec<Instruction> {
+    let mut instructions = generate_nested_mutant_sequence(check, mutations, restore_sequence, 1);
+
+    // Code following an unconditional branch is unreachable, which allows it to
+    // have any stack contents. The blocks of the mutations end with an empty
+    // stack though, so the code following them has to be marked as unreachable.
+    if matches!(
+        mutations[0].operator.old_instruction(),
+        Instruction::Br(_) | Instruction::BrTable(_)
+    ) {
+        instructions.push(Instruction::Unreachable);
+    }
+
+    instructions
+}
+
+/// Generate the checks of `mutations`, which are nested in `depth` blocks.
+/// Labels of branches are adjusted accordingly.
+fn generate_nested_mutant_sequence(
+    check: MutantCheck,
+    mutations: &[Mutation],
+    restore_sequence: &[Instruction],
+    depth: u32,
 ) -> Vec<Instruction> {
     let mut instructions = Vec::new();
 
@@ -547,23 +1644,29 @@ fn generate_mutant_sequence(
         .get(0)
         .expect("mutation slice is empty, this is bug.");
 
-    instructions.push(Instruction::I64Const(mutation.id));
-    instructions.push(Instruction::Call(func_index));
+    instructions.append(&mut check.instructions(mutation.id));
     instructions.push(Instruction::If(mutation.operator.result()));
     instructions.extend_from_slice(restore_sequence);
 
-    instructions.append(&mut mutation.operator.replacement());
+    let mut replacement = mutation.operator.replacement();
+    shift_branch_labels(&mut replacement, depth);
+    shift_call_indices(&mut replacement);
+    instructions.append(&mut replacement);
     instructions.push(Instruction::Else);
 
     let next = &mutations[1..];
     if next.is_empty() {
         instructions.extend_from_slice(restore_sequence);
-        instructions.push(mutations[0].operator.old_instruction().clone());
+        let mut original = [mutations[0].operator.old_instruction().clone()];
+        shift_branch_labels(&mut original, depth);
+        shift_call_indices(&mut original);
+        instructions.extend(original);
     } else {
-        instructions.append(&mut generate_mutant_sequence(
-            func_index,
+        instructions.append(&mut generate_nested_mutant_sequence(
+            check,
             next,
             restore_sequence,
+            depth + 1,
         ));
     }
 
@@ -572,84 +1675,129 @@ fn generate_mutant_sequence(
     instructions
 }
 
-struct ParameterSaver {
-    offset: usize,
-    number_of_saved_params: usize,
+/// Combine the mutated and the original body of a function, so that the
+/// mutated body is only executed if the global `function_gate` contains
+/// `function_number`.
+///
+/// Both bodies must end with the `End` instruction of the function.
+/// Wrapping the bodies in an `if` block does not change the semantics of
+/// branches to the outermost label, since the block ends with the function.
+fn gate_function_body(
+    function_gate: u32,
+    function_number: u64,
+    block_type: BlockType,
+    mut mutated: Vec<Instruction>,
+    mut original: Vec<Instruction>,
+) -> Result<Vec<Instruction>> {
+    if mutated.pop() != Some(Instruction::End) || original.pop() != Some(Instruction::End) {
+        return Err(anyhow!("Function body does not end with `end`")).context(ErrorKind::Module);
+    }
+
+    let mut instructions = vec![
+        Instruction::GetGlobal(function_gate),
+        Instruction::I64Const(function_number as i64),
+        Instruction::I64Eq,
+        Instruction::If(block_type),
+    ];
+    instructions.append(&mut mutated);
+    instructions.push(Instruction::Else);
+    instructions.append(&mut original);
+    instructions.push(Instruction::End);
+    instructions.push(Instruction::End);
+
+    Ok(instructions)
 }
 
-impl ParameterSaver {
-    fn new(number_of_saved_params: usize, globals: &mut Vec<GlobalEntry>) -> Self {
-        let offset = globals.len();
+/// Number of saved parameters, for every value type
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct SavedParameters {
+    i32: usize,
+    i64: usize,
+    f32: usize,
+    f64: usize,
+}
 
-        for _ in 0..number_of_saved_params {
-            globals.push(GlobalEntry::new(
-                GlobalType::new(ValueType::I32, true),
-                InitExpr::new(vec![Instruction::I32Const(0), Instruction::End]),
-            ));
+impl SavedParameters {
+    /// Globals needed to save `params`
+    fn of(params: &[ValueType]) -> Self {
+        let mut saved = Self::default();
+        for parameter in params {
+            *saved.get_mut(*parameter) += 1;
         }
+        saved
+    }
 
-        for _ in 0..number_of_saved_params {
-            globals.push(GlobalEntry::new(
-                GlobalType::new(ValueType::I64, true),
-                InitExpr::new(vec![Instruction::I64Const(0), Instruction::End]),
-            ));
+    /// Globals needed to save the parameters of both `self` and `other`
+    fn max(self, other: Self) -> Self {
+        Self {
+            i32: self.i32.max(other.i32),
+            i64: self.i64.max(other.i64),
+            f32: self.f32.max(other.f32),
+            f64: self.f64.max(other.f64),
         }
+    }
 
-        for _ in 0..number_of_saved_params {
-            globals.push(GlobalEntry::new(
-                GlobalType::new(ValueType::F32, true),
-                InitExpr::new(vec![Instruction::F32Const(0), Instruction::End]),
-            ));
+    fn get(&self, value_type: ValueType) -> usize {
+        match value_type {
+            ValueType::I32 => self.i32,
+            ValueType::I64 => self.i64,
+            ValueType::F32 => self.f32,
+            ValueType::F64 => self.f64,
         }
+    }
 
-        for _ in 0..number_of_saved_params {
-            globals.push(GlobalEntry::new(
-                GlobalType::new(ValueType::F64, true),
-                InitExpr::new(vec![Instruction::F64Const(0), Instruction::End]),
-            ));
+    fn get_mut(&mut self, value_type: ValueType) -> &mut usize {
+        match value_type {
+            ValueType::I32 => &mut self.i32,
+            ValueType::I64 => &mut self.i64,
+            ValueType::F32 => &mut self.f32,
+            ValueType::F64 => &mut self.f64,
         }
+    }
+}
 
-        Self {
-            offset,
-            number_of_saved_params,
+struct ParameterSaver {
+    /// Index of the first global of every value type
+    offsets: SavedParameters,
+}
+
+impl ParameterSaver {
+    fn new(saved: SavedParameters, globals: &mut Vec<GlobalEntry>) -> Self {
+        let mut offsets = SavedParameters::default();
+
+        let value_types = [
+            (ValueType::I32, Instruction::I32Const(0)),
+            (ValueType::I64, Instruction::I64Const(0)),
+            (ValueType::F32, Instruction::F32Const(0)),
+            (ValueType::F64, Instruction::F64Const(0)),
+        ];
+
+        for (value_type, init) in value_types {
+            *offsets.get_mut(value_type) = globals.len();
+
+            for _ in 0..saved.get(value_type) {
+                globals.push(GlobalEntry::new(
+                    GlobalType::new(value_type, true),
+                    InitExpr::new(vec![init.clone(), Instruction::End]),
+                ));
+            }
         }
+
+        Self { offsets }
     }
 
     fn save_sequence(&self, params: &[ValueType]) -> (Vec<Instruction>, Vec<Instruction>) {
-        let mut i32_params = 0;
-        let mut i64_params = 0;
-        let mut f32_params = 0;
-        let mut f64_params = 0;
+        let mut next = self.offsets;
 
         let mut save_sequence = Vec::new();
         let mut restore_sequence = Vec::new();
 
         for parameter in params.iter() {
-            let index = match parameter {
-                ValueType::I32 => {
-                    let index = self.offset + i32_params;
-                    i32_params += 1;
-                    index
-                }
-                ValueType::I64 => {
-                    let index = self.offset + self.number_of_saved_params + i64_params;
-                    i64_params += 1;
-                    index
-                }
-                ValueType::F32 => {
-                    let index = self.offset + 2 * self.number_of_saved_params + f32_params;
-                    f32_params += 1;
-                    index
-                }
-                ValueType::F64 => {
-                    let index = self.offset + 3 * self.number_of_saved_params + f64_params;
-                    f64_params += 1;
-                    index
-                }
-            };
+            let index = next.get_mut(*parameter);
 
-            save_sequence.push(Instruction::SetGlobal(index as u32));
-            restore_sequence.push(Instruction::GetGlobal(index as u32));
+            save_sequence.push(Instruction::SetGlobal(*index as u32));
+            restore_sequence.push(Instruction::GetGlobal(*index as u32));
+            *index += 1;
         }
 
         save_sequence.reverse();
@@ -658,10 +1806,174 @@ impl ParameterSaver {
     }
 }
 
+/// Functions recording the result and operands of comparisons, by operand type
+const COMPARISON_TRACE_FUNCTIONS: [(ValueType, &str); 4] = [
+    (ValueType::I32, "__wasmut_trace_comparison_i32"),
+    (ValueType::I64, "__wasmut_trace_comparison_i64"),
+    (ValueType::F32, "__wasmut_trace_comparison_f32"),
+    (ValueType::F64, "__wasmut_trace_comparison_f64"),
+];
+
+/// Operand type of a comparison instruction, `None` for other instructions
+fn comparison_operand_type(instruction: &Instruction) -> Option<ValueType> {
+    use Instruction::*;
+
+    match instruction {
+        I32Eq | I32Ne | I32LtS | I32LtU | I32GtS | I32GtU | I32LeS | I32LeU | I32GeS | I32GeU => {
+            Some(ValueType::I32)
+        }
+        I64Eq | I64Ne | I64LtS | I64LtU | I64GtS | I64GtU | I64LeS | I64LeU | I64GeS | I64GeU => {
+            Some(ValueType::I64)
+        }
+        F32Eq | F32Ne | F32Lt | F32Gt | F32Le | F32Ge => Some(ValueType::F32),
+        F64Eq | F64Ne | F64Lt | F64Gt | F64Le | F64Ge => Some(ValueType::F64),
+        _ => None,
+    }
+}
+
+/// Indices of the instructions that start a basic block, i.e. the first instruction
+/// of the function and every instruction following a control instruction
+fn block_leaders(instructions: &[Instruction]) -> Vec<usize> {
+    let mut leaders = vec![0];
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        let ends_block = matches!(
+            instruction,
+            Instruction::Block(_)
+                | Instruction::Loop(_)
+                | Instruction::If(_)
+                | Instruction::Else
+                | Instruction::End
+                | Instruction::Br(_)
+                | Instruction::BrIf(_)
+                | Instruction::BrTable(_)
+                | Instruction::Return
+                | Instruction::Unreachable
+        );
+
+        if ends_block && index + 1 < instructions.len() {
+            leaders.push(index + 1);
+        }
+    }
+
+    leaders
+}
+
+/// Track the labels of the blocks enclosing an instruction.
+/// `labels` contains whether branches to a label carry values.
+fn update_labels(instruction: &Instruction, labels: &mut Vec<bool>) {
+    match instruction {
+        Instruction::Block(block_type) | Instruction::If(block_type) => {
+            labels.push(*block_type != BlockType::NoResult)
+        }
+        // Branches to a loop jump to its start, without any values
+        Instruction::Loop(_) => labels.push(false),
+        Instruction::End => {
+            labels.pop();
+        }
+        _ => (),
+    }
+}
+
+/// Check whether a branch carries values to its target label.
+/// Labels that do not exist (in invalid modules) are assumed to carry values.
+fn branch_carries_values(instruction: &Instruction, labels: &[bool]) -> bool {
+    let depth = match instruction {
+        Instruction::Br(depth) | Instruction::BrIf(depth) => *depth,
+        // All targets of a table carry the same values
+        Instruction::BrTable(data) => data.default,
+        _ => return false,
+    };
+
+    labels
+        .len()
+        .checked_sub(depth as usize + 1)
+        .map(|index| labels[index])
+        .unwrap_or(true)
+}
+
+/// Whether the operand of the instruction at `index` is treated as signed or unsigned,
+/// judging by the instruction producing it and the instruction consuming the result.
+/// Returns `None` if neither tells or if they contradict each other.
+fn operand_signedness(instructions: &[Instruction], index: usize) -> Option<Signedness> {
+    use Instruction::*;
+
+    let produced = index
+        .checked_sub(1)
+        .and_then(|previous| instructions.get(previous))
+        .and_then(|instruction| match instruction {
+            I64ExtendSI32 | I64Load8S(..) | I64Load16S(..) | I64Load32S(..) | I64DivS | I64RemS
+            | I64ShrS | I64TruncSF32 | I64TruncSF64 => Some(Signedness::Signed),
+            I64ExtendUI32 | I64Load8U(..) | I64Load16U(..) | I64Load32U(..) | I64DivU | I64RemU
+            | I64ShrU | I64TruncUF32 | I64TruncUF64 => Some(Signedness::Unsigned),
+            _ => None,
+        });
+    let consumed = instructions
+        .get(index + 1)
+        .and_then(|instruction| match instruction {
+            I32LtS | I32GtS | I32LeS | I32GeS | I32DivS | I32RemS | I32ShrS | I64ExtendSI32
+            | F32ConvertSI32 | F64ConvertSI32 => Some(Signedness::Signed),
+            I32LtU | I32GtU | I32LeU | I32GeU | I32DivU | I32RemU | I32ShrU | I64ExtendUI32
+            | F32ConvertUI32 | F64ConvertUI32 => Some(Signedness::Unsigned),
+            _ => None,
+        });
+
+    match (produced, consumed) {
+        (Some(produced), Some(consumed)) if produced != consumed => None,
+        (produced, consumed) => produced.or(consumed),
+    }
+}
+
+/// Increase the labels of all branches by `depth`, since they
+/// are moved into `depth` additional blocks
+fn shift_branch_labels(instructions: &mut [Instruction], depth: u32) {
+    for instruction in instructions {
+        match instruction {
+            Instruction::Br(label) | Instruction::BrIf(label) => *label += depth,
+            Instruction::BrTable(data) => {
+                data.table.iter_mut().for_each(|label| *label += depth);
+                data.default += depth;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Increase the indices of all called functions by one. Mutations are
+/// discovered before the check function is imported at index 0, so
+/// their calls refer to the original function indices.
+fn shift_call_indices(instructions: &mut [Instruction]) {
+    for instruction in instructions {
+        if let Instruction::Call(function) = instruction {
+            *function += 1;
+        }
+    }
+}
+
+/// Cyclomatic-like complexity of a function body: one plus
+/// the number of branches (`if`, `br_if` and `br_table`)
+pub fn complexity(instructions: &[Instruction]) -> u64 {
+    let branches = instructions
+        .iter()
+        .filter(|instruction| {
+            matches!(
+                instruction,
+                Instruction::If(_) | Instruction::BrIf(_) | Instruction::BrTable(_)
+            )
+        })
+        .count();
+
+    1 + branches as u64
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::operator::ops::{
-        BinaryOperatorAddToSub, BinaryOperatorMulToDivS, BinaryOperatorMulToDivU,
+    use crate::operator::{
+        ops::{
+            BinaryOperatorAddToSub, BinaryOperatorMulToDivS, BinaryOperatorMulToDivU,
+            CallRemoveScalarCall,
+        },
+        InstructionContext,
     };
 
     #[allow(unused_imports)]
@@ -669,7 +1981,7 @@ mod tests {
 
     use super::*;
     use anyhow::Result;
-    use wasmut_wasm::elements::BlockType;
+    use wasmut_wasm::elements::{BlockType, ExportSection};
 
     #[test]
     fn parameter_save_restore() {
@@ -689,7 +2001,13 @@ mod tests {
             ValueType::F64,
         ];
 
-        let saver = ParameterSaver::new(10, &mut entries);
+        let saved = SavedParameters {
+            i32: 10,
+            i64: 10,
+            f32: 10,
+            f64: 10,
+        };
+        let saver = ParameterSaver::new(saved, &mut entries);
 
         assert_eq!(entries.len(), 41);
 
@@ -727,13 +2045,13 @@ mod tests {
     #[test]
     #[should_panic]
     fn generate_empty_case() {
-        generate_mutant_sequence(1337, &[], &[]);
+        generate_mutant_sequence(MutantCheck::Call(1337), &[], &[]);
     }
 
     #[test]
     fn generate_base_case() {
         let result = generate_mutant_sequence(
-            1337,
+            MutantCheck::Call(1337),
             &[Mutation {
                 id: 1234,
                 operator: Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap()),
@@ -759,10 +2077,856 @@ mod tests {
         );
     }
 
+    #[test]
+    fn exported_functions() {
+        let module = WasmModule {
+            module: Module::new(vec![Section::Export(ExportSection::with_entries(vec![
+                ExportEntry::new("_start".into(), Internal::Function(1)),
+                ExportEntry::new("memory".into(), Internal::Memory(0)),
+                ExportEntry::new("counter".into(), Internal::Global(0)),
+            ]))]),
+            path: "test.wasm".into(),
+            bytes: Arc::from([]),
+            address_index: None,
+        };
+
+        assert_eq!(
+            module.exported_functions(),
+            HashSet::from([String::from("_start")])
+        );
+    }
+
+    #[test]
+    fn function_details() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        let module = wasmut_wasm::builder::module()
+            .with_import(ImportEntry::new(
+                "env".into(),
+                "log".into(),
+                External::Function(0),
+            ))
+            .function()
+            .signature()
+            .with_params(vec![ValueType::I32, ValueType::I32])
+            .with_result(ValueType::I32)
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                GetLocal(0),
+                GetLocal(1),
+                I32Add,
+                End,
+            ]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![End]))
+            .build()
+            .build()
+            .export()
+            .field("add")
+            .internal()
+            .func(1)
+            .build()
+            .build();
+
+        // Write and load the module, so that instruction offsets are available
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        let details = module.function_details()?;
+        assert_eq!(
+            details,
+            vec![
+                FunctionDetails {
+                    index: 1,
+                    name: Some("add".into()),
+                    params: vec![ValueType::I32, ValueType::I32],
+                    results: vec![ValueType::I32],
+                    code_size: 6,
+                    exported: true,
+                },
+                FunctionDetails {
+                    index: 2,
+                    name: None,
+                    params: vec![],
+                    results: vec![],
+                    code_size: 1,
+                    exported: false,
+                },
+            ]
+        );
+        assert_eq!(details[0].signature(), "(i32, i32) -> i32");
+        assert_eq!(details[1].signature(), "()");
+
+        let functions = module.functions_detailed()?;
+        assert_eq!(functions.len(), 2);
+        assert_eq!(functions[0].details, details[0]);
+        assert_eq!(functions[0].body.code().elements().len(), 4);
+        assert_eq!(functions[0].instruction_offsets.len(), 4);
+        assert_eq!(functions[0].instruction_offsets[0], functions[0].offset);
+        assert_eq!(
+            module.function_offsets()[1].0,
+            functions[1].offset,
+            "offsets are relative to the code section"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn table_entry_candidates() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        let mut module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .with_result(ValueType::I32)
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![I32Const(1), End]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![End]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .with_result(ValueType::I32)
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![I32Const(2), End]))
+            .build()
+            .build()
+            .table()
+            .with_min(5)
+            .with_element(1, vec![0, 1, 0, 2])
+            .build()
+            .build();
+
+        // Identical types are compared structurally
+        module
+            .type_section_mut()
+            .unwrap()
+            .types_mut()
+            .push(Type::Function(FunctionType::new(
+                vec![],
+                vec![ValueType::I32],
+            )));
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        assert_eq!(
+            module.table_entry_candidates()?,
+            vec![
+                TableEntryCandidate {
+                    type_index: 0,
+                    params: vec![],
+                    result: BlockType::Value(ValueType::I32),
+                    slots: (1, 4),
+                },
+                TableEntryCandidate {
+                    type_index: 2,
+                    params: vec![],
+                    result: BlockType::Value(ValueType::I32),
+                    slots: (1, 4),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn function_complexity() {
+        use wasmut_wasm::elements::{BlockType, BrTableData};
+        use Instruction::*;
+
+        assert_eq!(complexity(&[End]), 1);
+        assert_eq!(
+            complexity(&[
+                Block(BlockType::NoResult),
+                I32Const(0),
+                If(BlockType::NoResult),
+                End,
+                I32Const(1),
+                BrIf(0),
+                I32Const(2),
+                BrTable(Box::new(BrTableData {
+                    table: Box::new([0, 0]),
+                    default: 0
+                })),
+                End,
+                End,
+            ]),
+            4
+        );
+    }
+
+    #[test]
+    fn stripped_module() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        let module = wasmut_wasm::builder::module()
+            .with_import(ImportEntry::new(
+                "env".into(),
+                "log".into(),
+                External::Function(0),
+            ))
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Nop, End]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![End]))
+            .build()
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        let debug_info = module.debug_info();
+        assert_eq!(
+            debug_info,
+            DebugInfo {
+                name_section: false,
+                dwarf: false
+            }
+        );
+        assert!(debug_info.is_stripped());
+
+        let error = debug_info.require().unwrap_err();
+        assert_eq!(crate::error::exit_code(&error), 5);
+        assert!(format!("{error:?}").contains("wasm-strip"));
+
+        let offsets = module.function_offsets();
+        assert_eq!(offsets.len(), 2);
+        assert_eq!(offsets[0].1, "func[1]");
+        assert_eq!(offsets[1].1, "func[2]");
+        assert!(offsets[0].0 < offsets[1].0);
+        Ok(())
+    }
+
+    #[test]
+    fn meta_mutant_size() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![I32Const(1), Drop, End]))
+            .build()
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        let original = module.size()?;
+        assert_eq!(original.globals, 0);
+        assert_eq!(original.instructions, 3);
+        assert_eq!(original.bytes, std::fs::metadata(&path)?.len());
+
+        let size = MetaMutantSize {
+            original,
+            meta_mutant: ModuleSize {
+                bytes: original.bytes * 2,
+                globals: 6,
+                instructions: 10,
+            },
+        };
+        assert_eq!(
+            size.added(),
+            ModuleSize {
+                bytes: original.bytes,
+                globals: 6,
+                instructions: 7
+            }
+        );
+        assert_eq!(
+            size.to_string(),
+            format!(
+                "{} bytes (2.00x the original module), 6 globals and 7 instructions added",
+                original.bytes * 2
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn instrumented_module() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        let dir = tempfile::tempdir()?;
+        let write = |namespace: &str| -> Result<String> {
+            let module = wasmut_wasm::builder::module()
+                .with_import(ImportEntry::new(
+                    namespace.into(),
+                    "__wasmut_check_mutant_id".into(),
+                    External::Function(0),
+                ))
+                .function()
+                .signature()
+                .build()
+                .body()
+                .with_instructions(Instructions::new(vec![Nop, End]))
+                .build()
+                .build()
+                .build();
+
+            let path = dir.path().join(format!("{namespace}.wasm"));
+            wasmut_wasm::serialize_to_file(&path, module)?;
+            Ok(path.to_str().unwrap().into())
+        };
+
+        let path = write("env")?;
+        let module = WasmModule::from_file(&path)?;
+        assert!(!module.is_instrumented());
+        module.require_uninstrumented()?;
+
+        let path = write(WASMUT_API_NAMESPACE)?;
+        let mutant = WasmModule::from_file(&path)?;
+        assert!(mutant.is_instrumented());
+
+        let error = mutant.require_uninstrumented().unwrap_err();
+        assert_eq!(crate::error::exit_code(&error), 5);
+        assert!(format!("{error:?}").contains("already instrumented"));
+        Ok(())
+    }
+
+    #[test]
+    fn branch_trace_points() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        let module = wasmut_wasm::builder::module()
+            .with_import(ImportEntry::new(
+                "env".into(),
+                "f".into(),
+                External::Function(0),
+            ))
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                I32Const(1),
+                I32Const(2),
+                I32LtS,
+                If(BlockType::NoResult),
+                Nop,
+                End,
+                I32Const(1),
+                I32Const(2),
+                I32GtS,
+                Drop,
+                End,
+            ]))
+            .build()
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("branches.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        let location = |statement_number| MutationLocation {
+            function_number: 0,
+            statement_number,
+            offset: statement_number * 100,
+            mutations: Vec::new(),
+        };
+        let branches = module.conditional_branches(&[location(2), location(8)]);
+        assert_eq!(branches.len(), 1);
+        let branch_offset = branches[&200];
+
+        let mut traced = module.clone();
+        traced.insert_trace_points()?;
+
+        let imports: Vec<&str> = traced
+            .module
+            .import_section()
+            .unwrap()
+            .entries()
+            .iter()
+            .map(|entry| entry.field())
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                "__wasmut_trace_comparison_f64",
+                "__wasmut_trace_comparison_f32",
+                "__wasmut_trace_comparison_i64",
+                "__wasmut_trace_comparison_i32",
+                "__wasmut_trace_branch",
+                "__wasmut_trace",
+                "f"
+            ]
+        );
+
+        // The comparison saves its operands in two new locals and passes them and its
+        // result to the comparison trace function. The condition is passed through
+        // the branch trace function, before the `if`
+        let body = &traced.module.code_section().unwrap().bodies()[0];
+        assert_eq!(body.locals(), &[Local::new(2, ValueType::I32)]);
+
+        let comparison_offset = branch_offset as i64 - 1;
+        let code = body.code().elements();
+        let index = code.iter().position(|i| matches!(i, If(_))).unwrap();
+        assert_eq!(
+            &code[index - 12..=index],
+            &[
+                SetLocal(1),
+                TeeLocal(0),
+                GetLocal(1),
+                I32LtS,
+                GetLocal(0),
+                GetLocal(1),
+                I64Const(comparison_offset),
+                Call(3),
+                I64Const(branch_offset as i64),
+                Call(5),
+                I64Const(branch_offset as i64),
+                Call(4),
+                If(BlockType::NoResult)
+            ]
+        );
+        assert_eq!(code.iter().filter(|i| **i == Call(4)).count(), 1);
+        assert_eq!(code.iter().filter(|i| **i == Call(3)).count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn executed_instructions_per_function() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Nop, Nop, End]))
+            .build()
+            .build()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Nop, End]))
+            .build()
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("functions.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        let code_section = module.module.code_section().unwrap();
+        let offsets = |function: usize| -> Vec<u64> {
+            code_section.bodies()[function]
+                .code()
+                .offsets()
+                .iter()
+                .map(|offset| offset - code_section.offset())
+                .collect()
+        };
+
+        let mut trace_points = TracePoints::default();
+        for offset in offsets(0) {
+            trace_points.add_point(offset);
+            trace_points.add_point(offset);
+        }
+        trace_points.add_point(offsets(1)[1]);
+
+        assert_eq!(module.executed_instructions(&trace_points), vec![6, 1]);
+        assert_eq!(
+            module.executed_instructions(&TracePoints::default()),
+            vec![0, 0]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn basic_block_leaders() {
+        use Instruction::*;
+
+        assert_eq!(block_leaders(&[End]), vec![0]);
+        assert_eq!(
+            block_leaders(&[
+                GetLocal(0),
+                If(BlockType::NoResult),
+                Nop,
+                Else,
+                Loop(BlockType::NoResult),
+                I32Const(1),
+                BrIf(0),
+                End,
+                End,
+                Nop,
+                End,
+            ]),
+            vec![0, 2, 4, 5, 7, 8, 9]
+        );
+    }
+
+    #[test]
+    fn generate_global_check() {
+        let result = generate_mutant_sequence(
+            MutantCheck::Global(42),
+            &[Mutation {
+                id: 1234,
+                operator: Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap()),
+            }],
+            &[Instruction::GetGlobal(10), Instruction::GetGlobal(11)],
+        );
+
+        assert_eq!(
+            result[..4],
+            [
+                Instruction::GetGlobal(42),
+                Instruction::I64Const(1234),
+                Instruction::I64Eq,
+                Instruction::If(BlockType::Value(ValueType::I32)),
+            ]
+        );
+    }
+
+    #[test]
+    fn branches_carrying_values() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .with_result(ValueType::I32)
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![
+                Block(BlockType::NoResult),
+                I32Const(0),
+                BrIf(0),
+                I32Const(7),
+                I32Const(0),
+                BrIf(1),
+                Drop,
+                End,
+                Loop(BlockType::Value(ValueType::I32)),
+                I32Const(0),
+                BrIf(0),
+                I32Const(1),
+                End,
+                End,
+            ]))
+            .build()
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        let branches: Vec<(u64, bool)> =
+            module.instruction_walker(&|instruction, location| match instruction {
+                BrIf(_) => vec![(location.instruction_index, location.branch_carries_values)],
+                _ => vec![],
+            })?;
+        assert_eq!(branches, vec![(2, false), (5, true), (10, false)]);
+        Ok(())
+    }
+
+    #[test]
+    fn mutate_all_in_huge_function() -> Result<()> {
+        use crate::runtime::wasmer::Validator;
+        use wasmut_wasm::elements::Instructions;
+        use Instruction::*;
+
+        const ADDITIONS: u64 = 20000;
+
+        let mut instructions = Vec::new();
+        for _ in 0..ADDITIONS {
+            instructions.extend([I32Const(1), I32Const(2), I32Add, Drop]);
+        }
+        instructions.push(End);
+
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(instructions))
+            .build()
+            .build()
+            .export()
+            .field("_start")
+            .internal()
+            .func(0)
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        let locations: Vec<MutationLocation> = (0..ADDITIONS)
+            .map(|i| MutationLocation {
+                function_number: 0,
+                statement_number: i * 4 + 2,
+                offset: i,
+                mutations: vec![Mutation {
+                    id: i as i64 + 1,
+                    operator: Box::new(BinaryOperatorAddToSub::new(&I32Add).unwrap()),
+                }],
+            })
+            .collect();
+
+        let mutant = module.clone_and_mutate_all(&locations, MutantActivation::Global)?;
+        Validator::default().validate(&mutant)?;
+
+        let code = mutant.module.code_section().unwrap().bodies()[0]
+            .code()
+            .elements();
+        assert_eq!(
+            code.iter().filter(|i| **i == I32Sub).count() as u64,
+            ADDITIONS
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mutants_share_original_bytecode() -> Result<()> {
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(wasmut_wasm::elements::Instructions::new(vec![
+                Instruction::I32Const(1),
+                Instruction::I32Const(2),
+                Instruction::I32Add,
+                Instruction::Drop,
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .export()
+            .field("_start")
+            .internal()
+            .func(0)
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        // The file is only read once
+        std::fs::remove_file(&path)?;
+        assert!(!module.bytes().is_empty());
+
+        let location = MutationLocation {
+            function_number: 0,
+            statement_number: 2,
+            offset: 0,
+            mutations: vec![Mutation {
+                id: 1,
+                operator: Box::new(BinaryOperatorAddToSub::new(&Instruction::I32Add).unwrap()),
+            }],
+        };
+        let mutant = module.clone_and_mutate_all(&[location], MutantActivation::Global)?;
+        assert_eq!(mutant.bytes().as_ptr(), module.bytes().as_ptr());
+
+        let instructions = module.instruction_walker::<()>(&|_, _| vec![()])?;
+        assert_eq!(instructions.len(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn from_bytes() -> Result<()> {
+        let bytes = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(wasmut_wasm::elements::Instructions::new(vec![
+                Instruction::I32Const(1),
+                Instruction::I32Const(2),
+                Instruction::I32Add,
+                Instruction::Drop,
+                Instruction::End,
+            ]))
+            .build()
+            .build()
+            .build()
+            .into_bytes()?;
+
+        let module = WasmModule::from_bytes(bytes.clone(), "upload.wasm")?;
+        assert_eq!(module.path(), "upload.wasm");
+        assert_eq!(module.bytes(), bytes.as_slice());
+        assert_eq!(module.function_lengths(), vec![5]);
+
+        let Err(error) = WasmModule::from_bytes(vec![0, 1, 2], "invalid.wasm") else {
+            panic!("Invalid bytecode was accepted");
+        };
+        assert_eq!(error.downcast_ref::<ErrorKind>(), Some(&ErrorKind::Module));
+        Ok(())
+    }
+
+    #[test]
+    fn meta_mutant_shifts_branch_labels() {
+        use crate::operator::ops::BrTableRotateTargets;
+        use wasmut_wasm::elements::BrTableData;
+
+        let br_table = Instruction::BrTable(Box::new(BrTableData {
+            table: Box::new([0, 1]),
+            default: 2,
+        }));
+        let mutations = [
+            Mutation {
+                id: 1,
+                operator: Box::new(BrTableRotateTargets::new(&br_table).unwrap()),
+            },
+            Mutation {
+                id: 2,
+                operator: Box::new(BrTableRotateTargets::new(&br_table).unwrap()),
+            },
+        ];
+
+        let result = generate_mutant_sequence(MutantCheck::Global(0), &mutations, &[]);
+        let branches: Vec<&Instruction> = result
+            .iter()
+            .filter(|instruction| matches!(instruction, Instruction::BrTable(_)))
+            .collect();
+
+        let shifted = |table: [u32; 2], default| {
+            Instruction::BrTable(Box::new(BrTableData {
+                table: Box::new(table),
+                default,
+            }))
+        };
+        assert_eq!(
+            branches,
+            [
+                &shifted([2, 1], 3),
+                &shifted([3, 2], 4),
+                &shifted([2, 3], 4)
+            ]
+        );
+        assert_eq!(result.last(), Some(&Instruction::Unreachable));
+    }
+
+    #[test]
+    fn gate_body() -> Result<()> {
+        let result = gate_function_body(
+            7,
+            3,
+            BlockType::Value(ValueType::I32),
+            vec![Instruction::I32Const(1), Instruction::End],
+            vec![Instruction::I32Const(2), Instruction::End],
+        )?;
+
+        assert_eq!(
+            result,
+            vec![
+                Instruction::GetGlobal(7),
+                Instruction::I64Const(3),
+                Instruction::I64Eq,
+                Instruction::If(BlockType::Value(ValueType::I32)),
+                Instruction::I32Const(1),
+                Instruction::Else,
+                Instruction::I32Const(2),
+                Instruction::End,
+                Instruction::End,
+            ]
+        );
+
+        let error = gate_function_body(
+            7,
+            3,
+            BlockType::NoResult,
+            vec![Instruction::Nop],
+            vec![Instruction::End],
+        )
+        .unwrap_err();
+        assert_eq!(error.downcast_ref::<ErrorKind>(), Some(&ErrorKind::Module));
+        Ok(())
+    }
+
+    #[test]
+    fn generate_call_to_first_function() {
+        let context = InstructionContext::new(vec![CallRemovalCandidate::FuncReturningScalar {
+            index: 0,
+            params: vec![ValueType::I32],
+            return_type: ValueType::I32,
+        }]);
+        let result = generate_mutant_sequence(
+            MutantCheck::Call(0),
+            &[Mutation {
+                id: 1234,
+                operator: Box::new(
+                    CallRemoveScalarCall::new(&Instruction::Call(0), &context).unwrap(),
+                ),
+            }],
+            &[Instruction::GetGlobal(10)],
+        );
+
+        // The check function is imported at index 0, the original call is shifted
+        assert_eq!(
+            result,
+            vec![
+                Instruction::I64Const(1234),
+                Instruction::Call(0),
+                Instruction::If(BlockType::Value(ValueType::I32)),
+                Instruction::GetGlobal(10),
+                Instruction::Drop,
+                Instruction::I32Const(42),
+                Instruction::Else,
+                Instruction::GetGlobal(10),
+                Instruction::Call(1),
+                Instruction::End,
+            ]
+        );
+    }
+
     #[test]
     fn generate_recursive_case() {
         let result = generate_mutant_sequence(
-            1337,
+            MutantCheck::Call(1337),
             &[
                 Mutation {
                     id: 1234,
@@ -895,16 +3059,122 @@ mod tests {
     }
 
     #[test]
-    fn max_number_of_params_of_same_type() -> Result<()> {
-        let module = WasmModule::from_file("testdata/factorial/test.wasm")?;
-        assert_eq!(module.max_number_of_params_of_same_type(), 1);
+    fn create_global_section_in_order() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Instruction::End]))
+            .build()
+            .build()
+            .export()
+            .field("_start")
+            .internal()
+            .func(0)
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let mut module = WasmModule::from_file(path.to_str().unwrap())?;
+
+        module
+            .get_or_create_global_section()?
+            .entries_mut()
+            .push(GlobalEntry::new(
+                GlobalType::new(ValueType::I32, true),
+                InitExpr::new(vec![Instruction::I32Const(0), Instruction::End]),
+            ));
 
-        let module = WasmModule::from_file("testdata/simple_add/test.wasm")?;
-        assert_eq!(module.max_number_of_params_of_same_type(), 2);
+        // The global section has to precede the export and code sections
+        let module: Module = wasmut_wasm::deserialize_buffer(&module.to_bytes()?)?;
+        assert_eq!(module.global_section().unwrap().entries().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn add_trace_function_import_without_imports() -> Result<()> {
+        use wasmut_wasm::elements::Instructions;
+
+        let module = wasmut_wasm::builder::module()
+            .function()
+            .signature()
+            .build()
+            .body()
+            .with_instructions(Instructions::new(vec![Instruction::End]))
+            .build()
+            .build()
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("test.wasm");
+        wasmut_wasm::serialize_to_file(&path, module)?;
+        let mut module = WasmModule::from_file(path.to_str().unwrap())?;
 
-        let module = WasmModule::from_file("testdata/simple_add64/test.wasm")?;
-        assert_eq!(module.max_number_of_params_of_same_type(), 2);
+        let type_index = module.find_or_insert_trace_function_signature()?;
+        let function_index = module.add_trace_function_import("__wasmut_trace", type_index)?;
+        assert_eq!(function_index, 0);
 
+        let module: Module = wasmut_wasm::deserialize_buffer(&module.to_bytes()?)?;
+        assert_eq!(module.import_count(ImportCountType::Function), 1);
         Ok(())
     }
+
+    #[test]
+    fn saved_parameters_of_mutations() {
+        use ValueType::*;
+
+        let saved = SavedParameters::of(&[I32, I64, I32])
+            .max(SavedParameters::of(&[F64]))
+            .max(SavedParameters::of(&[]));
+        assert_eq!(
+            saved,
+            SavedParameters {
+                i32: 2,
+                i64: 1,
+                f32: 0,
+                f64: 1,
+            }
+        );
+
+        let mut entries = Vec::new();
+        let saver = ParameterSaver::new(saved, &mut entries);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            saver.save_sequence(&[I32, F64]).1,
+            vec![Instruction::GetGlobal(0), Instruction::GetGlobal(3)]
+        );
+
+        ParameterSaver::new(SavedParameters::default(), &mut entries);
+        assert_eq!(entries.len(), 4);
+    }
+
+    #[test]
+    fn operand_signedness_of_wrap() {
+        use Instruction::*;
+
+        let signedness = |instructions: &[Instruction]| {
+            let index = instructions.iter().position(|i| *i == I32WrapI64).unwrap();
+            operand_signedness(instructions, index)
+        };
+
+        assert_eq!(
+            signedness(&[I64ExtendSI32, I32WrapI64]),
+            Some(Signedness::Signed)
+        );
+        assert_eq!(
+            signedness(&[I32WrapI64, I32LtU]),
+            Some(Signedness::Unsigned)
+        );
+        assert_eq!(
+            signedness(&[I64DivU, I32WrapI64, I32Add]),
+            Some(Signedness::Unsigned)
+        );
+        assert_eq!(signedness(&[GetLocal(0), I32WrapI64, I32Add]), None);
+        assert_eq!(signedness(&[I64ShrS, I32WrapI64, I32GeU]), None);
+    }
 }
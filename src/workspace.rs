@@ -0,0 +1,331 @@
+//! Workspace manifests (`wasmut.workspace.toml`), describing several
+//! WebAssembly modules that are mutated in a single run.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::{config::Config, error::ErrorKind, reporter::ScoreThresholds, wasmmodule::WasmModule};
+
+/// Default file name of a workspace manifest
+pub const WORKSPACE_FILE_NAME: &str = "wasmut.workspace.toml";
+
+/// A single module of the workspace, as specified in the manifest
+#[derive(Deserialize)]
+struct TargetConfig {
+    /// Unique name of the target, used for the report directory
+    name: String,
+
+    /// Path to the wasm module, relative to the manifest
+    module: String,
+
+    /// Path to a wasmut.toml configuration file, relative to the manifest
+    config: Option<String>,
+
+    /// Configuration values that override those of `config`
+    overrides: Option<toml::Table>,
+
+    /// Functions that must be exported by the module.
+    /// Defaults to `_start`.
+    entry_points: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct WorkspaceConfig {
+    target: Vec<TargetConfig>,
+
+    /// Mutation score thresholds of the dashboard
+    thresholds: Option<ScoreThresholds>,
+}
+
+/// A module of the workspace, with paths resolved relative to the manifest
+pub struct Target {
+    name: String,
+    module: PathBuf,
+    config: Option<PathBuf>,
+    overrides: toml::Table,
+    entry_points: Vec<String>,
+    /// Directory of the manifest
+    directory: PathBuf,
+}
+
+impl Target {
+    /// Unique name of the target
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Path to the wasm module
+    pub fn module(&self) -> Result<&str> {
+        self.module
+            .to_str()
+            .with_context(|| format!("Module path {:?} is not valid UTF-8", self.module))
+    }
+
+    /// Load the configuration of the target and apply its overrides
+    pub fn config(&self) -> Result<Config> {
+        let mut table = match &self.config {
+            Some(path) => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read configuration file {path:?}"))
+                    .context(ErrorKind::Config)?;
+                toml::from_str(&content).context(ErrorKind::Config)?
+            }
+            None => toml::Table::new(),
+        };
+
+        merge_tables(&mut table, &self.overrides);
+
+        // Like `Config::parse_file`, the configuration is interpreted relative to its
+        // file, or to the manifest if the target is configured by overrides only
+        let directory = match &self.config {
+            Some(path) => path.parent().unwrap_or_else(|| Path::new(".")),
+            None => &self.directory,
+        };
+        Ok(
+            Config::parse(&toml::to_string(&table).context(ErrorKind::Config)?)?
+                .with_directory(directory),
+        )
+    }
+
+    /// Check that the module exports all expected entry points
+    pub fn check_entry_points(&self, module: &WasmModule) -> Result<()> {
+        let exported = module.exported_functions();
+
+        let missing: Vec<&str> = self
+            .entry_points
+            .iter()
+            .filter(|entry_point| !exported.contains(*entry_point))
+            .map(|entry_point| entry_point.as_str())
+            .collect();
+
+        if !missing.is_empty() {
+            bail!(
+                "Module {:?} does not export the entry points {}",
+                self.module,
+                missing.join(", ")
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Parsed workspace manifest
+pub struct Workspace {
+    targets: Vec<Target>,
+    thresholds: ScoreThresholds,
+}
+
+impl Workspace {
+    /// Load a workspace manifest from a file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workspace manifest {path:?}"))
+            .context(ErrorKind::Config)?;
+
+        let base_directory = path.parent().unwrap_or_else(|| Path::new(""));
+        Self::parse(&content, base_directory)
+    }
+
+    /// Parse a workspace manifest. Paths are resolved relative to `base_directory`.
+    pub fn parse(s: &str, base_directory: &Path) -> Result<Self> {
+        let workspace: WorkspaceConfig = toml::from_str(s).context(ErrorKind::Config)?;
+
+        if workspace.target.is_empty() {
+            return Err(anyhow!("Workspace does not contain any targets"))
+                .context(ErrorKind::Config);
+        }
+
+        let thresholds = workspace.thresholds.unwrap_or_default();
+        thresholds.validate()?;
+
+        let mut names = HashSet::new();
+        for target in &workspace.target {
+            if target.name.is_empty() || target.name.contains(['/', '\\']) || target.name == ".." {
+                return Err(anyhow!("Invalid target name \"{}\"", target.name))
+                    .context(ErrorKind::Config);
+            }
+
+            if !names.insert(target.name.as_str()) {
+                return Err(anyhow!("Duplicate target name \"{}\"", target.name))
+                    .context(ErrorKind::Config);
+            }
+        }
+
+        let targets = workspace
+            .target
+            .into_iter()
+            .map(|target| Target {
+                name: target.name,
+                module: base_directory.join(target.module),
+                config: target.config.map(|config| base_directory.join(config)),
+                overrides: target.overrides.unwrap_or_default(),
+                entry_points: target
+                    .entry_points
+                    .unwrap_or_else(|| vec![String::from("_start")]),
+                directory: base_directory.into(),
+            })
+            .collect();
+
+        Ok(Self {
+            targets,
+            thresholds,
+        })
+    }
+
+    /// All targets of the workspace
+    pub fn targets(&self) -> &[Target] {
+        &self.targets
+    }
+
+    /// Mutation score thresholds used for coloring the dashboard
+    pub fn thresholds(&self) -> ScoreThresholds {
+        self.thresholds
+    }
+}
+
+/// Recursively merge `overrides` into `base`. Tables are merged,
+/// all other values of `overrides` replace those of `base`.
+fn merge_tables(base: &mut toml::Table, overrides: &toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(override_table)) => {
+                merge_tables(base_table, override_table);
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_workspace() -> Result<()> {
+        let workspace = Workspace::parse(
+            r#"
+            [[target]]
+            name = "add"
+            module = "simple_add/simple_add.wasm"
+            config = "simple_add/wasmut.toml"
+
+            [[target]]
+            name = "words"
+            module = "count_words/test.wasm"
+            entry_points = ["_start", "count"]
+            [target.overrides.engine]
+            map_dirs = [["count_words/files", "files"]]
+            "#,
+            Path::new("testdata"),
+        )?;
+
+        let targets = workspace.targets();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].name(), "add");
+        assert_eq!(targets[0].module()?, "testdata/simple_add/simple_add.wasm");
+        assert_eq!(
+            targets[0].config,
+            Some(PathBuf::from("testdata/simple_add/wasmut.toml"))
+        );
+        assert_eq!(targets[0].entry_points, ["_start"]);
+        assert_eq!(targets[1].entry_points, ["_start", "count"]);
+        assert_eq!(workspace.thresholds(), ScoreThresholds::default());
+
+        let config = targets[1].config()?;
+        assert_eq!(
+            config.engine().map_dirs(),
+            [(String::from("count_words/files"), String::from("files"))]
+        );
+        assert_eq!(config.directory(), Path::new("testdata"));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_workspaces() {
+        let parse = |s| Workspace::parse(s, Path::new(""));
+
+        assert!(parse("target = []").is_err());
+        assert!(parse(
+            r#"
+            [[target]]
+            name = "a"
+            module = "a.wasm"
+            [[target]]
+            name = "a"
+            module = "b.wasm"
+            "#
+        )
+        .is_err());
+        assert!(parse(
+            r#"
+            [[target]]
+            name = "../a"
+            module = "a.wasm"
+            "#
+        )
+        .is_err());
+        assert!(parse(
+            r#"
+            thresholds = { warning = 90, success = 60 }
+            [[target]]
+            name = "a"
+            module = "a.wasm"
+            "#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn config_overrides() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(
+            dir.path().join("wasmut.toml"),
+            r#"
+            [engine]
+            timeout_multiplier = 4.0
+            meta_mutant = false
+            "#,
+        )?;
+
+        let workspace = Workspace::parse(
+            r#"
+            [[target]]
+            name = "a"
+            module = "a.wasm"
+            config = "wasmut.toml"
+            [target.overrides.engine]
+            meta_mutant = true
+            "#,
+            dir.path(),
+        )?;
+
+        let config = workspace.targets()[0].config()?;
+        assert_eq!(config.directory(), dir.path());
+        assert_eq!(config.engine().timeout_multiplier(), 4.0);
+        assert!(config.engine().meta_mutant());
+        Ok(())
+    }
+
+    #[test]
+    fn merge_nested_tables() {
+        let mut base: toml::Table = toml::from_str("a = 1\n[b]\nc = 2\nd = 3").unwrap();
+        let overrides: toml::Table = toml::from_str("e = 4\n[b]\nc = 5").unwrap();
+
+        merge_tables(&mut base, &overrides);
+
+        assert_eq!(
+            base,
+            toml::from_str::<toml::Table>("a = 1\ne = 4\n[b]\nc = 5\nd = 3").unwrap()
+        );
+    }
+}